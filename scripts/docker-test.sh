@@ -99,7 +99,7 @@ validate_status() {
 }
 
 validate_categories() {
-    jq -e '.categories | index("desserts") != null' /tmp/response.json > /dev/null 2>&1
+    jq -e '.categories | map(.path) | index("desserts") != null' /tmp/response.json > /dev/null 2>&1
 }
 
 validate_recipes_list() {
@@ -113,7 +113,7 @@ validate_search_results() {
 
 validate_nested_categories() {
     # Verify nested categories appear in the response
-    jq -e '.categories | map(select(. == "meals/asian/thai" or . == "meals/european/italian")) | length == 2' /tmp/response.json > /dev/null 2>&1
+    jq -e '.categories | map(select(.path == "meals/asian/thai" or .path == "meals/european/italian")) | length == 2' /tmp/response.json > /dev/null 2>&1
 }
 
 validate_recipe_in_nested_category() {
@@ -0,0 +1,113 @@
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+
+/// Store aisles and the ingredients found in each, used to sort shopping lists
+///
+/// Read from a text file in cooklang's `aisle.conf` layout: a `[section]` line starts
+/// an aisle and each following line names an ingredient, with `|` between names for
+/// the same ingredient. Aisles keep the order of the file, which should be the order
+/// they are walked through in the store. Blank lines and lines starting with `#` are
+/// ignored, and names are compared lowercased:
+///
+/// ```text
+/// [produce]
+/// potatoes
+/// scallion | green onion
+///
+/// [dairy]
+/// milk
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Aisles {
+    /// Aisle names, in file order
+    names: Vec<String>,
+    /// Ingredient name → index of its aisle
+    ingredients: HashMap<String, usize>,
+}
+
+impl Aisles {
+    /// Parse an aisles file
+    ///
+    /// An ingredient before the first aisle, an empty name, or an ingredient listed in
+    /// two aisles is an error.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut aisles = Aisles::default();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) = line
+                .strip_prefix('[')
+                .and_then(|rest| rest.strip_suffix(']'))
+            {
+                let name = name.trim();
+                if name.is_empty() {
+                    bail!("Line {}: empty aisle name", number + 1);
+                }
+                aisles.names.push(name.to_string());
+                continue;
+            }
+            let Some(aisle) = aisles.names.len().checked_sub(1) else {
+                bail!("Line {}: ingredient outside of an aisle", number + 1);
+            };
+            for name in line.split('|').map(name_key) {
+                if name.is_empty() {
+                    bail!("Line {}: empty ingredient name", number + 1);
+                }
+                match aisles.ingredients.get(&name) {
+                    Some(&other) if other != aisle => bail!(
+                        "Line {}: '{}' is already in aisle '{}'",
+                        number + 1,
+                        name,
+                        aisles.names[other]
+                    ),
+                    _ => {
+                        aisles.ingredients.insert(name, aisle);
+                    }
+                }
+            }
+        }
+        Ok(aisles)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Position and name of the aisle holding the first of `names` that has one
+    pub fn find<'a>(&self, names: impl IntoIterator<Item = &'a str>) -> Option<(usize, &str)> {
+        names.into_iter().find_map(|name| {
+            self.ingredients
+                .get(&name_key(name))
+                .map(|&index| (index, self.names[index].as_str()))
+        })
+    }
+}
+
+fn name_key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_aisles() -> Result<()> {
+        let aisles = Aisles::parse(
+            "# walk order\n[Produce]\npotatoes\nScallion | green onion\n\n[dairy]\nmilk\n",
+        )?;
+        assert_eq!(aisles.find(["Green Onion"]), Some((0, "Produce")));
+        assert_eq!(aisles.find(["salt", "milk"]), Some((1, "dairy")));
+        assert_eq!(aisles.find(["salt"]), None);
+        assert!(Aisles::default().is_empty());
+
+        assert!(Aisles::parse("milk\n[dairy]").is_err());
+        assert!(Aisles::parse("[ ]\nmilk").is_err());
+        assert!(Aisles::parse("[dairy]\nmilk |").is_err());
+        assert!(Aisles::parse("[dairy]\nmilk\n[drinks]\nmilk").is_err());
+        Ok(())
+    }
+}
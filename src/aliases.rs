@@ -0,0 +1,190 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+/// A renamed category: lookups under `from` are redirected to `to`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryAlias {
+    pub from: String,
+    pub to: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Old → new category path mappings recorded when categories are renamed
+pub struct CategoryAliases {
+    // from path -> alias
+    aliases: DashMap<String, CategoryAlias>,
+    case_insensitive: bool,
+}
+
+impl CategoryAliases {
+    /// Create an empty alias table
+    pub fn new(case_insensitive: bool) -> Self {
+        CategoryAliases {
+            aliases: DashMap::new(),
+            case_insensitive,
+        }
+    }
+
+    /// Replace the table with aliases loaded from JSON
+    pub fn load(&self, json: &str) -> Result<()> {
+        let aliases: Vec<CategoryAlias> =
+            serde_json::from_str(json).context("Invalid category alias file")?;
+
+        self.aliases.clear();
+        for alias in aliases {
+            self.aliases.insert(alias.from.clone(), alias);
+        }
+        Ok(())
+    }
+
+    /// Serialize the table to JSON (sorted by old path, for stable diffs)
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&self.all())?)
+    }
+
+    /// All aliases, sorted by old path
+    pub fn all(&self) -> Vec<CategoryAlias> {
+        let mut aliases: Vec<CategoryAlias> = self
+            .aliases
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect();
+        aliases.sort_by(|a, b| a.from.cmp(&b.from));
+        aliases
+    }
+
+    /// Record that `from` was renamed to `to`
+    ///
+    /// Existing aliases pointing into `from` are re-targeted so chains of renames
+    /// resolve in one step, and an alias for `to` itself is dropped since that path
+    /// is live again.
+    pub fn record(&self, from: &str, to: &str, now: DateTime<Utc>) {
+        for mut entry in self.aliases.iter_mut() {
+            if let Some(rest) = self.strip_path_prefix(&entry.to, from) {
+                entry.to = format!("{}{}", to, rest);
+            }
+        }
+
+        let to_key = self
+            .aliases
+            .iter()
+            .find(|entry| self.paths_equal(entry.key(), to))
+            .map(|entry| entry.key().clone());
+        if let Some(key) = to_key {
+            self.aliases.remove(&key);
+        }
+
+        self.aliases.insert(
+            from.to_string(),
+            CategoryAlias {
+                from: from.to_string(),
+                to: to.to_string(),
+                created_at: now,
+            },
+        );
+    }
+
+    /// Resolve a path under a renamed category to its new location
+    ///
+    /// Uses the longest matching old prefix, ignoring aliases created before `cutoff`.
+    pub fn resolve(&self, path: &str, cutoff: Option<DateTime<Utc>>) -> Option<String> {
+        self.aliases
+            .iter()
+            .filter(|entry| cutoff.map(|c| entry.created_at >= c).unwrap_or(true))
+            .filter_map(|entry| {
+                self.strip_path_prefix(path, &entry.from)
+                    .map(|rest| (entry.from.len(), format!("{}{}", entry.to, rest)))
+            })
+            .max_by_key(|(prefix_len, _)| *prefix_len)
+            .map(|(_, resolved)| resolved)
+    }
+
+    /// Drop aliases created before `cutoff`, returning how many were removed
+    pub fn purge_expired(&self, cutoff: DateTime<Utc>) -> usize {
+        let before = self.aliases.len();
+        self.aliases.retain(|_, alias| alias.created_at >= cutoff);
+        before - self.aliases.len()
+    }
+
+    /// If `path` is `prefix` or below it, return the remainder (empty or starting with `/`)
+    fn strip_path_prefix<'a>(&self, path: &'a str, prefix: &str) -> Option<&'a str> {
+        if path.len() < prefix.len() || !path.is_char_boundary(prefix.len()) {
+            return None;
+        }
+
+        let (head, rest) = path.split_at(prefix.len());
+        if self.paths_equal(head, prefix) && (rest.is_empty() || rest.starts_with('/')) {
+            Some(rest)
+        } else {
+            None
+        }
+    }
+
+    fn paths_equal(&self, a: &str, b: &str) -> bool {
+        if self.case_insensitive {
+            a.to_lowercase() == b.to_lowercase()
+        } else {
+            a == b
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_resolve_prefix_and_nested_paths() {
+        let aliases = CategoryAliases::new(true);
+        aliases.record("sweets", "desserts", Utc::now());
+
+        assert_eq!(
+            aliases.resolve("sweets", None),
+            Some("desserts".to_string())
+        );
+        assert_eq!(
+            aliases.resolve("Sweets/cakes", None),
+            Some("desserts/cakes".to_string())
+        );
+        assert_eq!(aliases.resolve("sweetsandmore", None), None);
+        assert_eq!(aliases.resolve("mains", None), None);
+    }
+
+    #[test]
+    fn test_record_collapses_chains_and_drops_revived_paths() {
+        let aliases = CategoryAliases::new(false);
+        aliases.record("a", "b", Utc::now());
+        aliases.record("b", "c", Utc::now());
+
+        assert_eq!(aliases.resolve("a/x", None), Some("c/x".to_string()));
+        assert_eq!(aliases.resolve("b", None), Some("c".to_string()));
+
+        // Renaming back to "a" makes "a" live again
+        aliases.record("c", "a", Utc::now());
+        assert_eq!(aliases.resolve("a", None), None);
+        assert_eq!(aliases.resolve("b", None), Some("a".to_string()));
+    }
+
+    #[test]
+    fn test_expiry_and_json_round_trip() -> Result<()> {
+        let aliases = CategoryAliases::new(true);
+        let old = Utc::now() - Duration::days(60);
+        aliases.record("old", "new", old);
+        aliases.record("sweets", "desserts", Utc::now());
+
+        let cutoff = Utc::now() - Duration::days(30);
+        assert_eq!(aliases.resolve("old", Some(cutoff)), None);
+
+        let loaded = CategoryAliases::new(true);
+        loaded.load(&aliases.to_json()?)?;
+        assert_eq!(loaded.all(), aliases.all());
+
+        assert_eq!(loaded.purge_expired(cutoff), 1);
+        assert_eq!(loaded.all().len(), 1);
+
+        Ok(())
+    }
+}
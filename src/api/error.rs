@@ -0,0 +1,386 @@
+use axum::{
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::collections::{BTreeMap, HashMap};
+
+use super::middleware::{current_request_id, problem_details_instance, PROBLEM_JSON};
+use super::responses::{ErrorResponse, ProblemDetails};
+use crate::{
+    deadline::OperationTimedOut,
+    hooks::WriteRejected,
+    parser::RecipeError,
+    schema::SchemaViolation,
+    storage::{DraftConflict, MaintenanceRunning, PathTaken, ReadOnlyPath, VersionExists},
+    uploads::UploadError,
+};
+
+/// A resource a lookup can miss, each with its own `<resource>_not_found` code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resource {
+    Recipe,
+    Category,
+    Menu,
+    Template,
+    Version,
+    Note,
+    TrashEntry,
+    Draft,
+    Upload,
+    PantryItem,
+    Collection,
+    Share,
+    ShoppingList,
+    ShoppingItem,
+    Webhook,
+}
+
+impl Resource {
+    fn code(self) -> &'static str {
+        match self {
+            Resource::Recipe => "recipe_not_found",
+            Resource::Category => "category_not_found",
+            Resource::Menu => "menu_not_found",
+            Resource::Template => "template_not_found",
+            Resource::Version => "version_not_found",
+            Resource::Note => "note_not_found",
+            Resource::TrashEntry => "trash_entry_not_found",
+            Resource::Draft => "draft_not_found",
+            Resource::Upload => "upload_not_found",
+            Resource::PantryItem => "pantry_item_not_found",
+            Resource::Collection => "collection_not_found",
+            Resource::Share => "share_not_found",
+            Resource::ShoppingList => "shopping_list_not_found",
+            Resource::ShoppingItem => "shopping_item_not_found",
+            Resource::Webhook => "webhook_not_found",
+        }
+    }
+
+    fn message(self) -> &'static str {
+        match self {
+            Resource::Recipe => "Recipe not found",
+            Resource::Category => "Path not found",
+            Resource::Menu => "Menu not found",
+            Resource::Template => "Template not found",
+            Resource::Version => "Version not found",
+            Resource::Note => "Note not found",
+            Resource::TrashEntry => "Trash entry not found",
+            Resource::Draft => "Draft not found",
+            Resource::Upload => "Upload not found",
+            Resource::PantryItem => "Ingredient not in pantry",
+            Resource::Collection => "Collection not found",
+            Resource::Share => "Share link not found, expired or revoked",
+            Resource::ShoppingList => "Shopping list not found",
+            Resource::ShoppingItem => "Shopping list item not found",
+            Resource::Webhook => "Webhook not found",
+        }
+    }
+}
+
+/// A feature only the git storage backend provides
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitFeature {
+    Drafts,
+    Versions,
+    Blame,
+    Maintenance,
+}
+
+/// Error returned by API handlers
+///
+/// Each variant maps to an HTTP status and a stable `error` code that clients can
+/// branch on; `message` stays human-readable and may change between releases.
+#[derive(Debug)]
+pub enum ApiError {
+    /// 400 `validation_error`: a malformed request field, header or query parameter
+    Validation(String),
+    /// 400 `invalid_front_matter`: recipe content without a valid YAML `title`
+    InvalidFrontMatter(String),
+    /// 400 `invalid_recipe`: Cooklang the parser rejects
+    InvalidRecipe(String),
+    /// 400 `invalid_units`: a units file the parser rejects
+    InvalidUnits(String),
+    /// 400 `invalid_synonyms`: a synonyms file that cannot be parsed
+    InvalidSynonyms(String),
+    /// 400 `invalid_aisles`: an aisles file that cannot be parsed
+    InvalidAisles(String),
+    /// 400 `schema_violation`: front matter not matching `FRONT_MATTER_SCHEMA` (`details` maps field → problem)
+    SchemaViolation {
+        message: String,
+        fields: BTreeMap<String, String>,
+    },
+    /// 400 `write_rejected`: a write hook turned the change down (`details` has `hook` and `reason`)
+    WriteRejected {
+        message: String,
+        hook: String,
+        reason: String,
+    },
+    /// 400 `<feature>_unsupported`: the storage backend lacks the feature
+    RequiresGit(GitFeature),
+    /// 401 `unauthorized`: `API_TOKEN` is set and the request lacks it
+    Unauthorized,
+    /// 403 `read_only`: the change touches a read-only file (e.g. in a git submodule)
+    ReadOnly(String),
+    /// 404 `<resource>_not_found`
+    NotFound(Resource),
+    /// 409 `conflict`: the change collides with existing state
+    Conflict(String),
+    /// 409 `offset_mismatch`: an upload chunk at the wrong offset (`details.offset` has the right one)
+    OffsetMismatch { message: String, expected: u64 },
+    /// 413 `upload_too_large`
+    UploadTooLarge(String),
+    /// 500 `storage_io`: reading or writing the data directory or git repository failed
+    StorageIo(String),
+    /// 500 `internal_error`
+    Internal(String),
+    /// 504 `timeout`: the request deadline passed
+    Timeout {
+        message: String,
+        details: HashMap<String, String>,
+    },
+}
+
+impl ApiError {
+    /// Machine-readable code sent as the `error` field
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::Validation(_) => "validation_error",
+            ApiError::InvalidFrontMatter(_) => "invalid_front_matter",
+            ApiError::InvalidRecipe(_) => "invalid_recipe",
+            ApiError::InvalidUnits(_) => "invalid_units",
+            ApiError::InvalidSynonyms(_) => "invalid_synonyms",
+            ApiError::InvalidAisles(_) => "invalid_aisles",
+            ApiError::SchemaViolation { .. } => "schema_violation",
+            ApiError::WriteRejected { .. } => "write_rejected",
+            ApiError::RequiresGit(GitFeature::Drafts) => "drafts_unsupported",
+            ApiError::RequiresGit(GitFeature::Versions) => "versions_unsupported",
+            ApiError::RequiresGit(GitFeature::Blame) => "blame_unsupported",
+            ApiError::RequiresGit(GitFeature::Maintenance) => "maintenance_unsupported",
+            ApiError::Unauthorized => "unauthorized",
+            ApiError::ReadOnly(_) => "read_only",
+            ApiError::NotFound(resource) => resource.code(),
+            ApiError::Conflict(_) => "conflict",
+            ApiError::OffsetMismatch { .. } => "offset_mismatch",
+            ApiError::UploadTooLarge(_) => "upload_too_large",
+            ApiError::StorageIo(_) => "storage_io",
+            ApiError::Internal(_) => "internal_error",
+            ApiError::Timeout { .. } => "timeout",
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::Validation(_)
+            | ApiError::InvalidFrontMatter(_)
+            | ApiError::InvalidRecipe(_)
+            | ApiError::InvalidUnits(_)
+            | ApiError::InvalidSynonyms(_)
+            | ApiError::InvalidAisles(_)
+            | ApiError::SchemaViolation { .. }
+            | ApiError::WriteRejected { .. }
+            | ApiError::RequiresGit(_) => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ApiError::ReadOnly(_) => StatusCode::FORBIDDEN,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Conflict(_) | ApiError::OffsetMismatch { .. } => StatusCode::CONFLICT,
+            ApiError::UploadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            ApiError::StorageIo(_) | ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::Timeout { .. } => StatusCode::GATEWAY_TIMEOUT,
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            ApiError::Validation(message)
+            | ApiError::InvalidFrontMatter(message)
+            | ApiError::InvalidRecipe(message)
+            | ApiError::InvalidUnits(message)
+            | ApiError::InvalidSynonyms(message)
+            | ApiError::InvalidAisles(message)
+            | ApiError::SchemaViolation { message, .. }
+            | ApiError::WriteRejected { message, .. }
+            | ApiError::ReadOnly(message)
+            | ApiError::Conflict(message)
+            | ApiError::OffsetMismatch { message, .. }
+            | ApiError::UploadTooLarge(message)
+            | ApiError::StorageIo(message)
+            | ApiError::Internal(message)
+            | ApiError::Timeout { message, .. } => message.clone(),
+            ApiError::RequiresGit(GitFeature::Drafts) => "Drafts require git storage".to_string(),
+            ApiError::RequiresGit(GitFeature::Versions) => {
+                "Versions require git storage".to_string()
+            }
+            ApiError::RequiresGit(GitFeature::Blame) => "Blame requires git storage".to_string(),
+            ApiError::RequiresGit(GitFeature::Maintenance) => {
+                "Git maintenance requires git storage".to_string()
+            }
+            ApiError::Unauthorized => "Missing or invalid API token".to_string(),
+            ApiError::NotFound(resource) => resource.message().to_string(),
+        }
+    }
+
+    fn details(&self) -> Option<HashMap<String, String>> {
+        match self {
+            ApiError::OffsetMismatch { expected, .. } => Some(HashMap::from([(
+                "offset".to_string(),
+                expected.to_string(),
+            )])),
+            ApiError::SchemaViolation { fields, .. } => Some(fields.clone().into_iter().collect()),
+            ApiError::WriteRejected { hook, reason, .. } => Some(HashMap::from([
+                ("hook".to_string(), hook.clone()),
+                ("reason".to_string(), reason.clone()),
+            ])),
+            ApiError::Timeout { details, .. } => Some(details.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Errors are sent as an `ErrorResponse`, or as `ProblemDetails` to clients whose
+/// `Accept` header lists `application/problem+json`
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        let mut response = match problem_details_instance() {
+            Some(instance) => {
+                let body = ProblemDetails {
+                    problem_type: format!("urn:cooklang-store:error:{}", self.code()),
+                    title: status.canonical_reason().unwrap_or_default().to_string(),
+                    status: status.as_u16(),
+                    detail: self.message(),
+                    instance,
+                    details: self.details(),
+                    request_id: current_request_id(),
+                };
+                let content_type = [(header::CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON))];
+                (status, content_type, Json(body)).into_response()
+            }
+            None => {
+                let mut body = ErrorResponse::new(self.code(), self.message())
+                    .with_request_id(current_request_id());
+                if let Some(details) = self.details() {
+                    body = body.with_details(details);
+                }
+                (status, Json(body)).into_response()
+            }
+        };
+        if matches!(self, ApiError::Unauthorized) {
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+        }
+        response
+    }
+}
+
+/// Classify a repository error by the typed errors in its chain
+///
+/// Add `.context("Failed to ...")` before `?` so the message says what was attempted;
+/// the context does not hide the underlying error from classification.
+impl From<anyhow::Error> for ApiError {
+    fn from(e: anyhow::Error) -> Self {
+        let message = format!("{:#}", e);
+
+        for cause in e.chain() {
+            if let Some(timed_out) = cause.downcast_ref::<OperationTimedOut>() {
+                let mut details = HashMap::new();
+                details.insert("processed".to_string(), timed_out.processed.to_string());
+                if let Some(total) = timed_out.total {
+                    details.insert("total".to_string(), total.to_string());
+                }
+                return ApiError::Timeout {
+                    message: timed_out.to_string(),
+                    details,
+                };
+            }
+            if let Some(recipe_error) = cause.downcast_ref::<RecipeError>() {
+                return match recipe_error {
+                    RecipeError::InvalidFrontMatter(_) => ApiError::InvalidFrontMatter(message),
+                    RecipeError::Parse(_) => ApiError::InvalidRecipe(message),
+                };
+            }
+            if let Some(violation) = cause.downcast_ref::<SchemaViolation>() {
+                return ApiError::SchemaViolation {
+                    message,
+                    fields: violation.0.clone(),
+                };
+            }
+            if let Some(rejected) = cause.downcast_ref::<WriteRejected>() {
+                return ApiError::WriteRejected {
+                    message,
+                    hook: rejected.hook.clone(),
+                    reason: rejected.reason.clone(),
+                };
+            }
+            if cause.is::<ReadOnlyPath>() {
+                return ApiError::ReadOnly(message);
+            }
+            if cause.is::<DraftConflict>()
+                || cause.is::<VersionExists>()
+                || cause.is::<MaintenanceRunning>()
+                || cause.is::<PathTaken>()
+            {
+                return ApiError::Conflict(cause.to_string());
+            }
+            if cause.is::<std::io::Error>() || cause.is::<git2::Error>() {
+                return ApiError::StorageIo(message);
+            }
+        }
+
+        ApiError::Internal(message)
+    }
+}
+
+impl From<UploadError> for ApiError {
+    fn from(e: UploadError) -> Self {
+        match e {
+            UploadError::NotFound => ApiError::NotFound(Resource::Upload),
+            UploadError::OffsetMismatch { expected, .. } => ApiError::OffsetMismatch {
+                message: e.to_string(),
+                expected,
+            },
+            UploadError::TooLarge { .. } => ApiError::UploadTooLarge(e.to_string()),
+            UploadError::Io(e) => ApiError::from(e.context("Failed to write chunk")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_anyhow_errors_map_to_stable_codes() {
+        let e = anyhow::Error::from(RecipeError::Parse("bad step".to_string()))
+            .context("Failed to create recipe");
+        let api_error = ApiError::from(e);
+        assert_eq!(api_error.code(), "invalid_recipe");
+        assert_eq!(api_error.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            api_error.message(),
+            "Failed to create recipe: Failed to parse recipe: bad step"
+        );
+
+        let io = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        let e = Err::<(), _>(io).context("Failed to write").unwrap_err();
+        assert_eq!(ApiError::from(e).code(), "storage_io");
+
+        let e = anyhow::Error::from(OperationTimedOut {
+            processed: 3,
+            total: Some(10),
+        });
+        let api_error = ApiError::from(e);
+        assert_eq!(api_error.status(), StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!(
+            api_error.details().and_then(|d| d.get("total").cloned()),
+            Some("10".to_string())
+        );
+
+        assert_eq!(
+            ApiError::from(anyhow::anyhow!("boom")).code(),
+            "internal_error"
+        );
+    }
+}
@@ -1,17 +1,58 @@
+use anyhow::Context;
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
+    body::Bytes,
+    extract::{Extension, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Redirect, Response,
+    },
     Json,
 };
+use futures_util::Stream;
+use std::collections::HashMap;
 use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
 
 use crate::{
-    cache::generate_recipe_id, parser::extract_recipe_title, repository::RecipeRepository,
+    cache::{generate_recipe_id, CachedRecipe, RecipeFilter, SortOrder},
+    config::{StoreConfig, ENV_FILE},
+    convert,
+    favorites::DEFAULT_USER,
+    import::{to_cooklang, Fidelity, ImportOutcome, ImportedRecipe},
+    markdown, mealie, nextcloud,
+    notes::{RecipeNotes, MAX_RATING},
+    pantry::{self, PantryItem},
+    paprika,
+    parser::{
+        extract_active_minutes, extract_custom_fields, extract_recipe_title, extract_total_minutes,
+        generate_filename, FrontMatterPatch,
+    },
+    qr::{self, QrFormat},
+    render::HtmlOptions,
+    repository::{Draft, Recipe, RecipeRepository, ReindexReport},
+    shares::Share,
+    shopping::{self, ExportFormat, ItemUpdate, ShoppingItem, ShoppingList},
+    storage::{LastModified, MirrorReport, VersionTag},
+    tandoor,
+    uploads::UploadSession,
+    webhooks::{Delivery, DeliveryStatus, Webhook},
 };
 
 use super::{
-    models::{CreateRecipeRequest, ListQuery, PaginationInfo, SearchQuery, UpdateRecipeRequest},
+    error::{ApiError, GitFeature, Resource},
+    models::{
+        BatchTagRequest, CategoryRenameRequest, ConvertQuery, CookableQuery,
+        CreateFromTemplateRequest, CreateNoteRequest, CreateRecipeRequest, CreateUploadRequest,
+        CreateVersionRequest, CreateWebhookRequest, DeliveryQuery, FavoriteRequest, HtmlQuery,
+        ImportQuery, ListQuery, NormalizeQuery, PaginationInfo, PantryItemRequest,
+        PatchRecipeRequest, PublicListQuery, QrQuery, RatingRequest, RawUpdateQuery,
+        RecipeExportQuery, RecipeTargetRequest, ReconcileQuery, ReindexQuery, SearchQuery,
+        ShareRequest, ShoppingExportQuery, ShoppingItemRequest, ShoppingListQuery,
+        ShoppingListRequest, SummaryDetail, UpdateRecipeRequest,
+    },
     responses::*,
+    SelectedCollection,
 };
 
 /// Health check endpoint - returns simple OK response
@@ -21,14 +62,14 @@ pub async fn health_check() -> &'static str {
 
 /// Status endpoint - returns server status and recipe count
 pub async fn status(State(repo): State<Arc<RecipeRepository>>) -> Json<StatusResponse> {
-    let recipes = repo.list_all();
     let categories = repo.get_categories();
 
     Json(StatusResponse {
         status: "running".to_string(),
         version: env!("CARGO_PKG_VERSION").to_string(),
-        recipe_count: recipes.len(),
+        recipe_count: repo.recipe_count(),
         categories: categories.len(),
+        features: repo.config().features.iter().cloned().collect(),
     })
 }
 
@@ -36,34 +77,21 @@ pub async fn status(State(repo): State<Arc<RecipeRepository>>) -> Json<StatusRes
 pub async fn create_recipe(
     State(repo): State<Arc<RecipeRepository>>,
     Json(payload): Json<CreateRecipeRequest>,
-) -> Result<(StatusCode, Json<RecipeResponse>), (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Response, ApiError> {
     // Validate content is not empty
     if payload.content.trim().is_empty() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse::new(
-                "validation_error",
-                "Recipe content cannot be empty",
-            )),
+        return Err(ApiError::Validation(
+            "Recipe content cannot be empty".to_string(),
         ));
     }
 
     // Extract title from content (validates YAML front matter exists)
-    let recipe_title = match extract_recipe_title(&payload.content) {
-        Ok(title) => title,
-        Err(e) => {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse::new(
-                    "validation_error",
-                    format!(
-                        "Recipe content must include YAML front matter with 'title' field: {}",
-                        e
-                    ),
-                )),
-            ));
-        }
-    };
+    let recipe_title = extract_recipe_title(&payload.content).map_err(|e| {
+        ApiError::InvalidFrontMatter(format!(
+            "Recipe content must include YAML front matter with 'title' field: {}",
+            e
+        ))
+    })?;
 
     // Default path to empty string (root) if not provided
     let path = payload
@@ -71,6 +99,24 @@ pub async fn create_recipe(
         .as_deref()
         .and_then(|p| if p.trim().is_empty() { None } else { Some(p) });
 
+    if payload.draft {
+        require_drafts(&repo)?;
+        return match repo
+            .create_draft(
+                &payload.content,
+                path,
+                payload.author.as_deref(),
+                payload.comment.as_deref(),
+            )
+            .await
+        {
+            Ok(draft) => {
+                Ok((StatusCode::CREATED, Json(draft_response(draft, true))).into_response())
+            }
+            Err(e) => Err(ApiError::from(e.context("Failed to create draft"))),
+        };
+    }
+
     // Create recipe
     match repo
         .create_with_author_and_comment(
@@ -83,100 +129,155 @@ pub async fn create_recipe(
         .await
     {
         Ok(recipe) => {
-            let recipe_id = generate_recipe_id(&recipe.git_path);
-            Ok((
-                StatusCode::CREATED,
-                Json(RecipeResponse {
-                    recipe_id,
-                    recipe_name: recipe.name,
-                    path: recipe.category,
-                    file_name: recipe.file_name,
-                    content: recipe.content,
-                    description: recipe.description,
-                }),
-            ))
+            Ok((StatusCode::CREATED, Json(recipe_response(&repo, recipe))).into_response())
         }
-        Err(e) => Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse::new(
-                "creation_error",
-                format!("Failed to create recipe: {}", e),
-            )),
-        )),
+        Err(e) => Err(ApiError::from(e.context("Failed to create recipe"))),
     }
 }
 
 /// List all recipes with pagination
+///
+/// `custom.<key>=<value>` query parameters filter on custom front-matter fields,
+/// `meta.<key>=<value>` on standard metadata, `max_time` and `max_active_time` on total
+/// and hands-on time in minutes and `favorite` on the favorites of `user`. Drafts (`draft: true`) are left out
+/// unless `include_drafts=true`. Recipes are sorted by name, or by path with `sort=path`.
+/// Listings come from the index; `include_content=true` also reads the content of the
+/// recipes on the page.
 pub async fn list_recipes(
     State(repo): State<Arc<RecipeRepository>>,
     Query(params): Query<ListQuery>,
-) -> Json<RecipeListResponse> {
+    Query(raw_params): Query<HashMap<String, String>>,
+) -> Result<Json<RecipeListResponse>, ApiError> {
     let limit = std::cmp::min(params.limit.unwrap_or(20), 100);
     let offset = params.offset.unwrap_or(0);
 
-    let all_recipes = repo.list_all();
-    let total = all_recipes.len() as u32;
+    let prefixed = |prefix: &str| {
+        raw_params
+            .iter()
+            .filter_map(|(key, value)| {
+                key.strip_prefix(prefix)
+                    .map(|field| (field.to_string(), value.clone()))
+            })
+            .collect()
+    };
+    let filter = RecipeFilter {
+        custom_fields: prefixed("custom."),
+        metadata: prefixed("meta."),
+        max_minutes: params.max_time,
+        max_active_minutes: params.max_active_time,
+        ..Default::default()
+    };
+
+    let user = params.user.as_deref();
+    let (page, total) = repo.list_page(
+        &filter,
+        params.sort,
+        offset as usize,
+        limit as usize,
+        |cached| {
+            (params.include_drafts || !cached.is_draft())
+                && params
+                    .favorite
+                    .is_none_or(|favorite| repo.is_favorite(&cached.git_path, user) == favorite)
+        },
+    );
 
-    let recipes: Vec<RecipeSummary> = all_recipes
+    let recipes = page
         .into_iter()
-        .skip(offset as usize)
-        .take(limit as usize)
         .map(|recipe| {
-            let recipe_id = generate_recipe_id(&recipe.git_path);
-            RecipeSummary {
-                recipe_id,
-                recipe_name: recipe.name,
-                path: recipe.category,
-            }
+            let recipe = if params.include_content {
+                repo.with_content(recipe)
+                    .context("Failed to read recipe content")?
+            } else {
+                recipe
+            };
+            Ok(detailed_summary(&repo, recipe, params.detail))
         })
-        .collect();
+        .collect::<Result<Vec<RecipeSummary>, ApiError>>()?;
 
-    Json(RecipeListResponse {
+    Ok(Json(RecipeListResponse {
         recipes,
         pagination: PaginationInfo {
             limit,
             offset,
-            total,
+            total: total as u32,
         },
-    })
+    }))
 }
 
-/// Search recipes by name
+/// Search recipe names, ingredients and steps, best matches first
+///
+/// `path`, `tag`, `ingredient`, `max_time` and `max_active_time` narrow the results (all
+/// must match);
+/// with any of them set, `q` may be left out to list every matching recipe. Drafts
+/// are left out unless `include_drafts=true`.
 pub async fn search_recipes(
     State(repo): State<Arc<RecipeRepository>>,
     Query(params): Query<SearchQuery>,
-) -> Result<Json<RecipeListResponse>, (StatusCode, Json<ErrorResponse>)> {
-    if params.q.trim().is_empty() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse::new(
-                "validation_error",
-                "Search query cannot be empty",
-            )),
+) -> Result<Json<SearchResponse>, ApiError> {
+    let filter = RecipeFilter {
+        category: params
+            .path
+            .as_deref()
+            .map(|path| path.trim_matches('/'))
+            .filter(|path| !path.is_empty())
+            .map(|path| {
+                repo.resolve_category(path)
+                    .unwrap_or_else(|| path.to_string())
+            }),
+        tags: comma_separated(params.tag.as_deref()),
+        ingredients: comma_separated(params.ingredient.as_deref()),
+        max_minutes: params.max_time,
+        max_active_minutes: params.max_active_time,
+        ..Default::default()
+    };
+    let query = Some(params.q.trim()).filter(|q| !q.is_empty());
+    if query.is_none() && filter.is_empty() {
+        return Err(ApiError::Validation(
+            "Search query or a filter (path, tag, ingredient, max_time, max_active_time) is required"
+                .to_string(),
         ));
     }
 
     let limit = std::cmp::min(params.limit.unwrap_or(20), 100);
     let offset = params.offset.unwrap_or(0);
 
-    let all_results = repo.search_by_name(&params.q);
+    let mut all_results = repo.search_filtered(query, &filter);
+    if !params.include_drafts {
+        all_results.retain(|result| !repo.is_draft(&result.recipe.git_path));
+    }
+    if let Some(favorite) = params.favorite {
+        let user = params.user.as_deref();
+        all_results.retain(|result| repo.is_favorite(&result.recipe.git_path, user) == favorite);
+    }
     let total = all_results.len() as u32;
 
-    let recipes: Vec<RecipeSummary> = all_results
+    let recipes: Vec<SearchResultSummary> = all_results
         .into_iter()
         .skip(offset as usize)
         .take(limit as usize)
-        .map(|recipe| {
-            let recipe_id = generate_recipe_id(&recipe.git_path);
-            RecipeSummary {
-                recipe_id,
-                recipe_name: recipe.name,
-                path: recipe.category,
-            }
+        .map(|result| SearchResultSummary {
+            recipe_id: generate_recipe_id(&result.recipe.git_path),
+            recipe_name: result.recipe.name,
+            path: result.recipe.category,
+            score: result.score,
+            highlights: result
+                .highlights
+                .into_iter()
+                .map(|highlight| SearchHighlight {
+                    field: highlight.field.as_str().to_string(),
+                    snippet: highlight.snippet,
+                    matches: highlight
+                        .matches
+                        .into_iter()
+                        .map(|(start, end)| [start, end])
+                        .collect(),
+                })
+                .collect(),
         })
         .collect();
 
-    Ok(Json(RecipeListResponse {
+    Ok(Json(SearchResponse {
         recipes,
         pagination: PaginationInfo {
             limit,
@@ -190,254 +291,2626 @@ pub async fn search_recipes(
 pub async fn get_recipe(
     State(repo): State<Arc<RecipeRepository>>,
     Path(recipe_id): Path<String>,
-) -> Result<Json<RecipeResponse>, (StatusCode, Json<ErrorResponse>)> {
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
     // Look up git_path from recipe_id using the cache
-    let git_path = repo.get_recipe_git_path(&recipe_id).ok_or_else(|| {
-        (
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse::new("not_found", "Recipe not found")),
-        )
-    })?;
+    let Some(git_path) = repo.get_recipe_git_path(&recipe_id) else {
+        // IDs from before a category rename redirect to the recipe's new ID
+        if let Some(new_id) = repo.resolve_recipe_alias(&recipe_id) {
+            return Ok(Redirect::temporary(&format!("/api/v1/recipes/{}", new_id)).into_response());
+        }
+        return Err(ApiError::NotFound(Resource::Recipe));
+    };
 
     match repo.read(&git_path).await {
-        Ok(recipe) => Ok(Json(RecipeResponse {
-            recipe_id,
-            recipe_name: recipe.name,
-            path: recipe.category,
-            file_name: recipe.file_name,
-            content: recipe.content,
-            description: recipe.description,
-        })),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse::new(
-                "read_error",
-                format!("Failed to read recipe: {}", e),
-            )),
-        )),
+        Ok(recipe) if prefers_plain_text(&headers) => {
+            Ok(raw_recipe_response(recipe.content.unwrap_or_default()))
+        }
+        Ok(recipe) => Ok(Json(recipe_response(&repo, recipe)).into_response()),
+        Err(e) => Err(ApiError::from(e.context("Failed to read recipe"))),
     }
 }
 
-/// Update a recipe
-pub async fn update_recipe(
+/// Get a recipe's Cooklang source as `text/plain`
+pub async fn get_recipe_raw(
     State(repo): State<Arc<RecipeRepository>>,
     Path(recipe_id): Path<String>,
-    Json(payload): Json<UpdateRecipeRequest>,
-) -> Result<Json<RecipeResponse>, (StatusCode, Json<ErrorResponse>)> {
-    // Validate at least one field is provided
-    if payload.content.is_none() && payload.path.is_none() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse::new(
-                "validation_error",
-                "At least one of 'content' or 'path' must be provided",
-            )),
-        ));
+) -> Result<Response, ApiError> {
+    let Some(git_path) = repo.get_recipe_git_path(&recipe_id) else {
+        if let Some(new_id) = repo.resolve_recipe_alias(&recipe_id) {
+            return Ok(
+                Redirect::temporary(&format!("/api/v1/recipes/{}/raw", new_id)).into_response(),
+            );
+        }
+        return Err(ApiError::NotFound(Resource::Recipe));
+    };
+
+    match repo.read(&git_path).await {
+        Ok(recipe) => Ok(raw_recipe_response(recipe.content.unwrap_or_default())),
+        Err(e) => Err(ApiError::from(e.context("Failed to read recipe"))),
     }
+}
 
-    // Look up git_path from recipe_id
-    let git_path = repo.get_recipe_git_path(&recipe_id).ok_or_else(|| {
-        (
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse::new("not_found", "Recipe not found")),
-        )
-    })?;
+/// Get a recipe rendered as an HTML page (or, with `?fragment=true`, an `<article>`);
+/// `?print=true` selects the printable layout
+pub async fn get_recipe_html(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(recipe_id): Path<String>,
+    Query(params): Query<HtmlQuery>,
+) -> Result<Response, ApiError> {
+    let Some(git_path) = repo.get_recipe_git_path(&recipe_id) else {
+        if let Some(new_id) = repo.resolve_recipe_alias(&recipe_id) {
+            return Ok(
+                Redirect::temporary(&format!("/api/v1/recipes/{}/html", new_id)).into_response(),
+            );
+        }
+        return Err(ApiError::NotFound(Resource::Recipe));
+    };
 
-    // If content provided, validate it has YAML front matter with title
-    if let Some(ref content) = payload.content {
-        if extract_recipe_title(content).is_err() {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse::new(
-                    "validation_error",
-                    "Recipe content must include YAML front matter with 'title' field",
-                )),
-            ));
+    let options = HtmlOptions {
+        fragment: params.fragment,
+        print: params.print,
+    };
+    match repo.render_html(&git_path, &options) {
+        Ok(html) => Ok(([(header::CONTENT_TYPE, HTML_CONTENT_TYPE)], html).into_response()),
+        Err(e) => Err(ApiError::from(e.context("Failed to render recipe"))),
+    }
+}
+
+/// Content type of rendered recipes
+const HTML_CONTENT_TYPE: &str = "text/html; charset=utf-8";
+
+/// Get a QR code (SVG, or PNG with `?format=png`) linking to the recipe's HTML page
+pub async fn get_recipe_qr(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(recipe_id): Path<String>,
+    Query(params): Query<QrQuery>,
+    collection: Option<Extension<SelectedCollection>>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let format = match params.format.as_deref() {
+        None => QrFormat::Svg,
+        Some(value) => QrFormat::parse(value).ok_or_else(|| {
+            ApiError::Validation(format!(
+                "Unknown QR code format: {} (expected svg or png)",
+                value
+            ))
+        })?,
+    };
+    let Some(git_path) = repo.get_recipe_git_path(&recipe_id) else {
+        if let Some(new_id) = repo.resolve_recipe_alias(&recipe_id) {
+            let query = params
+                .format
+                .map(|format| format!("?format={}", urlencoding::encode(&format)))
+                .unwrap_or_default();
+            return Ok(
+                Redirect::temporary(&format!("/api/v1/recipes/{}/qr{}", new_id, query))
+                    .into_response(),
+            );
         }
+        return Err(ApiError::NotFound(Resource::Recipe));
+    };
+
+    // Public recipes link to the page that needs no API token
+    let route = if repo.is_public(&git_path) {
+        "public/recipes"
+    } else {
+        "recipes"
+    };
+    let url = format!(
+        "{}{}/{}/{}/html",
+        public_base_url(&repo, &headers),
+        api_prefix(collection),
+        route,
+        generate_recipe_id(&git_path)
+    );
+    match qr::render(&url, format) {
+        Ok(image) => Ok(([(header::CONTENT_TYPE, format.content_type())], image).into_response()),
+        Err(e) => Err(ApiError::from(e.context("Failed to create QR code"))),
     }
+}
 
-    // Convert empty path string to None
-    let path = payload
-        .path
-        .as_deref()
-        .and_then(|p| if p.trim().is_empty() { None } else { Some(p) });
+/// API path of the request's collection
+///
+/// Links opened elsewhere (scanned, shared) can't send `X-Collection`, so they name the
+/// collection in the path.
+fn api_prefix(collection: Option<Extension<SelectedCollection>>) -> String {
+    match collection {
+        Some(Extension(SelectedCollection(name))) => format!("/api/v1/collections/{}", name),
+        None => "/api/v1".to_string(),
+    }
+}
+
+/// Longest lifetime a share link can be given, one year
+const MAX_SHARE_LINK_HOURS: u64 = 24 * 365;
+
+/// Create a signed link giving read-only access to a recipe until it expires
+pub async fn create_share(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(recipe_id): Path<String>,
+    collection: Option<Extension<SelectedCollection>>,
+    headers: HeaderMap,
+    Json(payload): Json<ShareRequest>,
+) -> Result<(StatusCode, Json<ShareResponse>), ApiError> {
+    let hours = payload
+        .expires_in_hours
+        .unwrap_or(repo.config().share_link_ttl_hours);
+    if !(1..=MAX_SHARE_LINK_HOURS).contains(&hours) {
+        return Err(ApiError::Validation(format!(
+            "expiresInHours must be between 1 and {}",
+            MAX_SHARE_LINK_HOURS
+        )));
+    }
+    let git_path = repo
+        .get_recipe_git_path(&recipe_id)
+        .ok_or(ApiError::NotFound(Resource::Recipe))?;
 
+    let base = format!(
+        "{}{}",
+        public_base_url(&repo, &headers),
+        api_prefix(collection)
+    );
     match repo
-        .update_with_author_and_comment(
+        .share_recipe(
             &git_path,
-            None, // name parameter deprecated (extracted from content)
-            payload.content.as_deref(),
-            path.map(Some),
+            chrono::Duration::hours(hours as i64),
             payload.author.as_deref(),
-            payload.comment.as_deref(),
         )
         .await
     {
-        Ok(recipe) => {
-            let updated_id = generate_recipe_id(&recipe.git_path);
-            Ok(Json(RecipeResponse {
-                recipe_id: updated_id,
-                recipe_name: recipe.name,
-                path: recipe.category,
-                file_name: recipe.file_name,
-                content: recipe.content,
-                description: recipe.description,
-            }))
-        }
-        Err(e) => Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse::new(
-                "update_error",
-                format!("Failed to update recipe: {}", e),
-            )),
+        Ok((share, token)) => Ok((
+            StatusCode::CREATED,
+            Json(share_response(&base, share, token)),
         )),
+        Err(e) => Err(ApiError::from(e.context("Failed to create share link"))),
     }
 }
 
-/// Delete a recipe
-pub async fn delete_recipe(
+/// Active share links of a recipe
+pub async fn list_shares(
     State(repo): State<Arc<RecipeRepository>>,
     Path(recipe_id): Path<String>,
-) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
-    // Look up git_path from recipe_id
-    let git_path = repo.get_recipe_git_path(&recipe_id).ok_or_else(|| {
-        (
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse::new("not_found", "Recipe not found")),
-        )
-    })?;
+    collection: Option<Extension<SelectedCollection>>,
+    headers: HeaderMap,
+) -> Result<Json<ShareListResponse>, ApiError> {
+    let git_path = repo
+        .get_recipe_git_path(&recipe_id)
+        .ok_or(ApiError::NotFound(Resource::Recipe))?;
 
-    match repo.delete(&git_path).await {
-        Ok(_) => Ok(StatusCode::NO_CONTENT),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse::new(
-                "delete_error",
-                format!("Failed to delete recipe: {}", e),
-            )),
-        )),
+    let base = format!(
+        "{}{}",
+        public_base_url(&repo, &headers),
+        api_prefix(collection)
+    );
+    match repo.recipe_shares(&git_path) {
+        Ok(shares) => Ok(Json(ShareListResponse {
+            shares: shares
+                .into_iter()
+                .map(|(share, token)| share_response(&base, share, token))
+                .collect(),
+        })),
+        Err(e) => Err(ApiError::from(e.context("Failed to list share links"))),
     }
 }
 
-/// Find recipes by name (fallback lookup for when IDs change)
-pub async fn find_recipe_by_name(
+/// Revoke a share link; its token stops working immediately
+pub async fn revoke_share(
     State(repo): State<Arc<RecipeRepository>>,
-    Query(params): Query<SearchQuery>,
-) -> Result<Json<RecipeListResponse>, (StatusCode, Json<ErrorResponse>)> {
-    if params.q.trim().is_empty() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse::new(
-                "validation_error",
-                "Search query cannot be empty",
-            )),
-        ));
+    Path(share_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    match repo.revoke_share(&share_id, None).await {
+        Ok(Some(_)) => Ok(StatusCode::NO_CONTENT),
+        Ok(None) => Err(ApiError::NotFound(Resource::Share)),
+        Err(e) => Err(ApiError::from(e.context("Failed to revoke share link"))),
+    }
+}
+
+/// A shared recipe as JSON, for anyone holding the link
+pub async fn get_shared_recipe(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(token): Path<String>,
+) -> Result<Response, ApiError> {
+    let share = repo
+        .resolve_share(&token)
+        .ok_or(ApiError::NotFound(Resource::Share))?;
+
+    let recipe = read_only_recipe(&repo, &share.path).await?;
+    Ok((SHARED_HEADERS, recipe).into_response())
+}
+
+/// A shared recipe as a web page, for anyone holding the link
+pub async fn get_shared_recipe_html(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(token): Path<String>,
+    Query(params): Query<HtmlQuery>,
+) -> Result<Response, ApiError> {
+    let share = repo
+        .resolve_share(&token)
+        .ok_or(ApiError::NotFound(Resource::Share))?;
+
+    let options = HtmlOptions {
+        fragment: params.fragment,
+        print: params.print,
+    };
+    match repo.render_html(&share.path, &options) {
+        Ok(html) => Ok((
+            SHARED_HEADERS,
+            [(header::CONTENT_TYPE, HTML_CONTENT_TYPE)],
+            html,
+        )
+            .into_response()),
+        Err(e) => Err(ApiError::from(e.context("Failed to render recipe"))),
+    }
+}
+
+/// A recipe as JSON for readers who may not change it (share links, public routes)
+async fn read_only_recipe(
+    repo: &RecipeRepository,
+    git_path: &str,
+) -> Result<Json<RecipeResponse>, ApiError> {
+    match repo.read(git_path).await {
+        Ok(recipe) => Ok(Json(RecipeResponse {
+            read_only: true,
+            ..recipe_response(repo, recipe)
+        })),
+        Err(e) => Err(ApiError::from(e.context("Failed to read recipe"))),
     }
+}
 
+/// Public recipes (`visibility: public`), for readers without an API token
+pub async fn list_public_recipes(
+    State(repo): State<Arc<RecipeRepository>>,
+    Query(params): Query<PublicListQuery>,
+) -> Json<RecipeListResponse> {
     let limit = std::cmp::min(params.limit.unwrap_or(20), 100);
     let offset = params.offset.unwrap_or(0);
 
-    let all_results = repo.search_by_name(&params.q);
-    let total = all_results.len() as u32;
+    let (page, total) = repo.list_page(
+        &RecipeFilter::default(),
+        SortOrder::Name,
+        offset as usize,
+        limit as usize,
+        CachedRecipe::is_public,
+    );
 
-    let recipes: Vec<RecipeSummary> = all_results
+    let recipes: Vec<RecipeSummary> = page
         .into_iter()
-        .skip(offset as usize)
-        .take(limit as usize)
-        .map(|recipe| {
-            let recipe_id = generate_recipe_id(&recipe.git_path);
-            RecipeSummary {
-                recipe_id,
-                recipe_name: recipe.name,
-                path: recipe.category,
-            }
+        .map(|recipe| RecipeSummary {
+            read_only: true,
+            ..detailed_summary(&repo, recipe, params.detail)
         })
         .collect();
 
-    Ok(Json(RecipeListResponse {
+    Json(RecipeListResponse {
         recipes,
         pagination: PaginationInfo {
             limit,
             offset,
-            total,
+            total: total as u32,
         },
-    }))
+    })
 }
 
-/// Find a recipe by exact path (fallback lookup for when IDs change)
-#[derive(serde::Deserialize)]
-pub struct FindByPathQuery {
-    pub path: Option<String>,
+/// A public recipe as JSON; private recipes are reported as missing
+pub async fn get_public_recipe(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(recipe_id): Path<String>,
+) -> Result<Response, ApiError> {
+    let git_path = match repo.get_recipe_git_path(&recipe_id) {
+        Some(git_path) if repo.is_public(&git_path) => git_path,
+        Some(_) => return Err(ApiError::NotFound(Resource::Recipe)),
+        None => return public_alias_redirect(&repo, &recipe_id, ""),
+    };
+
+    Ok(read_only_recipe(&repo, &git_path).await?.into_response())
 }
 
-pub async fn find_recipe_by_path(
+/// A public recipe as a web page; private recipes are reported as missing
+pub async fn get_public_recipe_html(
     State(repo): State<Arc<RecipeRepository>>,
-    Query(params): Query<FindByPathQuery>,
-) -> Result<Json<Vec<RecipeSummary>>, (StatusCode, Json<ErrorResponse>)> {
-    let path = params.path.as_deref().unwrap_or("");
+    Path(recipe_id): Path<String>,
+    Query(params): Query<HtmlQuery>,
+) -> Result<Response, ApiError> {
+    let git_path = match repo.get_recipe_git_path(&recipe_id) {
+        Some(git_path) if repo.is_public(&git_path) => git_path,
+        Some(_) => return Err(ApiError::NotFound(Resource::Recipe)),
+        None => return public_alias_redirect(&repo, &recipe_id, "/html"),
+    };
 
-    // Find all recipes at the specified path
-    let all_recipes = repo.list_all();
+    let options = HtmlOptions {
+        fragment: params.fragment,
+        print: params.print,
+    };
+    match repo.render_html(&git_path, &options) {
+        Ok(html) => Ok(([(header::CONTENT_TYPE, HTML_CONTENT_TYPE)], html).into_response()),
+        Err(e) => Err(ApiError::from(e.context("Failed to render recipe"))),
+    }
+}
 
-    let matching: Vec<RecipeSummary> = all_recipes
-        .into_iter()
-        .filter(|recipe| recipe.category.as_deref().unwrap_or("") == path)
-        .map(|recipe| {
-            let recipe_id = generate_recipe_id(&recipe.git_path);
-            RecipeSummary {
-                recipe_id,
-                recipe_name: recipe.name,
-                path: recipe.category,
-            }
-        })
-        .collect();
+/// Redirect an ID from before a category rename to the public recipe it now names
+///
+/// Private recipes get the same `404` as missing ones, so their IDs stay hidden.
+fn public_alias_redirect(
+    repo: &RecipeRepository,
+    recipe_id: &str,
+    suffix: &str,
+) -> Result<Response, ApiError> {
+    let new_id = repo.resolve_recipe_alias(recipe_id).filter(|new_id| {
+        repo.get_recipe_git_path(new_id)
+            .is_some_and(|git_path| repo.is_public(&git_path))
+    });
+    match new_id {
+        Some(new_id) => Ok(Redirect::temporary(&format!(
+            "/api/v1/public/recipes/{}{}",
+            new_id, suffix
+        ))
+        .into_response()),
+        None => Err(ApiError::NotFound(Resource::Recipe)),
+    }
+}
+
+/// Headers of shared recipes: revocation must take effect at once, and the link is
+/// meant for the people it was given to, not search engines
+const SHARED_HEADERS: [(&str, &str); 2] =
+    [("cache-control", "no-store"), ("x-robots-tag", "noindex")];
 
-    Ok(Json(matching))
+fn share_response(base: &str, share: Share, token: String) -> ShareResponse {
+    ShareResponse {
+        share_id: share.id,
+        recipe_id: generate_recipe_id(&share.path),
+        url: format!("{}/shared/{}", base, token),
+        html_url: format!("{}/shared/{}/html", base, token),
+        token,
+        created_at: share.created_at.to_rfc3339(),
+        expires_at: share.expires_at.to_rfc3339(),
+        created_by: share.created_by,
+    }
 }
 
-/// List all categories
-pub async fn list_categories(
-    State(repo): State<Arc<RecipeRepository>>,
-) -> Json<CategoryListResponse> {
-    let categories = repo.get_categories();
-    Json(CategoryListResponse { categories })
+/// `PUBLIC_URL`, or the scheme and host the request came in on (as forwarded by a proxy)
+fn public_base_url(repo: &RecipeRepository, headers: &HeaderMap) -> String {
+    if let Some(url) = &repo.config().public_url {
+        return url.clone();
+    }
+    let header_value = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(',').next())
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let scheme = header_value("x-forwarded-proto").unwrap_or_else(|| "http".to_string());
+    let host = header_value("x-forwarded-host")
+        .or_else(|| header_value("host"))
+        .unwrap_or_else(|| "localhost:3000".to_string());
+    format!("{}://{}", scheme, host)
 }
 
-/// Get recipes in a category
-pub async fn get_category_recipes(
+/// Content type of raw Cooklang responses
+const COOKLANG_CONTENT_TYPE: &str = "text/plain; charset=utf-8";
+
+fn raw_recipe_response(content: String) -> Response {
+    ([(header::CONTENT_TYPE, COOKLANG_CONTENT_TYPE)], content).into_response()
+}
+
+/// Whether the `Accept` header asks for `text/plain` ahead of JSON
+///
+/// Media types are taken in the order listed; wildcards (`*/*`) and a missing
+/// header keep the JSON default.
+fn prefers_plain_text(headers: &HeaderMap) -> bool {
+    let Some(accept) = headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return false;
+    };
+
+    accept
+        .split(',')
+        .map(|media_type| {
+            media_type
+                .split(';')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_ascii_lowercase()
+        })
+        .find(|media_type| media_type == "text/plain" || media_type == "application/json")
+        .is_some_and(|media_type| media_type == "text/plain")
+}
+
+/// Update a recipe
+pub async fn update_recipe(
     State(repo): State<Arc<RecipeRepository>>,
-    Path(category_name): Path<String>,
-) -> Result<Json<CategoryRecipesResponse>, (StatusCode, Json<ErrorResponse>)> {
-    // Verify category exists
-    let categories = repo.get_categories();
-    if !categories.contains(&category_name) {
-        return Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse::new(
-                "not_found",
-                format!("Path '{}' not found", category_name),
-            )),
+    Path(recipe_id): Path<String>,
+    Json(payload): Json<UpdateRecipeRequest>,
+) -> Result<Response, ApiError> {
+    // Validate at least one field is provided
+    if payload.content.is_none() && payload.path.is_none() {
+        return Err(ApiError::Validation(
+            "At least one of 'content' or 'path' must be provided".to_string(),
         ));
     }
 
-    let recipes = repo.list_by_category(&category_name);
-    let summaries: Vec<RecipeSummary> = recipes
-        .into_iter()
-        .map(|recipe| {
-            let recipe_id = generate_recipe_id(&recipe.git_path);
-            RecipeSummary {
-                recipe_id,
-                recipe_name: recipe.name,
-                path: recipe.category,
+    // Look up git_path from recipe_id
+    let git_path = repo
+        .get_recipe_git_path(&recipe_id)
+        .ok_or(ApiError::NotFound(Resource::Recipe))?;
+
+    // If content provided, validate it has YAML front matter with title
+    if let Some(ref content) = payload.content {
+        if let Err(e) = extract_recipe_title(content) {
+            return Err(ApiError::InvalidFrontMatter(format!(
+                "Recipe content must include YAML front matter with 'title' field: {}",
+                e
+            )));
+        }
+    }
+
+    // Convert empty path string to None
+    let path = payload
+        .path
+        .as_deref()
+        .and_then(|p| if p.trim().is_empty() { None } else { Some(p) });
+
+    if payload.draft {
+        require_drafts(&repo)?;
+        let content = match (payload.content.as_deref(), path) {
+            (Some(content), None) => content,
+            _ => {
+                return Err(ApiError::Validation(
+                    "Drafts take 'content' only; move or rename the recipe after publishing"
+                        .to_string(),
+                ))
             }
-        })
-        .collect();
+        };
 
-    let count = summaries.len();
+        return match repo
+            .update_draft(
+                &git_path,
+                content,
+                payload.author.as_deref(),
+                payload.comment.as_deref(),
+            )
+            .await
+        {
+            Ok(draft) => Ok(Json(draft_response(draft, true)).into_response()),
+            Err(e) => Err(ApiError::from(e.context("Failed to save draft"))),
+        };
+    }
 
-    Ok(Json(CategoryRecipesResponse {
-        path: category_name,
-        recipes: summaries,
-        count,
-    }))
+    match repo
+        .update_with_author_and_comment(
+            &git_path,
+            None, // name parameter deprecated (extracted from content)
+            payload.content.as_deref(),
+            path.map(Some),
+            payload.author.as_deref(),
+            payload.comment.as_deref(),
+        )
+        .await
+    {
+        Ok(recipe) => Ok(Json(recipe_response(&repo, recipe)).into_response()),
+        Err(e) => Err(ApiError::from(e.context("Failed to update recipe"))),
+    }
+}
+
+/// Change a recipe's title, tags or servings without resending its content
+pub async fn patch_recipe(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(recipe_id): Path<String>,
+    Json(payload): Json<PatchRecipeRequest>,
+) -> Result<Response, ApiError> {
+    let git_path = repo
+        .get_recipe_git_path(&recipe_id)
+        .ok_or(ApiError::NotFound(Resource::Recipe))?;
+
+    if payload
+        .title
+        .as_deref()
+        .is_some_and(|title| title.trim().is_empty())
+    {
+        return Err(ApiError::Validation("Title cannot be empty".to_string()));
+    }
+    let servings = match payload.servings {
+        None => None,
+        Some(value @ (serde_json::Value::String(_) | serde_json::Value::Number(_))) => {
+            serde_yaml::to_value(value).ok()
+        }
+        Some(_) => {
+            return Err(ApiError::Validation(
+                "'servings' must be a string or a number".to_string(),
+            ))
+        }
+    };
+    let patch = FrontMatterPatch {
+        title: payload.title,
+        tags: payload.tags,
+        servings,
+    };
+    if patch.is_empty() {
+        return Err(ApiError::Validation(
+            "At least one of 'title', 'tags' or 'servings' must be provided".to_string(),
+        ));
+    }
+
+    match repo
+        .patch_front_matter(
+            &git_path,
+            &patch,
+            payload.author.as_deref(),
+            payload.comment.as_deref(),
+        )
+        .await
+    {
+        Ok(recipe) => Ok(Json(recipe_response(&repo, recipe)).into_response()),
+        Err(e) => Err(ApiError::from(e.context("Failed to update recipe"))),
+    }
+}
+
+/// Add and remove tags across many recipes in one change
+///
+/// Unknown recipe IDs are reported in `notFound`; the other recipes are still retagged.
+pub async fn batch_tag(
+    State(repo): State<Arc<RecipeRepository>>,
+    Json(payload): Json<BatchTagRequest>,
+) -> Result<Json<BatchTagResponse>, ApiError> {
+    let is_blank = |tags: &[String]| tags.iter().all(|tag| tag.trim().is_empty());
+    if is_blank(&payload.add) && is_blank(&payload.remove) {
+        return Err(ApiError::Validation(
+            "At least one tag to 'add' or 'remove' must be provided".to_string(),
+        ));
+    }
+    if payload.recipe_ids.is_empty() {
+        return Err(ApiError::Validation(
+            "'recipeIds' cannot be empty".to_string(),
+        ));
+    }
+
+    let mut git_paths = Vec::new();
+    let mut not_found = Vec::new();
+    for recipe_id in &payload.recipe_ids {
+        match repo.get_recipe_git_path(recipe_id) {
+            Some(git_path) => git_paths.push(git_path),
+            None => not_found.push(recipe_id.clone()),
+        }
+    }
+
+    let report = repo
+        .retag(
+            &git_paths,
+            &payload.add,
+            &payload.remove,
+            payload.author.as_deref(),
+            payload.comment.as_deref(),
+        )
+        .await
+        .context("Failed to retag recipes")?;
+
+    let ids = |git_paths: Vec<String>| {
+        git_paths
+            .iter()
+            .map(|git_path| generate_recipe_id(git_path))
+            .collect()
+    };
+    Ok(Json(BatchTagResponse {
+        updated: ids(report.updated),
+        unchanged: ids(report.unchanged),
+        not_found,
+    }))
+}
+
+/// Replace a recipe's content with a bare Cooklang body (`text/plain`)
+///
+/// Same as updating `content` through [`update_recipe`]: a changed title renames the
+/// file. `author` and `comment` come from the query string.
+pub async fn update_recipe_raw(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(recipe_id): Path<String>,
+    Query(params): Query<RawUpdateQuery>,
+    content: String,
+) -> Result<Response, ApiError> {
+    let git_path = repo
+        .get_recipe_git_path(&recipe_id)
+        .ok_or(ApiError::NotFound(Resource::Recipe))?;
+
+    if content.trim().is_empty() {
+        return Err(ApiError::Validation(
+            "Recipe content cannot be empty".to_string(),
+        ));
+    }
+    if let Err(e) = extract_recipe_title(&content) {
+        return Err(ApiError::InvalidFrontMatter(format!(
+            "Recipe content must include YAML front matter with 'title' field: {}",
+            e
+        )));
+    }
+
+    match repo
+        .update_with_author_and_comment(
+            &git_path,
+            None,
+            Some(&content),
+            None,
+            params.author.as_deref(),
+            params.comment.as_deref(),
+        )
+        .await
+    {
+        Ok(recipe) => Ok(Json(recipe_response(&repo, recipe)).into_response()),
+        Err(e) => Err(ApiError::from(e.context("Failed to update recipe"))),
+    }
+}
+
+/// Delete a recipe
+pub async fn delete_recipe(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(recipe_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    // Look up git_path from recipe_id
+    let git_path = repo
+        .get_recipe_git_path(&recipe_id)
+        .ok_or(ApiError::NotFound(Resource::Recipe))?;
+
+    match repo.delete(&git_path).await {
+        Ok(_) => Ok(StatusCode::NO_CONTENT),
+        Err(e) => Err(ApiError::from(e.context("Failed to delete recipe"))),
+    }
+}
+
+/// Move a recipe to another directory path without resending its content
+pub async fn move_recipe(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(recipe_id): Path<String>,
+    Json(payload): Json<RecipeTargetRequest>,
+) -> Result<Json<RecipeResponse>, ApiError> {
+    let git_path = repo
+        .get_recipe_git_path(&recipe_id)
+        .ok_or(ApiError::NotFound(Resource::Recipe))?;
+
+    // Convert empty path string to None (root)
+    let path = payload
+        .path
+        .as_deref()
+        .and_then(|p| if p.trim().is_empty() { None } else { Some(p) });
+
+    match repo
+        .move_recipe(
+            &git_path,
+            path,
+            payload.author.as_deref(),
+            payload.comment.as_deref(),
+        )
+        .await
+    {
+        Ok(recipe) => Ok(Json(recipe_response(&repo, recipe))),
+        Err(e) => Err(ApiError::from(e.context("Failed to move recipe"))),
+    }
+}
+
+/// Copy a recipe into a directory path (numeric suffix added on file name collision)
+pub async fn copy_recipe(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(recipe_id): Path<String>,
+    Json(payload): Json<RecipeTargetRequest>,
+) -> Result<(StatusCode, Json<RecipeResponse>), ApiError> {
+    let git_path = repo
+        .get_recipe_git_path(&recipe_id)
+        .ok_or(ApiError::NotFound(Resource::Recipe))?;
+
+    // Convert empty path string to None (root)
+    let path = payload
+        .path
+        .as_deref()
+        .and_then(|p| if p.trim().is_empty() { None } else { Some(p) });
+
+    match repo
+        .copy_recipe(
+            &git_path,
+            path,
+            payload.author.as_deref(),
+            payload.comment.as_deref(),
+        )
+        .await
+    {
+        Ok(recipe) => Ok((StatusCode::CREATED, Json(recipe_response(&repo, recipe)))),
+        Err(e) => Err(ApiError::from(e.context("Failed to copy recipe"))),
+    }
+}
+
+/// Tag the recipe's current content as a named version
+pub async fn create_version(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(recipe_id): Path<String>,
+    Json(payload): Json<CreateVersionRequest>,
+) -> Result<(StatusCode, Json<RecipeVersionResponse>), ApiError> {
+    let git_path = versioned_recipe_path(&repo, &recipe_id)?;
+
+    let name = payload.name.trim();
+    if !is_valid_version_name(name) {
+        return Err(ApiError::Validation("Version name must be 1-100 letters, digits, '.', '_' or '-' and start with a letter or digit".to_string()));
+    }
+
+    match repo
+        .tag_version(
+            &git_path,
+            name,
+            payload.author.as_deref(),
+            payload.comment.as_deref(),
+        )
+        .await
+    {
+        Ok(version) => Ok((StatusCode::CREATED, Json(version_response(version, None)))),
+        Err(e) => Err(ApiError::from(e.context("Failed to tag version"))),
+    }
+}
+
+/// List a recipe's tagged versions, newest first
+pub async fn list_versions(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(recipe_id): Path<String>,
+) -> Result<Json<RecipeVersionListResponse>, ApiError> {
+    let git_path = versioned_recipe_path(&repo, &recipe_id)?;
+
+    let versions = repo
+        .list_versions(&git_path)
+        .await
+        .context("Failed to list versions")?;
+
+    let versions: Vec<RecipeVersionResponse> = versions
+        .into_iter()
+        .map(|version| version_response(version, None))
+        .collect();
+    let count = versions.len();
+    Ok(Json(RecipeVersionListResponse { versions, count }))
+}
+
+/// Get a recipe's content at a tagged version
+pub async fn get_version(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path((recipe_id, name)): Path<(String, String)>,
+) -> Result<Json<RecipeVersionResponse>, ApiError> {
+    let git_path = versioned_recipe_path(&repo, &recipe_id)?;
+    if !is_valid_version_name(&name) {
+        return Err(ApiError::NotFound(Resource::Version));
+    }
+
+    match repo.get_version(&git_path, &name).await {
+        Ok(Some((version, content))) => Ok(Json(version_response(version, Some(content)))),
+        Ok(None) => Err(ApiError::NotFound(Resource::Version)),
+        Err(e) => Err(ApiError::from(e.context("Failed to read version"))),
+    }
+}
+
+/// The recipe's path, or 404 if it doesn't exist / 400 if the backend has no versions
+fn versioned_recipe_path(repo: &RecipeRepository, recipe_id: &str) -> Result<String, ApiError> {
+    let git_path = repo
+        .get_recipe_git_path(recipe_id)
+        .ok_or(ApiError::NotFound(Resource::Recipe))?;
+
+    if !repo.supports_versions() {
+        return Err(ApiError::RequiresGit(GitFeature::Versions));
+    }
+
+    Ok(git_path)
+}
+
+/// Version names become part of a git tag name, so keep them to a safe character set
+fn is_valid_version_name(name: &str) -> bool {
+    name.len() <= 100
+        && name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphanumeric())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+        && !name.ends_with(".lock")
+        && !name.contains("..")
+}
+
+fn version_response(version: VersionTag, content: Option<String>) -> RecipeVersionResponse {
+    RecipeVersionResponse {
+        name: version.name,
+        commit_id: version.commit_id,
+        message: version.message,
+        author: version.tagger,
+        created_at: version.created_at.to_rfc3339(),
+        content,
+    }
+}
+
+/// Star or unstar a recipe for a user
+pub async fn set_favorite(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(recipe_id): Path<String>,
+    Json(payload): Json<FavoriteRequest>,
+) -> Result<Json<FavoriteResponse>, ApiError> {
+    let git_path = repo
+        .get_recipe_git_path(&recipe_id)
+        .ok_or(ApiError::NotFound(Resource::Recipe))?;
+
+    let user = payload
+        .user
+        .as_deref()
+        .map(str::trim)
+        .filter(|user| !user.is_empty());
+
+    match repo.set_favorite(&git_path, user, payload.favorite).await {
+        Ok(()) => Ok(Json(FavoriteResponse {
+            recipe_id,
+            user: user.unwrap_or(DEFAULT_USER).to_string(),
+            favorite: payload.favorite,
+        })),
+        Err(e) => Err(ApiError::from(e.context("Failed to update favorite"))),
+    }
+}
+
+/// Rating and cooking notes of a recipe
+pub async fn get_notes(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(recipe_id): Path<String>,
+) -> Result<Json<RecipeNotesResponse>, ApiError> {
+    let git_path = notes_recipe_path(&repo, &recipe_id)?;
+
+    match repo.notes(&git_path) {
+        Ok(notes) => Ok(Json(notes_response(recipe_id, notes))),
+        Err(e) => Err(notes_error(e)),
+    }
+}
+
+/// Rate a recipe from 1 to 5 stars, or clear its rating with `null`
+pub async fn set_rating(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(recipe_id): Path<String>,
+    Json(payload): Json<RatingRequest>,
+) -> Result<Json<RecipeNotesResponse>, ApiError> {
+    let git_path = notes_recipe_path(&repo, &recipe_id)?;
+
+    if let Some(rating) = payload.rating {
+        if !(1..=MAX_RATING).contains(&rating) {
+            return Err(ApiError::Validation(format!(
+                "Rating must be between 1 and {}",
+                MAX_RATING
+            )));
+        }
+    }
+
+    match repo
+        .set_rating(&git_path, payload.rating, payload.author.as_deref())
+        .await
+    {
+        Ok(notes) => Ok(Json(notes_response(recipe_id, notes))),
+        Err(e) => Err(notes_error(e)),
+    }
+}
+
+/// Add a dated cooking note to a recipe
+pub async fn add_note(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(recipe_id): Path<String>,
+    Json(payload): Json<CreateNoteRequest>,
+) -> Result<(StatusCode, Json<RecipeNotesResponse>), ApiError> {
+    let git_path = notes_recipe_path(&repo, &recipe_id)?;
+
+    if payload.text.trim().is_empty() {
+        return Err(ApiError::Validation(
+            "Note text cannot be empty".to_string(),
+        ));
+    }
+
+    match repo
+        .add_note(
+            &git_path,
+            payload.text.trim(),
+            payload.date,
+            payload.author.as_deref(),
+        )
+        .await
+    {
+        Ok(notes) => Ok((StatusCode::CREATED, Json(notes_response(recipe_id, notes)))),
+        Err(e) => Err(notes_error(e)),
+    }
+}
+
+/// Delete a cooking note by its index
+pub async fn delete_note(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path((recipe_id, index)): Path<(String, usize)>,
+) -> Result<Json<RecipeNotesResponse>, ApiError> {
+    let git_path = notes_recipe_path(&repo, &recipe_id)?;
+
+    match repo.remove_note(&git_path, index, None).await {
+        Ok(Some(notes)) => Ok(Json(notes_response(recipe_id, notes))),
+        Ok(None) => Err(ApiError::NotFound(Resource::Note)),
+        Err(e) => Err(notes_error(e)),
+    }
+}
+
+fn notes_recipe_path(repo: &RecipeRepository, recipe_id: &str) -> Result<String, ApiError> {
+    repo.get_recipe_git_path(recipe_id)
+        .ok_or(ApiError::NotFound(Resource::Recipe))
+}
+
+fn notes_response(recipe_id: String, notes: RecipeNotes) -> RecipeNotesResponse {
+    RecipeNotesResponse {
+        recipe_id,
+        rating: notes.rating,
+        notes: notes
+            .notes
+            .into_iter()
+            .enumerate()
+            .map(|(index, note)| CookingNoteResponse {
+                index,
+                date: note.date.to_string(),
+                text: note.text,
+                author: note.author,
+            })
+            .collect(),
+    }
+}
+
+fn notes_error(e: anyhow::Error) -> ApiError {
+    ApiError::from(e.context("Failed to update recipe notes"))
+}
+
+/// Estimated calories and macros per serving (experimental `nutrition` feature)
+pub async fn get_nutrition(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(recipe_id): Path<String>,
+) -> Result<Json<NutritionResponse>, ApiError> {
+    let git_path = repo
+        .get_recipe_git_path(&recipe_id)
+        .ok_or(ApiError::NotFound(Resource::Recipe))?;
+
+    match repo.nutrition(&git_path) {
+        Ok(estimate) => Ok(Json(NutritionResponse {
+            recipe_id,
+            servings: estimate.servings,
+            per_serving: estimate.per_serving,
+            total: estimate.total,
+            unmatched: estimate.unmatched,
+        })),
+        Err(e) => Err(ApiError::from(e.context("Failed to estimate nutrition"))),
+    }
+}
+
+/// List every distinct ingredient with the recipes using it, synonyms merged under their
+/// canonical name
+pub async fn list_ingredients(
+    State(repo): State<Arc<RecipeRepository>>,
+) -> Json<IngredientListResponse> {
+    let ingredients = repo
+        .ingredient_catalog()
+        .into_iter()
+        .map(|usage| IngredientUsageResponse {
+            name: usage.name,
+            synonyms: usage.synonyms,
+            recipe_count: usage.recipes.len(),
+            recipes: usage
+                .recipes
+                .into_iter()
+                .map(|recipe| recipe_summary(&repo, recipe))
+                .collect(),
+        })
+        .collect();
+
+    Json(IngredientListResponse { ingredients })
+}
+
+/// List the pantry
+pub async fn get_pantry(State(repo): State<Arc<RecipeRepository>>) -> Json<PantryResponse> {
+    let items = repo
+        .pantry_items()
+        .into_iter()
+        .map(|(name, item)| PantryItemResponse {
+            name,
+            quantity: item.quantity,
+            unit: item.unit,
+        })
+        .collect();
+
+    Json(PantryResponse { items })
+}
+
+/// Add an ingredient to the pantry or change its quantity
+pub async fn set_pantry_item(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(name): Path<String>,
+    Json(payload): Json<PantryItemRequest>,
+) -> Result<Json<PantryItemResponse>, ApiError> {
+    let name = pantry::item_key(&name);
+    if name.is_empty() {
+        return Err(ApiError::Validation(
+            "Ingredient name must not be empty".to_string(),
+        ));
+    }
+    if payload
+        .quantity
+        .is_some_and(|quantity| !quantity.is_finite() || quantity < 0.0)
+    {
+        return Err(ApiError::Validation(
+            "Quantity must be a non-negative number".to_string(),
+        ));
+    }
+
+    let item = PantryItem {
+        quantity: payload.quantity,
+        unit: payload
+            .unit
+            .map(|unit| unit.trim().to_string())
+            .filter(|unit| !unit.is_empty()),
+    };
+    match repo
+        .set_pantry_item(&name, item.clone(), payload.author.as_deref())
+        .await
+    {
+        Ok(()) => Ok(Json(PantryItemResponse {
+            name,
+            quantity: item.quantity,
+            unit: item.unit,
+        })),
+        Err(e) => Err(ApiError::from(e.context("Failed to update pantry"))),
+    }
+}
+
+/// Remove an ingredient from the pantry
+pub async fn delete_pantry_item(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    match repo.remove_pantry_item(&name, None).await {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err(ApiError::NotFound(Resource::PantryItem)),
+        Err(e) => Err(ApiError::from(e.context("Failed to update pantry"))),
+    }
+}
+
+/// Recipes ranked by how many of their ingredients are in the pantry
+pub async fn list_cookable_recipes(
+    State(repo): State<Arc<RecipeRepository>>,
+    Query(params): Query<CookableQuery>,
+) -> Json<CookableListResponse> {
+    let limit = std::cmp::min(params.limit.unwrap_or(20), 100);
+    let offset = params.offset.unwrap_or(0);
+
+    let mut cookable = repo.cookable_recipes();
+    if let Some(max_missing) = params.max_missing {
+        cookable.retain(|recipe| recipe.missing.len() <= max_missing as usize);
+    }
+    let total = cookable.len() as u32;
+
+    let recipes = cookable
+        .into_iter()
+        .skip(offset as usize)
+        .take(limit as usize)
+        .map(|cookable| CookableRecipeResponse {
+            recipe_id: generate_recipe_id(&cookable.recipe.git_path),
+            coverage: cookable.coverage(),
+            recipe_name: cookable.recipe.name,
+            path: cookable.recipe.category,
+            on_hand: cookable.on_hand,
+            missing: cookable.missing,
+        })
+        .collect();
+
+    Json(CookableListResponse {
+        recipes,
+        pagination: PaginationInfo {
+            limit,
+            offset,
+            total,
+        },
+    })
+}
+
+/// Make a shopping list of everything the given recipes need
+pub async fn create_shopping_list(
+    State(repo): State<Arc<RecipeRepository>>,
+    Json(payload): Json<ShoppingListRequest>,
+) -> Result<(StatusCode, Json<ShoppingListResponse>), ApiError> {
+    if payload.recipe_ids.is_empty() {
+        return Err(ApiError::Validation(
+            "recipeIds must list at least one recipe".to_string(),
+        ));
+    }
+    let mut git_paths: Vec<String> = Vec::new();
+    for recipe_id in &payload.recipe_ids {
+        let git_path = repo
+            .get_recipe_git_path(recipe_id)
+            .ok_or(ApiError::NotFound(Resource::Recipe))?;
+        if !git_paths.contains(&git_path) {
+            git_paths.push(git_path);
+        }
+    }
+
+    let name = payload.name.as_deref().unwrap_or_default();
+    match repo
+        .create_shopping_list(name, &git_paths, payload.author.as_deref())
+        .await
+    {
+        Ok(list) => Ok((
+            StatusCode::CREATED,
+            Json(shopping_list_response(list, None)),
+        )),
+        Err(e) => Err(ApiError::from(e.context("Failed to create shopping list"))),
+    }
+}
+
+/// All shopping lists, newest first
+pub async fn list_shopping_lists(
+    State(repo): State<Arc<RecipeRepository>>,
+) -> Json<ShoppingListsResponse> {
+    let shopping_lists = repo
+        .shopping_lists()
+        .into_iter()
+        .map(|list| ShoppingListSummary {
+            item_count: list.items.len(),
+            purchased_count: list.items.iter().filter(|item| item.purchased).count(),
+            id: list.id,
+            name: list.name,
+            created_at: list.created_at.to_rfc3339(),
+        })
+        .collect();
+
+    Json(ShoppingListsResponse { shopping_lists })
+}
+
+/// A shopping list, optionally only its purchased or outstanding items
+pub async fn get_shopping_list(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(list_id): Path<String>,
+    Query(params): Query<ShoppingListQuery>,
+) -> Result<Json<ShoppingListResponse>, ApiError> {
+    let list = repo
+        .shopping_list(&list_id)
+        .ok_or(ApiError::NotFound(Resource::ShoppingList))?;
+    Ok(Json(shopping_list_response(list, params.purchased)))
+}
+
+/// A shopping list as text, Markdown or CSV, grouped and sorted by aisle
+pub async fn export_shopping_list(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(list_id): Path<String>,
+    Query(params): Query<ShoppingExportQuery>,
+) -> Result<Response, ApiError> {
+    let format = match params.format.as_deref() {
+        None => ExportFormat::Text,
+        Some(value) => ExportFormat::parse(value).ok_or_else(|| {
+            ApiError::Validation(format!(
+                "Unknown export format: {} (expected text, markdown or csv)",
+                value
+            ))
+        })?,
+    };
+    let list = repo
+        .shopping_list(&list_id)
+        .ok_or(ApiError::NotFound(Resource::ShoppingList))?;
+    let aisles = repo
+        .aisles()
+        .map_err(|e| ApiError::InvalidAisles(format!("{:#}", e)))?;
+
+    let items: Vec<ShoppingItem> = list
+        .items
+        .iter()
+        .filter(|item| {
+            params
+                .purchased
+                .is_none_or(|purchased| item.purchased == purchased)
+        })
+        .cloned()
+        .collect();
+    let text = shopping::export(
+        &list,
+        &items,
+        &aisles,
+        &repo.synonyms(),
+        &repo.config().quantity_format,
+        format,
+    );
+    Ok(([(header::CONTENT_TYPE, format.content_type())], text).into_response())
+}
+
+/// Check off a shopping list item or correct its amount
+pub async fn update_shopping_item(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path((list_id, item_id)): Path<(String, String)>,
+    Json(payload): Json<ShoppingItemRequest>,
+) -> Result<Json<ShoppingItemResponse>, ApiError> {
+    if repo.shopping_list(&list_id).is_none() {
+        return Err(ApiError::NotFound(Resource::ShoppingList));
+    }
+    let item_id: u32 = item_id
+        .parse()
+        .map_err(|_| ApiError::NotFound(Resource::ShoppingItem))?;
+    if payload
+        .quantity
+        .is_some_and(|quantity| !quantity.is_finite() || quantity < 0.0)
+    {
+        return Err(ApiError::Validation(
+            "Quantity must be a non-negative number".to_string(),
+        ));
+    }
+
+    let update = ItemUpdate {
+        purchased: payload.purchased,
+        quantity: payload.quantity,
+        unit: payload.unit.map(|unit| unit.trim().to_string()),
+    };
+    match repo
+        .update_shopping_item(&list_id, item_id, update, payload.author.as_deref())
+        .await
+    {
+        Ok(Some(item)) => Ok(Json(shopping_item_response(item))),
+        Ok(None) => Err(ApiError::NotFound(Resource::ShoppingItem)),
+        Err(e) => Err(ApiError::from(e.context("Failed to update shopping list"))),
+    }
+}
+
+/// Delete a shopping list
+pub async fn delete_shopping_list(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(list_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    match repo.delete_shopping_list(&list_id, None).await {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err(ApiError::NotFound(Resource::ShoppingList)),
+        Err(e) => Err(ApiError::from(e.context("Failed to delete shopping list"))),
+    }
+}
+
+/// Response for a shopping list, keeping only items whose `purchased` state matches
+/// `purchased` if given (the counts cover the whole list)
+fn shopping_list_response(list: ShoppingList, purchased: Option<bool>) -> ShoppingListResponse {
+    ShoppingListResponse {
+        item_count: list.items.len(),
+        purchased_count: list.items.iter().filter(|item| item.purchased).count(),
+        items: list
+            .items
+            .into_iter()
+            .filter(|item| purchased.is_none_or(|purchased| item.purchased == purchased))
+            .map(shopping_item_response)
+            .collect(),
+        id: list.id,
+        name: list.name,
+        recipes: list.recipes,
+        created_at: list.created_at.to_rfc3339(),
+        created_by: list.created_by,
+    }
+}
+
+fn shopping_item_response(item: ShoppingItem) -> ShoppingItemResponse {
+    ShoppingItemResponse {
+        id: item.id,
+        name: item.name,
+        quantity: item.quantity,
+        unit: item.unit,
+        purchased: item.purchased,
+        recipes: item.recipes,
+    }
+}
+
+/// List all menus
+pub async fn list_menus(State(repo): State<Arc<RecipeRepository>>) -> Json<MenuListResponse> {
+    let menus = repo
+        .list_menus()
+        .into_iter()
+        .map(|(menu_id, menu)| MenuSummary {
+            menu_id,
+            menu_name: repo.menu_name(&menu),
+            path: menu.git_path,
+        })
+        .collect();
+
+    Json(MenuListResponse { menus })
+}
+
+/// Get a menu with its recipe references resolved to recipe IDs
+pub async fn get_menu(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(menu_id): Path<String>,
+) -> Result<Json<MenuResponse>, ApiError> {
+    let menu = repo
+        .get_menu(&menu_id)
+        .ok_or(ApiError::NotFound(Resource::Menu))?;
+
+    let mut recipe_ids: Vec<String> = Vec::new();
+    let sections = menu
+        .sections
+        .iter()
+        .map(|section| MenuSectionResponse {
+            name: section.name.clone(),
+            items: section
+                .items
+                .iter()
+                .map(|item| {
+                    let recipe = repo.resolve_menu_reference(&menu.git_path, &item.reference);
+                    let recipe_id = recipe
+                        .as_ref()
+                        .map(|recipe| generate_recipe_id(&recipe.git_path));
+                    if let Some(id) = &recipe_id {
+                        if !recipe_ids.contains(id) {
+                            recipe_ids.push(id.clone());
+                        }
+                    }
+
+                    MenuItemResponse {
+                        meal: item.meal.clone(),
+                        reference: item.reference.clone(),
+                        quantity: item.quantity,
+                        unit: item.unit.clone(),
+                        recipe_id,
+                        recipe_name: recipe.map(|recipe| recipe.name),
+                    }
+                })
+                .collect(),
+        })
+        .collect();
+
+    Ok(Json(MenuResponse {
+        menu_id,
+        menu_name: repo.menu_name(&menu),
+        path: menu.git_path,
+        servings: menu.servings,
+        sections,
+        recipe_ids,
+    }))
+}
+
+/// List recipe templates
+pub async fn list_templates(
+    State(repo): State<Arc<RecipeRepository>>,
+) -> Result<Json<TemplateListResponse>, ApiError> {
+    let templates = repo
+        .list_templates()
+        .context("Failed to list templates")?
+        .into_iter()
+        .map(|template| TemplateResponse {
+            title: template.title(),
+            name: template.name,
+            builtin: template.builtin,
+            content: template.content,
+        })
+        .collect();
+
+    Ok(Json(TemplateListResponse { templates }))
+}
+
+/// Create a recipe from a template, with the requested title filled in
+pub async fn create_from_template(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(name): Path<String>,
+    Json(payload): Json<CreateFromTemplateRequest>,
+) -> Result<(StatusCode, Json<RecipeResponse>), ApiError> {
+    let title = payload.title.trim();
+    if title.is_empty() {
+        return Err(ApiError::Validation("Title cannot be empty".to_string()));
+    }
+
+    let path = payload
+        .path
+        .as_deref()
+        .and_then(|p| if p.trim().is_empty() { None } else { Some(p) });
+
+    let recipe = repo
+        .create_from_template(
+            &name,
+            title,
+            path,
+            payload.author.as_deref(),
+            payload.comment.as_deref(),
+        )
+        .await
+        .context("Failed to create recipe from template")?
+        .ok_or(ApiError::NotFound(Resource::Template))?;
+
+    Ok((StatusCode::CREATED, Json(recipe_response(&repo, recipe))))
+}
+
+/// Who last changed each line of a recipe
+pub async fn blame_recipe(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(recipe_id): Path<String>,
+) -> Result<Json<BlameResponse>, ApiError> {
+    let git_path = repo
+        .get_recipe_git_path(&recipe_id)
+        .ok_or(ApiError::NotFound(Resource::Recipe))?;
+
+    if !repo.supports_blame() {
+        return Err(ApiError::RequiresGit(GitFeature::Blame));
+    }
+
+    match repo.blame(&git_path).await {
+        Ok(lines) => Ok(Json(BlameResponse {
+            recipe_id,
+            lines: lines
+                .into_iter()
+                .map(|line| BlameLineResponse {
+                    line: line.line,
+                    content: line.content,
+                    commit_id: line.commit_id,
+                    author: line.author,
+                    committed_at: line.committed_at.map(|at| at.to_rfc3339()),
+                    summary: line.summary,
+                })
+                .collect(),
+        })),
+        Err(e) => Err(ApiError::from(e.context("Failed to blame recipe"))),
+    }
+}
+
+/// The whole collection as an archive another recipe app can import
+pub async fn export_recipes(
+    State(repo): State<Arc<RecipeRepository>>,
+    Query(params): Query<RecipeExportQuery>,
+) -> Result<Response, ApiError> {
+    match params
+        .format
+        .as_deref()
+        .map(|format| format.trim().to_lowercase())
+    {
+        Some(format) if format == "paprika" => {}
+        Some(format) => {
+            return Err(ApiError::Validation(format!(
+                "Unknown export format: {} (expected paprika)",
+                format
+            )))
+        }
+        None => {
+            return Err(ApiError::Validation(
+                "format is required (paprika)".to_string(),
+            ))
+        }
+    }
+
+    match repo.export_paprika() {
+        Ok(archive) => Ok((
+            [
+                (header::CONTENT_TYPE, "application/zip"),
+                (
+                    header::CONTENT_DISPOSITION,
+                    "attachment; filename=\"recipes.paprikarecipes\"",
+                ),
+            ],
+            archive,
+        )
+            .into_response()),
+        Err(e) => Err(ApiError::from(e.context("Failed to export recipes"))),
+    }
+}
+
+/// Import a Paprika `.paprikarecipes` archive, sent as the body or as an upload
+pub async fn import_paprika(
+    State(repo): State<Arc<RecipeRepository>>,
+    Query(params): Query<ImportQuery>,
+    body: Bytes,
+) -> Result<Json<ImportResponse>, ApiError> {
+    let recipes = read_import(&repo, &params, body, |archive| {
+        Ok(paprika::read_archive(archive)?
+            .into_iter()
+            .map(|(name, recipe)| (name, recipe.map(paprika::to_imported)))
+            .collect())
+    })
+    .await?;
+    import_recipes(&repo, &params, recipes, "Paprika").await
+}
+
+/// Import a Mealie site backup or zip of recipe JSON files, sent as the body or as an
+/// upload
+pub async fn import_mealie(
+    State(repo): State<Arc<RecipeRepository>>,
+    Query(params): Query<ImportQuery>,
+    body: Bytes,
+) -> Result<Json<ImportResponse>, ApiError> {
+    let recipes = read_import(&repo, &params, body, |archive| {
+        Ok(mealie::read_archive(archive)?
+            .into_iter()
+            .map(|(name, recipe)| (name, recipe.map(mealie::to_imported)))
+            .collect())
+    })
+    .await?;
+    import_recipes(&repo, &params, recipes, "Mealie").await
+}
+
+/// Import a Tandoor export (a zip of recipe zips), sent as the body or as an upload
+pub async fn import_tandoor(
+    State(repo): State<Arc<RecipeRepository>>,
+    Query(params): Query<ImportQuery>,
+    body: Bytes,
+) -> Result<Json<ImportResponse>, ApiError> {
+    let recipes = read_import(&repo, &params, body, |archive| {
+        Ok(tandoor::read_archive(archive)?
+            .into_iter()
+            .map(|(name, recipe)| (name, recipe.map(tandoor::to_imported)))
+            .collect())
+    })
+    .await?;
+    import_recipes(&repo, &params, recipes, "Tandoor").await
+}
+
+/// Import a zipped Nextcloud Cookbook folder, sent as the body or as an upload
+pub async fn import_nextcloud(
+    State(repo): State<Arc<RecipeRepository>>,
+    Query(params): Query<ImportQuery>,
+    body: Bytes,
+) -> Result<Json<ImportResponse>, ApiError> {
+    let recipes = read_import(&repo, &params, body, |archive| {
+        Ok(nextcloud::read_archive(archive)?
+            .into_iter()
+            .map(|(name, recipe)| (name, recipe.map(nextcloud::to_imported)))
+            .collect())
+    })
+    .await?;
+    import_recipes(&repo, &params, recipes, "Nextcloud Cookbook").await
+}
+
+/// Create the recipes read from an archive, then discard the upload it came from
+async fn import_recipes(
+    repo: &RecipeRepository,
+    params: &ImportQuery,
+    recipes: Vec<(String, anyhow::Result<ImportedRecipe>)>,
+    app: &str,
+) -> Result<Json<ImportResponse>, ApiError> {
+    let outcomes = repo
+        .import_recipes(recipes, app, params.author.as_deref())
+        .await
+        .map_err(|e| ApiError::from(e.context(format!("Failed to import from {}", app))))?;
+    if let Some(upload_id) = &params.upload_id {
+        if let Err(e) = repo.uploads().remove(upload_id) {
+            tracing::warn!("Failed to discard imported upload {}: {:#}", upload_id, e);
+        }
+    }
+    Ok(Json(import_response(outcomes)))
+}
+
+/// Recipes read from an archive to import, on the blocking pool
+///
+/// Uploaded archives can run to hundreds of megabytes, so loading and unzipping them
+/// stays off the runtime threads. `read` failing means the archive is unusable.
+async fn read_import<F>(
+    repo: &Arc<RecipeRepository>,
+    params: &ImportQuery,
+    body: Bytes,
+    read: F,
+) -> Result<Vec<(String, anyhow::Result<ImportedRecipe>)>, ApiError>
+where
+    F: FnOnce(&[u8]) -> anyhow::Result<Vec<(String, anyhow::Result<ImportedRecipe>)>>
+        + Send
+        + 'static,
+{
+    let (repo, upload_id) = (Arc::clone(repo), params.upload_id.clone());
+    tokio::task::spawn_blocking(move || {
+        let archive = import_archive(&repo, upload_id.as_deref(), body)?;
+        read(&archive).map_err(|e| ApiError::Validation(format!("{:#}", e)))
+    })
+    .await
+    .map_err(|e| ApiError::from(anyhow::Error::from(e)))?
+}
+
+/// Bytes of an archive to import: a completed upload if `upload_id` is given, the
+/// request body otherwise
+fn import_archive(
+    repo: &RecipeRepository,
+    upload_id: Option<&str>,
+    body: Bytes,
+) -> Result<Vec<u8>, ApiError> {
+    let Some(upload_id) = upload_id else {
+        if body.is_empty() {
+            return Err(ApiError::Validation(
+                "Send the archive as the request body, or name a completed upload with upload_id"
+                    .to_string(),
+            ));
+        }
+        return Ok(body.to_vec());
+    };
+
+    let session = repo
+        .uploads()
+        .get(upload_id)
+        .map_err(|e| ApiError::from(e.context("Failed to read upload")))?
+        .ok_or(ApiError::NotFound(Resource::Upload))?;
+    if !session.is_complete() {
+        return Err(ApiError::Validation(format!(
+            "Upload is not complete ({} of {} bytes received)",
+            session.offset, session.size
+        )));
+    }
+    let path = repo
+        .uploads()
+        .completed_path(upload_id)
+        .map_err(|e| ApiError::from(e.context("Failed to read upload")))?
+        .ok_or(ApiError::NotFound(Resource::Upload))?;
+    std::fs::read(&path)
+        .map_err(|e| ApiError::from(anyhow::Error::from(e).context("Failed to read upload")))
+}
+
+fn import_response(outcomes: Vec<ImportOutcome>) -> ImportResponse {
+    let imported = outcomes
+        .iter()
+        .filter(|outcome| outcome.result.is_ok())
+        .count();
+    let recipes = outcomes
+        .into_iter()
+        .map(|outcome| match outcome.result {
+            Ok((recipe, fidelity)) => ImportedRecipeResponse {
+                name: outcome.name,
+                status: "imported".to_string(),
+                recipe_id: Some(generate_recipe_id(&recipe.git_path)),
+                path: recipe.category,
+                error: None,
+                fidelity: Some(fidelity_response(fidelity)),
+            },
+            Err(error) => ImportedRecipeResponse {
+                name: outcome.name,
+                status: "failed".to_string(),
+                recipe_id: None,
+                path: None,
+                error: Some(error),
+                fidelity: None,
+            },
+        })
+        .collect::<Vec<_>>();
+
+    ImportResponse {
+        imported,
+        failed: recipes.len() - imported,
+        recipes,
+    }
+}
+
+fn fidelity_response(fidelity: Fidelity) -> FidelityResponse {
+    FidelityResponse {
+        score: fidelity.score(),
+        ingredients: fidelity.ingredients,
+        inline: fidelity.inline,
+        listed: fidelity.listed,
+        dropped: fidelity.dropped,
+    }
+}
+
+/// Convert a Markdown recipe (`text/markdown`) to Cooklang for review, without saving
+///
+/// Create the recipe from the returned `content` and `path` once it looks right.
+pub async fn import_markdown(markdown: String) -> Result<Json<ConversionResponse>, ApiError> {
+    if markdown.trim().is_empty() {
+        return Err(ApiError::Validation(
+            "Send the Markdown recipe as the request body".to_string(),
+        ));
+    }
+    let recipe =
+        markdown::parse(&markdown).map_err(|e| ApiError::Validation(format!("{:#}", e)))?;
+    let conversion = to_cooklang(&recipe).map_err(|e| ApiError::Validation(format!("{:#}", e)))?;
+    Ok(Json(ConversionResponse {
+        file_name: generate_filename(recipe.title.trim()),
+        content: conversion.content,
+        path: conversion.category,
+        fidelity: fidelity_response(conversion.fidelity),
+        timers: None,
+    }))
+}
+
+/// Convert pasted recipe text of any shape to Cooklang for review, without saving
+///
+/// Quantities and units are marked up where they can be told apart and times in the
+/// steps become timers. Pass `title` when the text doesn't start with one.
+pub async fn convert_recipe(
+    Query(params): Query<ConvertQuery>,
+    text: String,
+) -> Result<Json<ConversionResponse>, ApiError> {
+    if text.trim().is_empty() {
+        return Err(ApiError::Validation(
+            "Send the recipe text as the request body".to_string(),
+        ));
+    }
+    let recipe = convert::parse(&text, params.title.as_deref())
+        .map_err(|e| ApiError::Validation(format!("{:#}", e)))?;
+    let conversion = to_cooklang(&recipe).map_err(|e| ApiError::Validation(format!("{:#}", e)))?;
+    let (content, timers) = convert::mark_timers(&conversion.content);
+    Ok(Json(ConversionResponse {
+        file_name: generate_filename(recipe.title.trim()),
+        content,
+        path: conversion.category,
+        fidelity: fidelity_response(conversion.fidelity),
+        timers: Some(timers),
+    }))
+}
+
+/// Find recipes by name (fallback lookup for when IDs change)
+pub async fn find_recipe_by_name(
+    State(repo): State<Arc<RecipeRepository>>,
+    Query(params): Query<SearchQuery>,
+) -> Result<Json<RecipeListResponse>, ApiError> {
+    if params.q.trim().is_empty() {
+        return Err(ApiError::Validation(
+            "Search query cannot be empty".to_string(),
+        ));
+    }
+
+    let limit = std::cmp::min(params.limit.unwrap_or(20), 100);
+    let offset = params.offset.unwrap_or(0);
+
+    let all_results = repo.search_by_name(&params.q);
+    let total = all_results.len() as u32;
+
+    let recipes: Vec<RecipeSummary> = all_results
+        .into_iter()
+        .skip(offset as usize)
+        .take(limit as usize)
+        .map(|recipe| recipe_summary(&repo, recipe))
+        .collect();
+
+    Ok(Json(RecipeListResponse {
+        recipes,
+        pagination: PaginationInfo {
+            limit,
+            offset,
+            total,
+        },
+    }))
+}
+
+/// Find a recipe by exact path (fallback lookup for when IDs change)
+#[derive(serde::Deserialize)]
+pub struct FindByPathQuery {
+    pub path: Option<String>,
+}
+
+pub async fn find_recipe_by_path(
+    State(repo): State<Arc<RecipeRepository>>,
+    Query(params): Query<FindByPathQuery>,
+) -> Result<Response, ApiError> {
+    let path = params.path.as_deref().unwrap_or("").trim_matches('/');
+
+    // Find all recipes at the specified path (root if empty)
+    let all_recipes = if path.is_empty() {
+        repo.list_all()
+            .into_iter()
+            .filter(|recipe| recipe.category.is_none())
+            .collect()
+    } else {
+        match repo.resolve_category(path) {
+            Some(category) => repo.list_by_category(&category),
+            None => {
+                // Paths under a renamed category redirect to the new location
+                if let Some(new_path) = repo.resolve_category_alias(path) {
+                    return Ok(Redirect::temporary(&format!(
+                        "/api/v1/recipes/find-by-path?path={}",
+                        urlencoding::encode(&new_path)
+                    ))
+                    .into_response());
+                }
+                Vec::new()
+            }
+        }
+    };
+
+    let matching: Vec<RecipeSummary> = all_recipes
+        .into_iter()
+        .map(|recipe| recipe_summary(&repo, recipe))
+        .collect();
+
+    Ok(Json(matching).into_response())
+}
+
+/// List the named collections served next to the default one
+pub async fn list_collections(names: Arc<Vec<String>>) -> Json<CollectionListResponse> {
+    Json(CollectionListResponse {
+        collections: names.to_vec(),
+    })
+}
+
+/// List all categories
+pub async fn list_categories(
+    State(repo): State<Arc<RecipeRepository>>,
+) -> Json<CategoryListResponse> {
+    let categories = repo
+        .category_counts()
+        .into_iter()
+        .map(|category| CategoryItem {
+            path: category.path,
+            count: category.count,
+            direct_count: category.direct_count,
+        })
+        .collect();
+    Json(CategoryListResponse { categories })
+}
+
+/// Get recipes in a category
+pub async fn get_category_recipes(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(category_name): Path<String>,
+) -> Result<Response, ApiError> {
+    // Verify category exists (tolerating trailing slashes and, if configured, case)
+    let Some(category_name) = repo.resolve_category(&category_name) else {
+        // Old paths of renamed categories redirect to the new path
+        if let Some(new_path) = repo.resolve_category_alias(&category_name) {
+            return Ok(Redirect::temporary(&format!(
+                "/api/v1/categories/{}",
+                urlencoding::encode(&new_path)
+            ))
+            .into_response());
+        }
+        return Err(ApiError::NotFound(Resource::Category));
+    };
+
+    let recipes = repo.list_by_category(&category_name);
+    let summaries: Vec<RecipeSummary> = recipes
+        .into_iter()
+        .map(|recipe| recipe_summary(&repo, recipe))
+        .collect();
+
+    let count = summaries.len();
+    let breadcrumb = breadcrumb_for(&repo, Some(&category_name));
+
+    Ok(Json(CategoryRecipesResponse {
+        path: category_name,
+        recipes: summaries,
+        count,
+        breadcrumb,
+    })
+    .into_response())
+}
+
+/// Rename a category, moving its recipes and subcategories in one commit
+pub async fn rename_category(
+    State(repo): State<Arc<RecipeRepository>>,
+    Json(payload): Json<CategoryRenameRequest>,
+) -> Result<Json<CategoryRenameResponse>, ApiError> {
+    let from = payload.from.trim_matches('/');
+    let to = payload.to.trim_matches('/');
+    if from.is_empty() || to.is_empty() {
+        return Err(ApiError::Validation(
+            "Both 'from' and 'to' category paths are required".to_string(),
+        ));
+    }
+    let (from_key, to_key) = if repo.config().case_insensitive_paths {
+        (from.to_lowercase(), to.to_lowercase())
+    } else {
+        (from.to_string(), to.to_string())
+    };
+    if to_key == from_key || to_key.starts_with(&format!("{}/", from_key)) {
+        return Err(ApiError::Validation(format!(
+            "Cannot move category '{}' into itself",
+            from
+        )));
+    }
+
+    let rename = repo
+        .rename_category(
+            from,
+            to,
+            payload.author.as_deref(),
+            payload.comment.as_deref(),
+        )
+        .await
+        .context("Failed to rename category")?
+        .ok_or(ApiError::NotFound(Resource::Category))?;
+
+    Ok(Json(CategoryRenameResponse {
+        from: rename.from,
+        to: rename.to,
+        renamed: rename
+            .renamed
+            .iter()
+            .map(|(from, to)| (generate_recipe_id(from), generate_recipe_id(to)))
+            .collect(),
+    }))
+}
+
+/// List recipes in the trash, most recently deleted first
+pub async fn list_trash(
+    State(repo): State<Arc<RecipeRepository>>,
+) -> Result<Json<TrashListResponse>, ApiError> {
+    let trashed = repo.list_trash().context("Failed to list trash")?;
+
+    let entries: Vec<TrashEntryResponse> = trashed
+        .into_iter()
+        .map(|entry| TrashEntryResponse {
+            trash_id: entry.trash_id,
+            recipe_name: entry.name,
+            path: entry.category,
+            file_name: entry
+                .original_path
+                .rsplit('/')
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            deleted_at: entry.deleted_at.to_rfc3339(),
+        })
+        .collect();
+
+    let count = entries.len();
+    Ok(Json(TrashListResponse { entries, count }))
+}
+
+/// Restore a trashed recipe to its original path
+pub async fn restore_from_trash(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(trash_id): Path<String>,
+) -> Result<Json<RecipeResponse>, ApiError> {
+    let entry = match repo.get_trashed(&trash_id) {
+        Ok(Some(entry)) => entry,
+        Ok(None) => return Err(ApiError::NotFound(Resource::TrashEntry)),
+        Err(e) => return Err(ApiError::from(e.context("Failed to read trash"))),
+    };
+
+    let original_id = generate_recipe_id(&entry.original_path);
+    if repo.get_recipe_git_path(&original_id).is_some() {
+        return Err(ApiError::Conflict(
+            "A recipe already exists at the original path".to_string(),
+        ));
+    }
+
+    match repo.restore_from_trash(&trash_id).await {
+        Ok(recipe) => Ok(Json(recipe_response(&repo, recipe))),
+        Err(e) => Err(ApiError::from(e.context("Failed to restore recipe"))),
+    }
+}
+
+/// List drafts, most recently updated first
+pub async fn list_drafts(
+    State(repo): State<Arc<RecipeRepository>>,
+) -> Result<Json<DraftListResponse>, ApiError> {
+    let drafts = repo.list_drafts().await.context("Failed to list drafts")?;
+
+    let drafts: Vec<DraftResponse> = drafts
+        .into_iter()
+        .map(|draft| draft_response(draft, false))
+        .collect();
+    let count = drafts.len();
+    Ok(Json(DraftListResponse { drafts, count }))
+}
+
+/// Get a draft with its content
+pub async fn get_draft(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(draft_id): Path<String>,
+) -> Result<Json<DraftResponse>, ApiError> {
+    match repo.get_draft(&draft_id).await {
+        Ok(Some(draft)) => Ok(Json(draft_response(draft, true))),
+        Ok(None) => Err(ApiError::NotFound(Resource::Draft)),
+        Err(e) => Err(ApiError::from(e.context("Failed to read draft"))),
+    }
+}
+
+/// Publish a draft by merging it into the main branch
+pub async fn publish_draft(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(draft_id): Path<String>,
+) -> Result<Json<RecipeResponse>, ApiError> {
+    match repo.get_draft(&draft_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(ApiError::NotFound(Resource::Draft)),
+        Err(e) => return Err(ApiError::from(e.context("Failed to read draft"))),
+    }
+
+    match repo.publish_draft(&draft_id, None).await {
+        Ok(recipe) => Ok(Json(recipe_response(&repo, recipe))),
+        Err(e) => Err(ApiError::from(e.context("Failed to publish draft"))),
+    }
+}
+
+/// Discard a draft without publishing it
+pub async fn delete_draft(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(draft_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    match repo.discard_draft(&draft_id).await {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err(ApiError::NotFound(Resource::Draft)),
+        Err(e) => Err(ApiError::from(e.context("Failed to delete draft"))),
+    }
+}
+
+/// 400 unless the storage backend can keep drafts
+fn require_drafts(repo: &RecipeRepository) -> Result<(), ApiError> {
+    if repo.supports_drafts() {
+        Ok(())
+    } else {
+        Err(ApiError::RequiresGit(GitFeature::Drafts))
+    }
+}
+
+fn draft_response(draft: Draft, with_content: bool) -> DraftResponse {
+    DraftResponse {
+        draft_id: draft.draft_id,
+        recipe_name: draft.name,
+        path: draft.category,
+        file_name: draft.file_name,
+        content: with_content.then_some(draft.content),
+        updated_at: draft.updated_at.to_rfc3339(),
+    }
+}
+
+/// Header carrying the byte offset a chunk starts at (tus-style)
+const UPLOAD_OFFSET_HEADER: &str = "upload-offset";
+
+/// Start a resumable upload
+pub async fn create_upload(
+    State(repo): State<Arc<RecipeRepository>>,
+    Json(payload): Json<CreateUploadRequest>,
+) -> Result<(StatusCode, Json<UploadResponse>), ApiError> {
+    if payload.file_name.trim().is_empty() {
+        return Err(ApiError::Validation(
+            "fileName must not be empty".to_string(),
+        ));
+    }
+
+    if payload.size == 0 {
+        return Err(ApiError::Validation(
+            "size must be greater than 0".to_string(),
+        ));
+    }
+
+    let max_bytes = repo.config().upload_max_mb.saturating_mul(1024 * 1024);
+    if payload.size > max_bytes {
+        return Err(ApiError::UploadTooLarge(format!(
+            "Upload of {} bytes exceeds the {} MB limit",
+            payload.size,
+            repo.config().upload_max_mb
+        )));
+    }
+
+    match repo.start_upload(payload.file_name.trim(), payload.size) {
+        Ok(session) => Ok((StatusCode::CREATED, Json(upload_response(session)))),
+        Err(e) => Err(ApiError::from(e.context("Failed to start upload"))),
+    }
+}
+
+/// Get the state of a resumable upload (clients resume from `offset`)
+pub async fn get_upload(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(upload_id): Path<String>,
+) -> Result<Json<UploadResponse>, ApiError> {
+    match repo.uploads().get(&upload_id) {
+        Ok(Some(session)) => Ok(Json(upload_response(session))),
+        Ok(None) => Err(ApiError::NotFound(Resource::Upload)),
+        Err(e) => Err(ApiError::from(e.context("Failed to read upload"))),
+    }
+}
+
+/// Append a chunk to a resumable upload
+///
+/// The `Upload-Offset` header must match the bytes received so far.
+pub async fn upload_chunk(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(upload_id): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<UploadResponse>, ApiError> {
+    let offset = headers
+        .get(UPLOAD_OFFSET_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .ok_or_else(|| {
+            ApiError::Validation("Upload-Offset header with a byte offset is required".to_string())
+        })?;
+
+    let session = repo.uploads().append(&upload_id, offset, &body)?;
+    Ok(Json(upload_response(session)))
+}
+
+/// Cancel a resumable upload and discard the received bytes
+pub async fn delete_upload(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(upload_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    match repo.uploads().remove(&upload_id) {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err(ApiError::NotFound(Resource::Upload)),
+        Err(e) => Err(ApiError::from(e.context("Failed to delete upload"))),
+    }
+}
+
+fn upload_response(session: UploadSession) -> UploadResponse {
+    UploadResponse {
+        complete: session.is_complete(),
+        upload_id: session.upload_id,
+        file_name: session.file_name,
+        size: session.size,
+        offset: session.offset,
+        created_at: session.created_at.to_rfc3339(),
+    }
+}
+
+/// Non-empty trimmed items of a comma-separated query parameter
+fn comma_separated(value: Option<&str>) -> Vec<String> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Response for a recipe read with its content
+fn recipe_response(repo: &RecipeRepository, recipe: Recipe) -> RecipeResponse {
+    let content = recipe.content.unwrap_or_default();
+    RecipeResponse {
+        recipe_id: generate_recipe_id(&recipe.git_path),
+        recipe_name: recipe.name,
+        read_only: repo.is_read_only(&recipe.git_path),
+        breadcrumb: breadcrumb_for(repo, recipe.category.as_deref()),
+        last_modified: last_modified_info(recipe.last_modified),
+        custom_fields: extract_custom_fields(&content),
+        times: recipe_times(&content),
+        path: recipe.category,
+        file_name: recipe.file_name,
+        content,
+        description: recipe.description,
+    }
+}
+
+/// List entry for an indexed recipe, with its content if it was read
+fn recipe_summary(repo: &RecipeRepository, recipe: Recipe) -> RecipeSummary {
+    let (total_time_minutes, active_time_minutes) = repo.recipe_minutes(&recipe.git_path);
+    RecipeSummary {
+        recipe_id: generate_recipe_id(&recipe.git_path),
+        read_only: repo.is_read_only(&recipe.git_path),
+        recipe_name: recipe.name,
+        path: recipe.category,
+        times: RecipeTimesInfo {
+            total_time_minutes,
+            active_time_minutes,
+        },
+        last_modified: last_modified_info(recipe.last_modified),
+        content: recipe.content,
+        description: None,
+        tags: None,
+        servings: None,
+        image_url: None,
+    }
+}
+
+/// Listing entry for a recipe, with the front matter details too for `detail=full`
+fn detailed_summary(
+    repo: &RecipeRepository,
+    recipe: Recipe,
+    detail: SummaryDetail,
+) -> RecipeSummary {
+    if detail == SummaryDetail::Summary {
+        return recipe_summary(repo, recipe);
+    }
+    let metadata = repo.recipe_metadata(&recipe.git_path);
+    RecipeSummary {
+        description: recipe.description.clone().or(metadata.description),
+        tags: Some(metadata.tags),
+        servings: metadata.servings,
+        image_url: metadata.image,
+        ..recipe_summary(repo, recipe)
+    }
+}
+
+/// Response fields for a recipe's total and hands-on time
+fn recipe_times(content: &str) -> RecipeTimesInfo {
+    RecipeTimesInfo {
+        total_time_minutes: extract_total_minutes(content),
+        active_time_minutes: extract_active_minutes(content),
+    }
+}
+
+/// Response fields for when an indexed recipe last changed
+fn last_modified_info(last_modified: Option<LastModified>) -> LastModifiedInfo {
+    let Some(last_modified) = last_modified else {
+        return LastModifiedInfo::default();
+    };
+    LastModifiedInfo {
+        revision: last_modified.revision,
+        last_modified_by: last_modified.author,
+        last_modified_at: Some(last_modified.at.to_rfc3339()),
+        last_commit_message: last_modified.message,
+    }
+}
+
+/// Build the response breadcrumb for a category path from the cached category tree
+fn breadcrumb_for(repo: &RecipeRepository, category: Option<&str>) -> Vec<BreadcrumbItem> {
+    repo.category_breadcrumb(category)
+        .into_iter()
+        .map(|crumb| BreadcrumbItem {
+            name: crumb.name,
+            path: crumb.path,
+            recipe_count: crumb.recipe_count,
+        })
+        .collect()
+}
+
+/// Bring the index up to date with files changed outside the API (e.g. after a git pull)
+pub async fn reindex(
+    State(repo): State<Arc<RecipeRepository>>,
+    Query(params): Query<ReindexQuery>,
+) -> Result<Json<ReindexResponse>, ApiError> {
+    match repo.reindex(params.full).await {
+        Ok(report) => Ok(Json(reindex_response(report))),
+        Err(e) => Err(reindex_error(e)),
+    }
+}
+
+/// List recipe files whose name has drifted from their title
+pub async fn list_misaligned_files(
+    State(repo): State<Arc<RecipeRepository>>,
+) -> Json<MisalignedFilesResponse> {
+    let files: Vec<MisalignedFileResponse> = repo
+        .misaligned_files()
+        .into_iter()
+        .map(|file| MisalignedFileResponse {
+            recipe_id: generate_recipe_id(&file.git_path),
+            path: file.category,
+            file_name: file.file_name,
+            recipe_name: file.title,
+            expected_file_name: file.expected_filename,
+        })
+        .collect();
+
+    Json(MisalignedFilesResponse {
+        total: files.len(),
+        files,
+    })
+}
+
+/// Rename every misaligned recipe file to match its title, in one commit
+pub async fn reconcile_filenames(
+    State(repo): State<Arc<RecipeRepository>>,
+    Query(params): Query<ReconcileQuery>,
+) -> Result<Json<ReconcileResponse>, ApiError> {
+    let renames = repo
+        .reconcile_filenames(params.dry_run, params.author.as_deref())
+        .await
+        .context("Failed to rename recipe files")?;
+
+    Ok(Json(ReconcileResponse {
+        dry_run: params.dry_run,
+        renamed: renames
+            .iter()
+            .map(|(from, to)| (generate_recipe_id(from), generate_recipe_id(to)))
+            .collect(),
+    }))
+}
+
+/// Rewrite every recipe's front matter in canonical form, in one commit
+pub async fn normalize_front_matter(
+    State(repo): State<Arc<RecipeRepository>>,
+    Query(params): Query<NormalizeQuery>,
+) -> Result<Json<NormalizeResponse>, ApiError> {
+    let report = repo
+        .normalize_all(params.dry_run, params.author.as_deref())
+        .await
+        .context("Failed to normalize front matter")?;
+
+    Ok(Json(NormalizeResponse {
+        dry_run: params.dry_run,
+        updated: report
+            .updated
+            .iter()
+            .map(|git_path| generate_recipe_id(git_path))
+            .collect(),
+        unchanged: report.unchanged,
+        failed: report
+            .failed
+            .into_iter()
+            .map(|(git_path, error)| NormalizeFailure {
+                recipe_id: generate_recipe_id(&git_path),
+                error,
+            })
+            .collect(),
+    }))
+}
+
+/// Reload the custom units file and reparse every recipe with it
+pub async fn reload_units(
+    State(repo): State<Arc<RecipeRepository>>,
+) -> Result<Json<UnitsReloadResponse>, ApiError> {
+    let custom_units = repo
+        .reload_units()
+        .map_err(|e| ApiError::InvalidUnits(format!("{:#}", e)))?;
+
+    match repo.reindex(true).await {
+        Ok(report) => Ok(Json(UnitsReloadResponse {
+            custom_units,
+            reindex: reindex_response(report),
+        })),
+        Err(e) => Err(reindex_error(e)),
+    }
+}
+
+/// Reload the ingredient synonyms file
+///
+/// Synonyms apply when matching, so nothing needs reindexing.
+pub async fn reload_synonyms(
+    State(repo): State<Arc<RecipeRepository>>,
+) -> Result<Json<SynonymsReloadResponse>, ApiError> {
+    let groups = repo
+        .reload_synonyms()
+        .map_err(|e| ApiError::InvalidSynonyms(format!("{:#}", e)))?;
+    Ok(Json(SynonymsReloadResponse { groups }))
+}
+
+/// Re-read `.env` and the environment, applying the settings that can change while
+/// the server runs (the same as sending it SIGHUP, but for this collection only)
+pub async fn reload_config(
+    State(repo): State<Arc<RecipeRepository>>,
+) -> Json<ConfigReloadResponse> {
+    let fresh = StoreConfig::from_env_file(std::path::Path::new(ENV_FILE));
+    let reload = repo.reload_config(fresh);
+    Json(ConfigReloadResponse {
+        changed: reload.changed.iter().map(|var| var.to_string()).collect(),
+        restart_required: reload.restart_required,
+    })
+}
+
+fn reindex_response(report: ReindexReport) -> ReindexResponse {
+    ReindexResponse {
+        mode: if report.incremental {
+            "incremental"
+        } else {
+            "full"
+        }
+        .to_string(),
+        parsed: report.parsed,
+        removed: report.removed,
+        revision: report.revision,
+        duration_ms: report.duration_ms,
+    }
+}
+
+fn reindex_error(e: anyhow::Error) -> ApiError {
+    ApiError::from(e.context("Failed to reindex recipes"))
+}
+
+/// Run git gc now instead of waiting for the scheduled run
+pub async fn run_git_gc(
+    State(repo): State<Arc<RecipeRepository>>,
+) -> Result<Json<GitGcResponse>, ApiError> {
+    if !repo.supports_maintenance() {
+        return Err(ApiError::RequiresGit(GitFeature::Maintenance));
+    }
+
+    match repo.run_maintenance().await {
+        Ok(report) => Ok(Json(GitGcResponse {
+            loose_objects_before: report.loose_objects_before,
+            loose_objects_after: report.loose_objects_after,
+            packs: report.packs_after,
+            duration_ms: report.duration_ms,
+        })),
+        Err(e) => Err(ApiError::from(e.context("Git maintenance failed"))),
+    }
+}
+
+/// Compare the data directory with its mirror
+pub async fn check_mirror(
+    State(repo): State<Arc<RecipeRepository>>,
+) -> Result<Json<MirrorReportResponse>, ApiError> {
+    match repo.check_mirror().await {
+        Ok(report) => mirror_response(report),
+        Err(e) => Err(ApiError::from(e.context("Failed to check the mirror"))),
+    }
+}
+
+/// Copy what the mirror is missing and remove what it shouldn't have
+pub async fn repair_mirror(
+    State(repo): State<Arc<RecipeRepository>>,
+) -> Result<Json<MirrorReportResponse>, ApiError> {
+    match repo.repair_mirror().await {
+        Ok(report) => mirror_response(report),
+        Err(e) => Err(ApiError::from(e.context("Failed to repair the mirror"))),
+    }
+}
+
+fn mirror_response(report: Option<MirrorReport>) -> Result<Json<MirrorReportResponse>, ApiError> {
+    let report = report
+        .ok_or_else(|| ApiError::Validation("No mirror configured; set MIRROR_PATH".to_string()))?;
+    Ok(Json(MirrorReportResponse {
+        consistent: report.is_consistent(),
+        missing: report.missing,
+        different: report.different,
+        extra: report.extra,
+        pending: report.pending,
+        failures: report.failures,
+        last_error: report.last_error,
+    }))
+}
+
+/// What the recipe index holds, how big it is and how its rebuilds went
+pub async fn cache_stats(State(repo): State<Arc<RecipeRepository>>) -> Json<CacheStatsResponse> {
+    let stats = repo.index_metrics().snapshot();
+    let entries = repo.recipe_count();
+    let categories: Vec<CategoryItem> = repo
+        .category_counts()
+        .into_iter()
+        .map(|category| CategoryItem {
+            path: category.path,
+            count: category.count,
+            direct_count: category.direct_count,
+        })
+        .collect();
+    let categorized: usize = categories
+        .iter()
+        .map(|category| category.direct_count)
+        .sum();
+
+    Json(CacheStatsResponse {
+        entries,
+        approximate_bytes: repo.index_size(),
+        changed_at: repo.changed_at().to_rfc3339(),
+        last_rebuild: stats.last_rebuild.map(|rebuild| CacheRebuildInfo {
+            mode: if rebuild.incremental {
+                "incremental"
+            } else {
+                "full"
+            }
+            .to_string(),
+            finished_at: rebuild.finished_at.to_rfc3339(),
+            duration_ms: rebuild.duration.as_millis() as u64,
+            parsed: rebuild.parsed,
+        }),
+        full_rebuilds: stats.full_rebuilds,
+        incremental_rebuilds: stats.incremental_rebuilds,
+        parse_failures: stats.parse_failures,
+        failing_files: stats
+            .failing
+            .into_iter()
+            .map(|(path, error)| FailingFile { path, error })
+            .collect(),
+        categories,
+        uncategorized: entries.saturating_sub(categorized),
+    })
+}
+
+/// Prometheus text format, as scrapers expect it
+const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Storage operation timings and error counts, then index counters, in the Prometheus
+/// text format
+pub async fn metrics(State(repo): State<Arc<RecipeRepository>>) -> Response {
+    let mut text = repo.storage_metrics().to_prometheus();
+    text.push_str(&repo.index_metrics().to_prometheus(repo.recipe_count()));
+    ([(header::CONTENT_TYPE, METRICS_CONTENT_TYPE)], text).into_response()
+}
+
+/// Register a webhook; the response is the only time its secret is shown
+pub async fn create_webhook(
+    State(repo): State<Arc<RecipeRepository>>,
+    Json(payload): Json<CreateWebhookRequest>,
+) -> Result<(StatusCode, Json<WebhookResponse>), ApiError> {
+    let hook = repo
+        .webhooks()
+        .create(
+            &payload.url,
+            payload.events,
+            payload.secret.as_deref(),
+            payload.author.as_deref(),
+        )
+        .map_err(|e| ApiError::Validation(format!("{:#}", e)))?;
+    Ok((StatusCode::CREATED, Json(webhook_response(hook, true))))
+}
+
+/// Registered webhooks, without their secrets
+pub async fn list_webhooks(State(repo): State<Arc<RecipeRepository>>) -> Json<WebhookListResponse> {
+    Json(WebhookListResponse {
+        webhooks: repo
+            .webhooks()
+            .list()
+            .into_iter()
+            .map(|hook| webhook_response(hook, false))
+            .collect(),
+    })
+}
+
+/// A webhook, without its secret
+pub async fn get_webhook(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(webhook_id): Path<String>,
+) -> Result<Json<WebhookResponse>, ApiError> {
+    let hook = repo
+        .webhooks()
+        .get(&webhook_id)
+        .ok_or(ApiError::NotFound(Resource::Webhook))?;
+    Ok(Json(webhook_response(hook, false)))
+}
+
+/// Remove a webhook; deliveries already under way still finish
+pub async fn delete_webhook(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(webhook_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    match repo.webhooks().remove(&webhook_id) {
+        Ok(Some(_)) => Ok(StatusCode::NO_CONTENT),
+        Ok(None) => Err(ApiError::NotFound(Resource::Webhook)),
+        Err(e) => Err(ApiError::from(e.context("Failed to remove webhook"))),
+    }
+}
+
+/// Recent deliveries to a webhook, newest first, optionally in one `status`
+pub async fn list_webhook_deliveries(
+    State(repo): State<Arc<RecipeRepository>>,
+    Path(webhook_id): Path<String>,
+    Query(params): Query<DeliveryQuery>,
+) -> Result<Json<DeliveryListResponse>, ApiError> {
+    if repo.webhooks().get(&webhook_id).is_none() {
+        return Err(ApiError::NotFound(Resource::Webhook));
+    }
+    let status = match params.status.as_deref() {
+        Some(status) => Some(DeliveryStatus::parse(status).ok_or_else(|| {
+            ApiError::Validation(format!(
+                "Unknown delivery status '{}' (expected pending, delivered or failed)",
+                status
+            ))
+        })?),
+        None => None,
+    };
+    Ok(Json(delivery_list_response(
+        repo.webhooks().deliveries(Some(&webhook_id), status),
+    )))
+}
+
+/// Deliveries that ran out of attempts, across all webhooks (including removed ones)
+pub async fn list_dead_letters(
+    State(repo): State<Arc<RecipeRepository>>,
+) -> Json<DeliveryListResponse> {
+    Json(delivery_list_response(
+        repo.webhooks()
+            .deliveries(None, Some(DeliveryStatus::Failed)),
+    ))
+}
+
+fn webhook_response(hook: Webhook, with_secret: bool) -> WebhookResponse {
+    WebhookResponse {
+        webhook_id: hook.id,
+        url: hook.url,
+        events: hook.events,
+        secret: with_secret.then_some(hook.secret),
+        created_at: hook.created_at.to_rfc3339(),
+        created_by: hook.created_by,
+    }
+}
+
+fn delivery_list_response(deliveries: Vec<Delivery>) -> DeliveryListResponse {
+    DeliveryListResponse {
+        deliveries: deliveries
+            .into_iter()
+            .map(|delivery| DeliveryResponse {
+                payload: serde_json::from_str(&delivery.payload)
+                    .unwrap_or(serde_json::Value::String(delivery.payload)),
+                delivery_id: delivery.id,
+                webhook_id: delivery.webhook_id,
+                event: delivery.event,
+                status: delivery.status.as_str().to_string(),
+                attempts: delivery
+                    .attempts
+                    .into_iter()
+                    .map(|attempt| DeliveryAttemptResponse {
+                        at: attempt.at.to_rfc3339(),
+                        status_code: attempt.status_code,
+                        error: attempt.error,
+                    })
+                    .collect(),
+                created_at: delivery.created_at.to_rfc3339(),
+            })
+            .collect(),
+    }
+}
+
+/// Stream recipe changes as server-sent events, from the moment the client connects
+///
+/// Each event is named after its kind (`recipe.created`...) and carries the same JSON as
+/// a webhook payload. A client too slow to keep up gets a `lagged` event with the number
+/// of changes it missed, and should refetch what it shows.
+pub async fn stream_events(
+    State(repo): State<Arc<RecipeRepository>>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let events = repo.events().subscribe();
+    let stream = futures_util::stream::unfold(events, |mut events| async move {
+        let event = match events.recv().await {
+            Ok(event) => Event::default()
+                .event(event.event.as_str())
+                .json_data(&event),
+            Err(RecvError::Lagged(missed)) => {
+                Ok(Event::default().event("lagged").data(missed.to_string()))
+            }
+            Err(RecvError::Closed) => return None,
+        };
+        Some((event, events))
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
 }
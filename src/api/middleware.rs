@@ -0,0 +1,522 @@
+use axum::{
+    body::Body,
+    extract::{MatchedPath, Query, RawPathParams, Request},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::IntoResponse,
+    RequestPartsExt,
+};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{field, Instrument};
+
+use super::error::ApiError;
+use crate::deadline;
+
+/// Header identifying a request, taken from the client or generated, and echoed back
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest request ID accepted from a client; longer ones are replaced
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+static REQUEST_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Media type of RFC 7807 problem details, which clients opt into with `Accept`
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// What error responses need to know about the request being handled
+struct RequestContext {
+    id: String,
+    /// Request path, the `instance` of problem details
+    path: String,
+    /// Whether `Accept` lists `application/problem+json`
+    problem_json: bool,
+}
+
+tokio::task_local! {
+    static REQUEST: RequestContext;
+}
+
+/// Tag each request with an ID, so a failure a user reports can be found in the logs
+///
+/// A client-sent `X-Request-Id` of up to 128 visible ASCII characters is kept; anything
+/// else gets a generated one. The ID is a field of the `request` span every log line of
+/// the request is written in, and comes back in the `X-Request-Id` response header and
+/// the `requestId` of error bodies. Each request ends with a "request finished" line
+/// giving its `status` and `latency_ms`.
+pub async fn request_id(request: Request, next: Next) -> axum::response::Response {
+    let started = Instant::now();
+    let id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|id| {
+            !id.is_empty()
+                && id.len() <= MAX_REQUEST_ID_LEN
+                && id.bytes().all(|b| b.is_ascii_graphic())
+        })
+        .map(str::to_string)
+        .unwrap_or_else(generate_request_id);
+
+    // `route` and `recipe_id` are filled in by `record_route` once the request is routed
+    let span = tracing::info_span!(
+        "request",
+        request_id = %id,
+        method = %request.method(),
+        route = field::Empty,
+        recipe_id = field::Empty,
+    );
+    let context = RequestContext {
+        id: id.clone(),
+        path: request.uri().path().to_string(),
+        problem_json: accepts_problem_json(request.headers()),
+    };
+    let mut response = REQUEST
+        .scope(context, next.run(request))
+        .instrument(span.clone())
+        .await;
+    span.in_scope(|| {
+        tracing::info!(
+            status = response.status().as_u16(),
+            latency_ms = started.elapsed().as_millis() as u64,
+            "request finished"
+        )
+    });
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+/// Record the route pattern (not the path, which may hold share tokens) and the recipe
+/// ID, if the route has one, on the request span started by [`request_id`]
+pub async fn record_route(request: Request, next: Next) -> axum::response::Response {
+    let span = tracing::Span::current();
+    if let Some(route) = request.extensions().get::<MatchedPath>() {
+        span.record("route", route.as_str());
+    }
+    let (mut parts, body) = request.into_parts();
+    if let Ok(params) = parts.extract::<RawPathParams>().await {
+        if let Some((_, recipe_id)) = params.iter().find(|(name, _)| *name == "recipe_id") {
+            span.record("recipe_id", recipe_id);
+        }
+    }
+    next.run(Request::from_parts(parts, body)).await
+}
+
+/// ID of the request being handled, outside a request `None`
+pub fn current_request_id() -> Option<String> {
+    REQUEST.try_with(|request| request.id.clone()).ok()
+}
+
+/// Path of the request being handled if its errors should be problem details, else `None`
+pub fn problem_details_instance() -> Option<String> {
+    REQUEST
+        .try_with(|request| request.problem_json.then(|| request.path.clone()))
+        .ok()
+        .flatten()
+}
+
+/// Whether the `Accept` header lists `application/problem+json`, in any position
+fn accepts_problem_json(headers: &axum::http::HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|accept| accept.split(','))
+        .any(|media_type| {
+            media_type
+                .split(';')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .eq_ignore_ascii_case(PROBLEM_JSON)
+        })
+}
+
+fn generate_request_id() -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(
+        Utc::now()
+            .timestamp_nanos_opt()
+            .unwrap_or_default()
+            .to_le_bytes(),
+    );
+    hasher.update(
+        REQUEST_COUNTER
+            .fetch_add(1, Ordering::Relaxed)
+            .to_le_bytes(),
+    );
+    hasher.update(std::process::id().to_le_bytes());
+    format!("{:x}", hasher.finalize())[..16].to_string()
+}
+
+/// Import routes that take a completed upload, which can be far too large to import
+/// within the request timeout
+static UPLOAD_IMPORT_ROUTES: &[&str] = &[
+    "/api/v1/import/paprika",
+    "/api/v1/import/mealie",
+    "/api/v1/import/tandoor",
+    "/api/v1/import/nextcloud",
+];
+
+#[derive(Deserialize)]
+struct UploadQuery {
+    upload_id: Option<String>,
+}
+
+/// Whether a request imports a completed upload, which runs without a timeout
+pub fn imports_upload(request: &Request) -> bool {
+    request
+        .extensions()
+        .get::<MatchedPath>()
+        .is_some_and(|matched| UPLOAD_IMPORT_ROUTES.contains(&matched.as_str()))
+        && Query::<UploadQuery>::try_from_uri(request.uri())
+            .is_ok_and(|Query(query)| query.upload_id.is_some())
+}
+
+/// How long past the deadline a request may run before it is dropped outright
+const TIMEOUT_GRACE: Duration = Duration::from_secs(5);
+
+/// Abandon requests that run longer than `timeout` with 504 Gateway Timeout
+///
+/// The deadline is exposed to repository and storage loops through
+/// [`deadline::check`], so long-running work stops cooperatively and reports how far
+/// it got. Only a request still running [`TIMEOUT_GRACE`] later (e.g. stuck waiting) is
+/// dropped, with just the timeout in its details.
+pub async fn request_timeout(
+    timeout: Duration,
+    request: Request,
+    next: Next,
+) -> axum::response::Response {
+    let work = deadline::scope(timeout, next.run(request));
+    match tokio::time::timeout(timeout + TIMEOUT_GRACE, work).await {
+        Ok(response) => response,
+        Err(_) => {
+            let mut details = HashMap::new();
+            details.insert("timeoutSecs".to_string(), timeout.as_secs().to_string());
+            ApiError::Timeout {
+                message: format!("Request did not complete within {}s", timeout.as_secs()),
+                details,
+            }
+            .into_response()
+        }
+    }
+}
+
+/// Reject requests without `Authorization: Bearer <token>` with 401 Unauthorized
+pub async fn require_token(
+    token: Arc<str>,
+    request: Request,
+    next: Next,
+) -> axum::response::Response {
+    let sent = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim);
+
+    match sent {
+        Some(sent) if constant_time_eq(sent.as_bytes(), token.as_bytes()) => {
+            next.run(request).await
+        }
+        _ => ApiError::Unauthorized.into_response(),
+    }
+}
+
+/// Compare secrets without returning early, so timing doesn't reveal how much matched
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[derive(Deserialize)]
+struct FieldsQuery {
+    fields: Option<String>,
+}
+
+/// Trim successful JSON responses to the fields named in `?fields=recipeId,recipeName`
+///
+/// Works on the serialized body, so every endpoint supports it without handler changes.
+/// Paginated lists (objects with a `pagination` field) keep their envelope and trim the
+/// objects in their arrays; any other response object is trimmed itself. Names missing
+/// from a response are ignored, and error responses are left whole.
+pub async fn sparse_fields(request: Request, next: Next) -> axum::response::Response {
+    let fields: HashSet<String> = Query::<FieldsQuery>::try_from_uri(request.uri())
+        .ok()
+        .and_then(|Query(query)| query.fields)
+        .map(|fields| {
+            fields
+                .split(',')
+                .map(str::trim)
+                .filter(|field| !field.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let response = next.run(request).await;
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    if fields.is_empty() || !response.status().is_success() || !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return ApiError::Internal(format!("Failed to read response: {}", e)).into_response()
+        }
+    };
+    let Ok(mut value) = serde_json::from_slice::<Value>(&bytes) else {
+        return axum::response::Response::from_parts(parts, Body::from(bytes));
+    };
+    select_fields(&mut value, &fields);
+    parts.headers.remove(header::CONTENT_LENGTH);
+    let body = serde_json::to_vec(&value).unwrap_or_else(|_| bytes.to_vec());
+    axum::response::Response::from_parts(parts, Body::from(body))
+}
+
+/// Keep only `fields` of a response object, or of the items of a paginated list
+fn select_fields(value: &mut Value, fields: &HashSet<String>) {
+    let Value::Object(object) = value else {
+        return;
+    };
+    if !object.contains_key("pagination") {
+        object.retain(|key, _| fields.contains(key));
+        return;
+    }
+    for items in object.values_mut() {
+        if let Value::Array(items) = items {
+            for item in items.iter_mut().filter(|item| item.is_object()) {
+                select_fields(item, fields);
+            }
+        }
+    }
+}
+
+/// An endpoint slated for removal
+pub struct Deprecation {
+    pub method: Method,
+    /// Route pattern as registered, including the `/api/v1` prefix (e.g. `/api/v1/categories/:name`)
+    pub route: &'static str,
+    /// Date the endpoint was deprecated (`YYYY-MM-DD`)
+    pub deprecated_on: &'static str,
+    /// Date after which the endpoint may be removed (`YYYY-MM-DD`)
+    pub sunset_on: Option<&'static str>,
+    /// Replacement endpoint, advertised as a `successor-version` link
+    pub successor: Option<&'static str>,
+}
+
+/// Add `Deprecation` (RFC 9745), `Sunset` (RFC 8594) and `Link` headers to responses
+/// from deprecated endpoints, so clients notice before the endpoint goes away
+pub async fn deprecation_headers(
+    deprecations: &'static [Deprecation],
+    request: Request,
+    next: Next,
+) -> axum::response::Response {
+    let deprecation = request
+        .extensions()
+        .get::<MatchedPath>()
+        .and_then(|matched| {
+            deprecations.iter().find(|deprecation| {
+                deprecation.method == request.method() && deprecation.route == matched.as_str()
+            })
+        });
+
+    let mut response = next.run(request).await;
+
+    if let Some(deprecation) = deprecation {
+        let headers = response.headers_mut();
+        if let Some(date) = parse_date(deprecation.deprecated_on) {
+            let value = format!("@{}", date.and_time(NaiveTime::MIN).and_utc().timestamp());
+            headers.insert("deprecation", HeaderValue::from_str(&value).unwrap());
+        }
+        if let Some(date) = deprecation.sunset_on.and_then(parse_date) {
+            let value = http_date(date.and_time(NaiveTime::MIN).and_utc());
+            headers.insert("sunset", HeaderValue::from_str(&value).unwrap());
+        }
+        if let Some(successor) = deprecation.successor {
+            let value = format!("<{}>; rel=\"successor-version\"", successor);
+            if let Ok(value) = HeaderValue::from_str(&value) {
+                headers.append("link", value);
+            }
+        }
+    }
+
+    response
+}
+
+fn parse_date(date: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+/// `time` in the HTTP date format (`Tue, 01 Jul 2025 00:00:00 GMT`)
+fn http_date(time: DateTime<Utc>) -> String {
+    time.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Let browsers and proxies cache the responses of `routes` and revalidate them cheaply
+///
+/// Successful GET and HEAD responses get `Cache-Control: no-cache`, `Vary: Accept,
+/// X-Collection` and `changed_at` as `Last-Modified`; when `If-Modified-Since` is no
+/// older, the response is replaced by 304 Not Modified. `Last-Modified` is left out while
+/// `changed_at` is in the current second, as another change in that second would carry
+/// the same time. Behind a token (`protected`) responses are also `private` and vary on
+/// `Authorization`, so shared caches don't hand them to other clients.
+pub async fn last_modified(
+    routes: &'static [&'static str],
+    changed_at: DateTime<Utc>,
+    protected: bool,
+    request: Request,
+    next: Next,
+) -> axum::response::Response {
+    let cacheable = matches!(*request.method(), Method::GET | Method::HEAD)
+        && request
+            .extensions()
+            .get::<MatchedPath>()
+            .is_some_and(|matched| routes.contains(&matched.as_str()));
+    if !cacheable {
+        return next.run(request).await;
+    }
+    let if_modified_since = request
+        .headers()
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| DateTime::parse_from_rfc2822(value).ok());
+
+    let mut response = next.run(request).await;
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+    let headers = response.headers_mut();
+    let (cache_control, vary) = if protected {
+        ("private, no-cache", "accept, x-collection, authorization")
+    } else {
+        ("no-cache", "accept, x-collection")
+    };
+    headers.insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static(cache_control),
+    );
+    headers.append(header::VARY, HeaderValue::from_static(vary));
+    if changed_at.timestamp() >= Utc::now().timestamp() {
+        return response;
+    }
+    headers.insert(
+        header::LAST_MODIFIED,
+        HeaderValue::from_str(&http_date(changed_at)).unwrap(),
+    );
+
+    if if_modified_since.is_some_and(|since| changed_at.timestamp() <= since.timestamp()) {
+        let mut not_modified = StatusCode::NOT_MODIFIED.into_response();
+        for name in [header::CACHE_CONTROL, header::VARY, header::LAST_MODIFIED] {
+            for value in response.headers().get_all(&name) {
+                not_modified.headers_mut().append(&name, value.clone());
+            }
+        }
+        return not_modified;
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        routing::{get, post},
+        Router,
+    };
+    use tower::util::ServiceExt;
+
+    static DEPRECATIONS: &[Deprecation] = &[Deprecation {
+        method: Method::GET,
+        route: "/api/v1/old/:id",
+        deprecated_on: "2025-01-01",
+        sunset_on: Some("2025-07-01"),
+        successor: Some("/api/v1/new/{id}"),
+    }];
+
+    fn router() -> Router {
+        let api = Router::new()
+            .route("/old/:id", get(|| async { "old" }))
+            .route("/new/:id", get(|| async { "new" }))
+            .layer(axum::middleware::from_fn(|request: Request, next: Next| {
+                deprecation_headers(DEPRECATIONS, request, next)
+            }));
+        Router::new().nest("/api/v1", api)
+    }
+
+    #[tokio::test]
+    async fn test_deprecated_route_gets_headers() {
+        let request = Request::builder()
+            .uri("/api/v1/old/42")
+            .body(Body::empty())
+            .unwrap();
+        let response = router().oneshot(request).await.unwrap();
+
+        let headers = response.headers();
+        assert_eq!(headers["deprecation"], "@1735689600");
+        assert_eq!(headers["sunset"], "Tue, 01 Jul 2025 00:00:00 GMT");
+        assert_eq!(
+            headers["link"],
+            "</api/v1/new/{id}>; rel=\"successor-version\""
+        );
+    }
+
+    #[tokio::test]
+    async fn test_other_routes_untouched() {
+        let request = Request::builder()
+            .uri("/api/v1/new/42")
+            .body(Body::empty())
+            .unwrap();
+        let response = router().oneshot(request).await.unwrap();
+
+        assert!(response.headers().get("deprecation").is_none());
+        assert!(response.headers().get("sunset").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_imports_upload_matches_upload_imports_only() {
+        let api = Router::new()
+            .route("/import/paprika", post(|| async {}))
+            .route("/recipes", post(|| async {}))
+            .layer(axum::middleware::from_fn(
+                |request: Request, next: Next| async move {
+                    let exempt = imports_upload(&request);
+                    let mut response = next.run(request).await;
+                    response
+                        .headers_mut()
+                        .insert("x-exempt", exempt.to_string().parse().unwrap());
+                    response
+                },
+            ));
+        let app = Router::new().nest("/api/v1", api);
+
+        for (uri, exempt) in [
+            ("/api/v1/import/paprika?upload_id=abc", "true"),
+            ("/api/v1/import/paprika", "false"),
+            ("/api/v1/recipes?upload_id=abc", "false"),
+        ] {
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap();
+            let response = app.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.headers()["x-exempt"], exempt, "{}", uri);
+        }
+    }
+}
@@ -1,42 +1,410 @@
+pub mod error;
 pub mod handlers;
+pub mod middleware;
 pub mod models;
 pub mod responses;
+pub mod web;
 
 use axum::{
-    extract::DefaultBodyLimit,
-    routing::{delete, get, post, put},
+    extract::{DefaultBodyLimit, Request},
+    http::{header, HeaderValue, Uri},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::{delete, get, patch, post, put},
     Router,
 };
+use std::collections::BTreeMap;
+use std::convert::Infallible;
 use std::sync::Arc;
+use std::time::Duration;
+use tower::ServiceExt;
 use tower_http::cors::CorsLayer;
 
 use crate::repository::RecipeRepository;
+use error::{ApiError, Resource};
+use middleware::Deprecation;
+
+/// Header selecting a named collection for `/api/v1/...` requests
+pub const COLLECTION_HEADER: &str = "x-collection";
+
+/// Collection a request was routed to, for handlers that build links back to it
+#[derive(Debug, Clone)]
+pub struct SelectedCollection(pub String);
+
+/// Endpoints slated for removal; responses carry `Deprecation`/`Sunset` headers
+///
+/// Add an entry (with a sunset date at least one release out) before removing or
+/// replacing an endpoint, and note it in docs/API.md.
+static DEPRECATIONS: &[Deprecation] = &[];
+
+/// GET routes built only from the recipe index (and favorites), which get
+/// `Last-Modified` headers and answer `If-Modified-Since` (HEAD included)
+static CACHEABLE_ROUTES: &[&str] = &[
+    "/api/v1/recipes",
+    "/api/v1/recipes/search",
+    "/api/v1/recipes/:recipe_id",
+    "/api/v1/recipes/:recipe_id/raw",
+    "/api/v1/public/recipes",
+    "/api/v1/public/recipes/:recipe_id",
+];
 
 /// Build the API router with all routes
 pub fn build_router(repo: Arc<RecipeRepository>) -> Router {
+    build_router_with_collections(repo, BTreeMap::new())
+}
+
+/// Build the API router for a default repository plus named collections
+///
+/// A collection is selected with `/api/v1/collections/{name}/...` or with the
+/// `X-Collection` header on `/api/v1/...`; other requests go to `repo`. Each collection
+/// gets its own full router, so caches and state never mix.
+pub fn build_router_with_collections(
+    repo: Arc<RecipeRepository>,
+    collections: BTreeMap<String, Arc<RecipeRepository>>,
+) -> Router {
+    let names: Arc<Vec<String>> = Arc::new(collections.keys().cloned().collect());
+    let default = collection_router(repo, names.clone());
+    let collections: Arc<BTreeMap<String, Router>> = Arc::new(
+        collections
+            .into_iter()
+            .map(|(name, repo)| (name, collection_router(repo, names.clone())))
+            .collect(),
+    );
+
+    Router::new()
+        .fallback_service(tower::service_fn(move |request: Request| {
+            let default = default.clone();
+            let collections = collections.clone();
+            async move { Ok::<_, Infallible>(dispatch(default, &collections, request).await) }
+        }))
+        // Outermost, so every response and log line carries the request ID
+        .layer(axum::middleware::from_fn(middleware::request_id))
+}
+
+/// Route a request to the collection it names (by path or header), or to `default`
+async fn dispatch(
+    default: Router,
+    collections: &BTreeMap<String, Router>,
+    mut request: Request,
+) -> Response {
+    let by_path = split_collection_path(request.uri());
+    let selected = match &by_path {
+        Some((name, uri)) => {
+            *request.uri_mut() = uri.clone();
+            Some(name.clone())
+        }
+        None => request
+            .headers()
+            .get(COLLECTION_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(|name| name.trim().to_lowercase()),
+    };
+
+    let router = match &selected {
+        None => default,
+        Some(name) => match collections.get(name) {
+            Some(router) => {
+                request
+                    .extensions_mut()
+                    .insert(SelectedCollection(name.clone()));
+                router.clone()
+            }
+            None => return ApiError::NotFound(Resource::Collection).into_response(),
+        },
+    };
+    let mut response = match router.oneshot(request).await {
+        Ok(response) => response,
+        Err(never) => match never {},
+    };
+
+    // Keep redirects (e.g. to a renamed recipe) inside the collection
+    if let Some((name, _)) = by_path {
+        let location = response
+            .headers()
+            .get(header::LOCATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|location| location.strip_prefix("/api/v1/"))
+            .and_then(|rest| {
+                HeaderValue::from_str(&format!("/api/v1/collections/{}/{}", name, rest)).ok()
+            });
+        if let Some(location) = location {
+            response.headers_mut().insert(header::LOCATION, location);
+        }
+    }
+    response
+}
+
+/// `/api/v1/collections/{name}/rest?query` → (`name`, `/api/v1/rest?query`)
+fn split_collection_path(uri: &Uri) -> Option<(String, Uri)> {
+    let rest = uri.path().strip_prefix("/api/v1/collections/")?;
+    let (name, rest) = rest.split_once('/')?;
+    let path = match uri.query() {
+        Some(query) => format!("/api/v1/{}?{}", rest, query),
+        None => format!("/api/v1/{}", rest),
+    };
+    Some((name.to_lowercase(), path.parse().ok()?))
+}
+
+/// All routes for one repository
+fn collection_router(repo: Arc<RecipeRepository>, collections: Arc<Vec<String>>) -> Router {
     // Split routes: those that don't need state and those that do
-    let public_routes = Router::new().route("/health", get(handlers::health_check));
+    let mut public_routes = Router::new().route("/health", get(handlers::health_check));
+
+    // Built-in browser UI; it talks to the API below like any other client
+    if repo.config().web_ui {
+        public_routes = public_routes
+            .route("/", get(web::index))
+            .route("/ui/:file", get(web::asset));
+    }
 
-    let api_routes = Router::new()
+    let mut api_routes = Router::new()
         .route("/status", get(handlers::status))
+        .route("/events", get(handlers::stream_events))
+        .route(
+            "/collections",
+            get(move || handlers::list_collections(collections.clone())),
+        )
         // Recipe CRUD endpoints
         .route("/recipes", post(handlers::create_recipe))
         .route("/recipes", get(handlers::list_recipes))
         .route("/recipes/search", get(handlers::search_recipes))
+        .route("/recipes/cookable", get(handlers::list_cookable_recipes))
+        .route("/recipes/batch-tag", post(handlers::batch_tag))
+        .route("/recipes/export", get(handlers::export_recipes))
         .route("/recipes/find-by-name", get(handlers::find_recipe_by_name))
         .route("/recipes/find-by-path", get(handlers::find_recipe_by_path))
+        .route(
+            "/recipes/from-template/:name",
+            post(handlers::create_from_template),
+        )
         .route("/recipes/:recipe_id", get(handlers::get_recipe))
         .route("/recipes/:recipe_id", put(handlers::update_recipe))
+        .route("/recipes/:recipe_id", patch(handlers::patch_recipe))
         .route("/recipes/:recipe_id", delete(handlers::delete_recipe))
+        .route("/recipes/:recipe_id/raw", get(handlers::get_recipe_raw))
+        .route("/recipes/:recipe_id/raw", put(handlers::update_recipe_raw))
+        .route("/recipes/:recipe_id/html", get(handlers::get_recipe_html))
+        .route("/recipes/:recipe_id/qr", get(handlers::get_recipe_qr))
+        .route("/recipes/:recipe_id/move", post(handlers::move_recipe))
+        .route("/recipes/:recipe_id/copy", post(handlers::copy_recipe))
+        .route("/recipes/:recipe_id/blame", get(handlers::blame_recipe))
+        .route("/recipes/:recipe_id/favorite", put(handlers::set_favorite))
+        .route("/recipes/:recipe_id/rating", put(handlers::set_rating))
+        .route("/recipes/:recipe_id/notes", get(handlers::get_notes))
+        .route("/recipes/:recipe_id/notes", post(handlers::add_note))
+        .route(
+            "/recipes/:recipe_id/notes/:index",
+            delete(handlers::delete_note),
+        )
+        .route(
+            "/recipes/:recipe_id/versions",
+            post(handlers::create_version),
+        )
+        .route("/recipes/:recipe_id/versions", get(handlers::list_versions))
+        .route(
+            "/recipes/:recipe_id/versions/:name",
+            get(handlers::get_version),
+        )
         // Category endpoints
         .route("/categories", get(handlers::list_categories))
+        .route("/categories/rename", post(handlers::rename_category))
         .route("/categories/:name", get(handlers::get_category_recipes))
+        // Ingredient endpoints
+        .route("/ingredients", get(handlers::list_ingredients))
+        // Pantry endpoints
+        .route("/pantry", get(handlers::get_pantry))
+        .route("/pantry/:name", put(handlers::set_pantry_item))
+        .route("/pantry/:name", delete(handlers::delete_pantry_item))
+        // Shopping list endpoints
+        .route("/shopping-lists", get(handlers::list_shopping_lists))
+        .route("/shopping-lists", post(handlers::create_shopping_list))
+        .route("/shopping-lists/:list_id", get(handlers::get_shopping_list))
+        .route(
+            "/shopping-lists/:list_id",
+            delete(handlers::delete_shopping_list),
+        )
+        .route(
+            "/shopping-lists/:list_id/export",
+            get(handlers::export_shopping_list),
+        )
+        .route(
+            "/shopping-lists/:list_id/items/:item_id",
+            patch(handlers::update_shopping_item),
+        )
+        // Import endpoints
+        .route("/import/paprika", post(handlers::import_paprika))
+        .route("/import/mealie", post(handlers::import_mealie))
+        .route("/import/tandoor", post(handlers::import_tandoor))
+        .route("/import/nextcloud", post(handlers::import_nextcloud))
+        .route("/import/markdown", post(handlers::import_markdown))
+        .route("/convert", post(handlers::convert_recipe))
+        // Template endpoints
+        .route("/templates", get(handlers::list_templates))
+        // Menu endpoints
+        .route("/menus", get(handlers::list_menus))
+        .route("/menus/:menu_id", get(handlers::get_menu))
+        // Trash endpoints
+        .route("/trash", get(handlers::list_trash))
+        .route(
+            "/trash/:trash_id/restore",
+            post(handlers::restore_from_trash),
+        )
+        // Draft endpoints
+        .route("/drafts", get(handlers::list_drafts))
+        .route("/drafts/:draft_id", get(handlers::get_draft))
+        .route("/drafts/:draft_id", delete(handlers::delete_draft))
+        .route("/drafts/:draft_id/publish", post(handlers::publish_draft))
+        // Resumable upload endpoints
+        .route("/uploads", post(handlers::create_upload))
+        .route("/uploads/:upload_id", get(handlers::get_upload))
+        .route("/uploads/:upload_id", patch(handlers::upload_chunk))
+        .route("/uploads/:upload_id", delete(handlers::delete_upload));
+
+    // Maintenance and integrations, behind their own token (see below)
+    let mut admin_routes = Router::new()
+        .route("/admin/reindex", post(handlers::reindex))
+        .route("/admin/units/reload", post(handlers::reload_units))
+        .route("/admin/synonyms/reload", post(handlers::reload_synonyms))
+        .route("/admin/normalize", post(handlers::normalize_front_matter))
+        .route(
+            "/admin/misaligned-files",
+            get(handlers::list_misaligned_files),
+        )
+        .route("/admin/reconcile", post(handlers::reconcile_filenames))
+        .route("/admin/git/gc", post(handlers::run_git_gc))
+        .route("/admin/mirror", get(handlers::check_mirror))
+        .route("/admin/mirror/repair", post(handlers::repair_mirror))
+        .route("/admin/metrics", get(handlers::metrics))
+        .route("/admin/cache", get(handlers::cache_stats))
+        .route("/admin/reload-config", post(handlers::reload_config))
+        .route("/admin/webhooks", post(handlers::create_webhook))
+        .route("/admin/webhooks", get(handlers::list_webhooks))
+        .route(
+            "/admin/webhooks/dead-letters",
+            get(handlers::list_dead_letters),
+        )
+        .route("/admin/webhooks/:webhook_id", get(handlers::get_webhook))
+        .route(
+            "/admin/webhooks/:webhook_id",
+            delete(handlers::delete_webhook),
+        )
+        .route(
+            "/admin/webhooks/:webhook_id/deliveries",
+            get(handlers::list_webhook_deliveries),
+        );
+
+    // Experimental endpoints are mounted only when their flag is in `FEATURES`
+    if repo.config().feature_enabled("nutrition") {
+        api_routes = api_routes.route(
+            "/recipes/:recipe_id/nutrition",
+            get(handlers::get_nutrition),
+        );
+    }
+
+    // Read-only routes for readers without the API token: public recipes and share links
+    let mut open_routes = Router::new()
+        .route("/public/recipes", get(handlers::list_public_recipes))
+        .route(
+            "/public/recipes/:recipe_id",
+            get(handlers::get_public_recipe),
+        )
+        .route(
+            "/public/recipes/:recipe_id/html",
+            get(handlers::get_public_recipe_html),
+        );
+
+    // Share links need a signing secret
+    if repo.sharing_enabled() {
+        api_routes = api_routes
+            .route("/recipes/:recipe_id/share", post(handlers::create_share))
+            .route("/recipes/:recipe_id/shares", get(handlers::list_shares))
+            .route("/shares/:share_id", delete(handlers::revoke_share));
+        open_routes = open_routes
+            .route("/shared/:token", get(handlers::get_shared_recipe))
+            .route("/shared/:token/html", get(handlers::get_shared_recipe_html));
+    }
+
+    // Everything else needs `API_TOKEN` when one is set
+    if let Some(token) = &repo.config().api_token {
+        let token: Arc<str> = token.as_str().into();
+        api_routes = api_routes.route_layer(axum::middleware::from_fn(
+            move |request: Request, next: Next| {
+                middleware::require_token(token.clone(), request, next)
+            },
+        ));
+    }
+
+    // Admin routes take `ADMIN_TOKEN`, never `API_TOKEN`, so a leaked client token can't
+    // reindex, rewrite files or register webhooks. A protected API without an admin token
+    // leaves them out altogether.
+    let config = repo.config();
+    match (&config.admin_token, &config.api_token) {
+        (Some(token), _) => {
+            let token: Arc<str> = token.as_str().into();
+            admin_routes = admin_routes.route_layer(axum::middleware::from_fn(
+                move |request: Request, next: Next| {
+                    middleware::require_token(token.clone(), request, next)
+                },
+            ));
+        }
+        (None, Some(_)) => {
+            tracing::warn!("API_TOKEN is set without ADMIN_TOKEN; admin routes are disabled");
+            admin_routes = Router::new();
+        }
+        (None, None) => {}
+    }
+
+    let settings = repo.clone();
+    let index = repo.clone();
+    let mut api_routes = api_routes
+        .merge(admin_routes)
+        .merge(open_routes)
         .with_state(repo);
 
+    // Name the route and recipe in the request's log lines
+    api_routes = api_routes.layer(axum::middleware::from_fn(middleware::record_route));
+
+    // Trim JSON responses to `?fields=`
+    api_routes = api_routes.layer(axum::middleware::from_fn(middleware::sparse_fields));
+
+    // Announce endpoints slated for removal
+    api_routes = api_routes.layer(axum::middleware::from_fn(|request: Request, next: Next| {
+        middleware::deprecation_headers(DEPRECATIONS, request, next)
+    }));
+
+    // Revalidate index-backed reads with `If-Modified-Since`; the change time is read
+    // before the handler runs, so a change made meanwhile is never hidden by a 304
+    let protected = config.api_token.is_some();
+    api_routes = api_routes.layer(axum::middleware::from_fn(
+        move |request: Request, next: Next| {
+            middleware::last_modified(
+                CACHEABLE_ROUTES,
+                index.changed_at(),
+                protected,
+                request,
+                next,
+            )
+        },
+    ));
+
+    // Abandon slow requests with 504 (0 disables the timeout); read per request so a
+    // config reload applies. Imports of completed uploads take as long as they take.
+    api_routes = api_routes.layer(axum::middleware::from_fn(
+        move |request: Request, next: Next| {
+            let timeout_secs = settings.config().request_timeout_secs;
+            async move {
+                if timeout_secs == 0 || middleware::imports_upload(&request) {
+                    return next.run(request).await;
+                }
+                middleware::request_timeout(Duration::from_secs(timeout_secs), request, next).await
+            }
+        },
+    ));
+
     // Combine routers
     Router::new()
         .merge(public_routes)
         .nest("/api/v1", api_routes)
-        .layer(DefaultBodyLimit::max(10 * 1024 * 1024)) // 10MB limit for recipe content
+        .layer(DefaultBodyLimit::max(10 * 1024 * 1024)) // 10MB limit for recipe content and upload chunks
         .layer(CorsLayer::permissive())
 }
@@ -1,11 +1,15 @@
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
+use crate::cache::SortOrder;
+
 /// Request body for creating a recipe
 ///
 /// - `content`: required, must include YAML front matter with `title` field
 /// - `path`: optional directory path (no `recipes/` prefix, defaults to root)
 /// - `author`: optional git commit author
 /// - `comment`: optional git commit message
+/// - `draft`: save on a draft branch instead of publishing (git storage only)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateRecipeRequest {
     /// Recipe content in Cooklang format (must include YAML front matter with `title` field)
@@ -16,6 +20,9 @@ pub struct CreateRecipeRequest {
     pub author: Option<String>,
     /// Optional comment for git commit
     pub comment: Option<String>,
+    /// Save as a draft instead of publishing
+    #[serde(default)]
+    pub draft: bool,
 }
 
 /// Request body for updating a recipe
@@ -26,6 +33,7 @@ pub struct CreateRecipeRequest {
 /// - `path`: optional new directory path (no `recipes/` prefix)
 /// - `author`: optional git commit author
 /// - `comment`: optional git commit message
+/// - `draft`: save the new content on a draft branch instead of publishing (git storage only)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateRecipeRequest {
     /// Optional new recipe content (must include YAML front matter with `title` if provided)
@@ -36,6 +44,280 @@ pub struct UpdateRecipeRequest {
     pub author: Option<String>,
     /// Optional comment for git commit
     pub comment: Option<String>,
+    /// Save as a draft instead of publishing
+    #[serde(default)]
+    pub draft: bool,
+}
+
+/// Request body for changing front matter fields of a recipe
+///
+/// Only the given fields change; the rest of the recipe is kept as it is.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatchRecipeRequest {
+    /// New title (renames the file like a content update)
+    pub title: Option<String>,
+    /// New tags (an empty list removes them)
+    pub tags: Option<Vec<String>>,
+    /// New servings, a string (`"4 bowls"`) or a number
+    pub servings: Option<serde_json::Value>,
+    /// Optional author name for git commit
+    pub author: Option<String>,
+    /// Optional comment for git commit
+    pub comment: Option<String>,
+}
+
+/// Request body for adding and removing tags across recipes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchTagRequest {
+    /// Recipes to retag
+    #[serde(rename = "recipeIds")]
+    pub recipe_ids: Vec<String>,
+    /// Tags to add (kept once, ignoring case)
+    #[serde(default)]
+    pub add: Vec<String>,
+    /// Tags to remove (ignoring case)
+    #[serde(default)]
+    pub remove: Vec<String>,
+    /// Optional author name for git commit
+    pub author: Option<String>,
+    /// Optional comment for git commit
+    pub comment: Option<String>,
+}
+
+/// Query parameters for replacing a recipe with raw Cooklang content
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RawUpdateQuery {
+    /// Optional author name for git commit
+    pub author: Option<String>,
+    /// Optional comment for git commit
+    pub comment: Option<String>,
+}
+
+/// Request body for moving or copying a recipe
+///
+/// - `path`: target directory path (no `recipes/` prefix, empty or omitted for root)
+/// - `author`: optional git commit author
+/// - `comment`: optional git commit message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecipeTargetRequest {
+    /// Target directory path (relative to data-dir, no `recipes/` prefix)
+    pub path: Option<String>,
+    /// Optional author name for git commit
+    pub author: Option<String>,
+    /// Optional comment for git commit
+    pub comment: Option<String>,
+}
+
+/// Request body for creating a recipe from a template
+///
+/// - `title`: title of the new recipe (replaces the template's front matter `title`)
+/// - `path`: optional directory path (no `recipes/` prefix, empty or omitted for root)
+/// - `author`: optional git commit author
+/// - `comment`: optional git commit message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateFromTemplateRequest {
+    /// Title of the new recipe
+    pub title: String,
+    /// Optional directory path (relative to data-dir, no `recipes/` prefix)
+    pub path: Option<String>,
+    /// Optional author name for git commit
+    pub author: Option<String>,
+    /// Optional comment for git commit
+    pub comment: Option<String>,
+}
+
+/// Request body for starting a resumable upload
+///
+/// - `fileName`: original file name (informational)
+/// - `size`: total size in bytes; chunks are then sent with `PATCH /uploads/{id}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateUploadRequest {
+    /// Original file name
+    #[serde(rename = "fileName")]
+    pub file_name: String,
+    /// Total size in bytes
+    pub size: u64,
+}
+
+/// Request body for tagging a recipe version
+///
+/// - `name`: version name, e.g. `thanksgiving-2024` (letters, digits, `.`, `_`, `-`)
+/// - `author`: optional git tagger
+/// - `comment`: optional tag message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateVersionRequest {
+    /// Version name, unique per recipe
+    pub name: String,
+    /// Optional author name for the git tag
+    pub author: Option<String>,
+    /// Optional comment for the git tag message
+    pub comment: Option<String>,
+}
+
+/// Request body for renaming a category
+///
+/// - `from`: current category path, e.g. `desserts/cakes`
+/// - `to`: new category path; subcategories and their recipes move along
+/// - `author`, `comment`: optional git commit author and comment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryRenameRequest {
+    pub from: String,
+    pub to: String,
+    /// Optional author name for git commit
+    pub author: Option<String>,
+    /// Optional comment for git commit message
+    pub comment: Option<String>,
+}
+
+/// Request body for starring or unstarring a recipe
+///
+/// - `favorite`: `true` to star (default), `false` to unstar
+/// - `user`: whose favorites to change (defaults to the shared `default` user)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FavoriteRequest {
+    /// Whether the recipe should be a favorite
+    #[serde(default = "default_true")]
+    pub favorite: bool,
+    /// Optional user the favorite belongs to
+    pub user: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Request body for rating a recipe
+///
+/// - `rating`: stars from 1 to 5, or `null` to clear the rating
+/// - `author`: optional git commit author
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RatingRequest {
+    /// Star rating (1-5), `None` clears it
+    pub rating: Option<u8>,
+    /// Optional author name for git commit
+    pub author: Option<String>,
+}
+
+/// Request body for adding a cooking note
+///
+/// - `text`: required note text
+/// - `date`: optional day the note refers to (`YYYY-MM-DD`, defaults to today)
+/// - `author`: optional note and git commit author
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateNoteRequest {
+    /// Note text, e.g. "came out dry, add 10 min"
+    pub text: String,
+    /// Day the note refers to (defaults to today)
+    pub date: Option<NaiveDate>,
+    /// Optional author name for the note and git commit
+    pub author: Option<String>,
+}
+
+/// Request body for adding or updating a pantry item
+///
+/// - `quantity`: optional amount on hand (omit to just mark the ingredient as present, `0` for out of stock)
+/// - `unit`: optional unit of `quantity`
+/// - `author`: optional git commit author
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PantryItemRequest {
+    /// Amount on hand
+    pub quantity: Option<f64>,
+    /// Unit of the amount (e.g. `g`)
+    pub unit: Option<String>,
+    /// Optional author name for git commit
+    pub author: Option<String>,
+}
+
+/// Request body for creating a share link
+///
+/// - `expiresInHours`: optional lifetime of the link (defaults to `SHARE_LINK_TTL_HOURS`)
+/// - `author`: optional git commit author
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ShareRequest {
+    /// Hours until the link stops working
+    #[serde(rename = "expiresInHours")]
+    pub expires_in_hours: Option<u64>,
+    /// Optional author name for git commit
+    pub author: Option<String>,
+}
+
+/// Request body for registering a webhook
+///
+/// - `url`: where events are POSTed
+/// - `events`: optional events to send (all when empty)
+/// - `secret`: optional signing key (generated when missing)
+/// - `author`: optional name of who registered it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    /// `recipe.created`, `recipe.updated` and/or `recipe.deleted`
+    #[serde(default)]
+    pub events: Vec<String>,
+    /// Key the `X-Signature` HMAC is computed with
+    pub secret: Option<String>,
+    pub author: Option<String>,
+}
+
+/// Query parameters for listing webhook deliveries
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeliveryQuery {
+    /// `pending`, `delivered` or `failed`
+    pub status: Option<String>,
+}
+
+/// Request body for making a shopping list
+///
+/// - `name`: optional list name (defaults to the recipe names)
+/// - `recipeIds`: recipes to shop for, e.g. a menu's `recipeIds`
+/// - `author`: optional git commit author
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ShoppingListRequest {
+    pub name: Option<String>,
+    #[serde(rename = "recipeIds", default)]
+    pub recipe_ids: Vec<String>,
+    /// Optional author name for git commit
+    pub author: Option<String>,
+}
+
+/// Request body for changing a shopping list item; fields left out stay as they are
+///
+/// - `purchased`: whether the item is in the basket
+/// - `quantity`, `unit`: corrected amount to buy
+/// - `author`: optional git commit author
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ShoppingItemRequest {
+    pub purchased: Option<bool>,
+    pub quantity: Option<f64>,
+    pub unit: Option<String>,
+    /// Optional author name for git commit
+    pub author: Option<String>,
+}
+
+/// Query parameters for getting a shopping list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShoppingListQuery {
+    /// Only purchased (`true`) or only outstanding (`false`) items
+    pub purchased: Option<bool>,
+}
+
+/// Query parameters for exporting a shopping list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShoppingExportQuery {
+    /// `text` (default), `markdown` or `csv`
+    pub format: Option<String>,
+    /// Only purchased (`true`) or only outstanding (`false`) items
+    pub purchased: Option<bool>,
+}
+
+/// Query parameters for ranking recipes by pantry coverage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CookableQuery {
+    /// Number of items per page (default: 20, max: 100)
+    pub limit: Option<u32>,
+    /// Number of items to skip (default: 0)
+    pub offset: Option<u32>,
+    /// Only recipes missing at most this many ingredients (`0` = cookable right now)
+    pub max_missing: Option<u32>,
 }
 
 /// Query parameters for listing recipes
@@ -45,17 +327,146 @@ pub struct ListQuery {
     pub limit: Option<u32>,
     /// Number of items to skip (default: 0)
     pub offset: Option<u32>,
+    /// Only recipes whose total time is known and at most this many minutes
+    pub max_time: Option<u32>,
+    /// Only recipes whose hands-on time is known and at most this many minutes
+    pub max_active_time: Option<u32>,
+    /// Only favorites (`true`) or only non-favorites (`false`) of `user`
+    pub favorite: Option<bool>,
+    /// User whose favorites `favorite` refers to (defaults to the shared `default` user)
+    pub user: Option<String>,
+    /// Also return recipes marked `draft: true` in their front matter
+    #[serde(default)]
+    pub include_drafts: bool,
+    /// Include each recipe's full content, saving a request per recipe
+    #[serde(default)]
+    pub include_content: bool,
+    /// `name` (default), `path` or `modified` (most recently changed first)
+    #[serde(default)]
+    pub sort: SortOrder,
+    /// `summary` (default) or `full`
+    #[serde(default)]
+    pub detail: SummaryDetail,
+}
+
+/// How much of each recipe a listing returns
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SummaryDetail {
+    /// ID, name, path, times and last change
+    #[default]
+    Summary,
+    /// Also the description, tags, servings and image URL, saving a request per recipe
+    Full,
+}
+
+/// Query parameters for listing public recipes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PublicListQuery {
+    /// Number of items per page (default: 20, max: 100)
+    pub limit: Option<u32>,
+    /// Number of items to skip (default: 0)
+    pub offset: Option<u32>,
+    /// `summary` (default) or `full`
+    #[serde(default)]
+    pub detail: SummaryDetail,
+}
+
+/// Query parameters for rendering a recipe as HTML
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HtmlQuery {
+    /// Return only the `<article>` element, for embedding in another page
+    #[serde(default)]
+    pub fragment: bool,
+    /// Compact, ink-friendly layout for printing
+    #[serde(default)]
+    pub print: bool,
+}
+
+/// Query parameters for a recipe's QR code
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QrQuery {
+    /// `svg` (default) or `png`
+    pub format: Option<String>,
+}
+
+/// Query parameters for exporting the collection
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecipeExportQuery {
+    /// Archive format; only `paprika` so far
+    pub format: Option<String>,
+}
+
+/// Query parameters for importing recipes from another app
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportQuery {
+    /// Import a completed upload instead of the request body
+    pub upload_id: Option<String>,
+    pub author: Option<String>,
+}
+
+/// Query parameters for converting pasted recipe text
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConvertQuery {
+    /// Title to use when the text doesn't start with one
+    pub title: Option<String>,
+}
+
+/// Query parameters for reindexing
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReindexQuery {
+    /// Rebuild from every file instead of only those changed since the last index
+    #[serde(default)]
+    pub full: bool,
+}
+
+/// Query parameters for normalizing front matter
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NormalizeQuery {
+    /// Report what would change without writing anything
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Optional author name for git commit
+    pub author: Option<String>,
+}
+
+/// Query parameters for renaming files to match their titles
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReconcileQuery {
+    /// Report the planned renames without making them
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Optional author name for git commit
+    pub author: Option<String>,
 }
 
 /// Query parameters for searching recipes
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchQuery {
-    /// Search query term
+    /// Search query term (optional for searches when a filter is given)
+    #[serde(default)]
     pub q: String,
+    /// Only recipes in this category path or below it
+    pub path: Option<String>,
+    /// Comma-separated tags the recipe must all have
+    pub tag: Option<String>,
+    /// Comma-separated ingredients the recipe must all use
+    pub ingredient: Option<String>,
+    /// Only recipes whose total time is known and at most this many minutes
+    pub max_time: Option<u32>,
+    /// Only recipes whose hands-on time is known and at most this many minutes
+    pub max_active_time: Option<u32>,
     /// Number of items per page (default: 20, max: 100)
     pub limit: Option<u32>,
     /// Number of items to skip (default: 0)
     pub offset: Option<u32>,
+    /// Only favorites (`true`) or only non-favorites (`false`) of `user`
+    pub favorite: Option<bool>,
+    /// User whose favorites `favorite` refers to (defaults to the shared `default` user)
+    pub user: Option<String>,
+    /// Also return recipes marked `draft: true` in their front matter
+    #[serde(default)]
+    pub include_drafts: bool,
 }
 
 /// Pagination info
@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use super::models::PaginationInfo;
+use crate::nutrition::Nutrients;
 
 /// Single recipe response
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,6 +16,9 @@ pub struct RecipeResponse {
     /// Directory path (relative to data-dir, no `recipes/` prefix)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub path: Option<String>,
+    /// In a read-only directory (a git submodule), so changes are rejected
+    #[serde(rename = "readOnly", default)]
+    pub read_only: bool,
     /// File name on disk (derived from recipe title)
     #[serde(rename = "fileName")]
     pub file_name: String,
@@ -23,6 +27,59 @@ pub struct RecipeResponse {
     /// Optional description
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// Ancestor categories of `path`, from the top level down (empty for root recipes)
+    #[serde(default)]
+    pub breadcrumb: Vec<BreadcrumbItem>,
+    /// Front matter keys outside the standard Cooklang metadata, passed through as-is
+    #[serde(rename = "customFields", default)]
+    pub custom_fields: BTreeMap<String, serde_json::Value>,
+    /// Total and hands-on time
+    #[serde(flatten)]
+    pub times: RecipeTimesInfo,
+    /// When and by whom the file last changed
+    #[serde(flatten)]
+    pub last_modified: LastModifiedInfo,
+}
+
+/// Total and hands-on time of a recipe in minutes, flattened into recipe responses
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecipeTimesInfo {
+    /// From the `time` front matter, or prep plus cook time (the timers standing in for
+    /// a missing cook time)
+    #[serde(rename = "totalTimeMinutes", skip_serializing_if = "Option::is_none")]
+    pub total_time_minutes: Option<u32>,
+    /// From the `active time` front matter, or the total time minus the timers
+    #[serde(rename = "activeTimeMinutes", skip_serializing_if = "Option::is_none")]
+    pub active_time_minutes: Option<u32>,
+}
+
+/// Last change to a recipe file, flattened into recipe responses
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LastModifiedInfo {
+    /// Last commit that changed the file (git storage, once committed)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revision: Option<String>,
+    /// Author of that commit
+    #[serde(rename = "lastModifiedBy", skip_serializing_if = "Option::is_none")]
+    pub last_modified_by: Option<String>,
+    /// Time of that commit, or the file's modification time without one (RFC 3339)
+    #[serde(rename = "lastModifiedAt", skip_serializing_if = "Option::is_none")]
+    pub last_modified_at: Option<String>,
+    /// Message of that commit
+    #[serde(rename = "lastCommitMessage", skip_serializing_if = "Option::is_none")]
+    pub last_commit_message: Option<String>,
+}
+
+/// One ancestor segment of a category path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreadcrumbItem {
+    /// Segment name (e.g. `meat`)
+    pub name: String,
+    /// Full category path up to this segment (e.g. `meals/meat`), usable with `/categories/{name}`
+    pub path: String,
+    /// Number of recipes in this category and its subcategories
+    #[serde(rename = "recipeCount")]
+    pub recipe_count: usize,
 }
 
 /// Recipe summary (without full content, for listings)
@@ -37,6 +94,30 @@ pub struct RecipeSummary {
     /// Directory path (relative to data-dir, no `recipes/` prefix)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub path: Option<String>,
+    /// In a read-only directory (a git submodule), so changes are rejected
+    #[serde(rename = "readOnly", default)]
+    pub read_only: bool,
+    /// Total and hands-on time
+    #[serde(flatten)]
+    pub times: RecipeTimesInfo,
+    /// When and by whom the file last changed, as of the last reindex
+    #[serde(flatten)]
+    pub last_modified: LastModifiedInfo,
+    /// Full recipe content, only when listed with `include_content=true`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// Description from the front matter, only when listed with `detail=full`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Tags, only when listed with `detail=full`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Servings as written (e.g. `4-6 people`), only when listed with `detail=full`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub servings: Option<String>,
+    /// Image from the front matter (`image`, `images`, ...), only when listed with `detail=full`
+    #[serde(rename = "imageUrl", skip_serializing_if = "Option::is_none")]
+    pub image_url: Option<String>,
 }
 
 /// Paginated list of recipes
@@ -46,10 +127,63 @@ pub struct RecipeListResponse {
     pub pagination: PaginationInfo,
 }
 
+/// Where a search matched, with the matched text marked by offsets
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHighlight {
+    /// `name`, `ingredient` or `step`
+    pub field: String,
+    /// The field value, or for steps an excerpt around the first match
+    pub snippet: String,
+    /// `[start, end)` character offsets of each match within `snippet`
+    pub matches: Vec<[usize; 2]>,
+}
+
+/// Recipe summary in search results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResultSummary {
+    /// Unique recipe ID
+    #[serde(rename = "recipeId")]
+    pub recipe_id: String,
+    /// Recipe name (derived from Cooklang YAML front matter)
+    #[serde(rename = "recipeName")]
+    pub recipe_name: String,
+    /// Directory path (relative to data-dir, no `recipes/` prefix)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Relevance, higher is better (only comparable within one search)
+    pub score: f32,
+    pub highlights: Vec<SearchHighlight>,
+}
+
+/// Paginated search results, best matches first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResponse {
+    pub recipes: Vec<SearchResultSummary>,
+    pub pagination: PaginationInfo,
+}
+
+/// Named collections served next to the default one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionListResponse {
+    pub collections: Vec<String>,
+}
+
 /// Category list response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CategoryListResponse {
-    pub categories: Vec<String>,
+    pub categories: Vec<CategoryItem>,
+}
+
+/// A category in the category list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryItem {
+    /// Full category path (e.g. `meals/meat`), usable with `/categories/{name}`
+    pub path: String,
+    /// Number of recipes in this category and its subcategories
+    pub count: usize,
+    /// Number of recipes directly in this category
+    #[serde(rename = "directCount")]
+    pub direct_count: usize,
 }
 
 /// Category recipes response (deprecated - for backwards compatibility during transition)
@@ -58,6 +192,734 @@ pub struct CategoryRecipesResponse {
     pub path: String,
     pub recipes: Vec<RecipeSummary>,
     pub count: usize,
+    #[serde(default)]
+    pub breadcrumb: Vec<BreadcrumbItem>,
+}
+
+/// Result of renaming a category
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryRenameResponse {
+    /// Old category path
+    pub from: String,
+    /// New category path
+    pub to: String,
+    /// Old recipe ID → new recipe ID for every moved recipe
+    pub renamed: BTreeMap<String, String>,
+}
+
+/// A recipe in the trash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashEntryResponse {
+    /// Trash entry ID (used for restore)
+    #[serde(rename = "trashId")]
+    pub trash_id: String,
+    /// Recipe name (derived from Cooklang YAML front matter)
+    #[serde(rename = "recipeName")]
+    pub recipe_name: String,
+    /// Original directory path (relative to data-dir, no `recipes/` prefix)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Original file name
+    #[serde(rename = "fileName")]
+    pub file_name: String,
+    /// When the recipe was deleted (RFC 3339)
+    #[serde(rename = "deletedAt")]
+    pub deleted_at: String,
+}
+
+/// Trash listing response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashListResponse {
+    pub entries: Vec<TrashEntryResponse>,
+    pub count: usize,
+}
+
+/// A recipe draft, kept off the main listing until published
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DraftResponse {
+    /// Draft ID, equal to the recipe ID once published
+    #[serde(rename = "draftId")]
+    pub draft_id: String,
+    /// Recipe name (derived from Cooklang YAML front matter)
+    #[serde(rename = "recipeName")]
+    pub recipe_name: String,
+    /// Directory path (relative to data-dir, no `recipes/` prefix)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// File name the recipe is published under
+    #[serde(rename = "fileName")]
+    pub file_name: String,
+    /// Draft content (omitted in listings)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// When the draft was last saved (RFC 3339)
+    #[serde(rename = "updatedAt")]
+    pub updated_at: String,
+}
+
+/// Draft listing response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DraftListResponse {
+    pub drafts: Vec<DraftResponse>,
+    pub count: usize,
+}
+
+/// A tagged version of a recipe
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecipeVersionResponse {
+    /// Version name
+    pub name: String,
+    /// Commit the version points at
+    #[serde(rename = "commitId")]
+    pub commit_id: String,
+    /// Tag message
+    pub message: String,
+    /// Who tagged the version, when recorded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// When the version was tagged (RFC 3339)
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    /// Recipe content at this version (omitted in listings)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+/// Recipe version listing response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecipeVersionListResponse {
+    pub versions: Vec<RecipeVersionResponse>,
+    pub count: usize,
+}
+
+/// Who last changed a line of a recipe
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlameLineResponse {
+    /// 1-based line number
+    pub line: usize,
+    pub content: String,
+    /// Last commit touching the line (absent for uncommitted changes)
+    #[serde(rename = "commitId", skip_serializing_if = "Option::is_none")]
+    pub commit_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Commit time (RFC 3339)
+    #[serde(rename = "committedAt", skip_serializing_if = "Option::is_none")]
+    pub committed_at: Option<String>,
+    /// First line of the commit message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+/// Per-line blame for a recipe
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlameResponse {
+    #[serde(rename = "recipeId")]
+    pub recipe_id: String,
+    pub lines: Vec<BlameLineResponse>,
+}
+
+/// Favorite state of a recipe for a user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FavoriteResponse {
+    #[serde(rename = "recipeId")]
+    pub recipe_id: String,
+    pub user: String,
+    pub favorite: bool,
+}
+
+/// A link giving read-only access to one recipe
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareResponse {
+    /// Share ID (used to revoke the link)
+    #[serde(rename = "shareId")]
+    pub share_id: String,
+    #[serde(rename = "recipeId")]
+    pub recipe_id: String,
+    /// Signed token granting access
+    pub token: String,
+    /// The recipe as JSON
+    pub url: String,
+    /// The recipe as a web page
+    #[serde(rename = "htmlUrl")]
+    pub html_url: String,
+    /// When the link was created (RFC 3339)
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    /// When the link stops working (RFC 3339)
+    #[serde(rename = "expiresAt")]
+    pub expires_at: String,
+    #[serde(rename = "createdBy", skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+}
+
+/// Active share links of a recipe
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareListResponse {
+    pub shares: Vec<ShareResponse>,
+}
+
+/// A webhook; the secret is only returned when it is registered
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookResponse {
+    #[serde(rename = "webhookId")]
+    pub webhook_id: String,
+    pub url: String,
+    /// Events sent, all of them when empty
+    pub events: Vec<String>,
+    /// Key the `X-Signature` HMAC is computed with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// When the webhook was registered (RFC 3339)
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "createdBy", skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+}
+
+/// Registered webhooks, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookListResponse {
+    pub webhooks: Vec<WebhookResponse>,
+}
+
+/// An event sent (or being sent) to a webhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryResponse {
+    #[serde(rename = "deliveryId")]
+    pub delivery_id: String,
+    #[serde(rename = "webhookId")]
+    pub webhook_id: String,
+    pub event: String,
+    /// `pending`, `delivered` or `failed` (out of attempts)
+    pub status: String,
+    /// The body that was signed and sent
+    pub payload: serde_json::Value,
+    pub attempts: Vec<DeliveryAttemptResponse>,
+    /// When the event happened (RFC 3339)
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+}
+
+/// One try at sending a delivery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryAttemptResponse {
+    /// When the try was made (RFC 3339)
+    pub at: String,
+    /// HTTP status of the response, if there was one
+    #[serde(rename = "statusCode", skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Webhook deliveries, newest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryListResponse {
+    pub deliveries: Vec<DeliveryResponse>,
+}
+
+/// An ingredient to buy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShoppingItemResponse {
+    /// Item ID within the list (used to check it off)
+    pub id: u32,
+    /// Ingredient name, lowercased (the canonical name if it has synonyms)
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    pub purchased: bool,
+    /// Names of the recipes needing the item
+    pub recipes: Vec<String>,
+}
+
+/// A shopping list with its items
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShoppingListResponse {
+    pub id: String,
+    pub name: String,
+    /// Names of the recipes the list was made from
+    pub recipes: Vec<String>,
+    pub items: Vec<ShoppingItemResponse>,
+    #[serde(rename = "itemCount")]
+    pub item_count: usize,
+    #[serde(rename = "purchasedCount")]
+    pub purchased_count: usize,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "createdBy", skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+}
+
+/// A shopping list without its items
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShoppingListSummary {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "itemCount")]
+    pub item_count: usize,
+    #[serde(rename = "purchasedCount")]
+    pub purchased_count: usize,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+}
+
+/// Every shopping list, newest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShoppingListsResponse {
+    #[serde(rename = "shoppingLists")]
+    pub shopping_lists: Vec<ShoppingListSummary>,
+}
+
+/// A dated cooking note
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CookingNoteResponse {
+    /// Position in date order (used to delete the note)
+    pub index: usize,
+    /// Day the note refers to (`YYYY-MM-DD`)
+    pub date: String,
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+}
+
+/// Rating and cooking notes of a recipe
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecipeNotesResponse {
+    #[serde(rename = "recipeId")]
+    pub recipe_id: String,
+    /// Star rating from 1 to 5, if rated
+    pub rating: Option<u8>,
+    pub notes: Vec<CookingNoteResponse>,
+}
+
+/// Estimated nutrition of a recipe
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NutritionResponse {
+    #[serde(rename = "recipeId")]
+    pub recipe_id: String,
+    /// Servings the total is divided by (1 when the recipe does not say)
+    pub servings: f64,
+    #[serde(rename = "perServing")]
+    pub per_serving: Nutrients,
+    pub total: Nutrients,
+    /// Ingredients not counted (unknown, or an amount that cannot be converted to grams)
+    pub unmatched: Vec<String>,
+}
+
+/// An ingredient and the recipes using it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngredientUsageResponse {
+    /// Ingredient name, lowercased (the canonical name if it has synonyms)
+    pub name: String,
+    /// Other names recipes use for the ingredient, merged into this entry
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub synonyms: Vec<String>,
+    /// Number of recipes using the ingredient
+    #[serde(rename = "recipeCount")]
+    pub recipe_count: usize,
+    pub recipes: Vec<RecipeSummary>,
+}
+
+/// Every distinct ingredient across the collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngredientListResponse {
+    pub ingredients: Vec<IngredientUsageResponse>,
+}
+
+/// An ingredient on hand
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PantryItemResponse {
+    /// Ingredient name, lowercased
+    pub name: String,
+    pub quantity: Option<f64>,
+    pub unit: Option<String>,
+}
+
+/// Everything in the pantry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PantryResponse {
+    pub items: Vec<PantryItemResponse>,
+}
+
+/// A recipe ranked by how many of its ingredients are on hand
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CookableRecipeResponse {
+    #[serde(rename = "recipeId")]
+    pub recipe_id: String,
+    #[serde(rename = "recipeName")]
+    pub recipe_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Share of the recipe's distinct ingredients that are on hand (0 to 1)
+    pub coverage: f64,
+    /// Ingredients on hand
+    #[serde(rename = "onHand")]
+    pub on_hand: Vec<String>,
+    /// Ingredients not in the pantry (or out of stock)
+    pub missing: Vec<String>,
+}
+
+/// Paginated recipes ranked by pantry coverage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CookableListResponse {
+    pub recipes: Vec<CookableRecipeResponse>,
+    pub pagination: PaginationInfo,
+}
+
+/// A recipe template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateResponse {
+    pub name: String,
+    pub title: Option<String>,
+    /// Shipped with the store rather than read from `templates/` in the data dir
+    pub builtin: bool,
+    pub content: String,
+}
+
+/// All recipe templates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateListResponse {
+    pub templates: Vec<TemplateResponse>,
+}
+
+/// A menu in the menu listing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MenuSummary {
+    #[serde(rename = "menuId")]
+    pub menu_id: String,
+    #[serde(rename = "menuName")]
+    pub menu_name: String,
+    pub path: String,
+}
+
+/// All menus
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MenuListResponse {
+    pub menus: Vec<MenuSummary>,
+}
+
+/// A menu with its recipe references resolved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MenuResponse {
+    #[serde(rename = "menuId")]
+    pub menu_id: String,
+    #[serde(rename = "menuName")]
+    pub menu_name: String,
+    pub path: String,
+    /// Front matter `servings`, as written
+    pub servings: Option<String>,
+    pub sections: Vec<MenuSectionResponse>,
+    /// IDs of all resolved recipes, once each in menu order (e.g. for a shopping list)
+    #[serde(rename = "recipeIds")]
+    pub recipe_ids: Vec<String>,
+}
+
+/// A section of a menu (e.g. a day)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MenuSectionResponse {
+    pub name: Option<String>,
+    pub items: Vec<MenuItemResponse>,
+}
+
+/// A recipe reference in a menu
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MenuItemResponse {
+    /// Meal label the reference is listed under (e.g. "Breakfast")
+    pub meal: Option<String>,
+    /// Referenced path as written in the menu
+    pub reference: String,
+    /// Quantity in braces (usually servings)
+    pub quantity: Option<f64>,
+    pub unit: Option<String>,
+    /// Resolved recipe, `null` when the reference matches no recipe
+    #[serde(rename = "recipeId")]
+    pub recipe_id: Option<String>,
+    #[serde(rename = "recipeName")]
+    pub recipe_name: Option<String>,
+}
+
+/// Resumable upload state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadResponse {
+    /// Upload ID (used to send chunks and by consumers of the finished file)
+    #[serde(rename = "uploadId")]
+    pub upload_id: String,
+    /// Original file name
+    #[serde(rename = "fileName")]
+    pub file_name: String,
+    /// Declared total size in bytes
+    pub size: u64,
+    /// Bytes received so far; the next chunk must start here
+    pub offset: u64,
+    /// Whether all bytes have been received
+    pub complete: bool,
+    /// When the upload was started (RFC 3339)
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+}
+
+/// Result of importing recipes from another app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportResponse {
+    /// Recipes created
+    pub imported: usize,
+    /// Recipes that could not be read, converted or created
+    pub failed: usize,
+    /// One entry per recipe in the archive, in archive order
+    pub recipes: Vec<ImportedRecipeResponse>,
+}
+
+/// Outcome of importing one recipe
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedRecipeResponse {
+    /// Recipe title, or its file name if it could not be read
+    pub name: String,
+    /// `imported` or `failed`
+    pub status: String,
+    #[serde(rename = "recipeId", skip_serializing_if = "Option::is_none")]
+    pub recipe_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fidelity: Option<FidelityResponse>,
+}
+
+/// A recipe converted to Cooklang, not saved yet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversionResponse {
+    /// Cooklang recipe with front matter, ready to create
+    pub content: String,
+    /// Category path, when the source names one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// File name the recipe would be created under
+    #[serde(rename = "fileName")]
+    pub file_name: String,
+    pub fidelity: FidelityResponse,
+    /// Times in the steps marked up as timers, for free-text conversion
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timers: Option<usize>,
+}
+
+/// How faithfully a recipe came through conversion to Cooklang
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FidelityResponse {
+    /// Share of ingredients marked up in the step using them (1 without ingredients)
+    pub score: f64,
+    pub ingredients: usize,
+    /// Ingredients marked up where a step mentions them
+    pub inline: usize,
+    /// Ingredients no step mentions, listed in a first step instead
+    pub listed: usize,
+    /// Fields of the original left out (e.g. `photo`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dropped: Vec<String>,
+}
+
+/// Result of a git maintenance (gc) run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitGcResponse {
+    /// Loose objects before packing
+    #[serde(rename = "looseObjectsBefore")]
+    pub loose_objects_before: usize,
+    /// Loose objects left afterwards (recent unreachable objects are kept)
+    #[serde(rename = "looseObjectsAfter")]
+    pub loose_objects_after: usize,
+    /// Pack files after the run
+    pub packs: usize,
+    /// How long the run took
+    #[serde(rename = "durationMs")]
+    pub duration_ms: u64,
+}
+
+/// How the mirror compares with the data directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MirrorReportResponse {
+    /// No files missing, different or extra, and no copies queued
+    pub consistent: bool,
+    /// Files the mirror lacks
+    pub missing: Vec<String>,
+    /// Files whose contents differ
+    pub different: Vec<String>,
+    /// Files only the mirror has
+    pub extra: Vec<String>,
+    /// Changes still queued for copying
+    pub pending: usize,
+    /// Copies that failed since startup
+    pub failures: u64,
+    /// Why the latest failed copy failed
+    #[serde(rename = "lastError", skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// What the recipe index holds and has been through since startup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheStatsResponse {
+    /// Recipes in the index
+    pub entries: usize,
+    /// Rough memory the index takes up, from the serialized size of its entries
+    #[serde(rename = "approximateBytes")]
+    pub approximate_bytes: usize,
+    /// When a recipe was last added, changed or removed
+    #[serde(rename = "changedAt")]
+    pub changed_at: String,
+    /// The latest full or incremental rebuild
+    #[serde(rename = "lastRebuild", skip_serializing_if = "Option::is_none")]
+    pub last_rebuild: Option<CacheRebuildInfo>,
+    /// Full rebuilds, including the one at startup
+    #[serde(rename = "fullRebuilds")]
+    pub full_rebuilds: u64,
+    /// Reindexes that only parsed changed files
+    #[serde(rename = "incrementalRebuilds")]
+    pub incremental_rebuilds: u64,
+    /// Times a recipe file failed to parse, counting repeats
+    #[serde(rename = "parseFailures")]
+    pub parse_failures: u64,
+    /// Files left out of the index because they failed to parse when last read
+    #[serde(rename = "failingFiles")]
+    pub failing_files: Vec<FailingFile>,
+    /// Recipes per category
+    pub categories: Vec<CategoryItem>,
+    /// Recipes outside any category
+    pub uncategorized: usize,
+}
+
+/// A rebuild of the recipe index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheRebuildInfo {
+    /// `full` or `incremental`
+    pub mode: String,
+    #[serde(rename = "finishedAt")]
+    pub finished_at: String,
+    #[serde(rename = "durationMs")]
+    pub duration_ms: u64,
+    /// Recipe files parsed
+    pub parsed: usize,
+}
+
+/// A recipe file that fails to parse
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailingFile {
+    pub path: String,
+    pub error: String,
+}
+
+/// Result of adding and removing tags across recipes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchTagResponse {
+    /// IDs of recipes whose tags changed
+    pub updated: Vec<String>,
+    /// IDs of recipes that already had the requested tags
+    pub unchanged: Vec<String>,
+    /// Requested IDs that match no recipe
+    #[serde(rename = "notFound")]
+    pub not_found: Vec<String>,
+}
+
+/// Result of a reindex
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReindexResponse {
+    /// `incremental` (only changed files) or `full`
+    pub mode: String,
+    /// Recipe files parsed
+    pub parsed: usize,
+    /// Recipes dropped because their files are gone
+    pub removed: usize,
+    /// Commit the index now reflects (git storage)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revision: Option<String>,
+    /// How long the reindex took
+    #[serde(rename = "durationMs")]
+    pub duration_ms: u64,
+}
+
+/// Recipe files whose name does not match their title
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MisalignedFilesResponse {
+    pub files: Vec<MisalignedFileResponse>,
+    pub total: usize,
+}
+
+/// A recipe file whose name is not the one its title generates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MisalignedFileResponse {
+    #[serde(rename = "recipeId")]
+    pub recipe_id: String,
+    #[serde(rename = "recipeName")]
+    pub recipe_name: String,
+    /// Directory path (relative to data-dir, no `recipes/` prefix)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Current file name
+    #[serde(rename = "fileName")]
+    pub file_name: String,
+    /// File name generated from the title
+    #[serde(rename = "expectedFileName")]
+    pub expected_file_name: String,
+}
+
+/// Result of renaming files to match their titles
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconcileResponse {
+    /// Whether this was a dry run (nothing renamed)
+    #[serde(rename = "dryRun")]
+    pub dry_run: bool,
+    /// Old recipe ID → new recipe ID for every renamed file
+    pub renamed: BTreeMap<String, String>,
+}
+
+/// Result of normalizing front matter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NormalizeResponse {
+    /// Whether this was a dry run (nothing written)
+    #[serde(rename = "dryRun")]
+    pub dry_run: bool,
+    /// IDs of recipes rewritten (or that would be, for a dry run)
+    pub updated: Vec<String>,
+    /// Number of recipes already in canonical form
+    pub unchanged: usize,
+    /// Recipes left alone because their front matter could not be normalized
+    pub failed: Vec<NormalizeFailure>,
+}
+
+/// A recipe whose front matter could not be normalized
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NormalizeFailure {
+    #[serde(rename = "recipeId")]
+    pub recipe_id: String,
+    pub error: String,
+}
+
+/// Result of reloading the units file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnitsReloadResponse {
+    /// Whether a units file is in use (`false`: cooklang's bundled units only)
+    #[serde(rename = "customUnits")]
+    pub custom_units: bool,
+    /// The full reindex that reparsed recipes with the new units
+    pub reindex: ReindexResponse,
+}
+
+/// Result of reloading the synonyms file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SynonymsReloadResponse {
+    /// Number of synonym groups in use (0: no synonyms file)
+    pub groups: usize,
+}
+
+/// Result of reloading the configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigReloadResponse {
+    /// Environment variables of the settings that changed and now apply
+    pub changed: Vec<String>,
+    /// Whether other settings changed too, which only apply after a restart
+    #[serde(rename = "restartRequired")]
+    pub restart_required: bool,
 }
 
 /// Status response
@@ -67,6 +929,9 @@ pub struct StatusResponse {
     pub version: String,
     pub recipe_count: usize,
     pub categories: usize,
+    /// Enabled experimental features (`FEATURES`)
+    #[serde(default)]
+    pub features: Vec<String>,
 }
 
 /// Error response
@@ -76,6 +941,30 @@ pub struct ErrorResponse {
     pub message: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub details: Option<HashMap<String, String>>,
+    /// ID of the failed request, as in the `X-Request-Id` response header
+    #[serde(rename = "requestId", skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+/// Error response in the RFC 7807 problem details format, for clients that accept
+/// `application/problem+json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProblemDetails {
+    /// `urn:cooklang-store:error:<code>`, with the code `ErrorResponse` sends as `error`
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    /// Reason phrase of the status (e.g. `Not Found`)
+    pub title: String,
+    pub status: u16,
+    /// What went wrong, as `ErrorResponse` sends in `message`
+    pub detail: String,
+    /// Path of the failed request
+    pub instance: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<HashMap<String, String>>,
+    /// ID of the failed request, as in the `X-Request-Id` response header
+    #[serde(rename = "requestId", skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 impl ErrorResponse {
@@ -84,6 +973,7 @@ impl ErrorResponse {
             error: error.into(),
             message: message.into(),
             details: None,
+            request_id: None,
         }
     }
 
@@ -91,4 +981,9 @@ impl ErrorResponse {
         self.details = Some(details);
         self
     }
+
+    pub fn with_request_id(mut self, request_id: Option<String>) -> Self {
+        self.request_id = request_id;
+        self
+    }
 }
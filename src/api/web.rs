@@ -0,0 +1,61 @@
+use axum::{
+    extract::Path,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use rust_embed::RustEmbed;
+
+/// Browser UI assets from `web/`, compiled into the binary
+#[derive(RustEmbed)]
+#[folder = "web/"]
+struct Assets;
+
+/// `GET /`: the web UI's page
+pub async fn index(headers: HeaderMap) -> Response {
+    serve("index.html", &headers)
+}
+
+/// `GET /ui/{file}`: a script or stylesheet of the web UI
+pub async fn asset(Path(file): Path<String>, headers: HeaderMap) -> Response {
+    serve(&file, &headers)
+}
+
+/// An embedded file, or `304 Not Modified` when the browser's copy is current
+///
+/// Assets change with the binary, so the content hash serves as the ETag and browsers
+/// revalidate on each load rather than caching for a fixed time.
+fn serve(file: &str, headers: &HeaderMap) -> Response {
+    let Some(asset) = Assets::get(file) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let etag = format!(
+        "\"{}\"",
+        asset
+            .metadata
+            .sha256_hash()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>()
+    );
+    let cached = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value == etag);
+
+    let mut response = if cached {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        (
+            [(header::CONTENT_TYPE, asset.metadata.mimetype().to_string())],
+            asset.data,
+        )
+            .into_response()
+    };
+    let headers = response.headers_mut();
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    if let Ok(etag) = HeaderValue::from_str(&etag) {
+        headers.insert(header::ETAG, etag);
+    }
+    response
+}
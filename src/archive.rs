@@ -0,0 +1,130 @@
+use anyhow::{ensure, Context, Result};
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use std::io::{Cursor, Read, Write};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Largest total size of the entries read from one archive, against zip bombs
+pub(crate) const MAX_UNPACKED_SIZE: u64 = 512 * 1024 * 1024;
+
+/// A file inside a zip archive
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZipEntry {
+    /// Path inside the archive, with `/` separators
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+/// Write a zip archive of `entries`, stored uncompressed
+///
+/// Meant for entries that are compressed already (gzipped JSON, images). Names must
+/// be unique; they are written as UTF-8.
+pub fn write_zip(entries: &[ZipEntry], now: DateTime<Utc>) -> Result<Vec<u8>> {
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Stored)
+        .last_modified_time(dos_datetime(now));
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for entry in entries {
+        zip.start_file(entry.name.as_str(), options)
+            .with_context(|| format!("Failed to add {} to the archive", entry.name))?;
+        zip.write_all(&entry.data)?;
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+/// Read every file of a zip archive, in directory order
+///
+/// Handles stored and deflated entries, which is what recipe apps write; directories
+/// are skipped. Encrypted archives are not supported.
+pub fn read_zip(bytes: &[u8]) -> Result<Vec<ZipEntry>> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).context("Not a zip archive")?;
+
+    let mut entries = Vec::with_capacity(archive.len());
+    let mut unpacked = 0u64;
+    for index in 0..archive.len() {
+        let mut file = archive
+            .by_index(index)
+            .with_context(|| format!("Unreadable zip entry #{}", index + 1))?;
+        let name = file.name().replace('\\', "/");
+        if file.is_dir() {
+            continue;
+        }
+
+        // The declared size can't be trusted, so the budget caps what is decompressed
+        let remaining = MAX_UNPACKED_SIZE - unpacked;
+        let mut data = Vec::with_capacity(file.size().min(remaining) as usize);
+        (&mut file)
+            .take(remaining + 1)
+            .read_to_end(&mut data)
+            .with_context(|| format!("Corrupt zip entry: {}", name))?;
+        unpacked += data.len() as u64;
+        ensure!(
+            unpacked <= MAX_UNPACKED_SIZE,
+            "Zip archive unpacks to more than {} MB",
+            MAX_UNPACKED_SIZE / 1024 / 1024
+        );
+        ensure!(
+            data.len() as u64 == file.size(),
+            "Size mismatch in zip entry: {}",
+            name
+        );
+        entries.push(ZipEntry { name, data });
+    }
+    Ok(entries)
+}
+
+/// MS-DOS time and date of a zip entry (two-second resolution, 1980 to 2107)
+fn dos_datetime(at: DateTime<Utc>) -> zip::DateTime {
+    zip::DateTime::from_date_and_time(
+        at.year().clamp(1980, 2107) as u16,
+        at.month() as u8,
+        at.day() as u8,
+        at.hour() as u8,
+        at.minute() as u8,
+        at.second() as u8,
+    )
+    // In range once the year is clamped
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zip_round_trip() -> Result<()> {
+        let entries = vec![
+            ZipEntry {
+                name: "Bread.paprikarecipe".to_string(),
+                data: b"bread".to_vec(),
+            },
+            ZipEntry {
+                name: "Crème brûlée.paprikarecipe".to_string(),
+                data: Vec::new(),
+            },
+        ];
+        let zip = write_zip(&entries, Utc::now())?;
+        assert_eq!(&zip[..4], b"PK\x03\x04");
+        assert_eq!(read_zip(&zip)?, entries);
+
+        // A deflated entry, as written by most zip tools, behind an archive comment
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        writer.add_directory("notes/", SimpleFileOptions::default())?;
+        writer.start_file(
+            "notes/a.txt",
+            SimpleFileOptions::default().compression_method(CompressionMethod::Deflated),
+        )?;
+        writer.write_all(b"hello hello hello")?;
+        writer.set_comment("abc");
+        let zip = writer.finish()?.into_inner();
+        let read = read_zip(&zip)?;
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].name, "notes/a.txt");
+        assert_eq!(read[0].data, b"hello hello hello");
+
+        assert!(read_zip(b"not a zip").is_err());
+        assert!(read_zip(&zip[..zip.len() / 2]).is_err());
+        Ok(())
+    }
+}
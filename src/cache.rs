@@ -1,7 +1,18 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use dashmap::DashMap;
-use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::Path;
+use std::sync::{Arc, RwLock};
 
-use crate::parser::ScalableRecipe;
+use crate::parser::{RecipeMetadata, ScalableRecipe};
+use crate::storage::{FileStat, LastModified};
+use crate::synonyms::Synonyms;
+
+/// Bumped whenever `CachedRecipe` changes shape, so old snapshots are ignored
+const SNAPSHOT_FORMAT: u32 = 6;
 
 /// Generate a recipe ID by hashing the git_path
 pub fn generate_recipe_id(git_path: &str) -> String {
@@ -13,23 +24,269 @@ pub fn generate_recipe_id(git_path: &str) -> String {
     format!("{:x}", result)[..12].to_string()
 }
 
+/// SHA-256 of recipe content, used to tell whether a file changed since it was indexed
+pub fn content_hash(content: &str) -> String {
+    use sha2::{Digest, Sha256};
+    format!("{:x}", Sha256::digest(content))
+}
+
 /// Represents a recipe in the cache
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CachedRecipe {
     pub recipe_id: String,
     pub git_path: String,
     pub name: String,
     pub description: Option<String>,
     pub category: Option<String>,
+    /// Front matter keys outside the standard Cooklang metadata
+    pub custom_fields: BTreeMap<String, serde_json::Value>,
+    /// Standard front matter fields (servings, tags, author, ...)
+    pub metadata: RecipeMetadata,
+    /// Total time in minutes from metadata or timers, if known
+    pub total_minutes: Option<u32>,
+    /// Hands-on part of the total time in minutes, if known
+    pub active_minutes: Option<u32>,
+    /// [`content_hash`] of the file this entry was parsed from
+    pub content_hash: String,
+    /// When the file last changed (and by whom, with git storage)
+    pub last_modified: Option<LastModified>,
+    /// Size and modification time of the file when it was indexed
+    pub stat: Option<FileStat>,
     pub recipe: ScalableRecipe,
 }
 
+impl CachedRecipe {
+    /// When the file last changed, as listings sorted by [`SortOrder::Modified`] see it
+    pub fn modified_at(&self) -> Option<DateTime<Utc>> {
+        self.last_modified
+            .as_ref()
+            .map(|last_modified| last_modified.at)
+            .or_else(|| self.stat.as_ref().map(|stat| stat.modified))
+    }
+
+    /// Whether the front matter marks the recipe as a work in progress (`draft: true`)
+    ///
+    /// Drafts stay indexed but are left out of listings and searches unless asked for.
+    pub fn is_draft(&self) -> bool {
+        self.custom_fields
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case("draft"))
+            .is_some_and(|(_, value)| {
+                custom_field_matches(value, "true") || custom_field_matches(value, "yes")
+            })
+    }
+
+    /// Whether the front matter opens the recipe to everyone (`visibility: public`)
+    ///
+    /// Drafts are never public, whatever their visibility.
+    pub fn is_public(&self) -> bool {
+        !self.is_draft()
+            && self
+                .custom_fields
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case("visibility"))
+                .is_some_and(|(_, value)| custom_field_matches(value, "public"))
+    }
+}
+
+/// On-disk copy of the index, written on shutdown and reused on the next startup
+#[derive(Serialize, Deserialize)]
+struct IndexSnapshot {
+    format: u32,
+    /// Parser output can change between releases, so snapshots are only reused by the same version
+    version: String,
+    /// Hash of the custom units file the recipes were parsed with, if any
+    #[serde(default)]
+    units: Option<String>,
+    recipes: Vec<CachedRecipe>,
+}
+
+/// Conditions a recipe must meet to be listed (all of them, empty matches everything)
+#[derive(Debug, Clone, Default)]
+pub struct RecipeFilter {
+    /// Category path; recipes in it or any of its subdirectories match
+    pub category: Option<String>,
+    /// Tags the recipe must all have (case-insensitive)
+    pub tags: Vec<String>,
+    /// Ingredients the recipe must all use (case-insensitive substring of an ingredient name)
+    pub ingredients: Vec<String>,
+    /// Names treated as the same ingredient when matching `ingredients`
+    pub synonyms: Arc<Synonyms>,
+    /// Custom front matter field → expected value
+    pub custom_fields: BTreeMap<String, String>,
+    /// Standard metadata field (see [`RecipeMetadata::KEYS`]) → expected value
+    pub metadata: BTreeMap<String, String>,
+    /// Longest total time in minutes; recipes without a known time are excluded
+    pub max_minutes: Option<u32>,
+    /// Longest hands-on time in minutes; recipes without a known active time are excluded
+    pub max_active_minutes: Option<u32>,
+}
+
+impl RecipeFilter {
+    /// Whether the filter has no conditions
+    pub fn is_empty(&self) -> bool {
+        self.category.is_none()
+            && self.tags.is_empty()
+            && self.ingredients.is_empty()
+            && self.custom_fields.is_empty()
+            && self.metadata.is_empty()
+            && self.max_minutes.is_none()
+            && self.max_active_minutes.is_none()
+    }
+
+    /// Whether a cached recipe meets every condition
+    ///
+    /// Conditions run cheapest first and stop at the first one that fails.
+    pub fn matches(&self, recipe: &CachedRecipe) -> bool {
+        self.category_matches(recipe)
+            && self.time_matches(recipe)
+            && self.tags_match(recipe)
+            && self.metadata_matches(recipe)
+            && self.custom_fields_match(recipe)
+            && self.ingredients_match(recipe)
+    }
+
+    fn category_matches(&self, recipe: &CachedRecipe) -> bool {
+        let Some(category) = &self.category else {
+            return true;
+        };
+        recipe.category.as_deref().is_some_and(|cat| {
+            cat == category
+                || cat
+                    .strip_prefix(category.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'))
+        })
+    }
+
+    fn time_matches(&self, recipe: &CachedRecipe) -> bool {
+        let within = |limit: Option<u32>, minutes: Option<u32>| match limit {
+            Some(max) => minutes.is_some_and(|minutes| minutes <= max),
+            None => true,
+        };
+        within(self.max_minutes, recipe.total_minutes)
+            && within(self.max_active_minutes, recipe.active_minutes)
+    }
+
+    fn tags_match(&self, recipe: &CachedRecipe) -> bool {
+        self.tags.iter().all(|tag| {
+            recipe
+                .metadata
+                .tags
+                .iter()
+                .any(|value| value.eq_ignore_ascii_case(tag.trim()))
+        })
+    }
+
+    fn metadata_matches(&self, recipe: &CachedRecipe) -> bool {
+        self.metadata.iter().all(|(key, expected)| {
+            recipe
+                .metadata
+                .values(key)
+                .iter()
+                .any(|value| value.eq_ignore_ascii_case(expected.trim()))
+        })
+    }
+
+    fn custom_fields_match(&self, recipe: &CachedRecipe) -> bool {
+        self.custom_fields.iter().all(|(key, expected)| {
+            recipe
+                .custom_fields
+                .get(key)
+                .map(|value| custom_field_matches(value, expected))
+                .unwrap_or(false)
+        })
+    }
+
+    fn ingredients_match(&self, recipe: &CachedRecipe) -> bool {
+        if self.ingredients.is_empty() {
+            return true;
+        }
+        let names = ingredient_names(recipe);
+        self.ingredients.iter().all(|wanted| {
+            self.synonyms
+                .names(wanted)
+                .iter()
+                .any(|wanted| names.iter().any(|name| name.contains(wanted)))
+        })
+    }
+}
+
+/// How many recipes a category holds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryCount {
+    pub path: String,
+    /// Recipes in the category and its subcategories
+    pub count: usize,
+    /// Recipes directly in the category
+    pub direct_count: usize,
+}
+
+/// Order listings are returned in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// By name ignoring case, then by git path
+    #[default]
+    Name,
+    /// By git path, which groups recipes by category
+    Path,
+    /// Most recently changed first, then by git path
+    Modified,
+}
+
+/// Git paths of the indexed recipes, kept sorted in every [`SortOrder`]
+#[derive(Default)]
+struct SortedPaths {
+    /// (lowercase name, git_path)
+    by_name: BTreeSet<(String, String)>,
+    by_path: BTreeSet<String>,
+    /// (newest first, git_path); recipes without a time come last
+    by_modified: BTreeSet<(Reverse<Option<DateTime<Utc>>>, String)>,
+}
+
+impl SortedPaths {
+    fn insert(&mut self, name: &str, modified: Option<DateTime<Utc>>, git_path: &str) {
+        self.by_name
+            .insert((name.to_lowercase(), git_path.to_string()));
+        self.by_path.insert(git_path.to_string());
+        self.by_modified
+            .insert((Reverse(modified), git_path.to_string()));
+    }
+
+    fn remove(&mut self, name: &str, modified: Option<DateTime<Utc>>, git_path: &str) {
+        self.by_name
+            .remove(&(name.to_lowercase(), git_path.to_string()));
+        self.by_path.remove(git_path);
+        self.by_modified
+            .remove(&(Reverse(modified), git_path.to_string()));
+    }
+
+    fn iter(&self, order: SortOrder) -> Box<dyn Iterator<Item = &String> + '_> {
+        match order {
+            SortOrder::Name => Box::new(self.by_name.iter().map(|(_, git_path)| git_path)),
+            SortOrder::Path => Box::new(self.by_path.iter()),
+            SortOrder::Modified => Box::new(self.by_modified.iter().map(|(_, git_path)| git_path)),
+        }
+    }
+}
+
 /// In-memory index for fast recipe lookups
 pub struct RecipeIndex {
     // Primary index: git_path -> Recipe
     recipes: Arc<DashMap<String, CachedRecipe>>,
     // Reverse index: recipe_id -> git_path
     id_to_path: Arc<DashMap<String, String>>,
+    // Inverted index: lowercase ingredient name -> git_paths of recipes using it
+    ingredients: Arc<DashMap<String, BTreeSet<String>>>,
+    // Secondary index: category path -> git_paths of the recipes directly in it
+    categories: Arc<DashMap<String, HashSet<String>>>,
+    // Serialized size of each recipe, measured once when it is inserted
+    sizes: Arc<DashMap<String, usize>>,
+    // Git paths in listing order, so listings are stable and can be paged without
+    // copying the index
+    sorted: Arc<RwLock<SortedPaths>>,
+    // When the index last changed, for `Last-Modified` headers
+    changed_at: Arc<RwLock<DateTime<Utc>>>,
 }
 
 impl RecipeIndex {
@@ -38,14 +295,47 @@ impl RecipeIndex {
         RecipeIndex {
             recipes: Arc::new(DashMap::new()),
             id_to_path: Arc::new(DashMap::new()),
+            ingredients: Arc::new(DashMap::new()),
+            categories: Arc::new(DashMap::new()),
+            sizes: Arc::new(DashMap::new()),
+            sorted: Arc::new(RwLock::new(SortedPaths::default())),
+            changed_at: Arc::new(RwLock::new(Utc::now())),
         }
     }
 
     /// Insert a recipe into the index
     pub fn insert(&self, git_path: String, recipe: CachedRecipe) {
         let recipe_id = recipe.recipe_id.clone();
-        self.recipes.insert(git_path.clone(), recipe);
+        let ingredients = ingredient_names(&recipe);
+        let (name, modified) = (recipe.name.clone(), recipe.modified_at());
+        let category = recipe.category.clone();
+        let size = serde_json::to_vec(&recipe).map_or(0, |json| json.len());
+        // Held while the recipe is stored, so listings never see it half-indexed
+        let mut sorted = self
+            .sorted
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(previous) = self.recipes.insert(git_path.clone(), recipe) {
+            self.unindex_ingredients(&git_path, &previous);
+            self.unindex_category(&git_path, &previous);
+            sorted.remove(&previous.name, previous.modified_at(), &git_path);
+        }
+        sorted.insert(&name, modified, &git_path);
+        if let Some(category) = category {
+            self.categories
+                .entry(category)
+                .or_default()
+                .insert(git_path.clone());
+        }
+        for name in ingredients {
+            self.ingredients
+                .entry(name)
+                .or_default()
+                .insert(git_path.clone());
+        }
+        self.sizes.insert(git_path.clone(), size);
         self.id_to_path.insert(recipe_id, git_path);
+        self.touch();
     }
 
     /// Get a recipe by git_path
@@ -58,73 +348,285 @@ impl RecipeIndex {
         self.id_to_path.get(recipe_id).map(|r| r.clone())
     }
 
+    /// Find the git path of an indexed recipe whose path matches apart from case
+    pub fn find_git_path_ignore_case(&self, git_path: &str) -> Option<String> {
+        if self.recipes.contains_key(git_path) {
+            return Some(git_path.to_string());
+        }
+
+        let lower = git_path.to_lowercase();
+        self.recipes
+            .iter()
+            .find(|entry| entry.key().to_lowercase() == lower)
+            .map(|entry| entry.key().clone())
+    }
+
     /// Remove a recipe from the index
     pub fn remove(&self, git_path: &str) -> Option<CachedRecipe> {
+        let mut sorted = self
+            .sorted
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
         if let Some((_, recipe)) = self.recipes.remove(git_path) {
             self.id_to_path.remove(&recipe.recipe_id);
+            self.sizes.remove(git_path);
+            self.unindex_ingredients(git_path, &recipe);
+            self.unindex_category(git_path, &recipe);
+            sorted.remove(&recipe.name, recipe.modified_at(), git_path);
+            self.touch();
             Some(recipe)
         } else {
             None
         }
     }
 
-    /// Get all recipes
-    pub fn get_all(&self) -> Vec<CachedRecipe> {
+    /// Drop a recipe's entries from the ingredient index
+    fn unindex_ingredients(&self, git_path: &str, recipe: &CachedRecipe) {
+        for name in ingredient_names(recipe) {
+            if let Some(mut paths) = self.ingredients.get_mut(&name) {
+                paths.remove(git_path);
+            }
+            self.ingredients
+                .remove_if(&name, |_, paths| paths.is_empty());
+        }
+    }
+
+    /// Drop a recipe from the category index
+    fn unindex_category(&self, git_path: &str, recipe: &CachedRecipe) {
+        let Some(category) = &recipe.category else {
+            return;
+        };
+        if let Some(mut paths) = self.categories.get_mut(category) {
+            paths.remove(git_path);
+        }
+        self.categories
+            .remove_if(category, |_, paths| paths.is_empty());
+    }
+
+    /// The recipes at `git_paths` that are indexed, by name
+    fn by_name<'a>(&self, git_paths: impl IntoIterator<Item = &'a String>) -> Vec<CachedRecipe> {
+        let mut recipes: Vec<CachedRecipe> = git_paths
+            .into_iter()
+            .filter_map(|git_path| self.get(git_path))
+            .collect();
+        recipes.sort_by_cached_key(|recipe| (recipe.name.to_lowercase(), recipe.git_path.clone()));
+        recipes
+    }
+
+    /// Every distinct ingredient (lowercased) with the git paths of the recipes using it,
+    /// sorted by name
+    pub fn ingredient_catalog(&self) -> Vec<(String, Vec<String>)> {
+        let mut catalog: Vec<(String, Vec<String>)> = self
+            .ingredients
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().iter().cloned().collect()))
+            .collect();
+        catalog.sort_by(|a, b| a.0.cmp(&b.0));
+        catalog
+    }
+
+    /// Size and modification time of every indexed file when it was indexed, by git path
+    pub fn file_stats(&self) -> HashMap<String, Option<FileStat>> {
         self.recipes
             .iter()
-            .map(|entry| entry.value().clone())
+            .map(|entry| (entry.key().clone(), entry.value().stat.clone()))
             .collect()
     }
 
-    /// Search recipes by name (case-insensitive substring match)
+    /// Rough number of bytes the index takes up: the serialized size of the recipes (as
+    /// measured when they were inserted) plus the keys and paths of the lookup tables
+    pub fn approximate_size(&self) -> usize {
+        let recipes: usize = self
+            .sizes
+            .iter()
+            .map(|entry| entry.key().len() + entry.value())
+            .sum();
+        let ids: usize = self
+            .id_to_path
+            .iter()
+            .map(|entry| entry.key().len() + entry.value().len())
+            .sum();
+        let ingredients: usize = self
+            .ingredients
+            .iter()
+            .map(|entry| entry.key().len() + entry.value().iter().map(String::len).sum::<usize>())
+            .sum();
+        let categories: usize = self
+            .categories
+            .iter()
+            .map(|entry| entry.key().len() + entry.value().iter().map(String::len).sum::<usize>())
+            .sum();
+        recipes + ids + ingredients + categories
+    }
+
+    /// Get all recipes, by name
+    pub fn get_all(&self) -> Vec<CachedRecipe> {
+        self.sorted(SortOrder::Name, |_| true)
+    }
+
+    /// Recipes meeting `keep`, in the given order
+    pub fn sorted<F>(&self, order: SortOrder, keep: F) -> Vec<CachedRecipe>
+    where
+        F: Fn(&CachedRecipe) -> bool,
+    {
+        let mut recipes = Vec::new();
+        self.visit(order, |recipe| {
+            if keep(recipe) {
+                recipes.push(recipe.clone());
+            }
+        });
+        recipes
+    }
+
+    /// Recipes meeting `keep` from `offset` on, at most `limit` of them, in the given
+    /// order, and how many meet it in all
+    ///
+    /// Only the recipes on the page are copied out of the index.
+    pub fn page<F>(
+        &self,
+        order: SortOrder,
+        offset: usize,
+        limit: usize,
+        keep: F,
+    ) -> (Vec<CachedRecipe>, usize)
+    where
+        F: Fn(&CachedRecipe) -> bool,
+    {
+        let mut page = Vec::new();
+        let mut total = 0;
+        self.visit(order, |recipe| {
+            if !keep(recipe) {
+                return;
+            }
+            if total >= offset && page.len() < limit {
+                page.push(recipe.clone());
+            }
+            total += 1;
+        });
+        (page, total)
+    }
+
+    /// Call `visit` on every recipe in the given order, without copying them
+    fn visit<F>(&self, order: SortOrder, mut visit: F)
+    where
+        F: FnMut(&CachedRecipe),
+    {
+        let sorted = self
+            .sorted
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        for git_path in sorted.iter(order) {
+            if let Some(recipe) = self.recipes.get(git_path) {
+                visit(&recipe);
+            }
+        }
+    }
+
+    /// Search recipes by name (case-insensitive substring match), by name
     pub fn search_by_name(&self, query: &str) -> Vec<CachedRecipe> {
         let query_lower = query.to_lowercase();
-        self.recipes
-            .iter()
-            .filter(|entry| entry.value().name.to_lowercase().contains(&query_lower))
-            .map(|entry| entry.value().clone())
-            .collect()
+        self.sorted(SortOrder::Name, |recipe| {
+            recipe.name.to_lowercase().contains(&query_lower)
+        })
     }
 
-    /// Get recipes by category
+    /// Get recipes by category, by name
     pub fn get_by_category(&self, category: &str) -> Vec<CachedRecipe> {
-        self.recipes
-            .iter()
-            .filter(|entry| entry.value().category.as_deref() == Some(category))
-            .map(|entry| entry.value().clone())
-            .collect()
+        let git_paths: Vec<String> = self
+            .categories
+            .get(category)
+            .map(|paths| paths.iter().cloned().collect())
+            .unwrap_or_default();
+        self.by_name(&git_paths)
     }
 
-    /// Get all unique categories
-    pub fn get_categories(&self) -> Vec<String> {
-        let mut categories = std::collections::HashSet::new();
-        for entry in self.recipes.iter() {
-            if let Some(cat) = &entry.value().category {
-                categories.insert(cat.clone());
+    /// Get recipes by category, ignoring case differences in the path, by name
+    pub fn get_by_category_ignore_case(&self, category: &str) -> Vec<CachedRecipe> {
+        let category_lower = category.to_lowercase();
+        let mut git_paths = Vec::new();
+        for entry in self.categories.iter() {
+            if entry.key().to_lowercase() == category_lower {
+                git_paths.extend(entry.value().iter().cloned());
             }
         }
-        let mut cats: Vec<_> = categories.into_iter().collect();
+        self.by_name(&git_paths)
+    }
+
+    /// Get all unique categories
+    pub fn get_categories(&self) -> Vec<String> {
+        let mut cats: Vec<String> = self
+            .categories
+            .iter()
+            .map(|entry| entry.key().clone())
+            .collect();
         cats.sort();
         cats
     }
 
-    /// Filter recipes by ingredient name
-    pub fn filter_by_ingredient(&self, ingredient_name: &str) -> Vec<CachedRecipe> {
-        let ingredient_lower = ingredient_name.to_lowercase();
-        self.recipes
+    /// Every category holding recipes, sorted by path, with its recipe counts
+    pub fn category_counts(&self) -> Vec<CategoryCount> {
+        let direct: BTreeMap<String, usize> = self
+            .categories
             .iter()
-            .filter(|entry| {
-                entry
-                    .value()
-                    .recipe
-                    .ingredients
-                    .iter()
-                    .any(|ing| ing.name.to_lowercase().contains(&ingredient_lower))
+            .map(|entry| (entry.key().clone(), entry.value().len()))
+            .collect();
+
+        direct
+            .iter()
+            .map(|(path, &direct_count)| {
+                // Subcategories sort together, from `path/` on
+                let prefix = format!("{}/", path);
+                let nested: usize = direct
+                    .range(prefix.clone()..)
+                    .take_while(|(cat, _)| cat.starts_with(&prefix))
+                    .map(|(_, count)| count)
+                    .sum();
+                CategoryCount {
+                    path: path.clone(),
+                    count: direct_count + nested,
+                    direct_count,
+                }
             })
-            .map(|entry| entry.value().clone())
             .collect()
     }
 
+    /// Count recipes in a category, including those in nested subcategories
+    pub fn count_in_category_tree(&self, category: &str) -> usize {
+        let prefix = format!("{}/", category);
+        self.categories
+            .iter()
+            .filter(|entry| entry.key() == category || entry.key().starts_with(&prefix))
+            .map(|entry| entry.value().len())
+            .sum()
+    }
+
+    /// Filter recipes whose custom fields match every `key = value` filter
+    ///
+    /// Values compare case-insensitively; list values match if any element matches.
+    pub fn filter_by_custom_fields(&self, filters: &BTreeMap<String, String>) -> Vec<CachedRecipe> {
+        self.filter(&RecipeFilter {
+            custom_fields: filters.clone(),
+            ..Default::default()
+        })
+    }
+
+    /// Recipes meeting every condition of a filter, by name
+    pub fn filter(&self, filter: &RecipeFilter) -> Vec<CachedRecipe> {
+        self.sorted(SortOrder::Name, |recipe| filter.matches(recipe))
+    }
+
+    /// Filter recipes by ingredient name
+    pub fn filter_by_ingredient(&self, ingredient_name: &str) -> Vec<CachedRecipe> {
+        let ingredient_lower = ingredient_name.to_lowercase();
+        self.sorted(SortOrder::Name, |recipe| {
+            recipe
+                .recipe
+                .ingredients
+                .iter()
+                .any(|ing| ing.name.to_lowercase().contains(&ingredient_lower))
+        })
+    }
+
     /// Get the number of recipes in the index
     pub fn len(&self) -> usize {
         self.recipes.len()
@@ -137,8 +639,90 @@ impl RecipeIndex {
 
     /// Clear all recipes from the index
     pub fn clear(&self) {
+        let mut sorted = self
+            .sorted
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
         self.recipes.clear();
         self.id_to_path.clear();
+        self.ingredients.clear();
+        self.categories.clear();
+        self.sizes.clear();
+        *sorted = SortedPaths::default();
+        self.touch();
+    }
+
+    /// When a recipe was last added, changed or removed
+    pub fn changed_at(&self) -> DateTime<Utc> {
+        *self
+            .changed_at
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Mark the index as changed now, also for state outside it that listings depend on
+    ///
+    /// Called after the change is made, so a reader that sees the new time sees the change.
+    pub fn touch(&self) {
+        *self
+            .changed_at
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Utc::now();
+    }
+
+    /// Write the index to `path` (via a temporary file, so a crash never leaves half a snapshot)
+    ///
+    /// `units` is the hash of the custom units file the recipes were parsed with.
+    pub fn save_snapshot(&self, path: &Path, units: Option<&str>) -> Result<()> {
+        let snapshot = IndexSnapshot {
+            format: SNAPSHOT_FORMAT,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            units: units.map(str::to_string),
+            recipes: self.get_all(),
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).context("Failed to create snapshot directory")?;
+        }
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec(&snapshot)?)
+            .context("Failed to write index snapshot")?;
+        std::fs::rename(&tmp_path, path).context("Failed to replace index snapshot")?;
+
+        Ok(())
+    }
+
+    /// Read a snapshot written by [`save_snapshot`](Self::save_snapshot), keyed by git_path
+    ///
+    /// Returns `None` if there is no snapshot, it was written by another version or with
+    /// other custom units. Entries are not validated; callers compare `content_hash` with
+    /// the current files.
+    pub fn load_snapshot(
+        path: &Path,
+        units: Option<&str>,
+    ) -> Result<Option<HashMap<String, CachedRecipe>>> {
+        let data = match std::fs::read(path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).context("Failed to read index snapshot"),
+        };
+
+        let snapshot: IndexSnapshot =
+            serde_json::from_slice(&data).context("Invalid index snapshot")?;
+        if snapshot.format != SNAPSHOT_FORMAT
+            || snapshot.version != env!("CARGO_PKG_VERSION")
+            || snapshot.units.as_deref() != units
+        {
+            return Ok(None);
+        }
+
+        Ok(Some(
+            snapshot
+                .recipes
+                .into_iter()
+                .map(|recipe| (recipe.git_path.clone(), recipe))
+                .collect(),
+        ))
     }
 }
 
@@ -153,10 +737,39 @@ impl Clone for RecipeIndex {
         RecipeIndex {
             recipes: Arc::clone(&self.recipes),
             id_to_path: Arc::clone(&self.id_to_path),
+            ingredients: Arc::clone(&self.ingredients),
+            categories: Arc::clone(&self.categories),
+            sizes: Arc::clone(&self.sizes),
+            sorted: Arc::clone(&self.sorted),
+            changed_at: Arc::clone(&self.changed_at),
         }
     }
 }
 
+/// Distinct ingredient names of a recipe, lowercased and trimmed
+pub fn ingredient_names(recipe: &CachedRecipe) -> BTreeSet<String> {
+    recipe
+        .recipe
+        .ingredients
+        .iter()
+        .map(|ingredient| ingredient.name.trim().to_lowercase())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Whether a custom field value matches a filter value from a query string
+fn custom_field_matches(value: &serde_json::Value, expected: &str) -> bool {
+    match value {
+        serde_json::Value::String(s) => s.eq_ignore_ascii_case(expected),
+        serde_json::Value::Number(n) => n.to_string() == expected,
+        serde_json::Value::Bool(b) => b.to_string().eq_ignore_ascii_case(expected),
+        serde_json::Value::Array(items) => items
+            .iter()
+            .any(|item| custom_field_matches(item, expected)),
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -185,6 +798,13 @@ mod tests {
             name: "Test Recipe".to_string(),
             description: None,
             category: Some("desserts".to_string()),
+            custom_fields: BTreeMap::new(),
+            metadata: RecipeMetadata::default(),
+            total_minutes: None,
+            active_minutes: None,
+            content_hash: String::new(),
+            last_modified: None,
+            stat: None,
             recipe: create_test_recipe("Test Recipe"),
         };
 
@@ -214,6 +834,13 @@ mod tests {
                 name: name.to_string(),
                 description: None,
                 category: None,
+                custom_fields: BTreeMap::new(),
+                metadata: RecipeMetadata::default(),
+                total_minutes: None,
+                active_minutes: None,
+                content_hash: String::new(),
+                last_modified: None,
+                stat: None,
                 recipe: create_test_recipe(name),
             };
             index.insert(git_path, recipe);
@@ -245,16 +872,101 @@ mod tests {
                 name: name.to_string(),
                 description: None,
                 category: category.map(|s| s.to_string()),
+                custom_fields: BTreeMap::new(),
+                metadata: RecipeMetadata::default(),
+                total_minutes: None,
+                active_minutes: None,
+                content_hash: String::new(),
+                last_modified: None,
+                stat: None,
                 recipe: create_test_recipe(name),
             };
             index.insert(git_path, recipe);
         }
 
         let desserts = index.get_by_category("desserts");
-        assert_eq!(desserts.len(), 2);
+        let names: Vec<&str> = desserts.iter().map(|recipe| recipe.name.as_str()).collect();
+        assert_eq!(names, vec!["Brownie", "Cake"]);
 
         let mains = index.get_by_category("mains");
         assert_eq!(mains.len(), 1);
+        assert_eq!(index.get_by_category_ignore_case("MAINS").len(), 1);
+
+        // Re-inserting under another category moves the recipe
+        let mut pasta = mains[0].clone();
+        pasta.category = Some("mains/italian".to_string());
+        index.insert(pasta.git_path.clone(), pasta);
+        assert!(index.get_by_category("mains").is_empty());
+        assert_eq!(index.get_categories(), vec!["desserts", "mains/italian"]);
+        assert_eq!(index.count_in_category_tree("mains"), 1);
+
+        // Removing the last recipe of a category drops the category
+        index.remove("recipes/mains/pasta.cook");
+        assert_eq!(index.get_categories(), vec!["desserts"]);
+        assert_eq!(
+            index.category_counts(),
+            vec![CategoryCount {
+                path: "desserts".to_string(),
+                count: 2,
+                direct_count: 2,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_page() {
+        let index = RecipeIndex::new();
+        let cached = |path: &str, name: &str| CachedRecipe {
+            recipe_id: generate_recipe_id(path),
+            git_path: path.to_string(),
+            name: name.to_string(),
+            description: None,
+            category: None,
+            custom_fields: BTreeMap::new(),
+            metadata: RecipeMetadata::default(),
+            total_minutes: None,
+            active_minutes: None,
+            content_hash: String::new(),
+            last_modified: None,
+            stat: None,
+            recipe: create_test_recipe(name),
+        };
+        for (path, name) in [
+            ("recipes/mains/pasta.cook", "Pasta"),
+            ("recipes/desserts/cake.cook", "cake"),
+            ("recipes/desserts/brownie.cook", "Brownie"),
+            ("recipes/mains/curry.cook", "Apple Curry"),
+        ] {
+            index.insert(path.to_string(), cached(path, name));
+        }
+
+        let names = |page: Vec<CachedRecipe>| -> Vec<String> {
+            page.into_iter().map(|recipe| recipe.name).collect()
+        };
+        let (page, total) = index.page(SortOrder::Path, 1, 2, |_| true);
+        assert_eq!(names(page), vec!["cake", "Apple Curry"]);
+        assert_eq!(total, 4);
+        // Names compare ignoring case
+        let (page, _) = index.page(SortOrder::Name, 1, 2, |_| true);
+        assert_eq!(names(page), vec!["Brownie", "cake"]);
+
+        // Totals count every match, not just the page
+        let (page, total) =
+            index.page(SortOrder::Name, 0, 1, |recipe| recipe.name != "Apple Curry");
+        assert_eq!(names(page), vec!["Brownie"]);
+        assert_eq!(total, 3);
+
+        // A renamed recipe moves to its new place
+        let path = "recipes/desserts/brownie.cook";
+        index.insert(path.to_string(), cached(path, "Zebra Cake"));
+        index.remove("recipes/desserts/cake.cook");
+        assert_eq!(
+            names(index.get_all()),
+            vec!["Apple Curry", "Pasta", "Zebra Cake"]
+        );
+        let (page, total) = index.page(SortOrder::Path, 5, 10, |_| true);
+        assert!(page.is_empty());
+        assert_eq!(total, 3);
     }
 
     #[test]
@@ -268,14 +980,23 @@ mod tests {
             name: "Test".to_string(),
             description: None,
             category: None,
+            custom_fields: BTreeMap::new(),
+            metadata: RecipeMetadata::default(),
+            total_minutes: None,
+            active_minutes: None,
+            content_hash: String::new(),
+            last_modified: None,
+            stat: None,
             recipe: create_test_recipe("Test"),
         };
 
         index.insert(git_path.clone(), recipe);
         assert_eq!(index.len(), 1);
+        assert!(index.approximate_size() > git_path.len());
 
         index.remove(&git_path);
         assert_eq!(index.len(), 0);
+        assert_eq!(index.approximate_size(), 0);
         // Verify reverse index is also cleaned up
         assert!(index.get_git_path(&recipe_id).is_none());
     }
@@ -291,6 +1012,13 @@ mod tests {
             name: "Test".to_string(),
             description: None,
             category: None,
+            custom_fields: BTreeMap::new(),
+            metadata: RecipeMetadata::default(),
+            total_minutes: None,
+            active_minutes: None,
+            content_hash: String::new(),
+            last_modified: None,
+            stat: None,
             recipe: create_test_recipe("Test"),
         };
 
@@ -318,6 +1046,13 @@ mod tests {
                 name: name.to_string(),
                 description: None,
                 category: category.map(|s| s.to_string()),
+                custom_fields: BTreeMap::new(),
+                metadata: RecipeMetadata::default(),
+                total_minutes: None,
+                active_minutes: None,
+                content_hash: String::new(),
+                last_modified: None,
+                stat: None,
                 recipe: create_test_recipe(name),
             };
             index.insert(git_path, recipe);
@@ -329,4 +1064,279 @@ mod tests {
         assert!(categories.contains(&"mains".to_string()));
         assert!(categories.contains(&"appetizers".to_string()));
     }
+
+    #[test]
+    fn test_filter_by_custom_fields() {
+        let index = RecipeIndex::new();
+        let fields = [
+            ("recipes/buns.cook", "Buns", serde_json::json!("Easter")),
+            (
+                "recipes/eggs.cook",
+                "Eggs",
+                serde_json::json!(["easter", "spring"]),
+            ),
+            ("recipes/pie.cook", "Pie", serde_json::json!("christmas")),
+        ];
+
+        for (path, name, holiday) in fields {
+            let mut custom_fields = BTreeMap::new();
+            custom_fields.insert("holiday".to_string(), holiday);
+            index.insert(
+                path.to_string(),
+                CachedRecipe {
+                    recipe_id: generate_recipe_id(path),
+                    git_path: path.to_string(),
+                    name: name.to_string(),
+                    description: None,
+                    category: None,
+                    custom_fields,
+                    metadata: RecipeMetadata::default(),
+                    total_minutes: None,
+                    active_minutes: None,
+                    content_hash: String::new(),
+                    last_modified: None,
+                    stat: None,
+                    recipe: create_test_recipe(name),
+                },
+            );
+        }
+
+        let mut filters = BTreeMap::new();
+        filters.insert("holiday".to_string(), "easter".to_string());
+        assert_eq!(index.filter_by_custom_fields(&filters).len(), 2);
+
+        filters.insert("missing".to_string(), "x".to_string());
+        assert!(index.filter_by_custom_fields(&filters).is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() -> Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join("cache/index.json");
+        assert!(RecipeIndex::load_snapshot(&path, None)?.is_none());
+
+        let index = RecipeIndex::new();
+        let git_path = "recipes/test.cook".to_string();
+        index.insert(
+            git_path.clone(),
+            CachedRecipe {
+                recipe_id: generate_recipe_id(&git_path),
+                git_path: git_path.clone(),
+                name: "Test Recipe".to_string(),
+                description: None,
+                category: None,
+                custom_fields: BTreeMap::new(),
+                metadata: RecipeMetadata::default(),
+                total_minutes: None,
+                active_minutes: None,
+                content_hash: content_hash("# Test Recipe"),
+                last_modified: None,
+                stat: None,
+                recipe: create_test_recipe("Test Recipe"),
+            },
+        );
+        index.save_snapshot(&path, None)?;
+
+        let loaded = RecipeIndex::load_snapshot(&path, None)?.unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[&git_path].name, "Test Recipe");
+        assert_eq!(
+            loaded[&git_path].content_hash,
+            content_hash("# Test Recipe")
+        );
+
+        // Snapshots parsed with other units are ignored
+        assert!(RecipeIndex::load_snapshot(&path, Some("units-hash"))?.is_none());
+
+        // Snapshots from another release are ignored rather than trusted
+        let mut raw: serde_json::Value = serde_json::from_slice(&std::fs::read(&path)?)?;
+        raw["version"] = serde_json::json!("0.0.0-other");
+        std::fs::write(&path, serde_json::to_vec(&raw)?)?;
+        assert!(RecipeIndex::load_snapshot(&path, None)?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ingredient_catalog_follows_insert_and_remove() {
+        let parser = CooklangParser::new(
+            crate::parser::Extensions::all(),
+            crate::parser::Converter::default(),
+        );
+        let index = RecipeIndex::new();
+        let insert = |git_path: &str, content: &str| {
+            let recipe = parser
+                .parse(content, git_path)
+                .into_result()
+                .map(|(recipe, _)| recipe)
+                .unwrap();
+            index.insert(
+                git_path.to_string(),
+                CachedRecipe {
+                    recipe_id: generate_recipe_id(git_path),
+                    git_path: git_path.to_string(),
+                    name: git_path.to_string(),
+                    description: None,
+                    category: None,
+                    custom_fields: BTreeMap::new(),
+                    metadata: RecipeMetadata::default(),
+                    total_minutes: None,
+                    active_minutes: None,
+                    content_hash: content_hash(content),
+                    last_modified: None,
+                    stat: None,
+                    recipe,
+                },
+            );
+        };
+
+        insert(
+            "recipes/cake.cook",
+            "Mix @flour{200%g}, @Eggs{2} and more @flour{}.",
+        );
+        insert(
+            "recipes/bread.cook",
+            "Knead @flour{500%g} with @water{300%ml}.",
+        );
+        assert_eq!(
+            index.ingredient_catalog(),
+            vec![
+                ("eggs".to_string(), vec!["recipes/cake.cook".to_string()]),
+                (
+                    "flour".to_string(),
+                    vec![
+                        "recipes/bread.cook".to_string(),
+                        "recipes/cake.cook".to_string()
+                    ]
+                ),
+                ("water".to_string(), vec!["recipes/bread.cook".to_string()]),
+            ]
+        );
+
+        // Replacing a recipe drops ingredients it no longer uses
+        insert(
+            "recipes/bread.cook",
+            "Knead @flour{500%g} with @milk{300%ml}.",
+        );
+        index.remove("recipes/cake.cook");
+        let names: Vec<String> = index
+            .ingredient_catalog()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, vec!["flour", "milk"]);
+
+        index.clear();
+        assert!(index.ingredient_catalog().is_empty());
+    }
+
+    #[test]
+    fn test_filter_combines_conditions() {
+        let parser = CooklangParser::new(
+            crate::parser::Extensions::all(),
+            crate::parser::Converter::default(),
+        );
+        let index = RecipeIndex::new();
+        let insert = |git_path: &str, category: &str, tags: &[&str], minutes, content: &str| {
+            let recipe = parser
+                .parse(content, git_path)
+                .into_result()
+                .map(|(recipe, _)| recipe)
+                .unwrap();
+            index.insert(
+                git_path.to_string(),
+                CachedRecipe {
+                    recipe_id: generate_recipe_id(git_path),
+                    git_path: git_path.to_string(),
+                    name: git_path.to_string(),
+                    description: None,
+                    category: Some(category.to_string()),
+                    custom_fields: BTreeMap::new(),
+                    metadata: RecipeMetadata {
+                        tags: tags.iter().map(|tag| tag.to_string()).collect(),
+                        ..Default::default()
+                    },
+                    total_minutes: minutes,
+                    active_minutes: None,
+                    content_hash: content_hash(content),
+                    last_modified: None,
+                    stat: None,
+                    recipe,
+                },
+            );
+        };
+
+        insert(
+            "recipes/mains/soup/tomato.cook",
+            "mains/soup",
+            &["vegan", "quick"],
+            Some(20),
+            "Simmer @tomatoes{4} with @garlic{2%cloves}.",
+        );
+        insert(
+            "recipes/mains/stew.cook",
+            "mains",
+            &["Vegan"],
+            Some(90),
+            "Braise @carrots{3} and @garlic{1%clove}.",
+        );
+        insert(
+            "recipes/mainsfish/cod.cook",
+            "mainsfish",
+            &["quick"],
+            Some(15),
+            "Bake @cod{1%fillet} with @garlic{}.",
+        );
+
+        let paths = |filter: RecipeFilter| {
+            let mut paths: Vec<String> = index
+                .filter(&filter)
+                .into_iter()
+                .map(|recipe| recipe.git_path)
+                .collect();
+            paths.sort();
+            paths
+        };
+
+        // A category includes its subdirectories but not siblings sharing a prefix
+        let mains = RecipeFilter {
+            category: Some("mains".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            paths(mains.clone()),
+            vec!["recipes/mains/soup/tomato.cook", "recipes/mains/stew.cook"]
+        );
+
+        let vegan_garlic = RecipeFilter {
+            tags: vec!["VEGAN".to_string()],
+            ingredients: vec!["garl".to_string()],
+            ..mains.clone()
+        };
+        assert_eq!(paths(vegan_garlic.clone()).len(), 2);
+
+        let quick = RecipeFilter {
+            max_minutes: Some(30),
+            ..vegan_garlic
+        };
+        assert_eq!(paths(quick), vec!["recipes/mains/soup/tomato.cook"]);
+
+        let missing_ingredient = RecipeFilter {
+            ingredients: vec!["garlic".to_string(), "cod".to_string()],
+            ..mains.clone()
+        };
+        assert!(paths(missing_ingredient).is_empty());
+
+        // A synonym matches recipes naming the ingredient any other way
+        let ajo = RecipeFilter {
+            ingredients: vec!["Ajo".to_string()],
+            ..mains
+        };
+        assert!(paths(ajo.clone()).is_empty());
+        let ajo = RecipeFilter {
+            synonyms: Arc::new(Synonyms::parse("garlic = ajo").unwrap()),
+            ..ajo
+        };
+        assert_eq!(paths(ajo).len(), 2);
+    }
 }
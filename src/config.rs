@@ -0,0 +1,673 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::events::EventKind;
+use crate::git::{CommitSigning, SigningFormat, DEFAULT_AUTHOR_EMAIL, DEFAULT_AUTHOR_NAME};
+use crate::quantity::QuantityFormat;
+use crate::schema::FrontMatterSchema;
+
+/// Commit message used when `COMMIT_MESSAGE_TEMPLATE` is not set
+pub const DEFAULT_COMMIT_MESSAGE_TEMPLATE: &str = "{action} recipe: {path}";
+
+/// File of `KEY=value` lines read into the environment at startup and on reload
+pub const ENV_FILE: &str = ".env";
+
+/// Swaps the log filter of the running server, installed by the binary with its logger
+type LogFilterReload = Box<dyn Fn(&str) -> Result<(), String> + Send + Sync>;
+
+static LOG_FILTER_RELOAD: OnceLock<LogFilterReload> = OnceLock::new();
+
+/// Runtime options for the recipe store, read from environment variables
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoreConfig {
+    /// Move deleted recipes to `.trash/` instead of removing them (`TRASH_ENABLED`)
+    pub trash_enabled: bool,
+    /// Days to keep trashed recipes before purging them, 0 keeps them forever (`TRASH_RETENTION_DAYS`)
+    pub trash_retention_days: u32,
+    /// Match category paths case-insensitively and reuse existing spelling on write (`CASE_INSENSITIVE_PATHS`)
+    pub case_insensitive_paths: bool,
+    /// Days to keep redirecting old paths of renamed categories, 0 keeps them forever (`CATEGORY_ALIAS_DAYS`)
+    pub category_alias_days: u32,
+    /// Seconds before a request is abandoned with 504, 0 disables the timeout (`REQUEST_TIMEOUT_SECS`)
+    pub request_timeout_secs: u64,
+    /// Largest accepted resumable upload in megabytes (`UPLOAD_MAX_MB`)
+    pub upload_max_mb: u64,
+    /// Hours before unfinished uploads are discarded, 0 keeps them forever (`UPLOAD_EXPIRY_HOURS`)
+    pub upload_expiry_hours: u32,
+    /// Seconds to cache derived outputs such as rendered recipes, 0 disables caching (`DERIVED_CACHE_TTL_SECS`)
+    pub derived_cache_ttl_secs: u64,
+    /// Hours between background `git gc` runs in git mode, 0 disables them (`GIT_GC_INTERVAL_HOURS`)
+    pub git_gc_interval_hours: u32,
+    /// Seconds during which git-mode writes are combined into one commit, 0 commits each write (`COMMIT_BATCH_WINDOW_SECS`)
+    pub commit_batch_window_secs: u64,
+    /// Save the recipe index on shutdown and reuse unchanged entries at startup (`INDEX_SNAPSHOT`)
+    pub index_snapshot: bool,
+    /// Remove directories left empty when files are deleted or moved out of them (`REMOVE_EMPTY_DIRS`)
+    pub remove_empty_dirs: bool,
+    /// Data directory every change is also copied to, e.g. a network share; unset for
+    /// no mirror (`MIRROR_PATH`)
+    pub mirror_path: Option<String>,
+    /// Storage type of the mirror, `disk` or `git` (`MIRROR_STORAGE`)
+    pub mirror_storage: String,
+    /// Git commit message with `{action}`, `{title}`, `{path}`, `{author}`, `{comment}` placeholders (`COMMIT_MESSAGE_TEMPLATE`)
+    pub commit_message_template: String,
+    /// Git identity for changes made without an author (`COMMIT_AUTHOR_NAME`)
+    pub commit_author_name: String,
+    /// Email for the default identity and for authors without a mapping (`COMMIT_AUTHOR_EMAIL`)
+    pub commit_author_email: String,
+    /// Author name → email, e.g. `Alice=alice@example.com,Bob=bob@example.com` (`COMMIT_AUTHOR_EMAILS`)
+    pub commit_author_emails: BTreeMap<String, String>,
+    /// Key to sign git commits with: a GPG key ID or an SSH key path, unset for unsigned commits (`COMMIT_SIGNING_KEY`)
+    pub commit_signing_key: Option<String>,
+    /// Signature type for `commit_signing_key`, `openpgp` (default) or `ssh` (`COMMIT_SIGNING_FORMAT`)
+    pub commit_signing_format: SigningFormat,
+    /// JSON file of extra nutrient entries for nutrition estimates (`NUTRITION_DB`)
+    pub nutrition_db: Option<String>,
+    /// Fractions quantities are rounded to in rendered recipes, e.g. denominators `2,3,4,8`
+    /// (`none` for decimals only) within a tolerance of `0.05` (`FRACTION_DENOMINATORS`,
+    /// `FRACTION_TOLERANCE`)
+    pub quantity_format: QuantityFormat,
+    /// Experimental features to enable, comma-separated (`FEATURES`)
+    pub features: BTreeSet<String>,
+    /// Front matter fields checked on create and update, e.g. `servings=integer required,source=url` (`FRONT_MATTER_SCHEMA`)
+    pub front_matter_schema: FrontMatterSchema,
+    /// Largest recipe file accepted on write in kilobytes, 0 for no limit (`MAX_RECIPE_SIZE_KB`)
+    pub max_recipe_size_kb: u64,
+    /// Words recipes may not contain, comma-separated and matched case-insensitively (`BANNED_WORDS`)
+    pub banned_words: Vec<String>,
+    /// Shell command that vets writes, rejecting them by exiting non-zero (`WRITE_HOOK_COMMAND`)
+    pub write_hook_command: Option<String>,
+    /// URL that vets writes, rejecting them with a non-2xx response (`WRITE_HOOK_URL`)
+    pub write_hook_url: Option<String>,
+    /// Seconds an external write hook may take before the write is rejected (`WRITE_HOOK_TIMEOUT_SECS`)
+    pub write_hook_timeout_secs: u64,
+    /// Tries per webhook delivery before it is kept as a dead letter (`WEBHOOK_MAX_ATTEMPTS`)
+    pub webhook_max_attempts: u32,
+    /// Seconds before the first webhook retry, doubled for each one after (`WEBHOOK_RETRY_DELAY_SECS`)
+    pub webhook_retry_delay_secs: u64,
+    /// Seconds one webhook delivery attempt may take (`WEBHOOK_TIMEOUT_SECS`)
+    pub webhook_timeout_secs: u64,
+    /// ntfy topic URL recipe changes are announced on, e.g. `https://ntfy.sh/family-recipes` (`NTFY_URL`)
+    pub ntfy_url: Option<String>,
+    /// Access token for a protected ntfy topic (`NTFY_TOKEN`)
+    pub ntfy_token: Option<String>,
+    /// Matrix homeserver recipe changes are announced through, e.g. `https://matrix.org` (`MATRIX_HOMESERVER`)
+    pub matrix_homeserver: Option<String>,
+    /// Matrix room to announce changes in, e.g. `!abc123:matrix.org` (`MATRIX_ROOM_ID`)
+    pub matrix_room_id: Option<String>,
+    /// Access token of the Matrix user that posts the announcements (`MATRIX_ACCESS_TOKEN`)
+    pub matrix_access_token: Option<String>,
+    /// Kinds of recipe change announced, comma-separated, e.g. `recipe.created,recipe.deleted` (`NOTIFY_EVENTS`)
+    pub notify_events: Vec<EventKind>,
+    /// Serve the built-in web UI at `/` (`WEB_UI`)
+    pub web_ui: bool,
+    /// Address clients reach the server at, e.g. `https://recipes.example.com`, for links
+    /// such as QR codes; unset to use the request's `Host` (`PUBLIC_URL`)
+    pub public_url: Option<String>,
+    /// Bearer token API clients must send; unset leaves the API open, which suits a
+    /// trusted network or a proxy that authenticates (`API_TOKEN`)
+    pub api_token: Option<String>,
+    /// Bearer token for the `/api/v1/admin` routes, which `API_TOKEN` never opens; unset
+    /// with `API_TOKEN` set, the admin routes are disabled (`ADMIN_TOKEN`)
+    pub admin_token: Option<String>,
+    /// Key signing share links; unset disables sharing (`SHARE_SECRET`, falling back to `JWT_SECRET`)
+    pub share_secret: Option<String>,
+    /// Hours a share link stays valid when the request gives no expiry (`SHARE_LINK_TTL_HOURS`)
+    pub share_link_ttl_hours: u64,
+    /// Directory of the data dir holding recipes, empty for the root (`RECIPES_DIR`)
+    pub recipes_dir: String,
+    /// Extra collections served next to the data dir, name → data directory, e.g.
+    /// `family=/data/family,work=/data/work` (`COLLECTIONS`)
+    pub collections: BTreeMap<String, String>,
+    /// Log filter, e.g. `info` or `cooklang_store=debug` (`RUST_LOG`)
+    pub log_filter: Option<String>,
+}
+
+/// What reloading the configuration changed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigReload {
+    /// Environment variables of the settings that changed and now apply
+    pub changed: Vec<&'static str>,
+    /// Whether other settings changed too, which only apply after a restart
+    pub restart_required: bool,
+}
+
+impl Default for StoreConfig {
+    fn default() -> Self {
+        StoreConfig {
+            trash_enabled: false,
+            trash_retention_days: 30,
+            case_insensitive_paths: true,
+            category_alias_days: 30,
+            request_timeout_secs: 30,
+            upload_max_mb: 1024,
+            upload_expiry_hours: 24,
+            derived_cache_ttl_secs: 300,
+            git_gc_interval_hours: 24,
+            commit_batch_window_secs: 0,
+            index_snapshot: true,
+            remove_empty_dirs: true,
+            mirror_path: None,
+            mirror_storage: "disk".to_string(),
+            commit_message_template: DEFAULT_COMMIT_MESSAGE_TEMPLATE.to_string(),
+            commit_author_name: DEFAULT_AUTHOR_NAME.to_string(),
+            commit_author_email: DEFAULT_AUTHOR_EMAIL.to_string(),
+            commit_author_emails: BTreeMap::new(),
+            commit_signing_key: None,
+            commit_signing_format: SigningFormat::OpenPgp,
+            nutrition_db: None,
+            quantity_format: QuantityFormat::default(),
+            features: BTreeSet::new(),
+            front_matter_schema: FrontMatterSchema::default(),
+            max_recipe_size_kb: 0,
+            banned_words: Vec::new(),
+            write_hook_command: None,
+            write_hook_url: None,
+            write_hook_timeout_secs: 10,
+            webhook_max_attempts: 5,
+            webhook_retry_delay_secs: 30,
+            webhook_timeout_secs: 10,
+            ntfy_url: None,
+            ntfy_token: None,
+            matrix_homeserver: None,
+            matrix_room_id: None,
+            matrix_access_token: None,
+            notify_events: vec![EventKind::RecipeCreated],
+            web_ui: true,
+            public_url: None,
+            api_token: None,
+            admin_token: None,
+            share_secret: None,
+            share_link_ttl_hours: 168,
+            recipes_dir: "recipes".to_string(),
+            collections: BTreeMap::new(),
+            log_filter: None,
+        }
+    }
+}
+
+impl StoreConfig {
+    /// Build configuration from environment variables, falling back to defaults
+    pub fn from_env() -> Self {
+        let defaults = StoreConfig::default();
+
+        StoreConfig {
+            trash_enabled: env_bool("TRASH_ENABLED").unwrap_or(defaults.trash_enabled),
+            trash_retention_days: env_parse("TRASH_RETENTION_DAYS")
+                .unwrap_or(defaults.trash_retention_days),
+            case_insensitive_paths: env_bool("CASE_INSENSITIVE_PATHS")
+                .unwrap_or(defaults.case_insensitive_paths),
+            category_alias_days: env_parse("CATEGORY_ALIAS_DAYS")
+                .unwrap_or(defaults.category_alias_days),
+            request_timeout_secs: env_parse("REQUEST_TIMEOUT_SECS")
+                .unwrap_or(defaults.request_timeout_secs),
+            upload_max_mb: env_parse("UPLOAD_MAX_MB").unwrap_or(defaults.upload_max_mb),
+            upload_expiry_hours: env_parse("UPLOAD_EXPIRY_HOURS")
+                .unwrap_or(defaults.upload_expiry_hours),
+            derived_cache_ttl_secs: env_parse("DERIVED_CACHE_TTL_SECS")
+                .unwrap_or(defaults.derived_cache_ttl_secs),
+            git_gc_interval_hours: env_parse("GIT_GC_INTERVAL_HOURS")
+                .unwrap_or(defaults.git_gc_interval_hours),
+            commit_batch_window_secs: env_parse("COMMIT_BATCH_WINDOW_SECS")
+                .unwrap_or(defaults.commit_batch_window_secs),
+            index_snapshot: env_bool("INDEX_SNAPSHOT").unwrap_or(defaults.index_snapshot),
+            remove_empty_dirs: env_bool("REMOVE_EMPTY_DIRS").unwrap_or(defaults.remove_empty_dirs),
+            mirror_path: env_string("MIRROR_PATH").or(defaults.mirror_path),
+            mirror_storage: env_string("MIRROR_STORAGE")
+                .map(|value| value.to_lowercase())
+                .unwrap_or(defaults.mirror_storage),
+            // `\n` in the variable stands for a newline so templates can have a body
+            commit_message_template: std::env::var("COMMIT_MESSAGE_TEMPLATE")
+                .ok()
+                .filter(|template| !template.trim().is_empty())
+                .map(|template| template.replace("\\n", "\n"))
+                .unwrap_or(defaults.commit_message_template),
+            commit_author_name: env_string("COMMIT_AUTHOR_NAME")
+                .unwrap_or(defaults.commit_author_name),
+            commit_author_email: env_string("COMMIT_AUTHOR_EMAIL")
+                .unwrap_or(defaults.commit_author_email),
+            commit_author_emails: env_string("COMMIT_AUTHOR_EMAILS")
+                .map(|value| parse_author_emails(&value))
+                .unwrap_or(defaults.commit_author_emails),
+            commit_signing_key: env_string("COMMIT_SIGNING_KEY").or(defaults.commit_signing_key),
+            commit_signing_format: env_string("COMMIT_SIGNING_FORMAT")
+                .and_then(|value| {
+                    let format = SigningFormat::parse(&value);
+                    if format.is_none() {
+                        tracing::warn!("Ignoring invalid COMMIT_SIGNING_FORMAT: {}", value);
+                    }
+                    format
+                })
+                .unwrap_or(defaults.commit_signing_format),
+            nutrition_db: env_string("NUTRITION_DB").or(defaults.nutrition_db),
+            quantity_format: QuantityFormat {
+                denominators: env_string("FRACTION_DENOMINATORS")
+                    .and_then(|value| parse_denominators(&value))
+                    .unwrap_or(defaults.quantity_format.denominators),
+                tolerance: env_parse("FRACTION_TOLERANCE")
+                    .filter(|tolerance: &f64| (0.0..0.5).contains(tolerance))
+                    .unwrap_or(defaults.quantity_format.tolerance),
+            },
+            features: env_string("FEATURES")
+                .map(|value| parse_features(&value))
+                .unwrap_or(defaults.features),
+            front_matter_schema: env_string("FRONT_MATTER_SCHEMA")
+                .map(|value| FrontMatterSchema::parse(&value))
+                .unwrap_or(defaults.front_matter_schema),
+            max_recipe_size_kb: env_parse("MAX_RECIPE_SIZE_KB")
+                .unwrap_or(defaults.max_recipe_size_kb),
+            banned_words: env_string("BANNED_WORDS")
+                .map(|value| {
+                    value
+                        .split(',')
+                        .map(|word| word.trim().to_string())
+                        .filter(|word| !word.is_empty())
+                        .collect()
+                })
+                .unwrap_or(defaults.banned_words),
+            write_hook_command: env_string("WRITE_HOOK_COMMAND").or(defaults.write_hook_command),
+            write_hook_url: env_string("WRITE_HOOK_URL").or(defaults.write_hook_url),
+            write_hook_timeout_secs: env_parse("WRITE_HOOK_TIMEOUT_SECS")
+                .unwrap_or(defaults.write_hook_timeout_secs),
+            webhook_max_attempts: env_parse("WEBHOOK_MAX_ATTEMPTS")
+                .unwrap_or(defaults.webhook_max_attempts),
+            webhook_retry_delay_secs: env_parse("WEBHOOK_RETRY_DELAY_SECS")
+                .unwrap_or(defaults.webhook_retry_delay_secs),
+            webhook_timeout_secs: env_parse("WEBHOOK_TIMEOUT_SECS")
+                .unwrap_or(defaults.webhook_timeout_secs),
+            ntfy_url: env_string("NTFY_URL").or(defaults.ntfy_url),
+            ntfy_token: env_string("NTFY_TOKEN").or(defaults.ntfy_token),
+            matrix_homeserver: env_string("MATRIX_HOMESERVER").or(defaults.matrix_homeserver),
+            matrix_room_id: env_string("MATRIX_ROOM_ID").or(defaults.matrix_room_id),
+            matrix_access_token: env_string("MATRIX_ACCESS_TOKEN").or(defaults.matrix_access_token),
+            notify_events: env_string("NOTIFY_EVENTS")
+                .map(|value| parse_event_kinds(&value))
+                .unwrap_or(defaults.notify_events),
+            web_ui: env_bool("WEB_UI").unwrap_or(defaults.web_ui),
+            public_url: env_string("PUBLIC_URL")
+                .map(|url| url.trim_end_matches('/').to_string())
+                .or(defaults.public_url),
+            api_token: env_string("API_TOKEN").or(defaults.api_token),
+            admin_token: env_string("ADMIN_TOKEN").or(defaults.admin_token),
+            share_secret: env_string("SHARE_SECRET")
+                .or_else(|| env_string("JWT_SECRET"))
+                .or(defaults.share_secret),
+            share_link_ttl_hours: env_parse("SHARE_LINK_TTL_HOURS")
+                .unwrap_or(defaults.share_link_ttl_hours),
+            // Set but empty (or `.`/`/`) means recipes live at the root of the data dir
+            recipes_dir: std::env::var("RECIPES_DIR")
+                .map(|dir| {
+                    dir.trim()
+                        .trim_matches('/')
+                        .trim_start_matches("./")
+                        .to_string()
+                })
+                .map(|dir| if dir == "." { String::new() } else { dir })
+                .unwrap_or(defaults.recipes_dir),
+            collections: env_string("COLLECTIONS")
+                .map(|value| parse_collections(&value))
+                .unwrap_or(defaults.collections),
+            log_filter: env_string("RUST_LOG").or(defaults.log_filter),
+        }
+    }
+
+    /// Read `path` (usually [`ENV_FILE`]) into the environment, then build the
+    /// configuration from it
+    ///
+    /// Values in the file override the process environment. A line removed from the
+    /// file leaves its last value in place; set it empty to go back to the default.
+    pub fn from_env_file(path: &Path) -> Self {
+        load_env_file(path);
+        Self::from_env()
+    }
+
+    /// This configuration with the settings that can change while the server runs
+    /// taken from `fresh`
+    ///
+    /// Everything else (storage, tokens, routes, collections, notifications) is kept,
+    /// and [`ConfigReload::restart_required`] says whether `fresh` differs there.
+    pub fn reloaded(&self, fresh: &StoreConfig) -> (StoreConfig, ConfigReload) {
+        let mut config = self.clone();
+        let mut reload = ConfigReload::default();
+        macro_rules! reload {
+            ($($($field:ident).+ => $var:literal),* $(,)?) => {$(
+                if config.$($field).+ != fresh.$($field).+ {
+                    config.$($field).+ = fresh.$($field).+.clone();
+                    reload.changed.push($var);
+                }
+            )*};
+        }
+        reload! {
+            trash_enabled => "TRASH_ENABLED",
+            trash_retention_days => "TRASH_RETENTION_DAYS",
+            category_alias_days => "CATEGORY_ALIAS_DAYS",
+            request_timeout_secs => "REQUEST_TIMEOUT_SECS",
+            upload_max_mb => "UPLOAD_MAX_MB",
+            upload_expiry_hours => "UPLOAD_EXPIRY_HOURS",
+            commit_message_template => "COMMIT_MESSAGE_TEMPLATE",
+            commit_author_name => "COMMIT_AUTHOR_NAME",
+            commit_author_email => "COMMIT_AUTHOR_EMAIL",
+            commit_author_emails => "COMMIT_AUTHOR_EMAILS",
+            quantity_format.denominators => "FRACTION_DENOMINATORS",
+            quantity_format.tolerance => "FRACTION_TOLERANCE",
+            front_matter_schema => "FRONT_MATTER_SCHEMA",
+            max_recipe_size_kb => "MAX_RECIPE_SIZE_KB",
+            banned_words => "BANNED_WORDS",
+            write_hook_command => "WRITE_HOOK_COMMAND",
+            write_hook_url => "WRITE_HOOK_URL",
+            write_hook_timeout_secs => "WRITE_HOOK_TIMEOUT_SECS",
+            webhook_max_attempts => "WEBHOOK_MAX_ATTEMPTS",
+            webhook_retry_delay_secs => "WEBHOOK_RETRY_DELAY_SECS",
+            webhook_timeout_secs => "WEBHOOK_TIMEOUT_SECS",
+            share_link_ttl_hours => "SHARE_LINK_TTL_HOURS",
+            log_filter => "RUST_LOG",
+        }
+        reload.restart_required = config != *fresh;
+        (config, reload)
+    }
+
+    /// Whether an experimental feature flag is enabled (names are case-insensitive)
+    pub fn feature_enabled(&self, feature: &str) -> bool {
+        self.features.contains(&feature.to_lowercase())
+    }
+
+    /// Email for a commit author: the mapped address (names match case-insensitively)
+    /// or the default email
+    pub fn author_email(&self, author: &str) -> &str {
+        self.commit_author_emails
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(author.trim()))
+            .map(|(_, email)| email.as_str())
+            .unwrap_or(&self.commit_author_email)
+    }
+
+    /// Signing settings for git commits, `None` when no signing key is set
+    pub fn commit_signing(&self) -> Option<CommitSigning> {
+        self.commit_signing_key.as_ref().map(|key| CommitSigning {
+            format: self.commit_signing_format,
+            key: key.clone(),
+        })
+    }
+}
+
+/// Parse `Name=email` pairs separated by commas
+fn parse_author_emails(value: &str) -> BTreeMap<String, String> {
+    value
+        .split(',')
+        .filter(|pair| !pair.trim().is_empty())
+        .filter_map(|pair| match pair.split_once('=') {
+            Some((name, email)) if !name.trim().is_empty() && !email.trim().is_empty() => {
+                Some((name.trim().to_string(), email.trim().to_string()))
+            }
+            _ => {
+                tracing::warn!("Ignoring invalid COMMIT_AUTHOR_EMAILS entry: {}", pair);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Parse `name=data-dir` pairs separated by commas
+///
+/// Names are lowercased and limited to letters, digits, `-` and `_` so they fit in a URL.
+fn parse_collections(value: &str) -> BTreeMap<String, String> {
+    value
+        .split(',')
+        .filter(|pair| !pair.trim().is_empty())
+        .filter_map(|pair| match pair.split_once('=') {
+            Some((name, dir))
+                if !name.trim().is_empty()
+                    && !dir.trim().is_empty()
+                    && name
+                        .trim()
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') =>
+            {
+                Some((name.trim().to_lowercase(), dir.trim().to_string()))
+            }
+            _ => {
+                tracing::warn!("Ignoring invalid COLLECTIONS entry: {}", pair);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Parse comma-separated fraction denominators (`none` for none)
+fn parse_denominators(value: &str) -> Option<Vec<u32>> {
+    if value.eq_ignore_ascii_case("none") {
+        return Some(Vec::new());
+    }
+    let denominators: Option<Vec<u32>> = value
+        .split(',')
+        .map(|denominator| denominator.trim().parse().ok().filter(|&d| d >= 2))
+        .collect();
+    if denominators.is_none() {
+        tracing::warn!("Ignoring invalid FRACTION_DENOMINATORS: {}", value);
+    }
+    denominators
+}
+
+/// Parse a comma-separated list of feature names, lowercased
+fn parse_features(value: &str) -> BTreeSet<String> {
+    value
+        .split(',')
+        .map(|feature| feature.trim().to_lowercase())
+        .filter(|feature| !feature.is_empty())
+        .collect()
+}
+
+/// Parse a comma-separated list of event kinds, skipping unknown ones with a warning
+fn parse_event_kinds(value: &str) -> Vec<EventKind> {
+    let mut kinds = Vec::new();
+    for name in value
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        match EventKind::parse(name) {
+            Some(kind) if !kinds.contains(&kind) => kinds.push(kind),
+            Some(_) => {}
+            None => tracing::warn!("Ignoring unknown event in NOTIFY_EVENTS: {}", name),
+        }
+    }
+    kinds
+}
+
+/// Set the environment variables in a file of `KEY=value` lines, if it exists
+///
+/// Lines starting with `#` are comments; values may be wrapped in double quotes.
+pub fn load_env_file(path: &Path) {
+    let Ok(env_file) = std::fs::read_to_string(path) else {
+        return;
+    };
+    for line in env_file.lines() {
+        if let Some((key, value)) = line.split_once('=') {
+            let key = key.trim();
+            let value = value.trim().trim_matches('"');
+            if !key.starts_with('#') && !key.is_empty() {
+                std::env::set_var(key, value);
+            }
+        }
+    }
+}
+
+/// Install the function that swaps the running log filter (the first one set is kept)
+pub fn set_log_filter_reload(reload: impl Fn(&str) -> Result<(), String> + Send + Sync + 'static) {
+    let _ = LOG_FILTER_RELOAD.set(Box::new(reload));
+}
+
+/// Switch logging to `filter` (`RUST_LOG` syntax), if the binary made that possible
+pub fn reload_log_filter(filter: &str) {
+    if let Some(reload) = LOG_FILTER_RELOAD.get() {
+        if let Err(e) = reload(filter) {
+            tracing::warn!("Ignoring invalid RUST_LOG {}: {}", filter, e);
+        }
+    }
+}
+
+/// Read a non-empty string environment variable
+fn env_string(key: &str) -> Option<String> {
+    std::env::var(key)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Read a boolean environment variable (true/false, 1/0, yes/no)
+fn env_bool(key: &str) -> Option<bool> {
+    let value = std::env::var(key).ok()?;
+    match value.trim().to_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Some(true),
+        "false" | "0" | "no" | "off" => Some(false),
+        _ => {
+            tracing::warn!("Ignoring invalid boolean value for {}: {}", key, value);
+            None
+        }
+    }
+}
+
+/// Read and parse an environment variable
+fn env_parse<T: std::str::FromStr>(key: &str) -> Option<T> {
+    let value = std::env::var(key).ok()?;
+    match value.trim().parse() {
+        Ok(parsed) => Some(parsed),
+        Err(_) => {
+            tracing::warn!("Ignoring invalid value for {}: {}", key, value);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config() {
+        let config = StoreConfig::default();
+        assert!(!config.trash_enabled);
+        assert_eq!(config.trash_retention_days, 30);
+        assert!(config.case_insensitive_paths);
+        assert_eq!(config.category_alias_days, 30);
+        assert_eq!(config.request_timeout_secs, 30);
+        assert_eq!(config.upload_max_mb, 1024);
+        assert_eq!(config.upload_expiry_hours, 24);
+        assert_eq!(config.derived_cache_ttl_secs, 300);
+        assert_eq!(config.git_gc_interval_hours, 24);
+        assert_eq!(config.commit_batch_window_secs, 0);
+        assert!(config.index_snapshot);
+        assert!(config.remove_empty_dirs);
+        assert_eq!(config.mirror_path, None);
+        assert_eq!(config.mirror_storage, "disk");
+        assert_eq!(config.commit_message_template, "{action} recipe: {path}");
+        assert_eq!(config.commit_author_name, "Cooklang Store");
+        assert!(config.commit_author_emails.is_empty());
+        assert!(config.commit_signing().is_none());
+        assert_eq!(config.commit_signing_format, SigningFormat::OpenPgp);
+        assert!(config.nutrition_db.is_none());
+        assert_eq!(config.quantity_format.denominators, vec![2, 3, 4, 8]);
+        assert!(config.features.is_empty());
+        assert!(config.front_matter_schema.is_empty());
+        assert_eq!(config.max_recipe_size_kb, 0);
+        assert!(config.banned_words.is_empty());
+        assert!(config.write_hook_command.is_none());
+        assert!(config.write_hook_url.is_none());
+        assert_eq!(config.write_hook_timeout_secs, 10);
+        assert_eq!(config.webhook_max_attempts, 5);
+        assert_eq!(config.webhook_retry_delay_secs, 30);
+        assert!(config.ntfy_url.is_none());
+        assert!(config.matrix_room_id.is_none());
+        assert_eq!(config.notify_events, vec![EventKind::RecipeCreated]);
+        assert!(config.web_ui);
+        assert!(config.public_url.is_none());
+        assert!(config.api_token.is_none());
+        assert!(config.admin_token.is_none());
+        assert!(config.log_filter.is_none());
+        assert!(config.share_secret.is_none());
+        assert_eq!(config.share_link_ttl_hours, 168);
+        assert_eq!(config.recipes_dir, "recipes");
+        assert!(config.collections.is_empty());
+    }
+
+    #[test]
+    fn test_feature_flags() {
+        let config = StoreConfig {
+            features: parse_features("GraphQL, sync-api,,"),
+            ..Default::default()
+        };
+
+        assert_eq!(config.features.len(), 2);
+        assert!(config.feature_enabled("graphql"));
+        assert!(config.feature_enabled("SYNC-API"));
+        assert!(!config.feature_enabled("mcp"));
+    }
+
+    #[test]
+    fn test_parse_event_kinds() {
+        assert_eq!(
+            parse_event_kinds("recipe.deleted, recipe.created,recipe.deleted,recipe.eaten"),
+            vec![EventKind::RecipeDeleted, EventKind::RecipeCreated]
+        );
+    }
+
+    #[test]
+    fn test_parse_collections() {
+        let collections =
+            parse_collections("Family=/data/family, work = /data/work,bad name=/x,blog=");
+        assert_eq!(collections.len(), 2);
+        assert_eq!(collections["family"], "/data/family");
+        assert_eq!(collections["work"], "/data/work");
+    }
+
+    #[test]
+    fn test_parse_denominators() {
+        assert_eq!(parse_denominators("2, 3,4"), Some(vec![2, 3, 4]));
+        assert_eq!(parse_denominators("None"), Some(Vec::new()));
+        assert_eq!(parse_denominators("2,1"), None);
+        assert_eq!(parse_denominators("halves"), None);
+    }
+
+    #[test]
+    fn test_author_email_mapping() {
+        let config = StoreConfig {
+            commit_author_emails: parse_author_emails(
+                "Alice=alice@example.com, Bob Smith = bob@example.com,broken",
+            ),
+            ..Default::default()
+        };
+
+        assert_eq!(config.commit_author_emails.len(), 2);
+        assert_eq!(config.author_email("alice"), "alice@example.com");
+        assert_eq!(config.author_email("Bob Smith"), "bob@example.com");
+        assert_eq!(config.author_email("Carol"), "store@cooklang.local");
+    }
+
+    #[test]
+    fn test_reloaded() {
+        let current = StoreConfig {
+            api_token: Some("kitchen-secret".to_string()),
+            ..Default::default()
+        };
+        let fresh = StoreConfig {
+            request_timeout_secs: 5,
+            banned_words: vec!["durian".to_string()],
+            ..current.clone()
+        };
+        let (config, reload) = current.reloaded(&fresh);
+        assert_eq!(config, fresh);
+        assert_eq!(reload.changed, vec!["REQUEST_TIMEOUT_SECS", "BANNED_WORDS"]);
+        assert!(!reload.restart_required);
+
+        // Settings baked in at startup are kept, and flagged
+        let fresh = StoreConfig {
+            api_token: None,
+            ..fresh
+        };
+        let (config, reload) = current.reloaded(&fresh);
+        assert_eq!(config.api_token.as_deref(), Some("kitchen-secret"));
+        assert_eq!(config.request_timeout_secs, 5);
+        assert!(reload.restart_required);
+    }
+}
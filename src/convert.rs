@@ -0,0 +1,287 @@
+use anyhow::{bail, Result};
+
+use crate::import::{ImportedIngredient, ImportedRecipe};
+use crate::markdown::{bullet_item, heading, inline_text, metadata, numbered_item};
+
+/// Title used when the text doesn't start with one
+pub const DEFAULT_TITLE: &str = "Untitled recipe";
+
+/// Units marked up as timers after a number (`25 minutes`)
+const TIMER_UNITS: &[&str] = &[
+    "s", "sec", "secs", "second", "seconds", "min", "mins", "minute", "minutes", "h", "hr", "hrs",
+    "hour", "hours",
+];
+
+/// Longest ingredient line, in words, when no heading says where ingredients are
+const MAX_INGREDIENT_WORDS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Section {
+    /// Nothing has said what the lines are yet
+    Unknown,
+    Ingredients,
+    Steps,
+    Notes,
+}
+
+/// Read pasted recipe text of any shape, best effort
+///
+/// Section headings are recognized as Markdown (`## Ingredients`) or as plain lines
+/// (`Ingredients:`, `Method`). Without them, a short first line is the title, lines
+/// with an amount or unit (`2 eggs`, `salt, 1 tsp`) before the first step are
+/// ingredients, and the rest are steps, one per line or numbered item. `Servings: 4`
+/// and the like before the ingredients become metadata. `title` overrides the title.
+pub fn parse(text: &str, title: Option<&str>) -> Result<ImportedRecipe> {
+    let mut recipe = ImportedRecipe {
+        title: title.map(str::trim).unwrap_or_default().to_string(),
+        ..Default::default()
+    };
+    let mut section = Section::Unknown;
+    let mut description = Vec::new();
+    let mut notes = Vec::new();
+    // Whether the last step came from a numbered item, which wrapped lines continue
+    let mut numbered_step = false;
+    let mut previous_ingredient = false;
+    let mut first_line = true;
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            numbered_step = false;
+            continue;
+        }
+        let is_first = std::mem::take(&mut first_line);
+
+        let bare = heading(trimmed).map_or(trimmed, |(_, text)| text);
+        if let Some(kind) = section_heading(bare) {
+            section = kind;
+            numbered_step = false;
+            continue;
+        }
+        if is_first && recipe.title.is_empty() && !ingredient_like(trimmed) && title_like(bare) {
+            recipe.title = inline_text(bare);
+            continue;
+        }
+
+        let numbered = numbered_item(trimmed);
+        let item = inline_text(bullet_item(trimmed).or(numbered).unwrap_or(trimmed));
+        match section {
+            Section::Ingredients => recipe.ingredients.extend(ImportedIngredient::parse(&item)),
+            Section::Notes => notes.push(item),
+            Section::Steps => {
+                push_step(&mut recipe.steps, item, numbered.is_some(), numbered_step);
+                numbered_step = numbered.is_some() || numbered_step;
+            }
+            Section::Unknown => {
+                let listed = previous_ingredient
+                    && bullet_item(trimmed).is_some()
+                    && item.split_whitespace().count() <= MAX_INGREDIENT_WORDS;
+                if recipe.steps.is_empty() && (ingredient_like(trimmed) || listed) {
+                    recipe.ingredients.extend(ImportedIngredient::parse(&item));
+                    previous_ingredient = true;
+                    continue;
+                }
+                previous_ingredient = false;
+                if recipe.ingredients.is_empty() && recipe.steps.is_empty() && numbered.is_none() {
+                    if metadata(&mut recipe, trimmed) {
+                        continue;
+                    }
+                    if !step_like(&item) {
+                        description.push(item);
+                        continue;
+                    }
+                }
+                push_step(&mut recipe.steps, item, numbered.is_some(), numbered_step);
+                numbered_step = numbered.is_some() || numbered_step;
+            }
+        }
+    }
+
+    if recipe.ingredients.is_empty() && recipe.steps.is_empty() {
+        bail!("No ingredients or steps found");
+    }
+    if recipe.title.is_empty() {
+        recipe.title = DEFAULT_TITLE.to_string();
+    }
+    recipe.description = Some(description.join(" ")).filter(|text| !text.is_empty());
+    recipe.notes = Some(notes.join("\n")).filter(|text| !text.is_empty());
+    Ok(recipe)
+}
+
+/// Mark up times in the steps of a converted recipe as Cooklang timers
+/// (`bake for 25 minutes` → `bake for ~{25%minutes}`), returning how many were marked
+///
+/// Front matter and the insides of existing markup are left alone, as are ranges
+/// (`10-12 minutes`), which Cooklang timers can't hold.
+pub fn mark_timers(content: &str) -> (String, usize) {
+    let body_start = content
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.find("\n---\n"))
+        .map_or(0, |end| "---\n".len() + end + "\n---\n".len());
+    let (front_matter, body) = content.split_at(body_start);
+
+    let mut out = String::with_capacity(content.len() + 32);
+    out.push_str(front_matter);
+    let mut count = 0;
+    let mut depth = 0usize;
+    let mut rest = body;
+    while let Some(c) = rest.chars().next() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        if depth == 0 && c.is_ascii_digit() && !out.ends_with(|p: char| is_number_part(p)) {
+            if let Some((timer, len)) = timer_at(rest) {
+                out.push_str(&timer);
+                rest = &rest[len..];
+                count += 1;
+                continue;
+            }
+        }
+        out.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    (out, count)
+}
+
+/// A timer starting at `text` (`25 minutes`, `1.5h`) and the length it covers
+fn timer_at(text: &str) -> Option<(String, usize)> {
+    let number_len = text.len() - text.trim_start_matches(is_number_part).len();
+    let number = text[..number_len].trim_end_matches(['.', ',', '/', '-']);
+    let after = &text[number.len()..];
+    let gap = after.len() - after.trim_start_matches([' ', '\u{a0}']).len();
+    let word_len = after[gap..]
+        .find(|c: char| !c.is_alphabetic())
+        .unwrap_or(after.len() - gap);
+    let unit = &after[gap..gap + word_len];
+    if number.is_empty()
+        || number.contains('-')
+        || !TIMER_UNITS.contains(&unit.to_lowercase().as_str())
+    {
+        return None;
+    }
+    let number = number.replace(',', ".");
+    Some((
+        format!("~{{{}%{}}}", number, unit),
+        number.len() + gap + word_len,
+    ))
+}
+
+fn is_number_part(c: char) -> bool {
+    c.is_ascii_digit() || matches!(c, '.' | ',' | '/' | '-')
+}
+
+/// Which section a heading-like line (`Ingredients:`, `Method`) starts
+fn section_heading(line: &str) -> Option<Section> {
+    let line = inline_text(line);
+    let name = line.trim_end_matches(':').trim().to_lowercase();
+    if name.split_whitespace().count() > 3 {
+        return None;
+    }
+    if name.contains("ingredient") {
+        Some(Section::Ingredients)
+    } else if ["instruction", "direction", "method", "step", "preparation"]
+        .iter()
+        .any(|word| name.contains(word))
+    {
+        Some(Section::Steps)
+    } else if matches!(name.as_str(), "notes" | "note" | "tips" | "tip") {
+        Some(Section::Notes)
+    } else {
+        None
+    }
+}
+
+/// Whether a line reads as an ingredient: short, no full stop, with an amount or unit
+fn ingredient_like(line: &str) -> bool {
+    let item = inline_text(bullet_item(line).unwrap_or(line));
+    if item.ends_with('.') || item.split_whitespace().count() > MAX_INGREDIENT_WORDS {
+        return false;
+    }
+    ImportedIngredient::parse(&item)
+        .is_some_and(|ingredient| ingredient.quantity.is_some() || ingredient.unit.is_some())
+}
+
+/// Whether a first line can be a title: short, and not a sentence
+fn title_like(line: &str) -> bool {
+    let words = line.split_whitespace().count();
+    (1..=12).contains(&words) && !line.ends_with(['.', ':'])
+}
+
+/// Whether a line reads as an instruction rather than a description
+fn step_like(line: &str) -> bool {
+    line.ends_with('.') && line.split_whitespace().count() > 3
+}
+
+/// Add a step, or continue a numbered one that wrapped onto another line
+fn push_step(steps: &mut Vec<String>, text: String, numbered: bool, numbered_step: bool) {
+    match steps.last_mut() {
+        Some(last) if numbered_step && !numbered => {
+            last.push(' ');
+            last.push_str(&text);
+        }
+        _ => steps.push(text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pasted_text() -> Result<()> {
+        let recipe = parse(
+            "Easy Banana Bread\n\
+             A weekend favourite\n\
+             Serves: 8\n\
+             \n\
+             3 ripe bananas\n\
+             250 g flour\n\
+             1 tsp baking soda\n\
+             - salt\n\
+             \n\
+             Mash the bananas with a fork.\n\
+             Stir in the flour, baking soda and salt.\n\
+             Bake for 1 hour.\n",
+            None,
+        )?;
+        assert_eq!(recipe.title, "Easy Banana Bread");
+        assert_eq!(recipe.description.as_deref(), Some("A weekend favourite"));
+        assert_eq!(recipe.servings.as_deref(), Some("8"));
+        let names: Vec<&str> = recipe.ingredients.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, vec!["ripe bananas", "flour", "baking soda", "salt"]);
+        assert_eq!(recipe.steps.len(), 3);
+
+        let recipe = parse(
+            "Ingredients:\n2 eggs\nmilk\n\nMethod\n1. Beat the eggs\n   with the milk.\n2. Fry.\n",
+            Some("Omelette"),
+        )?;
+        assert_eq!(recipe.title, "Omelette");
+        assert_eq!(recipe.ingredients.len(), 2);
+        assert_eq!(recipe.steps, vec!["Beat the eggs with the milk.", "Fry."]);
+
+        let recipe = parse("2 eggs\nFry the eggs in butter.\n", None)?;
+        assert_eq!(recipe.title, DEFAULT_TITLE);
+        assert_eq!(recipe.ingredients.len(), 1);
+
+        assert!(parse("Just a title\n", None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_mark_timers() {
+        let (content, count) = mark_timers(
+            "---\ntitle: 5 minute bread\n---\n\n\
+             Rest @dough{500%g} for 10 minutes, then bake 1.5h.\n\n\
+             Cool 5-10 min in a 20cm tin; serve in 2 mins.\n",
+        );
+        assert_eq!(
+            content,
+            "---\ntitle: 5 minute bread\n---\n\n\
+             Rest @dough{500%g} for ~{10%minutes}, then bake ~{1.5%h}.\n\n\
+             Cool 5-10 min in a 20cm tin; serve in ~{2%mins}.\n"
+        );
+        assert_eq!(count, 3);
+    }
+}
@@ -0,0 +1,95 @@
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+tokio::task_local! {
+    static DEADLINE: Instant;
+}
+
+/// Error returned when work is abandoned because the request deadline passed
+#[derive(Debug, thiserror::Error)]
+#[error("operation timed out after processing {processed} item(s){}", total.map(|t| format!(" of {}", t)).unwrap_or_default())]
+pub struct OperationTimedOut {
+    /// Items fully processed before the deadline
+    pub processed: usize,
+    /// Total items, when known up front
+    pub total: Option<usize>,
+}
+
+/// Run a future with a deadline `timeout` from now (set per request by the API)
+pub async fn scope<F: Future>(timeout: Duration, future: F) -> F::Output {
+    DEADLINE.scope(Instant::now() + timeout, future).await
+}
+
+/// Wrap `work` for `tokio::task::spawn_blocking` so it keeps the current task's deadline
+///
+/// Blocking threads don't see task-locals, so without this [`check`] would never fire
+/// in storage loops run off the runtime.
+pub fn carry<F, R>(work: F) -> impl FnOnce() -> R + Send + 'static
+where
+    F: FnOnce() -> R + Send + 'static,
+{
+    let deadline = DEADLINE.try_with(|deadline| *deadline).ok();
+    move || match deadline {
+        Some(deadline) => DEADLINE.sync_scope(deadline, work),
+        None => work(),
+    }
+}
+
+/// Whether the current task's deadline (if any) has passed
+pub fn expired() -> bool {
+    DEADLINE
+        .try_with(|deadline| Instant::now() >= *deadline)
+        .unwrap_or(false)
+}
+
+/// Fail with [`OperationTimedOut`] if the current task's deadline has passed
+///
+/// Long loops (discovery, trash scans, bulk operations) call this between items so
+/// slow work stops with partial progress instead of hanging. Outside a [`scope`]
+/// (e.g. at startup) there is no deadline and this always succeeds.
+pub fn check(processed: usize, total: Option<usize>) -> Result<(), OperationTimedOut> {
+    if expired() {
+        Err(OperationTimedOut { processed, total })
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_deadline_outside_scope() {
+        assert!(check(0, None).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_check_fails_after_deadline() {
+        let result = scope(Duration::ZERO, async { check(3, Some(10)) }).await;
+        let err = result.unwrap_err();
+        assert_eq!(err.processed, 3);
+        assert_eq!(err.total, Some(10));
+        assert_eq!(
+            err.to_string(),
+            "operation timed out after processing 3 item(s) of 10"
+        );
+
+        let result = scope(Duration::from_secs(60), async { check(0, None) }).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_deadline_carried_into_blocking_work() {
+        let result = scope(Duration::ZERO, async {
+            tokio::task::spawn_blocking(carry(|| check(0, None)))
+                .await
+                .unwrap()
+        })
+        .await;
+        assert!(result.is_err());
+
+        let outside = tokio::task::spawn_blocking(carry(|| check(0, None))).await;
+        assert!(outside.unwrap().is_ok());
+    }
+}
@@ -0,0 +1,152 @@
+use anyhow::Result;
+use dashmap::DashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::cache::content_hash;
+
+/// A cached output and the content it was computed from
+struct DerivedEntry {
+    content_hash: String,
+    value: Arc<Vec<u8>>,
+    expires_at: Instant,
+}
+
+/// TTL cache for outputs derived from recipe content (rendered HTML, nutrition, parsed JSON, ...)
+///
+/// Entries are keyed by recipe path and output kind and remember the hash of the content
+/// they were computed from, so an edited recipe never serves a stale render even before
+/// its entries are invalidated.
+pub struct DerivedCache {
+    // (git_path, kind) -> entry
+    entries: DashMap<(String, String), DerivedEntry>,
+    ttl: Duration,
+}
+
+impl DerivedCache {
+    /// Create a cache whose entries live for `ttl` (zero disables caching)
+    pub fn new(ttl: Duration) -> Self {
+        DerivedCache {
+            entries: DashMap::new(),
+            ttl,
+        }
+    }
+
+    /// Return the cached `kind` output for `git_path`, computing it if missing, stale or expired
+    pub fn get_or_compute<F>(
+        &self,
+        git_path: &str,
+        kind: &str,
+        content: &str,
+        compute: F,
+    ) -> Result<Arc<Vec<u8>>>
+    where
+        F: FnOnce(&str) -> Result<Vec<u8>>,
+    {
+        if self.ttl.is_zero() {
+            return Ok(Arc::new(compute(content)?));
+        }
+
+        let key = (git_path.to_string(), kind.to_string());
+        let content_hash = content_hash(content);
+
+        if let Some(entry) = self.entries.get(&key) {
+            if entry.content_hash == content_hash && entry.expires_at > Instant::now() {
+                return Ok(entry.value.clone());
+            }
+        }
+
+        let value = Arc::new(compute(content)?);
+        self.entries.insert(
+            key,
+            DerivedEntry {
+                content_hash,
+                value: value.clone(),
+                expires_at: Instant::now() + self.ttl,
+            },
+        );
+
+        Ok(value)
+    }
+
+    /// Drop every output cached for a recipe (after it is updated, moved or deleted)
+    pub fn invalidate(&self, git_path: &str) {
+        self.entries.retain(|(path, _), _| path != git_path);
+    }
+
+    /// Drop expired entries, returning how many were removed
+    pub fn purge_expired(&self) -> usize {
+        let before = self.entries.len();
+        let now = Instant::now();
+        self.entries.retain(|_, entry| entry.expires_at > now);
+        before - self.entries.len()
+    }
+
+    /// Drop all entries
+    pub fn clear(&self) {
+        self.entries.clear();
+    }
+
+    /// Number of cached outputs
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether nothing is cached
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_reuses_output_until_content_changes() -> Result<()> {
+        let cache = DerivedCache::new(Duration::from_secs(60));
+        let calls = Cell::new(0);
+        let render = |content: &str| {
+            calls.set(calls.get() + 1);
+            Ok(content.to_uppercase().into_bytes())
+        };
+
+        let first = cache.get_or_compute("recipes/cake.cook", "html", "cake", render)?;
+        let second = cache.get_or_compute("recipes/cake.cook", "html", "cake", render)?;
+        assert_eq!(first, second);
+        assert_eq!(calls.get(), 1);
+
+        // Edited content is recomputed even without invalidation
+        let edited = cache.get_or_compute("recipes/cake.cook", "html", "cake v2", render)?;
+        assert_eq!(edited.as_slice(), b"CAKE V2");
+        assert_eq!(calls.get(), 2);
+
+        // Kinds are cached separately
+        cache.get_or_compute("recipes/cake.cook", "nutrition", "cake v2", render)?;
+        assert_eq!(cache.len(), 2);
+
+        cache.invalidate("recipes/cake.cook");
+        assert!(cache.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_expiry_and_disabled_cache() -> Result<()> {
+        let cache = DerivedCache::new(Duration::from_millis(1));
+        cache.get_or_compute("recipes/cake.cook", "html", "cake", |c| {
+            Ok(c.as_bytes().to_vec())
+        })?;
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(cache.purge_expired(), 1);
+
+        let disabled = DerivedCache::new(Duration::ZERO);
+        disabled.get_or_compute("recipes/cake.cook", "html", "cake", |c| {
+            Ok(c.as_bytes().to_vec())
+        })?;
+        assert!(disabled.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,192 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::task::JoinHandle;
+
+use crate::cache::generate_recipe_id;
+
+/// Events kept for subscribers that fall behind; older ones are skipped
+const EVENT_BUFFER: usize = 256;
+
+/// What happened to a recipe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EventKind {
+    #[serde(rename = "recipe.created")]
+    RecipeCreated,
+    /// Content, title or category changed; a move carries the old path
+    #[serde(rename = "recipe.updated")]
+    RecipeUpdated,
+    #[serde(rename = "recipe.deleted")]
+    RecipeDeleted,
+}
+
+impl EventKind {
+    pub const ALL: &'static [EventKind] = &[
+        EventKind::RecipeCreated,
+        EventKind::RecipeUpdated,
+        EventKind::RecipeDeleted,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EventKind::RecipeCreated => "recipe.created",
+            EventKind::RecipeUpdated => "recipe.updated",
+            EventKind::RecipeDeleted => "recipe.deleted",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.as_str() == value.trim())
+    }
+}
+
+/// A recipe change, published by the repository once it is stored
+///
+/// Serialized as sent to webhooks and event stream clients.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositoryEvent {
+    pub event: EventKind,
+    pub recipe_id: String,
+    pub path: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// Where the recipe was before it moved or was renamed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl RepositoryEvent {
+    pub fn new(
+        event: EventKind,
+        git_path: &str,
+        name: &str,
+        category: Option<&str>,
+        author: Option<&str>,
+    ) -> Self {
+        RepositoryEvent {
+            event,
+            recipe_id: generate_recipe_id(git_path),
+            path: git_path.to_string(),
+            name: name.to_string(),
+            category: category.map(str::to_string),
+            previous_path: None,
+            author: author
+                .map(str::trim)
+                .filter(|author| !author.is_empty())
+                .map(str::to_string),
+            occurred_at: Utc::now(),
+        }
+    }
+
+    /// The same event for a recipe that was at `previous_path`, if it moved
+    pub fn moved_from(mut self, previous_path: Option<&str>) -> Self {
+        self.previous_path = previous_path
+            .filter(|path| *path != self.path)
+            .map(str::to_string);
+        self
+    }
+}
+
+/// Broadcast channel the repository publishes its events on
+///
+/// Subscribers each see every event published after they subscribed. Publishing never
+/// blocks: a subscriber that falls too far behind skips the oldest events it missed.
+pub struct EventBus {
+    sender: broadcast::Sender<RepositoryEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        EventBus {
+            sender: broadcast::channel(EVENT_BUFFER).0,
+        }
+    }
+
+    /// Send an event to the current subscribers, if any
+    pub fn publish(&self, event: RepositoryEvent) {
+        // No subscribers is not an error: nobody is listening yet
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<RepositoryEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Run `handle` on every event from now on, in a background task
+    ///
+    /// Missed events are logged and skipped. The task ends when the bus is dropped;
+    /// outside a Tokio runtime nothing is started.
+    pub fn spawn_listener<F>(&self, name: &'static str, mut handle: F) -> Option<JoinHandle<()>>
+    where
+        F: FnMut(RepositoryEvent) + Send + 'static,
+    {
+        let runtime = tokio::runtime::Handle::try_current().ok()?;
+        let mut events = self.subscribe();
+        Some(runtime.spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => handle(event),
+                    Err(RecvError::Lagged(missed)) => {
+                        tracing::warn!("{} fell behind and missed {} events", name, missed)
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        }))
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_listener_sees_published_events() {
+        let bus = EventBus::new();
+        let (sender, mut received) = tokio::sync::mpsc::unbounded_channel();
+        let task = bus
+            .spawn_listener("test", move |event| {
+                let _ = sender.send(event);
+            })
+            .unwrap();
+
+        let event = RepositoryEvent::new(
+            EventKind::RecipeUpdated,
+            "recipes/soups/pea.cook",
+            "Pea Soup",
+            Some("soups"),
+            Some(" "),
+        )
+        .moved_from(Some("recipes/pea.cook"));
+        bus.publish(event.clone());
+        assert_eq!(received.recv().await, Some(event.clone()));
+        assert_eq!(event.author, None);
+        assert_eq!(event.previous_path.as_deref(), Some("recipes/pea.cook"));
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["event"], "recipe.updated");
+        assert_eq!(json["previousPath"], "recipes/pea.cook");
+        assert_eq!(
+            EventKind::parse("recipe.deleted"),
+            Some(EventKind::RecipeDeleted)
+        );
+
+        // Dropping the bus ends its listeners
+        drop(bus);
+        task.await.unwrap();
+    }
+}
@@ -0,0 +1,153 @@
+use anyhow::{Context, Result};
+use dashmap::DashMap;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// User that favorites belong to when a request names none (before authentication)
+pub const DEFAULT_USER: &str = "default";
+
+/// Starred recipes per user, keyed by git path
+pub struct Favorites {
+    // user -> git paths
+    users: DashMap<String, BTreeSet<String>>,
+}
+
+impl Default for Favorites {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Favorites {
+    /// Create an empty favorites table
+    pub fn new() -> Self {
+        Favorites {
+            users: DashMap::new(),
+        }
+    }
+
+    /// Replace the table with favorites loaded from JSON
+    pub fn load(&self, json: &str) -> Result<()> {
+        let users: BTreeMap<String, BTreeSet<String>> =
+            serde_json::from_str(json).context("Invalid favorites file")?;
+
+        self.users.clear();
+        for (user, paths) in users {
+            if !paths.is_empty() {
+                self.users.insert(user, paths);
+            }
+        }
+        Ok(())
+    }
+
+    /// Serialize the table to JSON (sorted by user and path, for stable diffs)
+    pub fn to_json(&self) -> Result<String> {
+        let users: BTreeMap<String, BTreeSet<String>> = self
+            .users
+            .iter()
+            .filter(|entry| !entry.value().is_empty())
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+        Ok(serde_json::to_string_pretty(&users)?)
+    }
+
+    /// Star or unstar a recipe for a user, returning whether anything changed
+    pub fn set(&self, user: Option<&str>, git_path: &str, favorite: bool) -> bool {
+        let user = user_key(user);
+        if favorite {
+            self.users
+                .entry(user)
+                .or_default()
+                .insert(git_path.to_string())
+        } else {
+            let changed = self
+                .users
+                .get_mut(&user)
+                .map(|mut paths| paths.remove(git_path))
+                .unwrap_or(false);
+            self.users.remove_if(&user, |_, paths| paths.is_empty());
+            changed
+        }
+    }
+
+    /// Whether a user starred a recipe
+    pub fn contains(&self, user: Option<&str>, git_path: &str) -> bool {
+        self.users
+            .get(&user_key(user))
+            .map(|paths| paths.contains(git_path))
+            .unwrap_or(false)
+    }
+
+    /// Follow a recipe that moved, for every user; returns whether anything changed
+    pub fn rename(&self, from: &str, to: &str) -> bool {
+        let mut changed = false;
+        for mut paths in self.users.iter_mut() {
+            if paths.remove(from) {
+                paths.insert(to.to_string());
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Forget a deleted recipe for every user; returns whether anything changed
+    pub fn remove(&self, git_path: &str) -> bool {
+        let mut changed = false;
+        for mut paths in self.users.iter_mut() {
+            changed |= paths.remove(git_path);
+        }
+        self.users.retain(|_, paths| !paths.is_empty());
+        changed
+    }
+}
+
+/// Normalize a request's user name, falling back to [`DEFAULT_USER`]
+fn user_key(user: Option<&str>) -> String {
+    user.map(str::trim)
+        .filter(|user| !user.is_empty())
+        .unwrap_or(DEFAULT_USER)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_favorites_per_user() {
+        let favorites = Favorites::new();
+        assert!(favorites.set(None, "recipes/cake.cook", true));
+        assert!(!favorites.set(Some(" "), "recipes/cake.cook", true));
+        assert!(favorites.set(Some("alice"), "recipes/soup.cook", true));
+
+        assert!(favorites.contains(Some("default"), "recipes/cake.cook"));
+        assert!(!favorites.contains(Some("alice"), "recipes/cake.cook"));
+        assert!(favorites.contains(Some("alice"), "recipes/soup.cook"));
+
+        assert!(favorites.set(Some("alice"), "recipes/soup.cook", false));
+        assert!(!favorites.set(Some("alice"), "recipes/soup.cook", false));
+        assert!(!favorites.contains(Some("alice"), "recipes/soup.cook"));
+    }
+
+    #[test]
+    fn test_rename_remove_and_json_round_trip() -> Result<()> {
+        let favorites = Favorites::new();
+        favorites.set(None, "recipes/cake.cook", true);
+        favorites.set(Some("bob"), "recipes/cake.cook", true);
+        favorites.set(Some("bob"), "recipes/soup.cook", true);
+
+        assert!(favorites.rename("recipes/cake.cook", "recipes/desserts/cake.cook"));
+        assert!(favorites.contains(None, "recipes/desserts/cake.cook"));
+        assert!(favorites.contains(Some("bob"), "recipes/desserts/cake.cook"));
+
+        let loaded = Favorites::new();
+        loaded.load(&favorites.to_json()?)?;
+        assert_eq!(loaded.to_json()?, favorites.to_json()?);
+
+        assert!(loaded.remove("recipes/desserts/cake.cook"));
+        assert!(!loaded.remove("recipes/desserts/cake.cook"));
+        assert!(loaded.contains(Some("bob"), "recipes/soup.cook"));
+        assert!(!loaded.to_json()?.contains(DEFAULT_USER));
+
+        Ok(())
+    }
+}
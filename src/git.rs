@@ -1,6 +1,19 @@
 use anyhow::{Context, Result};
-use git2::{Repository, Signature};
+use chrono::{DateTime, Utc};
+use git2::{BranchType, Repository, Signature};
+use std::collections::hash_map::{Entry, HashMap};
+use std::collections::HashSet;
+use std::io::Write;
 use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::storage::{BlameLine, DraftConflict, DraftFile, FileChanges, VersionExists, VersionTag};
+
+/// Branch name prefix for drafts (`drafts/{draft_id}`)
+pub const DRAFT_BRANCH_PREFIX: &str = "drafts/";
+
+/// Tag name prefix for recipe versions (`versions/{recipe_id}/{name}`)
+pub const VERSION_TAG_PREFIX: &str = "versions/";
 
 /// Initializes a git repository at the given path
 pub fn init_repo(path: &Path) -> Result<Repository> {
@@ -14,18 +27,152 @@ pub fn init_repo(path: &Path) -> Result<Repository> {
     }
 }
 
-/// Get or create the default git signature for commits
-fn get_default_signature() -> Result<Signature<'static>> {
-    Signature::now("Cooklang Store", "store@cooklang.local")
-        .context("Failed to create git signature")
+/// Default identity for commits made without an explicit author
+pub const DEFAULT_AUTHOR_NAME: &str = "Cooklang Store";
+pub const DEFAULT_AUTHOR_EMAIL: &str = "store@cooklang.local";
+
+/// Name and email recorded as a commit's author and committer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitAuthor {
+    pub name: String,
+    pub email: String,
+}
+
+impl Default for CommitAuthor {
+    fn default() -> Self {
+        CommitAuthor {
+            name: DEFAULT_AUTHOR_NAME.to_string(),
+            email: DEFAULT_AUTHOR_EMAIL.to_string(),
+        }
+    }
+}
+
+impl CommitAuthor {
+    fn signature(&self) -> Result<Signature<'static>> {
+        Signature::now(&self.name, &self.email).context(format!(
+            "Failed to create git signature for author: {} <{}>",
+            self.name, self.email
+        ))
+    }
+}
+
+/// How commits are signed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SigningFormat {
+    /// OpenPGP signatures made with `gpg`
+    OpenPgp,
+    /// SSH signatures made with `ssh-keygen -Y sign`
+    Ssh,
+}
+
+impl SigningFormat {
+    /// Parse a format name as in git's `gpg.format`: `openpgp` (or `gpg`) or `ssh`
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "openpgp" | "gpg" => Some(SigningFormat::OpenPgp),
+            "ssh" => Some(SigningFormat::Ssh),
+            _ => None,
+        }
+    }
+}
+
+/// Key the store signs its commits with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitSigning {
+    pub format: SigningFormat,
+    /// GPG key ID or fingerprint, or the path of an SSH private key (or of a public key
+    /// whose private half is in ssh-agent)
+    pub key: String,
+}
+
+impl CommitSigning {
+    /// Detached, armored signature of `content`, made by the `gpg` or `ssh-keygen` CLI
+    pub fn sign(&self, content: &str) -> Result<String> {
+        let (program, args) = match self.format {
+            SigningFormat::OpenPgp => (
+                "gpg",
+                ["--status-fd=2", "-bsau", self.key.as_str()].to_vec(),
+            ),
+            SigningFormat::Ssh => (
+                "ssh-keygen",
+                ["-Y", "sign", "-n", "git", "-f", self.key.as_str()].to_vec(),
+            ),
+        };
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context(format!("Failed to run {} (is it installed?)", program))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(content.as_bytes())
+                .context(format!("Failed to pass commit to {}", program))?;
+        }
+
+        let output = child
+            .wait_with_output()
+            .context(format!("Failed to run {}", program))?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "{} failed to sign: {}",
+                program,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        String::from_utf8(output.stdout)
+            .context(format!("{} returned an invalid signature", program))
+    }
+}
+
+/// Create a commit and move `refname` to it, signed with `signing` when set
+///
+/// `HEAD` moves the branch it points at (which may not exist yet), like `git commit`.
+fn create_commit(
+    repo: &Repository,
+    refname: &str,
+    author: &CommitAuthor,
+    message: &str,
+    tree: &git2::Tree,
+    parents: &[&git2::Commit],
+    signing: Option<&CommitSigning>,
+) -> Result<git2::Oid> {
+    let signature = author.signature()?;
+    let Some(signing) = signing else {
+        return Ok(repo.commit(
+            Some(refname),
+            &signature,
+            &signature,
+            message,
+            tree,
+            parents,
+        )?);
+    };
+
+    let buffer = repo.commit_create_buffer(&signature, &signature, message, tree, parents)?;
+    let content = buffer.as_str().context("Commit is not valid UTF-8")?;
+    let oid = repo.commit_signed(content, &signing.sign(content)?, None)?;
+
+    // `commit_signed` only writes the object, so the branch is moved by hand
+    let target = match repo.find_reference(refname) {
+        Ok(reference) => reference.symbolic_target().unwrap_or(refname).to_string(),
+        Err(_) => refname.to_string(),
+    };
+    let subject = message.lines().next().unwrap_or_default();
+    repo.reference(&target, oid, true, &format!("commit: {}", subject))?;
+    Ok(oid)
 }
 
-/// Create a git signature with a specific author name
-fn get_signature_with_author(author: &str) -> Result<Signature<'_>> {
-    Signature::now(author, "backend@cooklang.local").context(format!(
-        "Failed to create git signature for author: {}",
-        author
-    ))
+/// Commit author for an optional author name, using the default email
+fn author_for(author: Option<&str>) -> CommitAuthor {
+    match author {
+        Some(name) => CommitAuthor {
+            name: name.to_string(),
+            ..CommitAuthor::default()
+        },
+        None => CommitAuthor::default(),
+    }
 }
 
 /// Commit a single file to the repository
@@ -40,41 +187,28 @@ pub fn commit_file_with_author(
     message: &str,
     author: Option<&str>,
 ) -> Result<git2::Oid> {
-    let mut index = repo.index()?;
-    index.add_path(Path::new(rel_path))?;
-    index.write()?;
-
-    let tree_id = index.write_tree()?;
-    let tree = repo.find_tree(tree_id)?;
-
-    let signature = if let Some(author_name) = author {
-        get_signature_with_author(author_name)?
-    } else {
-        get_default_signature()?
-    };
-
-    let parent_commit = match repo.head() {
-        Ok(head) => {
-            let commit = head.peel_to_commit()?;
-            vec![commit]
-        }
-        Err(_) => {
-            // First commit, no parent
-            vec![]
-        }
-    };
+    commit_file_as(repo, rel_path, message, &author_for(author))
+}
 
-    let parents: Vec<&git2::Commit> = parent_commit.iter().collect();
-    let oid = repo.commit(
-        Some("HEAD"),
-        &signature,
-        &signature,
-        message,
-        &tree,
-        &parents,
-    )?;
+/// Commit a single file as the given author
+pub fn commit_file_as(
+    repo: &Repository,
+    rel_path: &str,
+    message: &str,
+    author: &CommitAuthor,
+) -> Result<git2::Oid> {
+    commit_files_as(repo, &[rel_path], message, author)
+}
 
-    Ok(oid)
+/// Commit several files as one commit, as the given author
+pub fn commit_files_as(
+    repo: &Repository,
+    rel_paths: &[&str],
+    message: &str,
+    author: &CommitAuthor,
+) -> Result<git2::Oid> {
+    stage_files(repo, rel_paths)?;
+    commit_staged_as(repo, message, author, None)
 }
 
 /// Delete a file and commit the deletion
@@ -89,6 +223,58 @@ pub fn delete_file_with_author(
     message: &str,
     author: Option<&str>,
 ) -> Result<git2::Oid> {
+    delete_file_as(repo, rel_path, message, &author_for(author))
+}
+
+/// Delete a file and commit the deletion as the given author
+pub fn delete_file_as(
+    repo: &Repository,
+    rel_path: &str,
+    message: &str,
+    author: &CommitAuthor,
+) -> Result<git2::Oid> {
+    stage_deletion(repo, rel_path)?;
+    commit_staged_as(repo, message, author, None)
+}
+
+/// Move a file to a new path with the given content and commit both sides at once
+///
+/// Staging the removal and the addition in one commit lets git detect the rename
+/// (`git log --follow`) as long as the content stays similar.
+pub fn rename_file_as(
+    repo: &Repository,
+    from: &str,
+    to: &str,
+    content: &str,
+    message: &str,
+    author: &CommitAuthor,
+) -> Result<git2::Oid> {
+    rename_files_as(repo, &[(from, to, content)], message, author)
+}
+
+/// Move several files (`from`, `to`, `content`) as one commit, as the given author
+pub fn rename_files_as(
+    repo: &Repository,
+    renames: &[(&str, &str, &str)],
+    message: &str,
+    author: &CommitAuthor,
+) -> Result<git2::Oid> {
+    stage_renames(repo, renames)?;
+    commit_staged_as(repo, message, author, None)
+}
+
+/// Add files from the working directory to the index
+pub fn stage_files(repo: &Repository, rel_paths: &[&str]) -> Result<()> {
+    let mut index = repo.index()?;
+    for rel_path in rel_paths {
+        index.add_path(Path::new(rel_path))?;
+    }
+    index.write()?;
+    Ok(())
+}
+
+/// Delete a file from the working directory and the index
+pub fn stage_deletion(repo: &Repository, rel_path: &str) -> Result<()> {
     let file_path = repo
         .workdir()
         .context("Repository has no working directory")?
@@ -101,63 +287,684 @@ pub fn delete_file_with_author(
     let mut index = repo.index()?;
     index.remove_path(Path::new(rel_path))?;
     index.write()?;
+    Ok(())
+}
+
+/// Move each file (`from`, `to`, `content`) to its new path in the working tree and
+/// stage both sides
+pub fn stage_renames(repo: &Repository, renames: &[(&str, &str, &str)]) -> Result<()> {
+    let workdir = repo
+        .workdir()
+        .context("Repository has no working directory")?;
+
+    let mut index = repo.index()?;
+    for (from, to, content) in renames {
+        crate::storage::move_file(workdir, from, to, content)?;
+        index.remove_path(Path::new(from))?;
+        index.add_path(Path::new(to))?;
+    }
+    index.write()?;
+    Ok(())
+}
+
+/// Put each of `rel_paths` back as it is in HEAD, in the working tree and the index
+///
+/// Paths HEAD doesn't have are removed. Used to undo changes staged but not committed.
+pub fn restore_paths(repo: &Repository, rel_paths: &[&str]) -> Result<()> {
+    let workdir = repo
+        .workdir()
+        .context("Repository has no working directory")?;
+    let head = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+
+    let mut index = repo.index()?;
+    // Drop changes staged in memory but never written
+    index.read(true)?;
+    for rel_path in rel_paths {
+        let full_path = workdir.join(rel_path);
+        let entry = head
+            .as_ref()
+            .and_then(|tree| tree.get_path(Path::new(rel_path)).ok());
+        match entry {
+            Some(entry) => {
+                let blob = repo.find_blob(entry.id())?;
+                if let Some(parent) = full_path.parent() {
+                    std::fs::create_dir_all(parent).context("Failed to create recipe directory")?;
+                }
+                std::fs::write(&full_path, blob.content())
+                    .with_context(|| format!("Failed to restore {}", rel_path))?;
+                index.add_path(Path::new(rel_path))?;
+            }
+            None => {
+                if full_path.exists() {
+                    std::fs::remove_file(&full_path)
+                        .with_context(|| format!("Failed to remove {}", rel_path))?;
+                }
+                index.remove_path(Path::new(rel_path))?;
+            }
+        }
+    }
+    index.write()?;
+    Ok(())
+}
 
+/// Commit the index onto HEAD as the given author
+///
+/// The first commit of a repository (including files seeded before it) has no parent.
+pub fn commit_staged_as(
+    repo: &Repository,
+    message: &str,
+    author: &CommitAuthor,
+    signing: Option<&CommitSigning>,
+) -> Result<git2::Oid> {
+    let mut index = repo.index()?;
     let tree_id = index.write_tree()?;
     let tree = repo.find_tree(tree_id)?;
 
-    let signature = if let Some(author_name) = author {
-        get_signature_with_author(author_name)?
-    } else {
-        get_default_signature()?
+    let parent_commit = head_commit(repo)?;
+    let parents: Vec<&git2::Commit> = parent_commit.iter().collect();
+    create_commit(repo, "HEAD", author, message, &tree, &parents, signing)
+}
+
+/// Commit a file to a branch without touching HEAD, the index or the working directory
+///
+/// A branch that does not exist yet starts from HEAD (or from nothing in a repository
+/// without commits).
+pub fn commit_to_branch(
+    repo: &Repository,
+    branch: &str,
+    rel_path: &str,
+    content: &str,
+    message: &str,
+    author: &CommitAuthor,
+    signing: Option<&CommitSigning>,
+) -> Result<git2::Oid> {
+    let refname = format!("refs/heads/{}", branch);
+    let parent = match repo.find_reference(&refname) {
+        Ok(reference) => Some(reference.peel_to_commit()?),
+        Err(_) => head_commit(repo)?,
     };
 
-    let parent_commit = repo.head()?.peel_to_commit()?;
+    let mut index = git2::Index::new()?;
+    if let Some(parent) = &parent {
+        index.read_tree(&parent.tree()?)?;
+    }
+    let blob_id = repo.blob(content.as_bytes())?;
+    index.add(&git2::IndexEntry {
+        ctime: git2::IndexTime::new(0, 0),
+        mtime: git2::IndexTime::new(0, 0),
+        dev: 0,
+        ino: 0,
+        mode: 0o100644,
+        uid: 0,
+        gid: 0,
+        file_size: content.len() as u32,
+        id: blob_id,
+        flags: 0,
+        flags_extended: 0,
+        path: rel_path.as_bytes().to_vec(),
+    })?;
 
-    let oid = repo.commit(
-        Some("HEAD"),
-        &signature,
-        &signature,
+    let tree_id = index.write_tree_to(repo)?;
+    let tree = repo.find_tree(tree_id)?;
+
+    create_commit(
+        repo,
+        &refname,
+        author,
         message,
         &tree,
-        &[&parent_commit],
-    )?;
+        &parent.iter().collect::<Vec<_>>(),
+        signing,
+    )
+}
 
-    Ok(oid)
+/// All draft branches with the file each one changes
+pub fn list_drafts(repo: &Repository) -> Result<Vec<DraftFile>> {
+    let mut drafts = Vec::new();
+
+    for branch in repo.branches(Some(BranchType::Local))? {
+        let (branch, _) = branch?;
+        let Some(draft_id) = branch
+            .name()?
+            .and_then(|name| name.strip_prefix(DRAFT_BRANCH_PREFIX))
+        else {
+            continue;
+        };
+
+        let tip = branch.get().peel_to_commit()?;
+        if let Some(draft) = draft_file(repo, draft_id, &tip)? {
+            drafts.push(draft);
+        }
+    }
+
+    Ok(drafts)
 }
 
-/// Read a file from the repository
-pub fn read_file(repo: &Repository, rel_path: &str) -> Result<String> {
-    let file_path = repo
+/// Merge a draft branch into HEAD, update the working directory and delete the branch
+pub fn merge_draft(
+    repo: &Repository,
+    draft_id: &str,
+    message: &str,
+    author: &CommitAuthor,
+    signing: Option<&CommitSigning>,
+) -> Result<DraftFile> {
+    let mut branch = repo
+        .find_branch(
+            &format!("{}{}", DRAFT_BRANCH_PREFIX, draft_id),
+            BranchType::Local,
+        )
+        .context(format!("Draft not found: {}", draft_id))?;
+    let tip = branch.get().peel_to_commit()?;
+    let mut draft =
+        draft_file(repo, draft_id, &tip)?.context(format!("Draft has no changes: {}", draft_id))?;
+
+    let head = head_commit(repo)?;
+    let tree = match &head {
+        Some(head) => {
+            let mut merged = repo.merge_commits(head, &tip, None)?;
+            if merged.has_conflicts() {
+                return Err(DraftConflict {
+                    path: draft.rel_path,
+                }
+                .into());
+            }
+            repo.find_tree(merged.write_tree_to(repo)?)?
+        }
+        None => tip.tree()?,
+    };
+
+    let parents: Vec<&git2::Commit> = head.iter().chain([&tip]).collect();
+    create_commit(repo, "HEAD", author, message, &tree, &parents, signing)?;
+
+    // Non-overlapping edits made on the main branch meanwhile are merged in
+    let blob = tree
+        .get_path(Path::new(&draft.rel_path))?
+        .to_object(repo)?
+        .peel_to_blob()?;
+    draft.content =
+        String::from_utf8(blob.content().to_vec()).context("Published draft is not valid UTF-8")?;
+
+    // Bring the working directory and index in line with the new HEAD
+    let full_path = repo
         .workdir()
         .context("Repository has no working directory")?
-        .join(rel_path);
+        .join(&draft.rel_path);
+    if let Some(parent) = full_path.parent() {
+        std::fs::create_dir_all(parent).context("Failed to create recipe directory")?;
+    }
+    std::fs::write(&full_path, &draft.content).context("Failed to write published draft")?;
 
-    std::fs::read_to_string(&file_path).context(format!("Failed to read file: {}", rel_path))
+    let mut index = repo.index()?;
+    index.add_path(Path::new(&draft.rel_path))?;
+    index.write()?;
+
+    branch.delete()?;
+
+    Ok(draft)
 }
 
-/// Discover all .cook files in the repository recursively
-pub fn discover_cook_files(repo: &Repository) -> Result<Vec<String>> {
+/// Delete a draft branch, returning whether it existed
+pub fn delete_draft(repo: &Repository, draft_id: &str) -> Result<bool> {
+    match repo.find_branch(
+        &format!("{}{}", DRAFT_BRANCH_PREFIX, draft_id),
+        BranchType::Local,
+    ) {
+        Ok(mut branch) => {
+            branch.delete()?;
+            Ok(true)
+        }
+        Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The file a draft branch adds or changes relative to where it forked from HEAD
+fn draft_file(repo: &Repository, draft_id: &str, tip: &git2::Commit) -> Result<Option<DraftFile>> {
+    // A draft started before the first commit shares no history with HEAD
+    let base = match head_commit(repo)? {
+        Some(head) => match repo.merge_base(head.id(), tip.id()) {
+            Ok(base) => Some(repo.find_commit(base)?.tree()?),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => None,
+            Err(e) => return Err(e.into()),
+        },
+        None => None,
+    };
+    let diff = repo.diff_tree_to_tree(base.as_ref(), Some(&tip.tree()?), None)?;
+
+    let Some(delta) = diff
+        .deltas()
+        .find(|delta| matches!(delta.status(), git2::Delta::Added | git2::Delta::Modified))
+    else {
+        return Ok(None);
+    };
+
+    let rel_path = delta
+        .new_file()
+        .path()
+        .context("Draft file has no path")?
+        .to_string_lossy()
+        .to_string();
+    let blob = repo.find_blob(delta.new_file().id())?;
+    let content = String::from_utf8(blob.content().to_vec()).context("Draft is not valid UTF-8")?;
+
+    Ok(Some(DraftFile {
+        draft_id: draft_id.to_string(),
+        rel_path,
+        content,
+        updated_at: DateTime::<Utc>::from_timestamp(tip.time().seconds(), 0).unwrap_or_default(),
+    }))
+}
+
+/// Create an annotated tag for version `name` of a recipe at HEAD
+///
+/// `rel_path` must be part of HEAD, so the version can always be read back.
+pub fn tag_version(
+    repo: &Repository,
+    recipe_id: &str,
+    name: &str,
+    rel_path: &str,
+    message: &str,
+    author: &CommitAuthor,
+) -> Result<VersionTag> {
+    let head = head_commit(repo)?.context("Repository has no commits to tag")?;
+    head.tree()?
+        .get_path(Path::new(rel_path))
+        .context(format!("{} has no committed version", rel_path))?;
+
+    let tag_name = format!("{}{}/{}", VERSION_TAG_PREFIX, recipe_id, name);
+    let signature = author.signature()?;
+    match repo.tag(&tag_name, head.as_object(), &signature, message, false) {
+        Ok(_) => {}
+        Err(e) if e.code() == git2::ErrorCode::Exists => {
+            return Err(VersionExists {
+                name: name.to_string(),
+            }
+            .into())
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    version_tag(repo, name, &tag_name)
+}
+
+/// All version tags of a recipe
+pub fn list_versions(repo: &Repository, recipe_id: &str) -> Result<Vec<VersionTag>> {
+    let prefix = format!("{}{}/", VERSION_TAG_PREFIX, recipe_id);
+    let mut versions = Vec::new();
+
+    for tag_name in repo
+        .tag_names(Some(&format!("{}*", prefix)))?
+        .iter()
+        .flatten()
+    {
+        if let Some(name) = tag_name.strip_prefix(&prefix) {
+            versions.push(version_tag(repo, name, tag_name)?);
+        }
+    }
+
+    Ok(versions)
+}
+
+/// Content of `rel_path` at a recipe's version tag, `None` if the tag does not exist
+pub fn read_version(
+    repo: &Repository,
+    recipe_id: &str,
+    name: &str,
+    rel_path: &str,
+) -> Result<Option<String>> {
+    let refname = format!("refs/tags/{}{}/{}", VERSION_TAG_PREFIX, recipe_id, name);
+    let reference = match repo.find_reference(&refname) {
+        Ok(reference) => reference,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let blob = reference
+        .peel_to_commit()?
+        .tree()?
+        .get_path(Path::new(rel_path))
+        .context(format!("{} is not part of version {}", rel_path, name))?
+        .to_object(repo)?
+        .peel_to_blob()?;
+    let content =
+        String::from_utf8(blob.content().to_vec()).context("Version is not valid UTF-8")?;
+
+    Ok(Some(content))
+}
+
+/// Version details from an annotated tag, or from the commit for tags made with plain `git tag`
+fn version_tag(repo: &Repository, name: &str, tag_name: &str) -> Result<VersionTag> {
+    let reference = repo.find_reference(&format!("refs/tags/{}", tag_name))?;
+    let commit = reference.peel_to_commit()?;
+
+    let (message, tagger, time) = match reference.peel_to_tag() {
+        Ok(tag) => {
+            let tagger = tag.tagger();
+            (
+                tag.message().unwrap_or_default().trim().to_string(),
+                tagger.as_ref().and_then(|t| t.name()).map(str::to_string),
+                tagger.map(|t| t.when()).unwrap_or_else(|| commit.time()),
+            )
+        }
+        Err(_) => (
+            commit.message().unwrap_or_default().trim().to_string(),
+            None,
+            commit.time(),
+        ),
+    };
+
+    Ok(VersionTag {
+        name: name.to_string(),
+        commit_id: commit.id().to_string(),
+        message,
+        tagger,
+        created_at: DateTime::<Utc>::from_timestamp(time.seconds(), 0).unwrap_or_default(),
+    })
+}
+
+/// The commit HEAD points at, or `None` before the first commit
+fn head_commit(repo: &Repository) -> Result<Option<git2::Commit<'_>>> {
+    match repo.head() {
+        Ok(head) => Ok(Some(head.peel_to_commit()?)),
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// ID of the commit HEAD points at, or `None` before the first commit
+pub fn head_revision(repo: &Repository) -> Result<Option<String>> {
+    Ok(head_commit(repo)?.map(|commit| commit.id().to_string()))
+}
+
+/// Paths of the repository's submodules (e.g. a shared recipe pack), relative to the workdir
+pub fn submodule_paths(repo: &Repository) -> Result<Vec<String>> {
+    Ok(repo
+        .submodules()?
+        .iter()
+        .map(|submodule| submodule.path().to_string_lossy().to_string())
+        .collect())
+}
+
+/// The submodule containing `rel_path`, opened, with the path relative to its workdir
+///
+/// `None` if the path is not inside a checked-out submodule.
+pub fn submodule_for(repo: &Repository, rel_path: &str) -> Result<Option<(Repository, String)>> {
+    for submodule in repo.submodules()? {
+        let dir = submodule.path().to_string_lossy().to_string();
+        let Some(inner) = rel_path
+            .strip_prefix(dir.as_str())
+            .and_then(|rest| rest.strip_prefix('/'))
+        else {
+            continue;
+        };
+        return match submodule.open() {
+            Ok(opened) => Ok(Some((opened, inner.to_string()))),
+            Err(_) => Ok(None),
+        };
+    }
+    Ok(None)
+}
+
+/// The most recent commit reachable from HEAD that changed each of `rel_paths`
+///
+/// Paths not in HEAD (never committed, or deleted) are left out. A merge counts as
+/// changing a file only if its version differs from every parent's. History is walked
+/// once for all paths and only as far back as the oldest of them needs.
+pub fn last_commits_for_paths<'r>(
+    repo: &'r Repository,
+    rel_paths: &[String],
+) -> Result<HashMap<String, git2::Commit<'r>>> {
+    let mut found = HashMap::new();
+    let Some(head) = head_commit(repo)? else {
+        return Ok(found);
+    };
+    let head_tree = head.tree()?;
+    let mut pending: HashSet<&str> = rel_paths
+        .iter()
+        .map(String::as_str)
+        .filter(|path| head_tree.get_path(Path::new(path)).is_ok())
+        .collect();
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(head.id())?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+    for oid in revwalk {
+        if pending.is_empty() {
+            break;
+        }
+        let commit = repo.find_commit(oid?)?;
+        let tree = commit.tree()?;
+
+        // Paths that differ from every parent; `None` for a root commit
+        let mut changed: Option<HashSet<String>> = None;
+        for parent in commit.parents() {
+            let diff = repo.diff_tree_to_tree(Some(&parent.tree()?), Some(&tree), None)?;
+            let paths: HashSet<String> = diff
+                .deltas()
+                .filter(|delta| delta.status() != git2::Delta::Deleted)
+                .filter_map(|delta| delta.new_file().path())
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect();
+            changed = Some(match changed {
+                None => paths,
+                Some(changed) => changed.intersection(&paths).cloned().collect(),
+            });
+        }
+
+        let hits: Vec<&str> = pending
+            .iter()
+            .copied()
+            .filter(|path| match &changed {
+                Some(changed) => changed.contains(*path),
+                None => tree.get_path(Path::new(path)).is_ok(),
+            })
+            .collect();
+        for path in hits {
+            pending.remove(path);
+            found.insert(path.to_string(), commit.clone());
+        }
+    }
+    Ok(found)
+}
+
+/// `.cook` files in the working directory that differ from `revision`
+///
+/// Covers commits made since `revision` as well as uncommitted and untracked files.
+/// Renames show up as a deletion plus an addition. Returns `None` if `revision` is not
+/// a commit in this repository (e.g. history was rewritten), or if a submodule changed,
+/// since the diff does not look inside submodules.
+pub fn changed_files(repo: &Repository, revision: &str) -> Result<Option<FileChanges>> {
+    let Ok(oid) = git2::Oid::from_str(revision) else {
+        return Ok(None);
+    };
+    let tree = match repo.find_commit(oid) {
+        Ok(commit) => commit.tree()?,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut opts = git2::DiffOptions::new();
+    opts.include_untracked(true).recurse_untracked_dirs(true);
+    let diff = repo.diff_tree_to_workdir_with_index(Some(&tree), Some(&mut opts))?;
+
+    if diff.deltas().any(|delta| {
+        delta.old_file().mode() == git2::FileMode::Commit
+            || delta.new_file().mode() == git2::FileMode::Commit
+    }) {
+        return Ok(None);
+    }
+
+    let mut changes = FileChanges::default();
+    for delta in diff.deltas() {
+        let (file, deleted) = match delta.status() {
+            git2::Delta::Deleted => (delta.old_file(), true),
+            _ => (delta.new_file(), false),
+        };
+        let Some(path) = file.path() else {
+            continue;
+        };
+        if !is_recipe_path(path) {
+            continue;
+        }
+
+        let path = path.to_string_lossy().to_string();
+        if deleted {
+            changes.deleted.push(path);
+        } else {
+            changes.updated.push(path);
+        }
+    }
+
+    Ok(Some(changes))
+}
+
+/// A `.cook` file outside hidden directories (the files discovery indexes)
+fn is_recipe_path(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()) == Some("cook")
+        && !path
+            .components()
+            .any(|part| part.as_os_str().to_string_lossy().starts_with('.'))
+}
+
+/// Attribute each line of a file in the working directory to the commit that last changed it
+///
+/// Lines changed since the last commit (or in a file that was never committed) have no commit.
+pub fn blame_file(repo: &Repository, rel_path: &str) -> Result<Vec<BlameLine>> {
+    let content = read_file(repo, rel_path)?;
+
+    let committed = match head_commit(repo)? {
+        Some(_) => match repo.blame_file(Path::new(rel_path), None) {
+            Ok(blame) => Some(blame),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => None,
+            Err(e) => return Err(e.into()),
+        },
+        None => None,
+    };
+    let blame = match &committed {
+        Some(blame) => Some(blame.blame_buffer(content.as_bytes())?),
+        None => None,
+    };
+
+    let mut summaries: HashMap<git2::Oid, Option<String>> = HashMap::new();
+    let mut lines = Vec::new();
+
+    for (index, text) in content.lines().enumerate() {
+        let mut line = BlameLine {
+            line: index + 1,
+            content: text.to_string(),
+            commit_id: None,
+            author: None,
+            committed_at: None,
+            summary: None,
+        };
+
+        let hunk = blame.as_ref().and_then(|blame| blame.get_line(index + 1));
+        if let Some(hunk) = hunk.filter(|hunk| !hunk.final_commit_id().is_zero()) {
+            let commit_id = hunk.final_commit_id();
+            let signature = hunk.final_signature();
+
+            let summary = match summaries.entry(commit_id) {
+                Entry::Occupied(entry) => entry.get().clone(),
+                Entry::Vacant(entry) => entry
+                    .insert(repo.find_commit(commit_id)?.summary().map(str::to_string))
+                    .clone(),
+            };
+
+            line.commit_id = Some(commit_id.to_string());
+            line.author = signature.name().map(str::to_string);
+            line.committed_at = DateTime::<Utc>::from_timestamp(signature.when().seconds(), 0);
+            line.summary = summary;
+        }
+
+        lines.push(line);
+    }
+
+    Ok(lines)
+}
+
+/// Object counts around a `git gc` run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GcReport {
+    pub loose_objects_before: usize,
+    pub loose_objects_after: usize,
+    pub packs_after: usize,
+    pub duration_ms: u64,
+}
+
+/// Pack loose objects and prune unreachable ones with `git gc`
+///
+/// libgit2 cannot garbage-collect, so this runs the `git` CLI in the working
+/// directory. `git gc` is safe to run alongside commits made through libgit2:
+/// it only prunes unreachable objects older than `gc.pruneExpire` (two weeks).
+pub fn gc(repo: &Repository) -> Result<GcReport> {
     let workdir = repo
         .workdir()
         .context("Repository has no working directory")?;
+    let objects_dir = repo.path().join("objects");
 
-    let mut cook_files = Vec::new();
+    let started = std::time::Instant::now();
+    let loose_objects_before = count_loose_objects(&objects_dir);
 
-    for entry in walkdir::WalkDir::new(workdir)
-        .into_iter()
-        .filter_map(|e| e.ok())
-    {
-        if entry.path().extension().and_then(|s| s.to_str()) == Some("cook") {
-            let relative_path = entry
-                .path()
-                .strip_prefix(workdir)?
-                .to_string_lossy()
-                .to_string();
-            cook_files.push(relative_path);
-        }
+    let output = std::process::Command::new("git")
+        .args(["gc", "--quiet"])
+        .current_dir(workdir)
+        .output()
+        .context("Failed to run git gc (is git installed?)")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git gc failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
     }
 
-    Ok(cook_files)
+    let packs_after = std::fs::read_dir(objects_dir.join("pack"))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| {
+                    entry.path().extension().and_then(|ext| ext.to_str()) == Some("pack")
+                })
+                .count()
+        })
+        .unwrap_or(0);
+
+    Ok(GcReport {
+        loose_objects_before,
+        loose_objects_after: count_loose_objects(&objects_dir),
+        packs_after,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// Loose objects live in `objects/{2 hex chars}/{38 hex chars}`
+fn count_loose_objects(objects_dir: &Path) -> usize {
+    let Ok(entries) = std::fs::read_dir(objects_dir) else {
+        return 0;
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.len() == 2 && name.chars().all(|c| c.is_ascii_hexdigit())
+        })
+        .map(|entry| {
+            std::fs::read_dir(entry.path())
+                .map(|files| files.count())
+                .unwrap_or(0)
+        })
+        .sum()
+}
+
+/// Read a file from the repository
+pub fn read_file(repo: &Repository, rel_path: &str) -> Result<String> {
+    let file_path = repo
+        .workdir()
+        .context("Repository has no working directory")?
+        .join(rel_path);
+
+    std::fs::read_to_string(&file_path).context(format!("Failed to read file: {}", rel_path))
 }
 
 #[cfg(test)]
@@ -189,6 +996,38 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_last_commits_for_paths() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let repo_path = temp_dir.path().join("recipes");
+        let repo = init_repo(&repo_path)?;
+
+        std::fs::write(repo_path.join("cake.cook"), "# Cake")?;
+        let cake = commit_file(&repo, "cake.cook", "Add cake")?;
+        std::fs::write(repo_path.join("pie.cook"), "# Pie")?;
+        let pie = commit_file(&repo, "pie.cook", "Add pie")?;
+
+        let last = |path: &str| -> Result<Option<git2::Oid>> {
+            let mut found = last_commits_for_paths(&repo, &[path.to_string()])?;
+            Ok(found.remove(path).map(|commit| commit.id()))
+        };
+        assert_eq!(last("cake.cook")?, Some(cake));
+        assert_eq!(last("pie.cook")?, Some(pie));
+        assert_eq!(last("bread.cook")?, None);
+
+        std::fs::write(repo_path.join("cake.cook"), "# Better cake")?;
+        let edit = commit_file(&repo, "cake.cook", "Edit cake")?;
+        assert_eq!(last("cake.cook")?, Some(edit));
+
+        let paths = ["cake.cook", "pie.cook", "bread.cook"].map(str::to_string);
+        let found = last_commits_for_paths(&repo, &paths)?;
+        assert_eq!(found.len(), 2);
+        assert_eq!(found["cake.cook"].id(), edit);
+        assert_eq!(found["pie.cook"].id(), pie);
+
+        Ok(())
+    }
+
     #[test]
     fn test_commit_file() -> Result<()> {
         let temp_dir = TempDir::new()?;
@@ -270,6 +1109,128 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_gc_packs_loose_objects() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let repo_path = temp_dir.path().join("recipes");
+        let repo = init_repo(&repo_path)?;
+
+        for i in 0..3 {
+            std::fs::write(repo_path.join("test.cook"), format!("# Test {}", i))?;
+            commit_file(&repo, "test.cook", "Update test recipe")?;
+        }
+
+        let report = gc(&repo)?;
+        assert!(report.loose_objects_before > 0);
+        assert_eq!(report.loose_objects_after, 0);
+        assert_eq!(report.packs_after, 1);
+
+        // The repository is still usable after packing
+        assert_eq!(read_file(&repo, "test.cook")?, "# Test 2");
+        commit_file(&repo, "test.cook", "No-op commit")?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tag_and_read_version() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let repo_path = temp_dir.path().join("recipes");
+        let repo = init_repo(&repo_path)?;
+        let author = CommitAuthor::default();
+
+        std::fs::write(repo_path.join("test.cook"), "# Original")?;
+        commit_file(&repo, "test.cook", "Add test recipe")?;
+
+        let version = tag_version(&repo, "abc123", "holidays", "test.cook", "Tag", &author)?;
+        assert_eq!(version.name, "holidays");
+        assert_eq!(version.tagger.as_deref(), Some(DEFAULT_AUTHOR_NAME));
+
+        // Later edits don't change the tagged content
+        std::fs::write(repo_path.join("test.cook"), "# Edited")?;
+        commit_file(&repo, "test.cook", "Edit test recipe")?;
+        assert_eq!(
+            read_version(&repo, "abc123", "holidays", "test.cook")?.as_deref(),
+            Some("# Original")
+        );
+        assert!(read_version(&repo, "abc123", "missing", "test.cook")?.is_none());
+
+        let err =
+            tag_version(&repo, "abc123", "holidays", "test.cook", "Tag", &author).unwrap_err();
+        assert!(err.downcast_ref::<VersionExists>().is_some());
+
+        // Versions are scoped to their recipe
+        tag_version(&repo, "def456", "holidays", "test.cook", "Tag", &author)?;
+        assert_eq!(list_versions(&repo, "abc123")?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_blame_file() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let repo_path = temp_dir.path().join("recipes");
+        let repo = init_repo(&repo_path)?;
+
+        // Never committed: every line is unattributed
+        std::fs::write(repo_path.join("test.cook"), "# Test\nAdd @salt{}.\n")?;
+        assert!(blame_file(&repo, "test.cook")?
+            .iter()
+            .all(|line| line.commit_id.is_none()));
+
+        commit_file_with_author(&repo, "test.cook", "Add test recipe", Some("Alice"))?;
+        std::fs::write(repo_path.join("test.cook"), "# Test\nAdd @pepper{}.\n")?;
+        commit_file_with_author(&repo, "test.cook", "Swap salt for pepper", Some("Bob"))?;
+        std::fs::write(
+            repo_path.join("test.cook"),
+            "# Test\nAdd @pepper{}.\nServe.\n",
+        )?;
+
+        let lines = blame_file(&repo, "test.cook")?;
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].author.as_deref(), Some("Alice"));
+        assert_eq!(lines[0].summary.as_deref(), Some("Add test recipe"));
+        assert_eq!(lines[1].author.as_deref(), Some("Bob"));
+        assert_eq!(lines[1].content, "Add @pepper{}.");
+        assert!(lines[2].commit_id.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_changed_files_since_revision() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let repo_path = temp_dir.path().join("recipes");
+        let repo = init_repo(&repo_path)?;
+        assert!(head_revision(&repo)?.is_none());
+
+        std::fs::write(repo_path.join("cake.cook"), "# Cake\n")?;
+        std::fs::write(repo_path.join("pie.cook"), "# Pie\n")?;
+        commit_file(&repo, "cake.cook", "Add cake")?;
+        commit_file(&repo, "pie.cook", "Add pie")?;
+        let revision = head_revision(&repo)?.unwrap();
+
+        // One committed edit, one uncommitted deletion, one untracked file
+        std::fs::write(repo_path.join("cake.cook"), "# Chocolate Cake\n")?;
+        commit_file(&repo, "cake.cook", "Edit cake")?;
+        std::fs::remove_file(repo_path.join("pie.cook"))?;
+        std::fs::create_dir_all(repo_path.join("breads"))?;
+        std::fs::write(repo_path.join("breads/rye.cook"), "# Rye\n")?;
+        std::fs::create_dir_all(repo_path.join(".trash"))?;
+        std::fs::write(repo_path.join(".trash/old.cook"), "# Old\n")?;
+
+        let mut changes = changed_files(&repo, &revision)?.unwrap();
+        changes.updated.sort();
+        assert_eq!(changes.updated, vec!["breads/rye.cook", "cake.cook"]);
+        assert_eq!(changes.deleted, vec!["pie.cook"]);
+
+        // Unknown revisions ask the caller for a full scan
+        assert!(changed_files(&repo, &"0".repeat(40))?.is_none());
+        assert!(changed_files(&repo, "not-a-commit")?.is_none());
+
+        Ok(())
+    }
+
     #[test]
     fn test_read_file() -> Result<()> {
         let temp_dir = TempDir::new()?;
@@ -284,4 +1245,64 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_signed_commits() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let key = temp_dir.path().join("signing_key");
+        let status = Command::new("ssh-keygen")
+            .args(["-q", "-t", "ed25519", "-N", "", "-f"])
+            .arg(&key)
+            .status()?;
+        assert!(status.success());
+        let signing = CommitSigning {
+            format: SigningFormat::Ssh,
+            key: key.to_string_lossy().to_string(),
+        };
+
+        let repo_path = temp_dir.path().join("recipes");
+        let repo = init_repo(&repo_path)?;
+        std::fs::write(repo_path.join("test.cook"), "# Test")?;
+        stage_files(&repo, &["test.cook"])?;
+        let oid = commit_staged_as(&repo, "Add test", &CommitAuthor::default(), Some(&signing))?;
+
+        // HEAD's branch moved to the signed commit, which keeps its message and tree
+        assert_eq!(head_revision(&repo)?, Some(oid.to_string()));
+        assert_eq!(repo.find_commit(oid)?.message(), Some("Add test"));
+        let (signature, _) = repo.extract_signature(&oid, None)?;
+        assert!(signature
+            .as_str()
+            .unwrap()
+            .starts_with("-----BEGIN SSH SIGNATURE-----"));
+
+        let draft = commit_to_branch(
+            &repo,
+            "drafts/abc",
+            "test.cook",
+            "# Draft",
+            "Draft test",
+            &CommitAuthor::default(),
+            Some(&signing),
+        )?;
+        assert_eq!(head_revision(&repo)?, Some(oid.to_string()));
+        assert!(repo.extract_signature(&draft, None).is_ok());
+        assert_eq!(repo.find_commit(draft)?.parent_id(0)?, oid);
+
+        // Unsigned commits carry no signature
+        std::fs::write(repo_path.join("test.cook"), "# Test 2")?;
+        let unsigned = commit_file(&repo, "test.cook", "Update test")?;
+        assert!(repo.extract_signature(&unsigned, None).is_err());
+
+        let bad_key = CommitSigning {
+            format: SigningFormat::Ssh,
+            key: temp_dir
+                .path()
+                .join("missing")
+                .to_string_lossy()
+                .to_string(),
+        };
+        assert!(bad_key.sign("content").is_err());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,438 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::config::StoreConfig;
+use crate::parser::{parse_front_matter, RecipeError};
+use crate::schema::{FrontMatterSchema, SchemaViolation};
+
+/// Recipe content about to be written, as seen by write hooks
+#[derive(Debug, Clone, Serialize)]
+pub struct RecipeWrite {
+    /// `Create`, `Update`, `Copy`, `Retag`, `Normalize`, `Draft` or `Publish`
+    pub action: String,
+    /// Path of the file relative to the data dir
+    pub path: String,
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+}
+
+/// A hook turned a write down
+#[derive(Debug, thiserror::Error)]
+#[error("{hook} rejected the change: {reason}")]
+pub struct WriteRejected {
+    /// Name of the hook, e.g. `banned-words`
+    pub hook: String,
+    pub reason: String,
+}
+
+/// A check that runs before recipe content is written and can veto it
+///
+/// Hooks run in registration order and the first error stops the write. Return
+/// [`WriteRejected`] (or a typed error such as [`SchemaViolation`]) so the client gets
+/// the reason; any other error (say, a hook that cannot run) stops the write too.
+pub trait WriteHook: Send + Sync {
+    /// Name reported when the hook rejects a write
+    fn name(&self) -> &str;
+
+    /// Let `write` go ahead, or fail to stop it
+    fn check(&self, write: &RecipeWrite) -> Result<()>;
+
+    /// Whether the hook calls out to another process or service (and so may be slow)
+    fn is_external(&self) -> bool {
+        false
+    }
+}
+
+/// Hooks configured in `config`, built-ins first
+pub fn from_config(config: &StoreConfig) -> Vec<Arc<dyn WriteHook>> {
+    let mut hooks: Vec<Arc<dyn WriteHook>> = Vec::new();
+    if !config.front_matter_schema.is_empty() {
+        hooks.push(Arc::new(SchemaHook(config.front_matter_schema.clone())));
+    }
+    if config.max_recipe_size_kb > 0 {
+        hooks.push(Arc::new(MaxSizeHook {
+            max_bytes: config.max_recipe_size_kb as usize * 1024,
+        }));
+    }
+    if !config.banned_words.is_empty() {
+        hooks.push(Arc::new(BannedWordsHook::new(&config.banned_words)));
+    }
+
+    let timeout = Duration::from_secs(config.write_hook_timeout_secs);
+    if let Some(command) = &config.write_hook_command {
+        hooks.push(Arc::new(CommandHook {
+            command: command.clone(),
+            timeout,
+        }));
+    }
+    if let Some(url) = &config.write_hook_url {
+        hooks.push(Arc::new(HttpHook {
+            url: url.clone(),
+            timeout,
+        }));
+    }
+    hooks
+}
+
+fn rejected(hook: &str, reason: impl Into<String>) -> anyhow::Error {
+    WriteRejected {
+        hook: hook.to_string(),
+        reason: reason.into(),
+    }
+    .into()
+}
+
+/// Front matter must match `FRONT_MATTER_SCHEMA`
+pub struct SchemaHook(pub FrontMatterSchema);
+
+impl WriteHook for SchemaHook {
+    fn name(&self) -> &str {
+        "schema"
+    }
+
+    fn check(&self, write: &RecipeWrite) -> Result<()> {
+        let front_matter = parse_front_matter(&write.content)
+            .map_err(|e| RecipeError::InvalidFrontMatter(e.to_string()))?;
+        let errors = self.0.validate(&front_matter);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(SchemaViolation(errors).into())
+        }
+    }
+}
+
+/// Recipe files may not be larger than `max_bytes`
+pub struct MaxSizeHook {
+    pub max_bytes: usize,
+}
+
+impl WriteHook for MaxSizeHook {
+    fn name(&self) -> &str {
+        "max-size"
+    }
+
+    fn check(&self, write: &RecipeWrite) -> Result<()> {
+        if write.content.len() > self.max_bytes {
+            return Err(rejected(
+                self.name(),
+                format!(
+                    "recipe is {} bytes, the limit is {}",
+                    write.content.len(),
+                    self.max_bytes
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Recipe content may not contain any of the words (whole words, case-insensitive)
+pub struct BannedWordsHook {
+    words: Vec<String>,
+}
+
+impl BannedWordsHook {
+    pub fn new(words: &[String]) -> Self {
+        BannedWordsHook {
+            words: words.iter().map(|word| word.to_lowercase()).collect(),
+        }
+    }
+}
+
+impl WriteHook for BannedWordsHook {
+    fn name(&self) -> &str {
+        "banned-words"
+    }
+
+    fn check(&self, write: &RecipeWrite) -> Result<()> {
+        let content = write.content.to_lowercase();
+        let found: Vec<&str> = self
+            .words
+            .iter()
+            .filter(|word| {
+                content.match_indices(word.as_str()).any(|(start, _)| {
+                    let end = start + word.len();
+                    !content[..start].ends_with(char::is_alphanumeric)
+                        && !content[end..].starts_with(char::is_alphanumeric)
+                })
+            })
+            .map(String::as_str)
+            .collect();
+        if found.is_empty() {
+            Ok(())
+        } else {
+            Err(rejected(
+                self.name(),
+                format!("contains banned words: {}", found.join(", ")),
+            ))
+        }
+    }
+}
+
+/// Runs a shell command with the content on stdin; a non-zero exit rejects the write
+///
+/// `COOKLANG_ACTION`, `COOKLANG_PATH` and `COOKLANG_AUTHOR` describe the write, and the
+/// first line the command prints (stdout, else stderr) is the reason.
+pub struct CommandHook {
+    pub command: String,
+    pub timeout: Duration,
+}
+
+impl WriteHook for CommandHook {
+    fn name(&self) -> &str {
+        "command"
+    }
+
+    fn check(&self, write: &RecipeWrite) -> Result<()> {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(&self.command)
+            .env("COOKLANG_ACTION", &write.action)
+            .env("COOKLANG_PATH", &write.path)
+            .env(
+                "COOKLANG_AUTHOR",
+                write.author.as_deref().unwrap_or_default(),
+            )
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to run write hook command")?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // A command that exits without reading its input is fine
+            let _ = stdin.write_all(write.content.as_bytes());
+        }
+
+        let started = Instant::now();
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if started.elapsed() >= self.timeout {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(rejected(
+                    self.name(),
+                    format!("timed out after {} s", self.timeout.as_secs()),
+                ));
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        };
+        if status.success() {
+            return Ok(());
+        }
+
+        let mut output = String::new();
+        if let Some(mut stdout) = child.stdout.take() {
+            let _ = stdout.read_to_string(&mut output);
+        }
+        if output.trim().is_empty() {
+            if let Some(mut stderr) = child.stderr.take() {
+                let _ = stderr.read_to_string(&mut output);
+            }
+        }
+        let reason = output
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("command exited with {}", status));
+        Err(rejected(self.name(), reason))
+    }
+
+    fn is_external(&self) -> bool {
+        true
+    }
+}
+
+/// POSTs the write as JSON to a URL; any status but 2xx rejects it
+///
+/// The reason is the response's JSON `reason` field, or its body. Requests go through
+/// the `curl` CLI (installed in the Docker image), so `https://` URLs work.
+pub struct HttpHook {
+    pub url: String,
+    pub timeout: Duration,
+}
+
+impl WriteHook for HttpHook {
+    fn name(&self) -> &str {
+        "http"
+    }
+
+    fn check(&self, write: &RecipeWrite) -> Result<()> {
+        let body = serde_json::to_vec(write)?;
+        let mut child = Command::new("curl")
+            .args(["--silent", "--show-error", "--request", "POST"])
+            .args(["--header", "Content-Type: application/json"])
+            .args(["--data-binary", "@-"])
+            .args(["--max-time", &self.timeout.as_secs().max(1).to_string()])
+            .args(["--write-out", "\n%{http_code}"])
+            .arg(&self.url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to run curl for the write hook (is curl installed?)")?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(&body)
+                .context("Failed to send write to hook")?;
+        }
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(rejected(
+                self.name(),
+                format!(
+                    "request failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+            ));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let (response, status) = stdout.rsplit_once('\n').unwrap_or(("", &stdout));
+        if status.trim().starts_with('2') {
+            return Ok(());
+        }
+
+        let reason = serde_json::from_str::<serde_json::Value>(response)
+            .ok()
+            .and_then(|json| json.get("reason")?.as_str().map(str::to_string))
+            .or_else(|| Some(response.trim().to_string()).filter(|body| !body.is_empty()))
+            .unwrap_or_else(|| format!("HTTP {}", status.trim()));
+        Err(rejected(self.name(), reason))
+    }
+
+    fn is_external(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+
+    fn write(content: &str) -> RecipeWrite {
+        RecipeWrite {
+            action: "Create".to_string(),
+            path: "recipes/soup.cook".to_string(),
+            content: content.to_string(),
+            author: Some("Alice".to_string()),
+        }
+    }
+
+    fn rejection(result: Result<()>) -> WriteRejected {
+        result.unwrap_err().downcast::<WriteRejected>().unwrap()
+    }
+
+    #[test]
+    fn test_builtin_hooks() {
+        let config = StoreConfig {
+            front_matter_schema: FrontMatterSchema::parse("servings=integer required"),
+            max_recipe_size_kb: 1,
+            banned_words: vec!["Durian".to_string()],
+            ..Default::default()
+        };
+        let hooks = from_config(&config);
+        let names: Vec<&str> = hooks.iter().map(|hook| hook.name()).collect();
+        assert_eq!(names, vec!["schema", "max-size", "banned-words"]);
+
+        let soup = "---\ntitle: Soup\nservings: 2\n---\n\nSimmer @stock{1%l}.\n";
+        assert!(hooks.iter().all(|hook| hook.check(&write(soup)).is_ok()));
+
+        let error = hooks[0]
+            .check(&write("---\ntitle: Soup\n---\n\nSimmer.\n"))
+            .unwrap_err();
+        assert!(error.is::<SchemaViolation>());
+
+        let rejected = rejection(hooks[1].check(&write(&"a".repeat(1025))));
+        assert_eq!(rejected.hook, "max-size");
+
+        let rejected = rejection(hooks[2].check(&write("Top with @durian{}.")));
+        assert_eq!(rejected.reason, "contains banned words: durian");
+        // Only whole words count
+        assert!(hooks[2].check(&write("Add @durians{}.")).is_ok());
+    }
+
+    #[test]
+    fn test_command_hook() {
+        let hook = CommandHook {
+            command: "if grep -q lard; then echo \"no lard in $COOKLANG_PATH\"; exit 1; fi"
+                .to_string(),
+            timeout: Duration::from_secs(10),
+        };
+        assert!(hook.check(&write("Fry in @butter{}.")).is_ok());
+        let rejected = rejection(hook.check(&write("Fry in @lard{}.")));
+        assert_eq!(rejected.reason, "no lard in recipes/soup.cook");
+
+        let slow = CommandHook {
+            command: "sleep 5".to_string(),
+            timeout: Duration::from_millis(100),
+        };
+        assert!(rejection(slow.check(&write("Soup")))
+            .reason
+            .contains("timed out"));
+    }
+
+    #[test]
+    fn test_http_hook() -> Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}/check", listener.local_addr()?);
+        let server = std::thread::spawn(move || -> Result<Vec<String>> {
+            let mut bodies = Vec::new();
+            for status in ["200 OK", "422 Unprocessable Entity"] {
+                let (stream, _) = listener.accept()?;
+                let mut reader = BufReader::new(stream.try_clone()?);
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line)?;
+                    if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                        length = value.trim().parse()?;
+                    }
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body)?;
+                bodies.push(String::from_utf8(body)?);
+
+                let reply = r#"{"reason":"needs a photo"}"#;
+                write!(
+                    &stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    reply.len(),
+                    reply
+                )?;
+            }
+            Ok(bodies)
+        });
+
+        let hook = HttpHook {
+            url,
+            timeout: Duration::from_secs(10),
+        };
+        assert!(hook.check(&write("Soup")).is_ok());
+        let rejected = rejection(hook.check(&write("Soup")));
+        assert_eq!(rejected.hook, "http");
+        assert_eq!(rejected.reason, "needs a photo");
+
+        let bodies = server.join().unwrap()?;
+        let sent: serde_json::Value = serde_json::from_str(&bodies[0])?;
+        assert_eq!(sent["action"], "Create");
+        assert_eq!(sent["path"], "recipes/soup.cook");
+        assert_eq!(sent["author"], "Alice");
+        Ok(())
+    }
+}
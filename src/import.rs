@@ -0,0 +1,623 @@
+use anyhow::{bail, Result};
+use serde_yaml::{Mapping, Value};
+
+use crate::parser::{generate_filename, parse_quantity};
+use crate::repository::Recipe;
+
+/// Units recognized after the amount of a free-text ingredient line
+const UNITS: &[&str] = &[
+    "g",
+    "gram",
+    "grams",
+    "kg",
+    "kilogram",
+    "kilograms",
+    "mg",
+    "ml",
+    "milliliter",
+    "milliliters",
+    "millilitre",
+    "millilitres",
+    "cl",
+    "dl",
+    "l",
+    "liter",
+    "liters",
+    "litre",
+    "litres",
+    "tsp",
+    "teaspoon",
+    "teaspoons",
+    "tbsp",
+    "tbs",
+    "tablespoon",
+    "tablespoons",
+    "cup",
+    "cups",
+    "oz",
+    "ounce",
+    "ounces",
+    "lb",
+    "lbs",
+    "pound",
+    "pounds",
+    "pint",
+    "pints",
+    "quart",
+    "quarts",
+    "pinch",
+    "pinches",
+    "dash",
+    "dashes",
+    "clove",
+    "cloves",
+    "can",
+    "cans",
+    "package",
+    "packages",
+    "pkg",
+    "slice",
+    "slices",
+    "stick",
+    "sticks",
+    "bunch",
+    "bunches",
+    "handful",
+    "handfuls",
+    "sprig",
+    "sprigs",
+];
+
+/// Characters that can't be part of an ingredient name in Cooklang
+const NAME_STOPS: &[char] = &[
+    '@', '#', '~', '{', '}', '%', '|', '.', ',', ';', ':', '!', '?', '(', ')',
+];
+
+/// A recipe read from another app, before conversion to Cooklang
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportedRecipe {
+    pub title: String,
+    pub description: Option<String>,
+    pub ingredients: Vec<ImportedIngredient>,
+    /// Step texts, without any markup
+    pub steps: Vec<String>,
+    pub servings: Option<String>,
+    pub prep_time: Option<String>,
+    pub cook_time: Option<String>,
+    pub total_time: Option<String>,
+    pub difficulty: Option<String>,
+    pub source: Option<String>,
+    pub notes: Option<String>,
+    /// The first becomes the recipe's category path (nested with `/`), the others tags
+    pub categories: Vec<String>,
+    pub tags: Vec<String>,
+    /// Star rating from 1 to 5
+    pub rating: Option<u8>,
+    /// Fields of the original with no place in Cooklang, e.g. `photo`
+    pub dropped: Vec<String>,
+}
+
+/// An ingredient of an imported recipe
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportedIngredient {
+    pub name: String,
+    /// Amount as Cooklang writes it, e.g. `1.5` or `2/3`
+    pub quantity: Option<String>,
+    pub unit: Option<String>,
+    /// Preparation, e.g. `finely chopped`
+    pub note: Option<String>,
+    /// Index into the recipe's steps of the step the source says uses it
+    pub step: Option<usize>,
+}
+
+impl ImportedIngredient {
+    /// Read a free-text ingredient line such as `1 1/2 cups flour, sifted`
+    ///
+    /// Returns `None` for blank lines and section headings (`For the sauce:`).
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim().trim_start_matches(['-', '*', '•']).trim();
+        if line.is_empty() || line.ends_with(':') {
+            return None;
+        }
+
+        // Split vulgar fractions off whole numbers: `1½` reads as `1 1/2`
+        let mut text = String::new();
+        for c in line.chars() {
+            match vulgar_fraction(c) {
+                Some(fraction) => {
+                    text.push(' ');
+                    text.push_str(fraction);
+                    text.push(' ');
+                }
+                None => text.push(c),
+            }
+        }
+        let mut words: Vec<&str> = text.split_whitespace().collect();
+
+        let amount_len = words
+            .iter()
+            .take(2)
+            .take_while(|word| is_amount(word))
+            .count();
+        let mut quantity = (amount_len > 0)
+            .then(|| cooklang_quantity(&words[..amount_len]))
+            .flatten();
+        words.drain(..amount_len);
+
+        // `a pinch of salt`
+        if quantity.is_none()
+            && words.len() > 2
+            && matches!(words[0], "a" | "an" | "A" | "An")
+            && is_unit(words[1])
+        {
+            quantity = Some("1".to_string());
+            words.remove(0);
+        }
+        let unit = match words.first() {
+            Some(word) if words.len() > 1 && is_unit(word) => {
+                Some(word.trim_end_matches('.').to_string())
+            }
+            _ => None,
+        };
+        if unit.is_some() {
+            words.remove(0);
+        }
+        if words.len() > 1 && words[0].eq_ignore_ascii_case("of") {
+            words.remove(0);
+        }
+
+        let rest = words.join(" ");
+        let (name, note) = match rest.find([',', '(']) {
+            Some(pos) => (&rest[..pos], Some(&rest[pos + 1..])),
+            None => (rest.as_str(), None),
+        };
+        let name: String = name.replace(NAME_STOPS, " ");
+        let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+        if name.is_empty() {
+            return None;
+        }
+        let note = note
+            .map(|note| note.replace(['(', ')'], " "))
+            .map(|note| note.trim_matches([' ', ',']).to_string())
+            .filter(|note| !note.is_empty());
+
+        Some(ImportedIngredient {
+            name,
+            quantity,
+            unit,
+            note,
+            step: None,
+        })
+    }
+
+    /// Cooklang markup for the ingredient, with `text` as its name
+    fn markup(&self, text: &str) -> String {
+        let amount = match (&self.quantity, &self.unit) {
+            (Some(quantity), Some(unit)) => format!("{}%{}", quantity, unit),
+            (Some(quantity), None) => quantity.clone(),
+            (None, _) => String::new(),
+        };
+        let note = self
+            .note
+            .as_ref()
+            .map(|note| format!("({})", note))
+            .unwrap_or_default();
+        format!("@{}{{{}}}{}", text, amount, note)
+    }
+}
+
+/// How faithfully a recipe came through conversion
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Fidelity {
+    pub ingredients: usize,
+    /// Ingredients marked up in the step that uses them
+    pub inline: usize,
+    /// Ingredients no step mentions, marked up in a list step of their own
+    pub listed: usize,
+    /// Fields of the original that were left out
+    pub dropped: Vec<String>,
+}
+
+impl Fidelity {
+    /// Share of ingredients placed where a step uses them (1 without ingredients)
+    pub fn score(&self) -> f64 {
+        if self.ingredients == 0 {
+            return 1.0;
+        }
+        (self.inline as f64 / self.ingredients as f64 * 100.0).round() / 100.0
+    }
+}
+
+/// A recipe converted to Cooklang
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conversion {
+    pub content: String,
+    /// Category path from the first category
+    pub category: Option<String>,
+    pub fidelity: Fidelity,
+}
+
+/// Outcome of importing one recipe
+#[derive(Debug)]
+pub struct ImportOutcome {
+    /// Title, or the file name when the recipe couldn't be read
+    pub name: String,
+    pub result: Result<(Recipe, Fidelity), String>,
+}
+
+/// Convert an imported recipe to Cooklang
+///
+/// Each ingredient is marked up where a step first mentions it (singular or plural,
+/// ignoring case), looking in the step the source links it to first; ingredients no
+/// step mentions are listed in a first step. Metadata
+/// goes into the front matter, where `time`, `prep time` and `cook time` keep the
+/// original's wording.
+pub fn to_cooklang(recipe: &ImportedRecipe) -> Result<Conversion> {
+    let title = recipe.title.trim();
+    if title.is_empty() {
+        bail!("Recipe has no name");
+    }
+
+    // Original index of each step kept, for ingredients linked to a step
+    let (positions, mut steps): (Vec<usize>, Vec<String>) = recipe
+        .steps
+        .iter()
+        .map(|step| plain_text(step))
+        .enumerate()
+        .filter(|(_, step)| !step.is_empty())
+        .unzip();
+    let mut marked: Vec<Vec<(usize, usize)>> = vec![Vec::new(); steps.len()];
+    let mut fidelity = Fidelity {
+        ingredients: recipe.ingredients.len(),
+        dropped: recipe.dropped.clone(),
+        ..Default::default()
+    };
+    let mut unplaced = Vec::new();
+    for ingredient in &recipe.ingredients {
+        let linked = ingredient
+            .step
+            .and_then(|step| positions.iter().position(|&p| p == step));
+        if place(&mut steps, &mut marked, ingredient, linked) {
+            fidelity.inline += 1;
+        } else {
+            unplaced.push(ingredient.markup(&ingredient.name));
+        }
+    }
+    if !unplaced.is_empty() {
+        fidelity.listed = unplaced.len();
+        steps.insert(0, format!("Ingredients: {}.", unplaced.join(", ")));
+    }
+
+    let mut front_matter = Mapping::new();
+    let mut text = |key: &str, value: &Option<String>| {
+        if let Some(value) = value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+            front_matter.insert(key.into(), value.into());
+        }
+    };
+    text("title", &Some(title.to_string()));
+    text("description", &recipe.description);
+    text("servings", &recipe.servings);
+    text("prep time", &recipe.prep_time);
+    text("cook time", &recipe.cook_time);
+    text("time", &recipe.total_time);
+    text("difficulty", &recipe.difficulty);
+    text("source", &recipe.source);
+    text("notes", &recipe.notes);
+
+    let mut tags: Vec<String> = Vec::new();
+    for tag in recipe.categories.iter().skip(1).chain(&recipe.tags) {
+        let tag = tag.trim();
+        if !tag.is_empty() && !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    if !tags.is_empty() {
+        let tags = tags.into_iter().map(Value::from).collect();
+        front_matter.insert("tags".into(), Value::Sequence(tags));
+    }
+
+    let category = recipe
+        .categories
+        .first()
+        .map(|category| {
+            category
+                .split('/')
+                .map(|folder| {
+                    let slug = generate_filename(folder);
+                    slug.trim_end_matches(".cook").to_string()
+                })
+                .filter(|slug| !slug.trim_matches('.').is_empty())
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .filter(|path| !path.is_empty());
+
+    let content = format!(
+        "---\n{}---\n\n{}\n",
+        serde_yaml::to_string(&front_matter)?,
+        steps.join("\n\n")
+    );
+    Ok(Conversion {
+        content,
+        category,
+        fidelity,
+    })
+}
+
+/// Mark up the first mention of an ingredient in the steps, outside earlier markup,
+/// looking in the `linked` step first
+fn place(
+    steps: &mut [String],
+    marked: &mut [Vec<(usize, usize)>],
+    ingredient: &ImportedIngredient,
+    linked: Option<usize>,
+) -> bool {
+    let name = ingredient.name.to_lowercase();
+    let mut candidates = vec![name.clone(), format!("{}s", name), format!("{}es", name)];
+    if let Some(singular) = name.strip_suffix('s').filter(|s| s.len() > 2) {
+        candidates.push(singular.to_string());
+    }
+
+    let order = linked
+        .into_iter()
+        .chain((0..steps.len()).filter(|&index| Some(index) != linked));
+    for index in order {
+        let (step, spans) = (&mut steps[index], &mut marked[index]);
+        // Byte offsets only carry over when lowercasing keeps lengths
+        let lower = step.to_lowercase();
+        let haystack = if lower.len() == step.len() {
+            &lower
+        } else {
+            &*step
+        };
+        let found = candidates.iter().find_map(|candidate| {
+            haystack
+                .match_indices(candidate.as_str())
+                .find_map(|(start, _)| {
+                    let end = start + candidate.len();
+                    let bounded = !haystack[..start].ends_with(char::is_alphanumeric)
+                        && !haystack[end..].starts_with(char::is_alphanumeric);
+                    let free = spans.iter().all(|&(s, e)| end <= s || start >= e);
+                    (bounded && free).then_some((start, end))
+                })
+        });
+
+        if let Some((start, end)) = found {
+            let markup = ingredient.markup(&step[start..end]);
+            let shift = markup.len() as isize - (end - start) as isize;
+            for span in spans.iter_mut().filter(|span| span.0 >= end) {
+                span.0 = (span.0 as isize + shift) as usize;
+                span.1 = (span.1 as isize + shift) as usize;
+            }
+            step.replace_range(start..end, &markup);
+            spans.push((start, start + markup.len()));
+            return true;
+        }
+    }
+    false
+}
+
+/// Step text with anything Cooklang would read as markup made harmless
+///
+/// Leading step numbers (`1.`, `Step 2:`) are dropped, as the steps are numbered anyway.
+fn plain_text(step: &str) -> String {
+    let step = step.split_whitespace().collect::<Vec<_>>().join(" ");
+    let step =
+        step.trim_start_matches(|c: char| c == '>' || c == '=' || c == '-' || c.is_whitespace());
+    let numbered = step
+        .strip_prefix("Step ")
+        .or_else(|| step.strip_prefix("step "))
+        .unwrap_or(step);
+    let digits = numbered.len()
+        - numbered
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .len();
+    let step = match numbered[digits..].strip_prefix(['.', ')', ':']) {
+        Some(rest) if digits > 0 => rest.trim_start(),
+        _ => step,
+    };
+
+    step.replace('@', " at ")
+        .replace('#', "no. ")
+        .replace('~', "about ")
+        .replace('{', "(")
+        .replace('}', ")")
+        .replace("--", "–")
+        .replace("[-", "[ -")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A numeric amount as Cooklang writes it (`0.5`, `2`), rounded to three decimals;
+/// `None` for zero, which apps use for "no amount"
+pub fn decimal_quantity(value: f64) -> Option<String> {
+    if !value.is_finite() || value <= 0.0 {
+        return None;
+    }
+    let text = format!("{:.3}", value);
+    Some(text.trim_end_matches('0').trim_end_matches('.').to_string())
+}
+
+/// Drop `null` fields at every level of an exported recipe, so serde defaults apply
+pub(crate) fn strip_nulls(value: serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+    match value {
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .filter(|(_, field)| !field.is_null())
+                .map(|(key, field)| (key, strip_nulls(field)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(strip_nulls).collect()),
+        value => value,
+    }
+}
+
+/// Whether a word is (part of) an amount: `2`, `1/2`, `0.5`, `2-3`
+fn is_amount(word: &str) -> bool {
+    word.chars().any(|c| c.is_ascii_digit())
+        && word
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '.' | ',' | '/' | '-' | '–'))
+}
+
+fn is_unit(word: &str) -> bool {
+    UNITS.contains(&word.trim_end_matches('.').to_lowercase().as_str())
+}
+
+/// An amount as Cooklang reads it: mixed numbers (`1 1/2`) become a decimal, or an
+/// improper fraction when the decimal would be rounded (`1 1/3` → `4/3`)
+fn cooklang_quantity(words: &[&str]) -> Option<String> {
+    let words: Vec<String> = words
+        .iter()
+        .map(|word| word.replace(',', ".").replace('–', "-"))
+        .collect();
+    if words.len() == 1 {
+        return Some(words[0].clone());
+    }
+
+    let value = parse_quantity(&words.join(" "))?;
+    if (value * 100.0 - (value * 100.0).round()).abs() < 1e-9 {
+        let text = format!("{:.2}", value);
+        return Some(text.trim_end_matches('0').trim_end_matches('.').to_string());
+    }
+    let whole: u32 = words[0].parse().ok()?;
+    let (numerator, denominator) = words[1].split_once('/')?;
+    let (numerator, denominator): (u32, u32) = (numerator.parse().ok()?, denominator.parse().ok()?);
+    Some(format!(
+        "{}/{}",
+        whole * denominator + numerator,
+        denominator
+    ))
+}
+
+fn vulgar_fraction(c: char) -> Option<&'static str> {
+    Some(match c {
+        '½' => "1/2",
+        '⅓' => "1/3",
+        '⅔' => "2/3",
+        '¼' => "1/4",
+        '¾' => "3/4",
+        '⅕' => "1/5",
+        '⅖' => "2/5",
+        '⅗' => "3/5",
+        '⅘' => "4/5",
+        '⅙' => "1/6",
+        '⅚' => "5/6",
+        '⅛' => "1/8",
+        '⅜' => "3/8",
+        '⅝' => "5/8",
+        '⅞' => "7/8",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ingredient(line: &str) -> (String, Option<String>, Option<String>, Option<String>) {
+        let ingredient = ImportedIngredient::parse(line).unwrap();
+        (
+            ingredient.name,
+            ingredient.quantity,
+            ingredient.unit,
+            ingredient.note,
+        )
+    }
+
+    #[test]
+    fn test_parse_ingredient_lines() {
+        let some = |s: &str| Some(s.to_string());
+        assert_eq!(
+            ingredient("1 1/2 cups flour, sifted"),
+            ("flour".into(), some("1.5"), some("cups"), some("sifted"))
+        );
+        assert_eq!(
+            ingredient("1⅓ cup milk"),
+            ("milk".into(), some("4/3"), some("cup"), None)
+        );
+        assert_eq!(
+            ingredient("2 eggs (large)"),
+            ("eggs".into(), some("2"), None, some("large"))
+        );
+        assert_eq!(
+            ingredient("a pinch of salt"),
+            ("salt".into(), some("1"), some("pinch"), None)
+        );
+        assert_eq!(
+            ingredient("- olive oil"),
+            ("olive oil".into(), None, None, None)
+        );
+        assert_eq!(ImportedIngredient::parse("For the sauce:"), None);
+        assert_eq!(ImportedIngredient::parse("  "), None);
+    }
+
+    #[test]
+    fn test_to_cooklang() -> Result<()> {
+        let recipe = ImportedRecipe {
+            title: "Pancakes".to_string(),
+            ingredients: ["2 cups flour", "2 eggs", "a pinch of salt", "1 tsp vanilla"]
+                .into_iter()
+                .filter_map(ImportedIngredient::parse)
+                .collect(),
+            steps: vec![
+                "1. Whisk the Eggs with the flour.".to_string(),
+                "Step 2: Add the salt & bake @ 200 for ~5 min.".to_string(),
+            ],
+            total_time: Some("20 min".to_string()),
+            categories: vec!["Breakfast Dishes".to_string(), "Sweet".to_string()],
+            tags: vec!["sweet".to_string(), "quick".to_string()],
+            dropped: vec!["photo".to_string()],
+            ..Default::default()
+        };
+        let conversion = to_cooklang(&recipe)?;
+        assert_eq!(
+            conversion.content,
+            "---\ntitle: Pancakes\ntime: 20 min\ntags:\n- Sweet\n- quick\n---\n\n\
+             Ingredients: @vanilla{1%tsp}.\n\n\
+             Whisk the @Eggs{2} with the @flour{2%cups}.\n\n\
+             Add the @salt{1%pinch} & bake at 200 for about 5 min.\n"
+        );
+        assert_eq!(conversion.category.as_deref(), Some("breakfast-dishes"));
+        let nested = ImportedRecipe {
+            categories: vec!["Desserts/../Cakes & Tarts".to_string()],
+            ..recipe.clone()
+        };
+        assert_eq!(
+            to_cooklang(&nested)?.category.as_deref(),
+            Some("desserts/cakes-tarts")
+        );
+        assert_eq!(conversion.fidelity.inline, 3);
+        assert_eq!(conversion.fidelity.listed, 1);
+        assert_eq!(conversion.fidelity.score(), 0.75);
+
+        // A linked step wins over an earlier mention; empty steps don't shift links
+        let recipe = ImportedRecipe {
+            title: "Toast".to_string(),
+            ingredients: vec![ImportedIngredient {
+                name: "butter".to_string(),
+                quantity: decimal_quantity(0.5),
+                unit: Some("tbsp".to_string()),
+                step: Some(2),
+                ..Default::default()
+            }],
+            steps: vec![
+                "Soften the butter.".to_string(),
+                " ".to_string(),
+                "Spread the butter.".to_string(),
+            ],
+            ..Default::default()
+        };
+        assert!(to_cooklang(&recipe)?
+            .content
+            .ends_with("Soften the butter.\n\nSpread the @butter{0.5%tbsp}.\n"));
+        assert_eq!(decimal_quantity(1.0 / 3.0).as_deref(), Some("0.333"));
+        assert_eq!(decimal_quantity(0.0), None);
+
+        assert!(to_cooklang(&ImportedRecipe::default()).is_err());
+        Ok(())
+    }
+}
@@ -1,6 +1,39 @@
+pub mod aisles;
+pub mod aliases;
 pub mod api;
+pub mod archive;
 pub mod cache;
+pub mod config;
+pub mod convert;
+pub mod deadline;
+pub mod derived;
+pub mod events;
+pub mod favorites;
 pub mod git;
+pub mod hooks;
+pub mod import;
+pub mod markdown;
+pub mod mealie;
+pub mod menus;
+pub mod metrics;
+pub mod nextcloud;
+pub mod notes;
+pub mod notifications;
+pub mod nutrition;
+pub mod pantry;
+pub mod paprika;
 pub mod parser;
+pub mod qr;
+pub mod quantity;
+pub mod render;
 pub mod repository;
+pub mod schema;
+pub mod search;
+pub mod shares;
+pub mod shopping;
 pub mod storage;
+pub mod synonyms;
+pub mod tandoor;
+pub mod templates;
+pub mod uploads;
+pub mod webhooks;
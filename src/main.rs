@@ -1,9 +1,14 @@
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::sync::Arc;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
 
-use cooklang_store::{api, repository::RecipeRepository};
+use cooklang_store::{
+    api,
+    config::{self, StoreConfig},
+    repository::RecipeRepository,
+};
 
 #[derive(Parser)]
 #[command(name = "cooklang-store")]
@@ -16,57 +21,176 @@ struct Args {
     /// Storage type (disk or git)
     #[arg(short, long, default_value = "disk")]
     storage: String,
+
+    /// Search provider (memory)
+    #[arg(long, default_value = "memory")]
+    search: String,
+
+    /// Log output format
+    #[arg(long, value_enum, default_value = "text")]
+    log_format: LogFormat,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum LogFormat {
+    /// Human-readable lines
+    Text,
+    /// One JSON object per line, for log collectors (Loki, Elasticsearch)
+    Json,
 }
 
 #[tokio::main]
 async fn main() {
     // Load environment variables from .env file if it exists
-    if let Ok(env_file) = std::fs::read_to_string(".env") {
-        for line in env_file.lines() {
-            if let Some((key, value)) = line.split_once('=') {
-                let key = key.trim();
-                let value = value.trim().trim_matches('"');
-                if !key.starts_with('#') && !key.is_empty() {
-                    std::env::set_var(key, value);
-                }
+    config::load_env_file(Path::new(config::ENV_FILE));
+
+    // Parse command-line arguments
+    let args = Args::parse();
+
+    // JSON lines put event fields (`status`, `latency_ms`) at the top level and the
+    // request span's fields (`request_id`, `route`, `recipe_id`) under `span`
+    let log_layer = match args.log_format {
+        LogFormat::Text => tracing_subscriber::fmt::layer().boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(false)
+            .boxed(),
+    };
+    // The filter can be swapped when the config is reloaded
+    let (filter, filter_handle) = tracing_subscriber::reload::Layer::new(
+        EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| "cooklang_store=debug,tower_http=debug".into()),
+    );
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(log_layer)
+        .init();
+    config::set_log_filter_reload(move |filter| {
+        let filter = EnvFilter::try_new(filter).map_err(|e| e.to_string())?;
+        filter_handle.reload(filter).map_err(|e| e.to_string())
+    });
+
+    let repo_path = Path::new(&args.data_dir);
+
+    let config = StoreConfig::from_env();
+
+    let repo =
+        match RecipeRepository::with_config(repo_path, &args.storage, &args.search, config).await {
+            Ok(repo) => {
+                tracing::info!(
+                    "Initialized recipe repository at {:?} with storage type: {}",
+                    repo_path,
+                    args.storage
+                );
+                Arc::new(repo)
+            }
+            Err(e) => {
+                tracing::error!("Failed to initialize repository: {}", e);
+                std::process::exit(1);
+            }
+        };
+
+    // Each named collection gets its own repository, cache and index
+    let mut collections = BTreeMap::new();
+    for (name, data_dir) in &repo.config().collections {
+        let path = Path::new(data_dir);
+        // Collections mirror next to the main mirror rather than inside it
+        let mut config = StoreConfig::clone(&repo.config());
+        config.mirror_path = config
+            .mirror_path
+            .map(|mirror| format!("{}-{}", mirror.trim_end_matches('/'), name));
+        match RecipeRepository::with_config(path, &args.storage, &args.search, config).await {
+            Ok(collection) => {
+                tracing::info!("Initialized collection {} at {:?}", name, path);
+                collections.insert(name.clone(), Arc::new(collection));
+            }
+            Err(e) => {
+                tracing::error!("Failed to initialize collection {}: {}", name, e);
+                std::process::exit(1);
             }
         }
     }
 
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "cooklang_store=debug,tower_http=debug".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    // Periodic cache and upload sweeps, and git gc (git storage only)
+    repo.spawn_maintenance();
+    for collection in collections.values() {
+        collection.spawn_maintenance();
+    }
 
-    // Parse command-line arguments
-    let args = Args::parse();
+    // Re-read the configuration on SIGHUP
+    let repos: Vec<Arc<RecipeRepository>> = std::iter::once(repo.clone())
+        .chain(collections.values().cloned())
+        .collect();
+    tokio::spawn(reload_on_hangup(repos));
 
-    let repo_path = Path::new(&args.data_dir);
+    // Build the app with the repositories
+    let app = api::build_router_with_collections(repo.clone(), collections.clone());
 
-    let repo = match RecipeRepository::with_storage(repo_path, &args.storage).await {
-        Ok(repo) => {
-            tracing::info!(
-                "Initialized recipe repository at {:?} with storage type: {}",
-                repo_path,
-                args.storage
-            );
-            Arc::new(repo)
+    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
+
+    tracing::info!("Server listening on {}", listener.local_addr().unwrap());
+
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await
+        .unwrap();
+
+    // Persist the indexes so the next startup only re-parses changed recipes
+    for repo in std::iter::once(&repo).chain(collections.values()) {
+        if let Err(e) = repo.save_index_snapshot() {
+            tracing::warn!("Failed to save index snapshot: {}", e);
         }
+    }
+}
+
+/// Reload the configuration of every repository each time the process gets SIGHUP
+/// (e.g. `kill -HUP`, `docker kill --signal=HUP`)
+#[cfg(unix)]
+async fn reload_on_hangup(repos: Vec<Arc<RecipeRepository>>) {
+    let mut hangups = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) {
+        Ok(hangups) => hangups,
         Err(e) => {
-            tracing::error!("Failed to initialize repository: {}", e);
-            std::process::exit(1);
+            tracing::warn!("Failed to install SIGHUP handler: {}", e);
+            return;
         }
     };
+    while hangups.recv().await.is_some() {
+        tracing::info!("Reloading configuration");
+        let fresh = StoreConfig::from_env_file(Path::new(config::ENV_FILE));
+        for repo in &repos {
+            repo.reload_config(fresh.clone());
+        }
+    }
+}
 
-    // Build the app with the repository
-    let app = api::build_router(repo);
+#[cfg(not(unix))]
+async fn reload_on_hangup(_repos: Vec<Arc<RecipeRepository>>) {}
 
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
+/// Resolve on Ctrl+C or SIGTERM (e.g. `docker stop`)
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl+C handler");
+    };
 
-    tracing::info!("Server listening on {}", listener.local_addr().unwrap());
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
 
-    axum::serve(listener, app).await.unwrap();
+    tracing::info!("Shutting down");
 }
@@ -0,0 +1,301 @@
+use anyhow::{bail, Result};
+
+use crate::import::{ImportedIngredient, ImportedRecipe};
+
+/// Part of a Markdown recipe a line belongs to, from the heading above it
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Section {
+    /// Between the title and the first section heading
+    Intro,
+    Ingredients,
+    Steps,
+    Notes,
+    /// A heading the importer doesn't know
+    Other,
+}
+
+/// Read a Markdown recipe: a title heading, an ingredients bullet list and numbered
+/// steps, as most recipe sites and note apps write them
+///
+/// Sections are found by their headings (`## Ingredients`, `## Instructions`,
+/// `## Method`, `## Notes`...). Without headings, the first bullet list is taken as
+/// the ingredients and numbered lists as the steps. `Servings: 4`, `Prep time: 10 min`,
+/// `Source: ...` and the like before the first section become metadata, and other text
+/// there the description. Inline formatting and links are reduced to their text.
+pub fn parse(markdown: &str) -> Result<ImportedRecipe> {
+    let mut recipe = ImportedRecipe::default();
+    let mut section = Section::Intro;
+    let mut description = Vec::new();
+    let mut notes = Vec::new();
+    // Whether the last step or note can take continuation lines
+    let mut open_item = false;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            open_item = false;
+            continue;
+        }
+
+        if let Some((level, heading)) = heading(trimmed) {
+            open_item = false;
+            if recipe.title.is_empty() && (level == 1 || section == Section::Intro) {
+                recipe.title = inline_text(heading);
+                continue;
+            }
+            let name = heading.to_lowercase();
+            section = if name.contains("ingredient") {
+                Section::Ingredients
+            } else if ["instruction", "direction", "method", "step", "preparation"]
+                .iter()
+                .any(|word| name.contains(word))
+            {
+                Section::Steps
+            } else if name.contains("note") || name.contains("tip") {
+                Section::Notes
+            } else if section == Section::Ingredients || section == Section::Steps {
+                // `### For the sauce` inside a section
+                section
+            } else {
+                Section::Other
+            };
+            continue;
+        }
+
+        let bullet = bullet_item(trimmed);
+        let numbered = numbered_item(trimmed);
+        let indented = line.starts_with([' ', '\t']);
+        match section {
+            Section::Ingredients => {
+                if let Some(ingredient) =
+                    ImportedIngredient::parse(&inline_text(bullet.or(numbered).unwrap_or(trimmed)))
+                {
+                    recipe.ingredients.push(ingredient);
+                }
+            }
+            Section::Steps => {
+                let text = inline_text(numbered.or(bullet).unwrap_or(trimmed));
+                push_item(&mut recipe.steps, text, open_item && indented);
+                open_item = true;
+            }
+            Section::Notes => {
+                let text = inline_text(bullet.or(numbered).unwrap_or(trimmed));
+                push_item(&mut notes, text, open_item && bullet.is_none());
+                open_item = true;
+            }
+            Section::Intro | Section::Other => {
+                if let Some(item) = numbered {
+                    push_item(&mut recipe.steps, inline_text(item), false);
+                    open_item = true;
+                } else if let Some(item) = bullet {
+                    // Only the first bullet list outside any section: later ones are
+                    // more likely equipment or variations
+                    if recipe.steps.is_empty() && !has_ingredient_heading(markdown) {
+                        recipe
+                            .ingredients
+                            .extend(ImportedIngredient::parse(&inline_text(item)));
+                    }
+                } else if open_item && indented {
+                    push_item(&mut recipe.steps, inline_text(trimmed), true);
+                } else if section == Section::Intro && !metadata(&mut recipe, trimmed) {
+                    description.push(inline_text(trimmed));
+                }
+            }
+        }
+    }
+
+    if recipe.title.is_empty() {
+        bail!("No title: start the recipe with a heading such as '# Pancakes'");
+    }
+    if recipe.ingredients.is_empty() && recipe.steps.is_empty() {
+        bail!("No ingredients or steps found");
+    }
+    recipe.description = Some(description.join(" ")).filter(|text| !text.is_empty());
+    recipe.notes = Some(notes.join("\n")).filter(|text| !text.is_empty());
+    Ok(recipe)
+}
+
+/// Level and text of an ATX heading (`## Ingredients`)
+pub(crate) fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let text = line[level..].strip_prefix(' ')?;
+    (1..=6)
+        .contains(&level)
+        .then(|| (level, text.trim().trim_end_matches('#').trim()))
+}
+
+/// Text of a bullet list item (`- 2 eggs`)
+pub(crate) fn bullet_item(line: &str) -> Option<&str> {
+    let item = line.strip_prefix(['-', '*', '+'])?.strip_prefix(' ')?;
+    // Task list items: `- [ ] 2 eggs`
+    let item = item
+        .strip_prefix("[ ] ")
+        .or_else(|| item.strip_prefix("[x] "))
+        .unwrap_or(item);
+    Some(item.trim())
+}
+
+/// Text of a numbered list item (`1. Whisk` or `1) Whisk`)
+pub(crate) fn numbered_item(line: &str) -> Option<&str> {
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 {
+        return None;
+    }
+    let item = line[digits..].strip_prefix(['.', ')'])?.strip_prefix(' ')?;
+    Some(item.trim())
+}
+
+fn has_ingredient_heading(markdown: &str) -> bool {
+    markdown
+        .lines()
+        .filter_map(|line| heading(line.trim()))
+        .any(|(_, text)| text.to_lowercase().contains("ingredient"))
+}
+
+/// Add a list item, or continue the last one
+fn push_item(items: &mut Vec<String>, text: String, continuation: bool) {
+    match items.last_mut() {
+        Some(last) if continuation => {
+            last.push(' ');
+            last.push_str(&text);
+        }
+        _ => items.push(text),
+    }
+}
+
+/// Take a `Key: value` line such as `Servings: 4` into the recipe's metadata
+pub(crate) fn metadata(recipe: &mut ImportedRecipe, line: &str) -> bool {
+    let line = inline_text(line);
+    let Some((key, value)) = line.split_once(':') else {
+        return false;
+    };
+    let value = Some(value.trim().to_string()).filter(|value| !value.is_empty());
+    let field = match key.trim().to_lowercase().as_str() {
+        "servings" | "serves" | "yield" | "makes" => &mut recipe.servings,
+        "prep time" | "prep" | "preparation time" => &mut recipe.prep_time,
+        "cook time" | "cooking time" | "bake time" => &mut recipe.cook_time,
+        "total time" | "time" | "ready in" => &mut recipe.total_time,
+        "difficulty" => &mut recipe.difficulty,
+        "source" | "from" | "adapted from" | "url" => &mut recipe.source,
+        "tags" | "keywords" => {
+            recipe.tags.extend(
+                value
+                    .iter()
+                    .flat_map(|tags| tags.split(','))
+                    .map(|tag| tag.trim().to_string())
+                    .filter(|tag| !tag.is_empty()),
+            );
+            return true;
+        }
+        _ => return false,
+    };
+    if value.is_none() {
+        return false;
+    }
+    *field = value;
+    true
+}
+
+/// Text of a line without Markdown inline formatting: emphasis, code spans, links and
+/// images become their text (a link's URL is kept only when it is the whole text)
+pub(crate) fn inline_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        // `[label](url)` and `![alt](url)`
+        let link = rest.strip_prefix('!').unwrap_or(rest);
+        if let Some(inner) = link.strip_prefix('[') {
+            if let Some((label, after)) = inner.split_once("](") {
+                if let Some((url, tail)) = after.split_once(')') {
+                    out.push_str(if label.trim().is_empty() { url } else { label });
+                    rest = tail;
+                    continue;
+                }
+            }
+        }
+        if matches!(c, '*' | '_' | '`') {
+            // Emphasis markers; underscores inside words stay
+            let inside_word = c == '_'
+                && out.ends_with(char::is_alphanumeric)
+                && rest[1..].starts_with(char::is_alphanumeric);
+            if !inside_word {
+                rest = &rest[1..];
+                continue;
+            }
+        }
+        out.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    out.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_markdown() -> Result<()> {
+        let recipe = parse(
+            "# Classic **Pancakes**\n\
+             \n\
+             Fluffy and quick, from [Grandma's book](https://example.com).\n\
+             \n\
+             Servings: 4\n\
+             Prep time: 10 min\n\
+             \n\
+             ## Ingredients\n\
+             \n\
+             ### Batter\n\
+             - 1 1/2 cups flour\n\
+             - 2 eggs\n\
+             * a pinch of *salt*\n\
+             \n\
+             ## Instructions\n\
+             \n\
+             1. Whisk the flour with the eggs\n   \
+                until smooth.\n\
+             2) Fry in a hot pan.\n\
+             \n\
+             ## Notes\n\
+             \n\
+             - Freezes well.\n",
+        )?;
+        assert_eq!(recipe.title, "Classic Pancakes");
+        assert_eq!(
+            recipe.description.as_deref(),
+            Some("Fluffy and quick, from Grandma's book.")
+        );
+        assert_eq!(recipe.servings.as_deref(), Some("4"));
+        assert_eq!(recipe.prep_time.as_deref(), Some("10 min"));
+        let names: Vec<&str> = recipe.ingredients.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, vec!["flour", "eggs", "salt"]);
+        assert_eq!(
+            recipe.steps,
+            vec![
+                "Whisk the flour with the eggs until smooth.",
+                "Fry in a hot pan."
+            ]
+        );
+        assert_eq!(recipe.notes.as_deref(), Some("Freezes well."));
+
+        // No section headings: the first bullet list and the numbered list
+        let recipe = parse("## Toast\n\n- 2 slices bread\n- butter\n\n1. Toast the bread.\n2. Spread the butter.\n")?;
+        assert_eq!(recipe.title, "Toast");
+        assert_eq!(recipe.ingredients.len(), 2);
+        assert_eq!(recipe.steps.len(), 2);
+
+        assert!(parse("- 2 eggs\n").is_err());
+        assert!(parse("# Empty\n\nJust words.\n").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_inline_text() {
+        assert_eq!(inline_text("**Bold** and _em_ `code`"), "Bold and em code");
+        assert_eq!(inline_text("snake_case stays"), "snake_case stays");
+        assert_eq!(
+            inline_text("[](https://x.y) ![photo](a.jpg)"),
+            "https://x.y photo"
+        );
+    }
+}
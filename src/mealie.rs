@@ -0,0 +1,560 @@
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+use crate::archive::read_zip;
+use crate::import::{decimal_quantity, strip_nulls, ImportedIngredient, ImportedRecipe};
+
+/// A recipe in the JSON layout of Mealie's API and recipe exports
+///
+/// Only the fields the importer reads; missing ones take their defaults.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MealieRecipe {
+    pub name: String,
+    pub slug: String,
+    pub description: Option<String>,
+    /// Free text, e.g. `4 servings`
+    pub recipe_yield: Option<String>,
+    pub recipe_servings: Option<f64>,
+    pub total_time: Option<String>,
+    pub prep_time: Option<String>,
+    pub cook_time: Option<String>,
+    /// Mealie's name for the cooking time in most versions
+    pub perform_time: Option<String>,
+    #[serde(rename = "orgURL")]
+    pub org_url: Option<String>,
+    pub rating: Option<f64>,
+    pub recipe_category: Vec<MealieTag>,
+    pub tags: Vec<MealieTag>,
+    pub tools: Vec<MealieTag>,
+    pub recipe_ingredient: Vec<MealieIngredient>,
+    pub recipe_instructions: Vec<MealieInstruction>,
+    pub notes: Vec<MealieNote>,
+    pub nutrition: Option<Map<String, Value>>,
+    pub image: Option<Value>,
+}
+
+/// A category, tag or tool
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct MealieTag {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MealieIngredient {
+    /// Zero when there is no amount
+    pub quantity: Option<f64>,
+    pub unit: Option<MealieUnit>,
+    pub food: Option<MealieFood>,
+    /// Preparation, or the whole line when the ingredient was never parsed
+    pub note: Option<String>,
+    pub original_text: Option<String>,
+    pub display: Option<String>,
+    /// Section heading starting at this ingredient
+    pub title: Option<String>,
+    /// Linked to from the steps using the ingredient
+    pub reference_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct MealieUnit {
+    pub name: String,
+    pub abbreviation: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct MealieFood {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MealieInstruction {
+    /// Section heading starting at this step
+    pub title: Option<String>,
+    pub text: String,
+    pub ingredient_references: Vec<MealieReference>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MealieReference {
+    pub reference_id: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct MealieNote {
+    pub title: String,
+    pub text: String,
+}
+
+/// Read the recipes of a Mealie export, as `(name, recipe)`
+///
+/// Takes a site backup (a zip with `database.json`) or a zip of recipe JSON files as
+/// the API returns them. Only an unreadable archive is an error; a damaged recipe
+/// file is reported in its place so the others can still be imported.
+pub fn read_archive(bytes: &[u8]) -> Result<Vec<(String, Result<MealieRecipe>)>> {
+    let entries = read_zip(bytes).context("Invalid Mealie export")?;
+    if let Some(database) = entries
+        .iter()
+        .find(|entry| entry.name.rsplit('/').next() == Some("database.json"))
+    {
+        let tables: Map<String, Value> =
+            serde_json::from_slice(&database.data).context("Invalid Mealie backup database")?;
+        return Ok(from_backup(&tables)
+            .into_iter()
+            .map(|recipe| (recipe.name.clone(), Ok(recipe)))
+            .collect());
+    }
+
+    let recipes: Vec<_> = entries
+        .into_iter()
+        .filter(|entry| entry.name.ends_with(".json"))
+        .map(|entry| {
+            let name = entry.name.rsplit('/').next().unwrap_or_default();
+            let name = name.trim_end_matches(".json").to_string();
+            let recipe = from_json(&entry.data).context("Invalid recipe JSON");
+            match recipe {
+                Ok(recipe) if !recipe.name.trim().is_empty() => (recipe.name.clone(), Ok(recipe)),
+                recipe => (name, recipe),
+            }
+        })
+        .collect();
+    if recipes.is_empty() {
+        bail!("Not a Mealie export: no database.json or recipe JSON files");
+    }
+    Ok(recipes)
+}
+
+/// Assemble the recipes of a site backup from its database tables
+///
+/// Each table is a list of rows keyed by column name. Recipes are returned in the
+/// order of the `recipes` table, their ingredients and steps by position.
+fn from_backup(tables: &Map<String, Value>) -> Vec<MealieRecipe> {
+    let rows = |table: &str| -> Vec<&Map<String, Value>> {
+        tables
+            .get(table)
+            .and_then(Value::as_array)
+            .map(|rows| rows.iter().filter_map(Value::as_object).collect())
+            .unwrap_or_default()
+    };
+    let text = |row: &Map<String, Value>, column: &str| -> Option<String> {
+        match row.get(column)? {
+            Value::String(value) => Some(value.clone()),
+            Value::Number(value) => Some(value.to_string()),
+            _ => None,
+        }
+    };
+    let number = |row: &Map<String, Value>, column: &str| row.get(column).and_then(Value::as_f64);
+    let names = |table: &str| -> HashMap<String, String> {
+        rows(table)
+            .into_iter()
+            .filter_map(|row| Some((text(row, "id")?, text(row, "name")?)))
+            .collect()
+    };
+    // Rows of a table grouped by recipe, in `position` order
+    let by_recipe = |table: &str| {
+        let mut grouped: HashMap<String, Vec<&Map<String, Value>>> = HashMap::new();
+        for row in rows(table) {
+            if let Some(recipe_id) = text(row, "recipe_id") {
+                grouped.entry(recipe_id).or_default().push(row);
+            }
+        }
+        for rows in grouped.values_mut() {
+            rows.sort_by_key(|row| row.get("position").and_then(Value::as_i64));
+        }
+        grouped
+    };
+    // Names linked to each recipe through a join table
+    let linked = |join: &str, column: &str, names: &HashMap<String, String>| {
+        let mut linked: HashMap<String, Vec<MealieTag>> = HashMap::new();
+        for row in rows(join) {
+            if let (Some(recipe_id), Some(name)) = (
+                text(row, "recipe_id"),
+                text(row, column).and_then(|id| names.get(&id)),
+            ) {
+                linked
+                    .entry(recipe_id)
+                    .or_default()
+                    .push(MealieTag { name: name.clone() });
+            }
+        }
+        linked
+    };
+
+    let foods = names("ingredient_foods");
+    let units: HashMap<String, MealieUnit> = rows("ingredient_units")
+        .into_iter()
+        .filter_map(|row| {
+            let unit = MealieUnit {
+                name: text(row, "name")?,
+                abbreviation: text(row, "abbreviation"),
+            };
+            Some((text(row, "id")?, unit))
+        })
+        .collect();
+    let mut categories = linked("recipes_to_categories", "category_id", &names("categories"));
+    let mut tags = linked("recipes_to_tags", "tag_id", &names("tags"));
+    let mut tools = linked("recipes_to_tools", "tool_id", &names("tools"));
+    let mut ingredients = by_recipe("recipes_ingredients");
+    let mut instructions = by_recipe("recipe_instructions");
+    let mut notes = by_recipe("notes");
+    let mut references: HashMap<String, Vec<MealieReference>> = HashMap::new();
+    for row in rows("recipe_ingredient_ref_link") {
+        if let (Some(instruction_id), Some(reference_id)) =
+            (text(row, "instruction_id"), text(row, "reference_id"))
+        {
+            references
+                .entry(instruction_id)
+                .or_default()
+                .push(MealieReference { reference_id });
+        }
+    }
+
+    rows("recipes")
+        .into_iter()
+        .filter_map(|row| {
+            let id = text(row, "id")?;
+            Some(MealieRecipe {
+                name: text(row, "name").unwrap_or_default(),
+                slug: text(row, "slug").unwrap_or_default(),
+                description: text(row, "description"),
+                recipe_yield: text(row, "recipe_yield"),
+                recipe_servings: number(row, "recipe_servings"),
+                total_time: text(row, "total_time"),
+                prep_time: text(row, "prep_time"),
+                cook_time: text(row, "cook_time"),
+                perform_time: text(row, "perform_time"),
+                org_url: text(row, "org_url"),
+                rating: number(row, "rating"),
+                recipe_category: categories.remove(&id).unwrap_or_default(),
+                tags: tags.remove(&id).unwrap_or_default(),
+                tools: tools.remove(&id).unwrap_or_default(),
+                recipe_ingredient: ingredients
+                    .remove(&id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|row| MealieIngredient {
+                        quantity: number(row, "quantity"),
+                        unit: text(row, "unit_id").and_then(|id| units.get(&id).cloned()),
+                        food: text(row, "food_id")
+                            .and_then(|id| foods.get(&id))
+                            .map(|name| MealieFood { name: name.clone() }),
+                        note: text(row, "note"),
+                        original_text: text(row, "original_text"),
+                        display: None,
+                        title: text(row, "title"),
+                        reference_id: text(row, "reference_id"),
+                    })
+                    .collect(),
+                recipe_instructions: instructions
+                    .remove(&id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|row| MealieInstruction {
+                        title: text(row, "title"),
+                        text: text(row, "text").unwrap_or_default(),
+                        ingredient_references: text(row, "id")
+                            .and_then(|id| references.remove(&id))
+                            .unwrap_or_default(),
+                    })
+                    .collect(),
+                notes: notes
+                    .remove(&id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|row| MealieNote {
+                        title: text(row, "title").unwrap_or_default(),
+                        text: text(row, "text").unwrap_or_default(),
+                    })
+                    .collect(),
+                nutrition: None,
+                image: row.get("image").filter(|image| !image.is_null()).cloned(),
+            })
+        })
+        .collect()
+}
+
+/// Parse a recipe file, where Mealie writes `null` for anything unset
+fn from_json(json: &[u8]) -> Result<MealieRecipe> {
+    let value: Value = serde_json::from_slice(json)?;
+    if !value.is_object() {
+        bail!("expected a recipe object");
+    }
+    Ok(serde_json::from_value(strip_nulls(value))?)
+}
+
+/// Map a Mealie recipe onto the importer's model
+///
+/// Parsed ingredients keep Mealie's food, amount and unit, and the step each is linked
+/// to; unparsed ones are read from their text. Section titles start their first step.
+/// The image, tools and nutrition are left out.
+pub fn to_imported(recipe: MealieRecipe) -> ImportedRecipe {
+    let text = |value: Option<String>| {
+        value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let mut dropped = Vec::new();
+    if recipe.image.is_some() {
+        dropped.push("image".to_string());
+    }
+    if !recipe.tools.is_empty() {
+        dropped.push("tools".to_string());
+    }
+    if recipe
+        .nutrition
+        .as_ref()
+        .is_some_and(|nutrition| nutrition.values().any(|value| !value.is_null()))
+    {
+        dropped.push("nutrition".to_string());
+    }
+
+    let mut steps_by_reference = HashMap::new();
+    let steps = recipe
+        .recipe_instructions
+        .into_iter()
+        .enumerate()
+        .map(|(index, instruction)| {
+            for reference in instruction.ingredient_references {
+                steps_by_reference
+                    .entry(reference.reference_id)
+                    .or_insert(index);
+            }
+            match text(instruction.title) {
+                Some(title) => format!("{}: {}", title, instruction.text.trim()),
+                None => instruction.text,
+            }
+        })
+        .collect();
+
+    let ingredients = recipe
+        .recipe_ingredient
+        .into_iter()
+        .filter_map(|ingredient| {
+            let step = ingredient
+                .reference_id
+                .as_ref()
+                .and_then(|id| steps_by_reference.get(id).copied());
+            let quantity = ingredient.quantity.and_then(decimal_quantity);
+            let parsed = match ingredient.food.and_then(|food| text(Some(food.name))) {
+                Some(name) => ImportedIngredient {
+                    name,
+                    quantity,
+                    unit: ingredient
+                        .unit
+                        .and_then(|unit| text(unit.abbreviation).or_else(|| text(Some(unit.name)))),
+                    note: text(ingredient.note),
+                    step: None,
+                },
+                None => {
+                    let line = text(ingredient.original_text)
+                        .or_else(|| text(ingredient.note))
+                        .or_else(|| text(ingredient.display))?;
+                    let mut parsed = ImportedIngredient::parse(&line)?;
+                    parsed.quantity = parsed.quantity.or(quantity);
+                    parsed
+                }
+            };
+            Some(ImportedIngredient { step, ..parsed })
+        })
+        .collect();
+
+    let notes: Vec<String> = recipe
+        .notes
+        .into_iter()
+        .filter_map(
+            |note| match (text(Some(note.title)), text(Some(note.text))) {
+                (Some(title), Some(body)) => Some(format!("{}: {}", title, body)),
+                (title, body) => body.or(title),
+            },
+        )
+        .collect();
+
+    ImportedRecipe {
+        title: recipe.name,
+        description: text(recipe.description),
+        ingredients,
+        steps,
+        servings: text(recipe.recipe_yield)
+            .or_else(|| recipe.recipe_servings.and_then(decimal_quantity)),
+        prep_time: text(recipe.prep_time),
+        cook_time: text(recipe.cook_time).or_else(|| text(recipe.perform_time)),
+        total_time: text(recipe.total_time),
+        difficulty: None,
+        source: text(recipe.org_url),
+        notes: Some(notes.join("\n\n")).filter(|notes| !notes.is_empty()),
+        categories: recipe.recipe_category.into_iter().map(|c| c.name).collect(),
+        tags: recipe.tags.into_iter().map(|tag| tag.name).collect(),
+        rating: recipe
+            .rating
+            .map(|rating| rating.round() as u8)
+            .filter(|rating| (1..=5).contains(rating)),
+        dropped,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::{write_zip, ZipEntry};
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn zip(files: &[(&str, Value)]) -> Vec<u8> {
+        let entries: Vec<ZipEntry> = files
+            .iter()
+            .map(|(name, json)| ZipEntry {
+                name: name.to_string(),
+                data: serde_json::to_vec(json).unwrap(),
+            })
+            .collect();
+        write_zip(&entries, Utc::now()).unwrap()
+    }
+
+    #[test]
+    fn test_read_recipe_json() -> Result<()> {
+        let archive = zip(&[
+            (
+                "pancakes/pancakes.json",
+                json!({
+                    "name": "Pancakes",
+                    "description": null,
+                    "recipeYield": "4 servings",
+                    "performTime": "10 minutes",
+                    "orgURL": "https://example.com/pancakes",
+                    "rating": 4.0,
+                    "recipeCategory": [{ "name": "Breakfast" }],
+                    "tags": [{ "name": "Quick" }],
+                    "tools": null,
+                    "recipeIngredient": [
+                        {
+                            "quantity": 1.5,
+                            "unit": { "name": "cup", "abbreviation": "" },
+                            "food": { "name": "flour" },
+                            "note": "sifted",
+                            "referenceId": "a"
+                        },
+                        { "quantity": 0, "food": null, "note": "2 eggs", "referenceId": "b" }
+                    ],
+                    "recipeInstructions": [
+                        { "text": "Sift the flour.", "ingredientReferences": [] },
+                        {
+                            "title": "Batter",
+                            "text": "Whisk the eggs into the flour.",
+                            "ingredientReferences": [{ "referenceId": "a" }]
+                        }
+                    ],
+                    "notes": [{ "title": "Tip", "text": "Rest the batter." }],
+                    "nutrition": { "calories": "250", "fatContent": null },
+                    "image": "abc"
+                }),
+            ),
+            ("broken.json", json!(["not", "a", "recipe"])),
+        ]);
+
+        let recipes = read_archive(&archive)?;
+        assert_eq!(recipes.len(), 2);
+        assert_eq!(recipes[1].0, "broken");
+        assert!(recipes[1].1.is_err());
+
+        let recipe = to_imported(recipes.into_iter().next().unwrap().1?);
+        assert_eq!(recipe.title, "Pancakes");
+        assert_eq!(recipe.servings.as_deref(), Some("4 servings"));
+        assert_eq!(recipe.cook_time.as_deref(), Some("10 minutes"));
+        assert_eq!(
+            recipe.source.as_deref(),
+            Some("https://example.com/pancakes")
+        );
+        assert_eq!(recipe.notes.as_deref(), Some("Tip: Rest the batter."));
+        assert_eq!(recipe.categories, vec!["Breakfast"]);
+        assert_eq!(recipe.tags, vec!["Quick"]);
+        assert_eq!(recipe.rating, Some(4));
+        assert_eq!(recipe.dropped, vec!["image", "nutrition"]);
+        assert_eq!(recipe.steps[1], "Batter: Whisk the eggs into the flour.");
+        assert_eq!(
+            recipe.ingredients[0],
+            ImportedIngredient {
+                name: "flour".to_string(),
+                quantity: Some("1.5".to_string()),
+                unit: Some("cup".to_string()),
+                note: Some("sifted".to_string()),
+                step: Some(1),
+            }
+        );
+        assert_eq!(recipe.ingredients[1].name, "eggs");
+        assert_eq!(recipe.ingredients[1].quantity.as_deref(), Some("2"));
+        assert_eq!(recipe.ingredients[1].step, None);
+
+        assert!(read_archive(&zip(&[("readme.txt", json!("hi"))])).is_err());
+        assert!(read_archive(b"not a zip").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_backup() -> Result<()> {
+        let archive = zip(&[(
+            "database.json",
+            json!({
+                "recipes": [
+                    {
+                        "id": "r1",
+                        "name": "Soup",
+                        "recipe_yield": null,
+                        "recipe_servings": 2.0,
+                        "perform_time": "30 minutes",
+                        "image": null
+                    }
+                ],
+                "ingredient_foods": [{ "id": "f1", "name": "carrot" }],
+                "ingredient_units": [{ "id": "u1", "name": "gram", "abbreviation": "g" }],
+                "recipes_ingredients": [
+                    { "recipe_id": "r1", "position": 1, "quantity": 1.0, "note": "salt" },
+                    {
+                        "recipe_id": "r1",
+                        "position": 0,
+                        "quantity": 200.0,
+                        "unit_id": "u1",
+                        "food_id": "f1",
+                        "reference_id": "ref"
+                    }
+                ],
+                "recipe_instructions": [
+                    { "id": "i2", "recipe_id": "r1", "position": 1, "text": "Simmer." },
+                    { "id": "i1", "recipe_id": "r1", "position": 0, "text": "Chop the carrot." }
+                ],
+                "recipe_ingredient_ref_link": [{ "instruction_id": "i1", "reference_id": "ref" }],
+                "categories": [{ "id": "c1", "name": "Soups" }],
+                "recipes_to_categories": [{ "recipe_id": "r1", "category_id": "c1" }],
+                "tags": [{ "id": "t1", "name": "Vegan" }],
+                "recipes_to_tags": [{ "recipe_id": "r1", "tag_id": "t1" }],
+                "notes": [{ "recipe_id": "r1", "title": "", "text": "Freezes well." }]
+            }),
+        )]);
+
+        let recipes = read_archive(&archive)?;
+        assert_eq!(recipes.len(), 1);
+        assert_eq!(recipes[0].0, "Soup");
+        let recipe = to_imported(recipes.into_iter().next().unwrap().1?);
+        assert_eq!(recipe.steps, vec!["Chop the carrot.", "Simmer."]);
+        assert_eq!(recipe.servings.as_deref(), Some("2"));
+        assert_eq!(recipe.cook_time.as_deref(), Some("30 minutes"));
+        assert_eq!(recipe.categories, vec!["Soups"]);
+        assert_eq!(recipe.tags, vec!["Vegan"]);
+        assert_eq!(recipe.notes.as_deref(), Some("Freezes well."));
+        assert_eq!(recipe.ingredients[0].name, "carrot");
+        assert_eq!(recipe.ingredients[0].unit.as_deref(), Some("g"));
+        assert_eq!(recipe.ingredients[0].step, Some(0));
+        assert_eq!(recipe.ingredients[1].name, "salt");
+        assert_eq!(recipe.ingredients[1].quantity.as_deref(), Some("1"));
+        assert!(recipe.dropped.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,242 @@
+use crate::parser::{
+    extract_metadata, extract_recipe_title, generate_filename, parse_quantity, strip_front_matter,
+};
+
+/// A Cooklang menu file (`*.menu`): recipe references grouped into sections
+#[derive(Debug, Clone, PartialEq)]
+pub struct Menu {
+    pub git_path: String,
+    /// Front matter `title`, if any
+    pub title: Option<String>,
+    /// Front matter `servings`, as written
+    pub servings: Option<String>,
+    pub sections: Vec<MenuSection>,
+}
+
+/// A `== Day 1 ==` section of a menu (unnamed for references before the first header)
+#[derive(Debug, Clone, PartialEq)]
+pub struct MenuSection {
+    pub name: Option<String>,
+    pub items: Vec<MenuItem>,
+}
+
+/// A recipe reference such as `@./Breakfast/Easy Pancakes{2}`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MenuItem {
+    /// The `Breakfast:` style label the reference appears under, if any
+    pub meal: Option<String>,
+    /// Referenced path as written, without the leading `@` (e.g. `./Breakfast/Easy Pancakes`)
+    pub reference: String,
+    /// Quantity in braces (usually servings), if numeric
+    pub quantity: Option<f64>,
+    pub unit: Option<String>,
+}
+
+/// Parse a menu file
+///
+/// Lines starting with `=` open a section, a line ending in `:` without references sets
+/// the meal label for the references below it, and every `@./path{..}` (or `@../path{..}`)
+/// is an item.
+pub fn parse_menu(git_path: &str, content: &str) -> Menu {
+    let mut sections = vec![MenuSection {
+        name: None,
+        items: Vec::new(),
+    }];
+    let mut meal = None;
+
+    for line in strip_front_matter(content).lines() {
+        let line = line.split("--").next().unwrap_or_default().trim();
+        if line.starts_with(">>") {
+            continue;
+        }
+
+        if line.starts_with('=') {
+            let name = line.trim_matches(|c: char| c == '=' || c.is_whitespace());
+            sections.push(MenuSection {
+                name: (!name.is_empty()).then(|| name.to_string()),
+                items: Vec::new(),
+            });
+            meal = None;
+            continue;
+        }
+
+        let references = recipe_references(line);
+        if references.is_empty() {
+            if let Some(label) = line.trim_start_matches(['-', '*']).trim().strip_suffix(':') {
+                meal = Some(label.trim().to_string()).filter(|label| !label.is_empty());
+            }
+            continue;
+        }
+
+        let section = sections.last_mut().expect("menu always has a section");
+        section
+            .items
+            .extend(references.into_iter().map(|(reference, amount)| {
+                let (quantity, unit) = match amount.split_once('%') {
+                    Some((quantity, unit)) => (quantity, Some(unit.trim())),
+                    None => (amount, None),
+                };
+                MenuItem {
+                    meal: meal.clone(),
+                    reference: reference.to_string(),
+                    quantity: parse_quantity(quantity),
+                    unit: unit.filter(|u| !u.is_empty()).map(str::to_string),
+                }
+            }));
+    }
+
+    // Drop the implicit leading section when everything is under headers
+    if sections.len() > 1 && sections[0].items.is_empty() {
+        sections.remove(0);
+    }
+
+    Menu {
+        git_path: git_path.to_string(),
+        title: extract_recipe_title(content).ok(),
+        servings: extract_metadata(content).servings,
+        sections,
+    }
+}
+
+/// `(path, amount)` of each `@./path{amount}` or `@../path{amount}` in a line
+fn recipe_references(line: &str) -> Vec<(&str, &str)> {
+    let mut references = Vec::new();
+    let mut rest = line;
+    while let Some(pos) = rest.find('@') {
+        let after = &rest[pos + 1..];
+        if !(after.starts_with("./") || after.starts_with("../")) {
+            rest = after;
+            continue;
+        }
+
+        // Paths may contain spaces when followed by braces; otherwise they end at whitespace
+        let next_marker = after.find('@').unwrap_or(after.len());
+        let (path, amount, tail) = match after[..next_marker].find('{') {
+            Some(open) => match after[open..].find('}') {
+                Some(close) => (
+                    &after[..open],
+                    &after[open + 1..open + close],
+                    &after[open + close + 1..],
+                ),
+                None => (&after[..open], "", &after[open + 1..]),
+            },
+            None => {
+                let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                (&after[..end], "", &after[end..])
+            }
+        };
+
+        let path = path.trim();
+        if path.trim_start_matches(['.', '/']).is_empty() {
+            rest = tail;
+            continue;
+        }
+        references.push((path, amount.trim()));
+        rest = tail;
+    }
+    references
+}
+
+/// Git paths a menu reference may point to, most likely first
+///
+/// References are tried relative to the menu's directory, to `recipes_dir` and to the data
+/// directory, both as written (`Breakfast/Easy Pancakes.cook`) and with the file name
+/// generated from the title (`Breakfast/easy-pancakes.cook`).
+pub fn reference_candidates(menu_path: &str, recipes_dir: &str, reference: &str) -> Vec<String> {
+    let reference = reference.trim().trim_end_matches(".cook");
+    let menu_dir = menu_path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+
+    let mut candidates = Vec::new();
+    for base in [menu_dir, recipes_dir, ""] {
+        let Some(path) = join_relative(base, reference) else {
+            continue;
+        };
+        let (dir, name) = match path.rsplit_once('/') {
+            Some((dir, name)) => (format!("{}/", dir), name),
+            None => (String::new(), path.as_str()),
+        };
+
+        for candidate in [
+            format!("{}{}.cook", dir, name),
+            format!("{}{}", dir, generate_filename(name)),
+        ] {
+            if !candidates.contains(&candidate) {
+                candidates.push(candidate);
+            }
+        }
+    }
+    candidates
+}
+
+/// Resolve `./` and `../` segments of `reference` against `base`; `None` if it escapes the root
+fn join_relative(base: &str, reference: &str) -> Option<String> {
+    let mut segments: Vec<&str> = base.split('/').filter(|s| !s.is_empty()).collect();
+    for segment in reference.split('/') {
+        match segment.trim() {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            segment => segments.push(segment),
+        }
+    }
+    (!segments.is_empty()).then(|| segments.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_menu_sections_and_meals() {
+        let content = "---\ntitle: Week 1\nservings: 4\n---\n\n== Monday ==\n\nBreakfast:\n- @./Breakfast/Easy Pancakes{2}\n\nDinner:\n- @./mains/chili{} with @../sides/rice{1%batch}\n\n== Tuesday ==\n\n- @./mains/soup -- leftovers\n";
+        let menu = parse_menu("menus/week-1.menu", content);
+
+        assert_eq!(menu.title.as_deref(), Some("Week 1"));
+        assert_eq!(menu.servings.as_deref(), Some("4"));
+        assert_eq!(menu.sections.len(), 2);
+
+        let monday = &menu.sections[0];
+        assert_eq!(monday.name.as_deref(), Some("Monday"));
+        assert_eq!(monday.items.len(), 3);
+        assert_eq!(monday.items[0].meal.as_deref(), Some("Breakfast"));
+        assert_eq!(monday.items[0].reference, "./Breakfast/Easy Pancakes");
+        assert_eq!(monday.items[0].quantity, Some(2.0));
+        assert_eq!(monday.items[1].meal.as_deref(), Some("Dinner"));
+        assert_eq!(monday.items[1].quantity, None);
+        assert_eq!(monday.items[2].reference, "../sides/rice");
+        assert_eq!(monday.items[2].unit.as_deref(), Some("batch"));
+
+        let tuesday = &menu.sections[1];
+        assert_eq!(tuesday.items[0].reference, "./mains/soup");
+        assert_eq!(tuesday.items[0].meal, None);
+    }
+
+    #[test]
+    fn test_reference_candidates() {
+        let candidates =
+            reference_candidates("menus/week-1.menu", "recipes", "./Breakfast/Easy Pancakes");
+        assert_eq!(
+            candidates,
+            vec![
+                "menus/Breakfast/Easy Pancakes.cook",
+                "menus/Breakfast/easy-pancakes.cook",
+                "recipes/Breakfast/Easy Pancakes.cook",
+                "recipes/Breakfast/easy-pancakes.cook",
+                "Breakfast/Easy Pancakes.cook",
+                "Breakfast/easy-pancakes.cook",
+            ]
+        );
+
+        let candidates =
+            reference_candidates("recipes/menus/week.menu", "recipes", "../mains/chili");
+        assert_eq!(candidates[0], "recipes/mains/chili.cook");
+
+        // Escaping the data directory is not a candidate
+        assert!(
+            reference_candidates("week.menu", "recipes", "../../etc/passwd")
+                .iter()
+                .all(|c| !c.contains("etc"))
+        );
+    }
+}
@@ -0,0 +1,349 @@
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Upper bounds, in seconds, of the latency histogram buckets
+const BUCKETS: [f64; 12] = [
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Calls to one storage operation
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OperationStats {
+    pub count: u64,
+    /// Calls that returned an error (included in `count`)
+    pub errors: u64,
+    pub total: Duration,
+    pub max: Duration,
+    /// Calls that took at most each of [`BUCKETS`], cumulative
+    buckets: [u64; BUCKETS.len()],
+}
+
+/// How long storage operations take and how often they fail, per operation
+///
+/// Filled in by [`MeteredStorage`](crate::storage::MeteredStorage) and rendered in the
+/// Prometheus text format, so slow git commits can be told apart from slow disk reads.
+pub struct StorageMetrics {
+    /// Storage backend, `disk` or `git`, reported as the `backend` label
+    backend: String,
+    operations: Mutex<BTreeMap<&'static str, OperationStats>>,
+}
+
+impl StorageMetrics {
+    pub fn new(backend: &str) -> Self {
+        StorageMetrics {
+            backend: backend.to_string(),
+            operations: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Count one call to `operation`
+    pub fn record(&self, operation: &'static str, elapsed: Duration, failed: bool) {
+        let mut operations = self
+            .operations
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let stats = operations.entry(operation).or_default();
+        stats.count += 1;
+        stats.errors += u64::from(failed);
+        stats.total += elapsed;
+        stats.max = stats.max.max(elapsed);
+        let seconds = elapsed.as_secs_f64();
+        for (bucket, bound) in stats.buckets.iter_mut().zip(BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+    }
+
+    /// Operations called so far, by name
+    pub fn snapshot(&self) -> BTreeMap<&'static str, OperationStats> {
+        self.operations
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// The metrics in the Prometheus text exposition format
+    pub fn to_prometheus(&self) -> String {
+        let operations = self.snapshot();
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "# HELP cooklang_storage_operation_duration_seconds Time spent in storage operations"
+        );
+        let _ = writeln!(
+            out,
+            "# TYPE cooklang_storage_operation_duration_seconds histogram"
+        );
+        for (operation, stats) in &operations {
+            let labels = format!("backend=\"{}\",operation=\"{}\"", self.backend, operation);
+            for (count, bound) in stats.buckets.iter().zip(BUCKETS) {
+                let _ = writeln!(
+                    out,
+                    "cooklang_storage_operation_duration_seconds_bucket{{{},le=\"{}\"}} {}",
+                    labels, bound, count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "cooklang_storage_operation_duration_seconds_bucket{{{},le=\"+Inf\"}} {}",
+                labels, stats.count
+            );
+            let _ = writeln!(
+                out,
+                "cooklang_storage_operation_duration_seconds_sum{{{}}} {}",
+                labels,
+                stats.total.as_secs_f64()
+            );
+            let _ = writeln!(
+                out,
+                "cooklang_storage_operation_duration_seconds_count{{{}}} {}",
+                labels, stats.count
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP cooklang_storage_operation_errors_total Storage operations that failed"
+        );
+        let _ = writeln!(
+            out,
+            "# TYPE cooklang_storage_operation_errors_total counter"
+        );
+        for (operation, stats) in &operations {
+            let _ = writeln!(
+                out,
+                "cooklang_storage_operation_errors_total{{backend=\"{}\",operation=\"{}\"}} {}",
+                self.backend, operation, stats.errors
+            );
+        }
+        out
+    }
+}
+
+/// A rebuild of the recipe index, full or incremental
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexRebuild {
+    pub finished_at: DateTime<Utc>,
+    pub duration: Duration,
+    /// Only changed files were looked at
+    pub incremental: bool,
+    /// Recipe files parsed
+    pub parsed: usize,
+}
+
+/// What the recipe index has been through since startup
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IndexStats {
+    pub full_rebuilds: u64,
+    pub incremental_rebuilds: u64,
+    pub last_rebuild: Option<IndexRebuild>,
+    /// Times a recipe file failed to parse, counting repeats
+    pub parse_failures: u64,
+    /// Files that failed to parse when last read, with the error, by git path
+    pub failing: BTreeMap<String, String>,
+}
+
+/// Rebuilds of the recipe index and files that failed to parse
+///
+/// Filled in by the repository, shown by the cache endpoint and rendered in the
+/// Prometheus text format after the storage metrics.
+#[derive(Default)]
+pub struct IndexMetrics {
+    stats: Mutex<IndexStats>,
+}
+
+impl IndexMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, IndexStats> {
+        self.stats
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Count one rebuild that took `duration`
+    pub fn record_rebuild(&self, incremental: bool, parsed: usize, duration: Duration) {
+        let mut stats = self.lock();
+        if incremental {
+            stats.incremental_rebuilds += 1;
+        } else {
+            stats.full_rebuilds += 1;
+        }
+        stats.last_rebuild = Some(IndexRebuild {
+            finished_at: Utc::now(),
+            duration,
+            incremental,
+            parsed,
+        });
+    }
+
+    /// Count a file that failed to parse
+    pub fn record_failure(&self, git_path: &str, error: &str) {
+        let mut stats = self.lock();
+        stats.parse_failures += 1;
+        stats
+            .failing
+            .insert(git_path.to_string(), error.to_string());
+    }
+
+    /// A file parsed, or is gone: it no longer counts as failing
+    pub fn clear_failure(&self, git_path: &str) {
+        self.lock().failing.remove(git_path);
+    }
+
+    /// Forget the failing files, before every file is read again
+    pub fn clear_failures(&self) {
+        self.lock().failing.clear();
+    }
+
+    pub fn snapshot(&self) -> IndexStats {
+        self.lock().clone()
+    }
+
+    /// The metrics in the Prometheus text exposition format, with `entries` recipes indexed
+    pub fn to_prometheus(&self, entries: usize) -> String {
+        let stats = self.snapshot();
+        let mut out = String::new();
+        let _ = writeln!(out, "# HELP cooklang_index_entries Recipes in the index");
+        let _ = writeln!(out, "# TYPE cooklang_index_entries gauge");
+        let _ = writeln!(out, "cooklang_index_entries {}", entries);
+
+        let _ = writeln!(
+            out,
+            "# HELP cooklang_index_rebuilds_total Rebuilds of the index, by mode"
+        );
+        let _ = writeln!(out, "# TYPE cooklang_index_rebuilds_total counter");
+        let _ = writeln!(
+            out,
+            "cooklang_index_rebuilds_total{{mode=\"full\"}} {}",
+            stats.full_rebuilds
+        );
+        let _ = writeln!(
+            out,
+            "cooklang_index_rebuilds_total{{mode=\"incremental\"}} {}",
+            stats.incremental_rebuilds
+        );
+
+        let _ = writeln!(
+            out,
+            "# HELP cooklang_index_last_rebuild_duration_seconds Time the latest rebuild took"
+        );
+        let _ = writeln!(
+            out,
+            "# TYPE cooklang_index_last_rebuild_duration_seconds gauge"
+        );
+        let _ = writeln!(
+            out,
+            "cooklang_index_last_rebuild_duration_seconds {}",
+            stats
+                .last_rebuild
+                .map_or(0.0, |rebuild| rebuild.duration.as_secs_f64())
+        );
+
+        let _ = writeln!(
+            out,
+            "# HELP cooklang_index_parse_failures_total Recipe files that failed to parse"
+        );
+        let _ = writeln!(out, "# TYPE cooklang_index_parse_failures_total counter");
+        let _ = writeln!(
+            out,
+            "cooklang_index_parse_failures_total {}",
+            stats.parse_failures
+        );
+
+        let _ = writeln!(
+            out,
+            "# HELP cooklang_index_failing_files Recipe files left out of the index because they fail to parse"
+        );
+        let _ = writeln!(out, "# TYPE cooklang_index_failing_files gauge");
+        let _ = writeln!(out, "cooklang_index_failing_files {}", stats.failing.len());
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_metrics() {
+        let metrics = StorageMetrics::new("git");
+        metrics.record("read_file", Duration::from_millis(3), false);
+        metrics.record("read_file", Duration::from_millis(30), true);
+        metrics.record("write_file_with_commit", Duration::from_secs(2), false);
+
+        let operations = metrics.snapshot();
+        let read = &operations["read_file"];
+        assert_eq!((read.count, read.errors), (2, 1));
+        assert_eq!(read.total, Duration::from_millis(33));
+        assert_eq!(read.max, Duration::from_millis(30));
+
+        let text = metrics.to_prometheus();
+        let labels = "backend=\"git\",operation=\"read_file\"";
+        for line in [
+            format!(
+                "cooklang_storage_operation_duration_seconds_bucket{{{},le=\"0.001\"}} 0",
+                labels
+            ),
+            format!(
+                "cooklang_storage_operation_duration_seconds_bucket{{{},le=\"0.005\"}} 1",
+                labels
+            ),
+            format!(
+                "cooklang_storage_operation_duration_seconds_bucket{{{},le=\"0.05\"}} 2",
+                labels
+            ),
+            format!(
+                "cooklang_storage_operation_duration_seconds_bucket{{{},le=\"+Inf\"}} 2",
+                labels
+            ),
+            format!(
+                "cooklang_storage_operation_duration_seconds_count{{{}}} 2",
+                labels
+            ),
+            format!("cooklang_storage_operation_errors_total{{{}}} 1", labels),
+        ] {
+            assert!(text.contains(&line), "missing {}", line);
+        }
+    }
+
+    #[test]
+    fn test_index_metrics() {
+        let metrics = IndexMetrics::new();
+        metrics.record_rebuild(false, 3, Duration::from_millis(1500));
+        metrics.record_failure("recipes/broken.cook", "Unexpected end of input");
+        metrics.record_failure("recipes/broken.cook", "Unexpected end of input");
+        metrics.record_rebuild(true, 1, Duration::from_millis(20));
+
+        let stats = metrics.snapshot();
+        assert_eq!((stats.full_rebuilds, stats.incremental_rebuilds), (1, 1));
+        assert_eq!(stats.parse_failures, 2);
+        assert_eq!(stats.failing.len(), 1);
+        let last = stats.last_rebuild.unwrap();
+        assert!(last.incremental);
+        assert_eq!(last.parsed, 1);
+
+        let text = metrics.to_prometheus(7);
+        for line in [
+            "cooklang_index_entries 7",
+            "cooklang_index_rebuilds_total{mode=\"full\"} 1",
+            "cooklang_index_rebuilds_total{mode=\"incremental\"} 1",
+            "cooklang_index_last_rebuild_duration_seconds 0.02",
+            "cooklang_index_parse_failures_total 2",
+            "cooklang_index_failing_files 1",
+        ] {
+            assert!(text.contains(line), "missing {}", line);
+        }
+
+        // Failures stay counted once the file parses
+        metrics.clear_failure("recipes/broken.cook");
+        let stats = metrics.snapshot();
+        assert_eq!((stats.parse_failures, stats.failing.len()), (2, 0));
+    }
+}
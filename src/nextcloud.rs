@@ -0,0 +1,332 @@
+use anyhow::{bail, Context, Result};
+use serde_json::{Map, Value};
+
+use crate::archive::read_zip;
+use crate::import::{ImportedIngredient, ImportedRecipe};
+
+/// File extensions of the images Nextcloud Cookbook keeps next to `recipe.json`
+const IMAGE_EXTENSIONS: &[&str] = &[".jpg", ".jpeg", ".png", ".webp", ".gif"];
+
+/// A recipe folder of a Nextcloud Cookbook export
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NextcloudRecipe {
+    /// Folders above the recipe's own folder, outermost first
+    pub folders: Vec<String>,
+    /// The folder's own name, used when the recipe has none
+    pub folder: String,
+    /// The schema.org `Recipe` object of its `recipe.json`
+    pub json: Map<String, Value>,
+    /// Whether the folder holds images (`full.jpg`, thumbnails)
+    pub has_image: bool,
+}
+
+/// Read the recipe folders of a zipped Nextcloud Cookbook folder, as `(name, recipe)`
+///
+/// Every folder with a `recipe.json` is a recipe. A top folder holding all of them
+/// (as when zipping the `Recipes` folder itself) is skipped. Only an unreadable
+/// archive is an error; a damaged `recipe.json` is reported in its place so the
+/// others can still be imported.
+pub fn read_archive(bytes: &[u8]) -> Result<Vec<(String, Result<NextcloudRecipe>)>> {
+    let entries = read_zip(bytes).context("Invalid Nextcloud Cookbook export")?;
+    let recipe_folders: Vec<Vec<&str>> = entries
+        .iter()
+        .filter_map(|entry| entry.name.strip_suffix("recipe.json"))
+        .filter(|folder| folder.is_empty() || folder.ends_with('/'))
+        .map(|folder| folder.split('/').filter(|f| !f.is_empty()).collect())
+        .collect();
+    if recipe_folders.is_empty() {
+        bail!("Not a Nextcloud Cookbook export: no recipe.json files");
+    }
+    let root = match recipe_folders[0].first() {
+        Some(&top)
+            if recipe_folders
+                .iter()
+                .all(|folders| folders.len() > 1 && folders[0] == top) =>
+        {
+            1
+        }
+        _ => 0,
+    };
+
+    Ok(recipe_folders
+        .into_iter()
+        .map(|folders| {
+            let prefix: String = folders.iter().map(|f| format!("{}/", f)).collect();
+            let folder = folders.last().copied().unwrap_or("recipe").to_string();
+            let recipe = entries
+                .iter()
+                .find(|entry| entry.name == format!("{}recipe.json", prefix))
+                .context("No recipe.json")
+                .and_then(|entry| {
+                    match serde_json::from_slice(&entry.data).context("Invalid recipe JSON")? {
+                        Value::Object(json) => Ok(json),
+                        _ => bail!("Invalid recipe JSON: expected a recipe object"),
+                    }
+                })
+                .map(|json| NextcloudRecipe {
+                    folders: folders[root.min(folders.len())..folders.len().saturating_sub(1)]
+                        .iter()
+                        .map(|f| f.to_string())
+                        .collect(),
+                    folder: folder.clone(),
+                    json,
+                    has_image: entries.iter().any(|entry| {
+                        entry.name.strip_prefix(&prefix).is_some_and(|name| {
+                            let name = name.to_lowercase();
+                            !name.contains('/')
+                                && IMAGE_EXTENSIONS.iter().any(|ext| name.ends_with(ext))
+                        })
+                    }),
+                });
+            match recipe {
+                Ok(recipe) => match text(recipe.json.get("name")) {
+                    Some(name) => (name, Ok(recipe)),
+                    None => (folder, Ok(recipe)),
+                },
+                Err(e) => (folder, Err(e)),
+            }
+        })
+        .collect())
+}
+
+/// Map a Nextcloud Cookbook recipe onto the importer's model
+///
+/// The folders above the recipe become its category path, or its `recipeCategory`
+/// if it sits at the top; `keywords` become tags. ISO 8601 times (`PT1H30M`) are
+/// written out in minutes. Images, tools and nutrition are left out.
+pub fn to_imported(recipe: NextcloudRecipe) -> ImportedRecipe {
+    let json = &recipe.json;
+    let mut dropped = Vec::new();
+    if recipe.has_image || text(json.get("image")).is_some() {
+        dropped.push("image".to_string());
+    }
+    if !list(json.get("tool")).is_empty() {
+        dropped.push("tools".to_string());
+    }
+    let nutrition = json.get("nutrition").and_then(Value::as_object);
+    if nutrition.is_some_and(|nutrition| {
+        nutrition
+            .iter()
+            .any(|(key, value)| key != "@type" && text(Some(value)).is_some())
+    }) {
+        dropped.push("nutrition".to_string());
+    }
+
+    let mut categories = Vec::new();
+    if !recipe.folders.is_empty() {
+        categories.push(recipe.folders.join("/"));
+    }
+    categories.extend(list(json.get("recipeCategory")));
+
+    let ingredients = match json.get("recipeIngredient") {
+        Some(Value::String(lines)) => lines.lines().map(str::to_string).collect(),
+        other => list(other),
+    };
+    let mut steps = Vec::new();
+    instructions(json.get("recipeInstructions"), &mut steps);
+
+    ImportedRecipe {
+        title: text(json.get("name")).unwrap_or(recipe.folder),
+        description: text(json.get("description")),
+        ingredients: ingredients
+            .iter()
+            .filter_map(|line| ImportedIngredient::parse(line))
+            .collect(),
+        steps,
+        servings: text(json.get("recipeYield")),
+        prep_time: duration(json.get("prepTime")),
+        cook_time: duration(json.get("cookTime")),
+        total_time: duration(json.get("totalTime")),
+        difficulty: None,
+        source: text(json.get("url")),
+        notes: None,
+        categories,
+        tags: list(json.get("keywords")),
+        rating: None,
+        dropped,
+    }
+}
+
+/// A text field: a string or number, or the first of a list
+fn text(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::String(text) => Some(text.trim().to_string()).filter(|text| !text.is_empty()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Array(items) => items.iter().find_map(|item| text(Some(item))),
+        _ => None,
+    }
+}
+
+/// A list field: a list of strings or named objects, or a comma-separated string
+fn list(value: Option<&Value>) -> Vec<String> {
+    let items = match value {
+        Some(Value::String(items)) => items.split(',').map(str::to_string).collect(),
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| match item {
+                Value::Object(object) => text(object.get("name")),
+                item => text(Some(item)),
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    items
+        .into_iter()
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Collect step texts from `recipeInstructions`: a string, a list of strings or
+/// `HowToStep`s, or `HowToSection`s of those, whose name starts their first step
+fn instructions(value: Option<&Value>, steps: &mut Vec<String>) {
+    match value {
+        Some(Value::String(text)) => steps.extend(
+            text.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string),
+        ),
+        Some(Value::Array(items)) => {
+            for item in items {
+                match item {
+                    Value::Object(object) if object.contains_key("itemListElement") => {
+                        let first = steps.len();
+                        instructions(object.get("itemListElement"), steps);
+                        if let (Some(name), Some(step)) =
+                            (text(object.get("name")), steps.get_mut(first))
+                        {
+                            *step = format!("{}: {}", name, step);
+                        }
+                    }
+                    Value::Object(object) => steps.extend(text(object.get("text"))),
+                    item => instructions(Some(item), steps),
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// An ISO 8601 duration (`PT1H30M`) in minutes, or the text as written if it isn't
+/// one; zero durations, which Nextcloud writes for unset times, are `None`
+fn duration(value: Option<&Value>) -> Option<String> {
+    let text = text(value)?;
+    let Some(rest) = text.strip_prefix('P') else {
+        return Some(text);
+    };
+
+    let (mut minutes, mut number, mut in_time) = (0f64, String::new(), false);
+    for c in rest.chars() {
+        match c {
+            'T' => in_time = true,
+            '0'..='9' | '.' => number.push(c),
+            unit => {
+                let value: f64 = number.parse().ok()?;
+                number.clear();
+                minutes += value
+                    * match (unit, in_time) {
+                        ('D', false) => 24.0 * 60.0,
+                        ('H', true) => 60.0,
+                        ('M', true) => 1.0,
+                        ('S', true) => 1.0 / 60.0,
+                        _ => return Some(text),
+                    };
+            }
+        }
+    }
+    let minutes = minutes.round() as u64;
+    (minutes > 0).then(|| format!("{} minutes", minutes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::{write_zip, ZipEntry};
+    use chrono::Utc;
+    use serde_json::json;
+
+    #[test]
+    fn test_read_export() -> Result<()> {
+        let brownies = json!({
+            "@type": "Recipe",
+            "name": "Brownies",
+            "recipeCategory": "Baking",
+            "keywords": "chocolate, sweet",
+            "recipeYield": 12,
+            "prepTime": "PT0H20M0S",
+            "cookTime": "PT0H0M0S",
+            "totalTime": "1 hour",
+            "url": "https://example.com/brownies",
+            "image": "",
+            "tool": [],
+            "nutrition": { "@type": "NutritionInformation" },
+            "recipeIngredient": ["200 g chocolate", "3 eggs"],
+            "recipeInstructions": [
+                { "@type": "HowToSection", "name": "Batter", "itemListElement": [
+                    { "@type": "HowToStep", "text": "Melt the chocolate." },
+                    "Beat in the eggs."
+                ] },
+                "Bake."
+            ]
+        });
+        let entries = vec![
+            ZipEntry {
+                name: "Recipes/Desserts/Brownies/recipe.json".to_string(),
+                data: serde_json::to_vec(&brownies)?,
+            },
+            ZipEntry {
+                name: "Recipes/Desserts/Brownies/full.jpg".to_string(),
+                data: vec![0xff, 0xd8],
+            },
+            ZipEntry {
+                name: "Recipes/Toast/recipe.json".to_string(),
+                data: b"{ broken".to_vec(),
+            },
+        ];
+        let recipes = read_archive(&write_zip(&entries, Utc::now())?)?;
+        assert_eq!(recipes.len(), 2);
+        assert_eq!(recipes[1].0, "Toast");
+        assert!(recipes[1].1.is_err());
+
+        let (name, recipe) = recipes.into_iter().next().unwrap();
+        assert_eq!(name, "Brownies");
+        let recipe = recipe?;
+        assert_eq!(recipe.folders, vec!["Desserts"]);
+        assert!(recipe.has_image);
+
+        let recipe = to_imported(recipe);
+        assert_eq!(recipe.categories, vec!["Desserts", "Baking"]);
+        assert_eq!(recipe.tags, vec!["chocolate", "sweet"]);
+        assert_eq!(recipe.servings.as_deref(), Some("12"));
+        assert_eq!(recipe.prep_time.as_deref(), Some("20 minutes"));
+        assert_eq!(recipe.cook_time, None);
+        assert_eq!(recipe.total_time.as_deref(), Some("1 hour"));
+        assert_eq!(
+            recipe.source.as_deref(),
+            Some("https://example.com/brownies")
+        );
+        assert_eq!(
+            recipe.steps,
+            vec!["Batter: Melt the chocolate.", "Beat in the eggs.", "Bake."]
+        );
+        assert_eq!(recipe.ingredients.len(), 2);
+        assert_eq!(recipe.dropped, vec!["image"]);
+
+        // A recipe at the top of the archive takes its recipeCategory as path
+        let entries = vec![ZipEntry {
+            name: "Soup/recipe.json".to_string(),
+            data: serde_json::to_vec(&json!({ "name": "Soup", "recipeCategory": "Mains" }))?,
+        }];
+        let recipes = read_archive(&write_zip(&entries, Utc::now())?)?;
+        let recipe = to_imported(recipes.into_iter().next().unwrap().1?);
+        assert_eq!(recipe.categories, vec!["Mains"]);
+
+        assert_eq!(
+            duration(Some(&json!("P1DT1H30M"))).as_deref(),
+            Some("1530 minutes")
+        );
+        assert!(read_archive(&write_zip(&[], Utc::now())?).is_err());
+        Ok(())
+    }
+}
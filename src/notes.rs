@@ -0,0 +1,113 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Highest star rating a recipe can get (ratings run from 1 to this)
+pub const MAX_RATING: u8 = 5;
+
+/// Suffix of the sidecar file that holds a recipe's rating and notes
+const NOTES_SUFFIX: &str = ".notes.yaml";
+
+/// A dated cooking note, e.g. "came out dry, add 10 min"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CookingNote {
+    /// Day the note refers to (usually when the recipe was cooked)
+    pub date: NaiveDate,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+}
+
+/// Rating and cooking notes kept next to a recipe instead of in its `.cook` content
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecipeNotes {
+    /// Star rating from 1 to [`MAX_RATING`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<u8>,
+    /// Notes, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<CookingNote>,
+}
+
+impl RecipeNotes {
+    /// Parse a sidecar file
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        if yaml.trim().is_empty() {
+            return Ok(RecipeNotes::default());
+        }
+        serde_yaml::from_str(yaml).context("Invalid recipe notes file")
+    }
+
+    /// Serialize for the sidecar file
+    pub fn to_yaml(&self) -> Result<String> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+
+    /// Whether there is nothing worth keeping a sidecar file for
+    pub fn is_empty(&self) -> bool {
+        self.rating.is_none() && self.notes.is_empty()
+    }
+
+    /// Add a note, keeping notes ordered by date (same-day notes stay in insertion order)
+    pub fn add(&mut self, note: CookingNote) {
+        let index = self.notes.partition_point(|n| n.date <= note.date);
+        self.notes.insert(index, note);
+    }
+}
+
+/// Sidecar path for a recipe, e.g. `recipes/chocolate-cake.notes.yaml` for
+/// `recipes/chocolate-cake.cook`
+pub fn notes_path(git_path: &str) -> String {
+    format!(
+        "{}{}",
+        git_path.strip_suffix(".cook").unwrap_or(git_path),
+        NOTES_SUFFIX
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(date: &str, text: &str) -> CookingNote {
+        CookingNote {
+            date: date.parse().unwrap(),
+            text: text.to_string(),
+            author: None,
+        }
+    }
+
+    #[test]
+    fn test_notes_path() {
+        assert_eq!(
+            notes_path("recipes/desserts/chocolate-cake.cook"),
+            "recipes/desserts/chocolate-cake.notes.yaml"
+        );
+    }
+
+    #[test]
+    fn test_notes_sorted_and_yaml_round_trip() -> Result<()> {
+        let mut notes = RecipeNotes {
+            rating: Some(4),
+            ..Default::default()
+        };
+        notes.add(note("2024-05-03", "came out dry, add 10 min"));
+        notes.add(note("2024-05-01", "first try"));
+        notes.add(note("2024-05-03", "less salt"));
+
+        let texts: Vec<&str> = notes.notes.iter().map(|n| n.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec!["first try", "came out dry, add 10 min", "less salt"]
+        );
+
+        let yaml = notes.to_yaml()?;
+        assert!(yaml.contains("date: 2024-05-01"));
+        assert_eq!(RecipeNotes::from_yaml(&yaml)?, notes);
+
+        assert!(RecipeNotes::from_yaml("")?.is_empty());
+        assert!(RecipeNotes::from_yaml("rating: [").is_err());
+
+        Ok(())
+    }
+}
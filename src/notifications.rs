@@ -0,0 +1,342 @@
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+use crate::config::StoreConfig;
+use crate::events::{EventKind, RepositoryEvent};
+
+/// Time one notification may take to send
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where notices of recipe changes go
+#[derive(Debug, Clone, PartialEq)]
+pub enum NotificationSink {
+    /// An ntfy topic URL, e.g. `https://ntfy.sh/family-recipes`
+    Ntfy { url: String, token: Option<String> },
+    /// A Matrix room, posted to by the user the access token belongs to
+    Matrix {
+        homeserver: String,
+        room_id: String,
+        access_token: String,
+    },
+}
+
+impl NotificationSink {
+    fn name(&self) -> &'static str {
+        match self {
+            NotificationSink::Ntfy { .. } => "ntfy",
+            NotificationSink::Matrix { .. } => "Matrix",
+        }
+    }
+}
+
+/// Sends short human-readable notices ("Pea Soup was added by Bob") of recipe changes
+///
+/// Unlike webhooks these are fire-and-forget: a notice that can't be sent is logged
+/// and dropped, with no retries or delivery log.
+pub struct Notifier {
+    sinks: Vec<NotificationSink>,
+    /// Kinds of change announced
+    events: Vec<EventKind>,
+    /// Base for links to changed recipes, if set
+    public_url: Option<String>,
+    counter: AtomicU64,
+}
+
+impl Notifier {
+    pub fn new(
+        sinks: Vec<NotificationSink>,
+        events: Vec<EventKind>,
+        public_url: Option<String>,
+    ) -> Self {
+        Notifier {
+            sinks,
+            events,
+            public_url,
+            counter: AtomicU64::new(0),
+        }
+    }
+
+    /// The sinks configured in `config`, or `None` if there are none
+    ///
+    /// A Matrix room needs the homeserver, room ID and access token; one set without
+    /// the others is ignored with a warning.
+    pub fn from_config(config: &StoreConfig) -> Option<Self> {
+        let mut sinks = Vec::new();
+        if let Some(url) = &config.ntfy_url {
+            sinks.push(NotificationSink::Ntfy {
+                url: url.clone(),
+                token: config.ntfy_token.clone(),
+            });
+        }
+        match (
+            &config.matrix_homeserver,
+            &config.matrix_room_id,
+            &config.matrix_access_token,
+        ) {
+            (Some(homeserver), Some(room_id), Some(access_token)) => {
+                sinks.push(NotificationSink::Matrix {
+                    homeserver: homeserver.trim_end_matches('/').to_string(),
+                    room_id: room_id.clone(),
+                    access_token: access_token.clone(),
+                });
+            }
+            (None, None, None) => {}
+            _ => tracing::warn!(
+                "Matrix notifications need MATRIX_HOMESERVER, MATRIX_ROOM_ID and \
+                 MATRIX_ACCESS_TOKEN; not sending any"
+            ),
+        }
+        if sinks.is_empty() {
+            return None;
+        }
+        Some(Notifier::new(
+            sinks,
+            config.notify_events.clone(),
+            config.public_url.clone(),
+        ))
+    }
+
+    /// Announce a change on every sink, in the background
+    ///
+    /// Returns the sending tasks, which callers don't need to wait for. Changes of a
+    /// kind not announced, and anything outside a Tokio runtime, are skipped.
+    pub fn notify(self: &Arc<Self>, event: &RepositoryEvent) -> Vec<tokio::task::JoinHandle<()>> {
+        if !self.events.contains(&event.event) {
+            return Vec::new();
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return Vec::new();
+        };
+        let text = message(event);
+        let link = self
+            .public_url
+            .as_deref()
+            .filter(|_| event.event != EventKind::RecipeDeleted)
+            .map(|base| format!("{}/api/v1/recipes/{}/html", base, event.recipe_id));
+
+        self.sinks
+            .iter()
+            .map(|sink| {
+                let notifier = Arc::clone(self);
+                let (sink, text, link) = (sink.clone(), text.clone(), link.clone());
+                runtime.spawn(async move {
+                    if let Err(e) = notifier.send(&sink, &text, link.as_deref()).await {
+                        tracing::warn!("Failed to send {} notification: {:#}", sink.name(), e);
+                    }
+                })
+            })
+            .collect()
+    }
+
+    async fn send(&self, sink: &NotificationSink, text: &str, link: Option<&str>) -> Result<()> {
+        match sink {
+            NotificationSink::Ntfy { url, token } => {
+                let mut headers = vec!["Content-Type: text/plain; charset=utf-8".to_string()];
+                if let Some(link) = link {
+                    headers.push(format!("Click: {}", link));
+                }
+                if let Some(token) = token {
+                    headers.push(format!("Authorization: Bearer {}", token));
+                }
+                request("POST", url, &headers, text.as_bytes()).await
+            }
+            NotificationSink::Matrix {
+                homeserver,
+                room_id,
+                access_token,
+            } => {
+                // Each message needs its own transaction ID, or the homeserver drops it
+                // as a resend
+                let txn_id = format!(
+                    "cooklang-store-{}-{}",
+                    Utc::now().timestamp_millis(),
+                    self.counter.fetch_add(1, Ordering::Relaxed)
+                );
+                let url = format!(
+                    "{}/_matrix/client/v3/rooms/{}/send/m.room.message/{}",
+                    homeserver,
+                    urlencoding::encode(room_id),
+                    txn_id
+                );
+                let body = match link {
+                    Some(link) => format!("{}\n{}", text, link),
+                    None => text.to_string(),
+                };
+                let content = serde_json::json!({ "msgtype": "m.notice", "body": body });
+                let headers = [
+                    "Content-Type: application/json".to_string(),
+                    format!("Authorization: Bearer {}", access_token),
+                ];
+                request("PUT", &url, &headers, &serde_json::to_vec(&content)?).await
+            }
+        }
+    }
+}
+
+/// What a notice says about a change, e.g. "Pea Soup was added by Bob"
+pub fn message(event: &RepositoryEvent) -> String {
+    let verb = match event.event {
+        EventKind::RecipeCreated => "added",
+        EventKind::RecipeUpdated => "updated",
+        EventKind::RecipeDeleted => "deleted",
+    };
+    match &event.author {
+        Some(author) => format!("{} was {} by {}", event.name, verb, author),
+        None => format!("{} was {}", event.name, verb),
+    }
+}
+
+/// Send `body` to `url` through the `curl` CLI, failing on a non-2xx response
+async fn request(method: &str, url: &str, headers: &[String], body: &[u8]) -> Result<()> {
+    let mut command = tokio::process::Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--request", method])
+        .args(["--data-binary", "@-"])
+        .args(["--max-time", &SEND_TIMEOUT.as_secs().to_string()])
+        .args(["--output", "/dev/null", "--write-out", "%{http_code}"]);
+    for header in headers {
+        command.args(["--header", header]);
+    }
+    let mut child = command
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .context("Failed to run curl (is curl installed?)")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(body).await?;
+    }
+
+    let output = child.wait_with_output().await?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    match String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse::<u16>()
+    {
+        Ok(status) if (200..300).contains(&status) => Ok(()),
+        Ok(status) => bail!("HTTP {}", status),
+        Err(_) => bail!("No HTTP status in the response"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    /// Accept one request and answer 200, returning its request line, headers and body
+    fn serve() -> (String, std::thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let (mut request, mut length) = (String::new(), 0);
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                request.push_str(&line);
+                if line.trim().is_empty() {
+                    break;
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            request.push_str(&String::from_utf8(body).unwrap());
+            write!(
+                &stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            )
+            .unwrap();
+            request
+        });
+        (url, server)
+    }
+
+    fn event(kind: EventKind, author: Option<&str>) -> RepositoryEvent {
+        RepositoryEvent::new(kind, "recipes/pea-soup.cook", "Pea Soup", None, author)
+    }
+
+    #[test]
+    fn test_message() {
+        assert_eq!(
+            message(&event(EventKind::RecipeCreated, Some("Bob"))),
+            "Pea Soup was added by Bob"
+        );
+        assert_eq!(
+            message(&event(EventKind::RecipeDeleted, None)),
+            "Pea Soup was deleted"
+        );
+        let moved = RepositoryEvent::new(
+            EventKind::RecipeUpdated,
+            "recipes/soups/pea-soup.cook",
+            "Pea Soup",
+            Some("soups"),
+            None,
+        )
+        .moved_from(Some("recipes/pea-soup.cook"));
+        assert_eq!(message(&moved), "Pea Soup was updated");
+    }
+
+    #[test]
+    fn test_from_config() {
+        assert!(Notifier::from_config(&StoreConfig::default()).is_none());
+
+        // An incomplete Matrix setup is ignored
+        let config = StoreConfig {
+            ntfy_url: Some("https://ntfy.sh/recipes".to_string()),
+            matrix_homeserver: Some("https://matrix.org/".to_string()),
+            ..Default::default()
+        };
+        let notifier = Notifier::from_config(&config).unwrap();
+        assert_eq!(notifier.sinks.len(), 1);
+        assert_eq!(notifier.events, vec![EventKind::RecipeCreated]);
+    }
+
+    #[tokio::test]
+    async fn test_ntfy_notification() {
+        let (url, server) = serve();
+        let notifier = Arc::new(Notifier::new(
+            vec![NotificationSink::Ntfy {
+                url: format!("{}/recipes", url),
+                token: Some("tk_123".to_string()),
+            }],
+            vec![EventKind::RecipeCreated],
+            Some("https://recipes.example.com".to_string()),
+        ));
+
+        // Kinds not announced aren't sent
+        assert!(notifier
+            .notify(&event(EventKind::RecipeDeleted, None))
+            .is_empty());
+
+        let created = event(EventKind::RecipeCreated, Some("Bob"));
+        for task in notifier.notify(&created) {
+            task.await.unwrap();
+        }
+        let request = tokio::task::spawn_blocking(move || server.join().unwrap())
+            .await
+            .unwrap();
+        assert!(request.starts_with("POST /recipes "));
+        let lower = request.to_lowercase();
+        assert!(lower.contains("authorization: bearer tk_123"));
+        assert!(request.contains(&format!(
+            "Click: https://recipes.example.com/api/v1/recipes/{}/html",
+            created.recipe_id
+        )));
+        assert!(request.ends_with("\r\n\r\nPea Soup was added by Bob"));
+    }
+}
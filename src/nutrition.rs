@@ -0,0 +1,341 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::parser::{extract_ingredient_amounts, extract_metadata, IngredientAmount};
+
+/// Calories and macronutrients (grams)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Nutrients {
+    pub calories: f64,
+    pub protein: f64,
+    pub fat: f64,
+    pub carbohydrates: f64,
+}
+
+impl Nutrients {
+    fn add_scaled(&mut self, other: &Nutrients, factor: f64) {
+        self.calories += other.calories * factor;
+        self.protein += other.protein * factor;
+        self.fat += other.fat * factor;
+        self.carbohydrates += other.carbohydrates * factor;
+    }
+
+    /// Divide evenly, e.g. into servings
+    pub fn divided(&self, parts: f64) -> Nutrients {
+        let mut result = Nutrients::default();
+        result.add_scaled(self, 1.0 / parts);
+        result
+    }
+
+    /// Rounded to one decimal, for display
+    pub fn rounded(&self) -> Nutrients {
+        let round = |value: f64| (value * 10.0).round() / 10.0;
+        Nutrients {
+            calories: round(self.calories),
+            protein: round(self.protein),
+            fat: round(self.fat),
+            carbohydrates: round(self.carbohydrates),
+        }
+    }
+}
+
+/// Nutrient values of a food per 100 g, plus what is needed to convert amounts to grams
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FoodEntry {
+    #[serde(flatten)]
+    pub per_100g: Nutrients,
+    /// Grams per millilitre, for volume units (defaults to water)
+    #[serde(default = "default_density")]
+    pub density: f64,
+    /// Grams per piece, for counts such as `@eggs{2}`
+    #[serde(default)]
+    pub piece_grams: Option<f64>,
+}
+
+fn default_density() -> f64 {
+    1.0
+}
+
+/// Estimated nutrition of a recipe
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NutritionEstimate {
+    /// Servings the total is divided by (1 when the recipe does not say)
+    pub servings: f64,
+    pub total: Nutrients,
+    pub per_serving: Nutrients,
+    /// Ingredients left out because they are unknown or their amount cannot be converted
+    pub unmatched: Vec<String>,
+}
+
+/// Ingredient → nutrient table used for estimates
+///
+/// Starts from a small bundled table of common ingredients; `NUTRITION_DB` can point
+/// to a JSON file of extra or corrected entries in the same per-100 g format.
+pub struct NutritionDatabase {
+    // lowercase food name -> entry
+    foods: HashMap<String, FoodEntry>,
+}
+
+impl NutritionDatabase {
+    /// The bundled table
+    pub fn bundled() -> Self {
+        let foods = BUNDLED_FOODS
+            .iter()
+            .map(
+                |&(name, calories, protein, fat, carbohydrates, density, piece)| {
+                    let entry = FoodEntry {
+                        per_100g: Nutrients {
+                            calories,
+                            protein,
+                            fat,
+                            carbohydrates,
+                        },
+                        density,
+                        piece_grams: (piece > 0.0).then_some(piece),
+                    };
+                    (name.to_string(), entry)
+                },
+            )
+            .collect();
+        NutritionDatabase { foods }
+    }
+
+    /// Add or replace entries from a JSON object of `name → entry`
+    pub fn load_overrides(&mut self, json: &str) -> Result<usize> {
+        let entries: HashMap<String, FoodEntry> =
+            serde_json::from_str(json).context("Invalid nutrition database")?;
+        let count = entries.len();
+        for (name, entry) in entries {
+            self.foods.insert(name.trim().to_lowercase(), entry);
+        }
+        Ok(count)
+    }
+
+    /// The bundled table plus entries from an optional JSON file
+    pub fn with_overrides(path: Option<&Path>) -> Self {
+        let mut database = Self::bundled();
+        if let Some(path) = path {
+            let loaded = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))
+                .and_then(|json| database.load_overrides(&json));
+            match loaded {
+                Ok(count) => {
+                    tracing::info!("Loaded {} nutrition entries from {}", count, path.display())
+                }
+                Err(e) => tracing::warn!("Ignoring nutrition database: {:#}", e),
+            }
+        }
+        database
+    }
+
+    /// Find the entry for an ingredient name
+    ///
+    /// Tries the name itself, then its singular, then the longest known food that the
+    /// name ends with ("all-purpose flour" → "flour", "large eggs" → "egg").
+    pub fn lookup(&self, name: &str) -> Option<&FoodEntry> {
+        let name = name.trim().to_lowercase().replace('-', " ");
+        let words: Vec<&str> = name.split_whitespace().collect();
+
+        (0..words.len()).find_map(|start| {
+            let candidate = words[start..].join(" ");
+            self.foods
+                .get(&candidate)
+                .or_else(|| singular(&candidate).and_then(|s| self.foods.get(&s)))
+        })
+    }
+
+    /// Estimate a recipe's total and per-serving nutrition from its content
+    pub fn estimate(&self, content: &str) -> NutritionEstimate {
+        let mut total = Nutrients::default();
+        let mut unmatched = Vec::new();
+
+        for ingredient in extract_ingredient_amounts(content) {
+            match self.grams(&ingredient) {
+                Some((entry, grams)) => total.add_scaled(&entry.per_100g, grams / 100.0),
+                None => {
+                    if !unmatched.contains(&ingredient.name) {
+                        unmatched.push(ingredient.name);
+                    }
+                }
+            }
+        }
+
+        let servings = extract_metadata(content)
+            .servings
+            .as_deref()
+            .and_then(leading_number)
+            .filter(|&servings| servings > 0.0)
+            .unwrap_or(1.0);
+
+        NutritionEstimate {
+            servings,
+            total: total.rounded(),
+            per_serving: total.divided(servings).rounded(),
+            unmatched,
+        }
+    }
+
+    /// An ingredient's food entry and weight in grams, if both can be worked out
+    fn grams(&self, ingredient: &IngredientAmount) -> Option<(&FoodEntry, f64)> {
+        let entry = self.lookup(&ingredient.name)?;
+        let quantity = ingredient.quantity?;
+
+        let unit = ingredient
+            .unit
+            .as_deref()
+            .unwrap_or("")
+            .trim()
+            .to_lowercase();
+        let grams = match unit_factor(&unit) {
+            Some(Unit::Mass(grams)) => quantity * grams,
+            Some(Unit::Volume(millilitres)) => quantity * millilitres * entry.density,
+            None if PIECE_UNITS.contains(&unit.as_str()) => quantity * entry.piece_grams?,
+            None => return None,
+        };
+        Some((entry, grams))
+    }
+}
+
+enum Unit {
+    /// Grams per unit
+    Mass(f64),
+    /// Millilitres per unit
+    Volume(f64),
+}
+
+fn unit_factor(unit: &str) -> Option<Unit> {
+    let unit = match unit.trim_end_matches('.') {
+        "g" | "gr" | "gram" | "grams" => Unit::Mass(1.0),
+        "kg" | "kilogram" | "kilograms" => Unit::Mass(1000.0),
+        "mg" | "milligram" | "milligrams" => Unit::Mass(0.001),
+        "oz" | "ounce" | "ounces" => Unit::Mass(28.35),
+        "lb" | "lbs" | "pound" | "pounds" => Unit::Mass(453.6),
+        "ml" | "millilitre" | "milliliter" | "millilitres" | "milliliters" => Unit::Volume(1.0),
+        "cl" => Unit::Volume(10.0),
+        "dl" => Unit::Volume(100.0),
+        "l" | "litre" | "liter" | "litres" | "liters" => Unit::Volume(1000.0),
+        "tsp" | "teaspoon" | "teaspoons" => Unit::Volume(4.93),
+        "tbsp" | "tablespoon" | "tablespoons" => Unit::Volume(14.79),
+        "cup" | "cups" => Unit::Volume(240.0),
+        "pinch" | "pinches" => Unit::Volume(0.3),
+        _ => return None,
+    };
+    Some(unit)
+}
+
+/// Units that count pieces of the ingredient (an empty unit is a plain count)
+const PIECE_UNITS: &[&str] = &[
+    "", "piece", "pieces", "pc", "pcs", "whole", "small", "medium", "large", "clove", "cloves",
+    "slice", "slices",
+];
+
+/// "tomatoes" → "tomato", "eggs" → "egg"
+fn singular(name: &str) -> Option<String> {
+    name.strip_suffix("oes")
+        .map(|stem| format!("{}o", stem))
+        .or_else(|| name.strip_suffix("ies").map(|stem| format!("{}y", stem)))
+        .or_else(|| name.strip_suffix('s').map(str::to_string))
+}
+
+/// The number a servings value starts with, e.g. 4 for "4-6 people"
+fn leading_number(text: &str) -> Option<f64> {
+    let number: String = text
+        .trim()
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    number.parse().ok()
+}
+
+/// Bundled foods: name, kcal, protein, fat, carbohydrates (per 100 g), density (g/ml),
+/// grams per piece (0 if not counted in pieces). Values are rounded USDA figures.
+#[rustfmt::skip]
+const BUNDLED_FOODS: &[(&str, f64, f64, f64, f64, f64, f64)] = &[
+    ("flour", 364.0, 10.3, 1.0, 76.3, 0.53, 0.0),
+    ("whole wheat flour", 340.0, 13.2, 2.5, 72.0, 0.51, 0.0),
+    ("sugar", 387.0, 0.0, 0.0, 100.0, 0.85, 0.0),
+    ("brown sugar", 380.0, 0.1, 0.0, 98.1, 0.90, 0.0),
+    ("honey", 304.0, 0.3, 0.0, 82.4, 1.42, 0.0),
+    ("butter", 717.0, 0.9, 81.1, 0.1, 0.96, 0.0),
+    ("olive oil", 884.0, 0.0, 100.0, 0.0, 0.91, 0.0),
+    ("oil", 884.0, 0.0, 100.0, 0.0, 0.92, 0.0),
+    ("egg", 143.0, 12.6, 9.5, 0.7, 1.03, 50.0),
+    ("milk", 61.0, 3.2, 3.3, 4.8, 1.03, 0.0),
+    ("cream", 340.0, 2.8, 36.0, 2.8, 1.00, 0.0),
+    ("yogurt", 61.0, 3.5, 3.3, 4.7, 1.03, 0.0),
+    ("cheese", 402.0, 24.9, 33.1, 1.3, 0.45, 0.0),
+    ("parmesan", 431.0, 38.5, 28.6, 4.1, 0.40, 0.0),
+    ("water", 0.0, 0.0, 0.0, 0.0, 1.00, 0.0),
+    ("salt", 0.0, 0.0, 0.0, 0.0, 1.20, 0.0),
+    ("pepper", 251.0, 10.4, 3.3, 64.0, 0.45, 0.0),
+    ("baking powder", 53.0, 0.0, 0.0, 27.7, 0.90, 0.0),
+    ("rice", 365.0, 7.1, 0.7, 80.0, 0.85, 0.0),
+    ("pasta", 371.0, 13.0, 1.5, 74.7, 0.45, 0.0),
+    ("spaghetti", 371.0, 13.0, 1.5, 74.7, 0.45, 0.0),
+    ("noodles", 384.0, 14.2, 4.4, 71.3, 0.45, 0.0),
+    ("oats", 389.0, 16.9, 6.9, 66.3, 0.35, 0.0),
+    ("bread", 265.0, 9.0, 3.2, 49.0, 0.30, 30.0),
+    ("potato", 77.0, 2.0, 0.1, 17.5, 0.65, 170.0),
+    ("onion", 40.0, 1.1, 0.1, 9.3, 0.60, 110.0),
+    ("garlic", 149.0, 6.4, 0.5, 33.1, 0.60, 3.0),
+    ("tomato", 18.0, 0.9, 0.2, 3.9, 0.60, 120.0),
+    ("carrot", 41.0, 0.9, 0.2, 9.6, 0.55, 60.0),
+    ("spinach", 23.0, 2.9, 0.4, 3.6, 0.15, 0.0),
+    ("banana", 89.0, 1.1, 0.3, 22.8, 0.60, 118.0),
+    ("apple", 52.0, 0.3, 0.2, 13.8, 0.55, 180.0),
+    ("lemon", 29.0, 1.1, 0.3, 9.3, 0.60, 60.0),
+    ("lemon juice", 22.0, 0.4, 0.2, 6.9, 1.03, 0.0),
+    ("lentils", 353.0, 25.8, 1.1, 60.1, 0.80, 0.0),
+    ("chicken breast", 165.0, 31.0, 3.6, 0.0, 1.00, 170.0),
+    ("chicken", 239.0, 27.3, 13.6, 0.0, 1.00, 0.0),
+    ("beef", 250.0, 26.1, 15.4, 0.0, 1.00, 0.0),
+    ("bacon", 541.0, 37.0, 41.8, 1.4, 1.00, 8.0),
+    ("salmon", 208.0, 20.4, 13.4, 0.0, 1.00, 0.0),
+    ("coconut milk", 230.0, 2.3, 23.8, 5.5, 0.97, 0.0),
+    ("chocolate", 546.0, 4.9, 31.3, 61.2, 0.60, 0.0),
+    ("cocoa powder", 228.0, 19.6, 13.7, 57.9, 0.45, 0.0),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_matches_plurals_and_qualified_names() {
+        let database = NutritionDatabase::bundled();
+        assert!(database.lookup("Eggs").is_some());
+        assert!(database.lookup("tomatoes").is_some());
+        assert_eq!(
+            database.lookup("all-purpose flour"),
+            database.lookup("flour")
+        );
+        assert_eq!(
+            database.lookup("extra virgin olive oil"),
+            database.lookup("olive oil")
+        );
+        assert!(database.lookup("saffron").is_none());
+    }
+
+    #[test]
+    fn test_estimate_per_serving() -> Result<()> {
+        let content = "---\ntitle: Pancakes\nservings: 2-3\n---\n\nMix @flour{200%g}, @eggs{2}, @milk{1/2%cup} and @saffron{1%pinch}.\nFry in @butter{1%tbsp}.";
+        let mut database = NutritionDatabase::bundled();
+
+        let estimate = database.estimate(content);
+        assert_eq!(estimate.servings, 2.0);
+        assert_eq!(estimate.unmatched, vec!["saffron"]);
+        // 728 (flour) + 143 (eggs) + 75.4 (milk) + 101.8 (butter)
+        assert!((estimate.total.calories - 1048.2).abs() < 1.0);
+        assert!((estimate.per_serving.calories - 524.1).abs() < 1.0);
+
+        database.load_overrides(
+            r#"{"Saffron": {"calories": 310, "protein": 11.4, "fat": 5.9, "carbohydrates": 65.4}}"#,
+        )?;
+        assert!(database.estimate(content).unmatched.is_empty());
+
+        Ok(())
+    }
+}
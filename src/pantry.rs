@@ -0,0 +1,159 @@
+use anyhow::{Context, Result};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// How much of an ingredient is on hand
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PantryItem {
+    /// Amount on hand; `None` means "some" (present, amount not tracked)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+}
+
+impl PantryItem {
+    /// Whether the item counts as available (an explicit zero quantity does not)
+    pub fn in_stock(&self) -> bool {
+        self.quantity.map(|quantity| quantity > 0.0).unwrap_or(true)
+    }
+}
+
+/// Ingredients on hand, keyed by lowercased ingredient name
+pub struct Pantry {
+    items: DashMap<String, PantryItem>,
+}
+
+impl Default for Pantry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pantry {
+    /// Create an empty pantry
+    pub fn new() -> Self {
+        Pantry {
+            items: DashMap::new(),
+        }
+    }
+
+    /// Replace the pantry with items loaded from JSON
+    pub fn load(&self, json: &str) -> Result<()> {
+        let items: BTreeMap<String, PantryItem> =
+            serde_json::from_str(json).context("Invalid pantry file")?;
+
+        self.items.clear();
+        for (name, item) in items {
+            let name = item_key(&name);
+            if !name.is_empty() {
+                self.items.insert(name, item);
+            }
+        }
+        Ok(())
+    }
+
+    /// Serialize the pantry to JSON (sorted by name, for stable diffs)
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&self.items())?)
+    }
+
+    /// All items, sorted by name
+    pub fn items(&self) -> BTreeMap<String, PantryItem> {
+        self.items
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect()
+    }
+
+    /// Add or update an item, returning whether anything changed
+    pub fn set(&self, name: &str, item: PantryItem) -> bool {
+        let previous = self.items.insert(item_key(name), item.clone());
+        previous.as_ref() != Some(&item)
+    }
+
+    /// Remove an item, returning whether it was in the pantry
+    pub fn remove(&self, name: &str) -> bool {
+        self.items.remove(&item_key(name)).is_some()
+    }
+
+    /// Get an item by name (case-insensitive)
+    pub fn get(&self, name: &str) -> Option<PantryItem> {
+        self.items.get(&item_key(name)).map(|item| item.clone())
+    }
+
+    /// Whether an ingredient is on hand
+    pub fn has(&self, name: &str) -> bool {
+        self.get(name).map(|item| item.in_stock()).unwrap_or(false)
+    }
+}
+
+/// Normalize an ingredient name the way the ingredient index does
+pub fn item_key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pantry_set_remove_and_stock() {
+        let pantry = Pantry::new();
+        assert!(pantry.set(
+            " Flour ",
+            PantryItem {
+                quantity: Some(500.0),
+                unit: Some("g".to_string()),
+            }
+        ));
+        assert!(!pantry.set(
+            "flour",
+            PantryItem {
+                quantity: Some(500.0),
+                unit: Some("g".to_string()),
+            }
+        ));
+        pantry.set("salt", PantryItem::default());
+        pantry.set(
+            "eggs",
+            PantryItem {
+                quantity: Some(0.0),
+                unit: None,
+            },
+        );
+
+        assert!(pantry.has("FLOUR"));
+        assert!(pantry.has("salt"));
+        assert!(!pantry.has("eggs"));
+        assert!(!pantry.has("milk"));
+
+        assert!(pantry.remove("Salt"));
+        assert!(!pantry.remove("salt"));
+    }
+
+    #[test]
+    fn test_pantry_json_round_trip() -> Result<()> {
+        let pantry = Pantry::new();
+        pantry.set(
+            "flour",
+            PantryItem {
+                quantity: Some(1.5),
+                unit: Some("kg".to_string()),
+            },
+        );
+        pantry.set("salt", PantryItem::default());
+
+        let json = pantry.to_json()?;
+        assert!(json.contains("\"flour\""));
+        assert!(!json.contains("null"));
+
+        let loaded = Pantry::new();
+        loaded.load(&json)?;
+        assert_eq!(loaded.items(), pantry.items());
+        assert!(loaded.load("[").is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,384 @@
+use anyhow::{ensure, Context, Result};
+use chrono::{DateTime, Utc};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::{Read, Write};
+
+use crate::archive::{read_zip, write_zip, ZipEntry, MAX_UNPACKED_SIZE};
+use crate::import::{ImportedIngredient, ImportedRecipe};
+use crate::notes::RecipeNotes;
+use crate::parser::{
+    extract_ingredient_amounts, extract_metadata, extract_step_texts, extract_total_minutes,
+};
+use crate::quantity::QuantityFormat;
+use crate::repository::Recipe;
+
+/// A recipe in the JSON layout of Paprika's `.paprikarecipe` files
+///
+/// Text fields Paprika requires are empty strings rather than missing; photos are
+/// never exported. Fields missing from an imported file take their defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PaprikaRecipe {
+    /// Stable ID (derived from the recipe's path), so re-importing updates recipes
+    pub uid: String,
+    pub name: String,
+    /// One ingredient per line, e.g. `1½ cups flour`
+    pub ingredients: String,
+    /// Steps separated by blank lines
+    pub directions: String,
+    pub description: String,
+    pub notes: String,
+    pub servings: String,
+    pub prep_time: String,
+    pub cook_time: String,
+    pub total_time: String,
+    pub difficulty: String,
+    pub source: String,
+    pub source_url: String,
+    pub categories: Vec<String>,
+    /// 0 (unrated) to 5
+    pub rating: u8,
+    /// `YYYY-MM-DD HH:MM:SS`
+    pub created: String,
+    /// Changes whenever the recipe does, so Paprika knows to update it
+    pub hash: String,
+    pub nutritional_info: String,
+    pub image_url: Option<String>,
+    pub photo: Option<String>,
+    pub photo_hash: Option<String>,
+    pub photo_large: Option<String>,
+    pub photo_data: Option<String>,
+    pub photos: Vec<serde_json::Value>,
+}
+
+impl PaprikaRecipe {
+    /// Timestamp format of [`created`](Self::created)
+    pub const CREATED_FORMAT: &'static str = "%Y-%m-%d %H:%M:%S";
+
+    /// Set `uid` and `hash` from the recipe's path and content
+    pub fn with_ids(self, git_path: &str, content: &str) -> Self {
+        let path = hex_sha256(git_path).to_uppercase();
+        PaprikaRecipe {
+            uid: format!(
+                "{}-{}-{}-{}-{}",
+                &path[..8],
+                &path[8..12],
+                &path[12..16],
+                &path[16..20],
+                &path[20..32]
+            ),
+            hash: hex_sha256(content),
+            ..self
+        }
+    }
+}
+
+/// Convert a recipe (with its content loaded) and its notes to Paprika's layout
+///
+/// The category path and front matter tags become categories, and a `source` that is
+/// a URL goes to `source_url`.
+pub fn convert(recipe: &Recipe, notes: &RecipeNotes, quantities: &QuantityFormat) -> PaprikaRecipe {
+    let content = recipe.content.as_deref().unwrap_or_default();
+    let metadata = extract_metadata(content);
+    let minutes = |minutes: Option<u32>| {
+        minutes
+            .map(|minutes| format!("{} min", minutes))
+            .unwrap_or_default()
+    };
+
+    let ingredients: Vec<String> = extract_ingredient_amounts(content)
+        .into_iter()
+        .map(|ingredient| {
+            let amount = match (ingredient.quantity, ingredient.unit) {
+                (Some(quantity), Some(unit)) => {
+                    quantities.format_amount(&format!("{} {}", quantity, unit))
+                }
+                (Some(quantity), None) => quantities.format(quantity),
+                (None, _) => return ingredient.name,
+            };
+            format!("{} {}", amount, ingredient.name)
+        })
+        .collect();
+    let (source, source_url) = match metadata.source {
+        Some(url) if url.starts_with("http://") || url.starts_with("https://") => {
+            (String::new(), url)
+        }
+        source => (source.unwrap_or_default(), String::new()),
+    };
+    let created = recipe
+        .last_modified
+        .as_ref()
+        .map(|modified| modified.at)
+        .unwrap_or_else(Utc::now);
+
+    PaprikaRecipe {
+        name: recipe.name.clone(),
+        ingredients: ingredients.join("\n"),
+        directions: extract_step_texts(content).join("\n\n"),
+        description: recipe.description.clone().unwrap_or_default(),
+        notes: notes
+            .notes
+            .iter()
+            .map(|note| format!("{}: {}", note.date, note.text))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        servings: metadata.servings.unwrap_or_default(),
+        prep_time: minutes(metadata.prep_minutes),
+        cook_time: minutes(metadata.cook_minutes),
+        total_time: minutes(extract_total_minutes(content)),
+        difficulty: metadata.difficulty.unwrap_or_default(),
+        source,
+        source_url,
+        categories: recipe
+            .category
+            .iter()
+            .cloned()
+            .chain(metadata.tags)
+            .collect(),
+        rating: notes.rating.unwrap_or(0),
+        created: created.format(PaprikaRecipe::CREATED_FORMAT).to_string(),
+        ..Default::default()
+    }
+    .with_ids(&recipe.git_path, content)
+}
+
+/// A `.paprikarecipes` archive: a zip holding one gzipped JSON file per recipe
+///
+/// Recipes with the same name get a numbered file name (`Bread (2).paprikarecipe`).
+pub fn archive(recipes: &[PaprikaRecipe], now: DateTime<Utc>) -> Result<Vec<u8>> {
+    let mut entries = Vec::with_capacity(recipes.len());
+    let mut names = HashSet::new();
+    for recipe in recipes {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&serde_json::to_vec(recipe)?)?;
+        entries.push(ZipEntry {
+            name: entry_name(&recipe.name, &mut names),
+            data: encoder.finish()?,
+        });
+    }
+    write_zip(&entries, now)
+}
+
+/// Read the recipes of a `.paprikarecipes` archive, as `(file name, recipe)`
+///
+/// Only an unreadable archive is an error; a damaged recipe file is reported in its
+/// place so the others can still be imported.
+///
+/// The gunzipped recipe files count toward the archive's unpacked size limit, so a
+/// small gzip bomb fails the whole archive instead of filling memory.
+pub fn read_archive(bytes: &[u8]) -> Result<Vec<(String, Result<PaprikaRecipe>)>> {
+    let entries = read_zip(bytes).context("Invalid Paprika archive")?;
+    let mut unpacked: u64 = entries.iter().map(|entry| entry.data.len() as u64).sum();
+    let mut recipes = Vec::new();
+    for entry in entries {
+        if !entry.name.ends_with(".paprikarecipe") {
+            continue;
+        }
+        let remaining = MAX_UNPACKED_SIZE.saturating_sub(unpacked);
+        let mut json = Vec::new();
+        let read = GzDecoder::new(entry.data.as_slice())
+            .take(remaining + 1)
+            .read_to_end(&mut json);
+        unpacked += json.len() as u64;
+        ensure!(
+            unpacked <= MAX_UNPACKED_SIZE,
+            "Paprika archive unpacks to more than {} MB",
+            MAX_UNPACKED_SIZE / 1024 / 1024
+        );
+        let recipe = read
+            .context("Not a gzipped recipe")
+            .and_then(|_| from_json(&json).context("Invalid recipe JSON"));
+        let name = entry.name.rsplit('/').next().unwrap_or_default();
+        let name = name.trim_end_matches(".paprikarecipe").to_string();
+        recipes.push((name, recipe));
+    }
+    Ok(recipes)
+}
+
+/// Parse a recipe file, where Paprika writes `null` for many empty fields
+fn from_json(json: &[u8]) -> Result<PaprikaRecipe> {
+    let mut value: serde_json::Value = serde_json::from_slice(json)?;
+    if let Some(fields) = value.as_object_mut() {
+        fields.retain(|_, field| !field.is_null());
+    }
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Map a Paprika recipe onto the importer's model
+///
+/// Ingredients are free text, read line by line; `source_url` is preferred over the
+/// source's name. Photos and nutrition information are left out.
+pub fn to_imported(recipe: PaprikaRecipe) -> ImportedRecipe {
+    let text = |value: String| Some(value.trim().to_string()).filter(|v| !v.is_empty());
+    let mut dropped = Vec::new();
+    if recipe.photo_data.is_some() || recipe.photo.is_some() || !recipe.photos.is_empty() {
+        dropped.push("photo".to_string());
+    }
+    if !recipe.nutritional_info.trim().is_empty() {
+        dropped.push("nutritional_info".to_string());
+    }
+    let source = match (text(recipe.source_url), text(recipe.source)) {
+        (Some(url), Some(_)) => {
+            dropped.push("source".to_string());
+            Some(url)
+        }
+        (url, source) => url.or(source),
+    };
+
+    ImportedRecipe {
+        title: recipe.name,
+        description: text(recipe.description),
+        ingredients: recipe
+            .ingredients
+            .lines()
+            .filter_map(ImportedIngredient::parse)
+            .collect(),
+        steps: recipe
+            .directions
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect(),
+        servings: text(recipe.servings),
+        prep_time: text(recipe.prep_time),
+        cook_time: text(recipe.cook_time),
+        total_time: text(recipe.total_time),
+        difficulty: text(recipe.difficulty),
+        source,
+        notes: text(recipe.notes),
+        categories: recipe.categories,
+        tags: Vec::new(),
+        rating: Some(recipe.rating).filter(|rating| (1..=5).contains(rating)),
+        dropped,
+    }
+}
+
+/// File name for a recipe inside the archive, unique among `taken`
+fn entry_name(recipe_name: &str, taken: &mut HashSet<String>) -> String {
+    let base: String = recipe_name
+        .chars()
+        .map(|c| {
+            if matches!(c, '/' | '\\') || c.is_control() {
+                '-'
+            } else {
+                c
+            }
+        })
+        .collect();
+    let base = match base.trim() {
+        "" => "Recipe",
+        base => base,
+    };
+
+    let mut name = format!("{}.paprikarecipe", base);
+    let mut number = 1;
+    while !taken.insert(name.to_lowercase()) {
+        number += 1;
+        name = format!("{} ({}).paprikarecipe", base, number);
+    }
+    name
+}
+
+fn hex_sha256(text: &str) -> String {
+    use sha2::{Digest, Sha256};
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paprika_archive() -> Result<()> {
+        let bread = PaprikaRecipe {
+            name: "Bread".to_string(),
+            ingredients: "500 g flour".to_string(),
+            ..Default::default()
+        }
+        .with_ids("recipes/bread.cook", "content");
+        assert_eq!(bread.uid.len(), 36);
+        assert_eq!(
+            bread.uid,
+            bread.clone().with_ids("recipes/bread.cook", "other").uid
+        );
+        assert_ne!(
+            bread.hash,
+            bread.clone().with_ids("recipes/bread.cook", "other").hash
+        );
+
+        let now = DateTime::parse_from_rfc3339("2024-01-15T10:30:00Z")?.with_timezone(&Utc);
+        let zip = archive(&[bread.clone(), bread.clone()], now)?;
+        assert_eq!(&zip[..4], b"PK\x03\x04");
+        assert_eq!(
+            zip.len() - 22,
+            zip.windows(4).rposition(|w| w == b"PK\x05\x06").unwrap()
+        );
+
+        // First entry: 30-byte header, name, then the gzipped JSON
+        let name_len = u16::from_le_bytes([zip[26], zip[27]]) as usize;
+        assert_eq!(&zip[30..30 + name_len], b"Bread.paprikarecipe");
+        let size = u32::from_le_bytes(zip[18..22].try_into()?) as usize;
+        let mut json = String::new();
+        GzDecoder::new(&zip[30 + name_len..30 + name_len + size]).read_to_string(&mut json)?;
+        let value: serde_json::Value = serde_json::from_str(&json)?;
+        assert_eq!(value["name"], "Bread");
+        assert_eq!(value["ingredients"], "500 g flour");
+        assert_eq!(value["photos"], serde_json::json!([]));
+
+        let recipe = Recipe {
+            git_path: "recipes/bread.cook".to_string(),
+            file_name: "bread.cook".to_string(),
+            name: "Bread".to_string(),
+            description: None,
+            category: Some("baking".to_string()),
+            content: "---\ntitle: Bread\nsource: https://example.com/bread\ntags: [easy]\n\
+                      time: 2 hours\n---\n\nMix @flour{1.5%cups} and @salt.\n\nBake."
+                .to_string()
+                .into(),
+            last_modified: None,
+        };
+        let notes = RecipeNotes {
+            rating: Some(4),
+            ..Default::default()
+        };
+        let converted = convert(&recipe, &notes, &QuantityFormat::default());
+        assert_eq!(converted.ingredients, "1½ cups flour\nsalt");
+        assert_eq!(converted.directions, "Mix flour and salt.\n\nBake.");
+        assert_eq!(converted.categories, vec!["baking", "easy"]);
+        assert_eq!(converted.source_url, "https://example.com/bread");
+        assert_eq!(converted.total_time, "120 min");
+        assert_eq!(converted.rating, 4);
+
+        let read = read_archive(&zip)?;
+        assert_eq!(read.len(), 2);
+        assert_eq!(read[1].0, "Bread (2)");
+        assert_eq!(read[0].1.as_ref().unwrap(), &bread);
+        let nulls = from_json(br#"{"name": "Soup", "description": null, "rating": 3}"#)?;
+        assert_eq!((nulls.name.as_str(), nulls.rating), ("Soup", 3));
+
+        let imported = to_imported(PaprikaRecipe {
+            name: "Soup".to_string(),
+            ingredients: "1 l stock\n2 carrots".to_string(),
+            directions: "Simmer the carrots in the stock.\n\nBlend.".to_string(),
+            source: "Grandma".to_string(),
+            source_url: "https://example.com/soup".to_string(),
+            categories: vec!["Soups".to_string()],
+            photo_data: Some("aGVsbG8=".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(imported.ingredients.len(), 2);
+        assert_eq!(imported.steps.len(), 2);
+        assert_eq!(imported.source.as_deref(), Some("https://example.com/soup"));
+        assert_eq!(imported.rating, None);
+        assert_eq!(imported.dropped, vec!["photo", "source"]);
+
+        let mut taken = HashSet::new();
+        assert_eq!(entry_name("Bread", &mut taken), "Bread.paprikarecipe");
+        assert_eq!(entry_name("bread", &mut taken), "bread (2).paprikarecipe");
+        assert_eq!(entry_name("A/B", &mut taken), "A-B.paprikarecipe");
+        Ok(())
+    }
+}
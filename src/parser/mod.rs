@@ -1,9 +1,24 @@
 use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub mod units;
+
 pub use cooklang::{Converter, CooklangParser, Extensions, ScalableRecipe};
+pub use units::RecipeParser;
 
+/// Parse a recipe with cooklang's bundled units
 pub fn parse_recipe(content: &str, name: &str) -> Result<ScalableRecipe, String> {
     let parser = CooklangParser::new(Extensions::all(), Converter::default());
+    parse_recipe_with(&parser, content, name)
+}
 
+/// Parse a recipe with a configured parser (e.g. one with custom units)
+pub fn parse_recipe_with(
+    parser: &CooklangParser,
+    content: &str,
+    name: &str,
+) -> Result<ScalableRecipe, String> {
     parser
         .parse(content, name)
         .into_result()
@@ -11,6 +26,17 @@ pub fn parse_recipe(content: &str, name: &str) -> Result<ScalableRecipe, String>
         .map_err(|report| format!("{}", report))
 }
 
+/// Recipe content rejected on create or update
+#[derive(Debug, thiserror::Error)]
+pub enum RecipeError {
+    /// Front matter missing, malformed, or without a `title`
+    #[error("Invalid recipe content: {0}")]
+    InvalidFrontMatter(String),
+    /// Cooklang body the parser rejects
+    #[error("Failed to parse recipe: {0}")]
+    Parse(String),
+}
+
 /// Extracts the recipe title from Cooklang content's YAML front matter.
 ///
 /// Expected format:
@@ -40,6 +66,40 @@ pub fn parse_recipe(content: &str, name: &str) -> Result<ScalableRecipe, String>
 /// assert_eq!(title, "Chocolate Cake");
 /// ```
 pub fn extract_recipe_title(content: &str) -> Result<String> {
+    let front_matter = parse_front_matter(content)?;
+
+    // Extract title field from parsed YAML (case-insensitive key lookup)
+    let title_value = front_matter
+        .iter()
+        .find(|(key, _)| {
+            key.as_str()
+                .map(|k| k.to_lowercase() == "title")
+                .unwrap_or(false)
+        })
+        .map(|(_, v)| v)
+        .ok_or_else(|| {
+            anyhow!(
+                "Title field not found in YAML front matter. Expected format: title: Recipe Name"
+            )
+        })?;
+
+    let title = title_value
+        .as_str()
+        .ok_or_else(|| anyhow!("Title field must be a string"))?
+        .trim();
+
+    if title.is_empty() {
+        return Err(anyhow!("Title field is empty in YAML front matter"));
+    }
+
+    Ok(title.to_string())
+}
+
+/// Parses the YAML front matter block at the start of Cooklang content into a mapping.
+///
+/// Returns an error if content is empty, the front matter is missing or malformed,
+/// or the YAML is not a mapping.
+pub fn parse_front_matter(content: &str) -> Result<serde_yaml::Mapping> {
     let trimmed = content.trim();
 
     // Check for empty content
@@ -66,33 +126,793 @@ pub fn extract_recipe_title(content: &str) -> Result<String> {
     let yaml_value: serde_yaml::Value = serde_yaml::from_str(front_matter_str)
         .map_err(|e| anyhow!("Invalid YAML front matter: {}", e))?;
 
-    // Extract title field from parsed YAML (case-insensitive key lookup)
-    let title_value = yaml_value
-        .as_mapping()
-        .ok_or_else(|| anyhow!("YAML front matter must be a mapping"))?
+    match yaml_value {
+        serde_yaml::Value::Mapping(mapping) => Ok(mapping),
+        _ => Err(anyhow!("YAML front matter must be a mapping")),
+    }
+}
+
+/// Front matter fields to overwrite, leaving the rest of the recipe as it is
+#[derive(Debug, Clone, Default)]
+pub struct FrontMatterPatch {
+    pub title: Option<String>,
+    /// New tags; an empty list removes the `tags` key
+    pub tags: Option<Vec<String>>,
+    /// New servings, a string or a number
+    pub servings: Option<serde_yaml::Value>,
+}
+
+impl FrontMatterPatch {
+    /// Whether the patch changes nothing
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.tags.is_none() && self.servings.is_none()
+    }
+
+    /// Content with the patched fields set in its front matter (added if missing)
+    ///
+    /// Existing keys are replaced in place, matching their spelling variants (`Title`,
+    /// `serves`); other keys keep their order. The front matter is re-serialized, so
+    /// YAML comments in it are lost.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cooklang_store::parser::FrontMatterPatch;
+    /// let content = "---\ntitle: Curry\nserves: 2\n---\n\nStir @rice{}.";
+    /// let patch = FrontMatterPatch {
+    ///     servings: Some(4.into()),
+    ///     tags: Some(vec!["spicy".to_string()]),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(
+    ///     patch.apply(content).unwrap(),
+    ///     "---\ntitle: Curry\nserves: 4\ntags:\n- spicy\n---\n\nStir @rice{}."
+    /// );
+    /// ```
+    pub fn apply(&self, content: &str) -> Result<String> {
+        let has_front_matter = content.trim_start().starts_with("---");
+        let mut front_matter = if has_front_matter {
+            parse_front_matter(content)?
+        } else {
+            serde_yaml::Mapping::new()
+        };
+
+        if let Some(title) = &self.title {
+            set_front_matter_key(&mut front_matter, &["title"], title.trim().into());
+        }
+        if let Some(tags) = &self.tags {
+            let tags: Vec<serde_yaml::Value> = tags
+                .iter()
+                .map(|tag| tag.trim())
+                .filter(|tag| !tag.is_empty())
+                .map(Into::into)
+                .collect();
+            if tags.is_empty() {
+                remove_front_matter_key(&mut front_matter, &["tags"]);
+            } else {
+                set_front_matter_key(&mut front_matter, &["tags"], tags.into());
+            }
+        }
+        if let Some(servings) = &self.servings {
+            set_front_matter_key(
+                &mut front_matter,
+                &["servings", "serves", "yield"],
+                servings.clone(),
+            );
+        }
+
+        let yaml = serde_yaml::to_string(&front_matter)
+            .map_err(|e| anyhow!("Failed to write YAML front matter: {}", e))?;
+        Ok(if has_front_matter {
+            format!("---\n{}---{}", yaml, strip_front_matter(content))
+        } else {
+            format!("---\n{}---\n\n{}", yaml, content.trim_start())
+        })
+    }
+}
+
+/// Content with its front matter in canonical form, `None` if it already is
+///
+/// Standard keys are spelled as in the Cooklang conventions (`Title` → `title`,
+/// `prep_time` → `prep time`) and ordered `title` first, then the other standard keys,
+/// then custom keys in their original order. Comma-separated `tags` become a list and
+/// the YAML is re-serialized, which settles quoting. Content without front matter is
+/// left alone; front matter that is invalid or spells a standard key twice (`Title`
+/// and `title`) is an error.
+///
+/// # Examples
+/// ```
+/// # use cooklang_store::parser::normalize_front_matter;
+/// let content = "---\nholiday: 'easter'\nTags: sweet, baked\nTitle: Buns\n---\n\nBake.";
+/// assert_eq!(
+///     normalize_front_matter(content).unwrap().unwrap(),
+///     "---\ntitle: Buns\ntags:\n- sweet\n- baked\nholiday: easter\n---\n\nBake."
+/// );
+/// ```
+pub fn normalize_front_matter(content: &str) -> Result<Option<String>> {
+    if !content.trim_start().starts_with("---") {
+        return Ok(None);
+    }
+    let front_matter = parse_front_matter(content)?;
+
+    let mut standard: Vec<(usize, serde_yaml::Value, serde_yaml::Value)> = Vec::new();
+    let mut custom = serde_yaml::Mapping::new();
+    for (key, value) in front_matter {
+        let Some(name) = key.as_str() else {
+            custom.insert(key, value);
+            continue;
+        };
+        let canonical = name.trim().to_lowercase().replace(['_', '-'], " ");
+        match STANDARD_METADATA_KEYS.iter().position(|k| *k == canonical) {
+            Some(rank) => {
+                if standard.iter().any(|(other, _, _)| *other == rank) {
+                    return Err(anyhow!("Duplicate front matter key: {}", canonical));
+                }
+                let value = match (canonical.as_str(), value) {
+                    ("tags", serde_yaml::Value::String(list)) => list
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|tag| !tag.is_empty())
+                        .map(serde_yaml::Value::from)
+                        .collect::<Vec<_>>()
+                        .into(),
+                    (_, value) => value,
+                };
+                standard.push((rank, canonical.into(), value));
+            }
+            None => {
+                custom.insert(key, value);
+            }
+        }
+    }
+    standard.sort_by_key(|(rank, _, _)| *rank);
+
+    let mut normalized: serde_yaml::Mapping = standard
+        .into_iter()
+        .map(|(_, key, value)| (key, value))
+        .collect();
+    normalized.extend(custom);
+
+    let yaml = serde_yaml::to_string(&normalized)
+        .map_err(|e| anyhow!("Failed to write YAML front matter: {}", e))?;
+    let result = format!("---\n{}---{}", yaml, strip_front_matter(content));
+    Ok((result != content).then_some(result))
+}
+
+/// The key of the first entry whose name is among `keys` (lowercase, spaces for `_`/`-`)
+fn find_front_matter_key(
+    front_matter: &serde_yaml::Mapping,
+    keys: &[&str],
+) -> Option<serde_yaml::Value> {
+    front_matter
+        .keys()
+        .find(|key| {
+            key.as_str().is_some_and(|key| {
+                keys.contains(&key.to_lowercase().replace(['_', '-'], " ").as_str())
+            })
+        })
+        .cloned()
+}
+
+/// Replace the value of an existing key among `keys`, or append `keys[0]`
+fn set_front_matter_key(
+    front_matter: &mut serde_yaml::Mapping,
+    keys: &[&str],
+    value: serde_yaml::Value,
+) {
+    let key = find_front_matter_key(front_matter, keys).unwrap_or_else(|| keys[0].into());
+    front_matter.insert(key, value);
+}
+
+fn remove_front_matter_key(front_matter: &mut serde_yaml::Mapping, keys: &[&str]) {
+    if let Some(key) = find_front_matter_key(front_matter, keys) {
+        front_matter.shift_remove(&key);
+    }
+}
+
+/// Front matter keys with a meaning in the Cooklang metadata conventions.
+///
+/// Anything else is treated as a user-defined custom field.
+const STANDARD_METADATA_KEYS: &[&str] = &[
+    "title",
+    "description",
+    "tags",
+    "author",
+    "source",
+    "servings",
+    "serves",
+    "yield",
+    "course",
+    "category",
+    "time",
+    "duration",
+    "prep time",
+    "cook time",
+    "time required",
+    "difficulty",
+    "cuisine",
+    "diet",
+    "image",
+    "images",
+    "picture",
+    "pictures",
+    "locale",
+    "introduction",
+];
+
+/// Extracts front matter keys outside the standard Cooklang metadata as custom fields.
+///
+/// Keys keep their original spelling; values are converted to JSON. Content without
+/// valid front matter has no custom fields.
+///
+/// # Examples
+/// ```
+/// # use cooklang_store::parser::extract_custom_fields;
+/// let content = "---\ntitle: Hot Cross Buns\nholiday: easter\n---\n\nBake.";
+/// let fields = extract_custom_fields(content);
+/// assert_eq!(fields.len(), 1);
+/// assert_eq!(fields["holiday"], "easter");
+/// ```
+pub fn extract_custom_fields(content: &str) -> BTreeMap<String, serde_json::Value> {
+    let Ok(front_matter) = parse_front_matter(content) else {
+        return BTreeMap::new();
+    };
+
+    front_matter
         .iter()
-        .find(|(key, _)| {
-            key.as_str()
-                .map(|k| k.to_lowercase() == "title")
-                .unwrap_or(false)
+        .filter_map(|(key, value)| {
+            let key = key.as_str()?;
+            let key_lower = key.to_lowercase();
+            if STANDARD_METADATA_KEYS.contains(&key_lower.as_str()) {
+                return None;
+            }
+            let value = serde_json::to_value(value).ok()?;
+            Some((key.to_string(), value))
         })
-        .map(|(_, v)| v)
-        .ok_or_else(|| {
-            anyhow!(
-                "Title field not found in YAML front matter. Expected format: title: Recipe Name"
-            )
-        })?;
+        .collect()
+}
 
-    let title = title_value
+/// Who can read a recipe, from the front matter `visibility` key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Visibility {
+    /// Only API clients (the default)
+    #[default]
+    Private,
+    /// Also anyone, through the read-only `/api/v1/public` routes
+    Public,
+}
+
+/// Reads the `visibility` front matter key (`public` or `private`, case-insensitive).
+///
+/// Recipes without the key are private; any other value is an error.
+///
+/// # Examples
+/// ```
+/// # use cooklang_store::parser::{extract_visibility, Visibility};
+/// let content = "---\ntitle: Eggs\nvisibility: Public\n---\n\nBoil @eggs{2}.";
+/// assert_eq!(extract_visibility(content).unwrap(), Visibility::Public);
+/// assert_eq!(extract_visibility("Boil @eggs{2}.").unwrap(), Visibility::Private);
+/// ```
+pub fn extract_visibility(content: &str) -> Result<Visibility> {
+    let Ok(front_matter) = parse_front_matter(content) else {
+        return Ok(Visibility::Private);
+    };
+    let Some(value) = front_matter_value(&front_matter, &["visibility"]) else {
+        return Ok(Visibility::Private);
+    };
+
+    match value
         .as_str()
-        .ok_or_else(|| anyhow!("Title field must be a string"))?
-        .trim();
+        .map(|value| value.trim().to_lowercase())
+        .as_deref()
+    {
+        Some("public") => Ok(Visibility::Public),
+        Some("private") => Ok(Visibility::Private),
+        _ => Err(anyhow!(
+            "visibility must be public or private, got {}",
+            serde_yaml::to_string(value).unwrap_or_default().trim()
+        )),
+    }
+}
 
-    if title.is_empty() {
-        return Err(anyhow!("Title field is empty in YAML front matter"));
+/// Extracts the plain text of each step (paragraph) of a recipe, for search and snippets.
+///
+/// Front matter, `>>` metadata, `=` section headers and comments are dropped, and
+/// ingredient, cookware and timer markup is reduced to its name (or a timer's duration).
+///
+/// # Examples
+/// ```
+/// # use cooklang_store::parser::extract_step_texts;
+/// let content = "---\ntitle: Eggs\n---\n\nBoil @eggs{2} in a #small pot{} for ~{10%min}.";
+/// assert_eq!(extract_step_texts(content), vec!["Boil eggs in a small pot for 10 min."]);
+/// ```
+pub fn extract_step_texts(content: &str) -> Vec<String> {
+    let mut body = strip_front_matter(content).to_string();
+
+    // Block comments may span lines
+    while let Some(start) = body.find("[-") {
+        let end = body[start..]
+            .find("-]")
+            .map(|end| start + end + 2)
+            .unwrap_or(body.len());
+        body.replace_range(start..end, "");
+    }
+
+    let mut steps = Vec::new();
+    let mut paragraph = Vec::new();
+    for line in body.lines() {
+        let line = line.split("--").next().unwrap_or_default().trim();
+        if line.starts_with(">>") || line.starts_with('=') {
+            continue;
+        }
+        if line.is_empty() {
+            if !paragraph.is_empty() {
+                steps.push(strip_markup(&paragraph.join(" ")));
+                paragraph.clear();
+            }
+            continue;
+        }
+        paragraph.push(line);
+    }
+    if !paragraph.is_empty() {
+        steps.push(strip_markup(&paragraph.join(" ")));
     }
 
-    Ok(title.to_string())
+    steps.retain(|step| !step.is_empty());
+    steps
+}
+
+/// Content after the YAML front matter block (all of it if there is none)
+pub(crate) fn strip_front_matter(content: &str) -> &str {
+    let trimmed = content.trim_start();
+    trimmed
+        .strip_prefix("---")
+        .and_then(|rest| rest.find("---").map(|end| &rest[end + 3..]))
+        .unwrap_or(content)
+}
+
+/// Characters that cannot appear in a multi-word ingredient, cookware or timer name
+pub(crate) const MARKUP_STOPS: &[char] = &['@', '#', '~', '.', ',', ';', ':', '!', '?', '\n'];
+
+/// Replace `@name{..}`, `#name{..}` and `~name{..}` with their names, collapsing whitespace
+fn strip_markup(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(pos) = rest.find(['@', '#', '~']) {
+        out.push_str(&rest[..pos]);
+        let marker = &rest[pos..pos + 1];
+        // Skip modifiers such as `@?optional` or `@&reference`
+        let after = rest[pos + 1..].trim_start_matches(['&', '?', '+', '-']);
+
+        // Multi-word names run up to `{`, single words end at the first non-word character
+        let braced = after
+            .find('{')
+            .filter(|&open| !after[..open].contains(MARKUP_STOPS))
+            .and_then(|open| after[open..].find('}').map(|close| (open, open + close)));
+        let (name, amount, tail) = match braced {
+            Some((open, close)) => (&after[..open], &after[open + 1..close], &after[close + 1..]),
+            None => {
+                let end = after
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                (&after[..end], "", &after[end..])
+            }
+        };
+
+        let name = name.trim();
+        if !name.is_empty() {
+            out.push_str(name);
+        } else if marker == "~" && !amount.trim().is_empty() {
+            out.push_str(&amount.replace('%', " "));
+        } else if braced.is_none() {
+            // A lone marker, e.g. "#1" or an email address
+            out.push_str(marker);
+        }
+        rest = tail;
+    }
+    out.push_str(rest);
+
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Front matter keys holding a recipe's total time
+const TOTAL_TIME_KEYS: &[&str] = &["time", "duration", "time required", "total time"];
+
+/// Front matter keys holding a recipe's hands-on time (`hands-on time` reads as `hands on time`)
+const ACTIVE_TIME_KEYS: &[&str] = &["active time", "hands on time"];
+
+/// Extracts how long a recipe takes in total, in minutes.
+///
+/// Uses the `time` (or `duration`) front matter field if present; otherwise adds up
+/// `prep time` and `cook time`, taking the sum of the recipe's timers as the cook time
+/// when none is given. Returns `None` when none of these are available.
+///
+/// # Examples
+/// ```
+/// # use cooklang_store::parser::extract_total_minutes;
+/// let content = "---\ntitle: Rice\nprep time: 5 min\n---\n\nSimmer for ~{1%hour}, rest ~{10%minutes}.";
+/// assert_eq!(extract_total_minutes(content), Some(75));
+/// ```
+pub fn extract_total_minutes(content: &str) -> Option<u32> {
+    let front_matter = parse_front_matter(content).unwrap_or_default();
+    let field = |keys: &[&str]| front_matter_value(&front_matter, keys).and_then(yaml_minutes);
+
+    let total = field(TOTAL_TIME_KEYS).or_else(|| {
+        let prep = field(&["prep time"]);
+        let cook = field(&["cook time"]).or_else(|| timer_minutes(content));
+        match (prep, cook) {
+            (None, None) => None,
+            (prep, cook) => Some(prep.unwrap_or(0.0) + cook.unwrap_or(0.0)),
+        }
+    })?;
+
+    Some(total.round().max(0.0) as u32)
+}
+
+/// Extracts how much of a recipe's time is hands-on work, in minutes.
+///
+/// Uses the `active time` (or `hands-on time`) front matter field if present; otherwise
+/// takes the [total time](extract_total_minutes) minus the recipe's timers, which count
+/// as waiting (simmering, baking, resting). Returns `None` without a time in the front
+/// matter, or when the timers add up to more than the total.
+///
+/// # Examples
+/// ```
+/// # use cooklang_store::parser::extract_active_minutes;
+/// let content = "---\ntitle: Rice\nprep time: 5 min\n---\n\nSimmer for ~{1%hour}, rest ~{10%minutes}.";
+/// assert_eq!(extract_active_minutes(content), Some(5));
+/// ```
+pub fn extract_active_minutes(content: &str) -> Option<u32> {
+    let front_matter = parse_front_matter(content).unwrap_or_default();
+    let field = |keys: &[&str]| front_matter_value(&front_matter, keys).and_then(yaml_minutes);
+
+    if let Some(active) = field(ACTIVE_TIME_KEYS) {
+        return Some(active.round().max(0.0) as u32);
+    }
+    // Without a time in the front matter the total is just the timers
+    field(TOTAL_TIME_KEYS)
+        .or_else(|| field(&["prep time"]))
+        .or_else(|| field(&["cook time"]))?;
+
+    let total = extract_total_minutes(content)? as f64;
+    let waiting = timer_minutes(content).unwrap_or(0.0);
+    (waiting <= total + 0.5).then(|| (total - waiting).round().max(0.0) as u32)
+}
+
+/// Sum of a recipe's timers in minutes, `None` if it has none with a duration
+fn timer_minutes(content: &str) -> Option<f64> {
+    let timers: Vec<f64> = timer_amounts(strip_front_matter(content))
+        .into_iter()
+        .filter_map(|amount| parse_duration_minutes(&amount.replace('%', " ")))
+        .collect();
+    (!timers.is_empty()).then(|| timers.iter().sum())
+}
+
+/// An ingredient as written in the recipe, e.g. `@flour{200%g}`
+#[derive(Debug, Clone, PartialEq)]
+pub struct IngredientAmount {
+    pub name: String,
+    /// Numeric quantity (fractions and ranges resolved), `None` if missing or not a number
+    pub quantity: Option<f64>,
+    /// Unit as written, `None` for counts such as `@eggs{2}`
+    pub unit: Option<String>,
+}
+
+/// Extracts every ingredient of a recipe with its quantity and unit, in order of appearance.
+///
+/// Quantities such as `200`, `1.5`, `1/2` and `1 1/2` are understood; a range like `2-3`
+/// counts as its midpoint. Recipe references (`@&other{}`) are left out.
+///
+/// # Examples
+/// ```
+/// # use cooklang_store::parser::extract_ingredient_amounts;
+/// let content = "---\ntitle: Pancakes\n---\n\nMix @flour{1 1/2%cups} with @eggs{2} and @salt.";
+/// let ingredients = extract_ingredient_amounts(content);
+/// assert_eq!(ingredients[0].name, "flour");
+/// assert_eq!(ingredients[0].quantity, Some(1.5));
+/// assert_eq!(ingredients[0].unit.as_deref(), Some("cups"));
+/// assert_eq!((ingredients[1].quantity, ingredients[1].unit.as_deref()), (Some(2.0), None));
+/// assert_eq!(ingredients[2].quantity, None);
+/// ```
+pub fn extract_ingredient_amounts(content: &str) -> Vec<IngredientAmount> {
+    let body = strip_front_matter(content);
+    let mut ingredients = Vec::new();
+
+    for line in body.lines() {
+        let line = line.split("--").next().unwrap_or_default();
+        if line.trim_start().starts_with(">>") {
+            continue;
+        }
+
+        for (pos, _) in line.match_indices('@') {
+            let after = &line[pos + 1..];
+            if after.starts_with('&') {
+                continue;
+            }
+            let after = after.trim_start_matches(['?', '+', '-']);
+
+            let braced = after
+                .find('{')
+                .filter(|&open| !after[..open].contains(MARKUP_STOPS))
+                .and_then(|open| after[open..].find('}').map(|close| (open, open + close)));
+            let (name, amount) = match braced {
+                Some((open, close)) => (&after[..open], &after[open + 1..close]),
+                None => {
+                    let end = after
+                        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                        .unwrap_or(after.len());
+                    (&after[..end], "")
+                }
+            };
+
+            // Drop an alias such as `@flour|wheat flour{}`
+            let name = name.split('|').next().unwrap_or_default().trim();
+            if name.is_empty() {
+                continue;
+            }
+
+            let (quantity, unit) = match amount.split_once('%') {
+                Some((quantity, unit)) => (quantity, Some(unit.trim())),
+                None => (amount, None),
+            };
+            ingredients.push(IngredientAmount {
+                name: name.to_string(),
+                quantity: parse_quantity(quantity),
+                unit: unit.filter(|u| !u.is_empty()).map(str::to_string),
+            });
+        }
+    }
+
+    ingredients
+}
+
+/// Parses an ingredient quantity: decimals, fractions, mixed numbers and ranges (midpoint)
+pub(crate) fn parse_quantity(text: &str) -> Option<f64> {
+    // `=` marks a quantity that does not scale with servings
+    let text = text.trim().trim_start_matches('=').trim();
+    if let Some((low, high)) = text.split_once('-') {
+        return Some((parse_quantity(low)? + parse_quantity(high)?) / 2.0);
+    }
+
+    let mut total = 0.0;
+    let mut found = false;
+    for part in text.split_whitespace() {
+        let value = match part.split_once('/') {
+            Some((num, den)) => {
+                let den: f64 = den.trim().parse().ok()?;
+                if den == 0.0 {
+                    return None;
+                }
+                num.trim().parse::<f64>().ok()? / den
+            }
+            None => part.replace(',', ".").parse().ok()?,
+        };
+        total += value;
+        found = true;
+    }
+
+    found.then_some(total)
+}
+
+/// Standard metadata of a recipe, normalized from its front matter
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecipeMetadata {
+    pub description: Option<String>,
+    /// `servings`, `serves` or `yield`, as written (e.g. `4` or `4-6 people`)
+    pub servings: Option<String>,
+    /// From a YAML list or a comma-separated string
+    pub tags: Vec<String>,
+    pub author: Option<String>,
+    pub source: Option<String>,
+    pub course: Option<String>,
+    pub cuisine: Option<String>,
+    pub difficulty: Option<String>,
+    pub prep_minutes: Option<u32>,
+    pub cook_minutes: Option<u32>,
+    /// `image`, `images`, `picture` or `pictures` (the first of a list), as written
+    pub image: Option<String>,
+}
+
+impl RecipeMetadata {
+    /// Keys accepted by [`values`](Self::values), as used by `?meta.<key>=` filters
+    pub const KEYS: &'static [&'static str] = &[
+        "servings",
+        "tags",
+        "author",
+        "source",
+        "course",
+        "cuisine",
+        "difficulty",
+        "prep_time",
+        "cook_time",
+    ];
+
+    /// The values of a metadata field as strings (times in minutes), empty if unset or unknown
+    pub fn values(&self, key: &str) -> Vec<String> {
+        let single = |value: &Option<String>| value.iter().cloned().collect();
+        let minutes = |value: Option<u32>| value.iter().map(u32::to_string).collect();
+        match key.to_lowercase().replace([' ', '-'], "_").as_str() {
+            "servings" => single(&self.servings),
+            "tags" | "tag" => self.tags.clone(),
+            "author" => single(&self.author),
+            "source" => single(&self.source),
+            "course" => single(&self.course),
+            "cuisine" => single(&self.cuisine),
+            "difficulty" => single(&self.difficulty),
+            "prep_time" => minutes(self.prep_minutes),
+            "cook_time" => minutes(self.cook_minutes),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Extracts the standard metadata fields from a recipe's YAML front matter.
+///
+/// Key names match case-insensitively, with `_` or `-` standing in for spaces. `author`
+/// and `source` may also be mappings with a `name` (or, for sources, a `url`).
+///
+/// # Examples
+/// ```
+/// # use cooklang_store::parser::extract_metadata;
+/// let content = "---\ntitle: Curry\nserves: 4\ntags: spicy, quick\ncook_time: 1h\n---\n";
+/// let metadata = extract_metadata(content);
+/// assert_eq!(metadata.servings.as_deref(), Some("4"));
+/// assert_eq!(metadata.tags, vec!["spicy", "quick"]);
+/// assert_eq!(metadata.cook_minutes, Some(60));
+/// ```
+pub fn extract_metadata(content: &str) -> RecipeMetadata {
+    let Ok(front_matter) = parse_front_matter(content) else {
+        return RecipeMetadata::default();
+    };
+    let text = |keys: &[&str]| front_matter_value(&front_matter, keys).and_then(yaml_text);
+    let minutes = |keys: &[&str]| {
+        front_matter_value(&front_matter, keys)
+            .and_then(yaml_minutes)
+            .map(|minutes| minutes.round().max(0.0) as u32)
+    };
+
+    let tags = match front_matter_value(&front_matter, &["tags"]) {
+        Some(serde_yaml::Value::Sequence(items)) => items.iter().filter_map(yaml_text).collect(),
+        Some(serde_yaml::Value::String(list)) => list
+            .split(',')
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    };
+    let image = match front_matter_value(&front_matter, &["image", "images", "picture", "pictures"])
+    {
+        Some(serde_yaml::Value::Sequence(items)) => items.iter().find_map(yaml_text),
+        value => value.and_then(yaml_text),
+    };
+
+    RecipeMetadata {
+        description: text(&["description"]),
+        servings: text(&["servings", "serves", "yield"]),
+        tags,
+        author: text(&["author"]),
+        source: text(&["source"]),
+        course: text(&["course"]),
+        cuisine: text(&["cuisine"]),
+        difficulty: text(&["difficulty"]),
+        prep_minutes: minutes(&["prep time"]),
+        cook_minutes: minutes(&["cook time"]),
+        image,
+    }
+}
+
+/// The value of the first front matter key among `keys` (lowercase, spaces for `_`/`-`)
+pub(crate) fn front_matter_value<'a>(
+    front_matter: &'a serde_yaml::Mapping,
+    keys: &[&str],
+) -> Option<&'a serde_yaml::Value> {
+    front_matter.iter().find_map(|(key, value)| {
+        let key = key.as_str()?.to_lowercase().replace(['_', '-'], " ");
+        keys.contains(&key.as_str()).then_some(value)
+    })
+}
+
+/// A scalar as trimmed text, or a mapping's `name` (or `url`); `None` if empty
+pub(crate) fn yaml_text(value: &serde_yaml::Value) -> Option<String> {
+    let text = match value {
+        serde_yaml::Value::String(text) => text.trim().to_string(),
+        serde_yaml::Value::Number(number) => number.to_string(),
+        serde_yaml::Value::Bool(flag) => flag.to_string(),
+        serde_yaml::Value::Mapping(mapping) => {
+            return mapping
+                .get("name")
+                .or_else(|| mapping.get("url"))
+                .and_then(yaml_text)
+        }
+        _ => return None,
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+/// A duration in minutes from a number (already minutes) or text such as `1h 30m`
+fn yaml_minutes(value: &serde_yaml::Value) -> Option<f64> {
+    match value {
+        serde_yaml::Value::Number(minutes) => minutes.as_f64(),
+        serde_yaml::Value::String(text) => parse_duration_minutes(text),
+        _ => None,
+    }
+}
+
+/// The `{..}` contents of every `~timer{..}` in the text
+fn timer_amounts(text: &str) -> Vec<&str> {
+    text.match_indices('~')
+        .filter_map(|(pos, _)| {
+            let after = &text[pos + 1..];
+            let open = after.find('{')?;
+            if after[..open].contains(MARKUP_STOPS) {
+                return None;
+            }
+            let close = open + after[open..].find('}')?;
+            Some(&after[open + 1..close])
+        })
+        .collect()
+}
+
+/// Parses a human-written duration into minutes.
+///
+/// Understands plain numbers (minutes), unit words and abbreviations (`1 hour 30 min`,
+/// `1h30m`, `1.5 hours`, `45 sec`), ranges (`20-30 minutes` counts the upper bound) and
+/// ISO 8601 durations (`PT1H30M`).
+///
+/// # Examples
+/// ```
+/// # use cooklang_store::parser::parse_duration_minutes;
+/// assert_eq!(parse_duration_minutes("1h 30m"), Some(90.0));
+/// assert_eq!(parse_duration_minutes("20-30 minutes"), Some(30.0));
+/// assert_eq!(parse_duration_minutes("PT2H"), Some(120.0));
+/// assert_eq!(parse_duration_minutes("a while"), None);
+/// ```
+pub fn parse_duration_minutes(text: &str) -> Option<f64> {
+    let text = text.trim().to_lowercase();
+    let text = text
+        .strip_prefix("pt")
+        .filter(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+        .unwrap_or(&text);
+
+    let mut total = 0.0;
+    let mut found = false;
+    let mut chars = text.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if !(c.is_ascii_digit() || c == '.') {
+            chars.next();
+            continue;
+        }
+
+        let mut end = start;
+        while let Some(&(i, c)) = chars.peek() {
+            if !(c.is_ascii_digit() || c == '.') {
+                break;
+            }
+            end = i + c.len_utf8();
+            chars.next();
+        }
+        let Ok(value) = text[start..end].parse::<f64>() else {
+            continue;
+        };
+
+        let rest = text[end..].trim_start();
+        // The lower bound of a range is dropped in favour of the upper one
+        if rest.starts_with(['-', '–']) || rest.starts_with("to ") {
+            continue;
+        }
+
+        let unit: String = rest.chars().take_while(|c| c.is_alphabetic()).collect();
+        let factor = match unit.as_str() {
+            "" | "m" | "min" | "mins" | "minute" | "minutes" => 1.0,
+            "h" | "hr" | "hrs" | "hour" | "hours" => 60.0,
+            "s" | "sec" | "secs" | "second" | "seconds" => 1.0 / 60.0,
+            "d" | "day" | "days" => 24.0 * 60.0,
+            _ => continue,
+        };
+        total += value * factor;
+        found = true;
+    }
+
+    found.then_some(total)
 }
 
 /// Generates a filename from a recipe title.
@@ -852,6 +1672,157 @@ Add @eggs{2} to the bowl."#;
         assert!(should_rename_file("", "Chocolate Cake"));
     }
 
+    #[test]
+    fn test_extract_step_texts() {
+        let content = r#"---
+title: Scrambled Eggs
+---
+
+>> servings: 2
+
+== Prep ==
+
+Crack @eggs{2} into a bowl -- fresh ones
+and whisk with @?chives{}.
+
+[- TODO: measure the butter -]
+Heat @unsalted butter{1%tbsp} in a #non-stick pan{} and cook for ~{2%minutes}, or until ~set{}.
+"#;
+
+        assert_eq!(
+            extract_step_texts(content),
+            vec![
+                "Crack eggs into a bowl and whisk with chives.",
+                "Heat unsalted butter in a non-stick pan and cook for 2 minutes, or until set.",
+            ]
+        );
+        assert!(extract_step_texts("---\ntitle: Empty\n---\n").is_empty());
+    }
+
+    #[test]
+    fn test_extract_total_minutes() {
+        // `time` wins over everything else
+        let content =
+            "---\ntitle: Stew\ntime: 2 hours\nprep time: 20 min\n---\n\nSimmer ~{3%hours}.";
+        assert_eq!(extract_total_minutes(content), Some(120));
+
+        // Numbers are minutes, prep and cook time add up
+        let content = "---\ntitle: Stew\nprep_time: 15\ncook time: 1h 15m\n---\n\nSimmer.";
+        assert_eq!(extract_total_minutes(content), Some(90));
+
+        // Timers stand in for a missing cook time
+        let content = "---\ntitle: Eggs\n---\n\nBoil for ~eggs{10%min} and rest ~{30%sec}.";
+        assert_eq!(extract_total_minutes(content), Some(11));
+
+        assert_eq!(
+            extract_total_minutes("---\ntitle: Salad\n---\n\nToss."),
+            None
+        );
+    }
+
+    #[test]
+    fn test_extract_active_minutes() {
+        let content =
+            "---\ntitle: Stew\ntime: 2 hours\nactive time: 25 min\n---\n\nSimmer ~{90%min}.";
+        assert_eq!(extract_active_minutes(content), Some(25));
+
+        // Timers are waiting time
+        let content =
+            "---\ntitle: Stew\nprep time: 15\ncook time: 1h 15m\n---\n\nSimmer ~{1%hour}.";
+        assert_eq!(extract_active_minutes(content), Some(30));
+
+        // Timers alone say nothing about the work around them
+        let content = "---\ntitle: Eggs\n---\n\nBoil for ~eggs{10%min}.";
+        assert_eq!(extract_active_minutes(content), None);
+
+        // Overlapping timers can't be taken off the total
+        let content = "---\ntitle: Roast\ntime: 30 min\n---\n\nRoast ~{40%min}.";
+        assert_eq!(extract_active_minutes(content), None);
+    }
+
+    #[test]
+    fn test_extract_ingredient_amounts() {
+        let content = "---\ntitle: Stew\n---\n\n>> servings: 4\nBrown @beef chuck{500%g} and @onions{2-3}.\nAdd @&stock{} and @?salt{=1/2%tsp} -- to taste\nServe with @bread|crusty bread{}.";
+        let ingredients = extract_ingredient_amounts(content);
+
+        let summary: Vec<(&str, Option<f64>, Option<&str>)> = ingredients
+            .iter()
+            .map(|i| (i.name.as_str(), i.quantity, i.unit.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("beef chuck", Some(500.0), Some("g")),
+                ("onions", Some(2.5), None),
+                ("salt", Some(0.5), Some("tsp")),
+                ("bread", None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_metadata() {
+        let content = r#"---
+title: Green Curry
+description: A weeknight curry
+Servings: 4-6 people
+tags: [Thai, spicy]
+author:
+  name: Jane Doe
+  url: https://example.com/jane
+source: https://example.com/curry
+course: dinner
+cuisine: thai
+difficulty: easy
+prep-time: 15 min
+Cook Time: 25
+images: [https://example.com/curry.jpg, https://example.com/curry-2.jpg]
+---
+
+Simmer.
+"#;
+
+        let metadata = extract_metadata(content);
+        assert_eq!(metadata.description.as_deref(), Some("A weeknight curry"));
+        assert_eq!(metadata.servings.as_deref(), Some("4-6 people"));
+        assert_eq!(metadata.tags, vec!["Thai", "spicy"]);
+        assert_eq!(metadata.author.as_deref(), Some("Jane Doe"));
+        assert_eq!(
+            metadata.source.as_deref(),
+            Some("https://example.com/curry")
+        );
+        assert_eq!(metadata.course.as_deref(), Some("dinner"));
+        assert_eq!(metadata.cuisine.as_deref(), Some("thai"));
+        assert_eq!(metadata.difficulty.as_deref(), Some("easy"));
+        assert_eq!(metadata.prep_minutes, Some(15));
+        assert_eq!(metadata.cook_minutes, Some(25));
+        assert_eq!(
+            metadata.image.as_deref(),
+            Some("https://example.com/curry.jpg")
+        );
+
+        assert_eq!(metadata.values("tags"), vec!["Thai", "spicy"]);
+        assert_eq!(metadata.values("prep-time"), vec!["15"]);
+        assert!(metadata.values("calories").is_empty());
+
+        assert_eq!(
+            extract_metadata("no front matter"),
+            RecipeMetadata::default()
+        );
+    }
+
+    #[test]
+    fn test_parse_duration_minutes() {
+        assert_eq!(parse_duration_minutes("45"), Some(45.0));
+        assert_eq!(parse_duration_minutes("1 hour 20 minutes"), Some(80.0));
+        assert_eq!(parse_duration_minutes("1.5 hrs"), Some(90.0));
+        assert_eq!(parse_duration_minutes("2 to 3 hours"), Some(180.0));
+        assert_eq!(parse_duration_minutes("PT1H30M"), Some(90.0));
+        assert_eq!(parse_duration_minutes("1 day"), Some(1440.0));
+        assert_eq!(parse_duration_minutes("3 weeks"), None);
+        assert_eq!(parse_duration_minutes(""), None);
+    }
+
     #[test]
     fn test_should_rename_file_both_empty() {
         // Both empty or generate empty filenames
@@ -0,0 +1,80 @@
+use anyhow::{Context, Result};
+use cooklang::convert::{ConverterBuilder, UnitsFile};
+
+use super::{parse_recipe_with, Converter, CooklangParser, Extensions, ScalableRecipe};
+use crate::cache::content_hash;
+
+/// Cooklang parser using the bundled units, optionally extended by a units file
+///
+/// The units file uses cooklang's `units.toml` format: it can add units and aliases,
+/// change conversion ratios and pick the default unit system.
+pub struct RecipeParser {
+    parser: CooklangParser,
+    /// [`content_hash`] of the units file in use, `None` for the bundled units only
+    units_hash: Option<String>,
+}
+
+impl Default for RecipeParser {
+    fn default() -> Self {
+        Self::bundled()
+    }
+}
+
+impl RecipeParser {
+    /// Parser with cooklang's bundled units only
+    pub fn bundled() -> Self {
+        RecipeParser {
+            parser: CooklangParser::new(Extensions::all(), Converter::default()),
+            units_hash: None,
+        }
+    }
+
+    /// Parser with the bundled units extended by a `units.toml` file
+    pub fn with_units(units_toml: &str) -> Result<Self> {
+        let units: UnitsFile = toml::from_str(units_toml).context("Invalid units file")?;
+
+        let mut builder = ConverterBuilder::new();
+        builder
+            .add_bundled_units()
+            .context("Failed to load bundled units")?;
+        builder
+            .add_units_file(units)
+            .context("Invalid units file")?;
+        let converter = builder.finish().context("Invalid units file")?;
+
+        Ok(RecipeParser {
+            parser: CooklangParser::new(Extensions::all(), converter),
+            units_hash: Some(content_hash(units_toml)),
+        })
+    }
+
+    /// Parse a recipe (see [`parse_recipe`](super::parse_recipe))
+    pub fn parse(&self, content: &str, name: &str) -> Result<ScalableRecipe, String> {
+        parse_recipe_with(&self.parser, content, name)
+    }
+
+    /// Fingerprint of the custom units, so results parsed with other units can be told apart
+    pub fn units_hash(&self) -> Option<&str> {
+        self.units_hash.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_units_file() -> Result<()> {
+        let bundled = RecipeParser::bundled();
+        assert!(bundled.units_hash().is_none());
+
+        let units = "default_system = \"metric\"\n";
+        let custom = RecipeParser::with_units(units)?;
+        assert_eq!(custom.units_hash(), Some(content_hash(units).as_str()));
+        assert!(custom.parse("Add @flour{200%g}.", "Bread").is_ok());
+
+        assert!(RecipeParser::with_units("default_system = [").is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use anyhow::{Context, Result};
+use qrcode::{render::svg, Color, EcLevel, QrCode};
+
+/// Blank modules around the code; scanners need at least four
+const QUIET_ZONE: usize = 4;
+
+/// Pixels per module in PNG output
+const PNG_SCALE: usize = 8;
+
+/// Image format of a QR code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QrFormat {
+    Svg,
+    Png,
+}
+
+impl QrFormat {
+    /// Parse a format name (`svg` or `png`, case-insensitive)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "svg" => Some(QrFormat::Svg),
+            "png" => Some(QrFormat::Png),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            QrFormat::Svg => "image/svg+xml",
+            QrFormat::Png => "image/png",
+        }
+    }
+}
+
+/// A QR code for `text` as an image in `format`
+///
+/// Uses medium error correction, which survives a smudged or glare-covered screen
+/// while keeping the code coarse enough to scan from across a kitchen.
+pub fn render(text: &str, format: QrFormat) -> Result<Vec<u8>> {
+    let code = QrCode::with_error_correction_level(text.as_bytes(), EcLevel::M)
+        .context("Failed to encode QR code")?;
+    match format {
+        QrFormat::Svg => Ok(code
+            .render::<svg::Color>()
+            .min_dimensions(256, 256)
+            .quiet_zone(true)
+            .build()
+            .into_bytes()),
+        QrFormat::Png => png(&code),
+    }
+}
+
+/// Black-on-white 8-bit grayscale PNG of `code`
+fn png(code: &QrCode) -> Result<Vec<u8>> {
+    let modules = code.width();
+    let colors = code.to_colors();
+    let size = (modules + 2 * QUIET_ZONE) * PNG_SCALE;
+
+    let mut pixels = vec![255u8; size * size];
+    for (index, color) in colors.iter().enumerate() {
+        if *color != Color::Dark {
+            continue;
+        }
+        let x = (index % modules + QUIET_ZONE) * PNG_SCALE;
+        let y = (index / modules + QUIET_ZONE) * PNG_SCALE;
+        for row in y..y + PNG_SCALE {
+            pixels[row * size + x..row * size + x + PNG_SCALE].fill(0);
+        }
+    }
+
+    let mut image = Vec::new();
+    let mut encoder = png::Encoder::new(&mut image, size as u32, size as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&pixels)?;
+    writer.finish()?;
+    Ok(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_qr_code() -> Result<()> {
+        let url = "https://recipes.example.com/api/v1/recipes/a1b2c3d4e5f6/html";
+
+        let svg = String::from_utf8(render(url, QrFormat::Svg)?)?;
+        assert!(svg.contains("<svg"));
+
+        let image = render(url, QrFormat::Png)?;
+        let decoder = png::Decoder::new(image.as_slice());
+        let reader = decoder.read_info()?;
+        let info = reader.info();
+        assert_eq!(info.width, info.height);
+        assert_eq!(info.width as usize % PNG_SCALE, 0);
+
+        assert_eq!(QrFormat::parse(" PNG"), Some(QrFormat::Png));
+        assert_eq!(QrFormat::parse("gif"), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+/// Units that stay decimal: nobody weighs out "12½ g"
+const METRIC_UNITS: &[&str] = &[
+    "mg", "g", "gram", "grams", "kg", "ml", "cl", "dl", "l", "liter", "liters", "litre", "litres",
+];
+
+/// How quantities are written for cooks: `0.33 cup` becomes `⅓ cup`, `1.5 tbsp`
+/// becomes `1½ tbsp`
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuantityFormat {
+    /// Denominators a quantity may be rounded to, e.g. `[2, 3, 4, 8]`; empty keeps decimals
+    pub denominators: Vec<u32>,
+    /// Largest difference from the exact value a fraction may have, e.g. `0.05`;
+    /// quantities further from every fraction are written as decimals
+    pub tolerance: f64,
+}
+
+impl Default for QuantityFormat {
+    fn default() -> Self {
+        QuantityFormat {
+            denominators: vec![2, 3, 4, 8],
+            tolerance: 0.05,
+        }
+    }
+}
+
+impl QuantityFormat {
+    /// Write a number as a whole number, a fraction (`⅓`, `1½`) or a decimal rounded
+    /// to two places
+    pub fn format(&self, value: f64) -> String {
+        if !value.is_finite() || value < 0.0 {
+            return decimal(value);
+        }
+
+        let whole = value.trunc();
+        let fraction = value - whole;
+        let closest = self
+            .denominators
+            .iter()
+            .filter(|&&denominator| denominator > 0)
+            .map(|&denominator| {
+                let numerator = (fraction * denominator as f64).round() as u32;
+                let error = (fraction - numerator as f64 / denominator as f64).abs();
+                (numerator, denominator, error)
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2));
+
+        match closest {
+            Some((numerator, denominator, error)) if error <= self.tolerance => {
+                if numerator == 0 {
+                    decimal(whole)
+                } else if numerator == denominator {
+                    decimal(whole + 1.0)
+                } else {
+                    let fraction = glyph(numerator, denominator);
+                    if whole == 0.0 {
+                        fraction
+                    } else if fraction.contains('/') {
+                        format!("{} {}", whole, fraction)
+                    } else {
+                        format!("{}{}", whole, fraction)
+                    }
+                }
+            }
+            // Only whole numbers are exact without denominators
+            _ if (value - value.round()).abs() < 1e-9 => decimal(value.round()),
+            _ => decimal(value),
+        }
+    }
+
+    /// Rewrite the number an amount such as `0.5 cup` or `1/3 tsp` starts with
+    ///
+    /// Amounts in metric units, or not starting with a number (`a pinch`), are kept as
+    /// they are.
+    pub fn format_amount(&self, amount: &str) -> String {
+        let (number, unit) = match amount.split_once(' ') {
+            Some((number, unit)) => (number, unit.trim()),
+            None => (amount, ""),
+        };
+        if METRIC_UNITS.contains(&unit.to_lowercase().as_str()) {
+            return amount.to_string();
+        }
+
+        match parse_number(number) {
+            Some(value) if unit.is_empty() => self.format(value),
+            Some(value) => format!("{} {}", self.format(value), unit),
+            None => amount.to_string(),
+        }
+    }
+}
+
+/// A plain (`0.5`) or fractional (`1/2`) number
+fn parse_number(text: &str) -> Option<f64> {
+    let value = match text.split_once('/') {
+        Some((numerator, denominator)) => {
+            let denominator: f64 = denominator.trim().parse().ok()?;
+            if denominator == 0.0 {
+                return None;
+            }
+            numerator.trim().parse::<f64>().ok()? / denominator
+        }
+        None => text.trim().parse().ok()?,
+    };
+    value.is_finite().then_some(value)
+}
+
+/// A number rounded to two decimal places, without trailing zeros
+fn decimal(value: f64) -> String {
+    let text = format!("{:.2}", value);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// The Unicode vulgar fraction for `numerator/denominator` if there is one
+fn glyph(numerator: u32, denominator: u32) -> String {
+    let divisor = gcd(numerator, denominator);
+    let (numerator, denominator) = (numerator / divisor, denominator / divisor);
+    let glyph = match (numerator, denominator) {
+        (1, 2) => "½",
+        (1, 3) => "⅓",
+        (2, 3) => "⅔",
+        (1, 4) => "¼",
+        (3, 4) => "¾",
+        (1, 5) => "⅕",
+        (2, 5) => "⅖",
+        (3, 5) => "⅗",
+        (4, 5) => "⅘",
+        (1, 6) => "⅙",
+        (5, 6) => "⅚",
+        (1, 8) => "⅛",
+        (3, 8) => "⅜",
+        (5, 8) => "⅝",
+        (7, 8) => "⅞",
+        _ => return format!("{}/{}", numerator, denominator),
+    };
+    glyph.to_string()
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_quantities() {
+        let format = QuantityFormat::default();
+        assert_eq!(format.format(0.33), "⅓");
+        assert_eq!(format.format(1.5), "1½");
+        assert_eq!(format.format(2.0), "2");
+        assert_eq!(format.format(0.98), "1");
+        assert_eq!(format.format(2.375), "2⅜");
+        // Too far from any eighth or third
+        assert_eq!(format.format(0.19), "0.19");
+
+        assert_eq!(format.format_amount("0.33 cup"), "⅓ cup");
+        assert_eq!(format.format_amount("3/4 tsp"), "¾ tsp");
+        assert_eq!(format.format_amount("1.50"), "1½");
+        assert_eq!(format.format_amount("12.5 g"), "12.5 g");
+        assert_eq!(format.format_amount("a pinch"), "a pinch");
+
+        let fifths = QuantityFormat {
+            denominators: vec![5, 7],
+            tolerance: 0.01,
+        };
+        assert_eq!(fifths.format(1.4), "1⅖");
+        assert_eq!(fifths.format(2.0 / 7.0), "2/7");
+        assert_eq!(fifths.format(1.0 + 3.0 / 7.0), "1 3/7");
+
+        let decimals = QuantityFormat {
+            denominators: Vec::new(),
+            ..Default::default()
+        };
+        assert_eq!(decimals.format_amount("0.333 cup"), "0.33 cup");
+        assert_eq!(decimals.format_amount("1/2 cup"), "0.5 cup");
+    }
+}
@@ -0,0 +1,447 @@
+use std::fmt::Write;
+
+use crate::parser::{
+    extract_metadata, front_matter_value, parse_front_matter, strip_front_matter, yaml_text,
+    MARKUP_STOPS,
+};
+use crate::quantity::QuantityFormat;
+
+/// How to render a recipe
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HtmlOptions {
+    /// Only the `<article>`, for embedding in another page, instead of a whole document
+    pub fragment: bool,
+    /// Compact black-and-white layout for paper, with checkboxes by the ingredients
+    pub print: bool,
+}
+
+impl HtmlOptions {
+    /// Key telling renders with different options apart in the derived output cache
+    pub fn cache_kind(&self) -> &'static str {
+        match (self.print, self.fragment) {
+            (false, false) => "html",
+            (false, true) => "html-fragment",
+            (true, false) => "html-print",
+            (true, true) => "html-print-fragment",
+        }
+    }
+}
+
+/// Styles for standalone pages; fragments take the styles of the page they are put in
+const STYLE: &str = "body{font-family:system-ui,sans-serif;line-height:1.5;max-width:48rem;\
+margin:2rem auto;padding:0 1rem;color:#222}.quantity,.timer{font-weight:600}\
+.cookware{font-style:italic}.metadata{display:grid;grid-template-columns:max-content 1fr;\
+gap:.25rem 1rem}.metadata dt{color:#666}.note{color:#555;border-left:3px solid #ddd;\
+padding-left:1rem}";
+
+/// Styles for printing: one narrow column in black on white, small type and no colours
+const PRINT_STYLE: &str = "body{font-family:Georgia,serif;font-size:11pt;line-height:1.35;\
+max-width:40rem;margin:1rem auto;padding:0 1rem;color:#000;background:#fff}h1{font-size:18pt;\
+margin:0 0 .25rem}h2{font-size:12pt;margin:1rem 0 .25rem;border-bottom:1px solid #000}\
+h3{font-size:11pt;margin:.75rem 0 .25rem}a{color:#000;text-decoration:none}\
+.metadata{display:flex;flex-wrap:wrap;gap:0 1rem;margin:0}.metadata dt{font-weight:600}\
+.metadata dd{margin:0}.ingredients ul{list-style:none;padding:0;columns:2}\
+.ingredients li{break-inside:avoid}.quantity{font-weight:600}ol{padding-left:1.5rem}\
+.note{font-style:italic}@page{margin:1.5cm}";
+
+/// Render recipe content as HTML: title, description and metadata, then the ingredient
+/// list and the steps with their quantities, cookware and timers marked up inline
+///
+/// Ingredient quantities are written the cook-friendly way `quantities` describes
+/// (`0.5%cup` shows as `½ cup`).
+///
+/// # Examples
+/// ```
+/// # use cooklang_store::quantity::QuantityFormat;
+/// # use cooklang_store::render::{render_html, HtmlOptions};
+/// let content = "---\ntitle: Eggs\n---\n\nBoil @eggs{2} for ~{10%min}.";
+/// let options = HtmlOptions { fragment: true, ..Default::default() };
+/// let html = render_html(content, "Eggs", &options, &QuantityFormat::default());
+/// assert!(html.contains(r#"<li><span class="quantity">2</span> eggs</li>"#));
+/// assert!(html.contains(r#"<span class="timer">10 min</span>"#));
+/// ```
+pub fn render_html(
+    content: &str,
+    title: &str,
+    options: &HtmlOptions,
+    quantities: &QuantityFormat,
+) -> String {
+    let body = parse_body(content, quantities);
+    let mut article = String::new();
+    let _ = write!(
+        article,
+        "<article class=\"recipe\">\n<h1>{}</h1>\n",
+        escape(title)
+    );
+
+    let front_matter = parse_front_matter(content).unwrap_or_default();
+    if let Some(description) =
+        front_matter_value(&front_matter, &["description"]).and_then(yaml_text)
+    {
+        let _ = writeln!(
+            article,
+            "<p class=\"description\">{}</p>",
+            escape(&description)
+        );
+    }
+    article.push_str(&metadata_list(content));
+
+    if !body.ingredients.is_empty() {
+        article.push_str("<section class=\"ingredients\">\n<h2>Ingredients</h2>\n<ul>\n");
+        for (name, amounts) in &body.ingredients {
+            let amounts: Vec<&str> = amounts
+                .iter()
+                .map(String::as_str)
+                .filter(|a| !a.is_empty())
+                .collect();
+            let item = if amounts.is_empty() {
+                escape(name)
+            } else {
+                format!(
+                    "<span class=\"quantity\">{}</span> {}",
+                    escape(&amounts.join(" + ")),
+                    escape(name)
+                )
+            };
+            if options.print {
+                let _ = writeln!(
+                    article,
+                    "<li><label><input type=\"checkbox\"> {}</label></li>",
+                    item
+                );
+            } else {
+                let _ = writeln!(article, "<li>{}</li>", item);
+            }
+        }
+        article.push_str("</ul>\n</section>\n");
+    }
+
+    if !body.cookware.is_empty() {
+        article.push_str("<section class=\"cookware\">\n<h2>Cookware</h2>\n<ul>\n");
+        for name in &body.cookware {
+            let _ = writeln!(article, "<li>{}</li>", escape(name));
+        }
+        article.push_str("</ul>\n</section>\n");
+    }
+
+    article.push_str("<section class=\"steps\">\n<h2>Steps</h2>\n");
+    let mut in_list = false;
+    for block in &body.blocks {
+        let is_step = matches!(block, Block::Step(_));
+        if is_step != in_list {
+            article.push_str(if is_step { "<ol>\n" } else { "</ol>\n" });
+            in_list = is_step;
+        }
+        match block {
+            Block::Step(html) => {
+                let _ = writeln!(article, "<li>{}</li>", html);
+            }
+            Block::Section(name) => {
+                let _ = writeln!(article, "<h3>{}</h3>", escape(name));
+            }
+            Block::Note(text) => {
+                let _ = writeln!(article, "<p class=\"note\">{}</p>", escape(text));
+            }
+        }
+    }
+    if in_list {
+        article.push_str("</ol>\n");
+    }
+    article.push_str("</section>\n</article>\n");
+
+    if options.fragment {
+        return article;
+    }
+    format!(
+        "<!doctype html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape(title),
+        if options.print { PRINT_STYLE } else { STYLE },
+        article
+    )
+}
+
+/// Servings, times, author, source and tags as a definition list (empty if none are set)
+fn metadata_list(content: &str) -> String {
+    let metadata = extract_metadata(content);
+    let mut rows: Vec<(&str, String)> = Vec::new();
+    if let Some(servings) = &metadata.servings {
+        rows.push(("Servings", escape(servings)));
+    }
+    if let Some(minutes) = metadata.prep_minutes {
+        rows.push(("Prep time", format_minutes(minutes)));
+    }
+    if let Some(minutes) = metadata.cook_minutes {
+        rows.push(("Cook time", format_minutes(minutes)));
+    }
+    if let Some(author) = &metadata.author {
+        rows.push(("Author", escape(author)));
+    }
+    if let Some(source) = &metadata.source {
+        let source = if source.starts_with("http://") || source.starts_with("https://") {
+            format!("<a href=\"{0}\">{0}</a>", escape(source))
+        } else {
+            escape(source)
+        };
+        rows.push(("Source", source));
+    }
+    if !metadata.tags.is_empty() {
+        rows.push(("Tags", escape(&metadata.tags.join(", "))));
+    }
+    if rows.is_empty() {
+        return String::new();
+    }
+
+    let mut list = String::from("<dl class=\"metadata\">\n");
+    for (label, value) in rows {
+        let _ = writeln!(list, "<dt>{}</dt><dd>{}</dd>", label, value);
+    }
+    list.push_str("</dl>\n");
+    list
+}
+
+/// `90` → `1 h 30 min`
+fn format_minutes(minutes: u32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{} min", m),
+        (h, 0) => format!("{} h", h),
+        (h, m) => format!("{} h {} min", h, m),
+    }
+}
+
+enum Block {
+    /// A step, already rendered to HTML
+    Step(String),
+    /// `== Name ==` heading
+    Section(String),
+    /// `> text` note
+    Note(String),
+}
+
+struct Body<'a> {
+    blocks: Vec<Block>,
+    /// Name → formatted amounts, in order of first use
+    ingredients: Vec<(String, Vec<String>)>,
+    cookware: Vec<String>,
+    quantities: &'a QuantityFormat,
+}
+
+/// Split the recipe body into sections, notes and steps, collecting ingredients and
+/// cookware on the way (same paragraph rules as `extract_step_texts`)
+fn parse_body<'a>(content: &str, quantities: &'a QuantityFormat) -> Body<'a> {
+    let mut text = strip_front_matter(content).to_string();
+    while let Some(start) = text.find("[-") {
+        let end = text[start..]
+            .find("-]")
+            .map(|end| start + end + 2)
+            .unwrap_or(text.len());
+        text.replace_range(start..end, "");
+    }
+
+    let mut body = Body {
+        blocks: Vec::new(),
+        ingredients: Vec::new(),
+        cookware: Vec::new(),
+        quantities,
+    };
+    let mut paragraph: Vec<&str> = Vec::new();
+
+    for line in text.lines() {
+        let line = line.split("--").next().unwrap_or_default().trim();
+        if line.starts_with(">>") {
+            continue;
+        }
+        if line.starts_with('=') {
+            flush(&mut paragraph, &mut body);
+            let name = line.trim_matches('=').trim();
+            if !name.is_empty() {
+                body.blocks.push(Block::Section(name.to_string()));
+            }
+        } else if let Some(note) = line.strip_prefix('>') {
+            flush(&mut paragraph, &mut body);
+            body.blocks.push(Block::Note(note.trim().to_string()));
+        } else if line.is_empty() {
+            flush(&mut paragraph, &mut body);
+        } else {
+            paragraph.push(line);
+        }
+    }
+    flush(&mut paragraph, &mut body);
+    body
+}
+
+/// Finish the paragraph collected so far as a step
+fn flush(paragraph: &mut Vec<&str>, body: &mut Body) {
+    if !paragraph.is_empty() {
+        let step = render_step(&paragraph.join(" "), body);
+        if !step.is_empty() {
+            body.blocks.push(Block::Step(step));
+        }
+        paragraph.clear();
+    }
+}
+
+/// Render a step's markup to HTML, recording its ingredients and cookware in `body`
+fn render_step(text: &str, body: &mut Body) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(pos) = rest.find(['@', '#', '~']) {
+        out.push_str(&escape(&rest[..pos]));
+        let marker = &rest[pos..pos + 1];
+        let after = rest[pos + 1..].trim_start_matches(['&', '?', '+', '-']);
+
+        let braced = after
+            .find('{')
+            .filter(|&open| !after[..open].contains(MARKUP_STOPS))
+            .and_then(|open| after[open..].find('}').map(|close| (open, open + close)));
+        let (name, amount, tail) = match braced {
+            Some((open, close)) => (&after[..open], &after[open + 1..close], &after[close + 1..]),
+            None => {
+                let end = after
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                (&after[..end], "", &after[end..])
+            }
+        };
+        // `@flour|wheat flour{}` shows the alias
+        let name = name
+            .rsplit('|')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        let amount = amount
+            .trim()
+            .trim_start_matches('=')
+            .replace('%', " ")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        match marker {
+            "@" if !name.is_empty() => {
+                let amount = body.quantities.format_amount(&amount);
+                if amount.is_empty() {
+                    let _ = write!(out, "<span class=\"ingredient\">{}</span>", escape(&name));
+                } else {
+                    let _ = write!(
+                        out,
+                        "<span class=\"ingredient\">{} <span class=\"quantity\">({})</span></span>",
+                        escape(&name),
+                        escape(&amount)
+                    );
+                }
+                match body
+                    .ingredients
+                    .iter_mut()
+                    .find(|(existing, _)| existing.eq_ignore_ascii_case(&name))
+                {
+                    Some((_, amounts)) => amounts.push(amount),
+                    None => body.ingredients.push((name, vec![amount])),
+                }
+            }
+            "#" if !name.is_empty() => {
+                let _ = write!(out, "<span class=\"cookware\">{}</span>", escape(&name));
+                if !body
+                    .cookware
+                    .iter()
+                    .any(|existing| existing.eq_ignore_ascii_case(&name))
+                {
+                    body.cookware.push(name);
+                }
+            }
+            "~" if !amount.is_empty() || !name.is_empty() => {
+                let label = if amount.is_empty() { &name } else { &amount };
+                let _ = write!(out, "<span class=\"timer\">{}</span>", escape(label));
+            }
+            // A lone marker, e.g. "#1" or an email address
+            _ => out.push_str(&escape(marker)),
+        }
+        rest = tail;
+    }
+    out.push_str(&escape(rest));
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Escape text for HTML element content and attribute values
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_html() {
+        let content = "---\ntitle: Pancakes\ndescription: Fluffy & quick\nservings: 4\n\
+                       cook time: 90\nsource: https://example.com/pancakes\n---\n\n\
+                       == Batter ==\n\nMix @flour{200%g} with @eggs{2} in a #large bowl{}.\n\
+                       -- a comment\n\n> Rest the batter if you can.\n\nAdd more @flour{1%tbsp} \
+                       and a pinch of @salt.\n\n== Cooking ==\n\nFry for ~{3%minutes} <per side>.";
+        let html = render_html(
+            content,
+            "Pancakes",
+            &HtmlOptions::default(),
+            &QuantityFormat::default(),
+        );
+
+        assert!(html.starts_with("<!doctype html>"));
+        assert!(html.contains("<title>Pancakes</title>"));
+        assert!(html.contains("<p class=\"description\">Fluffy &amp; quick</p>"));
+        assert!(html.contains("<dt>Servings</dt><dd>4</dd>"));
+        assert!(html.contains("<dt>Cook time</dt><dd>1 h 30 min</dd>"));
+        assert!(html.contains("<a href=\"https://example.com/pancakes\">"));
+
+        // Repeated ingredients are listed once with all their amounts
+        assert!(html.contains("<li><span class=\"quantity\">200 g + 1 tbsp</span> flour</li>"));
+        assert!(html.contains("<li>salt</li>"));
+        assert!(html.contains("<h2>Cookware</h2>\n<ul>\n<li>large bowl</li>"));
+
+        assert!(html.contains(
+            "<li>Mix <span class=\"ingredient\">flour <span class=\"quantity\">(200 g)</span></span> \
+             with <span class=\"ingredient\">eggs <span class=\"quantity\">(2)</span></span> in a \
+             <span class=\"cookware\">large bowl</span>.</li>"
+        ));
+        assert!(html.contains("<h3>Batter</h3>"));
+        assert!(html.contains("<p class=\"note\">Rest the batter if you can.</p>"));
+        assert!(html.contains("<span class=\"timer\">3 minutes</span> &lt;per side&gt;."));
+        assert!(!html.contains("comment"));
+
+        let options = HtmlOptions {
+            fragment: true,
+            ..Default::default()
+        };
+        let fragment = render_html(content, "Pancakes", &options, &QuantityFormat::default());
+        assert!(fragment.starts_with("<article class=\"recipe\">"));
+        assert!(!fragment.contains("<style>"));
+
+        let options = HtmlOptions {
+            print: true,
+            ..Default::default()
+        };
+        let print = render_html(content, "Pancakes", &options, &QuantityFormat::default());
+        assert!(print.contains(PRINT_STYLE));
+        assert!(print.contains("<li><label><input type=\"checkbox\"> salt</label></li>"));
+        assert_ne!(options.cache_kind(), HtmlOptions::default().cache_kind());
+
+        // Quantities are written as fractions, except metric ones
+        let content = "Whisk @milk{0.33%cup} with @sugar{1.5%tbsp} and @butter{12.5%g}.";
+        let html = render_html(content, "Custard", &options, &QuantityFormat::default());
+        assert!(html.contains("<span class=\"quantity\">⅓ cup</span> milk"));
+        assert!(html.contains("sugar <span class=\"quantity\">(1½ tbsp)</span>"));
+        assert!(html.contains("<span class=\"quantity\">12.5 g</span> butter"));
+    }
+}
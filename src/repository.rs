@@ -1,9 +1,51 @@
-use anyhow::{anyhow, Result};
-use std::path::Path;
-
-use crate::cache::{generate_recipe_id, CachedRecipe, RecipeIndex};
-use crate::parser::{extract_recipe_title, generate_filename, parse_recipe, should_rename_file};
-use crate::storage::RecipeStorage;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use dashmap::DashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::aisles::Aisles;
+use crate::aliases::CategoryAliases;
+use crate::cache::{
+    content_hash, generate_recipe_id, ingredient_names, CachedRecipe, CategoryCount, RecipeFilter,
+    RecipeIndex, SortOrder,
+};
+use crate::config::{ConfigReload, StoreConfig};
+use crate::derived::DerivedCache;
+use crate::events::{EventBus, EventKind, RepositoryEvent};
+use crate::favorites::Favorites;
+use crate::hooks::{RecipeWrite, WriteHook};
+use crate::import::{to_cooklang, Fidelity, ImportOutcome, ImportedRecipe};
+use crate::menus::{parse_menu, reference_candidates, Menu};
+use crate::metrics::{IndexMetrics, StorageMetrics};
+use crate::notes::{notes_path, CookingNote, RecipeNotes};
+use crate::notifications::Notifier;
+use crate::nutrition::{NutritionDatabase, NutritionEstimate};
+use crate::pantry::{Pantry, PantryItem};
+use crate::paprika;
+use crate::parser::{
+    extract_active_minutes, extract_custom_fields, extract_ingredient_amounts, extract_metadata,
+    extract_recipe_title, extract_step_texts, extract_total_minutes, extract_visibility,
+    generate_filename, normalize_front_matter, should_rename_file, FrontMatterPatch, RecipeError,
+    RecipeMetadata, RecipeParser, ScalableRecipe,
+};
+use crate::render::{render_html, HtmlOptions};
+use crate::search::{Highlight, SearchDocument, SearchHit, SearchProvider};
+use crate::shares::{Share, ShareLinks};
+use crate::shopping::{ItemUpdate, ShoppingItem, ShoppingList, ShoppingLists};
+use crate::storage::{
+    is_below, BlameLine, CommitAuthor, CommitInfo, DraftFile, FileChanges, FileStat, GcReport,
+    GitOptions, LastModified, MeteredStorage, MirrorReport, MirroredStorage, PathTaken,
+    RecipeStorage, VersionTag,
+};
+use crate::synonyms::Synonyms;
+use crate::templates::{
+    builtin_templates, instantiate, is_template_path, template_name, Template, TEMPLATES_DIR,
+};
+use crate::uploads::{UploadSession, UploadStore};
+use crate::webhooks::{DeliverySettings, Webhooks};
 
 /// Represents the structure of a recipe (for API and display)
 #[derive(Debug, Clone)]
@@ -13,13 +55,263 @@ pub struct Recipe {
     pub name: String,
     pub description: Option<String>,
     pub category: Option<String>,
+    /// Cooklang source; `None` in listings, which are served from the index without
+    /// reading any files (see [`RecipeRepository::with_content`])
+    pub content: Option<String>,
+    /// When the file last changed, as of the last time it was indexed
+    pub last_modified: Option<LastModified>,
+}
+
+/// Directory (relative to the storage root) holding soft-deleted recipes
+const TRASH_DIR: &str = ".trash";
+
+/// Persisted category aliases (old → new paths of renamed categories)
+const CATEGORY_ALIASES_FILE: &str = ".cooklang-store/category-aliases.json";
+
+/// Persisted favorite recipes per user
+const FAVORITES_FILE: &str = ".cooklang-store/favorites.json";
+
+/// Persisted share links
+const SHARES_FILE: &str = ".cooklang-store/shares.json";
+
+/// Persisted shopping lists
+const SHOPPING_LISTS_FILE: &str = ".cooklang-store/shopping-lists.json";
+
+/// Custom units (cooklang `units.toml` format) used when parsing recipes
+const UNITS_FILE: &str = ".cooklang-store/units.toml";
+
+/// Ingredient synonym groups (`scallion = green onion = spring onion`, one per line)
+const SYNONYMS_FILE: &str = ".cooklang-store/synonyms.txt";
+
+/// Store aisles for sorting shopping lists (cooklang `aisle.conf` layout)
+const AISLES_FILE: &str = ".cooklang-store/aisle.conf";
+
+/// Persisted pantry (ingredients on hand)
+const PANTRY_FILE: &str = ".cooklang-store/pantry.json";
+
+/// Staging directory (relative to the data dir) for resumable uploads, never committed
+const UPLOADS_DIR: &str = ".cooklang-store/uploads";
+
+/// Webhooks and their delivery log (relative to the data dir), never committed as the
+/// webhooks hold signing secrets
+const WEBHOOKS_DIR: &str = ".cooklang-store/webhooks";
+
+/// Snapshot of the recipe index (relative to the data dir), never committed
+const INDEX_SNAPSHOT_FILE: &str = ".cooklang-store/cache/index.json";
+
+/// Directories written outside storage, so never copied to a mirror
+const UNMIRRORED_DIRS: [&str; 3] = [UPLOADS_DIR, WEBHOOKS_DIR, ".cooklang-store/cache"];
+
+/// Timestamp format of the per-deletion directory inside the trash
+const TRASH_TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S%.3fZ";
+
+/// How often expired derived outputs and uploads are swept away in the background
+const SWEEP_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// An ingredient (by canonical name) and the recipes using it under any of its names
+#[derive(Debug, Clone)]
+pub struct IngredientUsage {
+    pub name: String,
+    /// Other names the ingredient goes by in recipes, merged into this entry
+    pub synonyms: Vec<String>,
+    pub recipes: Vec<Recipe>,
+}
+
+/// A recipe with its ingredients split by whether they are in the pantry
+#[derive(Debug, Clone)]
+pub struct CookableRecipe {
+    pub recipe: Recipe,
+    /// Distinct ingredient names (lowercased) on hand
+    pub on_hand: Vec<String>,
+    /// Distinct ingredient names (lowercased) not on hand
+    pub missing: Vec<String>,
+}
+
+impl CookableRecipe {
+    /// Share of the recipe's ingredients that are on hand, from 0 to 1
+    pub fn coverage(&self) -> f64 {
+        let total = self.on_hand.len() + self.missing.len();
+        if total == 0 {
+            0.0
+        } else {
+            self.on_hand.len() as f64 / total as f64
+        }
+    }
+}
+
+/// A recipe moved to the trash, stored at `.trash/{timestamp}/{original git_path}`
+#[derive(Debug, Clone)]
+pub struct TrashedRecipe {
+    pub trash_id: String,
+    pub trash_path: String,
+    pub original_path: String,
+    pub name: String,
+    pub category: Option<String>,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// A recipe edit kept on a draft branch until it is published
+///
+/// The draft ID is the recipe ID the recipe has (or will have) on the main branch.
+#[derive(Debug, Clone)]
+pub struct Draft {
+    pub draft_id: String,
+    pub git_path: String,
+    pub file_name: String,
+    pub name: String,
+    pub category: Option<String>,
     pub content: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A recipe matching a search, with where and how well it matched
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    pub recipe: Recipe,
+    /// Relevance, higher is better (only comparable within one query)
+    pub score: f32,
+    pub highlights: Vec<Highlight>,
+}
+
+/// Outcome of bringing the index up to date with storage
+#[derive(Debug, Clone)]
+pub struct ReindexReport {
+    /// Only files changed since the last indexed revision were looked at
+    pub incremental: bool,
+    /// Recipe files parsed
+    pub parsed: usize,
+    /// Recipes dropped because their files are gone
+    pub removed: usize,
+    /// Revision the index now reflects (git storage)
+    pub revision: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// Everything a full rebuild reads from storage, gathered on the blocking pool
+struct StorageScan {
+    /// Revision taken before discovery so changes made while scanning are picked up
+    /// next time
+    revision: Option<String>,
+    /// Recipe files with their stat (including the last commit) and content
+    files: Vec<(String, FileStat, Result<String>)>,
+}
+
+impl StorageScan {
+    fn read(storage: &dyn RecipeStorage) -> Result<Self> {
+        let revision = storage.head_revision()?;
+        let mut cook_files = storage.discover_files()?;
+        cook_files.retain(|(git_path, _)| !is_template_path(git_path));
+        let git_paths: Vec<String> = cook_files
+            .iter()
+            .map(|(git_path, _)| git_path.clone())
+            .collect();
+        let mut modified = storage.last_modified(&git_paths).unwrap_or_else(|e| {
+            tracing::warn!("Failed to look up when recipes last changed: {}", e);
+            HashMap::new()
+        });
+
+        let total = cook_files.len();
+        let mut files = Vec::with_capacity(total);
+        for (processed, (git_path, mut stat)) in cook_files.into_iter().enumerate() {
+            crate::deadline::check(processed, Some(total))?;
+            stat.commit = modified
+                .remove(&git_path)
+                .filter(|last_modified| last_modified.revision.is_some());
+            let content = storage.read_file(&git_path);
+            files.push((git_path, stat, content));
+        }
+        Ok(Self { revision, files })
+    }
+}
+
+/// Outcome of adding and removing tags across recipes
+#[derive(Debug, Clone, Default)]
+pub struct RetagReport {
+    /// Recipes whose tags changed
+    pub updated: Vec<String>,
+    /// Recipes that already had the requested tags
+    pub unchanged: Vec<String>,
+}
+
+/// Outcome of normalizing front matter across recipes
+#[derive(Debug, Clone, Default)]
+pub struct NormalizeReport {
+    /// Recipes rewritten (or, for a dry run, that would be)
+    pub updated: Vec<String>,
+    /// Recipes already in canonical form or without front matter
+    pub unchanged: usize,
+    /// Recipes left alone because their front matter could not be normalized, with the reason
+    pub failed: Vec<(String, String)>,
+}
+
+/// Outcome of renaming a category
+#[derive(Debug, Clone)]
+pub struct CategoryRename {
+    /// Old category path, as it was stored
+    pub from: String,
+    /// New category path
+    pub to: String,
+    /// Old and new git path of every recipe moved
+    pub renamed: Vec<(String, String)>,
+}
+
+/// A recipe file whose name is not the one its title generates
+#[derive(Debug, Clone)]
+pub struct MisalignedFile {
+    pub git_path: String,
+    pub category: Option<String>,
+    pub file_name: String,
+    pub title: String,
+    /// [`generate_filename`] of the title, which the next update renames the file to
+    pub expected_filename: String,
+}
+
+/// One ancestor segment of a category path (e.g. "meals" and "meals/meat" for "meals/meat")
+#[derive(Debug, Clone)]
+pub struct CategoryCrumb {
+    pub name: String,
+    pub path: String,
+    /// Number of recipes in this category and its subcategories
+    pub recipe_count: usize,
 }
 
 /// Manages recipe operations across storage backend and in-memory cache
 pub struct RecipeRepository {
     cache: RecipeIndex,
-    storage: Box<dyn RecipeStorage>,
+    storage: Arc<dyn RecipeStorage>,
+    /// Timings of the operations on `storage`
+    storage_metrics: Arc<StorageMetrics>,
+    /// Rebuilds of `cache` and files that failed to parse
+    index_metrics: IndexMetrics,
+    search: Box<dyn SearchProvider>,
+    aliases: CategoryAliases,
+    favorites: Favorites,
+    pantry: Pantry,
+    shares: ShareLinks,
+    shopping_lists: ShoppingLists,
+    // menu_id -> parsed menu
+    menus: DashMap<String, Menu>,
+    uploads: UploadStore,
+    webhooks: Arc<Webhooks>,
+    /// Recipe changes, for reactions that don't need to finish before a write returns
+    events: EventBus,
+    derived: DerivedCache,
+    nutrition: NutritionDatabase,
+    /// Replaced as a whole when the units file is reloaded
+    parser: RwLock<Arc<RecipeParser>>,
+    /// Replaced as a whole when the synonyms file is reloaded
+    synonyms: RwLock<Arc<Synonyms>>,
+    /// Replaced as a whole when the configuration is reloaded
+    config: RwLock<Arc<StoreConfig>>,
+    snapshot_path: Option<PathBuf>,
+    /// Storage revision the cache was last brought up to date with
+    indexed_revision: RwLock<Option<String>>,
+    /// Directories whose recipes cannot be changed (git submodules), as of the last index
+    read_only_dirs: RwLock<Vec<String>>,
+    /// Checks run before recipe content is written, set up from the config
+    configured_hooks: RwLock<Vec<Arc<dyn WriteHook>>>,
+    /// Checks registered in code, run after the configured ones
+    hooks: RwLock<Vec<Arc<dyn WriteHook>>>,
 }
 
 impl RecipeRepository {
@@ -30,68 +322,490 @@ impl RecipeRepository {
 
     /// Create a new recipe repository with a specific storage backend
     pub async fn with_storage(repo_path: &Path, storage_type: &str) -> Result<Self> {
-        let storage = crate::storage::create_storage(storage_type, repo_path).await?;
+        Self::with_storage_and_search(repo_path, storage_type, "memory").await
+    }
+
+    /// Create a new recipe repository with specific storage and search backends
+    pub async fn with_storage_and_search(
+        repo_path: &Path,
+        storage_type: &str,
+        search_type: &str,
+    ) -> Result<Self> {
+        Self::with_config(repo_path, storage_type, search_type, StoreConfig::default()).await
+    }
+
+    /// Create a new recipe repository with specific backends and runtime configuration
+    pub async fn with_config(
+        repo_path: &Path,
+        storage_type: &str,
+        search_type: &str,
+        config: StoreConfig,
+    ) -> Result<Self> {
+        let git_options = GitOptions {
+            commit_window: Duration::from_secs(config.commit_batch_window_secs),
+            signing: config.commit_signing(),
+        };
+        let backend = if storage_type == "git" { "git" } else { "disk" };
+        let storage_metrics = Arc::new(StorageMetrics::new(backend));
+        let mut primary = crate::storage::create_storage(
+            storage_type,
+            repo_path,
+            &git_options,
+            !config.remove_empty_dirs,
+        )
+        .await?;
+        if let Some(mirror_path) = &config.mirror_path {
+            let mirror = crate::storage::create_storage(
+                &config.mirror_storage,
+                Path::new(mirror_path),
+                &git_options,
+                !config.remove_empty_dirs,
+            )
+            .await
+            .with_context(|| format!("Failed to open mirror at {}", mirror_path))?;
+            primary = Box::new(MirroredStorage::new(primary, mirror));
+        }
+        let storage: Arc<dyn RecipeStorage> =
+            Arc::new(MeteredStorage::new(primary, Arc::clone(&storage_metrics)));
+        let search = crate::search::create_search_provider(search_type)?;
         let cache = RecipeIndex::new();
 
-        let repo = RecipeRepository { cache, storage };
+        let aliases = CategoryAliases::new(config.case_insensitive_paths);
+        let uploads = UploadStore::new(&repo_path.join(UPLOADS_DIR))?;
+        let webhooks = Webhooks::new(
+            &repo_path.join(WEBHOOKS_DIR),
+            DeliverySettings::from_config(&config),
+        );
+        let derived = DerivedCache::new(Duration::from_secs(config.derived_cache_ttl_secs));
+        let nutrition =
+            NutritionDatabase::with_overrides(config.nutrition_db.as_deref().map(Path::new));
+        let snapshot_path = config
+            .index_snapshot
+            .then(|| repo_path.join(INDEX_SNAPSHOT_FILE));
+        let hooks = crate::hooks::from_config(&config);
+
+        let repo = RecipeRepository {
+            cache,
+            storage,
+            storage_metrics,
+            index_metrics: IndexMetrics::new(),
+            search,
+            aliases,
+            favorites: Favorites::new(),
+            pantry: Pantry::new(),
+            shares: ShareLinks::new(config.share_secret.as_deref()),
+            shopping_lists: ShoppingLists::new(),
+            menus: DashMap::new(),
+            uploads,
+            webhooks: Arc::new(webhooks),
+            events: EventBus::new(),
+            derived,
+            nutrition,
+            parser: RwLock::new(Arc::new(RecipeParser::bundled())),
+            synonyms: RwLock::new(Arc::default()),
+            config: RwLock::new(Arc::new(config)),
+            snapshot_path,
+            indexed_revision: RwLock::new(None),
+            read_only_dirs: RwLock::new(Vec::new()),
+            configured_hooks: RwLock::new(hooks),
+            hooks: RwLock::new(Vec::new()),
+        };
+
+        repo.load_category_aliases();
+        repo.load_favorites();
+        repo.load_pantry();
+        repo.load_shares();
+        repo.load_shopping_lists();
+        repo.load_webhooks();
+        let webhooks = Arc::clone(&repo.webhooks);
+        repo.events.spawn_listener("Webhooks", move |event| {
+            webhooks.dispatch(&event);
+        });
+        if let Some(notifier) = Notifier::from_config(&repo.config()) {
+            let notifier = Arc::new(notifier);
+            repo.events.spawn_listener("Notifications", move |event| {
+                notifier.notify(&event);
+            });
+        }
+        repo.load_units();
+        repo.load_synonyms();
+        repo.purge_expired_uploads();
+
+        // Drop trashed recipes past their retention period
+        if let Err(e) = repo.purge_expired_trash() {
+            tracing::warn!("Failed to purge expired trash: {}", e);
+        }
 
-        // Rebuild cache from storage on initialization
-        repo.rebuild_from_storage().await?;
+        // Build the cache from storage, reusing unchanged entries from the last snapshot
+        repo.load_from_snapshot().await?;
 
         Ok(repo)
     }
 
     /// Rebuild the entire cache from storage files
     pub async fn rebuild_from_storage(&self) -> Result<()> {
-        self.cache.clear();
+        self.rebuild_index(HashMap::new()).await?;
+        Ok(())
+    }
 
-        let cook_files = self.storage.discover_files()?;
+    /// Rebuild the cache, taking entries from the index snapshot for files whose
+    /// content hash is unchanged and parsing the rest
+    async fn load_from_snapshot(&self) -> Result<()> {
+        let previous = match &self.snapshot_path {
+            Some(path) => RecipeIndex::load_snapshot(path, self.parser().units_hash())
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Ignoring index snapshot: {}", e);
+                    None
+                }),
+            None => None,
+        }
+        .unwrap_or_default();
+        let snapshot_len = previous.len();
+
+        let parsed = self.rebuild_index(previous).await?;
+        tracing::info!(
+            "Indexed {} recipe(s), {} parsed and {} from the snapshot",
+            self.cache.len(),
+            parsed,
+            self.cache.len() - parsed
+        );
 
-        for git_path in cook_files {
-            // Read the file content
-            match self.storage.read_file(&git_path) {
-                Ok(content) => {
-                    // Extract category from path (recipes/{category}/{...}/{slug}.cook)
-                    let category = self.extract_category_from_path(&git_path);
-
-                    // Try to extract title from YAML front matter
-                    let recipe_name = match extract_recipe_title(&content) {
-                        Ok(title) => title,
-                        Err(_) => {
-                            // Fallback to path-based name if YAML front matter missing
-                            tracing::warn!(
-                                "Recipe {} missing YAML front matter, using path-based name",
-                                git_path
+        // Refresh the snapshot so a crash before shutdown doesn't lose this work
+        if parsed > 0 || self.cache.len() != snapshot_len {
+            if let Err(e) = self.save_index_snapshot() {
+                tracing::warn!("Failed to save index snapshot: {}", e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the index snapshot so the next startup only parses changed files
+    /// (no-op when `INDEX_SNAPSHOT` is disabled)
+    pub fn save_index_snapshot(&self) -> Result<()> {
+        let Some(path) = &self.snapshot_path else {
+            return Ok(());
+        };
+
+        // Keep the snapshot out of `git add -A` in the data directory
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+            let gitignore = dir.join(".gitignore");
+            if !gitignore.exists() {
+                std::fs::write(&gitignore, "*\n")?;
+            }
+        }
+
+        self.cache.save_snapshot(path, self.parser().units_hash())
+    }
+
+    /// Bring the cache up to date with storage
+    ///
+    /// With git storage only files changed since the last indexed commit (plus uncommitted
+    /// edits) are parsed, so reindexing after a pull is near-instant. Backends without
+    /// revisions (disk storage) parse the files whose size or modification time differ
+    /// from when they were indexed. Falls back to a full rebuild when `full` is set or the
+    /// backend cannot list changes. Changes picked up incrementally are published as
+    /// events, as if made through the API.
+    pub async fn reindex(&self, full: bool) -> Result<ReindexReport> {
+        let started = Instant::now();
+        let revision = self.on_storage(|storage| storage.head_revision()).await?;
+        let since = self.indexed_revision_snapshot();
+
+        let changes = match since {
+            Some(since) if !full => {
+                self.on_storage(move |storage| storage.changed_files(&since))
+                    .await?
+            }
+            None if !full && revision.is_none() => Some(self.stale_files().await?),
+            _ => None,
+        };
+
+        let (incremental, parsed, removed) = match changes {
+            Some(mut changes) => {
+                changes
+                    .updated
+                    .retain(|git_path| !is_template_path(git_path));
+                changes
+                    .deleted
+                    .retain(|git_path| !is_template_path(git_path));
+                let total = changes.updated.len() + changes.deleted.len();
+                let mut removed = 0;
+                for (processed, git_path) in changes.deleted.iter().enumerate() {
+                    crate::deadline::check(processed, Some(total))?;
+                    self.index_metrics.clear_failure(git_path);
+                    match self.cache.get(git_path) {
+                        Some(cached) => {
+                            self.publish(
+                                RepositoryEvent::new(
+                                    EventKind::RecipeDeleted,
+                                    git_path,
+                                    &cached.name,
+                                    cached.category.as_deref(),
+                                    None,
+                                ),
+                                None,
                             );
-                            self.path_to_name(&git_path)
+                            removed += 1;
                         }
-                    };
-
-                    match parse_recipe(&content, &recipe_name) {
-                        Ok(parsed_recipe) => {
-                            let recipe_id = generate_recipe_id(&git_path);
-                            let cached = CachedRecipe {
-                                recipe_id,
-                                git_path: git_path.clone(),
-                                name: recipe_name.clone(),
-                                description: None,
-                                category,
-                                recipe: parsed_recipe,
+                        None => self.derived.invalidate(git_path),
+                    }
+                }
+
+                let mut parsed = 0;
+                for (processed, git_path) in changes.updated.into_iter().enumerate() {
+                    crate::deadline::check(changes.deleted.len() + processed, Some(total))?;
+                    self.derived.invalidate(&git_path);
+                    let rel_path = git_path.clone();
+                    match self
+                        .on_storage(move |storage| storage.read_file(&rel_path))
+                        .await
+                    {
+                        Ok(content) => {
+                            let event = match self.cache.get(&git_path) {
+                                Some(_) => EventKind::RecipeUpdated,
+                                None => EventKind::RecipeCreated,
                             };
-                            self.cache.insert(git_path, cached);
+                            let stat = self.stat_of(&git_path).await;
+                            if self.index_file(git_path.clone(), &content, stat) {
+                                self.publish_indexed(event, &git_path, None, None);
+                                parsed += 1;
+                            }
                         }
                         Err(e) => {
-                            tracing::warn!("Failed to parse recipe {}: {}", git_path, e);
+                            tracing::warn!("Failed to read recipe file {}: {}", git_path, e);
                         }
                     }
                 }
+
+                self.index_menus().await;
+                self.refresh_read_only_dirs().await;
+                self.set_indexed_revision(revision.clone());
+                (true, parsed, removed)
+            }
+            None => {
+                let before = self.cache.len();
+                let parsed = self.rebuild_index(HashMap::new()).await?;
+                (false, parsed, before.saturating_sub(self.cache.len()))
+            }
+        };
+
+        if incremental {
+            self.index_metrics
+                .record_rebuild(true, parsed, started.elapsed());
+        }
+        let report = ReindexReport {
+            incremental,
+            parsed,
+            removed,
+            revision,
+            duration_ms: started.elapsed().as_millis() as u64,
+        };
+        tracing::info!(
+            "Reindexed ({}): {} parsed, {} removed in {} ms",
+            if report.incremental {
+                "incremental"
+            } else {
+                "full"
+            },
+            report.parsed,
+            report.removed,
+            report.duration_ms
+        );
+
+        Ok(report)
+    }
+
+    /// Recipe files added, changed or deleted since they were indexed, going by their
+    /// size and modification time
+    ///
+    /// Entries indexed without a stat (e.g. from an older snapshot) count as changed.
+    async fn stale_files(&self) -> Result<FileChanges> {
+        let files = self.on_storage(|storage| storage.discover_files()).await?;
+        let mut indexed = self.cache.file_stats();
+        let mut changes = FileChanges::default();
+        for (git_path, stat) in files {
+            match indexed.remove(&git_path) {
+                Some(Some(known)) if known.same_file(&stat) => {}
+                _ => changes.updated.push(git_path),
+            }
+        }
+        changes.deleted = indexed.into_keys().collect();
+        Ok(changes)
+    }
+
+    fn indexed_revision_snapshot(&self) -> Option<String> {
+        self.indexed_revision
+            .read()
+            .map(|revision| revision.clone())
+            .unwrap_or_default()
+    }
+
+    fn set_indexed_revision(&self, revision: Option<String>) {
+        if let Ok(mut indexed) = self.indexed_revision.write() {
+            *indexed = revision;
+        }
+    }
+
+    /// Clear and refill the cache from storage, returning how many files were parsed
+    ///
+    /// Entries in `previous` whose content hash matches the file are reused, with only
+    /// their last-modified details refreshed.
+    ///
+    /// Storage is read on the blocking pool before the cache is touched, so readers keep
+    /// the old entries while a slow backend is scanned.
+    async fn rebuild_index(&self, previous: HashMap<String, CachedRecipe>) -> Result<usize> {
+        let started = Instant::now();
+        let scan = self.on_storage(StorageScan::read).await?;
+
+        self.cache.clear();
+        self.index_metrics.clear_failures();
+        self.search.clear()?;
+        self.derived.clear();
+
+        let total = scan.files.len();
+        let mut parsed = 0;
+        for (processed, (git_path, stat, content)) in scan.files.into_iter().enumerate() {
+            crate::deadline::check(processed, Some(total))?;
+
+            match content {
+                Ok(content) => {
+                    let hash = content_hash(&content);
+                    if let Some(cached) = previous
+                        .get(&git_path)
+                        .filter(|cached| cached.content_hash == hash)
+                    {
+                        self.index_for_search(cached, &content);
+                        let mut cached = cached.clone();
+                        cached.last_modified = Some(stat.last_modified());
+                        cached.stat = Some(stat);
+                        self.cache.insert(git_path, cached);
+                        continue;
+                    }
+
+                    if self.index_file(git_path, &content, Some(stat)) {
+                        parsed += 1;
+                    }
+                }
                 Err(e) => {
                     tracing::warn!("Failed to read recipe file {}: {}", git_path, e);
                 }
             }
         }
 
-        Ok(())
+        self.index_menus().await;
+        self.refresh_read_only_dirs().await;
+        self.set_indexed_revision(scan.revision);
+        self.index_metrics
+            .record_rebuild(false, parsed, started.elapsed());
+        Ok(parsed)
+    }
+
+    async fn refresh_read_only_dirs(&self) {
+        let dirs = self
+            .on_storage(|storage| storage.read_only_dirs())
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to list read-only directories: {}", e);
+                Vec::new()
+            });
+        if let Ok(mut read_only_dirs) = self.read_only_dirs.write() {
+            *read_only_dirs = dirs;
+        }
+    }
+
+    /// Whether a recipe lives in a read-only directory, such as a git submodule holding a
+    /// shared recipe pack
+    ///
+    /// Such recipes are indexed and searchable like any other, but storage rejects changes
+    /// to them; bulk operations (normalize, reconcile) leave them out.
+    pub fn is_read_only(&self, git_path: &str) -> bool {
+        self.read_only_dirs
+            .read()
+            .map(|dirs| dirs.iter().any(|dir| is_below(git_path, dir)))
+            .unwrap_or(false)
+    }
+
+    /// Re-read all menu files (there are few, so they are always rescanned in full)
+    async fn index_menus(&self) {
+        let menu_files = self
+            .on_storage(|storage| {
+                Ok(storage
+                    .discover_menu_files()?
+                    .into_iter()
+                    .map(|git_path| {
+                        let content = storage.read_file(&git_path);
+                        (git_path, content)
+                    })
+                    .collect::<Vec<_>>())
+            })
+            .await;
+        let menu_files = match menu_files {
+            Ok(files) => files,
+            Err(e) => {
+                tracing::warn!("Failed to discover menu files: {}", e);
+                return;
+            }
+        };
+
+        self.menus.clear();
+        for (git_path, content) in menu_files {
+            match content {
+                Ok(content) => {
+                    let menu = parse_menu(&git_path, &content);
+                    self.menus.insert(generate_recipe_id(&git_path), menu);
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to read menu file {}: {}", git_path, e);
+                }
+            }
+        }
+    }
+
+    /// Parse a recipe file into the cache and search index, returning whether it parsed
+    ///
+    /// A file that fails to parse is logged and left out (dropping any stale entry).
+    fn index_file(&self, git_path: String, content: &str, stat: Option<FileStat>) -> bool {
+        // Extract category from path ({recipes dir}/{category}/{...}/{slug}.cook)
+        let category = self.extract_category_from_path(&git_path);
+
+        // Try to extract title from YAML front matter
+        let recipe_name = match extract_recipe_title(content) {
+            Ok(title) => title,
+            Err(_) => {
+                // Fallback to path-based name if YAML front matter missing
+                tracing::warn!(
+                    "Recipe {} missing YAML front matter, using path-based name",
+                    git_path
+                );
+                self.path_to_name(&git_path)
+            }
+        };
+
+        match self.parse_recipe(content, &recipe_name) {
+            Ok(parsed_recipe) => {
+                let cached = cached_entry(
+                    &git_path,
+                    &recipe_name,
+                    category,
+                    content,
+                    parsed_recipe,
+                    stat,
+                );
+                self.index_for_search(&cached, content);
+                self.index_metrics.clear_failure(&git_path);
+                self.cache.insert(git_path, cached);
+                true
+            }
+            Err(e) => {
+                tracing::warn!("Failed to parse recipe {}: {}", git_path, e);
+                self.index_metrics.record_failure(&git_path, &e);
+                if self.cache.remove(&git_path).is_some() {
+                    self.remove_from_search(&git_path);
+                }
+                false
+            }
+        }
     }
 
     /// Create a new recipe
@@ -123,16 +837,19 @@ impl RecipeRepository {
         _name: &str,
         content: &str,
         category: Option<&str>,
-        _author: Option<&str>,
-        _comment: Option<&str>,
+        author: Option<&str>,
+        comment: Option<&str>,
     ) -> Result<Recipe> {
+        let category = self.canonical_category(category);
+        let category = category.as_deref();
+
         // Extract title from YAML front matter (content must have it)
-        let recipe_title =
-            extract_recipe_title(content).map_err(|e| anyhow!("Invalid recipe content: {}", e))?;
+        let recipe_title = extract_recipe_title(content)
+            .map_err(|e| RecipeError::InvalidFrontMatter(e.to_string()))?;
 
         // Validate the recipe can be parsed
-        parse_recipe(content, &recipe_title)
-            .map_err(|e| anyhow!("Failed to parse recipe: {}", e))?;
+        self.parse_recipe(content, &recipe_title)
+            .map_err(RecipeError::Parse)?;
 
         // Generate filename from the extracted title
         let filename = generate_filename(&recipe_title);
@@ -141,25 +858,43 @@ impl RecipeRepository {
         let git_path = self
             .generate_git_path_from_filename(&filename, category)
             .await?;
+        self.check_write("Create", &git_path, content, author)
+            .await?;
 
         // Write to storage (source of truth)
-        self.storage.write_file(&git_path, content)?;
+        let commit = self.commit_info("Create", &git_path, &recipe_title, author, comment);
+        let (path, file_content) = (git_path.clone(), content.to_string());
+        self.on_storage(move |storage| {
+            storage.write_file_with_commit(&path, &file_content, &commit)
+        })
+        .await?;
 
         // Update cache
-        let parsed = parse_recipe(content, &recipe_title)
-            .map_err(|e| anyhow!("Failed to parse recipe: {}", e))?;
-
-        let recipe_id = generate_recipe_id(&git_path);
-        let cached = CachedRecipe {
-            recipe_id,
-            git_path: git_path.clone(),
-            name: recipe_title.clone(),
-            description: None,
-            category: category.map(|s| s.to_string()),
-            recipe: parsed,
-        };
+        let parsed = self
+            .parse_recipe(content, &recipe_title)
+            .map_err(RecipeError::Parse)?;
+
+        let stat = self.stat_of(&git_path).await;
+        let last_modified = stat.as_ref().map(FileStat::last_modified);
+        let cached = cached_entry(
+            &git_path,
+            &recipe_title,
+            category.map(|s| s.to_string()),
+            content,
+            parsed,
+            stat,
+        );
 
-        self.cache.insert(git_path.clone(), cached);
+        self.publish(
+            RepositoryEvent::new(
+                EventKind::RecipeCreated,
+                &git_path,
+                &recipe_title,
+                category,
+                author,
+            ),
+            Some((cached, content)),
+        );
 
         Ok(Recipe {
             git_path: git_path.clone(),
@@ -167,7 +902,8 @@ impl RecipeRepository {
             name: recipe_title,
             description: None,
             category: category.map(|s| s.to_string()),
-            content: content.to_string(),
+            content: Some(content.to_string()),
+            last_modified,
         })
     }
 
@@ -187,7 +923,8 @@ impl RecipeRepository {
             name: cached.name,
             description: cached.description,
             category: cached.category,
-            content,
+            content: Some(content),
+            last_modified: cached.last_modified,
         })
     }
 
@@ -223,8 +960,8 @@ impl RecipeRepository {
         name: Option<&str>,
         content: Option<&str>,
         category: Option<Option<&str>>,
-        _author: Option<&str>,
-        _comment: Option<&str>,
+        author: Option<&str>,
+        comment: Option<&str>,
     ) -> Result<Recipe> {
         // Read current recipe from cache
         let current = self
@@ -239,7 +976,7 @@ impl RecipeRepository {
         // Priority: extracted title from new content → provided name parameter → current name
         let new_title = if let Some(c) = content {
             // Extract title from new content if provided
-            extract_recipe_title(c).map_err(|e| anyhow!("Invalid recipe content: {}", e))?
+            extract_recipe_title(c).map_err(|e| RecipeError::InvalidFrontMatter(e.to_string()))?
         } else if let Some(n) = name {
             // Use provided name if content not changing
             n.to_string()
@@ -248,15 +985,17 @@ impl RecipeRepository {
             current.name.clone()
         };
 
-        let new_category = category
-            .as_ref()
-            .copied()
-            .flatten()
-            .or(current.category.as_deref());
+        // `Some(None)` moves the recipe to the root, `None` keeps the current category
+        let requested_category = category.map(|c| self.canonical_category(c));
+        let new_category = match &requested_category {
+            Some(c) => c.as_deref(),
+            None => current.category.as_deref(),
+        };
 
         // Validate new content if provided
         if let Some(c) = content {
-            parse_recipe(c, &new_title).map_err(|e| anyhow!("Failed to parse recipe: {}", e))?;
+            self.parse_recipe(c, &new_title)
+                .map_err(RecipeError::Parse)?;
         }
 
         // Generate new filename from title
@@ -274,6 +1013,9 @@ impl RecipeRepository {
         } else {
             git_path.to_string()
         };
+        if let Some(c) = content {
+            self.check_write("Update", &new_git_path, c, author).await?;
+        }
 
         // Write to storage (if content provided or path changed)
         if content.is_some() || new_git_path != git_path {
@@ -284,34 +1026,71 @@ impl RecipeRepository {
                 current_content.clone()
             };
 
-            self.storage.write_file(&new_git_path, &file_content)?;
+            // A path-only change is a move
+            let action = if content.is_none() && name.is_none() {
+                "Move"
+            } else {
+                "Update"
+            };
 
-            // If path changed, delete old file
+            let commit = self.commit_info(action, &new_git_path, &new_title, author, comment);
+            let (from, to, content) = (
+                git_path.to_string(),
+                new_git_path.clone(),
+                file_content.clone(),
+            );
             if new_git_path != git_path {
-                self.storage.delete_file(git_path)?;
+                // Rename in a single change so history follows the file
+                self.on_storage(move |storage| {
+                    storage.rename_file(&from, &to, &content, &commit)?;
+                    move_notes(storage, &from, &to, &commit)
+                })
+                .await?;
+            } else {
+                self.on_storage(move |storage| {
+                    storage.write_file_with_commit(&to, &content, &commit)
+                })
+                .await?;
             }
         }
 
         // Update cache
         let file_content = self.storage.read_file(&new_git_path)?;
-        let parsed = parse_recipe(&file_content, &new_title)
-            .map_err(|e| anyhow!("Failed to parse recipe: {}", e))?;
+        let parsed = self
+            .parse_recipe(&file_content, &new_title)
+            .map_err(RecipeError::Parse)?;
 
         if new_git_path != git_path {
-            self.cache.remove(git_path);
+            if self.favorites.rename(git_path, &new_git_path) {
+                self.save_favorites().await?;
+            }
+            if self.shares.rename(git_path, &new_git_path) {
+                self.save_shares().await?;
+            }
         }
 
-        let recipe_id = generate_recipe_id(&new_git_path);
-        let cached = CachedRecipe {
-            recipe_id,
-            git_path: new_git_path.clone(),
-            name: new_title.clone(),
-            description: None,
-            category: new_category.map(|s| s.to_string()),
-            recipe: parsed,
-        };
+        let stat = self.stat_of(&new_git_path).await;
+        let last_modified = stat.as_ref().map(FileStat::last_modified);
+        let cached = cached_entry(
+            &new_git_path,
+            &new_title,
+            new_category.map(|s| s.to_string()),
+            &file_content,
+            parsed,
+            stat,
+        );
 
-        self.cache.insert(new_git_path.clone(), cached);
+        self.publish(
+            RepositoryEvent::new(
+                EventKind::RecipeUpdated,
+                &new_git_path,
+                &new_title,
+                new_category,
+                author,
+            )
+            .moved_from(Some(git_path)),
+            Some((cached, &file_content)),
+        );
 
         Ok(Recipe {
             git_path: new_git_path,
@@ -319,104 +1098,2369 @@ impl RecipeRepository {
             name: new_title,
             description: None,
             category: new_category.map(|s| s.to_string()),
-            content: file_content,
+            content: Some(file_content),
+            last_modified,
         })
     }
 
-    /// Delete a recipe
-    pub async fn delete(&self, git_path: &str) -> Result<()> {
-        self.delete_with_author_and_comment(git_path, None, None)
-            .await
-    }
+    /// Overwrite front matter fields of a recipe without resending its content
+    ///
+    /// Goes through [`update_with_author_and_comment`](Self::update_with_author_and_comment),
+    /// so a new title renames the file.
+    pub async fn patch_front_matter(
+        &self,
+        git_path: &str,
+        patch: &FrontMatterPatch,
+        author: Option<&str>,
+        comment: Option<&str>,
+    ) -> Result<Recipe> {
+        let current_content = self.storage.read_file(git_path)?;
+        let content = patch
+            .apply(&current_content)
+            .map_err(|e| RecipeError::InvalidFrontMatter(e.to_string()))?;
 
-    /// Delete a recipe with an optional author
-    pub async fn delete_with_author(&self, git_path: &str, author: Option<&str>) -> Result<()> {
-        self.delete_with_author_and_comment(git_path, author, None)
+        self.update_with_author_and_comment(git_path, None, Some(&content), None, author, comment)
             .await
     }
 
-    /// Delete a recipe with optional author and comment
-    pub async fn delete_with_author_and_comment(
+    /// Add and remove tags on many recipes, saved as a single change
+    ///
+    /// Tags compare case-insensitively; existing tags keep their order and spelling and
+    /// added ones go last. Nothing is written if a recipe's front matter is invalid.
+    pub async fn retag(
         &self,
-        git_path: &str,
-        _author: Option<&str>,
-        _comment: Option<&str>,
-    ) -> Result<()> {
-        // Verify recipe exists in cache
-        let _cached = self
-            .cache
-            .get(git_path)
-            .ok_or_else(|| anyhow!("Recipe not found: {}", git_path))?;
+        git_paths: &[String],
+        add: &[String],
+        remove: &[String],
+        author: Option<&str>,
+        comment: Option<&str>,
+    ) -> Result<RetagReport> {
+        let mut report = RetagReport::default();
+        let mut files = Vec::new();
+
+        for git_path in git_paths {
+            if files.iter().any(|(path, _)| path == git_path) || report.unchanged.contains(git_path)
+            {
+                continue;
+            }
+            let content = self.storage.read_file(git_path)?;
+            let tags = extract_metadata(&content).tags;
+            let new_tags = retagged(&tags, add, remove);
+            if new_tags == tags {
+                report.unchanged.push(git_path.clone());
+                continue;
+            }
+
+            let patch = FrontMatterPatch {
+                tags: Some(new_tags),
+                ..Default::default()
+            };
+            let content = patch
+                .apply(&content)
+                .map_err(|e| RecipeError::InvalidFrontMatter(format!("{}: {}", git_path, e)))?;
+            self.check_write("Retag", git_path, &content, author)
+                .await?;
+            files.push((git_path.clone(), content));
+        }
 
-        // Delete from storage
-        self.storage.delete_file(git_path)?;
+        if files.is_empty() {
+            return Ok(report);
+        }
 
-        // Delete from cache
-        self.cache.remove(git_path);
+        let changes = add
+            .iter()
+            .map(|tag| format!("+{}", tag.trim()))
+            .chain(remove.iter().map(|tag| format!("-{}", tag.trim())))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let summary = format!("{} recipes", files.len());
+        let commit = self.commit_info("Retag", &summary, &changes, author, comment);
+        let written = files.clone();
+        self.on_storage(move |storage| storage.write_files_with_commit(&written, &commit))
+            .await?;
 
-        Ok(())
+        for (git_path, content) in files {
+            self.index_file(git_path.clone(), &content, self.stat_of(&git_path).await);
+            self.publish_indexed(EventKind::RecipeUpdated, &git_path, None, author);
+            report.updated.push(git_path);
+        }
+        Ok(report)
     }
 
-    /// List all recipes
-    pub fn list_all(&self) -> Vec<Recipe> {
-        self.cache
+    /// Rewrite every recipe's front matter in canonical form, saved as a single change
+    ///
+    /// See [`normalize_front_matter`] for what changes. Recipes whose front matter
+    /// cannot be normalized are reported and left as they are. With `dry_run` nothing
+    /// is written and `updated` lists the recipes that would change. Read-only recipes are
+    /// skipped.
+    pub async fn normalize_all(
+        &self,
+        dry_run: bool,
+        author: Option<&str>,
+    ) -> Result<NormalizeReport> {
+        let mut git_paths: Vec<String> = self
+            .cache
             .get_all()
             .into_iter()
-            .map(|cached| {
-                let file_name = self.extract_filename_from_path(&cached.git_path);
-                Recipe {
-                    git_path: cached.git_path,
-                    file_name,
-                    name: cached.name,
-                    description: cached.description,
-                    category: cached.category,
-                    content: String::new(), // Content not included in list
+            .map(|cached| cached.git_path)
+            .filter(|git_path| !self.is_read_only(git_path))
+            .collect();
+        git_paths.sort();
+
+        let total = git_paths.len();
+        let mut report = NormalizeReport::default();
+        let mut files = Vec::new();
+        for (processed, git_path) in git_paths.into_iter().enumerate() {
+            crate::deadline::check(processed, Some(total))?;
+            let content = self.storage.read_file(&git_path)?;
+            match normalize_front_matter(&content) {
+                Ok(Some(normalized)) => {
+                    match self
+                        .check_write("Normalize", &git_path, &normalized, author)
+                        .await
+                    {
+                        Ok(()) => files.push((git_path, normalized)),
+                        Err(e) => report.failed.push((git_path, e.to_string())),
+                    }
                 }
-            })
-            .collect()
+                Ok(None) => report.unchanged += 1,
+                Err(e) => report.failed.push((git_path, e.to_string())),
+            }
+        }
+
+        if !dry_run && !files.is_empty() {
+            let summary = format!("{} recipes", files.len());
+            let commit = self.commit_info("Normalize", &summary, "front matter", author, None);
+            let written = files.clone();
+            self.on_storage(move |storage| storage.write_files_with_commit(&written, &commit))
+                .await?;
+            for (git_path, content) in &files {
+                self.index_file(git_path.clone(), content, self.stat_of(git_path).await);
+                self.publish_indexed(EventKind::RecipeUpdated, git_path, None, author);
+            }
+        }
+
+        report.updated = files.into_iter().map(|(git_path, _)| git_path).collect();
+        Ok(report)
     }
 
-    /// Search recipes by name
-    pub fn search_by_name(&self, query: &str) -> Vec<Recipe> {
-        self.cache
-            .search_by_name(query)
+    /// Recipe files whose name does not match their title, e.g. after a manual rename or
+    /// a title edited outside the API, sorted by path
+    ///
+    /// Nothing is renamed; updating a recipe through the API realigns its file. Read-only
+    /// recipes are left out, since they cannot be renamed.
+    pub fn misaligned_files(&self) -> Vec<MisalignedFile> {
+        let mut files: Vec<MisalignedFile> = self
+            .cache
+            .get_all()
             .into_iter()
-            .map(|cached| {
+            .filter(|cached| !self.is_read_only(&cached.git_path))
+            .filter_map(|cached| {
                 let file_name = self.extract_filename_from_path(&cached.git_path);
-                Recipe {
+                should_rename_file(&file_name, &cached.name).then(|| MisalignedFile {
+                    expected_filename: generate_filename(&cached.name),
                     git_path: cached.git_path,
-                    file_name,
-                    name: cached.name,
-                    description: cached.description,
                     category: cached.category,
-                    content: String::new(),
+                    file_name,
+                    title: cached.name,
+                })
+            })
+            .collect();
+        files.sort_by(|a, b| a.git_path.cmp(&b.git_path));
+        files
+    }
+
+    /// Rename every misaligned recipe file to the name its title generates, saved as a
+    /// single change, returning the old and new paths
+    ///
+    /// A name already taken gets a numeric suffix as on create; a file already at the
+    /// first free name is left alone. Notes sidecars move with their recipes. With
+    /// `dry_run` nothing is written and the planned renames are returned.
+    pub async fn reconcile_filenames(
+        &self,
+        dry_run: bool,
+        author: Option<&str>,
+    ) -> Result<Vec<(String, String)>> {
+        let mut taken: HashSet<String> = HashSet::new();
+        let mut renames = Vec::new();
+        for file in self.misaligned_files() {
+            let base = file.expected_filename.trim_end_matches(".cook");
+            let in_category = |name: &str| self.recipe_path(file.category.as_deref(), name);
+
+            let mut new_git_path = in_category(&file.expected_filename);
+            let mut counter = 2;
+            while new_git_path != file.git_path
+                && (self.cache.get(&new_git_path).is_some() || taken.contains(&new_git_path))
+            {
+                new_git_path = in_category(&format!("{}-{}.cook", base, counter));
+                counter += 1;
+            }
+            if new_git_path != file.git_path {
+                taken.insert(new_git_path.clone());
+                renames.push((file.git_path, new_git_path));
+            }
+        }
+        if dry_run || renames.is_empty() {
+            return Ok(renames);
+        }
+
+        let mut files = Vec::new();
+        for (git_path, new_git_path) in &renames {
+            let content = self.storage.read_file(git_path)?;
+            files.push((git_path.clone(), new_git_path.clone(), content));
+            if let Ok(notes) = self.storage.read_file(&notes_path(git_path)) {
+                files.push((notes_path(git_path), notes_path(new_git_path), notes));
+            }
+        }
+        let summary = format!("{} recipes", renames.len());
+        let commit = self.commit_info("Rename", &summary, "match titles", author, None);
+        self.on_storage(move |storage| storage.rename_files_with_commit(&files, &commit))
+            .await?;
+
+        let mut favorites_changed = false;
+        let mut shares_changed = false;
+        for (git_path, new_git_path) in &renames {
+            let content = self.storage.read_file(new_git_path)?;
+            self.index_file(
+                new_git_path.clone(),
+                &content,
+                self.stat_of(new_git_path).await,
+            );
+            self.publish_indexed(
+                EventKind::RecipeUpdated,
+                new_git_path,
+                Some(git_path),
+                author,
+            );
+            favorites_changed |= self.favorites.rename(git_path, new_git_path);
+            shares_changed |= self.shares.rename(git_path, new_git_path);
+        }
+        if favorites_changed {
+            self.save_favorites().await?;
+        }
+        if shares_changed {
+            self.save_shares().await?;
+        }
+        Ok(renames)
+    }
+
+    /// Move every recipe in category `from` and its subcategories to `to` in one commit
+    /// (`None` if `from` holds no recipes)
+    ///
+    /// Notes move with their recipes, directories left empty are removed, and lookups
+    /// under the old path redirect for `CATEGORY_ALIAS_DAYS`. Nothing moves if any
+    /// recipe would land on an existing file.
+    pub async fn rename_category(
+        &self,
+        from: &str,
+        to: &str,
+        author: Option<&str>,
+        comment: Option<&str>,
+    ) -> Result<Option<CategoryRename>> {
+        let Some(from) = self.stored_category_prefix(from) else {
+            return Ok(None);
+        };
+        let to = self
+            .canonical_category(Some(to))
+            .ok_or_else(|| anyhow!("Category path is empty"))?;
+        if is_below(&to, &from) {
+            return Err(anyhow!("Cannot move category {} into itself", from));
+        }
+
+        let old_dir = self.recipe_path(Some(&from), "");
+        let old_prefix = format!("{}/", old_dir);
+        let mut renames = Vec::new();
+        for cached in self.cache.sorted(SortOrder::Path, |cached| {
+            cached.git_path.starts_with(&old_prefix)
+        }) {
+            let rest = &cached.git_path[old_prefix.len()..];
+            let new_git_path = self.recipe_path(Some(&to), rest);
+            if self.cache.get(&new_git_path).is_some()
+                || self.storage.read_file(&new_git_path).is_ok()
+            {
+                return Err(PathTaken { path: new_git_path }.into());
+            }
+            renames.push((cached.git_path, new_git_path));
+        }
+        if renames.is_empty() {
+            return Ok(None);
+        }
+
+        let mut files = Vec::new();
+        for (git_path, new_git_path) in &renames {
+            let content = self.storage.read_file(git_path)?;
+            files.push((git_path.clone(), new_git_path.clone(), content));
+            if let Ok(notes) = self.storage.read_file(&notes_path(git_path)) {
+                files.push((notes_path(git_path), notes_path(new_git_path), notes));
+            }
+        }
+        let title = format!("{} → {}", from, to);
+        let commit = self.commit_info("Move", &old_dir, &title, author, comment);
+        self.on_storage(move |storage| storage.rename_files_with_commit(&files, &commit))
+            .await?;
+
+        let mut favorites_changed = false;
+        let mut shares_changed = false;
+        for (git_path, new_git_path) in &renames {
+            let content = self.storage.read_file(new_git_path)?;
+            self.index_file(
+                new_git_path.clone(),
+                &content,
+                self.stat_of(new_git_path).await,
+            );
+            self.publish_indexed(
+                EventKind::RecipeUpdated,
+                new_git_path,
+                Some(git_path),
+                author,
+            );
+            favorites_changed |= self.favorites.rename(git_path, new_git_path);
+            shares_changed |= self.shares.rename(git_path, new_git_path);
+        }
+        if favorites_changed {
+            self.save_favorites().await?;
+        }
+        if shares_changed {
+            self.save_shares().await?;
+        }
+        self.record_category_alias(&from, &to).await?;
+
+        // Storage removes the directories the moves emptied; this also catches empty
+        // subcategories that never held a recipe
+        let mut category = Some(from.as_str()).filter(|_| self.config().remove_empty_dirs);
+        while let Some(current) = category {
+            match self
+                .storage
+                .remove_empty_dirs(&self.recipe_path(Some(current), ""))
+            {
+                Ok(true) => category = current.rsplit_once('/').map(|(parent, _)| parent),
+                Ok(false) => break,
+                Err(e) => {
+                    tracing::warn!("Failed to remove empty directories of {}: {:#}", current, e);
+                    break;
+                }
+            }
+        }
+        Ok(Some(CategoryRename {
+            from,
+            to,
+            renamed: renames,
+        }))
+    }
+
+    /// Delete a recipe
+    pub async fn delete(&self, git_path: &str) -> Result<()> {
+        self.delete_with_author_and_comment(git_path, None, None)
+            .await
+    }
+
+    /// Delete a recipe with an optional author
+    pub async fn delete_with_author(&self, git_path: &str, author: Option<&str>) -> Result<()> {
+        self.delete_with_author_and_comment(git_path, author, None)
+            .await
+    }
+
+    /// Delete a recipe with optional author and comment
+    pub async fn delete_with_author_and_comment(
+        &self,
+        git_path: &str,
+        author: Option<&str>,
+        comment: Option<&str>,
+    ) -> Result<()> {
+        // Verify recipe exists in cache
+        let cached = self
+            .cache
+            .get(git_path)
+            .ok_or_else(|| anyhow!("Recipe not found: {}", git_path))?;
+
+        if self.config().trash_enabled {
+            // Move the file into the trash instead of removing it
+            let content = self.storage.read_file(git_path)?;
+            let trash_path = format!(
+                "{}/{}/{}",
+                TRASH_DIR,
+                Utc::now().format(TRASH_TIMESTAMP_FORMAT),
+                git_path
+            );
+            let commit = self.commit_info("Trash", git_path, &cached.name, author, comment);
+            let (from, to) = (git_path.to_string(), trash_path.clone());
+            self.on_storage(move |storage| {
+                storage.rename_file(&from, &to, &content, &commit)?;
+                move_notes(storage, &from, &to, &commit)
+            })
+            .await?;
+        } else {
+            let commit = self.commit_info("Delete", git_path, &cached.name, author, comment);
+            let path = git_path.to_string();
+            self.on_storage(move |storage| {
+                storage.delete_file_with_commit(&path, &commit)?;
+                delete_notes(storage, &path, &commit)
+            })
+            .await?;
+        }
+
+        self.publish(
+            RepositoryEvent::new(
+                EventKind::RecipeDeleted,
+                git_path,
+                &cached.name,
+                cached.category.as_deref(),
+                author,
+            ),
+            None,
+        );
+
+        if self.favorites.remove(git_path) {
+            self.save_favorites().await?;
+        }
+        if self.shares.remove(git_path) {
+            self.save_shares().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Move a recipe to another category, keeping its content and file name
+    pub async fn move_recipe(
+        &self,
+        git_path: &str,
+        target_category: Option<&str>,
+        author: Option<&str>,
+        comment: Option<&str>,
+    ) -> Result<Recipe> {
+        self.update_with_author_and_comment(
+            git_path,
+            None,
+            None,
+            Some(target_category),
+            author,
+            comment,
+        )
+        .await
+    }
+
+    /// Copy a recipe into a category, adding a numeric suffix if the file name is taken
+    pub async fn copy_recipe(
+        &self,
+        git_path: &str,
+        target_category: Option<&str>,
+        author: Option<&str>,
+        comment: Option<&str>,
+    ) -> Result<Recipe> {
+        let current = self
+            .cache
+            .get(git_path)
+            .ok_or_else(|| anyhow!("Recipe not found: {}", git_path))?;
+
+        let target_category = self.canonical_category(target_category);
+        let target_category = target_category.as_deref();
+
+        let content = self.storage.read_file(git_path)?;
+        let filename = generate_filename(&current.name);
+
+        let new_git_path = self
+            .generate_git_path_from_filename(&filename, target_category)
+            .await?;
+        self.check_write("Copy", &new_git_path, &content, author)
+            .await?;
+
+        let commit = self.commit_info("Copy", &new_git_path, &current.name, author, comment);
+        let (path, file_content) = (new_git_path.clone(), content.clone());
+        self.on_storage(move |storage| {
+            storage.write_file_with_commit(&path, &file_content, &commit)
+        })
+        .await?;
+
+        let parsed = self
+            .parse_recipe(&content, &current.name)
+            .map_err(RecipeError::Parse)?;
+
+        let stat = self.stat_of(&new_git_path).await;
+        let last_modified = stat.as_ref().map(FileStat::last_modified);
+        let cached = cached_entry(
+            &new_git_path,
+            &current.name,
+            target_category.map(|s| s.to_string()),
+            &content,
+            parsed,
+            stat,
+        );
+
+        self.publish(
+            RepositoryEvent::new(
+                EventKind::RecipeCreated,
+                &new_git_path,
+                &current.name,
+                target_category,
+                author,
+            ),
+            Some((cached, &content)),
+        );
+
+        Ok(Recipe {
+            file_name: self.extract_filename_from_path(&new_git_path),
+            git_path: new_git_path,
+            name: current.name,
+            description: None,
+            category: target_category.map(|s| s.to_string()),
+            content: Some(content),
+            last_modified,
+        })
+    }
+
+    /// List recipes in the trash, most recently deleted first
+    pub fn list_trash(&self) -> Result<Vec<TrashedRecipe>> {
+        let mut entries = Vec::new();
+        let trash_files = self.storage.list_files(TRASH_DIR)?;
+        let total = trash_files.len();
+
+        for (processed, trash_path) in trash_files.into_iter().enumerate() {
+            crate::deadline::check(processed, Some(total))?;
+
+            // Notes sidecars travel with their recipe but are not entries themselves
+            if !trash_path.ends_with(".cook") {
+                continue;
+            }
+
+            let Some((stamp, original_path)) = trash_path
+                .strip_prefix(&format!("{}/", TRASH_DIR))
+                .and_then(|rest| rest.split_once('/'))
+            else {
+                continue;
+            };
+
+            let deleted_at = match NaiveDateTime::parse_from_str(stamp, TRASH_TIMESTAMP_FORMAT) {
+                Ok(naive) => naive.and_utc(),
+                Err(_) => {
+                    tracing::warn!(
+                        "Skipping trash entry with invalid timestamp: {}",
+                        trash_path
+                    );
+                    continue;
+                }
+            };
+
+            let name = self
+                .storage
+                .read_file(&trash_path)
+                .ok()
+                .and_then(|content| extract_recipe_title(&content).ok())
+                .unwrap_or_else(|| self.path_to_name(original_path));
+
+            entries.push(TrashedRecipe {
+                trash_id: generate_recipe_id(&trash_path),
+                category: self.extract_category_from_path(original_path),
+                original_path: original_path.to_string(),
+                trash_path,
+                name,
+                deleted_at,
+            });
+        }
+
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.deleted_at));
+        Ok(entries)
+    }
+
+    /// Find a trashed recipe by its trash ID
+    pub fn get_trashed(&self, trash_id: &str) -> Result<Option<TrashedRecipe>> {
+        Ok(self
+            .list_trash()?
+            .into_iter()
+            .find(|entry| entry.trash_id == trash_id))
+    }
+
+    /// Restore a trashed recipe to its original path
+    pub async fn restore_from_trash(&self, trash_id: &str) -> Result<Recipe> {
+        let entry = self
+            .get_trashed(trash_id)?
+            .ok_or_else(|| anyhow!("Trash entry not found: {}", trash_id))?;
+
+        if self.cache.get(&entry.original_path).is_some() {
+            return Err(anyhow!(
+                "A recipe already exists at {}",
+                entry.original_path
+            ));
+        }
+
+        let content = self.storage.read_file(&entry.trash_path)?;
+        let parsed = self
+            .parse_recipe(&content, &entry.name)
+            .map_err(RecipeError::Parse)?;
+
+        let commit = self.commit_info("Restore", &entry.original_path, &entry.name, None, None);
+        let (from, to, file_content) = (
+            entry.trash_path.clone(),
+            entry.original_path.clone(),
+            content.clone(),
+        );
+        self.on_storage(move |storage| {
+            storage.rename_file(&from, &to, &file_content, &commit)?;
+            move_notes(storage, &from, &to, &commit)
+        })
+        .await?;
+
+        let stat = self.stat_of(&entry.original_path).await;
+        let last_modified = stat.as_ref().map(FileStat::last_modified);
+        let cached = cached_entry(
+            &entry.original_path,
+            &entry.name,
+            entry.category.clone(),
+            &content,
+            parsed,
+            stat,
+        );
+
+        self.publish(
+            RepositoryEvent::new(
+                EventKind::RecipeCreated,
+                &entry.original_path,
+                &entry.name,
+                entry.category.as_deref(),
+                None,
+            ),
+            Some((cached, &content)),
+        );
+
+        Ok(Recipe {
+            file_name: self.extract_filename_from_path(&entry.original_path),
+            git_path: entry.original_path,
+            name: entry.name,
+            description: None,
+            category: entry.category,
+            content: Some(content),
+            last_modified,
+        })
+    }
+
+    /// Whether the storage backend supports drafts (git only)
+    pub fn supports_drafts(&self) -> bool {
+        self.storage.supports_drafts()
+    }
+
+    /// Save a new recipe as a draft, without adding it to the main listing
+    pub async fn create_draft(
+        &self,
+        content: &str,
+        category: Option<&str>,
+        author: Option<&str>,
+        comment: Option<&str>,
+    ) -> Result<Draft> {
+        let category = self.canonical_category(category);
+        let recipe_title = extract_recipe_title(content)
+            .map_err(|e| RecipeError::InvalidFrontMatter(e.to_string()))?;
+        self.parse_recipe(content, &recipe_title)
+            .map_err(RecipeError::Parse)?;
+
+        let filename = generate_filename(&recipe_title);
+        let git_path = self
+            .generate_git_path_from_filename(&filename, category.as_deref())
+            .await?;
+        self.check_write("Draft", &git_path, content, author)
+            .await?;
+
+        self.write_draft(&git_path, &recipe_title, content, author, comment)
+            .await
+    }
+
+    /// Save new content for an existing recipe as a draft; the published recipe is unchanged
+    ///
+    /// Drafts keep the recipe's current path, so renames and moves happen on publish-time
+    /// updates rather than in the draft.
+    pub async fn update_draft(
+        &self,
+        git_path: &str,
+        content: &str,
+        author: Option<&str>,
+        comment: Option<&str>,
+    ) -> Result<Draft> {
+        if self.cache.get(git_path).is_none() {
+            return Err(anyhow!("Recipe not found: {}", git_path));
+        }
+
+        let recipe_title = extract_recipe_title(content)
+            .map_err(|e| RecipeError::InvalidFrontMatter(e.to_string()))?;
+        self.parse_recipe(content, &recipe_title)
+            .map_err(RecipeError::Parse)?;
+        self.check_write("Draft", git_path, content, author).await?;
+
+        self.write_draft(git_path, &recipe_title, content, author, comment)
+            .await
+    }
+
+    /// All drafts, most recently updated first
+    pub async fn list_drafts(&self) -> Result<Vec<Draft>> {
+        let mut drafts: Vec<Draft> = self
+            .on_storage(|storage| storage.list_drafts())
+            .await?
+            .into_iter()
+            .map(|file| self.draft_from_file(file))
+            .collect();
+        drafts.sort_by_key(|draft| std::cmp::Reverse(draft.updated_at));
+        Ok(drafts)
+    }
+
+    /// Look up a draft by ID
+    pub async fn get_draft(&self, draft_id: &str) -> Result<Option<Draft>> {
+        Ok(self
+            .list_drafts()
+            .await?
+            .into_iter()
+            .find(|draft| draft.draft_id == draft_id))
+    }
+
+    /// Merge a draft into the main branch, making it the published recipe
+    pub async fn publish_draft(&self, draft_id: &str, author: Option<&str>) -> Result<Recipe> {
+        let draft = self
+            .get_draft(draft_id)
+            .await?
+            .ok_or_else(|| anyhow!("Draft not found: {}", draft_id))?;
+        self.check_write("Publish", &draft.git_path, &draft.content, author)
+            .await?;
+
+        let commit = self.commit_info("Publish", &draft.git_path, &draft.name, author, None);
+        let id = draft_id.to_string();
+        let published = self
+            .on_storage(move |storage| storage.publish_draft(&id, &commit))
+            .await?;
+        let published = self.draft_from_file(published);
+
+        let parsed = self
+            .parse_recipe(&published.content, &published.name)
+            .map_err(RecipeError::Parse)?;
+        let stat = self.stat_of(&published.git_path).await;
+        let last_modified = stat.as_ref().map(FileStat::last_modified);
+        let cached = cached_entry(
+            &published.git_path,
+            &published.name,
+            published.category.clone(),
+            &published.content,
+            parsed,
+            stat,
+        );
+
+        let event = match self.cache.get(&published.git_path) {
+            Some(_) => EventKind::RecipeUpdated,
+            None => EventKind::RecipeCreated,
+        };
+        self.publish(
+            RepositoryEvent::new(
+                event,
+                &published.git_path,
+                &published.name,
+                published.category.as_deref(),
+                author,
+            ),
+            Some((cached, &published.content)),
+        );
+
+        Ok(Recipe {
+            git_path: published.git_path,
+            file_name: published.file_name,
+            name: published.name,
+            description: None,
+            category: published.category,
+            content: Some(published.content),
+            last_modified,
+        })
+    }
+
+    /// Throw a draft away, returning whether it existed
+    pub async fn discard_draft(&self, draft_id: &str) -> Result<bool> {
+        let draft_id = draft_id.to_string();
+        self.on_storage(move |storage| storage.discard_draft(&draft_id))
+            .await
+    }
+
+    async fn write_draft(
+        &self,
+        git_path: &str,
+        title: &str,
+        content: &str,
+        author: Option<&str>,
+        comment: Option<&str>,
+    ) -> Result<Draft> {
+        let draft_id = generate_recipe_id(git_path);
+        let commit = self.commit_info("Draft", git_path, title, author, comment);
+        let (id, path, draft) = (draft_id.clone(), git_path.to_string(), content.to_string());
+        self.on_storage(move |storage| storage.write_draft(&id, &path, &draft, &commit))
+            .await?;
+
+        Ok(Draft {
+            draft_id,
+            git_path: git_path.to_string(),
+            file_name: self.extract_filename_from_path(git_path),
+            name: title.to_string(),
+            category: self.extract_category_from_path(git_path),
+            content: content.to_string(),
+            updated_at: Utc::now(),
+        })
+    }
+
+    fn draft_from_file(&self, file: DraftFile) -> Draft {
+        let name = extract_recipe_title(&file.content)
+            .unwrap_or_else(|_| self.path_to_name(&file.rel_path));
+
+        Draft {
+            draft_id: file.draft_id,
+            file_name: self.extract_filename_from_path(&file.rel_path),
+            category: self.extract_category_from_path(&file.rel_path),
+            git_path: file.rel_path,
+            name,
+            content: file.content,
+            updated_at: file.updated_at,
+        }
+    }
+
+    /// Whether the storage backend can tag recipe versions (git only)
+    pub fn supports_versions(&self) -> bool {
+        self.storage.supports_versions()
+    }
+
+    /// Tag the recipe's latest committed content as version `name`
+    pub async fn tag_version(
+        &self,
+        git_path: &str,
+        name: &str,
+        author: Option<&str>,
+        comment: Option<&str>,
+    ) -> Result<VersionTag> {
+        let cached = self
+            .cache
+            .get(git_path)
+            .ok_or_else(|| anyhow!("Recipe not found: {}", git_path))?;
+
+        let commit = self.commit_info("Tag", git_path, &cached.name, author, comment);
+        let (name, git_path) = (name.to_string(), git_path.to_string());
+        self.on_storage(move |storage| {
+            storage.tag_version(&generate_recipe_id(&git_path), &name, &git_path, &commit)
+        })
+        .await
+    }
+
+    /// Tagged versions of a recipe, newest first
+    pub async fn list_versions(&self, git_path: &str) -> Result<Vec<VersionTag>> {
+        let recipe_id = generate_recipe_id(git_path);
+        let mut versions = self
+            .on_storage(move |storage| storage.list_versions(&recipe_id))
+            .await?;
+        versions.sort_by_key(|version| std::cmp::Reverse(version.created_at));
+        Ok(versions)
+    }
+
+    /// A tagged version of a recipe with its content at that version
+    pub async fn get_version(
+        &self,
+        git_path: &str,
+        name: &str,
+    ) -> Result<Option<(VersionTag, String)>> {
+        let recipe_id = generate_recipe_id(git_path);
+        let (name, git_path) = (name.to_string(), git_path.to_string());
+        self.on_storage(move |storage| {
+            let Some(content) = storage.read_version(&recipe_id, &name, &git_path)? else {
+                return Ok(None);
+            };
+
+            let version = storage
+                .list_versions(&recipe_id)?
+                .into_iter()
+                .find(|version| version.name == name)
+                .ok_or_else(|| anyhow!("Version not found: {}", name))?;
+
+            Ok(Some((version, content)))
+        })
+        .await
+    }
+
+    /// Whether the storage backend can blame recipe lines (git only)
+    pub fn supports_blame(&self) -> bool {
+        self.storage.supports_blame()
+    }
+
+    /// The commit and author that last changed each line of a recipe
+    pub async fn blame(&self, git_path: &str) -> Result<Vec<BlameLine>> {
+        let git_path = git_path.to_string();
+        self.on_storage(move |storage| storage.blame_file(&git_path))
+            .await
+    }
+
+    /// Permanently delete trashed recipes older than the retention period, returning how many
+    pub fn purge_expired_trash(&self) -> Result<usize> {
+        if self.config().trash_retention_days == 0 {
+            return Ok(0);
+        }
+
+        let cutoff = Utc::now() - chrono::Duration::days(self.config().trash_retention_days as i64);
+        let mut purged = 0;
+
+        for entry in self.list_trash()? {
+            crate::deadline::check(purged, None)?;
+            if entry.deleted_at < cutoff {
+                let commit =
+                    self.commit_info("Purge", &entry.original_path, &entry.name, None, None);
+                self.storage
+                    .delete_file_with_commit(&entry.trash_path, &commit)?;
+                delete_notes(self.storage.as_ref(), &entry.trash_path, &commit)?;
+                purged += 1;
+            }
+        }
+
+        if purged > 0 {
+            tracing::info!("Purged {} expired recipe(s) from trash", purged);
+        }
+
+        Ok(purged)
+    }
+
+    /// List all recipes (without content), by name
+    pub fn list_all(&self) -> Vec<Recipe> {
+        self.cache
+            .get_all()
+            .into_iter()
+            .map(|cached| self.summary(cached))
+            .collect()
+    }
+
+    /// Number of indexed recipes
+    pub fn recipe_count(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// `recipe` with its content read from storage, if it was listed without
+    pub fn with_content(&self, mut recipe: Recipe) -> Result<Recipe> {
+        if recipe.content.is_none() {
+            recipe.content = Some(self.storage.read_file(&recipe.git_path)?);
+        }
+        Ok(recipe)
+    }
+
+    /// Whether an indexed recipe is marked `draft: true` in its front matter
+    pub fn is_draft(&self, git_path: &str) -> bool {
+        self.cache
+            .get(git_path)
+            .is_some_and(|cached| cached.is_draft())
+    }
+
+    /// Whether an indexed recipe is open to everyone (`visibility: public`, not a draft)
+    pub fn is_public(&self, git_path: &str) -> bool {
+        self.cache
+            .get(git_path)
+            .is_some_and(|cached| cached.is_public())
+    }
+
+    /// Total and hands-on minutes of an indexed recipe, as far as they are known
+    pub fn recipe_minutes(&self, git_path: &str) -> (Option<u32>, Option<u32>) {
+        self.cache
+            .get(git_path)
+            .map(|cached| (cached.total_minutes, cached.active_minutes))
+            .unwrap_or_default()
+    }
+
+    /// When recipes, or favorites, last changed, as far as responses built from the index
+    /// can tell
+    pub fn changed_at(&self) -> DateTime<Utc> {
+        self.cache.changed_at()
+    }
+
+    /// Standard front matter fields of an indexed recipe (empty if it isn't indexed)
+    pub fn recipe_metadata(&self, git_path: &str) -> RecipeMetadata {
+        self.cache
+            .get(git_path)
+            .map(|cached| cached.metadata)
+            .unwrap_or_default()
+    }
+
+    /// Search recipe names, ingredients and steps, best matches first
+    pub fn search(&self, query: &str) -> Vec<SearchResult> {
+        self.search_filtered(Some(query), &RecipeFilter::default())
+    }
+
+    /// Search results meeting every condition of the filter
+    ///
+    /// Without a query, every recipe meeting the filter is returned by name with a zero score.
+    pub fn search_filtered(&self, query: Option<&str>, filter: &RecipeFilter) -> Vec<SearchResult> {
+        let filter = &self.with_synonyms(filter);
+        let Some(query) = query else {
+            return self
+                .list_filtered(filter)
+                .into_iter()
+                .map(|recipe| SearchResult {
+                    recipe,
+                    score: 0.0,
+                    highlights: Vec::new(),
+                })
+                .collect();
+        };
+
+        self.search_hits(query)
+            .into_iter()
+            .filter(|hit| {
+                filter.is_empty()
+                    || self
+                        .cache
+                        .get(&hit.git_path)
+                        .is_some_and(|cached| filter.matches(&cached))
+            })
+            .filter_map(|hit| {
+                let recipe = self.summary_from_cache(&hit.git_path)?;
+                Some(SearchResult {
+                    recipe,
+                    score: hit.score,
+                    highlights: hit.highlights,
+                })
+            })
+            .collect()
+    }
+
+    /// Search recipes by name only using the configured search provider
+    pub fn search_by_name(&self, query: &str) -> Vec<Recipe> {
+        self.search_hits(query)
+            .into_iter()
+            .filter(|hit| hit.matched_name())
+            .filter_map(|hit| self.summary_from_cache(&hit.git_path))
+            .collect()
+    }
+
+    fn search_hits(&self, query: &str) -> Vec<SearchHit> {
+        self.search.query(query).unwrap_or_else(|e| {
+            tracing::warn!("Search provider query failed: {}", e);
+            Vec::new()
+        })
+    }
+
+    /// A cached recipe without its content
+    fn summary_from_cache(&self, git_path: &str) -> Option<Recipe> {
+        self.cache.get(git_path).map(|cached| self.summary(cached))
+    }
+
+    /// An index entry as a recipe without its content
+    fn summary(&self, cached: CachedRecipe) -> Recipe {
+        Recipe {
+            file_name: self.extract_filename_from_path(&cached.git_path),
+            git_path: cached.git_path,
+            name: cached.name,
+            description: cached.description,
+            category: cached.category,
+            content: None,
+            last_modified: cached.last_modified,
+        }
+    }
+
+    /// Every distinct ingredient (lowercased) with the recipes using it, sorted by name
+    pub fn ingredient_catalog(&self) -> Vec<IngredientUsage> {
+        // Synonyms merge under their canonical name: canonical -> (other names, git_paths)
+        let synonyms = self.synonyms();
+        let mut merged: BTreeMap<String, (BTreeSet<String>, BTreeSet<String>)> = BTreeMap::new();
+        for (name, git_paths) in self.cache.ingredient_catalog() {
+            let canonical = synonyms.canonical(&name);
+            let entry = merged.entry(canonical.clone()).or_default();
+            if name != canonical {
+                entry.0.insert(name);
+            }
+            entry.1.extend(git_paths);
+        }
+
+        merged
+            .into_iter()
+            .map(|(name, (names, git_paths))| {
+                let mut recipes: Vec<Recipe> = git_paths
+                    .iter()
+                    .filter_map(|git_path| self.summary_from_cache(git_path))
+                    .collect();
+                recipes.sort_by(|a, b| a.name.cmp(&b.name));
+                IngredientUsage {
+                    name,
+                    synonyms: names.into_iter().collect(),
+                    recipes,
+                }
+            })
+            .collect()
+    }
+
+    /// A copy of `filter` matching ingredients by the loaded synonyms
+    fn with_synonyms(&self, filter: &RecipeFilter) -> RecipeFilter {
+        RecipeFilter {
+            synonyms: self.synonyms(),
+            ..filter.clone()
+        }
+    }
+
+    /// Get recipes by category, by name
+    pub fn list_by_category(&self, category: &str) -> Vec<Recipe> {
+        let recipes = if self.config().case_insensitive_paths {
+            self.cache.get_by_category_ignore_case(category)
+        } else {
+            self.cache.get_by_category(category)
+        };
+
+        recipes
+            .into_iter()
+            .map(|cached| self.summary(cached))
+            .collect()
+    }
+
+    /// List recipes meeting every condition of the filter, by name
+    pub fn list_filtered(&self, filter: &RecipeFilter) -> Vec<Recipe> {
+        self.cache
+            .filter(&self.with_synonyms(filter))
+            .into_iter()
+            .map(|cached| self.summary(cached))
+            .collect()
+    }
+
+    /// One page of the recipes meeting the filter and `keep`, in the given order, with
+    /// how many meet them in all
+    ///
+    /// Pages are read straight from the index, so listing stays cheap however many
+    /// recipes there are.
+    pub fn list_page<F>(
+        &self,
+        filter: &RecipeFilter,
+        order: SortOrder,
+        offset: usize,
+        limit: usize,
+        keep: F,
+    ) -> (Vec<Recipe>, usize)
+    where
+        F: Fn(&CachedRecipe) -> bool,
+    {
+        let filter = self.with_synonyms(filter);
+        let (page, total) = self.cache.page(order, offset, limit, |cached| {
+            filter.matches(cached) && keep(cached)
+        });
+        let recipes = page
+            .into_iter()
+            .map(|cached| self.summary(cached))
+            .collect();
+        (recipes, total)
+    }
+
+    /// Start a resumable upload, discarding unfinished uploads past their expiry first
+    pub fn start_upload(&self, file_name: &str, size: u64) -> Result<UploadSession> {
+        self.purge_expired_uploads();
+        self.uploads.create(file_name, size, Utc::now())
+    }
+
+    /// Output derived from a recipe's current content (rendered HTML, nutrition, ...)
+    ///
+    /// Cached per recipe and `kind` for `DERIVED_CACHE_TTL_SECS`, and recomputed as soon
+    /// as the content changes.
+    pub fn derived_output<F>(&self, git_path: &str, kind: &str, compute: F) -> Result<Arc<Vec<u8>>>
+    where
+        F: FnOnce(&str) -> Result<Vec<u8>>,
+    {
+        let content = self.storage.read_file(git_path)?;
+        self.derived
+            .get_or_compute(git_path, kind, &content, compute)
+    }
+
+    /// Estimated calories and macros of a recipe, cached like other derived outputs
+    pub fn nutrition(&self, git_path: &str) -> Result<NutritionEstimate> {
+        let json = self.derived_output(git_path, "nutrition", |content| {
+            Ok(serde_json::to_vec(&self.nutrition.estimate(content))?)
+        })?;
+        Ok(serde_json::from_slice(&json)?)
+    }
+
+    /// A recipe rendered as HTML, cached like other derived outputs
+    pub fn render_html(&self, git_path: &str, options: &HtmlOptions) -> Result<String> {
+        let title = self
+            .cache
+            .get(git_path)
+            .map(|cached| cached.name)
+            .ok_or_else(|| anyhow!("Recipe not found: {}", git_path))?;
+        let html = self.derived_output(git_path, options.cache_kind(), |content| {
+            Ok(render_html(content, &title, options, &self.config().quantity_format).into_bytes())
+        })?;
+        Ok(String::from_utf8_lossy(&html).into_owned())
+    }
+
+    /// Built-in templates merged with `templates/*.cook` from the data dir, sorted by name
+    pub fn list_templates(&self) -> Result<Vec<Template>> {
+        let mut templates: BTreeMap<String, Template> = builtin_templates()
+            .into_iter()
+            .map(|template| (template.name.clone(), template))
+            .collect();
+
+        for git_path in self.storage.list_files(TEMPLATES_DIR)? {
+            let Some(name) = template_name(&git_path) else {
+                continue;
+            };
+            match self.storage.read_file(&git_path) {
+                Ok(content) => {
+                    templates.insert(
+                        name.to_string(),
+                        Template {
+                            name: name.to_string(),
+                            content,
+                            builtin: false,
+                        },
+                    );
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to read template {}: {}", git_path, e);
+                }
+            }
+        }
+
+        Ok(templates.into_values().collect())
+    }
+
+    /// Get a template by name
+    pub fn get_template(&self, name: &str) -> Result<Option<Template>> {
+        Ok(self
+            .list_templates()?
+            .into_iter()
+            .find(|template| template.name == name))
+    }
+
+    /// Create a recipe titled `title` from a template, `None` if there is no such template
+    pub async fn create_from_template(
+        &self,
+        template: &str,
+        title: &str,
+        category: Option<&str>,
+        author: Option<&str>,
+        comment: Option<&str>,
+    ) -> Result<Option<Recipe>> {
+        let Some(template) = self.get_template(template)? else {
+            return Ok(None);
+        };
+
+        let content = instantiate(&template.content, title);
+        self.create_with_author_and_comment(title, &content, category, author, comment)
+            .await
+            .map(Some)
+    }
+
+    /// All indexed menus, sorted by name
+    pub fn list_menus(&self) -> Vec<(String, Menu)> {
+        let mut menus: Vec<(String, Menu)> = self
+            .menus
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+        menus.sort_by_cached_key(|(_, menu)| self.menu_name(menu).to_lowercase());
+        menus
+    }
+
+    /// Get a menu by its ID (derived from its path, like recipe IDs)
+    pub fn get_menu(&self, menu_id: &str) -> Option<Menu> {
+        self.menus.get(menu_id).map(|menu| menu.clone())
+    }
+
+    /// Display name of a menu: its front matter title, or one derived from the file name
+    pub fn menu_name(&self, menu: &Menu) -> String {
+        menu.title
+            .clone()
+            .unwrap_or_else(|| self.path_to_name(&menu.git_path))
+    }
+
+    /// Resolve a menu's recipe reference (e.g. `./Breakfast/Easy Pancakes`) to an indexed
+    /// recipe (without content)
+    ///
+    /// Tried relative to the menu, to the recipes directory and to the data directory; paths are
+    /// compared ignoring case when `case_insensitive_paths` is enabled.
+    pub fn resolve_menu_reference(&self, menu_path: &str, reference: &str) -> Option<Recipe> {
+        let candidates = reference_candidates(menu_path, &self.config().recipes_dir, reference);
+        if let Some(recipe) = candidates
+            .iter()
+            .find_map(|candidate| self.summary_from_cache(candidate))
+        {
+            return Some(recipe);
+        }
+
+        if !self.config().case_insensitive_paths {
+            return None;
+        }
+        candidates
+            .iter()
+            .find_map(|candidate| self.cache.find_git_path_ignore_case(candidate))
+            .and_then(|git_path| self.summary_from_cache(&git_path))
+    }
+
+    /// [`RecipeStorage::stat`] on the blocking pool, logging failures
+    ///
+    /// Git storage looks up the last commit through its worker queue, which can sit
+    /// behind a slow commit.
+    async fn stat_of(&self, git_path: &str) -> Option<FileStat> {
+        let rel_path = git_path.to_string();
+        self.on_storage(move |storage| storage.stat(&rel_path))
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to look up {}: {}", git_path, e);
+                None
+            })
+    }
+
+    /// Run a storage call on the blocking pool
+    ///
+    /// Git storage queues writes behind each other; awaiting them here keeps a slow
+    /// commit from tying up a runtime thread that other requests need. The request
+    /// deadline comes along, so storage loops still stop when it passes.
+    async fn on_storage<T, F>(&self, call: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&dyn RecipeStorage) -> Result<T> + Send + 'static,
+    {
+        let storage = self.storage.clone();
+        tokio::task::spawn_blocking(crate::deadline::carry(move || call(storage.as_ref()))).await?
+    }
+
+    /// Whether the storage backend has maintenance to run (git only)
+    pub fn supports_maintenance(&self) -> bool {
+        self.storage.supports_maintenance()
+    }
+
+    /// Run storage maintenance (git gc) on a blocking thread
+    ///
+    /// Requests keep being served meanwhile; a second run while one is in progress
+    /// fails with [`crate::storage::MaintenanceRunning`].
+    pub async fn run_maintenance(&self) -> Result<GcReport> {
+        let storage = self.storage.clone();
+        let report = tokio::task::spawn_blocking(move || storage.run_maintenance()).await??;
+
+        tracing::info!(
+            "Git maintenance packed {} loose object(s) in {} ms ({} left, {} pack(s))",
+            report
+                .loose_objects_before
+                .saturating_sub(report.loose_objects_after),
+            report.duration_ms,
+            report.loose_objects_after,
+            report.packs_after
+        );
+        Ok(report)
+    }
+
+    /// Compare the data directory with its mirror, `None` without `MIRROR_PATH`
+    pub async fn check_mirror(&self) -> Result<Option<MirrorReport>> {
+        self.on_storage(|storage| storage.check_mirror(&UNMIRRORED_DIRS))
+            .await
+    }
+
+    /// Bring the mirror back in line with the data directory, `None` without `MIRROR_PATH`
+    ///
+    /// Returns the comparison made after the repair.
+    pub async fn repair_mirror(&self) -> Result<Option<MirrorReport>> {
+        let report = self
+            .on_storage(|storage| storage.repair_mirror(&UNMIRRORED_DIRS))
+            .await?;
+        if let Some(report) = &report {
+            tracing::info!(
+                "Mirror repaired: {} missing, {} different, {} extra file(s) left",
+                report.missing.len(),
+                report.different.len(),
+                report.extra.len()
+            );
+        }
+        Ok(report)
+    }
+
+    /// Run maintenance in the background
+    ///
+    /// Expired derived outputs and uploads are dropped every few minutes, so entries
+    /// nobody asks for again don't linger; git gc runs every `GIT_GC_INTERVAL_HOURS`
+    /// (git storage only).
+    pub fn spawn_maintenance(self: &Arc<Self>) {
+        let repo = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval_at(
+                tokio::time::Instant::now() + SWEEP_INTERVAL,
+                SWEEP_INTERVAL,
+            );
+            loop {
+                interval.tick().await;
+                let purged = repo.derived.purge_expired();
+                if purged > 0 {
+                    tracing::debug!("Dropped {} expired derived output(s)", purged);
+                }
+                repo.purge_expired_uploads();
+            }
+        });
+
+        let hours = self.config().git_gc_interval_hours;
+        if hours == 0 || !self.supports_maintenance() {
+            return;
+        }
+
+        let repo = Arc::clone(self);
+        let period = Duration::from_secs(u64::from(hours) * 3600);
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                if let Err(e) = repo.run_maintenance().await {
+                    tracing::warn!("Scheduled git maintenance failed: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Staging area for resumable uploads
+    pub fn uploads(&self) -> &UploadStore {
+        &self.uploads
+    }
+
+    fn purge_expired_uploads(&self) {
+        if self.config().upload_expiry_hours == 0 {
+            return;
+        }
+
+        let cutoff = Utc::now() - chrono::Duration::hours(self.config().upload_expiry_hours as i64);
+        match self.uploads.purge_expired(cutoff) {
+            Ok(0) => {}
+            Ok(purged) => tracing::info!("Discarded {} expired upload(s)", purged),
+            Err(e) => tracing::warn!("Failed to purge expired uploads: {}", e),
+        }
+    }
+
+    /// Add a check that runs before recipe content is written, after the configured ones
+    ///
+    /// See [`WriteHook`]; the built-in and external hooks are set up from the config.
+    pub fn register_write_hook(&self, hook: Arc<dyn WriteHook>) {
+        if let Ok(mut hooks) = self.hooks.write() {
+            hooks.push(hook);
+        }
+    }
+
+    /// Check content about to be written to `git_path`: its `visibility`, then the
+    /// write hooks, stopping at the first rejection
+    ///
+    /// Moves, renames, deletes and restores keep content as it is and are not checked.
+    async fn check_write(
+        &self,
+        action: &str,
+        git_path: &str,
+        content: &str,
+        author: Option<&str>,
+    ) -> Result<()> {
+        extract_visibility(content).map_err(|e| RecipeError::InvalidFrontMatter(e.to_string()))?;
+
+        let mut hooks = self
+            .configured_hooks
+            .read()
+            .map(|hooks| hooks.clone())
+            .unwrap_or_default();
+        if let Ok(registered) = self.hooks.read() {
+            hooks.extend(registered.iter().cloned());
+        }
+        if hooks.is_empty() {
+            return Ok(());
+        }
+
+        let write = RecipeWrite {
+            action: action.to_string(),
+            path: git_path.to_string(),
+            content: content.to_string(),
+            author: author.map(str::to_string),
+        };
+        // External hooks wait on other processes; keep them off the runtime threads
+        if hooks.iter().any(|hook| hook.is_external()) {
+            tokio::task::spawn_blocking(crate::deadline::carry(move || {
+                hooks.iter().try_for_each(|hook| hook.check(&write))
+            }))
+            .await?
+        } else {
+            hooks.iter().try_for_each(|hook| hook.check(&write))
+        }
+    }
+
+    /// Build the commit for a change from the configured message template
+    fn commit_info(
+        &self,
+        action: &str,
+        git_path: &str,
+        title: &str,
+        author: Option<&str>,
+        comment: Option<&str>,
+    ) -> CommitInfo {
+        CommitInfo {
+            message: render_commit_message(
+                &self.config().commit_message_template,
+                action,
+                title,
+                git_path,
+                author,
+                comment,
+            ),
+            author: Some(self.commit_author(author)),
+        }
+    }
+
+    /// Git identity for a request author, falling back to the configured store identity
+    fn commit_author(&self, author: Option<&str>) -> CommitAuthor {
+        match author.map(str::trim).filter(|a| !a.is_empty()) {
+            Some(name) => CommitAuthor {
+                name: name.to_string(),
+                email: self.config().author_email(name).to_string(),
+            },
+            None => CommitAuthor {
+                name: self.config().commit_author_name.clone(),
+                email: self.config().commit_author_email.clone(),
+            },
+        }
+    }
+
+    /// Runtime configuration in effect (see [`reload_config`](Self::reload_config))
+    pub fn config(&self) -> Arc<StoreConfig> {
+        self.config
+            .read()
+            .map(|config| Arc::clone(&config))
+            .unwrap_or_default()
+    }
+
+    /// Get all categories
+    pub fn get_categories(&self) -> Vec<String> {
+        self.cache.get_categories()
+    }
+
+    /// All categories with how many recipes each holds, directly and in subcategories
+    pub fn category_counts(&self) -> Vec<CategoryCount> {
+        self.cache.category_counts()
+    }
+
+    /// Resolve a user-supplied category path to an existing category
+    ///
+    /// Leading/trailing slashes are ignored, and case differences too when
+    /// `case_insensitive_paths` is enabled. Returns `None` if no category matches.
+    pub fn resolve_category(&self, path: &str) -> Option<String> {
+        let trimmed = path.trim_matches('/');
+        let categories = self.cache.get_categories();
+
+        if let Some(exact) = categories.iter().find(|cat| cat.as_str() == trimmed) {
+            return Some(exact.clone());
+        }
+
+        if self.config().case_insensitive_paths {
+            let trimmed_lower = trimmed.to_lowercase();
+            return categories
+                .into_iter()
+                .find(|cat| cat.to_lowercase() == trimmed_lower);
+        }
+
+        None
+    }
+
+    /// Spelling of category `path` as stored, which may also be just a parent of
+    /// categories (`meals` when only `meals/meat` holds recipes)
+    ///
+    /// Leading/trailing slashes are ignored, and case differences too when
+    /// `case_insensitive_paths` is enabled.
+    fn stored_category_prefix(&self, path: &str) -> Option<String> {
+        let trimmed = path.trim_matches('/');
+        if trimmed.is_empty() {
+            return None;
+        }
+        let depth = trimmed.split('/').count();
+        let ignore_case = self.config().case_insensitive_paths;
+        let mut found = None;
+        for cat in self.cache.get_categories() {
+            let segments: Vec<&str> = cat.split('/').collect();
+            if segments.len() < depth {
+                continue;
+            }
+            let prefix = segments[..depth].join("/");
+            if prefix == trimmed {
+                return Some(prefix);
+            }
+            if ignore_case && found.is_none() && prefix.to_lowercase() == trimmed.to_lowercase() {
+                found = Some(prefix);
+            }
+        }
+        found
+    }
+
+    /// Canonicalize a category path before writing
+    ///
+    /// Strips leading/trailing and repeated slashes (empty means root). When
+    /// `case_insensitive_paths` is enabled, each segment that matches an existing
+    /// category prefix apart from case reuses the existing spelling, so `Desserts/Cakes`
+    /// lands in `desserts/cakes` if that already exists.
+    fn canonical_category(&self, category: Option<&str>) -> Option<String> {
+        let segments: Vec<&str> = category?.split('/').filter(|s| !s.is_empty()).collect();
+        if segments.is_empty() {
+            return None;
+        }
+
+        if !self.config().case_insensitive_paths {
+            return Some(segments.join("/"));
+        }
+
+        // Every prefix of every existing category, e.g. "meals" and "meals/meat"
+        let mut existing_prefixes = std::collections::HashSet::new();
+        for cat in self.cache.get_categories() {
+            let mut prefix = String::new();
+            for segment in cat.split('/') {
+                if !prefix.is_empty() {
+                    prefix.push('/');
+                }
+                prefix.push_str(segment);
+                existing_prefixes.insert(prefix.clone());
+            }
+        }
+
+        let mut canonical = String::new();
+        for segment in segments {
+            let candidate = if canonical.is_empty() {
+                segment.to_string()
+            } else {
+                format!("{}/{}", canonical, segment)
+            };
+            let candidate_lower = candidate.to_lowercase();
+            canonical = existing_prefixes
+                .iter()
+                .find(|prefix| prefix.to_lowercase() == candidate_lower)
+                .cloned()
+                .unwrap_or(candidate);
+        }
+
+        Some(canonical)
+    }
+
+    /// Record that a category was renamed, so lookups under the old path redirect
+    pub async fn record_category_alias(&self, from: &str, to: &str) -> Result<()> {
+        self.aliases.record(from, to, Utc::now());
+        let json = self.aliases.to_json()?;
+        self.on_storage(move |storage| storage.write_file(CATEGORY_ALIASES_FILE, &json))
+            .await
+    }
+
+    /// Resolve a path under a renamed category to its current location
+    pub fn resolve_category_alias(&self, path: &str) -> Option<String> {
+        self.aliases
+            .resolve(path.trim_matches('/'), self.category_alias_cutoff())
+    }
+
+    /// Map the recipe ID a recipe had before its category was renamed to its current ID
+    pub fn resolve_recipe_alias(&self, recipe_id: &str) -> Option<String> {
+        let cutoff = self.category_alias_cutoff();
+
+        self.aliases
+            .all()
+            .into_iter()
+            .filter(|alias| cutoff.map(|c| alias.created_at >= c).unwrap_or(true))
+            .find_map(|alias| {
+                // Rebuild each current recipe's path under the old prefix and compare IDs
+                let new_prefix = format!("{}/", self.recipe_path(Some(&alias.to), ""));
+                self.cache.get_all().into_iter().find_map(|cached| {
+                    let rest = cached.git_path.strip_prefix(&new_prefix)?;
+                    let old_path = self.recipe_path(Some(&alias.from), rest);
+                    (generate_recipe_id(&old_path) == recipe_id).then_some(cached.recipe_id)
+                })
+            })
+    }
+
+    /// Oldest creation time of aliases that are still honored
+    fn category_alias_cutoff(&self) -> Option<DateTime<Utc>> {
+        match self.config().category_alias_days {
+            0 => None,
+            days => Some(Utc::now() - chrono::Duration::days(days as i64)),
+        }
+    }
+
+    /// Load persisted category aliases, dropping expired ones
+    fn load_category_aliases(&self) {
+        let Ok(json) = self.storage.read_file(CATEGORY_ALIASES_FILE) else {
+            return;
+        };
+
+        if let Err(e) = self.aliases.load(&json) {
+            tracing::warn!("Failed to load category aliases: {}", e);
+            return;
+        }
+
+        if let Some(cutoff) = self.category_alias_cutoff() {
+            if self.aliases.purge_expired(cutoff) > 0 {
+                if let Err(e) = self.save_category_aliases() {
+                    tracing::warn!("Failed to save category aliases: {}", e);
                 }
+            }
+        }
+    }
+
+    fn save_category_aliases(&self) -> Result<()> {
+        self.storage
+            .write_file(CATEGORY_ALIASES_FILE, &self.aliases.to_json()?)
+    }
+
+    /// Rating and cooking notes of a recipe (empty if it has none)
+    pub fn notes(&self, git_path: &str) -> Result<RecipeNotes> {
+        if self.cache.get(git_path).is_none() {
+            return Err(anyhow!("Recipe not found: {}", git_path));
+        }
+
+        match self.storage.read_file(&notes_path(git_path)) {
+            Ok(yaml) => RecipeNotes::from_yaml(&yaml),
+            Err(_) => Ok(RecipeNotes::default()),
+        }
+    }
+
+    /// Set (or with `None`, clear) a recipe's star rating
+    pub async fn set_rating(
+        &self,
+        git_path: &str,
+        rating: Option<u8>,
+        author: Option<&str>,
+    ) -> Result<RecipeNotes> {
+        let mut notes = self.notes(git_path)?;
+        notes.rating = rating;
+        self.save_notes(git_path, &notes, "Rate", author).await?;
+        Ok(notes)
+    }
+
+    /// Add a cooking note dated `date` (today if not given)
+    pub async fn add_note(
+        &self,
+        git_path: &str,
+        text: &str,
+        date: Option<NaiveDate>,
+        author: Option<&str>,
+    ) -> Result<RecipeNotes> {
+        let mut notes = self.notes(git_path)?;
+        notes.add(CookingNote {
+            date: date.unwrap_or_else(|| Utc::now().date_naive()),
+            text: text.to_string(),
+            author: author.map(str::to_string),
+        });
+        self.save_notes(git_path, &notes, "Annotate", author)
+            .await?;
+        Ok(notes)
+    }
+
+    /// Remove the note at `index` (in date order), returning `None` if there is no such note
+    pub async fn remove_note(
+        &self,
+        git_path: &str,
+        index: usize,
+        author: Option<&str>,
+    ) -> Result<Option<RecipeNotes>> {
+        let mut notes = self.notes(git_path)?;
+        if index >= notes.notes.len() {
+            return Ok(None);
+        }
+        notes.notes.remove(index);
+        self.save_notes(git_path, &notes, "Annotate", author)
+            .await?;
+        Ok(Some(notes))
+    }
+
+    /// Write a recipe's notes sidecar, removing it once there is nothing left in it
+    async fn save_notes(
+        &self,
+        git_path: &str,
+        notes: &RecipeNotes,
+        action: &str,
+        author: Option<&str>,
+    ) -> Result<()> {
+        let title = self
+            .cache
+            .get(git_path)
+            .map(|cached| cached.name)
+            .unwrap_or_else(|| self.path_to_name(git_path));
+        let commit = self.commit_info(action, git_path, &title, author, None);
+
+        let git_path = git_path.to_string();
+        let yaml = if notes.is_empty() {
+            None
+        } else {
+            Some(notes.to_yaml()?)
+        };
+        self.on_storage(move |storage| match yaml {
+            Some(yaml) => storage.write_file_with_commit(&notes_path(&git_path), &yaml, &commit),
+            None => delete_notes(storage, &git_path, &commit),
+        })
+        .await
+    }
+
+    /// Star or unstar a recipe for a user (the default user when none is given)
+    pub async fn set_favorite(
+        &self,
+        git_path: &str,
+        user: Option<&str>,
+        favorite: bool,
+    ) -> Result<()> {
+        let cached = self
+            .cache
+            .get(git_path)
+            .ok_or_else(|| anyhow!("Recipe not found: {}", git_path))?;
+
+        if self.favorites.set(user, git_path, favorite) {
+            let action = if favorite { "Favorite" } else { "Unfavorite" };
+            let commit = self.commit_info(action, git_path, &cached.name, user, None);
+            let json = self.favorites.to_json()?;
+            self.on_storage(move |storage| {
+                storage.write_file_with_commit(FAVORITES_FILE, &json, &commit)
             })
-            .collect()
+            .await?;
+            // `?favorite=` listings change with the favorites
+            self.cache.touch();
+        }
+        Ok(())
     }
 
-    /// Get recipes by category
-    pub fn list_by_category(&self, category: &str) -> Vec<Recipe> {
-        self.cache
-            .get_by_category(category)
+    /// Whether a user (the default user when none is given) starred a recipe
+    pub fn is_favorite(&self, git_path: &str, user: Option<&str>) -> bool {
+        self.favorites.contains(user, git_path)
+    }
+
+    /// Load persisted favorites
+    fn load_favorites(&self) {
+        let Ok(json) = self.storage.read_file(FAVORITES_FILE) else {
+            return;
+        };
+
+        if let Err(e) = self.favorites.load(&json) {
+            tracing::warn!("Failed to load favorites: {}", e);
+        }
+    }
+
+    async fn save_favorites(&self) -> Result<()> {
+        let json = self.favorites.to_json()?;
+        self.on_storage(move |storage| storage.write_file(FAVORITES_FILE, &json))
+            .await
+    }
+
+    /// Ingredients on hand, sorted by name
+    pub fn pantry_items(&self) -> BTreeMap<String, PantryItem> {
+        self.pantry.items()
+    }
+
+    /// Add an ingredient to the pantry or change its quantity
+    pub async fn set_pantry_item(
+        &self,
+        name: &str,
+        item: PantryItem,
+        author: Option<&str>,
+    ) -> Result<()> {
+        if self.pantry.set(name, item) {
+            let commit = self.commit_info("Stock", PANTRY_FILE, name.trim(), author, None);
+            self.save_pantry(commit).await?;
+        }
+        Ok(())
+    }
+
+    /// Remove an ingredient from the pantry, returning whether it was there
+    pub async fn remove_pantry_item(&self, name: &str, author: Option<&str>) -> Result<bool> {
+        if !self.pantry.remove(name) {
+            return Ok(false);
+        }
+        let commit = self.commit_info("Unstock", PANTRY_FILE, name.trim(), author, None);
+        self.save_pantry(commit).await?;
+        Ok(true)
+    }
+
+    async fn save_pantry(&self, commit: CommitInfo) -> Result<()> {
+        let json = self.pantry.to_json()?;
+        self.on_storage(move |storage| storage.write_file_with_commit(PANTRY_FILE, &json, &commit))
+            .await
+    }
+
+    /// Make a shopping list of everything the recipes need, synonyms merged
+    ///
+    /// A blank name defaults to the recipe names.
+    pub async fn create_shopping_list(
+        &self,
+        name: &str,
+        git_paths: &[String],
+        author: Option<&str>,
+    ) -> Result<ShoppingList> {
+        let mut recipes = Vec::new();
+        for git_path in git_paths {
+            let title = self
+                .cache
+                .get(git_path)
+                .map(|cached| cached.name)
+                .ok_or_else(|| anyhow!("Recipe not found: {}", git_path))?;
+            let content = self.storage.read_file(git_path)?;
+            recipes.push((title, extract_ingredient_amounts(&content)));
+        }
+
+        let name = match name.trim() {
+            "" => recipes
+                .iter()
+                .map(|(title, _)| title.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            name => name.to_string(),
+        };
+        let list = self
+            .shopping_lists
+            .create(&name, &recipes, &self.synonyms(), author);
+        let commit = self.commit_info("Shop", SHOPPING_LISTS_FILE, &list.name, author, None);
+        self.save_shopping_lists(commit).await?;
+        Ok(list)
+    }
+
+    /// All shopping lists, newest first
+    pub fn shopping_lists(&self) -> Vec<ShoppingList> {
+        self.shopping_lists.all()
+    }
+
+    pub fn shopping_list(&self, id: &str) -> Option<ShoppingList> {
+        self.shopping_lists.get(id)
+    }
+
+    /// Check off or correct a shopping list item; `None` if the list or item doesn't exist
+    pub async fn update_shopping_item(
+        &self,
+        list_id: &str,
+        item_id: u32,
+        update: ItemUpdate,
+        author: Option<&str>,
+    ) -> Result<Option<ShoppingItem>> {
+        let Some(item) = self.shopping_lists.update_item(list_id, item_id, update) else {
+            return Ok(None);
+        };
+        let commit = self.commit_info("Check", SHOPPING_LISTS_FILE, &item.name, author, None);
+        self.save_shopping_lists(commit).await?;
+        Ok(Some(item))
+    }
+
+    /// Delete a shopping list, returning whether it existed
+    pub async fn delete_shopping_list(&self, id: &str, author: Option<&str>) -> Result<bool> {
+        let Some(list) = self.shopping_lists.remove(id) else {
+            return Ok(false);
+        };
+        let commit = self.commit_info("Drop", SHOPPING_LISTS_FILE, &list.name, author, None);
+        self.save_shopping_lists(commit).await?;
+        Ok(true)
+    }
+
+    async fn save_shopping_lists(&self, commit: CommitInfo) -> Result<()> {
+        let json = self.shopping_lists.to_json()?;
+        self.on_storage(move |storage| {
+            storage.write_file_with_commit(SHOPPING_LISTS_FILE, &json, &commit)
+        })
+        .await
+    }
+
+    /// Create recipes converted from another app, one commit each
+    ///
+    /// Recipes are given as `(file name, recipe)`; one that can't be read, converted or
+    /// created is reported in its outcome without stopping the others. Ratings go to
+    /// the notes sidecar. Past the request deadline the import stops between recipes
+    /// with [`crate::deadline::OperationTimedOut`].
+    pub async fn import_recipes(
+        &self,
+        recipes: Vec<(String, Result<ImportedRecipe>)>,
+        app: &str,
+        author: Option<&str>,
+    ) -> Result<Vec<ImportOutcome>> {
+        let comment = format!("Import from {}", app);
+        let total = recipes.len();
+        let mut outcomes = Vec::with_capacity(total);
+        for (processed, (file_name, recipe)) in recipes.into_iter().enumerate() {
+            crate::deadline::check(processed, Some(total))?;
+            let name = match &recipe {
+                Ok(recipe) if !recipe.title.trim().is_empty() => recipe.title.trim().to_string(),
+                _ => file_name,
+            };
+            let result = match recipe {
+                Ok(recipe) => self.import_recipe(&recipe, author, &comment).await,
+                Err(e) => Err(e),
+            };
+            outcomes.push(ImportOutcome {
+                name,
+                result: result.map_err(|e| format!("{:#}", e)),
+            });
+        }
+        Ok(outcomes)
+    }
+
+    async fn import_recipe(
+        &self,
+        recipe: &ImportedRecipe,
+        author: Option<&str>,
+        comment: &str,
+    ) -> Result<(Recipe, Fidelity)> {
+        let conversion = to_cooklang(recipe)?;
+        let created = self
+            .create_with_author_and_comment(
+                &recipe.title,
+                &conversion.content,
+                conversion.category.as_deref(),
+                author,
+                Some(comment),
+            )
+            .await?;
+        if recipe.rating.is_some() {
+            self.set_rating(&created.git_path, recipe.rating, author)
+                .await?;
+        }
+        Ok((created, conversion.fidelity))
+    }
+
+    /// Every recipe except drafts as a Paprika `.paprikarecipes` archive, sorted by name
+    pub fn export_paprika(&self) -> Result<Vec<u8>> {
+        let mut recipes = self.list_all();
+        recipes.retain(|recipe| !self.is_draft(&recipe.git_path));
+        recipes.sort_by(|a, b| a.name.cmp(&b.name).then(a.git_path.cmp(&b.git_path)));
+
+        let mut converted = Vec::with_capacity(recipes.len());
+        for recipe in recipes {
+            let recipe = self.with_content(recipe)?;
+            let notes = self.notes(&recipe.git_path)?;
+            converted.push(paprika::convert(
+                &recipe,
+                &notes,
+                &self.config().quantity_format,
+            ));
+        }
+        paprika::archive(&converted, Utc::now())
+    }
+
+    /// Store aisles for sorting shopping lists (none without an aisles file)
+    ///
+    /// The file is read on every call, so edits apply to the next export without a
+    /// reload.
+    pub fn aisles(&self) -> Result<Aisles> {
+        match self.storage.read_file(AISLES_FILE) {
+            Ok(text) => Aisles::parse(&text),
+            Err(_) => Ok(Aisles::default()),
+        }
+    }
+
+    /// Recipes ranked by how much of their ingredient list is in the pantry
+    ///
+    /// Recipes with no ingredient on hand are left out. Ties are broken by fewer missing
+    /// ingredients, then by name.
+    pub fn cookable_recipes(&self) -> Vec<CookableRecipe> {
+        let synonyms = self.synonyms();
+        let mut cookable: Vec<CookableRecipe> = self
+            .cache
+            .get_all()
             .into_iter()
-            .map(|cached| {
-                let file_name = self.extract_filename_from_path(&cached.git_path);
-                Recipe {
-                    git_path: cached.git_path,
-                    file_name,
-                    name: cached.name,
-                    description: cached.description,
-                    category: cached.category,
-                    content: String::new(),
+            .filter_map(|cached| {
+                let (on_hand, missing): (Vec<String>, Vec<String>) =
+                    ingredient_names(&cached).into_iter().partition(|name| {
+                        synonyms
+                            .names(name)
+                            .iter()
+                            .any(|name| self.pantry.has(name))
+                    });
+                if on_hand.is_empty() {
+                    return None;
                 }
+                let recipe = self.summary_from_cache(&cached.git_path)?;
+                Some(CookableRecipe {
+                    recipe,
+                    on_hand,
+                    missing,
+                })
+            })
+            .collect();
+
+        cookable.sort_by(|a, b| {
+            b.coverage()
+                .total_cmp(&a.coverage())
+                .then(a.missing.len().cmp(&b.missing.len()))
+                .then_with(|| a.recipe.name.cmp(&b.recipe.name))
+        });
+        cookable
+    }
+
+    /// Parser for recipe content, using the custom units if a units file is loaded
+    fn parser(&self) -> Arc<RecipeParser> {
+        self.parser
+            .read()
+            .map(|parser| parser.clone())
+            .unwrap_or_default()
+    }
+
+    fn parse_recipe(&self, content: &str, name: &str) -> Result<ScalableRecipe, String> {
+        self.parser().parse(content, name)
+    }
+
+    /// Re-read the units file and use it for parsing from now on
+    ///
+    /// Returns whether custom units are in use (`false` when there is no units file).
+    /// An invalid file is an error and leaves the current units in place. Recipes already
+    /// in the index keep their old parse until they are reindexed.
+    pub fn reload_units(&self) -> Result<bool> {
+        let parser = match self.storage.read_file(UNITS_FILE) {
+            Ok(units) => RecipeParser::with_units(&units)?,
+            Err(_) => RecipeParser::bundled(),
+        };
+        let custom = parser.units_hash().is_some();
+
+        if let Ok(mut current) = self.parser.write() {
+            *current = Arc::new(parser);
+        }
+        Ok(custom)
+    }
+
+    /// Load the units file at startup, falling back to the bundled units if it is invalid
+    fn load_units(&self) {
+        if let Err(e) = self.reload_units() {
+            tracing::warn!("Ignoring units file {}: {:#}", UNITS_FILE, e);
+        }
+    }
+
+    /// Ingredient synonyms in use (empty without a synonyms file)
+    pub fn synonyms(&self) -> Arc<Synonyms> {
+        self.synonyms
+            .read()
+            .map(|synonyms| synonyms.clone())
+            .unwrap_or_default()
+    }
+
+    /// Re-read the synonyms file and match ingredients by it from now on
+    ///
+    /// Returns the number of synonym groups (0 when there is no synonyms file). An
+    /// invalid file is an error and leaves the current synonyms in place.
+    pub fn reload_synonyms(&self) -> Result<usize> {
+        let synonyms = match self.storage.read_file(SYNONYMS_FILE) {
+            Ok(text) => Synonyms::parse(&text)?,
+            Err(_) => Synonyms::default(),
+        };
+        let groups = synonyms.len();
+
+        if let Ok(mut current) = self.synonyms.write() {
+            *current = Arc::new(synonyms);
+        }
+        Ok(groups)
+    }
+
+    /// Switch to the settings of `fresh` that can change while the server runs
+    ///
+    /// Write hooks are rebuilt, webhook deliveries started from now on use the new retry
+    /// settings and `RUST_LOG` takes effect at once. Storage, tokens, routes and the
+    /// other settings read at startup keep their values until a restart.
+    pub fn reload_config(&self, fresh: StoreConfig) -> ConfigReload {
+        let (config, reload) = self.config().reloaded(&fresh);
+        if !reload.changed.is_empty() {
+            if let Ok(mut hooks) = self.configured_hooks.write() {
+                *hooks = crate::hooks::from_config(&config);
+            }
+            self.webhooks
+                .set_delivery_settings(DeliverySettings::from_config(&config));
+            if reload.changed.contains(&"RUST_LOG") {
+                crate::config::reload_log_filter(config.log_filter.as_deref().unwrap_or("info"));
+            }
+            // Rendered recipes depend on the fraction settings
+            self.derived.clear();
+            tracing::info!("Reloaded configuration: {}", reload.changed.join(", "));
+        }
+        if reload.restart_required {
+            tracing::warn!("Some changed settings only take effect after a restart");
+        }
+        if let Ok(mut current) = self.config.write() {
+            *current = Arc::new(config);
+        }
+        reload
+    }
+
+    /// Load the synonyms file at startup, ignoring it if it is invalid
+    fn load_synonyms(&self) {
+        if let Err(e) = self.reload_synonyms() {
+            tracing::warn!("Ignoring synonyms file {}: {:#}", SYNONYMS_FILE, e);
+        }
+    }
+
+    /// Whether share links can be created (a signing secret is configured)
+    pub fn sharing_enabled(&self) -> bool {
+        self.shares.enabled()
+    }
+
+    /// Create a link to a recipe valid for `valid_for`, returning the share and its token
+    pub async fn share_recipe(
+        &self,
+        git_path: &str,
+        valid_for: chrono::Duration,
+        author: Option<&str>,
+    ) -> Result<(Share, String)> {
+        let cached = self
+            .cache
+            .get(git_path)
+            .ok_or_else(|| anyhow!("Recipe not found: {}", git_path))?;
+
+        let (share, token) = self.shares.create(git_path, valid_for, author)?;
+        let commit = self.commit_info("Share", git_path, &cached.name, author, None);
+        self.save_shares_with_commit(commit).await?;
+        Ok((share, token))
+    }
+
+    /// Active share links of a recipe with their tokens, oldest first
+    pub fn recipe_shares(&self, git_path: &str) -> Result<Vec<(Share, String)>> {
+        self.shares
+            .for_recipe(git_path)
+            .into_iter()
+            .map(|share| {
+                let token = self.shares.token(&share)?;
+                Ok((share, token))
             })
             .collect()
     }
 
-    /// Get all categories
-    pub fn get_categories(&self) -> Vec<String> {
-        self.cache.get_categories()
+    /// Revoke a share link, returning it if it existed
+    pub async fn revoke_share(
+        &self,
+        share_id: &str,
+        author: Option<&str>,
+    ) -> Result<Option<Share>> {
+        let Some(share) = self.shares.revoke(share_id) else {
+            return Ok(None);
+        };
+
+        let title = self
+            .cache
+            .get(&share.path)
+            .map(|cached| cached.name.clone())
+            .unwrap_or_else(|| share.path.clone());
+        let commit = self.commit_info("Unshare", &share.path, &title, author, None);
+        self.save_shares_with_commit(commit).await?;
+        Ok(Some(share))
+    }
+
+    /// The share a token grants access to, if it is genuine, unexpired and not revoked
+    pub fn resolve_share(&self, token: &str) -> Option<Share> {
+        self.shares.resolve(token)
+    }
+
+    /// Load persisted share links
+    fn load_shares(&self) {
+        let Ok(json) = self.storage.read_file(SHARES_FILE) else {
+            return;
+        };
+
+        if let Err(e) = self.shares.load(&json) {
+            tracing::warn!("Failed to load share links: {}", e);
+        }
+    }
+
+    async fn save_shares(&self) -> Result<()> {
+        let json = self.shares.to_json()?;
+        self.on_storage(move |storage| storage.write_file(SHARES_FILE, &json))
+            .await
+    }
+
+    async fn save_shares_with_commit(&self, commit: CommitInfo) -> Result<()> {
+        let json = self.shares.to_json()?;
+        self.on_storage(move |storage| storage.write_file_with_commit(SHARES_FILE, &json, &commit))
+            .await
+    }
+
+    /// Registered webhooks and their delivery log
+    pub fn webhooks(&self) -> &Arc<Webhooks> {
+        &self.webhooks
+    }
+
+    /// Recipe changes published by this repository
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+
+    /// How long storage operations have taken, and how many failed
+    pub fn storage_metrics(&self) -> &StorageMetrics {
+        &self.storage_metrics
+    }
+
+    /// Rebuilds of the recipe index and files that failed to parse
+    pub fn index_metrics(&self) -> &IndexMetrics {
+        &self.index_metrics
+    }
+
+    /// Rough number of bytes the recipe index takes up
+    pub fn index_size(&self) -> usize {
+        self.cache.approximate_size()
+    }
+
+    /// Bring the cache, search index and derived outputs in line with a recipe change,
+    /// then publish it on the event bus
+    ///
+    /// `indexed` is the recipe's new cache entry and content, unless it was deleted or
+    /// already indexed. The index is kept here rather than by a bus listener so a read
+    /// right after a write sees the change; webhooks and event streams listen on the bus.
+    fn publish(&self, event: RepositoryEvent, indexed: Option<(CachedRecipe, &str)>) {
+        let gone = match event.event {
+            EventKind::RecipeDeleted => Some(event.path.as_str()),
+            _ => event.previous_path.as_deref(),
+        };
+        if let Some(git_path) = gone {
+            self.cache.remove(git_path);
+            self.remove_from_search(git_path);
+            self.derived.invalidate(git_path);
+        }
+        self.derived.invalidate(&event.path);
+        if let Some((cached, content)) = indexed {
+            self.index_for_search(&cached, content);
+            self.cache.insert(cached.git_path.clone(), cached);
+        }
+        self.events.publish(event);
+    }
+
+    /// [`publish`](Self::publish) a change to a recipe [`index_file`](Self::index_file)
+    /// has just indexed
+    fn publish_indexed(
+        &self,
+        event: EventKind,
+        git_path: &str,
+        previous_path: Option<&str>,
+        author: Option<&str>,
+    ) {
+        // A file that no longer parses isn't cached; describe it by its path
+        let (name, category) = match self.cache.get(git_path) {
+            Some(cached) => (cached.name, cached.category),
+            None => (
+                self.path_to_name(git_path),
+                self.extract_category_from_path(git_path),
+            ),
+        };
+        self.publish(
+            RepositoryEvent::new(event, git_path, &name, category.as_deref(), author)
+                .moved_from(previous_path),
+            None,
+        );
+    }
+
+    fn load_webhooks(&self) {
+        if let Err(e) = self.webhooks.load() {
+            tracing::warn!("Failed to load webhooks: {}", e);
+        }
+    }
+
+    /// Load the persisted pantry
+    fn load_shopping_lists(&self) {
+        let Ok(json) = self.storage.read_file(SHOPPING_LISTS_FILE) else {
+            return;
+        };
+
+        if let Err(e) = self.shopping_lists.load(&json) {
+            tracing::warn!("Failed to load shopping lists: {}", e);
+        }
+    }
+
+    fn load_pantry(&self) {
+        let Ok(json) = self.storage.read_file(PANTRY_FILE) else {
+            return;
+        };
+
+        if let Err(e) = self.pantry.load(&json) {
+            tracing::warn!("Failed to load pantry: {}", e);
+        }
+    }
+
+    /// Build the breadcrumb trail for a category path, one entry per ancestor segment
+    pub fn category_breadcrumb(&self, category: Option<&str>) -> Vec<CategoryCrumb> {
+        let Some(category) = category else {
+            return Vec::new();
+        };
+
+        let mut crumbs = Vec::new();
+        let mut path = String::new();
+        for segment in category.split('/').filter(|s| !s.is_empty()) {
+            if !path.is_empty() {
+                path.push('/');
+            }
+            path.push_str(segment);
+            crumbs.push(CategoryCrumb {
+                name: segment.to_string(),
+                path: path.clone(),
+                recipe_count: self.cache.count_in_category_tree(&path),
+            });
+        }
+        crumbs
     }
 
     /// Get git_path by recipe_id
@@ -424,17 +3468,42 @@ impl RecipeRepository {
         self.cache.get_git_path(recipe_id)
     }
 
+    /// Add or replace a recipe in the search provider (derived state, so failures are logged)
+    fn index_for_search(&self, cached: &CachedRecipe, content: &str) {
+        let doc = SearchDocument {
+            git_path: cached.git_path.clone(),
+            name: cached.name.clone(),
+            ingredients: cached
+                .recipe
+                .ingredients
+                .iter()
+                .map(|ingredient| ingredient.name.clone())
+                .collect(),
+            steps: extract_step_texts(content),
+        };
+        if let Err(e) = self.search.index(doc) {
+            tracing::warn!(
+                "Failed to index recipe {} for search: {}",
+                cached.git_path,
+                e
+            );
+        }
+    }
+
+    /// Remove a recipe from the search provider (derived state, so failures are logged)
+    fn remove_from_search(&self, git_path: &str) {
+        if let Err(e) = self.search.delete(git_path) {
+            tracing::warn!("Failed to remove recipe {} from search: {}", git_path, e);
+        }
+    }
+
     /// Generate a git path from a filename and category
     async fn generate_git_path_from_filename(
         &self,
         filename: &str,
         category: Option<&str>,
     ) -> Result<String> {
-        let mut path = if let Some(cat) = category {
-            format!("recipes/{}/{}", cat, filename)
-        } else {
-            format!("recipes/{}", filename)
-        };
+        let mut path = self.recipe_path(category, filename);
 
         // Check for duplicates and append numeric suffix if needed
         let mut counter = 2;
@@ -442,17 +3511,26 @@ impl RecipeRepository {
             // Insert counter before .cook extension
             let base = filename.strip_suffix(".cook").unwrap_or(filename);
             let new_filename = format!("{}-{}.cook", base, counter);
-            path = if let Some(cat) = category {
-                format!("recipes/{}/{}", cat, new_filename)
-            } else {
-                format!("recipes/{}", new_filename)
-            };
+            path = self.recipe_path(category, &new_filename);
             counter += 1;
         }
 
         Ok(path)
     }
 
+    /// Git path of `filename` in `category` (or at the top of the recipes directory)
+    fn recipe_path(&self, category: Option<&str>, filename: &str) -> String {
+        [
+            self.config().recipes_dir.as_str(),
+            category.unwrap_or(""),
+            filename,
+        ]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+    }
+
     /// Extract filename from a git path
     fn extract_filename_from_path(&self, git_path: &str) -> String {
         git_path.split('/').next_back().unwrap_or("").to_string()
@@ -462,19 +3540,17 @@ impl RecipeRepository {
     /// Categories support hierarchical nesting: recipes/meals/meat/traditional/chicken-biryani.cook
     /// would have category "meals/meat/traditional"
     fn extract_category_from_path(&self, git_path: &str) -> Option<String> {
-        // Expected: recipes/{category/path}/{slug}.cook
-        let parts: Vec<&str> = git_path.split('/').collect();
-        if parts.len() >= 3 && parts[0] == "recipes" {
-            // All parts between "recipes/" and the filename form the category path
-            let category_parts = &parts[1..parts.len() - 1];
-            if !category_parts.is_empty() {
-                Some(category_parts.join("/"))
-            } else {
-                None
-            }
+        // Expected: {recipes dir}/{category/path}/{slug}.cook
+        let dir = &self.config().recipes_dir;
+        let relative = if dir.is_empty() {
+            git_path
         } else {
-            None
-        }
+            git_path.strip_prefix(dir.as_str())?.strip_prefix('/')?
+        };
+
+        // All parts between the recipes directory and the filename form the category path
+        let (category, _filename) = relative.rsplit_once('/')?;
+        (!category.is_empty()).then(|| category.to_string())
     }
 
     /// Convert git path to recipe name
@@ -483,7 +3559,7 @@ impl RecipeRepository {
         git_path
             .split('/')
             .next_back()
-            .and_then(|f| f.strip_suffix(".cook"))
+            .and_then(|f| f.strip_suffix(".cook").or_else(|| f.strip_suffix(".menu")))
             .unwrap_or("")
             .replace('-', " ")
             .split_whitespace()
@@ -499,6 +3575,106 @@ impl RecipeRepository {
     }
 }
 
+/// Index entry for a recipe file that just parsed
+fn cached_entry(
+    git_path: &str,
+    name: &str,
+    category: Option<String>,
+    content: &str,
+    parsed: ScalableRecipe,
+    stat: Option<FileStat>,
+) -> CachedRecipe {
+    CachedRecipe {
+        recipe_id: generate_recipe_id(git_path),
+        git_path: git_path.to_string(),
+        name: name.to_string(),
+        description: None,
+        category,
+        custom_fields: extract_custom_fields(content),
+        metadata: extract_metadata(content),
+        total_minutes: extract_total_minutes(content),
+        active_minutes: extract_active_minutes(content),
+        content_hash: content_hash(content),
+        last_modified: stat.as_ref().map(FileStat::last_modified),
+        stat,
+        recipe: parsed,
+    }
+}
+
+/// Move a recipe's notes sidecar along with the recipe, if it has one
+fn move_notes(
+    storage: &dyn RecipeStorage,
+    from: &str,
+    to: &str,
+    commit: &CommitInfo,
+) -> Result<()> {
+    let from = notes_path(from);
+    match storage.read_file(&from) {
+        Ok(yaml) => storage.rename_file(&from, &notes_path(to), &yaml, commit),
+        Err(_) => Ok(()),
+    }
+}
+
+/// Delete a recipe's notes sidecar, if it has one
+fn delete_notes(storage: &dyn RecipeStorage, git_path: &str, commit: &CommitInfo) -> Result<()> {
+    let path = notes_path(git_path);
+    if storage.read_file(&path).is_ok() {
+        storage.delete_file_with_commit(&path, commit)?;
+    }
+    Ok(())
+}
+
+/// `tags` without those in `remove` and with those in `add` appended (ignoring case)
+fn retagged(tags: &[String], add: &[String], remove: &[String]) -> Vec<String> {
+    let mut result: Vec<String> = tags
+        .iter()
+        .filter(|tag| !remove.iter().any(|r| r.trim().eq_ignore_ascii_case(tag)))
+        .cloned()
+        .collect();
+    for tag in add
+        .iter()
+        .map(|tag| tag.trim())
+        .filter(|tag| !tag.is_empty())
+    {
+        if !result.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            result.push(tag.to_string());
+        }
+    }
+    result
+}
+
+/// Fill a commit message template
+///
+/// A comment is appended as the commit body when the template has no `{comment}`
+/// placeholder, so it is never silently dropped.
+fn render_commit_message(
+    template: &str,
+    action: &str,
+    title: &str,
+    path: &str,
+    author: Option<&str>,
+    comment: Option<&str>,
+) -> String {
+    let comment = comment.map(str::trim).filter(|c| !c.is_empty());
+
+    let mut message = template
+        .replace("{action}", action)
+        .replace("{title}", title)
+        .replace("{path}", path)
+        .replace("{author}", author.unwrap_or(""))
+        .replace("{comment}", comment.unwrap_or(""))
+        .trim()
+        .to_string();
+
+    if let Some(comment) = comment {
+        if !template.contains("{comment}") {
+            message = format!("{}\n\n{}", message, comment);
+        }
+    }
+
+    message
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -549,7 +3725,7 @@ mod tests {
         assert_eq!(read.name, created.name);
         assert_eq!(read.git_path, created.git_path);
         assert_eq!(read.file_name, created.file_name);
-        assert_eq!(read.content, content);
+        assert_eq!(read.content.as_deref(), Some(content));
 
         Ok(())
     }
@@ -611,7 +3787,7 @@ mod tests {
             .await?;
 
         let updated = repo.read(&recipe.git_path).await?;
-        assert_eq!(updated.content, new_content);
+        assert_eq!(updated.content.as_deref(), Some(new_content));
 
         Ok(())
     }
@@ -672,7 +3848,7 @@ mod tests {
         .await?;
 
         let updated = repo.read(&recipe.git_path).await?;
-        assert_eq!(updated.content, new_content);
+        assert_eq!(updated.content.as_deref(), Some(new_content));
 
         Ok(())
     }
@@ -695,7 +3871,7 @@ mod tests {
         .await?;
 
         let updated = repo.read(&recipe.git_path).await?;
-        assert_eq!(updated.content, new_content);
+        assert_eq!(updated.content.as_deref(), Some(new_content));
 
         Ok(())
     }
@@ -779,7 +3955,7 @@ mod tests {
         let all = repo.list_all();
         let updated = all.iter().find(|r| r.name == "New Name").unwrap();
         let recipe = repo.read(&updated.git_path).await?;
-        assert_eq!(recipe.content, new_content);
+        assert_eq!(recipe.content.as_deref(), Some(new_content));
 
         Ok(())
     }
@@ -804,7 +3980,7 @@ mod tests {
         let mains = repo.list_by_category("mains");
         assert_eq!(mains.len(), 1);
         let recipe = repo.read(&mains[0].git_path).await?;
-        assert_eq!(recipe.content, new_content);
+        assert_eq!(recipe.content.as_deref(), Some(new_content));
 
         Ok(())
     }
@@ -832,7 +4008,7 @@ mod tests {
         assert_eq!(mains.len(), 1);
         assert_eq!(mains[0].name, "New Name");
         let recipe = repo.read(&mains[0].git_path).await?;
-        assert_eq!(recipe.content, new_content);
+        assert_eq!(recipe.content.as_deref(), Some(new_content));
 
         Ok(())
     }
@@ -882,6 +4058,19 @@ mod tests {
         let results = repo.search_by_name("cake");
         assert_eq!(results.len(), 2);
 
+        // Full search also looks at ingredients and steps, name matches first
+        repo.create(
+            "Trifle",
+            "---\ntitle: Trifle\n---\n\nLayer @sponge{} with crumbled cake.",
+            Some("desserts"),
+        )
+        .await?;
+        let results = repo.search("cake");
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[2].recipe.name, "Trifle");
+        assert!(results[0].score > results[2].score);
+        assert_eq!(repo.search_by_name("cake").len(), 2);
+
         Ok(())
     }
 
@@ -917,6 +4106,304 @@ mod tests {
         Ok(())
     }
 
+    async fn setup_trash_test_repo(storage_type: &str) -> Result<(RecipeRepository, TempDir)> {
+        let dir = TempDir::new()?;
+        let config = StoreConfig {
+            trash_enabled: true,
+            ..StoreConfig::default()
+        };
+        let repo =
+            RecipeRepository::with_config(dir.path(), storage_type, "memory", config).await?;
+        Ok((repo, dir))
+    }
+
+    #[tokio::test]
+    async fn test_delete_moves_to_trash_and_restore() -> Result<()> {
+        for storage_type in ["disk", "git"] {
+            let (repo, _dir) = setup_trash_test_repo(storage_type).await?;
+
+            let recipe = repo
+                .create("Cake", "---\ntitle: Cake\n---\n\nBake.", Some("desserts"))
+                .await?;
+            repo.delete(&recipe.git_path).await?;
+
+            assert!(repo.list_all().is_empty());
+            let trash = repo.list_trash()?;
+            assert_eq!(trash.len(), 1);
+            assert_eq!(trash[0].original_path, recipe.git_path);
+            assert_eq!(trash[0].name, "Cake");
+
+            // Trashed files are not picked up again by discovery
+            repo.rebuild_from_storage().await?;
+            assert!(repo.list_all().is_empty());
+
+            let restored = repo.restore_from_trash(&trash[0].trash_id).await?;
+            assert_eq!(restored.git_path, recipe.git_path);
+            assert_eq!(restored.category, Some("desserts".to_string()));
+            assert_eq!(repo.list_all().len(), 1);
+            assert!(repo.list_trash()?.is_empty());
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_notes_follow_recipe_through_move_and_trash() -> Result<()> {
+        for storage_type in ["disk", "git"] {
+            let (repo, _dir) = setup_trash_test_repo(storage_type).await?;
+
+            let recipe = repo
+                .create("Cake", "---\ntitle: Cake\n---\n\nBake.", None)
+                .await?;
+            assert!(repo.notes(&recipe.git_path)?.is_empty());
+
+            repo.set_rating(&recipe.git_path, Some(4), None).await?;
+            let date = NaiveDate::from_ymd_opt(2024, 5, 1);
+            repo.add_note(&recipe.git_path, "came out dry", date, Some("Alice"))
+                .await?;
+
+            let moved = repo
+                .move_recipe(&recipe.git_path, Some("desserts"), None, None)
+                .await?;
+            let notes = repo.notes(&moved.git_path)?;
+            assert_eq!(notes.rating, Some(4));
+            assert_eq!(notes.notes[0].text, "came out dry");
+            assert_eq!(notes.notes[0].author.as_deref(), Some("Alice"));
+
+            // The sidecar is trashed with the recipe but is not an entry of its own
+            repo.delete(&moved.git_path).await?;
+            let trash = repo.list_trash()?;
+            assert_eq!(trash.len(), 1);
+
+            repo.restore_from_trash(&trash[0].trash_id).await?;
+            assert_eq!(repo.notes(&moved.git_path)?.rating, Some(4));
+
+            // Clearing everything removes the sidecar
+            repo.set_rating(&moved.git_path, None, None).await?;
+            assert!(repo.remove_note(&moved.git_path, 1, None).await?.is_none());
+            repo.remove_note(&moved.git_path, 0, None).await?;
+            assert!(repo
+                .storage
+                .read_file(&notes_path(&moved.git_path))
+                .is_err());
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_trash_stops_at_request_deadline() -> Result<()> {
+        let (repo, _dir) = setup_trash_test_repo("disk").await?;
+
+        let recipe = repo
+            .create("Cake", "---\ntitle: Cake\n---\n\nBake.", None)
+            .await?;
+        repo.delete(&recipe.git_path).await?;
+
+        let result =
+            crate::deadline::scope(std::time::Duration::ZERO, async { repo.list_trash() }).await;
+        let err = result.unwrap_err();
+        let timed_out = err
+            .downcast_ref::<crate::deadline::OperationTimedOut>()
+            .expect("expected a deadline error");
+        assert_eq!(timed_out.processed, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_purge_expired_trash() -> Result<()> {
+        let (repo, _dir) = setup_trash_test_repo("disk").await?;
+
+        repo.storage.write_file(
+            ".trash/20000101T000000.000Z/recipes/old.cook",
+            "---\ntitle: Old\n---\n",
+        )?;
+        let recipe = repo
+            .create("Fresh", "---\ntitle: Fresh\n---\n\nCook.", None)
+            .await?;
+        repo.delete(&recipe.git_path).await?;
+
+        assert_eq!(repo.list_trash()?.len(), 2);
+        assert_eq!(repo.purge_expired_trash()?, 1);
+
+        let trash = repo.list_trash()?;
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].name, "Fresh");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_reuses_existing_category_spelling() -> Result<()> {
+        let (repo, _temp) = setup_test_repo().await?;
+
+        repo.create("Cake", "---\ntitle: Cake\n---\n\nBake.", Some("desserts"))
+            .await?;
+        let pie = repo
+            .create(
+                "Pie",
+                "---\ntitle: Pie\n---\n\nBake.",
+                Some("/Desserts/Pies/"),
+            )
+            .await?;
+
+        assert_eq!(pie.category, Some("desserts/Pies".to_string()));
+        assert_eq!(pie.git_path, "recipes/desserts/Pies/pie.cook");
+        assert_eq!(
+            repo.resolve_category("DESSERTS/"),
+            Some("desserts".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_case_sensitive_paths_when_disabled() -> Result<()> {
+        let dir = TempDir::new()?;
+        let config = StoreConfig {
+            case_insensitive_paths: false,
+            ..StoreConfig::default()
+        };
+        let repo = RecipeRepository::with_config(dir.path(), "disk", "memory", config).await?;
+
+        repo.create("Cake", "---\ntitle: Cake\n---\n\nBake.", Some("desserts"))
+            .await?;
+        let pie = repo
+            .create("Pie", "---\ntitle: Pie\n---\n\nBake.", Some("Desserts/"))
+            .await?;
+
+        assert_eq!(pie.category, Some("Desserts".to_string()));
+        assert_eq!(repo.resolve_category("DESSERTS"), None);
+        assert_eq!(
+            repo.resolve_category("desserts/"),
+            Some("desserts".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_category_aliases_persist_and_resolve() -> Result<()> {
+        let dir = TempDir::new()?;
+        let repo = RecipeRepository::new(dir.path()).await?;
+
+        let recipe = repo
+            .create("Cake", "---\ntitle: Cake\n---\n\nBake.", Some("desserts"))
+            .await?;
+        repo.record_category_alias("sweets", "desserts").await?;
+
+        // Reload from storage
+        let repo = RecipeRepository::new(dir.path()).await?;
+        assert_eq!(
+            repo.resolve_category_alias("sweets/"),
+            Some("desserts".to_string())
+        );
+
+        let old_id = generate_recipe_id("recipes/sweets/cake.cook");
+        let new_id = generate_recipe_id(&recipe.git_path);
+        assert_eq!(repo.resolve_recipe_alias(&old_id), Some(new_id));
+        assert_eq!(repo.resolve_recipe_alias("unknown"), None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_favorites_persist_and_follow_moves() -> Result<()> {
+        let dir = TempDir::new()?;
+        let repo = RecipeRepository::with_storage(dir.path(), "git").await?;
+
+        let recipe = repo
+            .create("Cake", "---\ntitle: Cake\n---\n\nBake.", None)
+            .await?;
+        repo.set_favorite(&recipe.git_path, Some("alice"), true)
+            .await?;
+        assert!(repo
+            .set_favorite("recipes/missing.cook", None, true)
+            .await
+            .is_err());
+
+        let moved = repo
+            .move_recipe(&recipe.git_path, Some("desserts"), None, None)
+            .await?;
+
+        // Reload from storage
+        let repo = RecipeRepository::with_storage(dir.path(), "git").await?;
+        assert!(repo.is_favorite(&moved.git_path, Some("alice")));
+        assert!(!repo.is_favorite(&moved.git_path, None));
+
+        repo.delete(&moved.git_path).await?;
+        let repo = RecipeRepository::with_storage(dir.path(), "git").await?;
+        assert!(!repo.is_favorite(&moved.git_path, Some("alice")));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_menus_resolve_recipe_references() -> Result<()> {
+        let dir = TempDir::new()?;
+        let repo = RecipeRepository::with_storage(dir.path(), "disk").await?;
+
+        let pancakes = repo
+            .create(
+                "Easy Pancakes",
+                "---\ntitle: Easy Pancakes\n---\n\nMix.",
+                Some("breakfast"),
+            )
+            .await?;
+        std::fs::create_dir_all(dir.path().join("menus"))?;
+        std::fs::write(
+            dir.path().join("menus/week.menu"),
+            "== Monday ==\n- @../recipes/breakfast/Easy Pancakes{2}\n- @./missing{}\n",
+        )?;
+        repo.reindex(true).await?;
+
+        let menus = repo.list_menus();
+        assert_eq!(menus.len(), 1);
+        let (menu_id, menu) = &menus[0];
+        assert_eq!(repo.menu_name(menu), "Week");
+        assert_eq!(repo.get_menu(menu_id).as_ref(), Some(menu));
+
+        let items = &menu.sections[0].items;
+        assert_eq!(
+            repo.resolve_menu_reference(&menu.git_path, &items[0].reference)
+                .map(|recipe| recipe.git_path),
+            Some(pancakes.git_path)
+        );
+        assert!(repo
+            .resolve_menu_reference(&menu.git_path, &items[1].reference)
+            .is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_category_breadcrumb() -> Result<()> {
+        let (repo, _temp) = setup_test_repo().await?;
+
+        repo.create(
+            "Biryani",
+            "---\ntitle: Biryani\n---\n\nCook.",
+            Some("meals/meat"),
+        )
+        .await?;
+        repo.create("Dal", "---\ntitle: Dal\n---\n\nCook.", Some("meals"))
+            .await?;
+
+        let crumbs = repo.category_breadcrumb(Some("meals/meat"));
+        assert_eq!(crumbs.len(), 2);
+        assert_eq!(crumbs[0].name, "meals");
+        assert_eq!(crumbs[0].path, "meals");
+        assert_eq!(crumbs[0].recipe_count, 2);
+        assert_eq!(crumbs[1].name, "meat");
+        assert_eq!(crumbs[1].path, "meals/meat");
+        assert_eq!(crumbs[1].recipe_count, 1);
+
+        assert!(repo.category_breadcrumb(None).is_empty());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_extract_category_from_path() -> Result<()> {
         let temp_dir = TempDir::new()?;
@@ -1083,7 +4570,7 @@ mod tests {
         // Verify new path works
         let read = repo.read(&updated.git_path).await?;
         assert_eq!(read.name, "Dark Chocolate Cake");
-        assert_eq!(read.content, new_content);
+        assert_eq!(read.content.as_deref(), Some(new_content));
 
         Ok(())
     }
@@ -1160,7 +4647,7 @@ mod tests {
         // Verify new path works
         let read = repo.read(&updated.git_path).await?;
         assert_eq!(read.name, "Spaghetti Carbonara");
-        assert_eq!(read.content, new_content);
+        assert_eq!(read.content.as_deref(), Some(new_content));
         assert_eq!(read.category, Some("italian".to_string()));
 
         Ok(())
@@ -1204,6 +4691,228 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_move_recipe_to_root() -> Result<()> {
+        let (repo, _git) = setup_test_repo().await?;
+
+        let content = "---\ntitle: Flan\n---\n\nCaramel custard.";
+        let recipe = repo.create("Flan", content, Some("desserts")).await?;
+
+        let moved = repo.move_recipe(&recipe.git_path, None, None, None).await?;
+
+        assert_eq!(moved.category, None);
+        assert_eq!(moved.git_path, "recipes/flan.cook");
+        assert_eq!(moved.content.as_deref(), Some(content));
+        assert!(repo.read(&recipe.git_path).await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_copy_recipe_adds_numeric_suffix_on_collision() -> Result<()> {
+        let (repo, _git) = setup_test_repo().await?;
+
+        let content = "---\ntitle: Flan\n---\n\nCaramel custard.";
+        let recipe = repo.create("Flan", content, Some("desserts")).await?;
+
+        let copy = repo
+            .copy_recipe(&recipe.git_path, Some("desserts"), None, None)
+            .await?;
+
+        assert_eq!(copy.git_path, "recipes/desserts/flan-2.cook");
+        assert_eq!(copy.file_name, "flan-2.cook");
+        assert_eq!(copy.name, "Flan");
+        assert_eq!(copy.content.as_deref(), Some(content));
+
+        // Original is untouched
+        let original = repo.read(&recipe.git_path).await?;
+        assert_eq!(original.content.as_deref(), Some(content));
+        assert_eq!(repo.list_by_category("desserts").len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_commit_message() {
+        let default = crate::config::DEFAULT_COMMIT_MESSAGE_TEMPLATE;
+        assert_eq!(
+            render_commit_message(default, "Update", "Cake", "recipes/cake.cook", None, None),
+            "Update recipe: recipes/cake.cook"
+        );
+        assert_eq!(
+            render_commit_message(
+                default,
+                "Create",
+                "Cake",
+                "recipes/cake.cook",
+                Some("Alice"),
+                Some("Grandma's version")
+            ),
+            "Create recipe: recipes/cake.cook\n\nGrandma's version"
+        );
+        assert_eq!(
+            render_commit_message(
+                "{action} {title} ({author}): {comment}",
+                "Update",
+                "Cake",
+                "recipes/cake.cook",
+                Some("Alice"),
+                Some("less sugar")
+            ),
+            "Update Cake (Alice): less sugar"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_commit_message_uses_template_and_comment() -> Result<()> {
+        let git_dir = TempDir::new()?;
+        let config = StoreConfig {
+            commit_message_template: "{action}: {title}".to_string(),
+            ..Default::default()
+        };
+        let repo = RecipeRepository::with_config(git_dir.path(), "git", "memory", config).await?;
+
+        let content = "---\ntitle: Test Cake\n---\n\nMix @flour{200%g}.";
+        let recipe = repo
+            .create_with_author_and_comment("", content, None, Some("Alice"), Some("From Grandma"))
+            .await?;
+        let moved = repo
+            .move_recipe(&recipe.git_path, Some("desserts"), None, None)
+            .await?;
+        repo.copy_recipe(&moved.git_path, None, Some("Bob"), Some("For the party"))
+            .await?;
+
+        let git = git2::Repository::open(git_dir.path())?;
+        let mut revwalk = git.revwalk()?;
+        revwalk.push_head()?;
+        let messages: Vec<String> = revwalk
+            .map(|oid| {
+                let commit = git.find_commit(oid.unwrap()).unwrap();
+                commit.message().unwrap().to_string()
+            })
+            .collect();
+
+        assert_eq!(
+            messages,
+            vec![
+                "Copy: Test Cake\n\nFor the party",
+                "Move: Test Cake",
+                "Create: Test Cake\n\nFrom Grandma"
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_import_stops_at_deadline() -> Result<()> {
+        let (repo, _dir) = setup_test_repo().await?;
+        let recipes = vec![(
+            "soup.json".to_string(),
+            Ok(ImportedRecipe {
+                title: "Soup".to_string(),
+                ..Default::default()
+            }),
+        )];
+
+        let err = crate::deadline::scope(
+            Duration::ZERO,
+            repo.import_recipes(recipes, "Paprika", None),
+        )
+        .await
+        .unwrap_err();
+        let timed_out = err
+            .downcast_ref::<crate::deadline::OperationTimedOut>()
+            .unwrap();
+        assert_eq!((timed_out.processed, timed_out.total), (0, Some(1)));
+        assert_eq!(repo.recipe_count(), 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_check_mirror_stops_at_deadline() -> Result<()> {
+        let (data_dir, mirror_dir) = (TempDir::new()?, TempDir::new()?);
+        let config = StoreConfig {
+            mirror_path: Some(mirror_dir.path().to_string_lossy().into_owned()),
+            ..Default::default()
+        };
+        let repo = RecipeRepository::with_config(data_dir.path(), "disk", "memory", config).await?;
+        repo.create("Toast", "---\ntitle: Toast\n---\n\nToast.", None)
+            .await?;
+
+        let err = crate::deadline::scope(Duration::ZERO, repo.check_mirror())
+            .await
+            .unwrap_err();
+        assert!(err
+            .downcast_ref::<crate::deadline::OperationTimedOut>()
+            .is_some());
+        assert!(repo.check_mirror().await?.is_some());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_commit_author_identity() -> Result<()> {
+        let git_dir = TempDir::new()?;
+        let config = StoreConfig {
+            commit_author_name: "Recipe Bot".to_string(),
+            commit_author_email: "bot@example.com".to_string(),
+            commit_author_emails: [("Alice".to_string(), "alice@example.com".to_string())]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+        let repo = RecipeRepository::with_config(git_dir.path(), "git", "memory", config).await?;
+        let git = git2::Repository::open(git_dir.path())?;
+        let head_author = || {
+            let commit = git.head().unwrap().peel_to_commit().unwrap();
+            let author = commit.author();
+            (
+                author.name().unwrap().to_string(),
+                author.email().unwrap().to_string(),
+            )
+        };
+
+        let content = "---\ntitle: Test Cake\n---\n\nMix @flour{200%g}.";
+        let recipe = repo
+            .create_with_author("", content, None, Some("alice"))
+            .await?;
+        assert_eq!(head_author(), ("alice".into(), "alice@example.com".into()));
+
+        repo.update_with_author(&recipe.git_path, None, Some(content), None, Some("Bob"))
+            .await?;
+        assert_eq!(head_author(), ("Bob".into(), "bot@example.com".into()));
+
+        repo.delete(&recipe.git_path).await?;
+        assert_eq!(
+            head_author(),
+            ("Recipe Bot".into(), "bot@example.com".into())
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_derived_output_recomputed_after_update() -> Result<()> {
+        let (repo, _dir) = setup_test_repo().await?;
+        let content = "---\ntitle: Test Cake\n---\n\nMix @flour{200%g}.";
+        let recipe = repo.create("", content, None).await?;
+
+        let length = |content: &str| Ok(content.len().to_string().into_bytes());
+        let before = repo.derived_output(&recipe.git_path, "length", length)?;
+        let cached = repo.derived_output(&recipe.git_path, "length", |_| unreachable!())?;
+        assert_eq!(before, cached);
+
+        let updated = format!("{}\n\nBake.", content);
+        repo.update(&recipe.git_path, None, Some(&updated), None)
+            .await?;
+        let after = repo.derived_output(&recipe.git_path, "length", length)?;
+        assert_eq!(after.as_slice(), updated.len().to_string().as_bytes());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_recipe_id_changes_on_rename() -> Result<()> {
         let (repo, _git) = setup_test_repo().await?;
@@ -1247,4 +4956,30 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_reload_config() -> Result<()> {
+        let (repo, _dir) = setup_trash_test_repo("disk").await?;
+        let content = "---\ntitle: Pie\n---\n\nRub in @lard{100%g}.\n";
+
+        let fresh = StoreConfig {
+            banned_words: vec!["lard".to_string()],
+            ..StoreConfig::clone(&repo.config())
+        };
+        let reload = repo.reload_config(fresh.clone());
+        assert_eq!(reload.changed, vec!["BANNED_WORDS"]);
+        assert!(!reload.restart_required);
+        assert!(repo.create("Pie", content, None).await.is_err());
+
+        // Settings read at startup keep their value
+        let fresh = StoreConfig {
+            share_secret: Some("s3cret".to_string()),
+            ..fresh
+        };
+        let reload = repo.reload_config(fresh);
+        assert!(reload.changed.is_empty());
+        assert!(reload.restart_required);
+        assert!(repo.config().share_secret.is_none());
+        Ok(())
+    }
 }
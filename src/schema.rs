@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+
+/// Type a front matter field must have
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    /// An `http://` or `https://` URL
+    Url,
+    /// A YAML sequence, e.g. `tags: [quick, vegan]`
+    List,
+}
+
+impl FieldType {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "string" | "text" => Some(FieldType::String),
+            "integer" | "int" => Some(FieldType::Integer),
+            "number" | "float" => Some(FieldType::Number),
+            "boolean" | "bool" => Some(FieldType::Boolean),
+            "url" => Some(FieldType::Url),
+            "list" | "array" => Some(FieldType::List),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            FieldType::String => "a string",
+            FieldType::Integer => "an integer",
+            FieldType::Number => "a number",
+            FieldType::Boolean => "true or false",
+            FieldType::Url => "an http(s) URL",
+            FieldType::List => "a list",
+        }
+    }
+
+    fn matches(self, value: &serde_yaml::Value) -> bool {
+        use serde_yaml::Value;
+        match (self, value) {
+            (FieldType::String, Value::String(_)) => true,
+            (FieldType::Integer, Value::Number(number)) => number.is_i64() || number.is_u64(),
+            (FieldType::Number, Value::Number(_)) => true,
+            (FieldType::Boolean, Value::Bool(_)) => true,
+            (FieldType::Url, Value::String(url)) => {
+                let url = url.trim();
+                (url.starts_with("http://") || url.starts_with("https://"))
+                    && !url.contains(char::is_whitespace)
+            }
+            (FieldType::List, Value::Sequence(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Rule for one front matter field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldRule {
+    pub field_type: FieldType,
+    pub required: bool,
+}
+
+/// Front matter fields recipes must (or may) have, with their types
+///
+/// Keys are compared like the metadata keys: case-insensitively, with `_` or `-`
+/// standing in for spaces. Fields not in the schema are not checked.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FrontMatterSchema {
+    fields: BTreeMap<String, FieldRule>,
+}
+
+impl FrontMatterSchema {
+    /// Parse `key=type` entries separated by commas, with ` required` after the type
+    /// for mandatory fields, e.g. `servings=integer required,source=url`
+    ///
+    /// Invalid entries are logged and skipped.
+    pub fn parse(value: &str) -> Self {
+        let fields = value
+            .split(',')
+            .filter(|entry| !entry.trim().is_empty())
+            .filter_map(|entry| {
+                let rule = entry.split_once('=').and_then(|(key, spec)| {
+                    let mut words = spec.split_whitespace();
+                    let field_type = FieldType::parse(words.next()?)?;
+                    let required = match words.next() {
+                        None => false,
+                        Some(word) if word.eq_ignore_ascii_case("required") => true,
+                        Some(_) => return None,
+                    };
+                    let key = normalize_key(key);
+                    (!key.is_empty() && words.next().is_none()).then_some((
+                        key,
+                        FieldRule {
+                            field_type,
+                            required,
+                        },
+                    ))
+                });
+                if rule.is_none() {
+                    tracing::warn!("Ignoring invalid FRONT_MATTER_SCHEMA entry: {}", entry);
+                }
+                rule
+            })
+            .collect();
+        FrontMatterSchema { fields }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Problems with a recipe's front matter, by field (empty when it is valid)
+    ///
+    /// An empty value (`servings:`) counts as missing.
+    pub fn validate(&self, front_matter: &serde_yaml::Mapping) -> BTreeMap<String, String> {
+        let mut errors = BTreeMap::new();
+        for (key, rule) in &self.fields {
+            let value = front_matter
+                .iter()
+                .find(|(name, _)| {
+                    name.as_str()
+                        .is_some_and(|name| normalize_key(name) == *key)
+                })
+                .map(|(_, value)| value)
+                .filter(|value| !value.is_null());
+
+            match value {
+                None if rule.required => {
+                    errors.insert(key.clone(), "is required".to_string());
+                }
+                Some(value) if !rule.field_type.matches(value) => {
+                    errors.insert(key.clone(), format!("must be {}", rule.field_type.name()));
+                }
+                _ => {}
+            }
+        }
+        errors
+    }
+}
+
+/// Front matter fields that failed schema validation, field → problem
+#[derive(Debug, thiserror::Error)]
+#[error("Front matter does not match the schema: {}", describe(.0))]
+pub struct SchemaViolation(pub BTreeMap<String, String>);
+
+fn describe(errors: &BTreeMap<String, String>) -> String {
+    errors
+        .iter()
+        .map(|(field, problem)| format!("{} {}", field, problem))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn normalize_key(key: &str) -> String {
+    key.trim().to_lowercase().replace(['_', '-'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_front_matter() {
+        let schema = FrontMatterSchema::parse(
+            "servings=integer required, source=url, Prep_Time=string, tags=list, broken, x=color",
+        );
+        assert_eq!(schema.fields.len(), 4);
+
+        let front_matter = |yaml: &str| serde_yaml::from_str::<serde_yaml::Mapping>(yaml).unwrap();
+
+        let valid = front_matter("title: Soup\nServings: 4\nsource: https://example.com/soup\n");
+        assert!(schema.validate(&valid).is_empty());
+
+        let invalid = front_matter(
+            "title: Soup\nservings:\nsource: grandma\nprep time: 10\ntags: quick, vegan\n",
+        );
+        let errors = schema.validate(&invalid);
+        assert_eq!(errors["servings"], "is required");
+        assert_eq!(errors["source"], "must be an http(s) URL");
+        assert_eq!(errors["prep time"], "must be a string");
+        assert_eq!(errors["tags"], "must be a list");
+    }
+}
@@ -0,0 +1,380 @@
+use anyhow::Result;
+use dashmap::DashMap;
+
+/// A recipe as seen by a search provider
+#[derive(Debug, Clone)]
+pub struct SearchDocument {
+    pub git_path: String,
+    pub name: String,
+    /// Ingredient names
+    pub ingredients: Vec<String>,
+    /// Plain text of each step
+    pub steps: Vec<String>,
+}
+
+/// Part of a document a query matched
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchField {
+    Name,
+    Ingredient,
+    Step,
+}
+
+impl SearchField {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchField::Name => "name",
+            SearchField::Ingredient => "ingredient",
+            SearchField::Step => "step",
+        }
+    }
+}
+
+/// A snippet of a matched field and where the query occurs in it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Highlight {
+    pub field: SearchField,
+    pub snippet: String,
+    /// `[start, end)` character offsets of each match within `snippet`
+    pub matches: Vec<(usize, usize)>,
+}
+
+/// A document matching a query
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    pub git_path: String,
+    /// Relevance, higher is better (only comparable within one query)
+    pub score: f32,
+    pub highlights: Vec<Highlight>,
+}
+
+impl SearchHit {
+    /// Whether the recipe name itself matched
+    pub fn matched_name(&self) -> bool {
+        self.highlights
+            .iter()
+            .any(|highlight| highlight.field == SearchField::Name)
+    }
+}
+
+/// Trait for recipe search backends
+///
+/// The repository keeps the provider in sync on every mutation, so adapters for
+/// external engines (Meilisearch, Elasticsearch, ...) only need to implement these
+/// operations; handlers never talk to the provider directly.
+pub trait SearchProvider: Send + Sync {
+    /// Add a document to the index, replacing any document with the same git_path
+    fn index(&self, doc: SearchDocument) -> Result<()>;
+
+    /// Remove a document from the index
+    fn delete(&self, git_path: &str) -> Result<()>;
+
+    /// Find documents matching the query, best matches first
+    fn query(&self, query: &str) -> Result<Vec<SearchHit>>;
+
+    /// Remove all documents from the index
+    fn clear(&self) -> Result<()>;
+}
+
+/// Characters of context kept on each side of a match in step snippets
+const SNIPPET_CONTEXT: usize = 40;
+
+/// Most ingredient or step highlights returned per document
+const MAX_HIGHLIGHTS_PER_FIELD: usize = 3;
+
+/// Built-in search provider backed by an in-memory map
+///
+/// Matches the query as a case-insensitive substring of the name, ingredient names and
+/// step text. Name matches outrank ingredient matches, which outrank step matches.
+pub struct InMemorySearch {
+    // git_path -> document
+    docs: DashMap<String, SearchDocument>,
+}
+
+impl InMemorySearch {
+    /// Create a new empty in-memory search index
+    pub fn new() -> Self {
+        InMemorySearch {
+            docs: DashMap::new(),
+        }
+    }
+}
+
+impl Default for InMemorySearch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SearchProvider for InMemorySearch {
+    fn index(&self, doc: SearchDocument) -> Result<()> {
+        self.docs.insert(doc.git_path.clone(), doc);
+        Ok(())
+    }
+
+    fn delete(&self, git_path: &str) -> Result<()> {
+        self.docs.remove(git_path);
+        Ok(())
+    }
+
+    fn query(&self, query: &str) -> Result<Vec<SearchHit>> {
+        let needle = fold(query.trim());
+        if needle.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut hits: Vec<(String, SearchHit)> = self
+            .docs
+            .iter()
+            .filter_map(|entry| {
+                score_document(entry.value(), &needle)
+                    .map(|hit| (entry.value().name.to_lowercase(), hit))
+            })
+            .collect();
+
+        // Best first; ties by name, then path, so pages are stable
+        hits.sort_by(|(a_name, a), (b_name, b)| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a_name.cmp(b_name))
+                .then_with(|| a.git_path.cmp(&b.git_path))
+        });
+        Ok(hits.into_iter().map(|(_, hit)| hit).collect())
+    }
+
+    fn clear(&self) -> Result<()> {
+        self.docs.clear();
+        Ok(())
+    }
+}
+
+/// Score a document against a folded query, `None` if nothing matches
+fn score_document(doc: &SearchDocument, needle: &[char]) -> Option<SearchHit> {
+    let mut score = 0.0;
+    let mut highlights = Vec::new();
+
+    let name = fold(&doc.name);
+    let matches = find_matches(&name, needle);
+    if !matches.is_empty() {
+        score += if name == needle {
+            10.0
+        } else if matches[0].0 == 0 {
+            6.0
+        } else {
+            4.0
+        };
+        highlights.push(Highlight {
+            field: SearchField::Name,
+            snippet: doc.name.clone(),
+            matches,
+        });
+    }
+
+    let mut ingredient_hits = 0;
+    for ingredient in &doc.ingredients {
+        let matches = find_matches(&fold(ingredient), needle);
+        if matches.is_empty() {
+            continue;
+        }
+        ingredient_hits += 1;
+        if ingredient_hits <= MAX_HIGHLIGHTS_PER_FIELD {
+            score += 2.0;
+            highlights.push(Highlight {
+                field: SearchField::Ingredient,
+                snippet: ingredient.clone(),
+                matches,
+            });
+        }
+    }
+
+    let mut step_hits = 0;
+    for step in &doc.steps {
+        let matches = find_matches(&fold(step), needle);
+        if matches.is_empty() {
+            continue;
+        }
+        step_hits += 1;
+        if step_hits <= MAX_HIGHLIGHTS_PER_FIELD {
+            score += 1.0;
+            highlights.push(step_snippet(step, &matches));
+        }
+    }
+
+    (score > 0.0).then(|| SearchHit {
+        git_path: doc.git_path.clone(),
+        score,
+        highlights,
+    })
+}
+
+/// Lowercase character by character, so offsets in the result match the original text
+fn fold(text: &str) -> Vec<char> {
+    text.chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect()
+}
+
+/// Non-overlapping `[start, end)` character ranges where `needle` occurs in `haystack`
+fn find_matches(haystack: &[char], needle: &[char]) -> Vec<(usize, usize)> {
+    let mut matches = Vec::new();
+    let mut start = 0;
+    while start + needle.len() <= haystack.len() {
+        if haystack[start..start + needle.len()] == *needle {
+            matches.push((start, start + needle.len()));
+            start += needle.len();
+        } else {
+            start += 1;
+        }
+    }
+    matches
+}
+
+/// Cut a step down to the first match with some context, shifting match offsets to suit
+fn step_snippet(step: &str, matches: &[(usize, usize)]) -> Highlight {
+    let chars: Vec<char> = step.chars().collect();
+    let from = matches[0].0.saturating_sub(SNIPPET_CONTEXT);
+    let to = (matches[0].1 + SNIPPET_CONTEXT).min(chars.len());
+
+    let mut snippet = String::new();
+    let mut offset = 0;
+    if from > 0 {
+        snippet.push('…');
+        offset = 1;
+    }
+    snippet.extend(&chars[from..to]);
+    if to < chars.len() {
+        snippet.push('…');
+    }
+
+    Highlight {
+        field: SearchField::Step,
+        snippet,
+        matches: matches
+            .iter()
+            .filter(|(start, end)| *start >= from && *end <= to)
+            .map(|(start, end)| (start - from + offset, end - from + offset))
+            .collect(),
+    }
+}
+
+/// Create a search provider based on configuration
+pub fn create_search_provider(provider_type: &str) -> Result<Box<dyn SearchProvider>> {
+    match provider_type {
+        "memory" => Ok(Box::new(InMemorySearch::new())),
+        other => {
+            // Only the in-memory provider is built in; unknown types fall back to it
+            tracing::warn!(
+                "Unknown search provider '{}', using in-memory search",
+                other
+            );
+            Ok(Box::new(InMemorySearch::new()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(git_path: &str, name: &str) -> SearchDocument {
+        SearchDocument {
+            git_path: git_path.to_string(),
+            name: name.to_string(),
+            ingredients: Vec::new(),
+            steps: Vec::new(),
+        }
+    }
+
+    fn paths(hits: Vec<SearchHit>) -> Vec<String> {
+        hits.into_iter().map(|hit| hit.git_path).collect()
+    }
+
+    #[test]
+    fn test_query_matches_case_insensitive_substring() -> Result<()> {
+        let search = InMemorySearch::new();
+        search.index(doc("recipes/chocolate.cook", "Chocolate Cake"))?;
+        search.index(doc("recipes/vanilla.cook", "Vanilla Cake"))?;
+        search.index(doc("recipes/pasta.cook", "Pasta"))?;
+
+        assert_eq!(search.query("CAKE")?.len(), 2);
+        assert_eq!(paths(search.query("choc")?), vec!["recipes/chocolate.cook"]);
+        assert!(search.query("curry")?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_replaces_existing_document() -> Result<()> {
+        let search = InMemorySearch::new();
+        search.index(doc("recipes/cake.cook", "Cake"))?;
+        search.index(doc("recipes/cake.cook", "Brownie"))?;
+
+        assert!(search.query("cake")?.is_empty());
+        assert_eq!(search.query("brownie")?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_delete_and_clear() -> Result<()> {
+        let search = InMemorySearch::new();
+        search.index(doc("recipes/cake.cook", "Cake"))?;
+        search.index(doc("recipes/pie.cook", "Pie"))?;
+
+        search.delete("recipes/cake.cook")?;
+        assert!(search.query("cake")?.is_empty());
+        assert_eq!(search.query("pie")?.len(), 1);
+
+        search.clear()?;
+        assert!(search.query("pie")?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ranking_and_highlights() -> Result<()> {
+        let search = InMemorySearch::new();
+        search.index(SearchDocument {
+            ingredients: vec!["dark chocolate".to_string()],
+            steps: vec![format!(
+                "{} then fold in the melted Chocolate and stir.",
+                "Whisk the eggs and sugar until pale and doubled in volume, "
+            )],
+            ..doc("recipes/mousse.cook", "Mousse")
+        })?;
+        search.index(doc("recipes/chocolate.cook", "Chocolate"))?;
+        search.index(doc("recipes/cake.cook", "Chocolate Cake"))?;
+        search.index(doc("recipes/pie.cook", "Pie"))?;
+
+        let hits = search.query("chocolate")?;
+        assert_eq!(
+            paths(hits.clone()),
+            vec![
+                "recipes/chocolate.cook",
+                "recipes/cake.cook",
+                "recipes/mousse.cook"
+            ]
+        );
+        assert!(hits[0].score > hits[1].score);
+        assert!(hits[0].matched_name());
+        assert!(!hits[2].matched_name());
+
+        let name = &hits[1].highlights[0];
+        assert_eq!(name.field, SearchField::Name);
+        assert_eq!(name.matches, vec![(0, 9)]);
+
+        let ingredient = &hits[2].highlights[0];
+        assert_eq!(ingredient.field, SearchField::Ingredient);
+        assert_eq!(ingredient.matches, vec![(5, 14)]);
+
+        // Long steps are cut around the match, offsets point into the snippet
+        let step = &hits[2].highlights[1];
+        assert_eq!(step.field, SearchField::Step);
+        assert!(step.snippet.starts_with('…'));
+        let (start, end) = step.matches[0];
+        let matched: String = step.snippet.chars().skip(start).take(end - start).collect();
+        assert_eq!(matched, "Chocolate");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,230 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A link giving read-only access to one recipe until it expires or is revoked
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Share {
+    /// Share ID (used to revoke the link)
+    pub id: String,
+    /// Git path of the shared recipe
+    pub path: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+}
+
+impl Share {
+    fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at <= now
+    }
+}
+
+/// Claims of a share token; the share it names must still exist for the token to work
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    sid: String,
+    exp: i64,
+}
+
+/// Active share links, keyed by share ID
+///
+/// Tokens are HS256 JWTs carrying the share ID and expiry, so they can't be forged or
+/// extended; revoking deletes the share, which invalidates its token.
+pub struct ShareLinks {
+    shares: DashMap<String, Share>,
+    keys: Option<(EncodingKey, DecodingKey)>,
+    counter: AtomicU64,
+}
+
+impl ShareLinks {
+    /// Create an empty table signing with `secret` (sharing is disabled without one)
+    pub fn new(secret: Option<&str>) -> Self {
+        ShareLinks {
+            shares: DashMap::new(),
+            keys: secret.map(|secret| {
+                (
+                    EncodingKey::from_secret(secret.as_bytes()),
+                    DecodingKey::from_secret(secret.as_bytes()),
+                )
+            }),
+            counter: AtomicU64::new(0),
+        }
+    }
+
+    /// Whether a signing secret is configured
+    pub fn enabled(&self) -> bool {
+        self.keys.is_some()
+    }
+
+    /// Replace the table with shares loaded from JSON
+    pub fn load(&self, json: &str) -> Result<()> {
+        let shares: BTreeMap<String, Share> =
+            serde_json::from_str(json).context("Invalid shares file")?;
+
+        self.shares.clear();
+        for (id, share) in shares {
+            self.shares.insert(id, share);
+        }
+        Ok(())
+    }
+
+    /// Serialize unexpired shares to JSON (sorted by ID, for stable diffs)
+    pub fn to_json(&self) -> Result<String> {
+        let now = Utc::now();
+        let shares: BTreeMap<String, Share> = self
+            .shares
+            .iter()
+            .filter(|entry| !entry.value().is_expired(now))
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+        Ok(serde_json::to_string_pretty(&shares)?)
+    }
+
+    /// Share a recipe for `valid_for`, returning the share and its token
+    pub fn create(
+        &self,
+        git_path: &str,
+        valid_for: Duration,
+        author: Option<&str>,
+    ) -> Result<(Share, String)> {
+        let now = Utc::now();
+        let share = Share {
+            id: self.generate_id(git_path, now),
+            path: git_path.to_string(),
+            created_at: now,
+            expires_at: now + valid_for,
+            created_by: author
+                .map(str::trim)
+                .filter(|author| !author.is_empty())
+                .map(str::to_string),
+        };
+        let token = self.token(&share)?;
+        self.shares.insert(share.id.clone(), share.clone());
+        Ok((share, token))
+    }
+
+    /// Signed token of a share (the same one [`create`](Self::create) returned)
+    pub fn token(&self, share: &Share) -> Result<String> {
+        let (key, _) = self
+            .keys
+            .as_ref()
+            .ok_or_else(|| anyhow!("Share links need SHARE_SECRET to be set"))?;
+        let claims = Claims {
+            sid: share.id.clone(),
+            exp: share.expires_at.timestamp(),
+        };
+        jsonwebtoken::encode(&Header::new(Algorithm::HS256), &claims, key)
+            .context("Failed to sign share token")
+    }
+
+    /// The share a token grants access to, if the token is genuine, unexpired and
+    /// not revoked
+    pub fn resolve(&self, token: &str) -> Option<Share> {
+        let (_, key) = self.keys.as_ref()?;
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.leeway = 0;
+        let claims = jsonwebtoken::decode::<Claims>(token, key, &validation)
+            .ok()?
+            .claims;
+
+        self.shares
+            .get(&claims.sid)
+            .map(|share| share.clone())
+            .filter(|share| !share.is_expired(Utc::now()))
+    }
+
+    /// Unexpired shares of a recipe, oldest first
+    pub fn for_recipe(&self, git_path: &str) -> Vec<Share> {
+        let now = Utc::now();
+        let mut shares: Vec<Share> = self
+            .shares
+            .iter()
+            .filter(|entry| entry.path == git_path && !entry.is_expired(now))
+            .map(|entry| entry.value().clone())
+            .collect();
+        shares.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+        shares
+    }
+
+    /// Revoke a share, returning it if it existed
+    pub fn revoke(&self, id: &str) -> Option<Share> {
+        self.shares.remove(id).map(|(_, share)| share)
+    }
+
+    /// Follow a recipe that moved; returns whether anything changed
+    pub fn rename(&self, from: &str, to: &str) -> bool {
+        let mut changed = false;
+        for mut share in self.shares.iter_mut() {
+            if share.path == from {
+                share.path = to.to_string();
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Revoke every share of a deleted recipe; returns whether anything changed
+    pub fn remove(&self, git_path: &str) -> bool {
+        let before = self.shares.len();
+        self.shares.retain(|_, share| share.path != git_path);
+        self.shares.len() != before
+    }
+
+    fn generate_id(&self, git_path: &str, now: DateTime<Utc>) -> String {
+        use sha2::{Digest, Sha256};
+        let mut hasher = Sha256::new();
+        hasher.update(git_path);
+        hasher.update(now.timestamp_nanos_opt().unwrap_or_default().to_le_bytes());
+        hasher.update(self.counter.fetch_add(1, Ordering::Relaxed).to_le_bytes());
+        hasher.update(std::process::id().to_le_bytes());
+        format!("{:x}", hasher.finalize())[..16].to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_share_tokens() -> Result<()> {
+        let shares = ShareLinks::new(Some("secret"));
+        let (share, token) = shares.create("recipes/cake.cook", Duration::hours(1), Some("ana"))?;
+        assert_eq!(shares.resolve(&token), Some(share.clone()));
+        assert_eq!(shares.token(&share)?, token);
+
+        // Another key's signature, a tampered token and an expired share are all refused
+        let (_, other) =
+            ShareLinks::new(Some("other")).create("recipes/cake.cook", Duration::hours(1), None)?;
+        assert!(shares.resolve(&other).is_none());
+        assert!(shares.resolve(&format!("{}x", token)).is_none());
+        let (_, expired) = shares.create("recipes/cake.cook", Duration::hours(-1), None)?;
+        assert!(shares.resolve(&expired).is_none());
+        assert_eq!(shares.for_recipe("recipes/cake.cook"), vec![share.clone()]);
+
+        assert!(shares.rename("recipes/cake.cook", "recipes/desserts/cake.cook"));
+        assert_eq!(
+            shares.resolve(&token).map(|share| share.path),
+            Some("recipes/desserts/cake.cook".to_string())
+        );
+
+        let loaded = ShareLinks::new(Some("secret"));
+        loaded.load(&shares.to_json()?)?;
+        assert_eq!(loaded.resolve(&token), shares.resolve(&token));
+        assert!(loaded.revoke(&share.id).is_some());
+        assert!(loaded.resolve(&token).is_none());
+
+        assert!(shares.remove("recipes/desserts/cake.cook"));
+        assert!(shares.resolve(&token).is_none());
+        assert!(ShareLinks::new(None)
+            .create("recipes/cake.cook", Duration::hours(1), None)
+            .is_err());
+        Ok(())
+    }
+}
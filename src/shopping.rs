@@ -0,0 +1,470 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::aisles::Aisles;
+use crate::parser::IngredientAmount;
+use crate::quantity::QuantityFormat;
+use crate::synonyms::Synonyms;
+
+/// One line of a shopping list: an ingredient in one unit, summed over the recipes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShoppingItem {
+    /// Item ID, unique within its list
+    pub id: u32,
+    /// Ingredient name, lowercased (the canonical name if it has synonyms)
+    pub name: String,
+    /// Total amount; `None` when no recipe gives a number
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    #[serde(default)]
+    pub purchased: bool,
+    /// Names of the recipes needing the item
+    #[serde(default)]
+    pub recipes: Vec<String>,
+}
+
+/// Ingredients to buy for a set of recipes, kept until deleted
+///
+/// Items are a snapshot taken when the list is made: later edits to the recipes don't
+/// change it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShoppingList {
+    pub id: String,
+    pub name: String,
+    /// Names of the recipes the list was made from
+    pub recipes: Vec<String>,
+    pub items: Vec<ShoppingItem>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+}
+
+/// Changes to a shopping list item; fields left `None` stay as they are
+#[derive(Debug, Clone, Default)]
+pub struct ItemUpdate {
+    pub purchased: Option<bool>,
+    pub quantity: Option<f64>,
+    pub unit: Option<String>,
+}
+
+/// Persistent shopping lists, keyed by list ID
+pub struct ShoppingLists {
+    lists: DashMap<String, ShoppingList>,
+    counter: AtomicU64,
+}
+
+impl Default for ShoppingLists {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShoppingLists {
+    /// Create an empty table
+    pub fn new() -> Self {
+        ShoppingLists {
+            lists: DashMap::new(),
+            counter: AtomicU64::new(0),
+        }
+    }
+
+    /// Replace the table with lists loaded from JSON
+    pub fn load(&self, json: &str) -> Result<()> {
+        let lists: BTreeMap<String, ShoppingList> =
+            serde_json::from_str(json).context("Invalid shopping lists file")?;
+
+        self.lists.clear();
+        for (id, list) in lists {
+            self.lists.insert(id, list);
+        }
+        Ok(())
+    }
+
+    /// Serialize all lists to JSON (sorted by ID, for stable diffs)
+    pub fn to_json(&self) -> Result<String> {
+        let lists: BTreeMap<String, ShoppingList> = self
+            .lists
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+        Ok(serde_json::to_string_pretty(&lists)?)
+    }
+
+    /// Make a list from recipes given as `(recipe name, ingredients)`
+    pub fn create(
+        &self,
+        name: &str,
+        recipes: &[(String, Vec<IngredientAmount>)],
+        synonyms: &Synonyms,
+        author: Option<&str>,
+    ) -> ShoppingList {
+        let now = Utc::now();
+        let list = ShoppingList {
+            id: self.generate_id(name, now),
+            name: name.trim().to_string(),
+            recipes: recipes.iter().map(|(name, _)| name.clone()).collect(),
+            items: aggregate(recipes, synonyms),
+            created_at: now,
+            created_by: author
+                .map(str::trim)
+                .filter(|author| !author.is_empty())
+                .map(str::to_string),
+        };
+        self.lists.insert(list.id.clone(), list.clone());
+        list
+    }
+
+    /// Get a list by ID
+    pub fn get(&self, id: &str) -> Option<ShoppingList> {
+        self.lists.get(id).map(|list| list.clone())
+    }
+
+    /// All lists, newest first
+    pub fn all(&self) -> Vec<ShoppingList> {
+        let mut lists: Vec<ShoppingList> = self
+            .lists
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect();
+        lists.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(a.id.cmp(&b.id)));
+        lists
+    }
+
+    /// Change an item, returning it as updated (`None` if the list or item doesn't exist)
+    pub fn update_item(
+        &self,
+        list_id: &str,
+        item_id: u32,
+        update: ItemUpdate,
+    ) -> Option<ShoppingItem> {
+        let mut list = self.lists.get_mut(list_id)?;
+        let item = list.items.iter_mut().find(|item| item.id == item_id)?;
+        if let Some(purchased) = update.purchased {
+            item.purchased = purchased;
+        }
+        if let Some(quantity) = update.quantity {
+            item.quantity = Some(quantity);
+        }
+        if let Some(unit) = update.unit {
+            item.unit = Some(unit).filter(|unit| !unit.is_empty());
+        }
+        Some(item.clone())
+    }
+
+    /// Delete a list, returning it if it existed
+    pub fn remove(&self, id: &str) -> Option<ShoppingList> {
+        self.lists.remove(id).map(|(_, list)| list)
+    }
+
+    fn generate_id(&self, name: &str, now: DateTime<Utc>) -> String {
+        use sha2::{Digest, Sha256};
+        let mut hasher = Sha256::new();
+        hasher.update(name);
+        hasher.update(now.timestamp_nanos_opt().unwrap_or_default().to_le_bytes());
+        hasher.update(self.counter.fetch_add(1, Ordering::Relaxed).to_le_bytes());
+        format!("{:x}", hasher.finalize())[..12].to_string()
+    }
+}
+
+/// Sum the recipes' ingredients into items, one per ingredient and unit, sorted by name
+///
+/// Synonyms merge under their canonical name and units compare ignoring case; amounts
+/// in different units stay separate items rather than being converted.
+pub fn aggregate(
+    recipes: &[(String, Vec<IngredientAmount>)],
+    synonyms: &Synonyms,
+) -> Vec<ShoppingItem> {
+    // (name, lowercased unit) -> item
+    let mut items: BTreeMap<(String, String), ShoppingItem> = BTreeMap::new();
+    for (recipe, ingredients) in recipes {
+        for ingredient in ingredients {
+            let name = synonyms.canonical(&ingredient.name);
+            if name.is_empty() {
+                continue;
+            }
+            let unit = ingredient
+                .unit
+                .as_deref()
+                .map(str::trim)
+                .filter(|unit| !unit.is_empty());
+            let key = (name.clone(), unit.unwrap_or_default().to_lowercase());
+
+            let item = items.entry(key).or_insert_with(|| ShoppingItem {
+                id: 0,
+                name,
+                quantity: None,
+                unit: unit.map(str::to_string),
+                purchased: false,
+                recipes: Vec::new(),
+            });
+            if let Some(quantity) = ingredient.quantity {
+                item.quantity = Some(item.quantity.unwrap_or(0.0) + quantity);
+            }
+            if !item.recipes.contains(recipe) {
+                item.recipes.push(recipe.clone());
+            }
+        }
+    }
+
+    items
+        .into_values()
+        .enumerate()
+        .map(|(index, item)| ShoppingItem {
+            id: index as u32 + 1,
+            ..item
+        })
+        .collect()
+}
+
+/// Heading of the items in no aisle, listed last
+const OTHER_AISLE: &str = "Other";
+
+/// Text format of an exported shopping list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Text,
+    Markdown,
+    Csv,
+}
+
+impl ExportFormat {
+    /// Parse a format name (`text`, `markdown` or `csv`, case-insensitive)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "text" | "txt" => Some(ExportFormat::Text),
+            "markdown" | "md" => Some(ExportFormat::Markdown),
+            "csv" => Some(ExportFormat::Csv),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ExportFormat::Text => "text/plain; charset=utf-8",
+            ExportFormat::Markdown => "text/markdown; charset=utf-8",
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+        }
+    }
+}
+
+/// Write a list's items grouped by aisle, in the aisles' order with items in no aisle
+/// last
+///
+/// Items are matched to aisles under any of their synonyms. Without aisles the items
+/// are listed without headings. Text and Markdown write amounts the way cooks read
+/// them (`½ cup`); CSV keeps plain numbers for spreadsheets.
+pub fn export(
+    list: &ShoppingList,
+    items: &[ShoppingItem],
+    aisles: &Aisles,
+    synonyms: &Synonyms,
+    quantities: &QuantityFormat,
+    format: ExportFormat,
+) -> String {
+    let groups = group_by_aisle(items, aisles, synonyms);
+    let mut out = String::new();
+    match format {
+        ExportFormat::Text => {
+            out.push_str(&list.name);
+            out.push('\n');
+            for (aisle, items) in &groups {
+                out.push('\n');
+                if let Some(aisle) = aisle {
+                    out.push_str(aisle);
+                    out.push('\n');
+                }
+                for item in items {
+                    let check = if item.purchased { " ✓" } else { "" };
+                    out.push_str(&format!("- {}{}\n", item_text(item, quantities), check));
+                }
+            }
+        }
+        ExportFormat::Markdown => {
+            out.push_str(&format!("# {}\n", list.name));
+            for (aisle, items) in &groups {
+                out.push('\n');
+                if let Some(aisle) = aisle {
+                    out.push_str(&format!("## {}\n\n", aisle));
+                }
+                for item in items {
+                    let check = if item.purchased { "x" } else { " " };
+                    out.push_str(&format!("- [{}] {}\n", check, item_text(item, quantities)));
+                }
+            }
+        }
+        ExportFormat::Csv => {
+            out.push_str("aisle,name,quantity,unit,purchased\n");
+            for (aisle, items) in &groups {
+                for item in items {
+                    let fields = [
+                        aisle.unwrap_or_default().to_string(),
+                        item.name.clone(),
+                        item.quantity.map(|q| q.to_string()).unwrap_or_default(),
+                        item.unit.clone().unwrap_or_default(),
+                        item.purchased.to_string(),
+                    ];
+                    let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+                    out.push_str(&fields.join(","));
+                    out.push('\n');
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Items split into aisles, in aisle order; the aisle is `None` only when there are no
+/// aisles at all
+fn group_by_aisle<'a>(
+    items: &'a [ShoppingItem],
+    aisles: &'a Aisles,
+    synonyms: &Synonyms,
+) -> Vec<(Option<&'a str>, Vec<&'a ShoppingItem>)> {
+    if aisles.is_empty() {
+        return vec![(None, items.iter().collect())];
+    }
+
+    // (aisle position, or past the end for none) -> items
+    let mut groups: BTreeMap<usize, (&str, Vec<&ShoppingItem>)> = BTreeMap::new();
+    for item in items {
+        let names = synonyms.names(&item.name);
+        let (index, aisle) = aisles
+            .find(names.iter().map(String::as_str))
+            .unwrap_or((usize::MAX, OTHER_AISLE));
+        groups
+            .entry(index)
+            .or_insert((aisle, Vec::new()))
+            .1
+            .push(item);
+    }
+    groups
+        .into_values()
+        .map(|(aisle, items)| (Some(aisle), items))
+        .collect()
+}
+
+/// An item as `amount name`, e.g. `1½ cup flour`
+fn item_text(item: &ShoppingItem, quantities: &QuantityFormat) -> String {
+    let amount = match (item.quantity, item.unit.as_deref()) {
+        (Some(quantity), Some(unit)) => quantities.format_amount(&format!("{} {}", quantity, unit)),
+        (Some(quantity), None) => quantities.format(quantity),
+        (None, _) => return item.name.clone(),
+    };
+    format!("{} {}", amount, item.name)
+}
+
+/// A CSV field, quoted if it holds a comma, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn amount(name: &str, quantity: Option<f64>, unit: Option<&str>) -> IngredientAmount {
+        IngredientAmount {
+            name: name.to_string(),
+            quantity,
+            unit: unit.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_shopping_list_aggregates_and_updates() -> Result<()> {
+        let recipes = vec![
+            (
+                "Pancakes".to_string(),
+                vec![
+                    amount("Flour", Some(200.0), Some("g")),
+                    amount("eggs", Some(2.0), None),
+                    amount("scallions", Some(1.0), None),
+                ],
+            ),
+            (
+                "Bread".to_string(),
+                vec![
+                    amount("flour", Some(300.0), Some("G")),
+                    amount("flour", Some(1.0), Some("tbsp")),
+                    amount("green onion", Some(2.0), None),
+                    amount("salt", None, None),
+                ],
+            ),
+        ];
+        let synonyms = Synonyms::parse("scallions = green onion")?;
+        let lists = ShoppingLists::new();
+        let list = lists.create("Weekend", &recipes, &synonyms, Some("ana"));
+
+        let items: Vec<(&str, Option<f64>, Option<&str>)> = list
+            .items
+            .iter()
+            .map(|item| (item.name.as_str(), item.quantity, item.unit.as_deref()))
+            .collect();
+        assert_eq!(
+            items,
+            vec![
+                ("eggs", Some(2.0), None),
+                ("flour", Some(500.0), Some("g")),
+                ("flour", Some(1.0), Some("tbsp")),
+                ("salt", None, None),
+                ("scallions", Some(3.0), None),
+            ]
+        );
+        assert_eq!(list.items[4].recipes, vec!["Pancakes", "Bread"]);
+        assert_eq!(list.items[0].id, 1);
+
+        let update = ItemUpdate {
+            purchased: Some(true),
+            quantity: Some(6.0),
+            ..Default::default()
+        };
+        let item = lists.update_item(&list.id, 1, update).unwrap();
+        assert!(item.purchased);
+        assert_eq!(item.quantity, Some(6.0));
+        assert!(lists
+            .update_item(&list.id, 99, ItemUpdate::default())
+            .is_none());
+
+        let aisles = Aisles::parse("[produce]\ngreen onion\n[baking]\nflour")?;
+        let list = lists.get(&list.id).unwrap();
+        let export = |format| {
+            export(
+                &list,
+                &list.items,
+                &aisles,
+                &synonyms,
+                &QuantityFormat::default(),
+                format,
+            )
+        };
+        assert_eq!(
+            export(ExportFormat::Markdown),
+            "# Weekend\n\n## produce\n\n- [ ] 3 scallions\n\n## baking\n\n\
+             - [ ] 500 g flour\n- [ ] 1 tbsp flour\n\n## Other\n\n- [x] 6 eggs\n- [ ] salt\n"
+        );
+        assert!(export(ExportFormat::Text).contains("\nOther\n- 6 eggs ✓\n- salt\n"));
+        assert!(export(ExportFormat::Csv).starts_with(
+            "aisle,name,quantity,unit,purchased\nproduce,scallions,3,,false\nbaking,flour,500,g,false\n"
+        ));
+        assert_eq!(csv_field("a, \"b\""), "\"a, \"\"b\"\"\"");
+
+        let loaded = ShoppingLists::new();
+        loaded.load(&lists.to_json()?)?;
+        assert_eq!(loaded.get(&list.id), lists.get(&list.id));
+        assert!(loaded.remove(&list.id).is_some());
+        assert!(loaded.all().is_empty());
+        Ok(())
+    }
+}
@@ -1,11 +1,21 @@
 use anyhow::{Context, Result};
+use chrono::Utc;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 
-use super::RecipeStorage;
+use super::{
+    CommitInfo, FileStat, LastModified, RecipeStorage, StagedChange, Transaction, STAGING_DIR,
+};
+
+/// Numbers transactions so concurrent ones stage in separate directories
+static TRANSACTIONS: AtomicU64 = AtomicU64::new(0);
 
 /// Disk-based storage backend - stores recipes directly on filesystem without version control
 pub struct DiskStorage {
     repo_path: PathBuf,
+    /// Leave directories in place when their last file is deleted or moved away
+    keep_empty_dirs: bool,
 }
 
 impl DiskStorage {
@@ -16,8 +26,66 @@ impl DiskStorage {
 
         Ok(DiskStorage {
             repo_path: repo_path.to_path_buf(),
+            keep_empty_dirs: false,
         })
     }
+
+    /// Keep directories that deletes and moves leave empty instead of removing them
+    pub fn keep_empty_dirs(mut self, keep: bool) -> Self {
+        self.keep_empty_dirs = keep;
+        self
+    }
+
+    /// Swap the files of `transaction` into place, given the staged new content of each
+    /// path it writes (`None` for paths it vacates)
+    ///
+    /// Every step is a rename: the files being replaced are first moved to `backup`, then
+    /// the staged files to their paths. On failure the steps taken are reversed.
+    fn swap_in(&self, targets: &[(&str, Option<PathBuf>)], backup: &Path) -> Result<()> {
+        let mut set_aside = Vec::new();
+        let mut placed = Vec::new();
+        let result = (|| -> Result<()> {
+            for (index, (rel_path, _)) in targets.iter().enumerate() {
+                let full_path = self.repo_path.join(rel_path);
+                if full_path.is_file() {
+                    let kept = backup.join(index.to_string());
+                    std::fs::rename(&full_path, &kept)
+                        .with_context(|| format!("Failed to set aside file: {}", rel_path))?;
+                    set_aside.push((full_path, kept));
+                }
+            }
+            for (rel_path, staged) in targets {
+                let Some(staged) = staged else {
+                    continue;
+                };
+                let full_path = self.repo_path.join(rel_path);
+                if let Some(parent) = full_path.parent() {
+                    std::fs::create_dir_all(parent).context("Failed to create recipe directory")?;
+                }
+                std::fs::rename(staged, &full_path)
+                    .with_context(|| format!("Failed to write file: {}", rel_path))?;
+                placed.push(full_path);
+            }
+            Ok(())
+        })();
+
+        if result.is_err() {
+            for full_path in placed {
+                let _ = std::fs::remove_file(full_path);
+            }
+            for (full_path, kept) in set_aside.into_iter().rev() {
+                if let Err(e) = std::fs::rename(&kept, &full_path) {
+                    tracing::error!(
+                        "Failed to restore {} from {}: {}",
+                        full_path.display(),
+                        kept.display(),
+                        e
+                    );
+                }
+            }
+        }
+        result
+    }
 }
 
 impl RecipeStorage for DiskStorage {
@@ -45,28 +113,112 @@ impl RecipeStorage for DiskStorage {
             std::fs::remove_file(&full_path)
                 .context(format!("Failed to delete file: {}", rel_path))?;
         }
+        if !self.keep_empty_dirs {
+            super::remove_empty_parents(&self.repo_path, rel_path);
+        }
 
         Ok(())
     }
 
-    fn discover_files(&self) -> Result<Vec<String>> {
-        let mut cook_files = Vec::new();
+    /// Move the file with one `rename`, so it keeps its identity (e.g. for file
+    /// watchers and sync tools) and is never missing or duplicated
+    fn rename_file(&self, from: &str, to: &str, content: &str, _commit: &CommitInfo) -> Result<()> {
+        super::move_file(&self.repo_path, from, to, content)?;
+        if !self.keep_empty_dirs {
+            super::remove_empty_parents(&self.repo_path, from);
+        }
+        Ok(())
+    }
 
-        for entry in walkdir::WalkDir::new(&self.repo_path)
+    /// Stage the new content of every file in a directory of its own, then swap the
+    /// files into place with renames
+    ///
+    /// Anything that can fail for lack of space happens while staging, before a file
+    /// in the data dir is touched.
+    fn commit_transaction(&self, transaction: &Transaction, _commit: &CommitInfo) -> Result<()> {
+        if transaction.is_empty() {
+            return Ok(());
+        }
+        let staging = self.repo_path.join(STAGING_DIR).join(format!(
+            "{}-{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or_default(),
+            TRANSACTIONS.fetch_add(1, Ordering::Relaxed)
+        ));
+        let (new, backup) = (staging.join("new"), staging.join("old"));
+        std::fs::create_dir_all(&new).context("Failed to create staging directory")?;
+        std::fs::create_dir_all(&backup).context("Failed to create staging directory")?;
+
+        // The content each touched path ends up with, `None` if it ends up gone
+        let mut targets: Vec<(&str, Option<PathBuf>)> = transaction
+            .paths()
             .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            if entry.path().extension().and_then(|s| s.to_str()) == Some("cook") {
-                let relative_path = entry
-                    .path()
-                    .strip_prefix(&self.repo_path)?
-                    .to_string_lossy()
-                    .to_string();
-                cook_files.push(relative_path);
+            .map(|rel_path| (rel_path, None))
+            .collect();
+        let result = (|| -> Result<()> {
+            for (index, change) in transaction.changes().iter().enumerate() {
+                let (vacated, written) = match change {
+                    StagedChange::Write { rel_path, content } => (None, Some((rel_path, content))),
+                    StagedChange::Delete { rel_path } => (Some(rel_path), None),
+                    StagedChange::Rename { from, to, content } => (Some(from), Some((to, content))),
+                };
+                if let Some(rel_path) = vacated {
+                    if let Some(target) = targets.iter_mut().find(|(path, _)| path == rel_path) {
+                        target.1 = None;
+                    }
+                }
+                if let Some((rel_path, content)) = written {
+                    let staged = new.join(index.to_string());
+                    std::fs::write(&staged, content)
+                        .with_context(|| format!("Failed to stage file: {}", rel_path))?;
+                    if let Some(target) = targets.iter_mut().find(|(path, _)| path == rel_path) {
+                        target.1 = Some(staged);
+                    }
+                }
+            }
+            self.swap_in(&targets, &backup)
+        })();
+
+        // A failed rollback leaves files set aside; those are kept for a manual restore
+        let restored = std::fs::read_dir(&backup).is_ok_and(|mut entries| entries.next().is_none());
+        if result.is_ok() || restored {
+            if let Err(e) = std::fs::remove_dir_all(&staging) {
+                tracing::warn!("Failed to remove {}: {}", staging.display(), e);
+            }
+        }
+        result?;
+        if !self.keep_empty_dirs {
+            for rel_path in transaction.vacated_paths() {
+                super::remove_empty_parents(&self.repo_path, rel_path);
             }
         }
+        Ok(())
+    }
+
+    fn last_modified(&self, rel_paths: &[String]) -> Result<HashMap<String, LastModified>> {
+        Ok(super::modification_times(&self.repo_path, rel_paths))
+    }
+
+    fn stat(&self, rel_path: &str) -> Result<Option<FileStat>> {
+        super::file_stat(&self.repo_path, rel_path)
+    }
+
+    fn discover_files(&self) -> Result<Vec<(String, FileStat)>> {
+        super::discover_with_extension(&self.repo_path, "cook")
+    }
+
+    fn discover_menu_files(&self) -> Result<Vec<String>> {
+        Ok(super::discover_with_extension(&self.repo_path, "menu")?
+            .into_iter()
+            .map(|(rel_path, _)| rel_path)
+            .collect())
+    }
+
+    fn list_files(&self, rel_dir: &str) -> Result<Vec<String>> {
+        super::walk_files(&self.repo_path, rel_dir)
+    }
 
-        Ok(cook_files)
+    fn remove_empty_dirs(&self, rel_dir: &str) -> Result<bool> {
+        super::remove_empty_dirs(&self.repo_path, rel_dir)
     }
 }
 
@@ -139,6 +291,124 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_delete_removes_emptied_directories() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let storage = DiskStorage::new(temp_dir.path())?;
+
+        storage.write_file("recipes/desserts/cakes/sponge.cook", "# Sponge")?;
+        storage.write_file("recipes/desserts/tart.cook", "# Tart")?;
+        storage.delete_file("recipes/desserts/cakes/sponge.cook")?;
+        assert!(!temp_dir.path().join("recipes/desserts/cakes").exists());
+        assert!(temp_dir.path().join("recipes/desserts").exists());
+
+        // A move leaves the old directory behind only if asked to
+        let storage = storage.keep_empty_dirs(true);
+        let commit = CommitInfo {
+            message: "Move recipe".to_string(),
+            author: None,
+        };
+        storage.rename_file(
+            "recipes/desserts/tart.cook",
+            "recipes/tart.cook",
+            "# Tart",
+            &commit,
+        )?;
+        assert!(temp_dir.path().join("recipes/desserts").is_dir());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rename_file_moves_in_place() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let storage = DiskStorage::new(temp_dir.path())?;
+        let commit = CommitInfo {
+            message: "Rename recipe".to_string(),
+            author: None,
+        };
+
+        storage.write_file("recipes/soups/pea.cook", "# Pea")?;
+        let old_path = temp_dir.path().join("recipes/soups/pea.cook");
+        let modified = std::fs::metadata(&old_path)?.modified()?;
+        std::thread::sleep(std::time::Duration::from_millis(20));
+
+        // Unchanged content is moved without rewriting the file
+        storage.rename_file(
+            "recipes/soups/pea.cook",
+            "recipes/Pea.cook",
+            "# Pea",
+            &commit,
+        )?;
+        let new_path = temp_dir.path().join("recipes/Pea.cook");
+        assert!(!old_path.exists());
+        assert!(!temp_dir.path().join("recipes/soups").exists());
+        assert_eq!(std::fs::metadata(&new_path)?.modified()?, modified);
+
+        // A case-only rename keeps the file, with the new content
+        storage.rename_file(
+            "recipes/Pea.cook",
+            "recipes/pea.cook",
+            "# Pea Soup",
+            &commit,
+        )?;
+        assert_eq!(storage.read_file("recipes/pea.cook")?, "# Pea Soup");
+        assert_eq!(
+            storage
+                .discover_files()?
+                .into_iter()
+                .map(|(rel_path, _)| rel_path)
+                .collect::<Vec<_>>(),
+            vec!["recipes/pea.cook"]
+        );
+
+        // A source that is already gone just writes the target
+        storage.rename_file("recipes/gone.cook", "recipes/new.cook", "# New", &commit)?;
+        assert_eq!(storage.read_file("recipes/new.cook")?, "# New");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_transaction_is_all_or_nothing() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let storage = DiskStorage::new(temp_dir.path())?;
+        let commit = CommitInfo::default();
+        storage.write_file("recipes/soups/pea.cook", "# Pea")?;
+        storage.write_file("recipes/leek.cook", "# Leek")?;
+
+        // The last write lands below a file, so none of the changes are kept
+        let mut transaction = Transaction::new();
+        transaction
+            .write("recipes/leek.cook", "# Leek and Potato")
+            .rename("recipes/soups/pea.cook", "recipes/pea.cook", "# Pea Soup")
+            .write("recipes/leek.cook/broken.cook", "# Broken");
+        assert!(storage.commit_transaction(&transaction, &commit).is_err());
+        assert_eq!(storage.read_file("recipes/leek.cook")?, "# Leek");
+        assert_eq!(storage.read_file("recipes/soups/pea.cook")?, "# Pea");
+        assert!(!temp_dir.path().join("recipes/pea.cook").exists());
+
+        let mut transaction = Transaction::new();
+        transaction
+            .write("recipes/carrot.cook", "# Carrot")
+            .rename("recipes/soups/pea.cook", "recipes/pea.cook", "# Pea Soup")
+            .delete("recipes/leek.cook");
+        storage.commit_transaction(&transaction, &commit)?;
+        let mut files: Vec<String> = storage
+            .discover_files()?
+            .into_iter()
+            .map(|(rel_path, _)| rel_path)
+            .collect();
+        files.sort();
+        assert_eq!(files, vec!["recipes/carrot.cook", "recipes/pea.cook"]);
+        assert_eq!(storage.read_file("recipes/pea.cook")?, "# Pea Soup");
+        assert!(!temp_dir.path().join("recipes/soups").exists());
+        let staging = temp_dir.path().join(STAGING_DIR);
+        assert_eq!(std::fs::read_dir(staging)?.count(), 0);
+
+        Ok(())
+    }
+
     #[test]
     fn test_delete_nonexistent_file_succeeds() -> Result<()> {
         let temp_dir = TempDir::new()?;
@@ -162,9 +432,28 @@ mod tests {
 
         let files = storage.discover_files()?;
         assert_eq!(files.len(), 3);
-        assert!(files.iter().any(|f| f.contains("test1.cook")));
-        assert!(files.iter().any(|f| f.contains("cake.cook")));
-        assert!(files.iter().any(|f| f.contains("pasta.cook")));
+        assert!(files.iter().any(|(f, _)| f.contains("test1.cook")));
+        assert!(files.iter().any(|(f, _)| f.contains("cake.cook")));
+        assert!(files.iter().any(|(f, _)| f.contains("pasta.cook")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stat() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let storage = DiskStorage::new(temp_dir.path())?;
+        storage.write_file("recipes/pea.cook", "# Pea Soup")?;
+
+        let stat = storage.stat("recipes/pea.cook")?.unwrap();
+        assert_eq!(stat.size, 10);
+        assert_eq!(stat.commit, None);
+        assert_eq!(
+            storage.discover_files()?,
+            vec![("recipes/pea.cook".to_string(), stat)]
+        );
+        assert_eq!(storage.stat("recipes/missing.cook")?, None);
+        assert_eq!(storage.stat("recipes")?, None);
 
         Ok(())
     }
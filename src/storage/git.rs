@@ -1,80 +1,655 @@
 use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
 use git2::Repository as GitRepository;
-use std::path::Path;
-use std::sync::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex, TryLockError};
+use std::time::{Duration, Instant};
 
-use super::RecipeStorage;
-use crate::git;
+use super::{
+    BlameLine, CommitAuthor, CommitInfo, DraftFile, FileChanges, FileStat, GcReport, LastModified,
+    MaintenanceRunning, ReadOnlyPath, RecipeStorage, StagedChange, Transaction, VersionTag,
+};
+use crate::git::{self, CommitSigning};
+
+/// A git operation queued for the worker thread
+type Job = Box<dyn FnOnce(&mut GitWorker) + Send>;
 
 /// Git-based storage backend - maintains version history with automatic commits
+///
+/// The repository handle is owned by a dedicated worker thread that runs git operations
+/// one at a time, in the order they were queued. Plain file reads go straight to the
+/// working directory, so they never wait behind a slow commit.
 pub struct GitStorage {
-    repo: Mutex<GitRepository>,
+    jobs: mpsc::Sender<Job>,
+    workdir: PathBuf,
+    git_dir: PathBuf,
+    // Held for the duration of a gc run; gc uses its own handle so writes carry on
+    maintenance: Mutex<()>,
+    /// Leave directories in place when their last file is deleted or moved away
+    keep_empty_dirs: bool,
+}
+
+/// Settings for git storage
+#[derive(Debug, Clone, Default)]
+pub struct GitOptions {
+    /// Combine writes made within this long of each other into one commit
+    /// (`Duration::ZERO` commits every write at once)
+    ///
+    /// Batched writes are on disk and staged as soon as they return; the commit follows
+    /// when the window closes, before a draft is published or a version is tagged, and
+    /// when the storage is dropped.
+    pub commit_window: Duration,
+    /// Key to sign commits (including draft and merge commits) with, `None` for unsigned
+    pub signing: Option<CommitSigning>,
 }
 
 impl GitStorage {
-    /// Create a new git storage instance
+    /// Create a new git storage instance that commits every write at once
     pub fn new(repo_path: &Path) -> Result<Self> {
+        Self::with_options(repo_path, GitOptions::default())
+    }
+
+    /// Create a git storage instance that combines writes made within `window` of each
+    /// other into one commit (see [`GitOptions::commit_window`])
+    pub fn with_commit_window(repo_path: &Path, window: Duration) -> Result<Self> {
+        Self::with_options(
+            repo_path,
+            GitOptions {
+                commit_window: window,
+                ..GitOptions::default()
+            },
+        )
+    }
+
+    /// Create a git storage instance with the given options
+    ///
+    /// With signing configured, a test signature is made first so a missing key or
+    /// signing tool fails here rather than on the first write.
+    pub fn with_options(repo_path: &Path, options: GitOptions) -> Result<Self> {
+        if let Some(signing) = &options.signing {
+            signing
+                .sign("cooklang-store signing check\n")
+                .context("Commit signing is configured but does not work")?;
+        }
+
         let repo = git::init_repo(repo_path)?;
+        let workdir = repo
+            .workdir()
+            .context("Repository has no working directory")?
+            .to_path_buf();
+        let git_dir = repo.path().to_path_buf();
+
+        let (jobs, queue) = mpsc::channel::<Job>();
+        let window = options.commit_window;
+        let worker = GitWorker {
+            repo,
+            window: (!window.is_zero()).then_some(window),
+            pending: None,
+            signing: options.signing,
+        };
+        std::thread::Builder::new()
+            .name("git-worker".to_string())
+            .spawn(move || worker.run(queue))
+            .context("Failed to start git worker")?;
 
         Ok(GitStorage {
-            repo: Mutex::new(repo),
+            jobs,
+            workdir,
+            git_dir,
+            maintenance: Mutex::new(()),
+            keep_empty_dirs: false,
+        })
+    }
+
+    /// Keep directories that deletes and moves leave empty instead of removing them
+    ///
+    /// Git doesn't track directories, so this only affects the working tree.
+    pub fn keep_empty_dirs(mut self, keep: bool) -> Self {
+        self.keep_empty_dirs = keep;
+        self
+    }
+
+    /// Remove the directories that `rel_paths` leave empty, unless they are kept
+    fn remove_empty_parents<'a>(&self, rel_paths: impl IntoIterator<Item = &'a str>) {
+        if !self.keep_empty_dirs {
+            for rel_path in rel_paths {
+                super::remove_empty_parents(&self.workdir, rel_path);
+            }
+        }
+    }
+
+    /// Queue `operation` for the worker thread and wait for its result
+    fn run<T, F>(&self, operation: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut GitWorker) -> Result<T> + Send + 'static,
+    {
+        let (reply, result) = mpsc::sync_channel(1);
+        self.jobs
+            .send(Box::new(move |worker: &mut GitWorker| {
+                let _ = reply.send(operation(worker));
+            }))
+            .map_err(|_| anyhow!("Git worker has stopped"))?;
+        result
+            .recv()
+            .map_err(|_| anyhow!("Git operation failed without a result"))?
+    }
+}
+
+impl Drop for GitStorage {
+    fn drop(&mut self) {
+        // Commit any batched writes; the worker stops once the sender is gone
+        if let Err(e) = self.run(GitWorker::flush) {
+            tracing::error!("Failed to commit batched changes: {}", e);
+        }
+    }
+}
+
+/// Owner of the repository handle, running queued operations on its own thread
+struct GitWorker {
+    repo: GitRepository,
+    /// How long to hold staged changes for further writes, `None` to commit at once
+    window: Option<Duration>,
+    pending: Option<PendingCommit>,
+    signing: Option<CommitSigning>,
+}
+
+/// Staged changes waiting to be committed together
+struct PendingCommit {
+    /// (message, author) of each write, oldest first
+    changes: Vec<(String, CommitAuthor)>,
+    /// Files the changes touched, which have no commit for them yet
+    paths: HashSet<String>,
+    due: Instant,
+}
+
+impl GitWorker {
+    fn run(mut self, queue: mpsc::Receiver<Job>) {
+        loop {
+            let received = match &self.pending {
+                Some(pending) => {
+                    queue.recv_timeout(pending.due.saturating_duration_since(Instant::now()))
+                }
+                None => queue
+                    .recv()
+                    .map_err(|_| mpsc::RecvTimeoutError::Disconnected),
+            };
+            match received {
+                // A panicking job drops its reply channel, which its caller reports
+                Ok(job) => {
+                    let _ = panic::catch_unwind(AssertUnwindSafe(|| job(&mut self)));
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    if let Err(e) = self.flush() {
+                        tracing::error!("Failed to commit batched changes: {}", e);
+                    }
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    }
+
+    /// Commit what is staged, or add it to the pending batch when batching is on
+    fn commit(
+        &mut self,
+        commit: CommitInfo,
+        paths: impl IntoIterator<Item = String>,
+    ) -> Result<()> {
+        let author = commit.author.unwrap_or_default();
+        let Some(window) = self.window else {
+            git::commit_staged_as(&self.repo, &commit.message, &author, self.signing.as_ref())?;
+            return Ok(());
+        };
+
+        let pending = self.pending.get_or_insert_with(|| PendingCommit {
+            changes: Vec::new(),
+            paths: HashSet::new(),
+            due: Instant::now() + window,
+        });
+        pending.changes.push((commit.message, author));
+        pending.paths.extend(paths);
+        Ok(())
+    }
+
+    /// Commit the pending batch, if any, as one commit by the author of its first change
+    fn flush(&mut self) -> Result<()> {
+        let Some(pending) = self.pending.take() else {
+            return Ok(());
+        };
+        let (message, author) = match pending.changes.as_slice() {
+            [(message, author)] => (message.clone(), author.clone()),
+            changes => (batch_message(changes), changes[0].1.clone()),
+        };
+        git::commit_staged_as(&self.repo, &message, &author, self.signing.as_ref())?;
+        Ok(())
+    }
+
+    /// Fail with [`ReadOnlyPath`] if any of `rel_paths` is inside a submodule
+    fn ensure_writable<'a>(&self, rel_paths: impl IntoIterator<Item = &'a str>) -> Result<()> {
+        let submodules = git::submodule_paths(&self.repo)?;
+        for rel_path in rel_paths {
+            if submodules.iter().any(|dir| super::is_below(rel_path, dir)) {
+                return Err(ReadOnlyPath {
+                    path: rel_path.to_string(),
+                }
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `rel_path` has batched changes not committed yet
+    fn is_pending(&self, rel_path: &str) -> bool {
+        self.pending
+            .as_ref()
+            .is_some_and(|pending| pending.paths.contains(rel_path))
+    }
+}
+
+/// Message for a batch: a count, then the subject line of each change (with its author
+/// when the changes come from more than one)
+fn batch_message(changes: &[(String, CommitAuthor)]) -> String {
+    let single_author = changes.iter().all(|(_, author)| *author == changes[0].1);
+    let lines: Vec<String> = changes
+        .iter()
+        .map(|(message, author)| {
+            let subject = message.lines().next().unwrap_or_default();
+            if single_author {
+                format!("- {}", subject)
+            } else {
+                format!("- {} ({})", subject, author.name)
+            }
         })
+        .collect();
+    format!("Batch of {} changes\n\n{}", changes.len(), lines.join("\n"))
+}
+
+/// Make the changes of `transaction` in the working tree and stage them
+fn stage_transaction(repo: &GitRepository, transaction: &Transaction) -> Result<()> {
+    let workdir = repo
+        .workdir()
+        .context("Repository has no working directory")?;
+    for change in transaction.changes() {
+        match change {
+            StagedChange::Write { rel_path, content } => {
+                let full_path = workdir.join(rel_path);
+                if let Some(parent) = full_path.parent() {
+                    std::fs::create_dir_all(parent).context("Failed to create recipe directory")?;
+                }
+                std::fs::write(&full_path, content).context("Failed to write recipe file")?;
+                git::stage_files(repo, &[rel_path])?;
+            }
+            StagedChange::Delete { rel_path } => git::stage_deletion(repo, rel_path)?,
+            StagedChange::Rename { from, to, content } => {
+                git::stage_renames(repo, &[(from, to, content)])?
+            }
+        }
     }
+    Ok(())
+}
+
+/// Last commit touching each of `rel_paths` in `repo`, as [`LastModified`]
+fn last_commits(
+    repo: &GitRepository,
+    rel_paths: &[String],
+) -> Result<HashMap<String, LastModified>> {
+    let commits = git::last_commits_for_paths(repo, rel_paths)?;
+    Ok(commits
+        .into_iter()
+        .map(|(rel_path, commit)| {
+            let author = commit.author();
+            let last_modified = LastModified {
+                at: DateTime::<Utc>::from_timestamp(author.when().seconds(), 0).unwrap_or_default(),
+                author: author.name().map(str::to_string),
+                message: commit
+                    .message()
+                    .map(|message| message.trim_end().to_string()),
+                revision: Some(commit.id().to_string()),
+            };
+            (rel_path, last_modified)
+        })
+        .collect())
 }
 
 impl RecipeStorage for GitStorage {
     fn write_file(&self, rel_path: &str, content: &str) -> Result<()> {
-        let repo = self
-            .repo
-            .lock()
-            .map_err(|_| anyhow!("Failed to lock git repository"))?;
+        let commit = CommitInfo {
+            message: format!("Update recipe: {}", rel_path),
+            author: None,
+        };
+        self.write_file_with_commit(rel_path, content, &commit)
+    }
 
-        let workdir = repo
-            .workdir()
-            .context("Repository has no working directory")?;
-        let full_path = workdir.join(rel_path);
+    fn write_file_with_commit(
+        &self,
+        rel_path: &str,
+        content: &str,
+        commit: &CommitInfo,
+    ) -> Result<()> {
+        let (rel_path, content, commit) =
+            (rel_path.to_string(), content.to_string(), commit.clone());
+        self.run(move |worker| {
+            worker.ensure_writable([rel_path.as_str()])?;
+            let workdir = worker
+                .repo
+                .workdir()
+                .context("Repository has no working directory")?;
+            let full_path = workdir.join(&rel_path);
 
-        // Create parent directories
-        if let Some(parent) = full_path.parent() {
-            std::fs::create_dir_all(parent).context("Failed to create recipe directory")?;
-        }
+            // Create parent directories
+            if let Some(parent) = full_path.parent() {
+                std::fs::create_dir_all(parent).context("Failed to create recipe directory")?;
+            }
 
-        // Write the file
-        std::fs::write(&full_path, content).context("Failed to write recipe file")?;
+            // Write the file
+            std::fs::write(&full_path, content).context("Failed to write recipe file")?;
+
+            // Commit the change
+            git::stage_files(&worker.repo, &[&rel_path])?;
+            worker.commit(commit, [rel_path])
+        })
+    }
 
-        // Commit the change
-        let commit_message = format!("Update recipe: {}", rel_path);
-        git::commit_file(&repo, rel_path, &commit_message)?;
+    /// Stage every change and commit them together; if staging or the commit fails,
+    /// the touched files are put back as they are in HEAD
+    ///
+    /// Batched changes to the same files are committed first, so restoring them from
+    /// HEAD can't lose those.
+    fn commit_transaction(&self, transaction: &Transaction, commit: &CommitInfo) -> Result<()> {
+        if transaction.is_empty() {
+            return Ok(());
+        }
+        let (transaction, commit) = (transaction.clone(), commit.clone());
+        let vacated: Vec<String> = transaction
+            .vacated_paths()
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        self.run(move |worker| {
+            let paths = transaction.paths();
+            worker.ensure_writable(paths.iter().copied())?;
+            if paths.iter().any(|rel_path| worker.is_pending(rel_path)) {
+                worker.flush()?;
+            }
 
+            let result = stage_transaction(&worker.repo, &transaction).and_then(|()| {
+                worker.commit(commit, paths.iter().map(|rel_path| rel_path.to_string()))
+            });
+            if result.is_err() {
+                if let Err(e) = git::restore_paths(&worker.repo, &paths) {
+                    tracing::error!("Failed to roll back a transaction: {}", e);
+                }
+            }
+            result
+        })?;
+        self.remove_empty_parents(vacated.iter().map(String::as_str));
         Ok(())
     }
 
     fn read_file(&self, rel_path: &str) -> Result<String> {
-        let repo = self
-            .repo
-            .lock()
-            .map_err(|_| anyhow!("Failed to lock git repository"))?;
-        git::read_file(&repo, rel_path)
+        std::fs::read_to_string(self.workdir.join(rel_path))
+            .context(format!("Failed to read file: {}", rel_path))
     }
 
     fn delete_file(&self, rel_path: &str) -> Result<()> {
-        let repo = self
-            .repo
-            .lock()
-            .map_err(|_| anyhow!("Failed to lock git repository"))?;
+        let commit = CommitInfo {
+            message: format!("Delete recipe: {}", rel_path),
+            author: None,
+        };
+        self.delete_file_with_commit(rel_path, &commit)
+    }
 
-        let commit_message = format!("Delete recipe: {}", rel_path);
-        git::delete_file(&repo, rel_path, &commit_message)?;
+    fn delete_file_with_commit(&self, rel_path: &str, commit: &CommitInfo) -> Result<()> {
+        let (path, commit) = (rel_path.to_string(), commit.clone());
+        self.run(move |worker| {
+            worker.ensure_writable([path.as_str()])?;
+            git::stage_deletion(&worker.repo, &path)?;
+            worker.commit(commit, [path])
+        })?;
+        self.remove_empty_parents([rel_path]);
+        Ok(())
+    }
 
+    fn rename_file(&self, from: &str, to: &str, content: &str, commit: &CommitInfo) -> Result<()> {
+        let (old, new, content, commit) = (
+            from.to_string(),
+            to.to_string(),
+            content.to_string(),
+            commit.clone(),
+        );
+        self.run(move |worker| {
+            worker.ensure_writable([old.as_str(), new.as_str()])?;
+            git::stage_renames(&worker.repo, &[(&old, &new, &content)])?;
+            worker.commit(commit, [old, new])
+        })?;
+        self.remove_empty_parents([from]);
         Ok(())
     }
 
-    fn discover_files(&self) -> Result<Vec<String>> {
-        let repo = self
-            .repo
-            .lock()
-            .map_err(|_| anyhow!("Failed to lock git repository"))?;
-        git::discover_cook_files(&repo)
+    fn supports_drafts(&self) -> bool {
+        true
+    }
+
+    fn write_draft(
+        &self,
+        draft_id: &str,
+        rel_path: &str,
+        content: &str,
+        commit: &CommitInfo,
+    ) -> Result<()> {
+        let branch = format!("{}{}", git::DRAFT_BRANCH_PREFIX, draft_id);
+        let (rel_path, content, commit) =
+            (rel_path.to_string(), content.to_string(), commit.clone());
+        self.run(move |worker| {
+            worker.ensure_writable([rel_path.as_str()])?;
+            let author = commit.author.unwrap_or_default();
+            git::commit_to_branch(
+                &worker.repo,
+                &branch,
+                &rel_path,
+                &content,
+                &commit.message,
+                &author,
+                worker.signing.as_ref(),
+            )?;
+            Ok(())
+        })
+    }
+
+    fn list_drafts(&self) -> Result<Vec<DraftFile>> {
+        self.run(|worker| git::list_drafts(&worker.repo))
+    }
+
+    fn publish_draft(&self, draft_id: &str, commit: &CommitInfo) -> Result<DraftFile> {
+        let (draft_id, commit) = (draft_id.to_string(), commit.clone());
+        self.run(move |worker| {
+            // The merge commits onto HEAD, so batched changes go first
+            worker.flush()?;
+            let author = commit.author.unwrap_or_default();
+            git::merge_draft(
+                &worker.repo,
+                &draft_id,
+                &commit.message,
+                &author,
+                worker.signing.as_ref(),
+            )
+        })
+    }
+
+    fn discard_draft(&self, draft_id: &str) -> Result<bool> {
+        let draft_id = draft_id.to_string();
+        self.run(move |worker| git::delete_draft(&worker.repo, &draft_id))
+    }
+
+    fn supports_versions(&self) -> bool {
+        true
+    }
+
+    fn tag_version(
+        &self,
+        recipe_id: &str,
+        name: &str,
+        rel_path: &str,
+        commit: &CommitInfo,
+    ) -> Result<VersionTag> {
+        let (recipe_id, name, rel_path, commit) = (
+            recipe_id.to_string(),
+            name.to_string(),
+            rel_path.to_string(),
+            commit.clone(),
+        );
+        self.run(move |worker| {
+            // Submodule files are not in this repository's history
+            worker.ensure_writable([rel_path.as_str()])?;
+            // Tag the recipe as it is now, including batched changes
+            worker.flush()?;
+            let author = commit.author.unwrap_or_default();
+            git::tag_version(
+                &worker.repo,
+                &recipe_id,
+                &name,
+                &rel_path,
+                &commit.message,
+                &author,
+            )
+        })
+    }
+
+    fn list_versions(&self, recipe_id: &str) -> Result<Vec<VersionTag>> {
+        let recipe_id = recipe_id.to_string();
+        self.run(move |worker| git::list_versions(&worker.repo, &recipe_id))
+    }
+
+    fn read_version(&self, recipe_id: &str, name: &str, rel_path: &str) -> Result<Option<String>> {
+        let (recipe_id, name, rel_path) = (
+            recipe_id.to_string(),
+            name.to_string(),
+            rel_path.to_string(),
+        );
+        self.run(move |worker| git::read_version(&worker.repo, &recipe_id, &name, &rel_path))
+    }
+
+    fn supports_blame(&self) -> bool {
+        true
+    }
+
+    fn blame_file(&self, rel_path: &str) -> Result<Vec<BlameLine>> {
+        let rel_path = rel_path.to_string();
+        self.run(
+            move |worker| match git::submodule_for(&worker.repo, &rel_path)? {
+                Some((submodule, inner_path)) => git::blame_file(&submodule, &inner_path),
+                None => git::blame_file(&worker.repo, &rel_path),
+            },
+        )
+    }
+
+    fn head_revision(&self) -> Result<Option<String>> {
+        self.run(|worker| git::head_revision(&worker.repo))
+    }
+
+    fn last_modified(&self, rel_paths: &[String]) -> Result<HashMap<String, LastModified>> {
+        let paths = rel_paths.to_vec();
+        let mut modified = self.run(move |worker| {
+            // Files with batched changes are treated like uncommitted ones
+            let mut paths: Vec<String> = paths
+                .into_iter()
+                .filter(|rel_path| !worker.is_pending(rel_path))
+                .collect();
+            let mut modified = HashMap::new();
+
+            // Submodule files take their history from the submodule
+            for dir in git::submodule_paths(&worker.repo)? {
+                let (inside, rest): (Vec<String>, Vec<String>) = paths
+                    .into_iter()
+                    .partition(|rel_path| super::is_below(rel_path, &dir));
+                paths = rest;
+                let Ok(submodule) = worker
+                    .repo
+                    .find_submodule(&dir)
+                    .and_then(|submodule| submodule.open())
+                else {
+                    continue;
+                };
+                let inner_paths: Vec<String> = inside
+                    .iter()
+                    .map(|rel_path| rel_path[dir.len() + 1..].to_string())
+                    .collect();
+                for (inner_path, last_modified) in last_commits(&submodule, &inner_paths)? {
+                    modified.insert(format!("{}/{}", dir, inner_path), last_modified);
+                }
+            }
+
+            modified.extend(last_commits(&worker.repo, &paths)?);
+            Ok(modified)
+        })?;
+
+        // Files not committed yet fall back to their modification time
+        let uncommitted: Vec<String> = rel_paths
+            .iter()
+            .filter(|rel_path| !modified.contains_key(*rel_path))
+            .cloned()
+            .collect();
+        modified.extend(super::modification_times(&self.workdir, &uncommitted));
+        Ok(modified)
+    }
+
+    fn stat(&self, rel_path: &str) -> Result<Option<FileStat>> {
+        let Some(mut stat) = super::file_stat(&self.workdir, rel_path)? else {
+            return Ok(None);
+        };
+        stat.commit = self
+            .last_modified(&[rel_path.to_string()])?
+            .remove(rel_path)
+            .filter(|last_modified| last_modified.revision.is_some());
+        Ok(Some(stat))
+    }
+
+    fn changed_files(&self, revision: &str) -> Result<Option<FileChanges>> {
+        let revision = revision.to_string();
+        self.run(move |worker| git::changed_files(&worker.repo, &revision))
+    }
+
+    fn read_only_dirs(&self) -> Result<Vec<String>> {
+        self.run(|worker| git::submodule_paths(&worker.repo))
+    }
+
+    fn supports_maintenance(&self) -> bool {
+        true
+    }
+
+    fn run_maintenance(&self) -> Result<GcReport> {
+        let _running = match self.maintenance.try_lock() {
+            Ok(guard) => guard,
+            Err(TryLockError::WouldBlock) => return Err(MaintenanceRunning.into()),
+            Err(TryLockError::Poisoned(_)) => {
+                return Err(anyhow!("Failed to lock git maintenance"))
+            }
+        };
+
+        // Open a separate handle so the worker carries on with writes meanwhile
+        let repo = GitRepository::open(&self.git_dir).context("Failed to open git repository")?;
+        git::gc(&repo)
+    }
+
+    fn discover_files(&self) -> Result<Vec<(String, FileStat)>> {
+        super::discover_with_extension(&self.workdir, "cook")
+    }
+
+    fn discover_menu_files(&self) -> Result<Vec<String>> {
+        Ok(super::discover_with_extension(&self.workdir, "menu")?
+            .into_iter()
+            .map(|(rel_path, _)| rel_path)
+            .collect())
+    }
+
+    fn list_files(&self, rel_dir: &str) -> Result<Vec<String>> {
+        super::walk_files(&self.workdir, rel_dir)
+    }
+
+    fn remove_empty_dirs(&self, rel_dir: &str) -> Result<bool> {
+        super::remove_empty_dirs(&self.workdir, rel_dir)
     }
 }
 
@@ -107,7 +682,7 @@ mod tests {
         assert!(temp_dir.path().join("recipes/test.cook").exists());
 
         // Verify it's in git history
-        let repo = storage.repo.lock().unwrap();
+        let repo = GitRepository::open(temp_dir.path())?;
         let head = repo.head()?;
         let commit = head.peel_to_commit()?;
         assert!(commit.message().unwrap().contains("Update recipe"));
@@ -115,6 +690,82 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_concurrent_writes_are_queued() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let storage = std::sync::Arc::new(GitStorage::new(temp_dir.path())?);
+
+        let writers: Vec<_> = (0..8)
+            .map(|i| {
+                let storage = storage.clone();
+                std::thread::spawn(move || {
+                    storage.write_file(&format!("recipes/{}.cook", i), &format!("# Recipe {}", i))
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap()?;
+        }
+
+        let repo = GitRepository::open(temp_dir.path())?;
+        let mut revwalk = repo.revwalk()?;
+        revwalk.push_head()?;
+        assert_eq!(revwalk.count(), 8);
+        for i in 0..8 {
+            let path = format!("recipes/{}.cook", i);
+            assert_eq!(storage.read_file(&path)?, format!("# Recipe {}", i));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_commit_window_batches_writes() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let storage = GitStorage::with_commit_window(temp_dir.path(), Duration::from_secs(3600))?;
+        let commit = |message: &str, name: &str| CommitInfo {
+            message: message.to_string(),
+            author: Some(CommitAuthor {
+                name: name.to_string(),
+                email: format!("{}@example.com", name),
+            }),
+        };
+
+        storage.write_file_with_commit("recipes/a.cook", "a", &commit("Create a", "Alice"))?;
+        storage.write_file_with_commit("recipes/b.cook", "b", &commit("Create b", "Bob"))?;
+        storage.delete_file_with_commit("recipes/a.cook", &commit("Delete a", "Alice"))?;
+
+        // Written but not committed yet
+        assert_eq!(storage.read_file("recipes/b.cook")?, "b");
+        assert_eq!(storage.head_revision()?, None);
+        let modified = storage.last_modified(&["recipes/b.cook".to_string()])?;
+        assert_eq!(modified["recipes/b.cook"].revision, None);
+        let stat = storage.stat("recipes/b.cook")?.unwrap();
+        assert_eq!((stat.size, stat.commit), (1, None));
+
+        // Dropping the storage commits the batch
+        drop(storage);
+        let repo = GitRepository::open(temp_dir.path())?;
+        let head = repo.head()?.peel_to_commit()?;
+        assert_eq!(head.parent_count(), 0);
+        assert_eq!(
+            head.message(),
+            Some("Batch of 3 changes\n\n- Create a (Alice)\n- Create b (Bob)\n- Delete a (Alice)")
+        );
+        assert_eq!(head.author().name(), Some("Alice"));
+        let tree = head.tree()?;
+        assert!(tree.get_path(Path::new("recipes/b.cook")).is_ok());
+        assert!(tree.get_path(Path::new("recipes/a.cook")).is_err());
+
+        // Once committed, the stat carries the commit
+        let storage = GitStorage::new(temp_dir.path())?;
+        let commit = storage.stat("recipes/b.cook")?.unwrap().commit.unwrap();
+        assert_eq!(commit.revision, Some(head.id().to_string()));
+        assert_eq!(commit.author.as_deref(), Some("Alice"));
+
+        Ok(())
+    }
+
     #[test]
     fn test_read_file() -> Result<()> {
         let temp_dir = TempDir::new()?;
@@ -140,9 +791,12 @@ mod tests {
 
         storage.delete_file("recipes/test.cook")?;
         assert!(!temp_dir.path().join("recipes/test.cook").exists());
+        // The directory it emptied goes too, but never the repository itself
+        assert!(!temp_dir.path().join("recipes").exists());
+        assert!(temp_dir.path().exists());
 
         // Verify deletion is committed
-        let repo = storage.repo.lock().unwrap();
+        let repo = GitRepository::open(temp_dir.path())?;
         let head = repo.head()?;
         let commit = head.peel_to_commit()?;
         assert!(commit.message().unwrap().contains("Delete recipe"));
@@ -150,6 +804,217 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_write_file_with_commit_uses_message() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let storage = GitStorage::new(temp_dir.path())?;
+
+        let commit = CommitInfo {
+            message: "Create recipe: Cake\n\nGrandma's version".to_string(),
+            author: Some(git::CommitAuthor {
+                name: "Alice".to_string(),
+                email: "alice@example.com".to_string(),
+            }),
+        };
+        storage.write_file_with_commit("recipes/cake.cook", "# Cake", &commit)?;
+
+        let repo = GitRepository::open(temp_dir.path())?;
+        let head = repo.head()?.peel_to_commit()?;
+        assert_eq!(head.message().unwrap(), commit.message);
+        assert_eq!(head.author().email(), Some("alice@example.com"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rename_file_is_single_commit() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let storage = GitStorage::new(temp_dir.path())?;
+
+        let content = "---\ntitle: Cake\n---\n\nMix @flour{200%g} and @sugar{100%g}.\nBake.";
+        storage.write_file("recipes/cake.cook", content)?;
+        let commit = CommitInfo {
+            message: "Move recipe: recipes/desserts/cake.cook".to_string(),
+            author: None,
+        };
+        storage.rename_file(
+            "recipes/cake.cook",
+            "recipes/desserts/cake.cook",
+            content,
+            &commit,
+        )?;
+
+        assert!(!temp_dir.path().join("recipes/cake.cook").exists());
+        assert_eq!(storage.read_file("recipes/desserts/cake.cook")?, content);
+
+        let repo = GitRepository::open(temp_dir.path())?;
+        let head = repo.head()?.peel_to_commit()?;
+        assert_eq!(head.message().unwrap(), commit.message);
+
+        // Git sees the change as a rename
+        let parent = head.parent(0)?;
+        let mut diff = repo.diff_tree_to_tree(Some(&parent.tree()?), Some(&head.tree()?), None)?;
+        diff.find_similar(None)?;
+        let deltas: Vec<_> = diff.deltas().collect();
+        assert_eq!(deltas.len(), 1);
+        assert_eq!(deltas[0].status(), git2::Delta::Renamed);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_files_is_single_commit() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let storage = GitStorage::new(temp_dir.path())?;
+
+        storage.write_file("recipes/cake.cook", "---\ntitle: Cake\n---\n")?;
+        let files = vec![
+            (
+                "recipes/cake.cook".to_string(),
+                "---\ntitle: Cake\ntags: [sweet]\n---\n".to_string(),
+            ),
+            (
+                "recipes/desserts/pie.cook".to_string(),
+                "---\ntitle: Pie\ntags: [sweet]\n---\n".to_string(),
+            ),
+        ];
+        let commit = CommitInfo {
+            message: "Retag recipes".to_string(),
+            author: None,
+        };
+        storage.write_files_with_commit(&files, &commit)?;
+
+        for (rel_path, content) in &files {
+            assert_eq!(&storage.read_file(rel_path)?, content);
+        }
+
+        let repo = GitRepository::open(temp_dir.path())?;
+        let head = repo.head()?.peel_to_commit()?;
+        assert_eq!(head.message().unwrap(), commit.message);
+        let parent = head.parent(0)?;
+        let diff = repo.diff_tree_to_tree(Some(&parent.tree()?), Some(&head.tree()?), None)?;
+        assert_eq!(diff.deltas().count(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_failed_transaction_is_rolled_back() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let storage = GitStorage::new(temp_dir.path())?;
+        storage.write_file("recipes/soups/pea.cook", "# Pea")?;
+        storage.write_file("recipes/leek.cook", "# Leek")?;
+        let repo = GitRepository::open(temp_dir.path())?;
+        let before = repo.head()?.peel_to_commit()?.id();
+        let commit = CommitInfo {
+            message: "Reorganize soups".to_string(),
+            author: None,
+        };
+
+        // The last write lands below a file, so none of the changes are kept
+        let mut transaction = Transaction::new();
+        transaction
+            .rename("recipes/soups/pea.cook", "recipes/pea.cook", "# Pea Soup")
+            .write("recipes/leek.cook", "# Leek and Potato")
+            .write("recipes/leek.cook/broken.cook", "# Broken");
+        assert!(storage.commit_transaction(&transaction, &commit).is_err());
+        assert_eq!(repo.head()?.peel_to_commit()?.id(), before);
+        assert_eq!(storage.read_file("recipes/soups/pea.cook")?, "# Pea");
+        assert_eq!(storage.read_file("recipes/leek.cook")?, "# Leek");
+        assert!(!temp_dir.path().join("recipes/pea.cook").exists());
+        assert!(repo.statuses(None)?.is_empty());
+
+        let mut transaction = Transaction::new();
+        transaction
+            .rename("recipes/soups/pea.cook", "recipes/pea.cook", "# Pea Soup")
+            .delete("recipes/leek.cook");
+        storage.commit_transaction(&transaction, &commit)?;
+        let head = repo.head()?.peel_to_commit()?;
+        assert_eq!(head.parent_id(0)?, before);
+        assert_eq!(head.message().unwrap(), commit.message);
+        assert_eq!(
+            storage
+                .discover_files()?
+                .into_iter()
+                .map(|(rel_path, _)| rel_path)
+                .collect::<Vec<_>>(),
+            vec!["recipes/pea.cook"]
+        );
+        assert!(!temp_dir.path().join("recipes/soups").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_draft_stays_off_main_until_published() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let storage = GitStorage::new(temp_dir.path())?;
+        let commit = CommitInfo::default();
+
+        let content = "line 1\nline 2\nline 3\nline 4\nline 5\n";
+        storage.write_file("recipes/cake.cook", content)?;
+
+        let draft = "line 1\nline 2\nline 3\nline 4\nline 5 (draft)\n";
+        storage.write_draft("abc123", "recipes/cake.cook", draft, &commit)?;
+        storage.write_draft("def456", "recipes/new.cook", "new", &commit)?;
+
+        // The working tree and main branch are untouched
+        assert_eq!(storage.read_file("recipes/cake.cook")?, content);
+        assert!(!temp_dir.path().join("recipes/new.cook").exists());
+
+        let mut drafts = storage.list_drafts()?;
+        drafts.sort_by(|a, b| a.draft_id.cmp(&b.draft_id));
+        assert_eq!(drafts.len(), 2);
+        assert_eq!(drafts[0].rel_path, "recipes/cake.cook");
+        assert_eq!(drafts[0].content, draft);
+
+        // A non-overlapping edit on main merges cleanly with the draft
+        let edited = "line 1 (main)\nline 2\nline 3\nline 4\nline 5\n";
+        storage.write_file("recipes/cake.cook", edited)?;
+
+        let published = storage.publish_draft("abc123", &commit)?;
+        assert_eq!(
+            published.content,
+            "line 1 (main)\nline 2\nline 3\nline 4\nline 5 (draft)\n"
+        );
+        assert_eq!(storage.read_file("recipes/cake.cook")?, published.content);
+        assert_eq!(storage.list_drafts()?.len(), 1);
+
+        // Later commits on main keep the published file
+        storage.write_file("recipes/other.cook", "other")?;
+        {
+            let repo = GitRepository::open(temp_dir.path())?;
+            let tree = repo.head()?.peel_to_tree()?;
+            assert!(tree
+                .get_path(std::path::Path::new("recipes/cake.cook"))
+                .is_ok());
+        }
+
+        assert!(storage.discard_draft("def456")?);
+        assert!(!storage.discard_draft("def456")?);
+        assert!(storage.list_drafts()?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_publish_conflicting_draft_fails() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let storage = GitStorage::new(temp_dir.path())?;
+        let commit = CommitInfo::default();
+
+        storage.write_file("recipes/cake.cook", "sugar 100g\n")?;
+        storage.write_draft("abc123", "recipes/cake.cook", "sugar 80g\n", &commit)?;
+        storage.write_file("recipes/cake.cook", "sugar 120g\n")?;
+
+        let err = storage.publish_draft("abc123", &commit).unwrap_err();
+        assert!(err.downcast_ref::<super::super::DraftConflict>().is_some());
+        assert_eq!(storage.read_file("recipes/cake.cook")?, "sugar 120g\n");
+        assert_eq!(storage.list_drafts()?.len(), 1);
+
+        Ok(())
+    }
+
     #[test]
     fn test_discover_files() -> Result<()> {
         let temp_dir = TempDir::new()?;
@@ -164,4 +1029,67 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_submodule_files_are_read_only() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+
+        // A recipe pack in its own repository, added as the `community` submodule
+        let pack_path = temp_dir.path().join("pack");
+        let pack = git::init_repo(&pack_path)?;
+        std::fs::write(pack_path.join("pasta.cook"), "Boil @pasta{}.\n")?;
+        git::commit_file(&pack, "pasta.cook", "Add pasta")?;
+
+        let data_path = temp_dir.path().join("recipes");
+        let repo = git::init_repo(&data_path)?;
+        let url = pack_path.to_string_lossy();
+        let mut submodule = repo.submodule(&url, Path::new("community"), true)?;
+        submodule.clone(None)?;
+        submodule.add_finalize()?;
+        git::commit_staged_as(&repo, "Add community pack", &CommitAuthor::default(), None)?;
+        let head = git::head_revision(&repo)?.unwrap();
+
+        let storage = GitStorage::new(&data_path)?;
+        storage.write_file("desserts/cake.cook", "Mix @flour{}.\n")?;
+
+        let mut files: Vec<String> = storage
+            .discover_files()?
+            .into_iter()
+            .map(|(rel_path, _)| rel_path)
+            .collect();
+        files.sort();
+        assert_eq!(files, vec!["community/pasta.cook", "desserts/cake.cook"]);
+        assert_eq!(storage.read_only_dirs()?, vec!["community"]);
+
+        // History comes from the submodule
+        let paths = vec!["community/pasta.cook".to_string()];
+        let modified = storage.last_modified(&paths)?;
+        assert_eq!(
+            modified["community/pasta.cook"].message.as_deref(),
+            Some("Add pasta")
+        );
+        let blame = storage.blame_file("community/pasta.cook")?;
+        assert_eq!(blame[0].summary.as_deref(), Some("Add pasta"));
+
+        // Writes below the submodule are rejected before touching the file
+        let error = storage
+            .write_file("community/pasta.cook", "Burn @pasta{}.\n")
+            .unwrap_err();
+        assert!(error.is::<ReadOnlyPath>());
+        assert_eq!(
+            storage.read_file("community/pasta.cook")?,
+            "Boil @pasta{}.\n"
+        );
+        assert!(storage.delete_file("community/pasta.cook").is_err());
+        assert!(storage
+            .write_file("community/new.cook", "Stir @sauce{}.\n")
+            .is_err());
+        assert!(!data_path.join("community/new.cook").exists());
+
+        // A changed submodule calls for a full rescan
+        std::fs::write(data_path.join("community/pasta.cook"), "Drain @pasta{}.\n")?;
+        assert!(storage.changed_files(&head)?.is_none());
+
+        Ok(())
+    }
 }
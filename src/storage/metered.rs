@@ -0,0 +1,230 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use super::{
+    BlameLine, CommitInfo, DraftFile, FileChanges, FileStat, GcReport, LastModified, MirrorReport,
+    RecipeStorage, Transaction, VersionTag,
+};
+use crate::metrics::StorageMetrics;
+
+/// Storage backend wrapper that times every operation into [`StorageMetrics`]
+///
+/// Operations are recorded under their method name. The capability checks
+/// (`supports_*`) only read a flag and are passed through untimed.
+pub struct MeteredStorage {
+    inner: Box<dyn RecipeStorage>,
+    metrics: Arc<StorageMetrics>,
+}
+
+impl MeteredStorage {
+    pub fn new(inner: Box<dyn RecipeStorage>, metrics: Arc<StorageMetrics>) -> Self {
+        MeteredStorage { inner, metrics }
+    }
+
+    fn time<T>(&self, operation: &'static str, run: impl FnOnce() -> Result<T>) -> Result<T> {
+        let started = Instant::now();
+        let result = run();
+        self.metrics
+            .record(operation, started.elapsed(), result.is_err());
+        result
+    }
+}
+
+impl RecipeStorage for MeteredStorage {
+    fn write_file(&self, rel_path: &str, content: &str) -> Result<()> {
+        self.time("write_file", || self.inner.write_file(rel_path, content))
+    }
+
+    fn read_file(&self, rel_path: &str) -> Result<String> {
+        self.time("read_file", || self.inner.read_file(rel_path))
+    }
+
+    fn delete_file(&self, rel_path: &str) -> Result<()> {
+        self.time("delete_file", || self.inner.delete_file(rel_path))
+    }
+
+    fn write_file_with_commit(
+        &self,
+        rel_path: &str,
+        content: &str,
+        commit: &CommitInfo,
+    ) -> Result<()> {
+        self.time("write_file_with_commit", || {
+            self.inner.write_file_with_commit(rel_path, content, commit)
+        })
+    }
+
+    fn commit_transaction(&self, transaction: &Transaction, commit: &CommitInfo) -> Result<()> {
+        self.time("commit_transaction", || {
+            self.inner.commit_transaction(transaction, commit)
+        })
+    }
+
+    fn delete_file_with_commit(&self, rel_path: &str, commit: &CommitInfo) -> Result<()> {
+        self.time("delete_file_with_commit", || {
+            self.inner.delete_file_with_commit(rel_path, commit)
+        })
+    }
+
+    fn rename_file(&self, from: &str, to: &str, content: &str, commit: &CommitInfo) -> Result<()> {
+        self.time("rename_file", || {
+            self.inner.rename_file(from, to, content, commit)
+        })
+    }
+
+    fn supports_drafts(&self) -> bool {
+        self.inner.supports_drafts()
+    }
+
+    fn write_draft(
+        &self,
+        draft_id: &str,
+        rel_path: &str,
+        content: &str,
+        commit: &CommitInfo,
+    ) -> Result<()> {
+        self.time("write_draft", || {
+            self.inner.write_draft(draft_id, rel_path, content, commit)
+        })
+    }
+
+    fn list_drafts(&self) -> Result<Vec<DraftFile>> {
+        self.time("list_drafts", || self.inner.list_drafts())
+    }
+
+    fn publish_draft(&self, draft_id: &str, commit: &CommitInfo) -> Result<DraftFile> {
+        self.time("publish_draft", || {
+            self.inner.publish_draft(draft_id, commit)
+        })
+    }
+
+    fn discard_draft(&self, draft_id: &str) -> Result<bool> {
+        self.time("discard_draft", || self.inner.discard_draft(draft_id))
+    }
+
+    fn supports_versions(&self) -> bool {
+        self.inner.supports_versions()
+    }
+
+    fn tag_version(
+        &self,
+        recipe_id: &str,
+        name: &str,
+        rel_path: &str,
+        commit: &CommitInfo,
+    ) -> Result<VersionTag> {
+        self.time("tag_version", || {
+            self.inner.tag_version(recipe_id, name, rel_path, commit)
+        })
+    }
+
+    fn list_versions(&self, recipe_id: &str) -> Result<Vec<VersionTag>> {
+        self.time("list_versions", || self.inner.list_versions(recipe_id))
+    }
+
+    fn read_version(&self, recipe_id: &str, name: &str, rel_path: &str) -> Result<Option<String>> {
+        self.time("read_version", || {
+            self.inner.read_version(recipe_id, name, rel_path)
+        })
+    }
+
+    fn supports_blame(&self) -> bool {
+        self.inner.supports_blame()
+    }
+
+    fn blame_file(&self, rel_path: &str) -> Result<Vec<BlameLine>> {
+        self.time("blame_file", || self.inner.blame_file(rel_path))
+    }
+
+    fn supports_maintenance(&self) -> bool {
+        self.inner.supports_maintenance()
+    }
+
+    fn run_maintenance(&self) -> Result<GcReport> {
+        self.time("run_maintenance", || self.inner.run_maintenance())
+    }
+
+    fn head_revision(&self) -> Result<Option<String>> {
+        self.time("head_revision", || self.inner.head_revision())
+    }
+
+    fn last_modified(&self, rel_paths: &[String]) -> Result<HashMap<String, LastModified>> {
+        self.time("last_modified", || self.inner.last_modified(rel_paths))
+    }
+
+    fn stat(&self, rel_path: &str) -> Result<Option<FileStat>> {
+        self.time("stat", || self.inner.stat(rel_path))
+    }
+
+    fn changed_files(&self, revision: &str) -> Result<Option<FileChanges>> {
+        self.time("changed_files", || self.inner.changed_files(revision))
+    }
+
+    fn read_only_dirs(&self) -> Result<Vec<String>> {
+        self.time("read_only_dirs", || self.inner.read_only_dirs())
+    }
+
+    fn discover_files(&self) -> Result<Vec<(String, FileStat)>> {
+        self.time("discover_files", || self.inner.discover_files())
+    }
+
+    fn discover_menu_files(&self) -> Result<Vec<String>> {
+        self.time("discover_menu_files", || self.inner.discover_menu_files())
+    }
+
+    fn list_files(&self, rel_dir: &str) -> Result<Vec<String>> {
+        self.time("list_files", || self.inner.list_files(rel_dir))
+    }
+
+    fn remove_empty_dirs(&self, rel_dir: &str) -> Result<bool> {
+        self.time("remove_empty_dirs", || {
+            self.inner.remove_empty_dirs(rel_dir)
+        })
+    }
+
+    fn check_mirror(&self, skip_dirs: &[&str]) -> Result<Option<MirrorReport>> {
+        self.time("check_mirror", || self.inner.check_mirror(skip_dirs))
+    }
+
+    fn repair_mirror(&self, skip_dirs: &[&str]) -> Result<Option<MirrorReport>> {
+        self.time("repair_mirror", || self.inner.repair_mirror(skip_dirs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::DiskStorage;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_operations_are_timed() -> Result<()> {
+        let dir = TempDir::new()?;
+        let metrics = Arc::new(StorageMetrics::new("disk"));
+        let storage = MeteredStorage::new(
+            Box::new(DiskStorage::new(dir.path())?),
+            Arc::clone(&metrics),
+        );
+
+        storage.write_file_with_commit("soup.cook", "@water{1%l}", &CommitInfo::default())?;
+        assert_eq!(storage.read_file("soup.cook")?, "@water{1%l}");
+        assert!(storage.read_file("missing.cook").is_err());
+        assert!(!storage.supports_drafts());
+
+        let operations = metrics.snapshot();
+        assert_eq!(operations["write_file_with_commit"].count, 1);
+        assert_eq!(
+            (
+                operations["read_file"].count,
+                operations["read_file"].errors
+            ),
+            (2, 1)
+        );
+        // Calls the backend makes internally aren't counted twice
+        assert!(!operations.contains_key("write_file"));
+        assert!(!operations.contains_key("supports_drafts"));
+        Ok(())
+    }
+}
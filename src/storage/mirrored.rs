@@ -0,0 +1,459 @@
+use anyhow::Result;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use super::{
+    is_below, BlameLine, CommitInfo, DraftFile, FileChanges, FileStat, GcReport, LastModified,
+    MirrorReport, RecipeStorage, Transaction, VersionTag, STAGING_DIR,
+};
+
+/// Longest a consistency check waits for queued copies before comparing
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A change made on the primary, to be repeated on the mirror
+enum MirrorOp {
+    Write {
+        rel_path: String,
+        content: String,
+        commit: Option<CommitInfo>,
+    },
+    Delete {
+        rel_path: String,
+        commit: Option<CommitInfo>,
+    },
+    Rename {
+        from: String,
+        to: String,
+        content: String,
+        commit: CommitInfo,
+    },
+    Transaction {
+        transaction: Transaction,
+        commit: CommitInfo,
+    },
+    RemoveEmptyDirs(String),
+}
+
+/// How far the mirror is behind, shared with the copying thread
+#[derive(Default)]
+struct MirrorState {
+    /// Changes queued or being copied
+    pending: Mutex<usize>,
+    drained: Condvar,
+    /// Copies that failed since startup, and the last error
+    failures: Mutex<(u64, Option<String>)>,
+}
+
+impl MirrorState {
+    fn finish(&self, result: Result<()>) {
+        if let Err(e) = result {
+            tracing::warn!("Failed to copy a change to the storage mirror: {:#}", e);
+            let mut failures = self
+                .failures
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            failures.0 += 1;
+            failures.1 = Some(format!("{:#}", e));
+        }
+        let mut pending = self
+            .pending
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *pending = pending.saturating_sub(1);
+        if *pending == 0 {
+            self.drained.notify_all();
+        }
+    }
+
+    /// Wait until nothing is queued, at most `timeout`; returns what is still pending
+    fn wait_drained(&self, timeout: Duration) -> usize {
+        let deadline = Instant::now() + timeout;
+        let mut pending = self
+            .pending
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        while *pending > 0 {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                break;
+            }
+            pending = self
+                .drained
+                .wait_timeout(pending, left)
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .0;
+        }
+        *pending
+    }
+}
+
+/// Storage backend wrapper that copies every change to a second backend, e.g. a disk
+/// primary mirrored to a network share
+///
+/// Reads and history come from the primary, and a change succeeds once the primary has
+/// it. The mirror is updated afterwards, in order, by a background thread; a copy that
+/// fails is logged and shows up in [`check_mirror`](RecipeStorage::check_mirror) until
+/// [`repair_mirror`](RecipeStorage::repair_mirror) fixes it. Drafts and versions stay on
+/// the primary; publishing a draft copies the published file.
+pub struct MirroredStorage {
+    primary: Box<dyn RecipeStorage>,
+    mirror: Arc<dyn RecipeStorage>,
+    queue: Sender<MirrorOp>,
+    state: Arc<MirrorState>,
+}
+
+impl MirroredStorage {
+    pub fn new(primary: Box<dyn RecipeStorage>, mirror: Box<dyn RecipeStorage>) -> Self {
+        let mirror: Arc<dyn RecipeStorage> = Arc::from(mirror);
+        let state = Arc::new(MirrorState::default());
+        let (queue, changes) = mpsc::channel();
+        {
+            let (mirror, state) = (Arc::clone(&mirror), Arc::clone(&state));
+            // Ends when the storage is dropped and the queue closes
+            std::thread::spawn(move || copy_changes(mirror.as_ref(), &state, changes));
+        }
+        MirroredStorage {
+            primary,
+            mirror,
+            queue,
+            state,
+        }
+    }
+
+    fn enqueue(&self, op: MirrorOp) {
+        *self
+            .state
+            .pending
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) += 1;
+        if self.queue.send(op).is_err() {
+            self.state
+                .finish(Err(anyhow::anyhow!("The mirror thread has stopped")));
+        }
+    }
+
+    /// Compare the primary's files with the mirror's, leaving out `skip_dirs`
+    fn compare(&self, skip_dirs: &[&str]) -> Result<MirrorReport> {
+        let pending = self.state.wait_drained(DRAIN_TIMEOUT);
+        let listed = |storage: &dyn RecipeStorage| -> Result<BTreeSet<String>> {
+            Ok(storage
+                .list_files("")?
+                .into_iter()
+                .filter(|path| {
+                    !is_below(path, ".git")
+                        && !is_below(path, STAGING_DIR)
+                        && !skip_dirs.iter().any(|dir| is_below(path, dir))
+                })
+                .collect())
+        };
+        let primary_files = listed(self.primary.as_ref())?;
+        let mirror_files = listed(self.mirror.as_ref())?;
+
+        let mut report = MirrorReport {
+            pending,
+            ..Default::default()
+        };
+        for (checked, path) in primary_files.iter().enumerate() {
+            crate::deadline::check(checked, Some(primary_files.len()))?;
+            if !mirror_files.contains(path) {
+                report.missing.push(path.clone());
+            } else if self.primary.read_file(path).ok() != self.mirror.read_file(path).ok() {
+                report.different.push(path.clone());
+            }
+        }
+        report.extra = mirror_files.difference(&primary_files).cloned().collect();
+
+        let failures = self
+            .state
+            .failures
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        report.failures = failures.0;
+        report.last_error = failures.1.clone();
+        Ok(report)
+    }
+}
+
+/// Repeat queued changes on the mirror until the queue closes
+fn copy_changes(mirror: &dyn RecipeStorage, state: &MirrorState, changes: Receiver<MirrorOp>) {
+    for op in changes {
+        let result = match op {
+            MirrorOp::Write {
+                rel_path,
+                content,
+                commit: Some(commit),
+            } => mirror.write_file_with_commit(&rel_path, &content, &commit),
+            MirrorOp::Write {
+                rel_path, content, ..
+            } => mirror.write_file(&rel_path, &content),
+            MirrorOp::Delete {
+                rel_path,
+                commit: Some(commit),
+            } => mirror.delete_file_with_commit(&rel_path, &commit),
+            MirrorOp::Delete { rel_path, .. } => mirror.delete_file(&rel_path),
+            MirrorOp::Rename {
+                from,
+                to,
+                content,
+                commit,
+            } => mirror.rename_file(&from, &to, &content, &commit),
+            MirrorOp::Transaction {
+                transaction,
+                commit,
+            } => mirror.commit_transaction(&transaction, &commit),
+            MirrorOp::RemoveEmptyDirs(rel_dir) => mirror.remove_empty_dirs(&rel_dir).map(|_| ()),
+        };
+        state.finish(result);
+    }
+}
+
+impl RecipeStorage for MirroredStorage {
+    fn write_file(&self, rel_path: &str, content: &str) -> Result<()> {
+        self.primary.write_file(rel_path, content)?;
+        self.enqueue(MirrorOp::Write {
+            rel_path: rel_path.to_string(),
+            content: content.to_string(),
+            commit: None,
+        });
+        Ok(())
+    }
+
+    fn read_file(&self, rel_path: &str) -> Result<String> {
+        self.primary.read_file(rel_path)
+    }
+
+    fn delete_file(&self, rel_path: &str) -> Result<()> {
+        self.primary.delete_file(rel_path)?;
+        self.enqueue(MirrorOp::Delete {
+            rel_path: rel_path.to_string(),
+            commit: None,
+        });
+        Ok(())
+    }
+
+    fn write_file_with_commit(
+        &self,
+        rel_path: &str,
+        content: &str,
+        commit: &CommitInfo,
+    ) -> Result<()> {
+        self.primary
+            .write_file_with_commit(rel_path, content, commit)?;
+        self.enqueue(MirrorOp::Write {
+            rel_path: rel_path.to_string(),
+            content: content.to_string(),
+            commit: Some(commit.clone()),
+        });
+        Ok(())
+    }
+
+    fn commit_transaction(&self, transaction: &Transaction, commit: &CommitInfo) -> Result<()> {
+        self.primary.commit_transaction(transaction, commit)?;
+        self.enqueue(MirrorOp::Transaction {
+            transaction: transaction.clone(),
+            commit: commit.clone(),
+        });
+        Ok(())
+    }
+
+    fn delete_file_with_commit(&self, rel_path: &str, commit: &CommitInfo) -> Result<()> {
+        self.primary.delete_file_with_commit(rel_path, commit)?;
+        self.enqueue(MirrorOp::Delete {
+            rel_path: rel_path.to_string(),
+            commit: Some(commit.clone()),
+        });
+        Ok(())
+    }
+
+    fn rename_file(&self, from: &str, to: &str, content: &str, commit: &CommitInfo) -> Result<()> {
+        self.primary.rename_file(from, to, content, commit)?;
+        self.enqueue(MirrorOp::Rename {
+            from: from.to_string(),
+            to: to.to_string(),
+            content: content.to_string(),
+            commit: commit.clone(),
+        });
+        Ok(())
+    }
+
+    fn remove_empty_dirs(&self, rel_dir: &str) -> Result<bool> {
+        let removed = self.primary.remove_empty_dirs(rel_dir)?;
+        self.enqueue(MirrorOp::RemoveEmptyDirs(rel_dir.to_string()));
+        Ok(removed)
+    }
+
+    fn supports_drafts(&self) -> bool {
+        self.primary.supports_drafts()
+    }
+
+    fn write_draft(
+        &self,
+        draft_id: &str,
+        rel_path: &str,
+        content: &str,
+        commit: &CommitInfo,
+    ) -> Result<()> {
+        self.primary
+            .write_draft(draft_id, rel_path, content, commit)
+    }
+
+    fn list_drafts(&self) -> Result<Vec<DraftFile>> {
+        self.primary.list_drafts()
+    }
+
+    fn publish_draft(&self, draft_id: &str, commit: &CommitInfo) -> Result<DraftFile> {
+        let published = self.primary.publish_draft(draft_id, commit)?;
+        self.enqueue(MirrorOp::Write {
+            rel_path: published.rel_path.clone(),
+            content: published.content.clone(),
+            commit: Some(commit.clone()),
+        });
+        Ok(published)
+    }
+
+    fn discard_draft(&self, draft_id: &str) -> Result<bool> {
+        self.primary.discard_draft(draft_id)
+    }
+
+    fn supports_versions(&self) -> bool {
+        self.primary.supports_versions()
+    }
+
+    fn tag_version(
+        &self,
+        recipe_id: &str,
+        name: &str,
+        rel_path: &str,
+        commit: &CommitInfo,
+    ) -> Result<VersionTag> {
+        self.primary.tag_version(recipe_id, name, rel_path, commit)
+    }
+
+    fn list_versions(&self, recipe_id: &str) -> Result<Vec<VersionTag>> {
+        self.primary.list_versions(recipe_id)
+    }
+
+    fn read_version(&self, recipe_id: &str, name: &str, rel_path: &str) -> Result<Option<String>> {
+        self.primary.read_version(recipe_id, name, rel_path)
+    }
+
+    fn supports_blame(&self) -> bool {
+        self.primary.supports_blame()
+    }
+
+    fn blame_file(&self, rel_path: &str) -> Result<Vec<BlameLine>> {
+        self.primary.blame_file(rel_path)
+    }
+
+    fn supports_maintenance(&self) -> bool {
+        self.primary.supports_maintenance()
+    }
+
+    fn run_maintenance(&self) -> Result<GcReport> {
+        self.primary.run_maintenance()
+    }
+
+    fn head_revision(&self) -> Result<Option<String>> {
+        self.primary.head_revision()
+    }
+
+    fn last_modified(&self, rel_paths: &[String]) -> Result<HashMap<String, LastModified>> {
+        self.primary.last_modified(rel_paths)
+    }
+
+    fn stat(&self, rel_path: &str) -> Result<Option<FileStat>> {
+        self.primary.stat(rel_path)
+    }
+
+    fn changed_files(&self, revision: &str) -> Result<Option<FileChanges>> {
+        self.primary.changed_files(revision)
+    }
+
+    fn read_only_dirs(&self) -> Result<Vec<String>> {
+        self.primary.read_only_dirs()
+    }
+
+    fn discover_files(&self) -> Result<Vec<(String, FileStat)>> {
+        self.primary.discover_files()
+    }
+
+    fn discover_menu_files(&self) -> Result<Vec<String>> {
+        self.primary.discover_menu_files()
+    }
+
+    fn list_files(&self, rel_dir: &str) -> Result<Vec<String>> {
+        self.primary.list_files(rel_dir)
+    }
+
+    fn check_mirror(&self, skip_dirs: &[&str]) -> Result<Option<MirrorReport>> {
+        self.compare(skip_dirs).map(Some)
+    }
+
+    fn repair_mirror(&self, skip_dirs: &[&str]) -> Result<Option<MirrorReport>> {
+        let report = self.compare(skip_dirs)?;
+        let commit = CommitInfo {
+            message: "Repair mirror".to_string(),
+            author: None,
+        };
+        let mut transaction = Transaction::new();
+        for rel_path in report.missing.iter().chain(&report.different) {
+            transaction.write(rel_path, &self.primary.read_file(rel_path)?);
+        }
+        for rel_path in &report.extra {
+            transaction.delete(rel_path);
+        }
+        if !transaction.is_empty() {
+            self.enqueue(MirrorOp::Transaction {
+                transaction,
+                commit,
+            });
+        }
+        self.check_mirror(skip_dirs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::DiskStorage;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_changes_are_mirrored() -> Result<()> {
+        let (primary_dir, mirror_dir) = (TempDir::new()?, TempDir::new()?);
+        let storage = MirroredStorage::new(
+            Box::new(DiskStorage::new(primary_dir.path())?),
+            Box::new(DiskStorage::new(mirror_dir.path())?),
+        );
+        let mirror = DiskStorage::new(mirror_dir.path())?;
+        let commit = CommitInfo::default();
+
+        storage.write_file_with_commit("soups/pea.cook", "@peas{500%g}", &commit)?;
+        storage.write_file_with_commit("soups/leek.cook", "@leeks{2}", &commit)?;
+        storage.rename_file("soups/leek.cook", "leek.cook", "@leeks{3}", &commit)?;
+        storage.delete_file_with_commit("soups/pea.cook", &commit)?;
+
+        let report = storage.check_mirror(&[])?.unwrap();
+        assert!(report.is_consistent(), "{:?}", report);
+        assert_eq!(mirror.read_file("leek.cook")?, "@leeks{3}");
+        assert!(!mirror_dir.path().join("soups").exists());
+
+        // Drift made behind the storage's back is found and repaired
+        mirror.write_file("stray.cook", "@salt")?;
+        mirror.write_file("leek.cook", "@leeks{1}")?;
+        std::fs::write(primary_dir.path().join("new.cook"), "@eggs{2}")?;
+        std::fs::create_dir_all(primary_dir.path().join("skipped"))?;
+        std::fs::write(primary_dir.path().join("skipped/upload.bin"), "chunk")?;
+        let report = storage.check_mirror(&["skipped"])?.unwrap();
+        assert_eq!(report.missing, vec!["new.cook"]);
+        assert_eq!(report.different, vec!["leek.cook"]);
+        assert_eq!(report.extra, vec!["stray.cook"]);
+
+        let report = storage.repair_mirror(&["skipped"])?.unwrap();
+        assert!(report.is_consistent(), "{:?}", report);
+        assert_eq!(mirror.read_file("leek.cook")?, "@leeks{3}");
+        Ok(())
+    }
+}
@@ -1,11 +1,276 @@
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
 
 pub mod disk;
 pub mod git;
+pub mod metered;
+pub mod mirrored;
 
 pub use disk::DiskStorage;
-pub use git::GitStorage;
+pub use git::{GitOptions, GitStorage};
+pub use metered::MeteredStorage;
+pub use mirrored::MirroredStorage;
+
+pub use crate::git::{CommitAuthor, GcReport};
+
+/// Where disk storage stages transactions and sets aside the files they replace
+/// (relative to the data dir); hidden, so never discovered as recipes
+pub(crate) const STAGING_DIR: &str = ".cooklang-store/staging";
+
+/// Details recorded alongside a change by backends with history (ignored by disk storage)
+#[derive(Debug, Clone, Default)]
+pub struct CommitInfo {
+    /// Commit message (subject, optionally followed by a blank line and a body)
+    pub message: String,
+    /// Author and committer, `None` for the default store identity
+    pub author: Option<CommitAuthor>,
+}
+
+/// One file change in a [`Transaction`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum StagedChange {
+    Write {
+        rel_path: String,
+        content: String,
+    },
+    Delete {
+        rel_path: String,
+    },
+    /// Move `from` to `to` with (possibly updated) `content`
+    Rename {
+        from: String,
+        to: String,
+        content: String,
+    },
+}
+
+/// File changes to be stored together, all or none
+///
+/// Staging a change touches nothing; the changes are made, in order, when the
+/// transaction is passed to [`RecipeStorage::commit_transaction`]. Dropping a
+/// transaction instead rolls it back.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Transaction {
+    changes: Vec<StagedChange>,
+}
+
+impl Transaction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn write(&mut self, rel_path: &str, content: &str) -> &mut Self {
+        self.changes.push(StagedChange::Write {
+            rel_path: rel_path.to_string(),
+            content: content.to_string(),
+        });
+        self
+    }
+
+    pub fn delete(&mut self, rel_path: &str) -> &mut Self {
+        self.changes.push(StagedChange::Delete {
+            rel_path: rel_path.to_string(),
+        });
+        self
+    }
+
+    pub fn rename(&mut self, from: &str, to: &str, content: &str) -> &mut Self {
+        self.changes.push(StagedChange::Rename {
+            from: from.to_string(),
+            to: to.to_string(),
+            content: content.to_string(),
+        });
+        self
+    }
+
+    pub fn changes(&self) -> &[StagedChange] {
+        &self.changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Every path the changes touch, each once, in the order first touched
+    pub fn paths(&self) -> Vec<&str> {
+        let mut paths: Vec<&str> = Vec::new();
+        for change in &self.changes {
+            let touched = match change {
+                StagedChange::Write { rel_path, .. } | StagedChange::Delete { rel_path } => {
+                    vec![rel_path.as_str()]
+                }
+                StagedChange::Rename { from, to, .. } => vec![from.as_str(), to.as_str()],
+            };
+            for path in touched {
+                if !paths.contains(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+        paths
+    }
+
+    /// Paths the transaction leaves without a file: deleted or moved away from
+    pub fn vacated_paths(&self) -> Vec<&str> {
+        self.paths()
+            .into_iter()
+            .filter(|path| {
+                let last = self.changes.iter().rev().find_map(|change| match change {
+                    StagedChange::Write { rel_path, .. } if rel_path == path => Some(true),
+                    StagedChange::Delete { rel_path } if rel_path == path => Some(false),
+                    StagedChange::Rename { to, .. } if to == path => Some(true),
+                    StagedChange::Rename { from, .. } if from == path => Some(false),
+                    _ => None,
+                });
+                last == Some(false)
+            })
+            .collect()
+    }
+}
+
+/// A recipe file edited on a draft branch, not yet on the main branch
+#[derive(Debug, Clone)]
+pub struct DraftFile {
+    pub draft_id: String,
+    pub rel_path: String,
+    pub content: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Publishing a draft failed because the main branch changed the same file
+#[derive(Debug, thiserror::Error)]
+#[error("draft conflicts with changes to {path} on the main branch")]
+pub struct DraftConflict {
+    pub path: String,
+}
+
+/// A named version of a recipe file, frozen at a commit
+#[derive(Debug, Clone)]
+pub struct VersionTag {
+    pub name: String,
+    /// Commit the version points at
+    pub commit_id: String,
+    pub message: String,
+    /// Who created the version, when recorded
+    pub tagger: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Tagging a version failed because the recipe already has one with that name
+#[derive(Debug, thiserror::Error)]
+#[error("version '{name}' already exists")]
+pub struct VersionExists {
+    pub name: String,
+}
+
+/// A move would put a file where one already exists
+#[derive(Debug, thiserror::Error)]
+#[error("{path} already exists")]
+pub struct PathTaken {
+    pub path: String,
+}
+
+/// Who last changed one line of a file
+#[derive(Debug, Clone)]
+pub struct BlameLine {
+    /// 1-based line number
+    pub line: usize,
+    pub content: String,
+    /// Last commit touching the line, `None` while the change is uncommitted
+    pub commit_id: Option<String>,
+    pub author: Option<String>,
+    pub committed_at: Option<DateTime<Utc>>,
+    /// First line of the commit message
+    pub summary: Option<String>,
+}
+
+/// When a file last changed, and who changed it if the backend records that
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LastModified {
+    pub at: DateTime<Utc>,
+    /// Commit author, `None` on disk storage and for uncommitted changes
+    pub author: Option<String>,
+    /// Commit message, `None` on disk storage and for uncommitted changes
+    pub message: Option<String>,
+    /// Commit ID, `None` on disk storage and for uncommitted changes
+    pub revision: Option<String>,
+}
+
+/// Size and modification time of a stored file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileStat {
+    pub size: u64,
+    pub modified: DateTime<Utc>,
+    /// Last commit touching the file, `None` on disk storage and while it is uncommitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<LastModified>,
+}
+
+impl FileStat {
+    /// Whether `other` has the same size and modification time, i.e. the file is
+    /// most likely unchanged (commit info is not compared)
+    pub fn same_file(&self, other: &FileStat) -> bool {
+        self.size == other.size && self.modified == other.modified
+    }
+
+    /// The last commit, or the modification time while the file is uncommitted (and on
+    /// disk storage)
+    pub fn last_modified(&self) -> LastModified {
+        self.commit.clone().unwrap_or(LastModified {
+            at: self.modified,
+            author: None,
+            message: None,
+            revision: None,
+        })
+    }
+}
+
+/// Recipe files that differ from an earlier revision
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileChanges {
+    /// Added or modified `.cook` files
+    pub updated: Vec<String>,
+    /// `.cook` files that no longer exist
+    pub deleted: Vec<String>,
+}
+
+/// A change touched a file in a read-only directory (see [`RecipeStorage::read_only_dirs`])
+#[derive(Debug, thiserror::Error)]
+#[error("{path} is read-only")]
+pub struct ReadOnlyPath {
+    pub path: String,
+}
+
+/// Maintenance was requested while a previous run is still going
+#[derive(Debug, thiserror::Error)]
+#[error("repository maintenance is already running")]
+pub struct MaintenanceRunning;
+
+/// How a storage mirror differs from its primary (see [`MirroredStorage`])
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MirrorReport {
+    /// Files the mirror lacks
+    pub missing: Vec<String>,
+    /// Files whose content differs on the mirror
+    pub different: Vec<String>,
+    /// Files only the mirror has
+    pub extra: Vec<String>,
+    /// Changes still waiting to be copied when the files were compared
+    pub pending: usize,
+    /// Copies that failed since startup
+    pub failures: u64,
+    pub last_error: Option<String>,
+}
+
+impl MirrorReport {
+    /// Whether the mirror holds the same files as the primary
+    pub fn is_consistent(&self) -> bool {
+        self.missing.is_empty() && self.different.is_empty() && self.extra.is_empty()
+    }
+}
 
 /// Trait for recipe file storage backends
 pub trait RecipeStorage: Send + Sync {
@@ -18,18 +283,445 @@ pub trait RecipeStorage: Send + Sync {
     /// Delete a file from storage
     fn delete_file(&self, rel_path: &str) -> Result<()>;
 
-    /// Discover all .cook files in storage
-    fn discover_files(&self) -> Result<Vec<String>>;
+    /// Write a file, recording `commit` if the backend keeps history
+    fn write_file_with_commit(
+        &self,
+        rel_path: &str,
+        content: &str,
+        _commit: &CommitInfo,
+    ) -> Result<()> {
+        self.write_file(rel_path, content)
+    }
+
+    /// Write several files as one change, recording a single `commit` if the backend keeps history
+    fn write_files_with_commit(
+        &self,
+        files: &[(String, String)],
+        commit: &CommitInfo,
+    ) -> Result<()> {
+        let mut transaction = Transaction::new();
+        for (rel_path, content) in files {
+            transaction.write(rel_path, content);
+        }
+        self.commit_transaction(&transaction, commit)
+    }
+
+    /// Make every change in `transaction`, or none of them, recording a single `commit`
+    /// if the backend keeps history
+    ///
+    /// If a change fails, the ones already made are undone before the error is
+    /// returned. The default makes the changes one by one and writes back what it
+    /// touched on failure; backends that can stage changes do so instead.
+    fn commit_transaction(&self, transaction: &Transaction, commit: &CommitInfo) -> Result<()> {
+        let originals: Vec<(&str, Option<String>)> = transaction
+            .paths()
+            .into_iter()
+            .map(|rel_path| (rel_path, self.read_file(rel_path).ok()))
+            .collect();
+        let result = transaction
+            .changes()
+            .iter()
+            .try_for_each(|change| match change {
+                StagedChange::Write { rel_path, content } => {
+                    self.write_file_with_commit(rel_path, content, commit)
+                }
+                StagedChange::Delete { rel_path } => self.delete_file_with_commit(rel_path, commit),
+                StagedChange::Rename { from, to, content } => {
+                    self.rename_file(from, to, content, commit)
+                }
+            });
+        if result.is_err() {
+            for (rel_path, original) in originals {
+                let restored = match original {
+                    Some(content) => self.write_file(rel_path, &content),
+                    None => self.delete_file(rel_path),
+                };
+                if let Err(e) = restored {
+                    tracing::error!("Failed to roll back {}: {}", rel_path, e);
+                }
+            }
+        }
+        result
+    }
+
+    /// Delete a file, recording `commit` if the backend keeps history
+    fn delete_file_with_commit(&self, rel_path: &str, _commit: &CommitInfo) -> Result<()> {
+        self.delete_file(rel_path)
+    }
+
+    /// Move a file to `to` with (possibly updated) `content`, as a single change
+    ///
+    /// Backends with history record this as one rename so `git log --follow` keeps
+    /// working; the default writes the new file and then deletes the old one.
+    fn rename_file(&self, from: &str, to: &str, content: &str, commit: &CommitInfo) -> Result<()> {
+        self.write_file_with_commit(to, content, commit)?;
+        self.delete_file_with_commit(from, commit)
+    }
+
+    /// Move several files (`from`, `to`, `content`) as one change, all or none
+    fn rename_files_with_commit(
+        &self,
+        renames: &[(String, String, String)],
+        commit: &CommitInfo,
+    ) -> Result<()> {
+        let mut transaction = Transaction::new();
+        for (from, to, content) in renames {
+            transaction.rename(from, to, content);
+        }
+        self.commit_transaction(&transaction, commit)
+    }
+
+    /// Remove directory `rel_dir` and the directories in it if none of them hold a file,
+    /// returning whether it is gone
+    ///
+    /// Git doesn't track directories, so this only tidies the working tree.
+    fn remove_empty_dirs(&self, _rel_dir: &str) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Whether the backend can keep drafts off the main branch
+    fn supports_drafts(&self) -> bool {
+        false
+    }
+
+    /// Save `content` at `rel_path` on draft `draft_id`, leaving the main branch untouched
+    fn write_draft(
+        &self,
+        _draft_id: &str,
+        _rel_path: &str,
+        _content: &str,
+        _commit: &CommitInfo,
+    ) -> Result<()> {
+        Err(anyhow!("Drafts require git storage"))
+    }
+
+    /// All drafts with their file as it currently stands on the draft
+    fn list_drafts(&self) -> Result<Vec<DraftFile>> {
+        Ok(Vec::new())
+    }
+
+    /// Merge a draft into the main branch and remove it, returning the published file
+    ///
+    /// Fails with [`DraftConflict`] if the main branch changed the same file meanwhile.
+    fn publish_draft(&self, _draft_id: &str, _commit: &CommitInfo) -> Result<DraftFile> {
+        Err(anyhow!("Drafts require git storage"))
+    }
+
+    /// Remove a draft without publishing it, returning whether it existed
+    fn discard_draft(&self, _draft_id: &str) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Whether the backend can tag named versions of recipes
+    fn supports_versions(&self) -> bool {
+        false
+    }
+
+    /// Tag the latest committed state of `rel_path` as version `name` of `recipe_id`
+    ///
+    /// Fails with [`VersionExists`] if the name is taken.
+    fn tag_version(
+        &self,
+        _recipe_id: &str,
+        _name: &str,
+        _rel_path: &str,
+        _commit: &CommitInfo,
+    ) -> Result<VersionTag> {
+        Err(anyhow!("Versions require git storage"))
+    }
+
+    /// All tagged versions of a recipe
+    fn list_versions(&self, _recipe_id: &str) -> Result<Vec<VersionTag>> {
+        Ok(Vec::new())
+    }
+
+    /// Content of `rel_path` at version `name`, `None` if there is no such version
+    fn read_version(
+        &self,
+        _recipe_id: &str,
+        _name: &str,
+        _rel_path: &str,
+    ) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Whether the backend can attribute lines to the changes that made them
+    fn supports_blame(&self) -> bool {
+        false
+    }
+
+    /// The commit and author that last touched each line of `rel_path`
+    fn blame_file(&self, _rel_path: &str) -> Result<Vec<BlameLine>> {
+        Err(anyhow!("Blame requires git storage"))
+    }
+
+    /// Whether the backend has housekeeping to run (git gc)
+    fn supports_maintenance(&self) -> bool {
+        false
+    }
+
+    /// Compact the backend's storage, e.g. pack loose git objects
+    ///
+    /// Blocks until done; fails with [`MaintenanceRunning`] if another run is in progress.
+    fn run_maintenance(&self) -> Result<GcReport> {
+        Err(anyhow!("Git maintenance requires git storage"))
+    }
+
+    /// Current revision of the stored files (the HEAD commit), if the backend tracks one
+    fn head_revision(&self) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// When each of `rel_paths` last changed (files that do not exist are left out)
+    ///
+    /// Git storage reports the last commit touching each file, or the modification time
+    /// while a file is uncommitted; disk storage reports modification times.
+    fn last_modified(&self, rel_paths: &[String]) -> Result<HashMap<String, LastModified>>;
+
+    /// Size and modification time of a file, `None` if it does not exist
+    ///
+    /// Git storage adds the last commit touching the file.
+    fn stat(&self, rel_path: &str) -> Result<Option<FileStat>>;
+
+    /// Recipe files changed since `revision`, including uncommitted edits
+    ///
+    /// `None` means the backend cannot tell (no history, or `revision` is unknown) and
+    /// the caller should fall back to [`discover_files`](Self::discover_files).
+    fn changed_files(&self, _revision: &str) -> Result<Option<FileChanges>> {
+        Ok(None)
+    }
+
+    /// Directories whose files are indexed but cannot be changed, relative to the root
+    ///
+    /// Git storage reports its submodules (e.g. a shared recipe pack); writes below them
+    /// fail with [`ReadOnlyPath`].
+    fn read_only_dirs(&self) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    /// Discover all .cook files in storage with their size and modification time
+    /// (hidden directories such as `.trash/` are skipped, and no commit info is looked up)
+    fn discover_files(&self) -> Result<Vec<(String, FileStat)>>;
+
+    /// Discover all .menu files in storage (hidden directories are skipped)
+    fn discover_menu_files(&self) -> Result<Vec<String>>;
+
+    /// List all files below a directory, as paths relative to the storage root
+    fn list_files(&self, rel_dir: &str) -> Result<Vec<String>>;
+
+    /// Compare the files with the mirror changes are copied to, `None` without one
+    ///
+    /// Waits briefly for queued copies first. Files below `skip_dirs` (kept outside
+    /// storage) and `.git` are left out.
+    fn check_mirror(&self, _skip_dirs: &[&str]) -> Result<Option<MirrorReport>> {
+        Ok(None)
+    }
+
+    /// Copy files that are missing or differ to the mirror and delete the ones only it
+    /// has, then compare again; `None` without a mirror
+    fn repair_mirror(&self, _skip_dirs: &[&str]) -> Result<Option<MirrorReport>> {
+        Ok(None)
+    }
 }
 
-/// Create a storage backend based on configuration
+/// Modification times of the files among `rel_paths` that exist below `root`
+pub(crate) fn modification_times(
+    root: &Path,
+    rel_paths: &[String],
+) -> HashMap<String, LastModified> {
+    rel_paths
+        .iter()
+        .filter_map(|rel_path| {
+            let modified = std::fs::metadata(root.join(rel_path))
+                .and_then(|metadata| metadata.modified())
+                .ok()?;
+            Some((
+                rel_path.clone(),
+                LastModified {
+                    at: modified.into(),
+                    author: None,
+                    message: None,
+                    revision: None,
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Create a storage backend based on configuration (disk storage ignores `git_options`)
+///
+/// Directories left empty by deletes and moves are removed unless `keep_empty_dirs` is set.
 pub async fn create_storage(
     storage_type: &str,
     repo_path: &Path,
+    git_options: &GitOptions,
+    keep_empty_dirs: bool,
 ) -> Result<Box<dyn RecipeStorage>> {
     match storage_type {
-        "git" => Ok(Box::new(GitStorage::new(repo_path)?)),
-        _ => Ok(Box::new(DiskStorage::new(repo_path)?)),
+        "git" => Ok(Box::new(
+            GitStorage::with_options(repo_path, git_options.clone())?
+                .keep_empty_dirs(keep_empty_dirs),
+        )),
+        _ => Ok(Box::new(
+            DiskStorage::new(repo_path)?.keep_empty_dirs(keep_empty_dirs),
+        )),
+    }
+}
+
+/// Whether `rel_path` is `dir` itself or inside it
+pub(crate) fn is_below(rel_path: &str, dir: &str) -> bool {
+    rel_path
+        .strip_prefix(dir)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Whether a walked entry is hidden (e.g. `.git`, `.trash`); the walk root itself never is
+pub(crate) fn is_hidden(entry: &walkdir::DirEntry) -> bool {
+    entry.depth() > 0
+        && entry
+            .file_name()
+            .to_str()
+            .map(|name| name.starts_with('.'))
+            .unwrap_or(false)
+}
+
+/// Size and modification time of `root/rel_path`, `None` if it is missing or not a file
+pub(crate) fn file_stat(root: &Path, rel_path: &str) -> Result<Option<FileStat>> {
+    match std::fs::metadata(root.join(rel_path)) {
+        Ok(metadata) if metadata.is_file() => Ok(Some(stat_of(&metadata)?)),
+        Ok(_) => Ok(None),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("Failed to stat {}", rel_path)),
+    }
+}
+
+fn stat_of(metadata: &std::fs::Metadata) -> Result<FileStat> {
+    Ok(FileStat {
+        size: metadata.len(),
+        modified: metadata.modified()?.into(),
+        commit: None,
+    })
+}
+
+/// Find all files with the given extension below `root` with their stats, skipping
+/// hidden entries
+pub(crate) fn discover_with_extension(
+    root: &Path,
+    extension: &str,
+) -> Result<Vec<(String, FileStat)>> {
+    let mut files = Vec::new();
+    for entry in walkdir::WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| !is_hidden(e))
+        .filter_map(|e| e.ok())
+    {
+        crate::deadline::check(files.len(), None)?;
+        if entry.file_type().is_file()
+            && entry.path().extension().and_then(|s| s.to_str()) == Some(extension)
+        {
+            let relative_path = entry
+                .path()
+                .strip_prefix(root)?
+                .to_string_lossy()
+                .to_string();
+            files.push((relative_path, stat_of(&entry.metadata()?)?));
+        }
+    }
+
+    Ok(files)
+}
+
+/// List all files below `root/rel_dir`, as paths relative to `root`
+pub(crate) fn walk_files(root: &Path, rel_dir: &str) -> Result<Vec<String>> {
+    let dir = root.join(rel_dir);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files = Vec::new();
+    for entry in walkdir::WalkDir::new(&dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+    {
+        crate::deadline::check(files.len(), None)?;
+        if entry.file_type().is_file() {
+            let relative_path = entry
+                .path()
+                .strip_prefix(root)?
+                .to_string_lossy()
+                .to_string();
+            files.push(relative_path);
+        }
+    }
+
+    Ok(files)
+}
+
+/// [`RecipeStorage::remove_empty_dirs`] for a backend rooted at `root`
+pub(crate) fn remove_empty_dirs(root: &Path, rel_dir: &str) -> Result<bool> {
+    let dir = root.join(rel_dir);
+    if rel_dir.is_empty() || !dir.is_dir() {
+        return Ok(false);
+    }
+
+    // Deepest first, so directories holding only empty directories go too
+    for entry in walkdir::WalkDir::new(&dir)
+        .contents_first(true)
+        .into_iter()
+        .filter_map(|e| e.ok())
+    {
+        if entry.file_type().is_dir() && std::fs::read_dir(entry.path())?.next().is_none() {
+            std::fs::remove_dir(entry.path())
+                .with_context(|| format!("Failed to remove directory: {}", rel_dir))?;
+        }
+    }
+    Ok(!dir.exists())
+}
+
+/// Move `root/from` to `root/to` with `content`, in a single `rename` so the file is
+/// never at both paths or at neither
+///
+/// Unlike writing the new path and deleting the old one, this also works for case-only
+/// renames on case-insensitive file systems. If `from` is already gone the file is just
+/// written at `to`.
+pub(crate) fn move_file(root: &Path, from: &str, to: &str, content: &str) -> Result<()> {
+    let (from_path, to_path) = (root.join(from), root.join(to));
+    if let Some(parent) = to_path.parent() {
+        std::fs::create_dir_all(parent).context("Failed to create recipe directory")?;
+    }
+    if !from_path.exists() {
+        return std::fs::write(&to_path, content)
+            .with_context(|| format!("Failed to write file: {}", to));
+    }
+    if std::fs::read_to_string(&from_path).ok().as_deref() != Some(content) {
+        std::fs::write(&from_path, content)
+            .with_context(|| format!("Failed to write file: {}", from))?;
+    }
+    std::fs::rename(&from_path, &to_path)
+        .with_context(|| format!("Failed to move {} to {}", from, to))
+}
+
+/// Remove the directories holding `rel_path`, deepest first, while they are empty
+///
+/// Called once a file is deleted or moved away. `root` itself is always kept; a
+/// directory that can't be removed is logged and left in place.
+pub(crate) fn remove_empty_parents(root: &Path, rel_path: &str) {
+    let mut dir = Path::new(rel_path).parent();
+    while let Some(rel_dir) = dir.filter(|dir| !dir.as_os_str().is_empty()) {
+        let full_path = root.join(rel_dir);
+        let empty = std::fs::read_dir(&full_path).is_ok_and(|mut entries| entries.next().is_none());
+        if !empty {
+            break;
+        }
+        if let Err(e) = std::fs::remove_dir(&full_path) {
+            tracing::warn!(
+                "Failed to remove empty directory {}: {}",
+                rel_dir.display(),
+                e
+            );
+            break;
+        }
+        dir = rel_dir.parent();
     }
 }
 
@@ -41,7 +733,8 @@ mod tests {
     #[tokio::test]
     async fn test_create_disk_storage() -> Result<()> {
         let temp_dir = TempDir::new()?;
-        let storage = create_storage("disk", temp_dir.path()).await?;
+        let storage =
+            create_storage("disk", temp_dir.path(), &GitOptions::default(), false).await?;
 
         // Should create storage without error
         assert!(storage.discover_files()?.is_empty());
@@ -53,17 +746,91 @@ mod tests {
     async fn test_create_disk_storage_default() -> Result<()> {
         let temp_dir = TempDir::new()?;
         // Omitting "disk" explicitly, using empty string which defaults to disk
-        let storage = create_storage("unknown", temp_dir.path()).await?;
+        let storage =
+            create_storage("unknown", temp_dir.path(), &GitOptions::default(), false).await?;
 
         assert!(storage.discover_files()?.is_empty());
 
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_discover_skips_hidden_directories() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let storage =
+            create_storage("disk", temp_dir.path(), &GitOptions::default(), false).await?;
+
+        storage.write_file("recipes/cake.cook", "cake")?;
+        storage.write_file(".trash/20240101T000000000Z/recipes/old.cook", "old")?;
+
+        assert_eq!(
+            storage
+                .discover_files()?
+                .into_iter()
+                .map(|(rel_path, _)| rel_path)
+                .collect::<Vec<_>>(),
+            vec!["recipes/cake.cook"]
+        );
+        assert_eq!(
+            storage.list_files(".trash")?,
+            vec![".trash/20240101T000000000Z/recipes/old.cook"]
+        );
+        assert!(storage.list_files("missing")?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_remove_empty_dirs() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let storage =
+            create_storage("disk", temp_dir.path(), &GitOptions::default(), false).await?;
+
+        storage.write_file("recipes/desserts/cakes/sponge.cook", "sponge")?;
+        storage.write_file("recipes/desserts/pies/apple.cook", "apple")?;
+        storage.delete_file("recipes/desserts/cakes/sponge.cook")?;
+        std::fs::create_dir_all(temp_dir.path().join("recipes/desserts/cakes/layered/empty"))?;
+
+        assert!(storage.remove_empty_dirs("recipes/desserts/cakes")?);
+        assert!(!temp_dir.path().join("recipes/desserts/cakes").exists());
+        // Directories holding files stay
+        assert!(!storage.remove_empty_dirs("recipes/desserts")?);
+        assert_eq!(
+            storage.list_files("recipes")?,
+            vec!["recipes/desserts/pies/apple.cook"]
+        );
+        assert!(!storage.remove_empty_dirs("missing")?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_discover_menu_files() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let storage =
+            create_storage("disk", temp_dir.path(), &GitOptions::default(), false).await?;
+
+        storage.write_file("recipes/cake.cook", "cake")?;
+        storage.write_file("menus/week.menu", "@./recipes/cake{}")?;
+        storage.write_file(".trash/20240101T000000000Z/menus/old.menu", "old")?;
+
+        assert_eq!(storage.discover_menu_files()?, vec!["menus/week.menu"]);
+        assert_eq!(
+            storage
+                .discover_files()?
+                .into_iter()
+                .map(|(rel_path, _)| rel_path)
+                .collect::<Vec<_>>(),
+            vec!["recipes/cake.cook"]
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_create_git_storage() -> Result<()> {
         let temp_dir = TempDir::new()?;
-        let storage = create_storage("git", temp_dir.path()).await?;
+        let storage = create_storage("git", temp_dir.path(), &GitOptions::default(), false).await?;
 
         assert!(storage.discover_files()?.is_empty());
 
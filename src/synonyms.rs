@@ -0,0 +1,117 @@
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+
+/// Groups of ingredient names that mean the same thing
+///
+/// Read from a text file with one group per line, names separated by `=`; the first
+/// name of a group is its canonical name. Blank lines and lines starting with `#` are
+/// ignored, and names are compared lowercased:
+///
+/// ```text
+/// # canonical = other names
+/// scallion = green onion = spring onion
+/// coriander = cilantro
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Synonyms {
+    /// Every name of a group → the group's canonical name
+    canonical: HashMap<String, String>,
+    /// Canonical name → every name of the group, canonical first
+    groups: HashMap<String, Vec<String>>,
+}
+
+impl Synonyms {
+    /// Parse a synonyms file
+    ///
+    /// A line with a single name, or a name listed in two groups, is an error.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut synonyms = Synonyms::default();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut names: Vec<String> = Vec::new();
+            for name in line.split('=').map(name_key) {
+                if name.is_empty() {
+                    bail!("Line {}: empty ingredient name", number + 1);
+                }
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+            if names.len() < 2 {
+                bail!("Line {}: a group needs at least two names", number + 1);
+            }
+            for name in &names {
+                if let Some(canonical) = synonyms.canonical.get(name) {
+                    bail!(
+                        "Line {}: '{}' is already a synonym of '{}'",
+                        number + 1,
+                        name,
+                        canonical
+                    );
+                }
+                synonyms.canonical.insert(name.clone(), names[0].clone());
+            }
+            synonyms.groups.insert(names[0].clone(), names);
+        }
+        Ok(synonyms)
+    }
+
+    /// Number of synonym groups
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Canonical name of an ingredient (the name itself, lowercased, if it has no synonyms)
+    pub fn canonical(&self, name: &str) -> String {
+        let name = name_key(name);
+        self.canonical.get(&name).cloned().unwrap_or(name)
+    }
+
+    /// Every name meaning the same as `name`, canonical first (just `name`, lowercased,
+    /// if it has no synonyms)
+    pub fn names(&self, name: &str) -> Vec<String> {
+        let name = name_key(name);
+        match self.canonical.get(&name) {
+            Some(canonical) => self.groups[canonical].clone(),
+            None => vec![name],
+        }
+    }
+}
+
+fn name_key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_synonyms() -> Result<()> {
+        let synonyms = Synonyms::parse(
+            "# herbs\ncoriander = cilantro\n\nScallion = green onion = Spring Onion\n",
+        )?;
+        assert_eq!(synonyms.len(), 2);
+        assert_eq!(synonyms.canonical(" Green Onion"), "scallion");
+        assert_eq!(synonyms.canonical("scallion"), "scallion");
+        assert_eq!(synonyms.canonical("Garlic"), "garlic");
+        assert_eq!(
+            synonyms.names("spring onion"),
+            vec!["scallion", "green onion", "spring onion"]
+        );
+        assert_eq!(synonyms.names("garlic"), vec!["garlic"]);
+
+        assert!(Synonyms::parse("scallion").is_err());
+        assert!(Synonyms::parse("scallion = ").is_err());
+        assert!(Synonyms::parse("scallion = green onion\nspring onion = scallion").is_err());
+        Ok(())
+    }
+}
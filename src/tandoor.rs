@@ -0,0 +1,330 @@
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::archive::{read_zip, ZipEntry};
+use crate::import::{decimal_quantity, strip_nulls, ImportedIngredient, ImportedRecipe};
+
+/// A recipe in the JSON layout of Tandoor's `recipe.json` export files
+///
+/// Only the fields the importer reads; missing ones take their defaults.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct TandoorRecipe {
+    pub name: String,
+    pub description: Option<String>,
+    pub keywords: Vec<TandoorNamed>,
+    pub steps: Vec<TandoorStep>,
+    /// Active time in minutes
+    pub working_time: u32,
+    /// Passive time (baking, resting) in minutes
+    pub waiting_time: u32,
+    pub servings: u32,
+    /// What a serving is, e.g. `pieces`
+    pub servings_text: String,
+    pub source_url: Option<String>,
+    pub nutrition: Option<Value>,
+    /// Whether the export holds an image for the recipe (set by the reader)
+    #[serde(skip)]
+    pub has_image: bool,
+}
+
+/// A keyword, food or unit
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct TandoorNamed {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct TandoorStep {
+    /// Section heading shown above the step
+    pub name: String,
+    pub instruction: String,
+    pub ingredients: Vec<TandoorIngredient>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct TandoorIngredient {
+    pub food: Option<TandoorNamed>,
+    pub unit: Option<TandoorNamed>,
+    /// A number, or a decimal string (`"200.000"`) in older exports
+    pub amount: Value,
+    pub note: Option<String>,
+    pub original_text: Option<String>,
+    /// A heading in the ingredient list rather than an ingredient
+    pub is_header: bool,
+    pub no_amount: bool,
+}
+
+/// Read the recipes of a Tandoor export, as `(name, recipe)`
+///
+/// Tandoor's default export is a zip holding one zip per recipe, each with a
+/// `recipe.json` and the recipe's image; a zip with the `recipe.json` files directly
+/// is read too. Only an unreadable archive is an error; a damaged recipe is reported
+/// in its place so the others can still be imported.
+pub fn read_archive(bytes: &[u8]) -> Result<Vec<(String, Result<TandoorRecipe>)>> {
+    let entries = read_zip(bytes).context("Invalid Tandoor export")?;
+    let mut recipes = Vec::new();
+    for entry in &entries {
+        if entry.name.ends_with(".zip") {
+            let name = entry_stem(&entry.name, ".zip");
+            let recipe = read_zip(&entry.data)
+                .context("Invalid recipe zip")
+                .and_then(|files| from_files(&files));
+            recipes.push(named(name, recipe));
+        } else if entry.name.rsplit('/').next() == Some("recipe.json") {
+            // Images sit next to their recipe.json
+            let folder = entry.name.trim_end_matches("recipe.json");
+            let files: Vec<ZipEntry> = entries
+                .iter()
+                .filter(|file| {
+                    file.name
+                        .strip_prefix(folder)
+                        .is_some_and(|rest| !rest.contains('/'))
+                })
+                .cloned()
+                .collect();
+            let name = entry_stem(folder.trim_end_matches('/'), "");
+            recipes.push(named(name, from_files(&files)));
+        }
+    }
+    if recipes.is_empty() {
+        bail!("Not a Tandoor export: no recipe zips or recipe.json files");
+    }
+    Ok(recipes)
+}
+
+/// A recipe from the files of its export folder
+fn from_files(files: &[ZipEntry]) -> Result<TandoorRecipe> {
+    let json = files
+        .iter()
+        .find(|file| file.name.rsplit('/').next() == Some("recipe.json"))
+        .context("No recipe.json")?;
+    let value: Value = serde_json::from_slice(&json.data).context("Invalid recipe JSON")?;
+    if !value.is_object() {
+        bail!("Invalid recipe JSON: expected a recipe object");
+    }
+    let mut recipe: TandoorRecipe =
+        serde_json::from_value(strip_nulls(value)).context("Invalid recipe JSON")?;
+    recipe.has_image = files.iter().any(|file| {
+        let name = file.name.to_lowercase();
+        name.rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .starts_with("image")
+    });
+    Ok(recipe)
+}
+
+/// Report a recipe under its name, or under its file's name if it has none
+fn named(file_name: String, recipe: Result<TandoorRecipe>) -> (String, Result<TandoorRecipe>) {
+    match recipe {
+        Ok(recipe) if !recipe.name.trim().is_empty() => (recipe.name.clone(), Ok(recipe)),
+        recipe => (file_name, recipe),
+    }
+}
+
+fn entry_stem(path: &str, extension: &str) -> String {
+    let name = path.rsplit('/').next().unwrap_or_default();
+    name.trim_end_matches(extension).to_string()
+}
+
+/// Map a Tandoor recipe onto the importer's model
+///
+/// Each step's ingredients are marked up in that step, keeping Tandoor's food, amount
+/// and unit. Keywords become tags; Tandoor has no categories, so recipes land at the
+/// top level. Working and waiting time become prep and cook time. The image and
+/// nutrition are left out.
+pub fn to_imported(recipe: TandoorRecipe) -> ImportedRecipe {
+    let text = |value: Option<String>| {
+        value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let minutes = |value: u32| (value > 0).then(|| format!("{} minutes", value));
+    let mut dropped = Vec::new();
+    if recipe.has_image {
+        dropped.push("image".to_string());
+    }
+    if recipe.nutrition.is_some() {
+        dropped.push("nutrition".to_string());
+    }
+
+    let mut ingredients = Vec::new();
+    let mut steps = Vec::with_capacity(recipe.steps.len());
+    for (index, step) in recipe.steps.into_iter().enumerate() {
+        for ingredient in step.ingredients {
+            if ingredient.is_header {
+                continue;
+            }
+            let quantity = match &ingredient.amount {
+                _ if ingredient.no_amount => None,
+                Value::Number(amount) => amount.as_f64().and_then(decimal_quantity),
+                Value::String(amount) => amount.trim().parse().ok().and_then(decimal_quantity),
+                _ => None,
+            };
+            let parsed = match ingredient.food.and_then(|food| text(Some(food.name))) {
+                Some(name) => ImportedIngredient {
+                    name,
+                    quantity,
+                    unit: ingredient.unit.and_then(|unit| text(Some(unit.name))),
+                    note: text(ingredient.note),
+                    step: None,
+                },
+                None => {
+                    let Some(parsed) = text(ingredient.original_text)
+                        .or_else(|| text(ingredient.note))
+                        .and_then(|line| ImportedIngredient::parse(&line))
+                    else {
+                        continue;
+                    };
+                    parsed
+                }
+            };
+            ingredients.push(ImportedIngredient {
+                step: Some(index),
+                ..parsed
+            });
+        }
+        steps.push(match text(Some(step.name)) {
+            Some(title) => format!("{}: {}", title, step.instruction.trim()),
+            None => step.instruction,
+        });
+    }
+
+    let servings = (recipe.servings > 0).then(|| match text(Some(recipe.servings_text)) {
+        Some(unit) => format!("{} {}", recipe.servings, unit),
+        None => recipe.servings.to_string(),
+    });
+
+    ImportedRecipe {
+        title: recipe.name,
+        description: text(recipe.description),
+        ingredients,
+        steps,
+        servings,
+        prep_time: minutes(recipe.working_time),
+        cook_time: minutes(recipe.waiting_time),
+        total_time: None,
+        difficulty: None,
+        source: text(recipe.source_url),
+        notes: None,
+        categories: Vec::new(),
+        tags: recipe
+            .keywords
+            .into_iter()
+            .map(|keyword| keyword.name)
+            .collect(),
+        rating: None,
+        dropped,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::write_zip;
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn zip(files: Vec<(&str, Vec<u8>)>) -> Vec<u8> {
+        let entries: Vec<ZipEntry> = files
+            .into_iter()
+            .map(|(name, data)| ZipEntry {
+                name: name.to_string(),
+                data,
+            })
+            .collect();
+        write_zip(&entries, Utc::now()).unwrap()
+    }
+
+    #[test]
+    fn test_read_export() -> Result<()> {
+        let recipe = json!({
+            "name": "Focaccia",
+            "description": null,
+            "keywords": [{ "name": "Baking" }, { "name": "Italian" }],
+            "working_time": 20,
+            "waiting_time": 0,
+            "servings": 8,
+            "servings_text": "pieces",
+            "source_url": "https://example.com/focaccia",
+            "nutrition": null,
+            "steps": [
+                {
+                    "name": "",
+                    "instruction": "Mix the flour with the water.",
+                    "ingredients": [
+                        { "is_header": true, "note": "Dough" },
+                        {
+                            "food": { "name": "flour" },
+                            "unit": { "name": "g" },
+                            "amount": "500.000",
+                            "note": ""
+                        },
+                        { "food": { "name": "water" }, "unit": null, "amount": 0, "no_amount": true }
+                    ]
+                },
+                {
+                    "name": "Topping",
+                    "instruction": "Drizzle with oil.",
+                    "ingredients": [
+                        { "food": null, "unit": null, "amount": 0, "original_text": "2 tbsp olive oil" }
+                    ]
+                }
+            ]
+        });
+        let inner = zip(vec![
+            ("recipe.json", serde_json::to_vec(&recipe)?),
+            ("image.jpg", vec![0xff, 0xd8]),
+        ]);
+        let archive = zip(vec![
+            ("1.zip", inner),
+            ("2.zip", zip(vec![("recipe.json", b"[]".to_vec())])),
+        ]);
+
+        let recipes = read_archive(&archive)?;
+        assert_eq!(recipes.len(), 2);
+        assert_eq!(recipes[1].0, "2");
+        assert!(recipes[1].1.is_err());
+
+        let recipe = to_imported(recipes.into_iter().next().unwrap().1?);
+        assert_eq!(recipe.title, "Focaccia");
+        assert_eq!(recipe.servings.as_deref(), Some("8 pieces"));
+        assert_eq!(recipe.prep_time.as_deref(), Some("20 minutes"));
+        assert_eq!(recipe.cook_time, None);
+        assert_eq!(recipe.tags, vec!["Baking", "Italian"]);
+        assert!(recipe.categories.is_empty());
+        assert_eq!(recipe.dropped, vec!["image"]);
+        assert_eq!(recipe.steps[1], "Topping: Drizzle with oil.");
+        assert_eq!(
+            recipe.ingredients[0],
+            ImportedIngredient {
+                name: "flour".to_string(),
+                quantity: Some("500".to_string()),
+                unit: Some("g".to_string()),
+                note: None,
+                step: Some(0),
+            }
+        );
+        assert_eq!(recipe.ingredients[1].quantity, None);
+        assert_eq!(recipe.ingredients[2].name, "olive oil");
+        assert_eq!(recipe.ingredients[2].unit.as_deref(), Some("tbsp"));
+        assert_eq!(recipe.ingredients[2].step, Some(1));
+
+        // Recipe folders unpacked into one zip
+        let flat = zip(vec![(
+            "focaccia/recipe.json",
+            serde_json::to_vec(&json!({ "name": "Focaccia" }))?,
+        )]);
+        let recipes = read_archive(&flat)?;
+        assert_eq!(recipes[0].0, "Focaccia");
+        assert!(!recipes[0].1.as_ref().unwrap().has_image);
+
+        assert!(read_archive(&zip(vec![("notes.txt", b"hi".to_vec())])).is_err());
+        Ok(())
+    }
+}
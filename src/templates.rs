@@ -0,0 +1,150 @@
+use crate::parser::{extract_recipe_title, strip_front_matter};
+
+/// Directory of the data dir holding recipe templates (`templates/<name>.cook`)
+///
+/// Files here are skeletons, not recipes, so they are left out of the index.
+pub const TEMPLATES_DIR: &str = "templates";
+
+/// A Cooklang skeleton new recipes can start from
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    /// File stem, used in the API path (e.g. `bread`)
+    pub name: String,
+    pub content: String,
+    /// Shipped with the store rather than read from the data dir
+    pub builtin: bool,
+}
+
+impl Template {
+    /// Front matter `title` of the skeleton, if any
+    pub fn title(&self) -> Option<String> {
+        extract_recipe_title(&self.content).ok()
+    }
+}
+
+const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    (
+        "bread",
+        "---\ntitle: Bread\ntags: [bread, baking]\nservings: 1 loaf\nprep time: 30 minutes\ncook time: 40 minutes\n---\n\n\
+Mix @flour{500%g}, @water{350%ml}, @salt{10%g} and @yeast{7%g} in a #bowl{}.\n\n\
+Knead until smooth, then let rise for ~{1%hour}.\n\n\
+Shape, place in a #loaf tin{} and bake for ~{40%minutes}.\n",
+    ),
+    (
+        "stew",
+        "---\ntitle: Stew\ntags: [stew]\nservings: 4\nprep time: 20 minutes\ncook time: 2 hours\n---\n\n\
+Brown @meat{800%g} in @oil{2%tbsp} in a #dutch oven{}.\n\n\
+Add @onion{1}, @carrots{2} and @stock{1%l}, then simmer for ~{2%hours}.\n\n\
+Season with @salt{} and @pepper{} to taste.\n",
+    ),
+    (
+        "cocktail",
+        "---\ntitle: Cocktail\ntags: [cocktail, drinks]\nservings: 1\ntime: 5 minutes\n---\n\n\
+Add @spirit{50%ml}, @citrus juice{25%ml}, @syrup{15%ml} and @ice{} to a #shaker{}.\n\n\
+Shake for ~{15%seconds} and strain into a chilled #glass{}.\n",
+    ),
+];
+
+/// Templates shipped with the store (a data dir template of the same name replaces one)
+pub fn builtin_templates() -> Vec<Template> {
+    BUILTIN_TEMPLATES
+        .iter()
+        .map(|(name, content)| Template {
+            name: name.to_string(),
+            content: content.to_string(),
+            builtin: true,
+        })
+        .collect()
+}
+
+/// Template name of a `templates/<name>.cook` path, `None` for other paths
+pub fn template_name(git_path: &str) -> Option<&str> {
+    let name = git_path
+        .strip_prefix(TEMPLATES_DIR)?
+        .strip_prefix('/')?
+        .strip_suffix(".cook")?;
+    (!name.is_empty() && !name.contains('/')).then_some(name)
+}
+
+/// Whether a path is inside the templates directory (and so not a recipe)
+pub fn is_template_path(git_path: &str) -> bool {
+    git_path
+        .strip_prefix(TEMPLATES_DIR)
+        .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Recipe content from a template, with its front matter `title` set to `title`
+pub fn instantiate(template: &str, title: &str) -> String {
+    let title_line = format!("title: {}", yaml_string(title));
+    let body = strip_front_matter(template);
+
+    let Some(front_matter) = template
+        .trim_start()
+        .strip_prefix("---")
+        .and_then(|rest| rest.strip_suffix(body))
+        .and_then(|rest| rest.trim_end().strip_suffix("---"))
+    else {
+        return format!("---\n{}\n---\n\n{}", title_line, template.trim_start());
+    };
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut replaced = false;
+    for line in front_matter.lines().filter(|line| !line.trim().is_empty()) {
+        if !replaced && line.to_lowercase().starts_with("title:") {
+            lines.push(title_line.clone());
+            replaced = true;
+        } else {
+            lines.push(line.to_string());
+        }
+    }
+    if !replaced {
+        lines.insert(0, title_line);
+    }
+
+    format!("---\n{}\n---{}", lines.join("\n"), body)
+}
+
+/// A YAML scalar for `value`, quoted only when it would otherwise not read back as a string
+fn yaml_string(value: &str) -> String {
+    serde_yaml::to_string(value)
+        .map(|yaml| yaml.trim_end().to_string())
+        .unwrap_or_else(|_| format!("{:?}", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instantiate_sets_title() {
+        for template in builtin_templates() {
+            let content = instantiate(&template.content, "Grandma's Rye: Take 2");
+            assert_eq!(
+                extract_recipe_title(&content).unwrap(),
+                "Grandma's Rye: Take 2"
+            );
+            assert_eq!(
+                strip_front_matter(&content),
+                strip_front_matter(&template.content)
+            );
+            assert!(crate::parser::parse_recipe(&content, "test").is_ok());
+        }
+
+        let content = instantiate("---\nservings: 2\n---\n\nStir @sugar{}.\n", "Yes");
+        assert_eq!(extract_recipe_title(&content).unwrap(), "Yes");
+        assert!(content.contains("servings: 2"));
+
+        let content = instantiate("Stir @sugar{}.\n", "Syrup");
+        assert_eq!(extract_recipe_title(&content).unwrap(), "Syrup");
+        assert!(content.ends_with("Stir @sugar{}.\n"));
+    }
+
+    #[test]
+    fn test_template_paths() {
+        assert_eq!(template_name("templates/bread.cook"), Some("bread"));
+        assert_eq!(template_name("templates/nested/bread.cook"), None);
+        assert_eq!(template_name("recipes/templates/bread.cook"), None);
+        assert!(is_template_path("templates/nested/bread.cook"));
+        assert!(!is_template_path("templatesque/bread.cook"));
+    }
+}
@@ -0,0 +1,329 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// A resumable upload in progress, assembled chunk by chunk at `{id}.part`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UploadSession {
+    pub upload_id: String,
+    pub file_name: String,
+    /// Declared total size in bytes
+    pub size: u64,
+    pub created_at: DateTime<Utc>,
+    /// Bytes received so far (the size of the part file, not persisted)
+    #[serde(skip)]
+    pub offset: u64,
+}
+
+impl UploadSession {
+    /// Whether all declared bytes have been received
+    pub fn is_complete(&self) -> bool {
+        self.offset >= self.size
+    }
+}
+
+/// Why a chunk was rejected
+#[derive(Debug, thiserror::Error)]
+pub enum UploadError {
+    #[error("upload not found")]
+    NotFound,
+    #[error("chunk starts at offset {given} but {expected} byte(s) have been received")]
+    OffsetMismatch { expected: u64, given: u64 },
+    #[error("chunk would exceed the declared upload size of {size} byte(s)")]
+    TooLarge { size: u64 },
+    #[error(transparent)]
+    Io(#[from] anyhow::Error),
+}
+
+/// Staging area for resumable uploads (e.g. large import archives)
+///
+/// Each upload is a metadata file `{id}.json` plus the bytes received so far in
+/// `{id}.part`. The part file's length is the authoritative offset, so clients can
+/// resume after a dropped connection or a server restart by asking for the offset
+/// and sending the remaining bytes from there.
+pub struct UploadStore {
+    dir: PathBuf,
+    counter: AtomicU64,
+    // upload_id -> lock held while a chunk is checked and appended
+    appending: DashMap<String, Arc<Mutex<()>>>,
+}
+
+impl UploadStore {
+    /// Open (creating if needed) the staging directory
+    pub fn new(dir: &Path) -> Result<Self> {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create upload directory {}", dir.display()))?;
+
+        // Keep partial uploads out of `git add -A` in the data directory
+        let gitignore = dir.join(".gitignore");
+        if !gitignore.exists() {
+            fs::write(&gitignore, "*\n").context("Failed to write upload .gitignore")?;
+        }
+
+        Ok(UploadStore {
+            dir: dir.to_path_buf(),
+            counter: AtomicU64::new(0),
+            appending: DashMap::new(),
+        })
+    }
+
+    /// Start a new upload of `size` bytes
+    pub fn create(&self, file_name: &str, size: u64, now: DateTime<Utc>) -> Result<UploadSession> {
+        let upload_id = self.generate_id(file_name, now);
+        let session = UploadSession {
+            upload_id: upload_id.clone(),
+            file_name: file_name.to_string(),
+            size,
+            created_at: now,
+            offset: 0,
+        };
+
+        fs::write(
+            self.meta_path(&upload_id),
+            serde_json::to_string_pretty(&session)?,
+        )
+        .context("Failed to write upload metadata")?;
+        fs::File::create(self.part_path(&upload_id)).context("Failed to create upload file")?;
+
+        Ok(session)
+    }
+
+    /// Look up an upload and its current offset
+    pub fn get(&self, upload_id: &str) -> Result<Option<UploadSession>> {
+        if !is_valid_id(upload_id) {
+            return Ok(None);
+        }
+
+        let meta_path = self.meta_path(upload_id);
+        if !meta_path.exists() {
+            return Ok(None);
+        }
+
+        let mut session: UploadSession = serde_json::from_str(
+            &fs::read_to_string(&meta_path).context("Failed to read upload metadata")?,
+        )
+        .context("Invalid upload metadata")?;
+        session.offset = fs::metadata(self.part_path(upload_id))
+            .map(|meta| meta.len())
+            .unwrap_or(0);
+
+        Ok(Some(session))
+    }
+
+    /// Append a chunk that starts at `offset`
+    ///
+    /// The offset must equal the bytes received so far; a mismatch means the client
+    /// lost track (e.g. a chunk was half-written) and should re-query the offset.
+    /// Appends to one upload run one at a time, so two requests sending the same chunk
+    /// can't both pass the offset check.
+    pub fn append(
+        &self,
+        upload_id: &str,
+        offset: u64,
+        chunk: &[u8],
+    ) -> Result<UploadSession, UploadError> {
+        if self.get(upload_id)?.is_none() {
+            return Err(UploadError::NotFound);
+        }
+        let lock = Arc::clone(
+            self.appending
+                .entry(upload_id.to_string())
+                .or_default()
+                .value(),
+        );
+        let _appending = lock.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut session = self.get(upload_id)?.ok_or(UploadError::NotFound)?;
+
+        if offset != session.offset {
+            return Err(UploadError::OffsetMismatch {
+                expected: session.offset,
+                given: offset,
+            });
+        }
+        if session.offset + chunk.len() as u64 > session.size {
+            return Err(UploadError::TooLarge { size: session.size });
+        }
+
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(self.part_path(upload_id))
+            .context("Failed to open upload file")?;
+        file.write_all(chunk)
+            .context("Failed to write upload chunk")?;
+        file.sync_data().context("Failed to flush upload chunk")?;
+
+        session.offset += chunk.len() as u64;
+        Ok(session)
+    }
+
+    /// Path of a fully received upload, for consumers such as importers
+    pub fn completed_path(&self, upload_id: &str) -> Result<Option<PathBuf>> {
+        Ok(self
+            .get(upload_id)?
+            .filter(|session| session.is_complete())
+            .map(|_| self.part_path(upload_id)))
+    }
+
+    /// Discard an upload, returning whether it existed
+    pub fn remove(&self, upload_id: &str) -> Result<bool> {
+        if !is_valid_id(upload_id) || !self.meta_path(upload_id).exists() {
+            return Ok(false);
+        }
+
+        self.appending.remove(upload_id);
+        fs::remove_file(self.meta_path(upload_id)).context("Failed to remove upload metadata")?;
+        let part_path = self.part_path(upload_id);
+        if part_path.exists() {
+            fs::remove_file(part_path).context("Failed to remove upload file")?;
+        }
+        Ok(true)
+    }
+
+    /// Discard uploads started before `cutoff`, returning how many were removed
+    pub fn purge_expired(&self, cutoff: DateTime<Utc>) -> Result<usize> {
+        let mut removed = 0;
+
+        for entry in fs::read_dir(&self.dir).context("Failed to read upload directory")? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let Some(upload_id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+
+            match self.get(upload_id) {
+                Ok(Some(session)) if session.created_at >= cutoff => {}
+                Ok(_) | Err(_) => {
+                    if self.remove(upload_id)? {
+                        removed += 1;
+                    }
+                }
+            }
+        }
+
+        Ok(removed)
+    }
+
+    fn generate_id(&self, file_name: &str, now: DateTime<Utc>) -> String {
+        use sha2::{Digest, Sha256};
+        let mut hasher = Sha256::new();
+        hasher.update(file_name);
+        hasher.update(now.timestamp_nanos_opt().unwrap_or_default().to_le_bytes());
+        hasher.update(self.counter.fetch_add(1, Ordering::Relaxed).to_le_bytes());
+        hasher.update(std::process::id().to_le_bytes());
+        format!("{:x}", hasher.finalize())[..24].to_string()
+    }
+
+    fn meta_path(&self, upload_id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", upload_id))
+    }
+
+    fn part_path(&self, upload_id: &str) -> PathBuf {
+        self.dir.join(format!("{}.part", upload_id))
+    }
+}
+
+/// Upload IDs are hex digests; anything else could escape the staging directory
+fn is_valid_id(upload_id: &str) -> bool {
+    !upload_id.is_empty() && upload_id.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_chunked_upload_and_resume() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let store = UploadStore::new(temp_dir.path())?;
+
+        let session = store.create("export.zip", 10, Utc::now())?;
+        assert_eq!(session.offset, 0);
+
+        let session = store.append(&session.upload_id, 0, b"hello")?;
+        assert_eq!(session.offset, 5);
+        assert!(!session.is_complete());
+
+        // A retried chunk at a stale offset is rejected with the current offset
+        match store.append(&session.upload_id, 0, b"hello") {
+            Err(UploadError::OffsetMismatch { expected, given }) => {
+                assert_eq!((expected, given), (5, 0));
+            }
+            other => panic!("expected offset mismatch, got {:?}", other),
+        }
+
+        // State survives reopening the store (e.g. after a restart)
+        let store = UploadStore::new(temp_dir.path())?;
+        assert_eq!(store.get(&session.upload_id)?.unwrap().offset, 5);
+        assert!(store.completed_path(&session.upload_id)?.is_none());
+
+        assert!(matches!(
+            store.append(&session.upload_id, 5, b"world!"),
+            Err(UploadError::TooLarge { size: 10 })
+        ));
+
+        let session = store.append(&session.upload_id, 5, b"world")?;
+        assert!(session.is_complete());
+
+        let path = store.completed_path(&session.upload_id)?.unwrap();
+        assert_eq!(fs::read(path)?, b"helloworld");
+
+        assert!(store.remove(&session.upload_id)?);
+        assert!(store.get(&session.upload_id)?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_concurrent_appends_at_same_offset() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let store = UploadStore::new(temp_dir.path())?;
+        let session = store.create("export.zip", 100, Utc::now())?;
+
+        // Retries racing each other: only one chunk may land
+        let accepted = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| scope.spawn(|| store.append(&session.upload_id, 0, b"hello").is_ok()))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .filter(|&accepted| accepted)
+                .count()
+        });
+        assert_eq!(accepted, 1);
+        assert_eq!(store.get(&session.upload_id)?.unwrap().offset, 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_invalid_ids_and_purges_expired() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let store = UploadStore::new(temp_dir.path())?;
+
+        assert!(store.get("../secrets")?.is_none());
+        assert!(matches!(
+            store.append("../secrets", 0, b"x"),
+            Err(UploadError::NotFound)
+        ));
+
+        let old = store.create("old.zip", 1, Utc::now() - Duration::hours(48))?;
+        let fresh = store.create("fresh.zip", 1, Utc::now())?;
+
+        assert_eq!(store.purge_expired(Utc::now() - Duration::hours(24))?, 1);
+        assert!(store.get(&old.upload_id)?.is_none());
+        assert!(store.get(&fresh.upload_id)?.is_some());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,638 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+use crate::config::StoreConfig;
+use crate::events::{EventKind, RepositoryEvent};
+
+/// Deliveries kept for inspection, oldest dropped first
+const MAX_DELIVERIES: usize = 500;
+
+/// Header carrying the payload's HMAC-SHA256 signature (`sha256=<hex>`)
+pub const SIGNATURE_HEADER: &str = "X-Signature";
+
+/// A URL told about recipe changes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    pub id: String,
+    pub url: String,
+    /// Events delivered, all of them when empty
+    pub events: Vec<String>,
+    /// Key payloads are signed with
+    pub secret: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+}
+
+impl Webhook {
+    /// Whether the webhook subscribes to `event`
+    pub fn wants(&self, event: &str) -> bool {
+        self.events.is_empty() || self.events.iter().any(|e| e == event)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    /// Being sent or waiting for a retry
+    Pending,
+    Delivered,
+    /// Out of attempts; kept as a dead letter
+    Failed,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Delivered => "delivered",
+            DeliveryStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "pending" => Some(DeliveryStatus::Pending),
+            "delivered" => Some(DeliveryStatus::Delivered),
+            "failed" => Some(DeliveryStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// One try at sending a delivery
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryAttempt {
+    pub at: DateTime<Utc>,
+    /// HTTP status of the response, if there was one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// An event sent (or being sent) to one webhook
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Delivery {
+    pub id: String,
+    pub webhook_id: String,
+    pub event: String,
+    /// JSON body exactly as signed
+    pub payload: String,
+    pub status: DeliveryStatus,
+    pub attempts: Vec<DeliveryAttempt>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// How deliveries are sent and retried
+#[derive(Debug, Clone, Copy)]
+pub struct DeliverySettings {
+    /// Tries before a delivery becomes a dead letter
+    pub max_attempts: u32,
+    /// Wait before the first retry, doubled for each one after
+    pub retry_delay: Duration,
+    /// Time one try may take
+    pub timeout: Duration,
+}
+
+impl DeliverySettings {
+    /// The settings given by `WEBHOOK_MAX_ATTEMPTS`, `WEBHOOK_RETRY_DELAY_SECS` and
+    /// `WEBHOOK_TIMEOUT_SECS`
+    pub fn from_config(config: &StoreConfig) -> Self {
+        DeliverySettings {
+            max_attempts: config.webhook_max_attempts,
+            retry_delay: Duration::from_secs(config.webhook_retry_delay_secs),
+            timeout: Duration::from_secs(config.webhook_timeout_secs),
+        }
+    }
+}
+
+/// Registered webhooks and their delivery log, kept in a directory of their own
+///
+/// Nothing here is committed: the webhooks hold their signing secrets. Deliveries go
+/// out in the background through the `curl` CLI, like the HTTP write hook, and are
+/// retried with exponential backoff; those that run out of attempts stay in the log as
+/// dead letters until newer deliveries push them out.
+pub struct Webhooks {
+    hooks: DashMap<String, Webhook>,
+    deliveries: Mutex<VecDeque<Delivery>>,
+    dir: PathBuf,
+    settings: RwLock<DeliverySettings>,
+    counter: AtomicU64,
+}
+
+impl Webhooks {
+    /// Webhooks persisted in `dir`, which is created on first save
+    pub fn new(dir: &Path, settings: DeliverySettings) -> Self {
+        Webhooks {
+            hooks: DashMap::new(),
+            deliveries: Mutex::new(VecDeque::new()),
+            dir: dir.to_path_buf(),
+            settings: RwLock::new(settings),
+            counter: AtomicU64::new(0),
+        }
+    }
+
+    /// Load persisted webhooks and deliveries; deliveries cut off by a restart are
+    /// marked failed
+    pub fn load(&self) -> Result<()> {
+        let hooks_file = self.dir.join("webhooks.json");
+        if hooks_file.exists() {
+            let hooks: BTreeMap<String, Webhook> =
+                serde_json::from_str(&std::fs::read_to_string(&hooks_file)?)
+                    .context("Invalid webhooks file")?;
+            self.hooks.clear();
+            for (id, hook) in hooks {
+                self.hooks.insert(id, hook);
+            }
+        }
+
+        let deliveries_file = self.dir.join("deliveries.json");
+        if deliveries_file.exists() {
+            let mut deliveries: VecDeque<Delivery> =
+                serde_json::from_str(&std::fs::read_to_string(&deliveries_file)?)
+                    .context("Invalid webhook deliveries file")?;
+            for delivery in deliveries.iter_mut() {
+                if delivery.status == DeliveryStatus::Pending {
+                    delivery.status = DeliveryStatus::Failed;
+                }
+            }
+            *self.lock_deliveries() = deliveries;
+        }
+        Ok(())
+    }
+
+    /// Register a webhook; a secret is generated unless one is given
+    pub fn create(
+        &self,
+        url: &str,
+        events: Vec<String>,
+        secret: Option<&str>,
+        author: Option<&str>,
+    ) -> Result<Webhook> {
+        let url = url.trim();
+        if !(url.starts_with("http://") || url.starts_with("https://")) || url.contains(' ') {
+            bail!("Webhook URL must be an http:// or https:// URL");
+        }
+        if let Some(event) = events.iter().find(|e| EventKind::parse(e).is_none()) {
+            let known: Vec<&str> = EventKind::ALL.iter().map(|kind| kind.as_str()).collect();
+            bail!(
+                "Unknown webhook event '{}' (expected one of {})",
+                event,
+                known.join(", ")
+            );
+        }
+        let secret = match secret.map(str::trim) {
+            Some("") => bail!("Webhook secret cannot be empty"),
+            Some(secret) => secret.to_string(),
+            None => self.generate_id(url, 64),
+        };
+
+        let mut events = events;
+        events.sort();
+        events.dedup();
+        let hook = Webhook {
+            id: self.generate_id(url, 16),
+            url: url.to_string(),
+            events,
+            secret,
+            created_at: Utc::now(),
+            created_by: author
+                .map(str::trim)
+                .filter(|author| !author.is_empty())
+                .map(str::to_string),
+        };
+        self.hooks.insert(hook.id.clone(), hook.clone());
+        self.save_hooks()?;
+        Ok(hook)
+    }
+
+    /// All webhooks, oldest first
+    pub fn list(&self) -> Vec<Webhook> {
+        let mut hooks: Vec<Webhook> = self.hooks.iter().map(|e| e.value().clone()).collect();
+        hooks.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+        hooks
+    }
+
+    pub fn get(&self, id: &str) -> Option<Webhook> {
+        self.hooks.get(id).map(|hook| hook.clone())
+    }
+
+    /// Remove a webhook, returning it if it existed; its deliveries stay in the log
+    pub fn remove(&self, id: &str) -> Result<Option<Webhook>> {
+        let Some((_, hook)) = self.hooks.remove(id) else {
+            return Ok(None);
+        };
+        self.save_hooks()?;
+        Ok(Some(hook))
+    }
+
+    /// Logged deliveries, newest first, optionally of one webhook or in one status
+    pub fn deliveries(
+        &self,
+        webhook_id: Option<&str>,
+        status: Option<DeliveryStatus>,
+    ) -> Vec<Delivery> {
+        self.lock_deliveries()
+            .iter()
+            .rev()
+            .filter(|d| webhook_id.is_none_or(|id| d.webhook_id == id))
+            .filter(|d| status.is_none_or(|status| d.status == status))
+            .cloned()
+            .collect()
+    }
+
+    /// Send an event to every webhook subscribed to it, in the background
+    ///
+    /// Returns the delivery tasks, which callers don't need to wait for. Outside a
+    /// Tokio runtime nothing is sent.
+    pub fn dispatch(self: &Arc<Self>, event: &RepositoryEvent) -> Vec<tokio::task::JoinHandle<()>> {
+        let hooks: Vec<Webhook> = self
+            .list()
+            .into_iter()
+            .filter(|hook| hook.wants(event.event.as_str()))
+            .collect();
+        if hooks.is_empty() {
+            return Vec::new();
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            tracing::warn!(
+                "Not sending {} webhooks outside a runtime",
+                event.event.as_str()
+            );
+            return Vec::new();
+        };
+        let payload = match serde_json::to_string(event) {
+            Ok(payload) => payload,
+            Err(e) => {
+                tracing::warn!("Failed to serialize webhook payload: {}", e);
+                return Vec::new();
+            }
+        };
+
+        hooks
+            .into_iter()
+            .map(|hook| {
+                let delivery = Delivery {
+                    id: self.generate_id(&hook.id, 16),
+                    webhook_id: hook.id.clone(),
+                    event: event.event.as_str().to_string(),
+                    payload: payload.clone(),
+                    status: DeliveryStatus::Pending,
+                    attempts: Vec::new(),
+                    created_at: Utc::now(),
+                };
+                self.record(delivery.clone());
+                runtime.spawn(Arc::clone(self).deliver(hook, delivery))
+            })
+            .collect()
+    }
+
+    /// Retry settings for deliveries started from now on
+    pub fn set_delivery_settings(&self, settings: DeliverySettings) {
+        *self
+            .settings
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = settings;
+    }
+
+    /// Try a delivery until it succeeds or runs out of attempts
+    async fn deliver(self: Arc<Self>, hook: Webhook, delivery: Delivery) {
+        let settings = *self
+            .settings
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let attempts = settings.max_attempts.max(1);
+        for attempt in 1..=attempts {
+            let result = post(&hook, &delivery, settings.timeout).await;
+            let delivered = result.is_ok();
+            let (status_code, error) = match result {
+                Ok(status) => (Some(status), None),
+                Err((status, error)) => (status, Some(error)),
+            };
+            let status = if delivered {
+                DeliveryStatus::Delivered
+            } else if attempt == attempts {
+                tracing::warn!(
+                    "Webhook delivery {} to {} failed after {} attempt(s): {}",
+                    delivery.id,
+                    hook.url,
+                    attempts,
+                    error.as_deref().unwrap_or_default()
+                );
+                DeliveryStatus::Failed
+            } else {
+                DeliveryStatus::Pending
+            };
+            self.update(
+                &delivery.id,
+                status,
+                DeliveryAttempt {
+                    at: Utc::now(),
+                    status_code,
+                    error,
+                },
+            );
+            if status != DeliveryStatus::Pending {
+                return;
+            }
+            tokio::time::sleep(settings.retry_delay * 2u32.pow(attempt - 1)).await;
+        }
+    }
+
+    fn record(&self, delivery: Delivery) {
+        let mut deliveries = self.lock_deliveries();
+        deliveries.push_back(delivery);
+        while deliveries.len() > MAX_DELIVERIES {
+            deliveries.pop_front();
+        }
+        self.save_deliveries(&deliveries);
+    }
+
+    fn update(&self, id: &str, status: DeliveryStatus, attempt: DeliveryAttempt) {
+        let mut deliveries = self.lock_deliveries();
+        if let Some(delivery) = deliveries.iter_mut().find(|d| d.id == id) {
+            delivery.status = status;
+            delivery.attempts.push(attempt);
+        }
+        self.save_deliveries(&deliveries);
+    }
+
+    fn lock_deliveries(&self) -> std::sync::MutexGuard<'_, VecDeque<Delivery>> {
+        self.deliveries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn save_hooks(&self) -> Result<()> {
+        let hooks: BTreeMap<String, Webhook> = self
+            .hooks
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+        self.write_file("webhooks.json", &serde_json::to_string_pretty(&hooks)?)
+    }
+
+    /// Save the delivery log (called with it locked, so saves don't interleave)
+    fn save_deliveries(&self, deliveries: &VecDeque<Delivery>) {
+        let result = serde_json::to_string_pretty(deliveries)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| self.write_file("deliveries.json", &json));
+        if let Err(e) = result {
+            tracing::warn!("Failed to save webhook deliveries: {}", e);
+        }
+    }
+
+    fn write_file(&self, name: &str, content: &str) -> Result<()> {
+        // Keep secrets out of `git add -A` in the data directory
+        std::fs::create_dir_all(&self.dir)?;
+        let gitignore = self.dir.join(".gitignore");
+        if !gitignore.exists() {
+            std::fs::write(&gitignore, "*\n")?;
+        }
+        std::fs::write(self.dir.join(name), content)
+            .with_context(|| format!("Failed to write {}", name))
+    }
+
+    fn generate_id(&self, seed: &str, len: usize) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(seed);
+        hasher.update(
+            Utc::now()
+                .timestamp_nanos_opt()
+                .unwrap_or_default()
+                .to_le_bytes(),
+        );
+        hasher.update(self.counter.fetch_add(1, Ordering::Relaxed).to_le_bytes());
+        hasher.update(std::process::id().to_le_bytes());
+        format!("{:x}", hasher.finalize())[..len].to_string()
+    }
+}
+
+/// `sha256=` and the hex HMAC-SHA256 of `body` under `secret`, as sent in
+/// [`SIGNATURE_HEADER`]
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    const BLOCK_SIZE: usize = 64;
+    let mut key = [0u8; BLOCK_SIZE];
+    if secret.len() > BLOCK_SIZE {
+        key[..32].copy_from_slice(&Sha256::digest(secret.as_bytes()));
+    } else {
+        key[..secret.len()].copy_from_slice(secret.as_bytes());
+    }
+
+    let pad = |byte: u8| key.iter().map(|k| k ^ byte).collect::<Vec<u8>>();
+    let inner = Sha256::new()
+        .chain_update(pad(0x36))
+        .chain_update(body)
+        .finalize();
+    let outer = Sha256::new()
+        .chain_update(pad(0x5c))
+        .chain_update(inner)
+        .finalize();
+    format!("sha256={:x}", outer)
+}
+
+/// POST a delivery, returning the 2xx status, or the status (if any) and what went wrong
+async fn post(
+    hook: &Webhook,
+    delivery: &Delivery,
+    timeout: Duration,
+) -> std::result::Result<u16, (Option<u16>, String)> {
+    let signature = sign(&hook.secret, delivery.payload.as_bytes());
+    let mut child = tokio::process::Command::new("curl")
+        .args(["--silent", "--show-error", "--request", "POST"])
+        .args(["--header", "Content-Type: application/json"])
+        .args(["--header", &format!("{}: {}", SIGNATURE_HEADER, signature)])
+        .args(["--header", &format!("X-Webhook-Event: {}", delivery.event)])
+        .args(["--header", &format!("X-Webhook-Delivery: {}", delivery.id)])
+        .args(["--data-binary", "@-"])
+        .args(["--max-time", &timeout.as_secs().max(1).to_string()])
+        .args(["--output", "/dev/null", "--write-out", "%{http_code}"])
+        .arg(&hook.url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| {
+            (
+                None,
+                format!("Failed to run curl (is curl installed?): {}", e),
+            )
+        })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(delivery.payload.as_bytes())
+            .await
+            .map_err(|e| (None, format!("Failed to send payload: {}", e)))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(|e| (None, e.to_string()))?;
+    if !output.status.success() {
+        return Err((
+            None,
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    match String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse::<u16>()
+    {
+        Ok(status) if (200..300).contains(&status) => Ok(status),
+        Ok(status) => Err((Some(status), format!("HTTP {}", status))),
+        Err(_) => Err((None, "No HTTP status in the response".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    fn event() -> RepositoryEvent {
+        RepositoryEvent::new(
+            EventKind::RecipeCreated,
+            "recipes/soup.cook",
+            "Soup",
+            None,
+            Some("Alice"),
+        )
+    }
+
+    fn settings(max_attempts: u32) -> DeliverySettings {
+        DeliverySettings {
+            max_attempts,
+            retry_delay: Duration::from_millis(10),
+            timeout: Duration::from_secs(10),
+        }
+    }
+
+    /// Answer requests with `statuses` in turn, returning each request's headers and body
+    fn serve(statuses: Vec<&'static str>) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for status in statuses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let (mut request, mut length) = (String::new(), 0);
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                        length = value.trim().parse().unwrap();
+                    }
+                    request.push_str(&line);
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                request.push_str(&String::from_utf8(body).unwrap());
+                requests.push(request);
+                write!(
+                    &stream,
+                    "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    status
+                )
+                .unwrap();
+            }
+            requests
+        });
+        (url, server)
+    }
+
+    #[test]
+    fn test_sign() {
+        // RFC 4231, test case 2
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delivery_retries() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let webhooks = Arc::new(Webhooks::new(dir.path(), settings(3)));
+        let (url, server) = serve(vec!["500 Internal Server Error", "204 No Content"]);
+        let hook = webhooks.create(&url, vec!["recipe.created".to_string()], Some("key"), None)?;
+
+        for task in webhooks.dispatch(&event()) {
+            task.await?;
+        }
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 2);
+        let delivery = &webhooks.deliveries(Some(&hook.id), None)[0];
+        assert_eq!(delivery.status, DeliveryStatus::Delivered);
+        assert_eq!(delivery.attempts.len(), 2);
+        assert_eq!(delivery.attempts[0].status_code, Some(500));
+        assert_eq!(delivery.attempts[1].status_code, Some(204));
+
+        // Signed with the webhook's secret
+        let signature = sign("key", delivery.payload.as_bytes());
+        assert!(requests[1]
+            .to_lowercase()
+            .contains(&format!("x-signature: {}", signature)));
+        assert!(requests[1].ends_with(&delivery.payload));
+
+        // Unsubscribed events aren't sent
+        let mut deleted = event();
+        deleted.event = EventKind::RecipeDeleted;
+        assert!(webhooks.dispatch(&deleted).is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dead_letters() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let webhooks = Arc::new(Webhooks::new(dir.path(), settings(2)));
+        let (url, server) = serve(vec!["503 Service Unavailable", "503 Service Unavailable"]);
+        let hook = webhooks.create(&url, Vec::new(), None, Some("Alice"))?;
+        assert_eq!(hook.secret.len(), 64);
+
+        for task in webhooks.dispatch(&event()) {
+            task.await?;
+        }
+        server.join().unwrap();
+        let failed = webhooks.deliveries(None, Some(DeliveryStatus::Failed));
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].attempts.len(), 2);
+
+        // Webhooks and the log survive a restart
+        let loaded = Webhooks::new(dir.path(), settings(2));
+        loaded.load()?;
+        assert_eq!(loaded.get(&hook.id), Some(hook.clone()));
+        assert_eq!(loaded.deliveries(None, None), failed);
+        assert!(loaded.remove(&hook.id)?.is_some());
+        assert!(loaded.list().is_empty());
+        assert!(std::fs::read_to_string(dir.path().join(".gitignore"))?.contains('*'));
+
+        assert!(webhooks
+            .create("ftp://example.com", Vec::new(), None, None)
+            .is_err());
+        assert!(webhooks
+            .create(&url, vec!["recipe.eaten".to_string()], None, None)
+            .is_err());
+        Ok(())
+    }
+}
@@ -61,6 +61,33 @@ async fn test_status_endpoint_disk() {
     test_status_endpoint_impl("disk").await;
 }
 
+async fn test_status_lists_enabled_features_impl(backend: &str) {
+    let config = cooklang_store::config::StoreConfig {
+        features: ["graphql".to_string()].into_iter().collect(),
+        ..Default::default()
+    };
+    let (build_router, _temp_dir) = setup_api_with_config(backend, config).await;
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/status", None))
+        .await
+        .unwrap();
+
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["features"], serde_json::json!(["graphql"]));
+}
+
+#[tokio::test]
+async fn test_status_lists_enabled_features_git() {
+    test_status_lists_enabled_features_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_status_lists_enabled_features_disk() {
+    test_status_lists_enabled_features_impl("disk").await;
+}
+
 // ============================================================================
 // RECIPE CREATION TESTS
 // ============================================================================
@@ -364,6 +391,281 @@ async fn test_list_recipes_with_limit_disk() {
     test_list_recipes_with_limit_impl("disk").await;
 }
 
+async fn test_list_recipes_include_content_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_seeded_fixtures(
+        backend,
+        vec![
+            ("recipe-1", Some("desserts"), "recipe-1.cook"),
+            ("recipe-2", Some("desserts"), "recipe-2.cook"),
+        ],
+    )
+    .await;
+
+    // Listings leave content out by default
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes", None))
+        .await
+        .unwrap();
+    let json: Value = serde_json::from_str(&extract_response_body(response).await).unwrap();
+    assert!(json["recipes"][0].get("content").is_none());
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/recipes?include_content=true&limit=1",
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let json: Value = serde_json::from_str(&extract_response_body(response).await).unwrap();
+    let recipes = json["recipes"].as_array().unwrap();
+    assert_eq!(recipes.len(), 1);
+    let recipe_id = recipes[0]["recipeId"].as_str().unwrap();
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    let recipe: Value = serde_json::from_str(&extract_response_body(response).await).unwrap();
+    assert!(!recipe["content"].as_str().unwrap().is_empty());
+    assert_eq!(recipes[0]["content"], recipe["content"]);
+}
+
+#[tokio::test]
+async fn test_list_recipes_include_content_git() {
+    test_list_recipes_include_content_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_list_recipes_include_content_disk() {
+    test_list_recipes_include_content_impl("disk").await;
+}
+
+#[tokio::test]
+async fn test_list_recipes_full_detail() {
+    let (build_router, _temp_dir) = setup_api_with_seeded_files(
+        "disk",
+        vec![],
+        vec![
+            (
+                "recipes/curry.cook",
+                "---\ntitle: Curry\ndescription: Weeknight green curry\ntags: [thai, quick]\n\
+                 servings: 4\ntime: 30 min\nimage: https://example.com/curry.jpg\n---\n\n\
+                 Simmer @coconut milk{400%ml}.\n",
+            ),
+            (
+                "recipes/toast.cook",
+                "---\ntitle: Toast\n---\n\nToast @bread{2%slices}.\n",
+            ),
+        ],
+    )
+    .await;
+
+    // The summary is the default
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes", None))
+        .await
+        .unwrap();
+    let json: Value = serde_json::from_str(&extract_response_body(response).await).unwrap();
+    let curry = &json["recipes"][0];
+    assert_eq!(curry["recipeName"], "Curry");
+    assert_eq!(curry["totalTimeMinutes"], 30);
+    assert!(curry.get("tags").is_none());
+    assert!(curry.get("imageUrl").is_none());
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes?detail=full", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let json: Value = serde_json::from_str(&extract_response_body(response).await).unwrap();
+    let (curry, toast) = (&json["recipes"][0], &json["recipes"][1]);
+    assert_eq!(curry["description"], "Weeknight green curry");
+    assert_eq!(curry["tags"], serde_json::json!(["thai", "quick"]));
+    assert_eq!(curry["servings"], "4");
+    assert_eq!(curry["totalTimeMinutes"], 30);
+    assert_eq!(curry["imageUrl"], "https://example.com/curry.jpg");
+    assert_eq!(toast["tags"], serde_json::json!([]));
+    assert!(toast.get("imageUrl").is_none());
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/recipes?detail=everything",
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_sparse_fieldsets() {
+    let (build_router, _temp_dir) = setup_api_with_seeded_files(
+        "disk",
+        vec![],
+        vec![(
+            "recipes/curry.cook",
+            "---\ntitle: Curry\ntags: [thai]\ntime: 30 min\n---\n\nSimmer @coconut milk{400%ml}.\n",
+        )],
+    )
+    .await;
+    let recipe_id = cooklang_store::cache::generate_recipe_id("recipes/curry.cook");
+    let keys = |value: &Value| -> Vec<String> {
+        let mut keys: Vec<String> = value.as_object().unwrap().keys().cloned().collect();
+        keys.sort();
+        keys
+    };
+
+    // Lists keep their pagination and trim each item
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/recipes?detail=full&fields=recipeId,%20tags,unknown",
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let json: Value = serde_json::from_str(&extract_response_body(response).await).unwrap();
+    assert_eq!(json["pagination"]["total"], 1);
+    assert_eq!(keys(&json["recipes"][0]), vec!["recipeId", "tags"]);
+    assert_eq!(json["recipes"][0]["tags"], serde_json::json!(["thai"]));
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/recipes/search?q=curry&fields=recipeName,score",
+            None,
+        ))
+        .await
+        .unwrap();
+    let json: Value = serde_json::from_str(&extract_response_body(response).await).unwrap();
+    assert_eq!(keys(&json["recipes"][0]), vec!["recipeName", "score"]);
+
+    // A single recipe is trimmed itself
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!(
+                "/api/v1/recipes/{}?fields=recipeName,totalTimeMinutes",
+                recipe_id
+            ),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let json: Value = serde_json::from_str(&extract_response_body(response).await).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({ "recipeName": "Curry", "totalTimeMinutes": 30 })
+    );
+
+    // Errors are left whole
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/recipes/missing?fields=recipeName",
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+    let json: Value = serde_json::from_str(&extract_response_body(response).await).unwrap();
+    assert!(json.get("error").is_some());
+}
+
+#[tokio::test]
+async fn test_list_recipes_sort() {
+    let (build_router, temp_dir) = setup_api_with_seeded_files(
+        "disk",
+        vec![],
+        vec![
+            (
+                "recipes/mains/apple-curry.cook",
+                "---\ntitle: Apple Curry\n---\n\nCook.",
+            ),
+            (
+                "recipes/desserts/zebra-cake.cook",
+                "---\ntitle: zebra cake\n---\n\nBake.",
+            ),
+            (
+                "recipes/desserts/brownies.cook",
+                "---\ntitle: Brownies\n---\n\nBake.",
+            ),
+        ],
+    )
+    .await;
+
+    let names = |json: &Value| -> Vec<String> {
+        json["recipes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|recipe| recipe["recipeName"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    // By name by default, ignoring case, and the same on every request
+    for _ in 0..3 {
+        let response = build_router()
+            .oneshot(make_request("GET", "/api/v1/recipes", None))
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_str(&extract_response_body(response).await).unwrap();
+        assert_eq!(names(&json), vec!["Apple Curry", "Brownies", "zebra cake"]);
+    }
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/recipes?sort=path&offset=1",
+            None,
+        ))
+        .await
+        .unwrap();
+    let json: Value = serde_json::from_str(&extract_response_body(response).await).unwrap();
+    assert_eq!(names(&json), vec!["zebra cake", "Apple Curry"]);
+
+    // Most recently changed first, once a reindex has seen the new modification times
+    for (path, year) in [
+        ("recipes/mains/apple-curry.cook", 2020),
+        ("recipes/desserts/brownies.cook", 2021),
+        ("recipes/desserts/zebra-cake.cook", 2022),
+    ] {
+        let modified = std::time::UNIX_EPOCH
+            + std::time::Duration::from_secs((year - 1970) * 365 * 24 * 60 * 60);
+        std::fs::File::options()
+            .write(true)
+            .open(temp_dir.path().join(path))
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    }
+    build_router()
+        .oneshot(make_request("POST", "/api/v1/admin/reindex", None))
+        .await
+        .unwrap();
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes?sort=modified", None))
+        .await
+        .unwrap();
+    let json: Value = serde_json::from_str(&extract_response_body(response).await).unwrap();
+    assert_eq!(names(&json), vec!["zebra cake", "Brownies", "Apple Curry"]);
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes?sort=size", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+}
+
 async fn test_get_recipe_not_found_impl(backend: &str) {
     let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
     let app = build_router();
@@ -436,589 +738,967 @@ async fn test_get_recipe_by_id_disk() {
     test_get_recipe_by_id_impl("disk").await;
 }
 
-// ============================================================================
-// RECIPE SEARCH TESTS
-// ============================================================================
-
-async fn test_search_recipes_empty_impl(backend: &str) {
+async fn test_get_recipe_raw_impl(backend: &str) {
     let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
-    let app = build_router();
 
-    let response = app
-        .oneshot(make_request("GET", "/api/v1/recipes/search?q=test", None))
+    let content = load_recipe_fixture("test-recipe");
+    let payload = serde_json::json!({ "content": content, "path": "desserts" });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
         .await
         .unwrap();
-
-    assert_eq!(response.status(), axum::http::StatusCode::OK);
-
     let body = extract_response_body(response).await;
     let json: Value = serde_json::from_str(&body).unwrap();
+    let recipe_id = json["recipeId"].as_str().unwrap().to_string();
 
-    assert_eq!(json["recipes"].as_array().unwrap().len(), 0);
+    let get = |uri: String, accept: Option<&'static str>| {
+        let app = build_router();
+        async move {
+            let mut request = axum::http::Request::builder().method("GET").uri(uri);
+            if let Some(accept) = accept {
+                request = request.header("accept", accept);
+            }
+            let response = app
+                .oneshot(request.body(axum::body::Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), axum::http::StatusCode::OK);
+            let content_type = response.headers()["content-type"]
+                .to_str()
+                .unwrap()
+                .to_string();
+            (content_type, extract_response_body(response).await)
+        }
+    };
+
+    // `/raw` and `Accept: text/plain` both return the file as stored
+    let (content_type, body) = get(format!("/api/v1/recipes/{}/raw", recipe_id), None).await;
+    assert_eq!(content_type, "text/plain; charset=utf-8");
+    assert_eq!(body, content);
+
+    let uri = format!("/api/v1/recipes/{}", recipe_id);
+    let (content_type, body) = get(uri.clone(), Some("text/plain")).await;
+    assert!(content_type.starts_with("text/plain"));
+    assert_eq!(body, content);
+
+    // JSON stays the default, including for wildcards and JSON listed first
+    for accept in [None, Some("*/*"), Some("application/json, text/plain")] {
+        let (content_type, body) = get(uri.clone(), accept).await;
+        assert!(content_type.starts_with("application/json"));
+        let json: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["recipeName"], "Test Recipe");
+    }
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes/nonexistent/raw", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
 }
 
 #[tokio::test]
-async fn test_search_recipes_empty_git() {
-    test_search_recipes_empty_impl("git").await;
+async fn test_get_recipe_raw_git() {
+    test_get_recipe_raw_impl("git").await;
 }
 
 #[tokio::test]
-async fn test_search_recipes_empty_disk() {
-    test_search_recipes_empty_impl("disk").await;
+async fn test_get_recipe_raw_disk() {
+    test_get_recipe_raw_impl("disk").await;
 }
 
-async fn test_search_recipes_by_name_impl(backend: &str) {
-    let (build_router, _temp_dir) = setup_api_with_seeded_fixtures(
-        backend,
-        vec![
-            ("chocolate-cake", Some("main"), "chocolate-cake.cook"),
-            ("vanilla-cake", Some("main"), "vanilla-cake.cook"),
-            ("pasta", Some("main"), "pasta.cook"),
-        ],
+#[tokio::test]
+async fn test_conditional_get_and_head() {
+    let (build_router, _temp_dir) = setup_api_with_seeded_files(
+        "disk",
+        vec![],
+        vec![(
+            "recipes/toast.cook",
+            "---\ntitle: Toast\n---\n\nToast @bread{2%slices}.\n",
+        )],
     )
     .await;
+    let recipe_uri = format!(
+        "/api/v1/recipes/{}",
+        cooklang_store::cache::generate_recipe_id("recipes/toast.cook")
+    );
+    let send = |method: &'static str, uri: String, since: Option<String>| {
+        let app = build_router();
+        async move {
+            let mut request = axum::http::Request::builder().method(method).uri(uri);
+            if let Some(since) = since {
+                request = request.header("if-modified-since", since);
+            }
+            app.oneshot(request.body(axum::body::Body::empty()).unwrap())
+                .await
+                .unwrap()
+        }
+    };
+
+    // Last-Modified is only sent once the second of the last change has passed
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    for uri in ["/api/v1/recipes".to_string(), recipe_uri.clone()] {
+        let response = send("GET", uri.clone(), None).await;
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+        assert_eq!(response.headers()["cache-control"], "no-cache");
+        assert_eq!(response.headers()["vary"], "accept, x-collection");
+        let last_modified = response.headers()["last-modified"]
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        let response = send("GET", uri.clone(), Some(last_modified.clone())).await;
+        assert_eq!(response.status(), axum::http::StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()["last-modified"], last_modified.as_str());
+        assert!(extract_response_body(response).await.is_empty());
+
+        let response = send("HEAD", uri.clone(), None).await;
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+        assert_eq!(response.headers()["last-modified"], last_modified.as_str());
+        assert!(extract_response_body(response).await.is_empty());
+
+        let response = send("HEAD", uri, Some(last_modified)).await;
+        assert_eq!(response.status(), axum::http::StatusCode::NOT_MODIFIED);
+    }
+    let last_modified = send("GET", "/api/v1/recipes".to_string(), None)
+        .await
+        .headers()["last-modified"]
+        .to_str()
+        .unwrap()
+        .to_string();
 
-    // Search for "cake"
-    let app = build_router();
-    let response = app
-        .oneshot(make_request("GET", "/api/v1/recipes/search?q=cake", None))
+    // Any change makes cached copies stale
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Porridge\n---\n\nCook @oats{50%g}.\n",
+        "path": "breakfast"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
         .await
         .unwrap();
-
-    let body = extract_response_body(response).await;
-    let json: Value = serde_json::from_str(&body).unwrap();
-
-    let results = json["recipes"].as_array().unwrap();
-    assert_eq!(results.len(), 2);
-
-    let names: Vec<&str> = results
-        .iter()
-        .map(|r| r["recipeName"].as_str().unwrap())
-        .collect();
-    assert!(names.contains(&"Chocolate Cake"));
-    assert!(names.contains(&"Vanilla Cake"));
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    for uri in ["/api/v1/recipes".to_string(), recipe_uri] {
+        let response = send("GET", uri, Some(last_modified.clone())).await;
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+    }
+
+    // Routes not built from the index aren't cached
+    let response = send("GET", "/api/v1/status".to_string(), None).await;
+    assert!(response.headers().get("last-modified").is_none());
 }
 
 #[tokio::test]
-async fn test_search_recipes_by_name_git() {
-    test_search_recipes_by_name_impl("git").await;
-}
+async fn test_errors_as_problem_details() {
+    let (build_router, _temp_dir) = setup_api_with_storage("disk").await;
+    let get = |accept: Option<&'static str>| {
+        let app = build_router();
+        async move {
+            let mut request = axum::http::Request::builder()
+                .method("GET")
+                .uri("/api/v1/recipes/nonexistent")
+                .header("x-request-id", "req-42");
+            if let Some(accept) = accept {
+                request = request.header("accept", accept);
+            }
+            let response = app
+                .oneshot(request.body(axum::body::Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+            let content_type = response.headers()["content-type"]
+                .to_str()
+                .unwrap()
+                .to_string();
+            let body = extract_response_body(response).await;
+            (content_type, serde_json::from_str::<Value>(&body).unwrap())
+        }
+    };
+
+    let (content_type, json) = get(Some("application/json, application/problem+json")).await;
+    assert_eq!(content_type, "application/problem+json");
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "type": "urn:cooklang-store:error:recipe_not_found",
+            "title": "Not Found",
+            "status": 404,
+            "detail": "Recipe not found",
+            "instance": "/api/v1/recipes/nonexistent",
+            "requestId": "req-42"
+        })
+    );
 
-#[tokio::test]
-async fn test_search_recipes_by_name_disk() {
-    test_search_recipes_by_name_impl("disk").await;
+    // Clients that don't ask keep the existing format
+    for accept in [None, Some("application/json")] {
+        let (content_type, json) = get(accept).await;
+        assert_eq!(content_type, "application/json");
+        assert_eq!(json["error"], "recipe_not_found");
+        assert_eq!(json["requestId"], "req-42");
+    }
 }
 
-async fn test_search_case_insensitive_impl(backend: &str) {
-    let (build_router, _temp_dir) = setup_api_with_seeded_fixtures(
-        backend,
-        vec![("chocolate-cake", Some("desserts"), "chocolate-cake.cook")],
+async fn test_update_recipe_raw_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
+
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Pancakes\n---\n\nMix @flour{200%g}.\n",
+        "path": "breakfast"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let recipe_id = json["recipeId"].as_str().unwrap().to_string();
+
+    let put_raw = |uri: String, content: &'static str| {
+        let app = build_router();
+        async move {
+            app.oneshot(
+                axum::http::Request::builder()
+                    .method("PUT")
+                    .uri(uri)
+                    .header("content-type", "text/plain")
+                    .body(axum::body::Body::from(content))
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+        }
+    };
+
+    // A new title renames the file, like a JSON update
+    let content = "---\ntitle: Fluffy Pancakes\n---\n\nMix @flour{250%g} and @milk{300%ml}.\n";
+    let response = put_raw(
+        format!("/api/v1/recipes/{}/raw?author=Editor", recipe_id),
+        content,
     )
     .await;
-
-    // Search with different cases
-    let app = build_router();
-    let response = app
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["recipeName"], "Fluffy Pancakes");
+    assert_eq!(json["fileName"], "fluffy-pancakes.cook");
+    assert_eq!(json["path"], "breakfast");
+    assert_eq!(json["content"], content);
+    let new_id = json["recipeId"].as_str().unwrap().to_string();
+    assert_ne!(new_id, recipe_id);
+
+    let response = build_router()
         .oneshot(make_request(
             "GET",
-            "/api/v1/recipes/search?q=CHOCOLATE",
+            &format!("/api/v1/recipes/{}/raw", new_id),
             None,
         ))
         .await
         .unwrap();
+    assert_eq!(extract_response_body(response).await, content);
 
+    // Content without a title is rejected and the recipe is left as it was
+    let response = put_raw(format!("/api/v1/recipes/{}/raw", new_id), "Mix @flour{}.\n").await;
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
     let body = extract_response_body(response).await;
     let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["error"], "invalid_front_matter");
 
-    assert_eq!(json["recipes"].as_array().unwrap().len(), 1);
+    let response = put_raw(
+        "/api/v1/recipes/nonexistent/raw".to_string(),
+        "---\ntitle: Ghost\n---\n",
+    )
+    .await;
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
 }
 
 #[tokio::test]
-async fn test_search_case_insensitive_git() {
-    test_search_case_insensitive_impl("git").await;
+async fn test_update_recipe_raw_git() {
+    test_update_recipe_raw_impl("git").await;
 }
 
 #[tokio::test]
-async fn test_search_case_insensitive_disk() {
-    test_search_case_insensitive_impl("disk").await;
+async fn test_update_recipe_raw_disk() {
+    test_update_recipe_raw_impl("disk").await;
 }
 
-// ============================================================================
-// CATEGORY TESTS
-// ============================================================================
-
-async fn test_list_categories_empty_impl(backend: &str) {
+async fn test_patch_recipe_front_matter_impl(backend: &str) {
     let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
-    let app = build_router();
 
-    let response = app
-        .oneshot(make_request("GET", "/api/v1/categories", None))
+    let body_text = "\n\n# Batter\n\nMix @flour{200%g} with @milk{300%ml}.\n";
+    let payload = serde_json::json!({
+        "content": format!("---\ntitle: Pancakes\nserves: 2\ncuisine: french\n---{}", body_text),
+        "path": "breakfast"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let recipe_id = json["recipeId"].as_str().unwrap().to_string();
+    let uri = format!("/api/v1/recipes/{}", recipe_id);
+
+    // Tags and servings change in place; other fields and the body are kept
+    let response = build_router()
+        .oneshot(make_request(
+            "PATCH",
+            &uri,
+            Some(serde_json::json!({ "tags": ["sweet", "quick"], "servings": 4 })),
+        ))
         .await
         .unwrap();
-
     assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["recipeId"], recipe_id.as_str());
+    let content = json["content"].as_str().unwrap();
+    assert!(content.ends_with(body_text));
+    assert!(content.contains("serves: 4\n"));
+    assert!(!content.contains("servings"));
+    assert!(content.contains("cuisine: french\n"));
+    assert!(content.contains("- sweet\n- quick\n"));
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/recipes/search?tag=quick",
+            None,
+        ))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["recipes"][0]["recipeName"], "Pancakes");
 
+    // A new title renames the file like a full update
+    let response = build_router()
+        .oneshot(make_request(
+            "PATCH",
+            &uri,
+            Some(serde_json::json!({ "title": "Crêpes" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
     let body = extract_response_body(response).await;
     let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["recipeName"], "Crêpes");
+    assert_ne!(json["recipeId"], recipe_id.as_str());
+    assert!(json["content"].as_str().unwrap().ends_with(body_text));
+
+    let new_uri = format!("/api/v1/recipes/{}", json["recipeId"].as_str().unwrap());
+    for payload in [
+        serde_json::json!({}),
+        serde_json::json!({ "title": " " }),
+        serde_json::json!({ "servings": [4] }),
+    ] {
+        let response = build_router()
+            .oneshot(make_request("PATCH", &new_uri, Some(payload)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    }
+
+    let response = build_router()
+        .oneshot(make_request(
+            "PATCH",
+            "/api/v1/recipes/nonexistent",
+            Some(serde_json::json!({ "servings": 1 })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+}
 
-    assert_eq!(json["categories"].as_array().unwrap().len(), 0);
+#[tokio::test]
+async fn test_patch_recipe_front_matter_git() {
+    test_patch_recipe_front_matter_impl("git").await;
 }
 
 #[tokio::test]
-async fn test_list_categories_empty_git() {
-    test_list_categories_empty_impl("git").await;
+async fn test_patch_recipe_front_matter_disk() {
+    test_patch_recipe_front_matter_impl("disk").await;
+}
+
+async fn test_batch_tag_impl(backend: &str) {
+    let (build_router, temp_dir) = setup_api_with_storage(backend).await;
+
+    let mut recipe_ids = Vec::new();
+    for content in [
+        "---\ntitle: Chili\ntags: [Quick, spicy]\n---\n\nSimmer @beans{400%g}.\n",
+        "---\ntitle: Omelette\ntags: [quick]\n---\n\nWhisk @eggs{3}.\n",
+        "---\ntitle: Roast\ntags: [weeknight]\n---\n\nRoast @chicken{1}.\n",
+    ] {
+        let payload = serde_json::json!({ "content": content });
+        let response = build_router()
+            .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+            .await
+            .unwrap();
+        let body = extract_response_body(response).await;
+        let json: Value = serde_json::from_str(&body).unwrap();
+        recipe_ids.push(json["recipeId"].as_str().unwrap().to_string());
+    }
+
+    let payload = serde_json::json!({
+        "recipeIds": [recipe_ids[0], recipe_ids[1], recipe_ids[2], "nonexistent"],
+        "add": ["weeknight"],
+        "remove": ["quick"]
+    });
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            "/api/v1/recipes/batch-tag",
+            Some(payload),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        json["updated"],
+        serde_json::json!([recipe_ids[0], recipe_ids[1]])
+    );
+    assert_eq!(json["unchanged"], serde_json::json!([recipe_ids[2]]));
+    assert_eq!(json["notFound"], serde_json::json!(["nonexistent"]));
+
+    // The index reflects the new tags right away
+    let names = |uri: &'static str| {
+        let app = build_router();
+        async move {
+            let response = app.oneshot(make_request("GET", uri, None)).await.unwrap();
+            let body = extract_response_body(response).await;
+            let json: Value = serde_json::from_str(&body).unwrap();
+            json["recipes"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|r| r["recipeName"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        }
+    };
+    assert_eq!(
+        names("/api/v1/recipes/search?tag=weeknight").await,
+        vec!["Chili", "Omelette", "Roast"]
+    );
+    assert!(names("/api/v1/recipes/search?tag=quick").await.is_empty());
+
+    let chili = std::fs::read_to_string(temp_dir.path().join("recipes/chili.cook")).unwrap();
+    assert!(chili.contains("- spicy\n- weeknight\n"));
+    assert!(chili.ends_with("\n\nSimmer @beans{400%g}.\n"));
+
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            "/api/v1/recipes/batch-tag",
+            Some(serde_json::json!({ "recipeIds": [recipe_ids[0]] })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
 }
 
 #[tokio::test]
-async fn test_list_categories_empty_disk() {
-    test_list_categories_empty_impl("disk").await;
+async fn test_batch_tag_git() {
+    test_batch_tag_impl("git").await;
 }
 
-async fn test_list_categories_impl(backend: &str) {
-    let (build_router, _temp_dir) = setup_api_with_seeded_fixtures(
-        backend,
-        vec![
-            ("cake", Some("desserts"), "cake.cook"),
-            ("pasta", Some("main"), "pasta.cook"),
-            ("test-recipe", Some("appetizers"), "test-recipe.cook"),
-        ],
-    )
-    .await;
+#[tokio::test]
+async fn test_batch_tag_disk() {
+    test_batch_tag_impl("disk").await;
+}
 
-    // List categories
+// ============================================================================
+// RECIPE SEARCH TESTS
+// ============================================================================
+
+async fn test_search_recipes_empty_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
     let app = build_router();
+
     let response = app
-        .oneshot(make_request("GET", "/api/v1/categories", None))
+        .oneshot(make_request("GET", "/api/v1/recipes/search?q=test", None))
         .await
         .unwrap();
 
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+
     let body = extract_response_body(response).await;
     let json: Value = serde_json::from_str(&body).unwrap();
 
-    let cats = json["categories"].as_array().unwrap();
-    assert_eq!(cats.len(), 3);
-
-    let cat_names: Vec<&str> = cats.iter().map(|c| c.as_str().unwrap()).collect();
-    assert!(cat_names.contains(&"desserts"));
-    assert!(cat_names.contains(&"main"));
-    assert!(cat_names.contains(&"appetizers"));
+    assert_eq!(json["recipes"].as_array().unwrap().len(), 0);
 }
 
 #[tokio::test]
-async fn test_list_categories_git() {
-    test_list_categories_impl("git").await;
+async fn test_search_recipes_empty_git() {
+    test_search_recipes_empty_impl("git").await;
 }
 
 #[tokio::test]
-async fn test_list_categories_disk() {
-    test_list_categories_impl("disk").await;
+async fn test_search_recipes_empty_disk() {
+    test_search_recipes_empty_impl("disk").await;
 }
 
-async fn test_get_recipes_in_category_impl(backend: &str) {
+async fn test_search_recipes_by_name_impl(backend: &str) {
     let (build_router, _temp_dir) = setup_api_with_seeded_fixtures(
         backend,
         vec![
-            ("cake", Some("desserts"), "cake.cook"),
-            ("cake", Some("desserts"), "cookie.cook"),
+            ("chocolate-cake", Some("main"), "chocolate-cake.cook"),
+            ("vanilla-cake", Some("main"), "vanilla-cake.cook"),
             ("pasta", Some("main"), "pasta.cook"),
-            ("pasta", Some("main"), "steak.cook"),
         ],
     )
     .await;
 
-    // Get recipes in desserts category
+    // Search for "cake"
     let app = build_router();
     let response = app
-        .oneshot(make_request("GET", "/api/v1/categories/desserts", None))
+        .oneshot(make_request("GET", "/api/v1/recipes/search?q=cake", None))
         .await
         .unwrap();
 
-    assert_eq!(response.status(), axum::http::StatusCode::OK);
-
     let body = extract_response_body(response).await;
     let json: Value = serde_json::from_str(&body).unwrap();
 
-    let recipes = json["recipes"].as_array().unwrap();
-    assert_eq!(recipes.len(), 2);
+    let results = json["recipes"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
 
-    let names: Vec<&str> = recipes
+    let names: Vec<&str> = results
         .iter()
         .map(|r| r["recipeName"].as_str().unwrap())
         .collect();
-    assert!(names.contains(&"Cake"));
-    assert!(names.contains(&"Cake"));
+    assert!(names.contains(&"Chocolate Cake"));
+    assert!(names.contains(&"Vanilla Cake"));
 }
 
 #[tokio::test]
-async fn test_get_recipes_in_category_git() {
-    test_get_recipes_in_category_impl("git").await;
+async fn test_search_recipes_by_name_git() {
+    test_search_recipes_by_name_impl("git").await;
 }
 
 #[tokio::test]
-async fn test_get_recipes_in_category_disk() {
-    test_get_recipes_in_category_impl("disk").await;
+async fn test_search_recipes_by_name_disk() {
+    test_search_recipes_by_name_impl("disk").await;
 }
 
-async fn test_get_category_not_found_impl(backend: &str) {
-    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
-    let app = build_router();
+async fn test_search_case_insensitive_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_seeded_fixtures(
+        backend,
+        vec![("chocolate-cake", Some("desserts"), "chocolate-cake.cook")],
+    )
+    .await;
 
+    // Search with different cases
+    let app = build_router();
     let response = app
-        .oneshot(make_request("GET", "/api/v1/categories/nonexistent", None))
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/recipes/search?q=CHOCOLATE",
+            None,
+        ))
         .await
         .unwrap();
 
-    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(json["recipes"].as_array().unwrap().len(), 1);
 }
 
 #[tokio::test]
-async fn test_get_category_not_found_git() {
-    test_get_category_not_found_impl("git").await;
+async fn test_search_case_insensitive_git() {
+    test_search_case_insensitive_impl("git").await;
 }
 
 #[tokio::test]
-async fn test_get_category_not_found_disk() {
-    test_get_category_not_found_impl("disk").await;
+async fn test_search_case_insensitive_disk() {
+    test_search_case_insensitive_impl("disk").await;
 }
 
-// ============================================================================
-// RECIPE UPDATE TESTS
-// ============================================================================
-
-async fn test_update_recipe_impl(backend: &str) -> TempDir {
-    let (build_router, temp_dir) = setup_api_with_storage(backend).await;
-    let app1 = build_router();
-
-    // Create a recipe
-    let create_content = load_recipe_fixture("original-name");
-    let create_payload = serde_json::json!({
-        "content": create_content.clone(),
-        "path": "desserts"
-    });
+async fn test_search_ranking_and_highlights_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
 
-    let response = app1
-        .oneshot(make_request(
-            "POST",
-            "/api/v1/recipes",
-            Some(create_payload),
-        ))
+    for content in [
+        "---\ntitle: Banana Bread\n---\n\nMash @bananas{3} and fold into the batter.\n",
+        "---\ntitle: Smoothie\n---\n\nBlend @banana{1} with @milk{200%ml}.\n",
+        "---\ntitle: Pancakes\n---\n\nServe with sliced banana.\n",
+    ] {
+        let payload = serde_json::json!({ "content": content });
+        let response = build_router()
+            .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    }
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes/search?q=banana", None))
         .await
         .unwrap();
-
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
     let body = extract_response_body(response).await;
     let json: Value = serde_json::from_str(&body).unwrap();
-    let recipe_id = json["recipeId"].as_str().unwrap().to_string();
 
-    // Verify original file exists
-    verify_recipe_file_exists(&temp_dir, "Original Name", "desserts");
-    let original_content = read_recipe_file(&temp_dir, "Original Name", "desserts");
-    assert_eq!(original_content, create_content);
+    // Name match first, then ingredient match, then step match
+    let results = json["recipes"].as_array().unwrap();
+    let names: Vec<&str> = results
+        .iter()
+        .map(|r| r["recipeName"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["Banana Bread", "Smoothie", "Pancakes"]);
+    assert!(results[0]["score"].as_f64().unwrap() > results[1]["score"].as_f64().unwrap());
+    assert_eq!(json["pagination"]["total"], 3);
 
-    // Update the recipe
-    let app2 = build_router();
-    let update_content = load_recipe_fixture("updated-name");
-    let update_payload = serde_json::json!({
-        "content": update_content.clone(),
-        "path": "main"
-    });
+    let highlight = &results[0]["highlights"][0];
+    assert_eq!(highlight["field"], "name");
+    assert_eq!(highlight["snippet"], "Banana Bread");
+    assert_eq!(highlight["matches"], serde_json::json!([[0, 6]]));
 
-    let response = app2
+    assert_eq!(results[1]["highlights"][0]["field"], "ingredient");
+    let step = &results[2]["highlights"][0];
+    assert_eq!(step["field"], "step");
+    assert_eq!(step["snippet"], "Serve with sliced banana.");
+    assert_eq!(step["matches"], serde_json::json!([[18, 24]]));
+
+    // Find-by-name still only looks at names
+    let response = build_router()
         .oneshot(make_request(
-            "PUT",
-            &format!("/api/v1/recipes/{}", recipe_id),
-            Some(update_payload.clone()),
+            "GET",
+            "/api/v1/recipes/find-by-name?q=banana",
+            None,
         ))
         .await
         .unwrap();
-
-    assert_eq!(response.status(), axum::http::StatusCode::OK);
-
     let body = extract_response_body(response).await;
     let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["recipes"].as_array().unwrap().len(), 1);
+}
 
-    assert_eq!(json["recipeName"], "Updated Name");
-    assert_eq!(json["path"], "main");
+#[tokio::test]
+async fn test_search_ranking_and_highlights_git() {
+    test_search_ranking_and_highlights_impl("git").await;
+}
 
-    // Verify file was updated on disk (moved to new category)
-    let filename = verify_recipe_file_exists(&temp_dir, "Updated Name", "main");
-    assert!(filename.ends_with(".cook"));
+#[tokio::test]
+async fn test_search_ranking_and_highlights_disk() {
+    test_search_ranking_and_highlights_impl("disk").await;
+}
 
-    // Verify file contents were updated
-    let updated_file_content = read_recipe_file(&temp_dir, "Updated Name", "main");
-    assert_eq!(updated_file_content, update_content);
+async fn test_search_combined_filters_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
 
-    // Verify original file is gone from desserts category
-    verify_recipe_file_deleted(&temp_dir, "Original Name", "desserts");
+    for (content, path) in [
+        (
+            "---\ntitle: Tomato Soup\ntags: [vegan, quick]\ntime: 20 minutes\n---\n\nSimmer @tomatoes{4} with @garlic{2%cloves}.\n",
+            "mains/soups",
+        ),
+        (
+            "---\ntitle: Garlic Stew\ntags: [vegan]\ntime: 90 minutes\n---\n\nBraise @carrots{3} with @garlic{1%clove}.\n",
+            "mains",
+        ),
+        (
+            "---\ntitle: Garlic Bread\ntags: [quick]\ntime: 15 minutes\n---\n\nToast @bread{1%loaf} with @garlic{}.\n",
+            "sides",
+        ),
+    ] {
+        let payload = serde_json::json!({ "content": content, "path": path });
+        let response = build_router()
+            .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    }
+
+    let names = |uri: &'static str| {
+        let app = build_router();
+        async move {
+            let response = app.oneshot(make_request("GET", uri, None)).await.unwrap();
+            assert_eq!(response.status(), axum::http::StatusCode::OK);
+            let body = extract_response_body(response).await;
+            let json: Value = serde_json::from_str(&body).unwrap();
+            json["recipes"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|r| r["recipeName"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        }
+    };
+
+    // Filters narrow the text search (AND semantics)
+    assert_eq!(
+        names("/api/v1/recipes/search?q=garlic&path=mains").await,
+        vec!["Garlic Stew", "Tomato Soup"]
+    );
+    assert_eq!(
+        names("/api/v1/recipes/search?q=garlic&path=mains&max_time=30").await,
+        vec!["Tomato Soup"]
+    );
+    assert_eq!(
+        names("/api/v1/recipes/search?q=garlic&tag=quick").await,
+        vec!["Garlic Bread", "Tomato Soup"]
+    );
 
-    temp_dir
+    // Without `q`, filters alone list matching recipes by name
+    assert_eq!(
+        names("/api/v1/recipes/search?tag=vegan,quick").await,
+        vec!["Tomato Soup"]
+    );
+    assert_eq!(
+        names("/api/v1/recipes/search?ingredient=garlic,carrots").await,
+        vec!["Garlic Stew"]
+    );
+    assert!(names("/api/v1/recipes/search?path=sides&tag=vegan")
+        .await
+        .is_empty());
+
+    // Neither a query nor a filter is rejected
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes/search", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
 }
 
 #[tokio::test]
-async fn test_update_recipe_git() {
-    let temp_dir = test_update_recipe_impl("git").await;
-
-    // Git-specific verification
-    let commit_count = count_git_commits(&temp_dir);
-    assert!(
-        commit_count >= 2,
-        "Expected at least 2 commits (create + update) in git repo"
-    );
+async fn test_search_combined_filters_git() {
+    test_search_combined_filters_impl("git").await;
 }
 
 #[tokio::test]
-async fn test_update_recipe_disk() {
-    let _temp_dir = test_update_recipe_impl("disk").await;
+async fn test_search_combined_filters_disk() {
+    test_search_combined_filters_impl("disk").await;
 }
 
-async fn test_update_recipe_not_found_impl(backend: &str) {
+async fn test_draft_recipes_impl(backend: &str) {
     let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
-    let app = build_router();
 
-    let payload = serde_json::json!({
-        "content": "# Updated\n\n@flour{2%cup}",
-        "path": "desserts"
-    });
-
-    let response = app
-        .oneshot(make_request(
-            "PUT",
-            "/api/v1/recipes/nonexistent",
-            Some(payload),
-        ))
-        .await
-        .unwrap();
-
-    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+    for content in [
+        "---\ntitle: Lemon Tart\n---\n\nBake @lemons{3} into a tart.\n",
+        "---\ntitle: Lemon Curd\ndraft: true\n---\n\nWhisk @lemons{2} with @eggs{3}.\n",
+    ] {
+        let payload = serde_json::json!({ "content": content });
+        let response = build_router()
+            .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    }
+
+    let names = |uri: &'static str| {
+        let app = build_router();
+        async move {
+            let response = app.oneshot(make_request("GET", uri, None)).await.unwrap();
+            assert_eq!(response.status(), axum::http::StatusCode::OK);
+            let body = extract_response_body(response).await;
+            let json: Value = serde_json::from_str(&body).unwrap();
+            let mut names = json["recipes"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|r| r["recipeName"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>();
+            names.sort();
+            names
+        }
+    };
+
+    // Drafts are indexed but hidden unless asked for
+    assert_eq!(names("/api/v1/recipes").await, vec!["Lemon Tart"]);
+    assert_eq!(
+        names("/api/v1/recipes?include_drafts=true").await,
+        vec!["Lemon Curd", "Lemon Tart"]
+    );
+    assert_eq!(
+        names("/api/v1/recipes/search?q=lemon").await,
+        vec!["Lemon Tart"]
+    );
+    assert_eq!(
+        names("/api/v1/recipes/search?q=lemon&include_drafts=true").await,
+        vec!["Lemon Curd", "Lemon Tart"]
+    );
 }
 
 #[tokio::test]
-async fn test_update_recipe_not_found_git() {
-    test_update_recipe_not_found_impl("git").await;
+async fn test_draft_recipes_git() {
+    test_draft_recipes_impl("git").await;
 }
 
 #[tokio::test]
-async fn test_update_recipe_not_found_disk() {
-    test_update_recipe_not_found_impl("disk").await;
+async fn test_draft_recipes_disk() {
+    test_draft_recipes_impl("disk").await;
 }
 
 // ============================================================================
-// RECIPE DELETE TESTS
+// CATEGORY TESTS
 // ============================================================================
 
-async fn test_delete_recipe_impl(backend: &str) -> TempDir {
-    let (build_router, temp_dir) = setup_api_with_storage(backend).await;
-    let app1 = build_router();
-
-    // Create a recipe
-    let content = load_recipe_fixture("to-delete");
-    let payload = serde_json::json!({
-        "content": content,
-        "path": "desserts"
-    });
+async fn test_list_categories_empty_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
+    let app = build_router();
 
-    let response = app1
-        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+    let response = app
+        .oneshot(make_request("GET", "/api/v1/categories", None))
         .await
         .unwrap();
 
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+
     let body = extract_response_body(response).await;
     let json: Value = serde_json::from_str(&body).unwrap();
-    let recipe_id = json["recipeId"].as_str().unwrap().to_string();
-
-    // Verify file exists before deletion
-    verify_recipe_file_exists(&temp_dir, "To Delete", "desserts");
-
-    // Delete the recipe
-    let app2 = build_router();
-    let response = app2
-        .oneshot(make_request(
-            "DELETE",
-            &format!("/api/v1/recipes/{}", recipe_id),
-            None,
-        ))
-        .await
-        .unwrap();
-
-    assert_eq!(response.status(), axum::http::StatusCode::NO_CONTENT);
-
-    // Verify file was deleted from disk
-    verify_recipe_file_deleted(&temp_dir, "To Delete", "desserts");
-
-    // Verify it's deleted via API
-    let app3 = build_router();
-    let response = app3
-        .oneshot(make_request(
-            "GET",
-            &format!("/api/v1/recipes/{}", recipe_id),
-            None,
-        ))
-        .await
-        .unwrap();
 
-    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
-
-    temp_dir
+    assert_eq!(json["categories"].as_array().unwrap().len(), 0);
 }
 
 #[tokio::test]
-async fn test_delete_recipe_git() {
-    let temp_dir = test_delete_recipe_impl("git").await;
-
-    // Git-specific verification
-    let commit_count = count_git_commits(&temp_dir);
-    assert!(
-        commit_count >= 2,
-        "Expected at least 2 commits (create + delete) in git repo"
-    );
+async fn test_list_categories_empty_git() {
+    test_list_categories_empty_impl("git").await;
 }
 
 #[tokio::test]
-async fn test_delete_recipe_disk() {
-    let _temp_dir = test_delete_recipe_impl("disk").await;
+async fn test_list_categories_empty_disk() {
+    test_list_categories_empty_impl("disk").await;
 }
 
-async fn test_delete_recipe_not_found_impl(backend: &str) {
-    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
-    let app = build_router();
+async fn test_list_categories_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_seeded_fixtures(
+        backend,
+        vec![
+            ("cake", Some("desserts"), "cake.cook"),
+            ("pasta", Some("main"), "pasta.cook"),
+            ("test-recipe", Some("appetizers"), "test-recipe.cook"),
+        ],
+    )
+    .await;
 
+    // List categories
+    let app = build_router();
     let response = app
-        .oneshot(make_request("DELETE", "/api/v1/recipes/nonexistent", None))
+        .oneshot(make_request("GET", "/api/v1/categories", None))
         .await
         .unwrap();
 
-    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+
+    let cats = json["categories"].as_array().unwrap();
+    assert_eq!(cats.len(), 3);
+
+    let cat_names: Vec<&str> = cats.iter().map(|c| c["path"].as_str().unwrap()).collect();
+    assert!(cat_names.contains(&"desserts"));
+    assert!(cat_names.contains(&"main"));
+    assert!(cat_names.contains(&"appetizers"));
 }
 
 #[tokio::test]
-async fn test_delete_recipe_not_found_git() {
-    test_delete_recipe_not_found_impl("git").await;
+async fn test_list_categories_git() {
+    test_list_categories_impl("git").await;
 }
 
 #[tokio::test]
-async fn test_delete_recipe_not_found_disk() {
-    test_delete_recipe_not_found_impl("disk").await;
+async fn test_list_categories_disk() {
+    test_list_categories_impl("disk").await;
 }
 
-// ============================================================================
-// STATUS AFTER MODIFICATIONS TESTS
-// ============================================================================
-
-async fn test_status_updates_with_recipes_impl(backend: &str) {
+async fn test_get_recipes_in_category_impl(backend: &str) {
     let (build_router, _temp_dir) = setup_api_with_seeded_fixtures(
         backend,
         vec![
             ("cake", Some("desserts"), "cake.cook"),
+            ("cake", Some("desserts"), "cookie.cook"),
             ("pasta", Some("main"), "pasta.cook"),
+            ("pasta", Some("main"), "steak.cook"),
         ],
     )
     .await;
 
-    // Check status
+    // Get recipes in desserts category
     let app = build_router();
     let response = app
-        .oneshot(make_request("GET", "/api/v1/status", None))
+        .oneshot(make_request("GET", "/api/v1/categories/desserts", None))
         .await
         .unwrap();
 
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+
     let body = extract_response_body(response).await;
     let json: Value = serde_json::from_str(&body).unwrap();
 
-    assert_eq!(json["recipe_count"], 2);
-    assert_eq!(json["categories"], 2);
+    let recipes = json["recipes"].as_array().unwrap();
+    assert_eq!(recipes.len(), 2);
+
+    let names: Vec<&str> = recipes
+        .iter()
+        .map(|r| r["recipeName"].as_str().unwrap())
+        .collect();
+    assert!(names.contains(&"Cake"));
+    assert!(names.contains(&"Cake"));
 }
 
 #[tokio::test]
-async fn test_status_updates_with_recipes_git() {
-    test_status_updates_with_recipes_impl("git").await;
+async fn test_get_recipes_in_category_git() {
+    test_get_recipes_in_category_impl("git").await;
 }
 
 #[tokio::test]
-async fn test_status_updates_with_recipes_disk() {
-    test_status_updates_with_recipes_impl("disk").await;
+async fn test_get_recipes_in_category_disk() {
+    test_get_recipes_in_category_impl("disk").await;
 }
 
-// ============================================================================
-// HIERARCHICAL CATEGORY TESTS
-// ============================================================================
-
-async fn test_create_recipe_in_nested_category_impl(backend: &str) -> TempDir {
-    let (build_router, temp_dir) = setup_api_with_storage(backend).await;
+async fn test_get_category_not_found_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
     let app = build_router();
 
-    // Create recipe in nested category
-    let content = load_recipe_fixture("chicken-biryani");
-    let create_payload = serde_json::json!({
-        "content": content,
-        "path": "meals/meat/traditional"
-    });
-
     let response = app
-        .oneshot(make_request(
-            "POST",
-            "/api/v1/recipes",
-            Some(create_payload),
-        ))
+        .oneshot(make_request("GET", "/api/v1/categories/nonexistent", None))
         .await
         .unwrap();
 
-    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
-
-    let body = extract_response_body(response).await;
-    let json: Value = serde_json::from_str(&body).unwrap();
-
-    assert_eq!(json["recipeName"], "Chicken Biryani");
-    assert_eq!(json["path"], "meals/meat/traditional");
-
-    // Verify nested directory structure exists on disk
-    verify_recipe_file_exists(&temp_dir, "Chicken Biryani", "meals/meat/traditional");
-
-    temp_dir
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
 }
 
 #[tokio::test]
-async fn test_create_recipe_in_nested_category_git() {
-    let temp_dir = test_create_recipe_in_nested_category_impl("git").await;
-
-    // Git-specific verification
-    let commit_count = count_git_commits(&temp_dir);
-    assert!(commit_count > 0);
+async fn test_get_category_not_found_git() {
+    test_get_category_not_found_impl("git").await;
 }
 
 #[tokio::test]
-async fn test_create_recipe_in_nested_category_disk() {
-    let _temp_dir = test_create_recipe_in_nested_category_impl("disk").await;
+async fn test_get_category_not_found_disk() {
+    test_get_category_not_found_impl("disk").await;
 }
 
-async fn test_read_recipe_from_nested_category_impl(backend: &str) {
+async fn test_category_breadcrumb_impl(backend: &str) {
     let (build_router, _temp_dir) = setup_api_with_seeded_fixtures(
         backend,
-        vec![(
-            "thai-green-curry",
-            Some("meals/asian/thai"),
-            "thai-green-curry.cook",
-        )],
+        vec![
+            ("cake", Some("meals/meat"), "cake.cook"),
+            ("pasta", Some("meals"), "pasta.cook"),
+        ],
     )
     .await;
 
-    // Get the recipe by listing all
     let app = build_router();
     let response = app
-        .oneshot(make_request("GET", "/api/v1/recipes", None))
+        .oneshot(make_request("GET", "/api/v1/categories/meals%2Fmeat", None))
         .await
         .unwrap();
 
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+
     let body = extract_response_body(response).await;
     let json: Value = serde_json::from_str(&body).unwrap();
-    let recipes = json["recipes"].as_array().unwrap();
-    assert_eq!(recipes.len(), 1);
-
-    let recipe_id = recipes[0]["recipeId"].as_str().unwrap().to_string();
-    assert_eq!(recipes[0]["recipeName"], "Thai Green Curry");
-    assert_eq!(recipes[0]["path"], "meals/asian/thai");
 
-    // Verify content contains expected ingredient
-    let app2 = build_router();
-    let response = app2
+    let breadcrumb = json["breadcrumb"].as_array().unwrap();
+    assert_eq!(breadcrumb.len(), 2);
+    assert_eq!(breadcrumb[0]["name"], "meals");
+    assert_eq!(breadcrumb[0]["path"], "meals");
+    assert_eq!(breadcrumb[0]["recipeCount"], 2);
+    assert_eq!(breadcrumb[1]["name"], "meat");
+    assert_eq!(breadcrumb[1]["path"], "meals/meat");
+    assert_eq!(breadcrumb[1]["recipeCount"], 1);
+
+    // Recipe responses carry the same breadcrumb for their path
+    let recipe_id = json["recipes"][0]["recipeId"].as_str().unwrap();
+    let app = build_router();
+    let response = app
         .oneshot(make_request(
             "GET",
             &format!("/api/v1/recipes/{}", recipe_id),
@@ -1027,38 +1707,35 @@ async fn test_read_recipe_from_nested_category_impl(backend: &str) {
         .await
         .unwrap();
 
-    assert_eq!(response.status(), axum::http::StatusCode::OK);
-
     let body = extract_response_body(response).await;
     let json: Value = serde_json::from_str(&body).unwrap();
-
-    assert_eq!(json["recipeName"], "Thai Green Curry");
-    assert_eq!(json["path"], "meals/asian/thai");
-    assert!(json["content"]
-        .as_str()
-        .unwrap()
-        .contains("@coconut-milk{400%ml}"));
+    assert_eq!(json["breadcrumb"].as_array().unwrap().len(), 2);
+    assert_eq!(json["breadcrumb"][1]["path"], "meals/meat");
 }
 
 #[tokio::test]
-async fn test_read_recipe_from_nested_category_git() {
-    test_read_recipe_from_nested_category_impl("git").await;
+async fn test_category_breadcrumb_git() {
+    test_category_breadcrumb_impl("git").await;
 }
 
 #[tokio::test]
-async fn test_read_recipe_from_nested_category_disk() {
-    test_read_recipe_from_nested_category_impl("disk").await;
+async fn test_category_breadcrumb_disk() {
+    test_category_breadcrumb_impl("disk").await;
 }
 
-async fn test_move_recipe_between_nested_categories_impl(backend: &str) -> TempDir {
+// ============================================================================
+// RECIPE UPDATE TESTS
+// ============================================================================
+
+async fn test_update_recipe_impl(backend: &str) -> TempDir {
     let (build_router, temp_dir) = setup_api_with_storage(backend).await;
     let app1 = build_router();
 
-    // Create recipe in one nested category
-    let content = load_recipe_fixture("chocolate-cake");
+    // Create a recipe
+    let create_content = load_recipe_fixture("original-name");
     let create_payload = serde_json::json!({
-        "content": content,
-        "path": "desserts/cakes/chocolate"
+        "content": create_content.clone(),
+        "path": "desserts"
     });
 
     let response = app1
@@ -1074,20 +1751,24 @@ async fn test_move_recipe_between_nested_categories_impl(backend: &str) -> TempD
     let json: Value = serde_json::from_str(&body).unwrap();
     let recipe_id = json["recipeId"].as_str().unwrap().to_string();
 
-    // Verify it exists in original nested category
-    verify_recipe_file_exists(&temp_dir, "Chocolate Cake", "desserts/cakes/chocolate");
+    // Verify original file exists
+    verify_recipe_file_exists(&temp_dir, "Original Name", "desserts");
+    let original_content = read_recipe_file(&temp_dir, "Original Name", "desserts");
+    assert_eq!(original_content, create_content);
 
-    // Move to different nested category
+    // Update the recipe
     let app2 = build_router();
+    let update_content = load_recipe_fixture("updated-name");
     let update_payload = serde_json::json!({
-        "path": "desserts/cakes/dark-chocolate"
+        "content": update_content.clone(),
+        "path": "main"
     });
 
     let response = app2
         .oneshot(make_request(
             "PUT",
             &format!("/api/v1/recipes/{}", recipe_id),
-            Some(update_payload),
+            Some(update_payload.clone()),
         ))
         .await
         .unwrap();
@@ -1096,99 +1777,89 @@ async fn test_move_recipe_between_nested_categories_impl(backend: &str) -> TempD
 
     let body = extract_response_body(response).await;
     let json: Value = serde_json::from_str(&body).unwrap();
-    assert_eq!(json["path"], "desserts/cakes/dark-chocolate");
 
-    // Verify file moved to new nested category
-    verify_recipe_file_exists(&temp_dir, "Chocolate Cake", "desserts/cakes/dark-chocolate");
+    assert_eq!(json["recipeName"], "Updated Name");
+    assert_eq!(json["path"], "main");
 
-    // Verify file no longer exists in original category
-    verify_recipe_file_deleted(&temp_dir, "Chocolate Cake", "desserts/cakes/chocolate");
+    // Verify file was updated on disk (moved to new category)
+    let filename = verify_recipe_file_exists(&temp_dir, "Updated Name", "main");
+    assert!(filename.ends_with(".cook"));
+
+    // Verify file contents were updated
+    let updated_file_content = read_recipe_file(&temp_dir, "Updated Name", "main");
+    assert_eq!(updated_file_content, update_content);
+
+    // Verify original file is gone from desserts category
+    verify_recipe_file_deleted(&temp_dir, "Original Name", "desserts");
 
     temp_dir
 }
 
 #[tokio::test]
-async fn test_move_recipe_between_nested_categories_git() {
-    let _temp_dir = test_move_recipe_between_nested_categories_impl("git").await;
+async fn test_update_recipe_git() {
+    let temp_dir = test_update_recipe_impl("git").await;
+
+    // Git-specific verification
+    let commit_count = count_git_commits(&temp_dir);
+    assert!(
+        commit_count >= 2,
+        "Expected at least 2 commits (create + update) in git repo"
+    );
 }
 
 #[tokio::test]
-async fn test_move_recipe_between_nested_categories_disk() {
-    let _temp_dir = test_move_recipe_between_nested_categories_impl("disk").await;
+async fn test_update_recipe_disk() {
+    let _temp_dir = test_update_recipe_impl("disk").await;
 }
 
-async fn test_get_recipes_from_nested_category_impl(backend: &str) {
-    let (build_router, _temp_dir) = setup_api_with_seeded_fixtures(
-        backend,
-        vec![
-            ("pad-thai", Some("meals/asian/thai"), "pad-thai.cook"),
-            ("green-curry", Some("meals/asian/thai"), "green-curry.cook"),
-            (
-                "spaghetti",
-                Some("meals/european/italian"),
-                "spaghetti.cook",
-            ),
-        ],
-    )
-    .await;
-
-    // Get recipes from nested Thai category (URL-encoded)
+async fn test_update_recipe_not_found_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
     let app = build_router();
+
+    let payload = serde_json::json!({
+        "content": "# Updated\n\n@flour{2%cup}",
+        "path": "desserts"
+    });
+
     let response = app
         .oneshot(make_request(
-            "GET",
-            "/api/v1/categories/meals%2Fasian%2Fthai",
-            None,
+            "PUT",
+            "/api/v1/recipes/nonexistent",
+            Some(payload),
         ))
         .await
         .unwrap();
 
-    assert_eq!(response.status(), axum::http::StatusCode::OK);
-
-    let body = extract_response_body(response).await;
-    let json: Value = serde_json::from_str(&body).unwrap();
-
-    assert_eq!(json["path"], "meals/asian/thai");
-    assert_eq!(json["count"], 2);
-
-    let recipes = json["recipes"].as_array().unwrap();
-    assert_eq!(recipes.len(), 2);
-
-    let names: Vec<String> = recipes
-        .iter()
-        .map(|r| r["recipeName"].as_str().unwrap().to_string())
-        .collect();
-    assert!(names.contains(&"Pad Thai".to_string()));
-    assert!(names.contains(&"Green Curry".to_string()));
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
 }
 
 #[tokio::test]
-async fn test_get_recipes_from_nested_category_git() {
-    test_get_recipes_from_nested_category_impl("git").await;
+async fn test_update_recipe_not_found_git() {
+    test_update_recipe_not_found_impl("git").await;
 }
 
 #[tokio::test]
-async fn test_get_recipes_from_nested_category_disk() {
-    test_get_recipes_from_nested_category_impl("disk").await;
+async fn test_update_recipe_not_found_disk() {
+    test_update_recipe_not_found_impl("disk").await;
 }
 
-async fn test_move_recipe_between_flat_and_nested_category_impl(backend: &str) -> TempDir {
+// ============================================================================
+// RECIPE DELETE TESTS
+// ============================================================================
+
+async fn test_delete_recipe_impl(backend: &str) -> TempDir {
     let (build_router, temp_dir) = setup_api_with_storage(backend).await;
     let app1 = build_router();
 
-    // Create recipe in flat category
-    let content = load_recipe_fixture("vanilla-cake");
-    let create_payload = serde_json::json!({
+    // Create a recipe
+    let content = load_recipe_fixture("to-delete");
+    let payload = serde_json::json!({
         "content": content,
         "path": "desserts"
     });
 
     let response = app1
-        .oneshot(make_request(
-            "POST",
-            "/api/v1/recipes",
-            Some(create_payload),
-        ))
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
         .await
         .unwrap();
 
@@ -1196,109 +1867,134 @@ async fn test_move_recipe_between_flat_and_nested_category_impl(backend: &str) -
     let json: Value = serde_json::from_str(&body).unwrap();
     let recipe_id = json["recipeId"].as_str().unwrap().to_string();
 
-    // Verify in flat category
-    verify_recipe_file_exists(&temp_dir, "Vanilla Cake", "desserts");
+    // Verify file exists before deletion
+    verify_recipe_file_exists(&temp_dir, "To Delete", "desserts");
 
-    // Move to nested category
+    // Delete the recipe
     let app2 = build_router();
-    let update_payload = serde_json::json!({
-        "path": "desserts/cakes/vanilla"
-    });
-
     let response = app2
         .oneshot(make_request(
-            "PUT",
+            "DELETE",
             &format!("/api/v1/recipes/{}", recipe_id),
-            Some(update_payload),
+            None,
         ))
         .await
         .unwrap();
 
-    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    assert_eq!(response.status(), axum::http::StatusCode::NO_CONTENT);
 
-    let body = extract_response_body(response).await;
-    let json: Value = serde_json::from_str(&body).unwrap();
-    assert_eq!(json["path"], "desserts/cakes/vanilla");
+    // Verify file was deleted from disk
+    verify_recipe_file_deleted(&temp_dir, "To Delete", "desserts");
 
-    // Verify moved to nested category
-    verify_recipe_file_exists(&temp_dir, "Vanilla Cake", "desserts/cakes/vanilla");
-    verify_recipe_file_deleted(&temp_dir, "Vanilla Cake", "desserts");
+    // Verify it's deleted via API
+    let app3 = build_router();
+    let response = app3
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
 
     temp_dir
 }
 
 #[tokio::test]
-async fn test_move_recipe_between_flat_and_nested_category_git() {
-    let _temp_dir = test_move_recipe_between_flat_and_nested_category_impl("git").await;
+async fn test_delete_recipe_git() {
+    let temp_dir = test_delete_recipe_impl("git").await;
+
+    // Git-specific verification
+    let commit_count = count_git_commits(&temp_dir);
+    assert!(
+        commit_count >= 2,
+        "Expected at least 2 commits (create + delete) in git repo"
+    );
 }
 
 #[tokio::test]
-async fn test_move_recipe_between_flat_and_nested_category_disk() {
-    let _temp_dir = test_move_recipe_between_flat_and_nested_category_impl("disk").await;
+async fn test_delete_recipe_disk() {
+    let _temp_dir = test_delete_recipe_impl("disk").await;
 }
 
-async fn test_list_categories_includes_nested_impl(backend: &str) {
+async fn test_delete_recipe_not_found_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
+    let app = build_router();
+
+    let response = app
+        .oneshot(make_request("DELETE", "/api/v1/recipes/nonexistent", None))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_delete_recipe_not_found_git() {
+    test_delete_recipe_not_found_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_delete_recipe_not_found_disk() {
+    test_delete_recipe_not_found_impl("disk").await;
+}
+
+// ============================================================================
+// STATUS AFTER MODIFICATIONS TESTS
+// ============================================================================
+
+async fn test_status_updates_with_recipes_impl(backend: &str) {
     let (build_router, _temp_dir) = setup_api_with_seeded_fixtures(
         backend,
         vec![
-            ("tiramisu", Some("desserts/cakes/italian"), "tiramisu.cook"),
-            (
-                "cheesecake",
-                Some("desserts/cakes/american"),
-                "cheesecake.cook",
-            ),
-            ("flan", Some("desserts/custards"), "flan.cook"),
+            ("cake", Some("desserts"), "cake.cook"),
+            ("pasta", Some("main"), "pasta.cook"),
         ],
     )
     .await;
 
-    // List all categories
+    // Check status
     let app = build_router();
     let response = app
-        .oneshot(make_request("GET", "/api/v1/categories", None))
+        .oneshot(make_request("GET", "/api/v1/status", None))
         .await
         .unwrap();
 
-    assert_eq!(response.status(), axum::http::StatusCode::OK);
-
     let body = extract_response_body(response).await;
     let json: Value = serde_json::from_str(&body).unwrap();
 
-    let categories: Vec<String> = json["categories"]
-        .as_array()
-        .unwrap()
-        .iter()
-        .map(|c| c.as_str().unwrap().to_string())
-        .collect();
-
-    // Should have nested categories, not flattened
-    assert!(categories.contains(&"desserts/cakes/italian".to_string()));
-    assert!(categories.contains(&"desserts/cakes/american".to_string()));
-    assert!(categories.contains(&"desserts/custards".to_string()));
+    assert_eq!(json["recipe_count"], 2);
+    assert_eq!(json["categories"], 2);
 }
 
 #[tokio::test]
-async fn test_list_categories_includes_nested_git() {
-    test_list_categories_includes_nested_impl("git").await;
+async fn test_status_updates_with_recipes_git() {
+    test_status_updates_with_recipes_impl("git").await;
 }
 
 #[tokio::test]
-async fn test_list_categories_includes_nested_disk() {
-    test_list_categories_includes_nested_impl("disk").await;
+async fn test_status_updates_with_recipes_disk() {
+    test_status_updates_with_recipes_impl("disk").await;
 }
 
-async fn test_move_between_different_category_structures_impl(backend: &str) {
-    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
-    let app1 = build_router();
+// ============================================================================
+// HIERARCHICAL CATEGORY TESTS
+// ============================================================================
 
-    // Create recipe in one category structure
-    let content = load_recipe_fixture("authors-dinner");
+async fn test_create_recipe_in_nested_category_impl(backend: &str) -> TempDir {
+    let (build_router, temp_dir) = setup_api_with_storage(backend).await;
+    let app = build_router();
+
+    // Create recipe in nested category
+    let content = load_recipe_fixture("chicken-biryani");
     let create_payload = serde_json::json!({
         "content": content,
-        "path": "author1/dinner/meat"
+        "path": "meals/meat/traditional"
     });
 
-    let response = app1
+    let response = app
         .oneshot(make_request(
             "POST",
             "/api/v1/recipes",
@@ -1311,21 +2007,64 @@ async fn test_move_between_different_category_structures_impl(backend: &str) {
 
     let body = extract_response_body(response).await;
     let json: Value = serde_json::from_str(&body).unwrap();
-    let recipe_id = json["recipeId"].as_str().unwrap().to_string();
 
-    assert_eq!(json["path"], "author1/dinner/meat");
+    assert_eq!(json["recipeName"], "Chicken Biryani");
+    assert_eq!(json["path"], "meals/meat/traditional");
 
-    // Move to completely different category structure
-    let app2 = build_router();
-    let update_payload = serde_json::json!({
-        "path": "author2/meat/dinner"
-    });
+    // Verify nested directory structure exists on disk
+    verify_recipe_file_exists(&temp_dir, "Chicken Biryani", "meals/meat/traditional");
+
+    temp_dir
+}
+
+#[tokio::test]
+async fn test_create_recipe_in_nested_category_git() {
+    let temp_dir = test_create_recipe_in_nested_category_impl("git").await;
+
+    // Git-specific verification
+    let commit_count = count_git_commits(&temp_dir);
+    assert!(commit_count > 0);
+}
+
+#[tokio::test]
+async fn test_create_recipe_in_nested_category_disk() {
+    let _temp_dir = test_create_recipe_in_nested_category_impl("disk").await;
+}
+
+async fn test_read_recipe_from_nested_category_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_seeded_fixtures(
+        backend,
+        vec![(
+            "thai-green-curry",
+            Some("meals/asian/thai"),
+            "thai-green-curry.cook",
+        )],
+    )
+    .await;
+
+    // Get the recipe by listing all
+    let app = build_router();
+    let response = app
+        .oneshot(make_request("GET", "/api/v1/recipes", None))
+        .await
+        .unwrap();
+
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let recipes = json["recipes"].as_array().unwrap();
+    assert_eq!(recipes.len(), 1);
+
+    let recipe_id = recipes[0]["recipeId"].as_str().unwrap().to_string();
+    assert_eq!(recipes[0]["recipeName"], "Thai Green Curry");
+    assert_eq!(recipes[0]["path"], "meals/asian/thai");
 
+    // Verify content contains expected ingredient
+    let app2 = build_router();
     let response = app2
         .oneshot(make_request(
-            "PUT",
+            "GET",
             &format!("/api/v1/recipes/{}", recipe_id),
-            Some(update_payload),
+            None,
         ))
         .await
         .unwrap();
@@ -1335,142 +2074,180 @@ async fn test_move_between_different_category_structures_impl(backend: &str) {
     let body = extract_response_body(response).await;
     let json: Value = serde_json::from_str(&body).unwrap();
 
-    // Verify category updated to new structure
-    assert_eq!(json["path"], "author2/meat/dinner");
-    assert_eq!(json["recipeName"], "Author's Dinner");
+    assert_eq!(json["recipeName"], "Thai Green Curry");
+    assert_eq!(json["path"], "meals/asian/thai");
+    assert!(json["content"]
+        .as_str()
+        .unwrap()
+        .contains("@coconut-milk{400%ml}"));
 }
 
 #[tokio::test]
-async fn test_move_between_different_category_structures_git() {
-    test_move_between_different_category_structures_impl("git").await;
+async fn test_read_recipe_from_nested_category_git() {
+    test_read_recipe_from_nested_category_impl("git").await;
 }
 
 #[tokio::test]
-async fn test_move_between_different_category_structures_disk() {
-    test_move_between_different_category_structures_impl("disk").await;
+async fn test_read_recipe_from_nested_category_disk() {
+    test_read_recipe_from_nested_category_impl("disk").await;
 }
 
-// ============================================================================
-// YAML FRONT MATTER & FILE RENAMING TESTS (PHASE 2.4)
-// ============================================================================
-
-async fn test_create_recipe_missing_yaml_front_matter_impl(backend: &str) {
-    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
-    let app = build_router();
+async fn test_move_recipe_between_nested_categories_impl(backend: &str) -> TempDir {
+    let (build_router, temp_dir) = setup_api_with_storage(backend).await;
+    let app1 = build_router();
 
-    // Content without YAML front matter (no title field)
-    let content = "---\ndescription: This is just a description\n---\n\nNo title provided.";
-    let payload = serde_json::json!({
+    // Create recipe in one nested category
+    let content = load_recipe_fixture("chocolate-cake");
+    let create_payload = serde_json::json!({
         "content": content,
-        "path": "desserts"
+        "path": "desserts/cakes/chocolate"
     });
 
-    let response = app
-        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+    let response = app1
+        .oneshot(make_request(
+            "POST",
+            "/api/v1/recipes",
+            Some(create_payload),
+        ))
         .await
         .unwrap();
 
-    // Should return 400 Bad Request (invalid recipe content)
-    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let recipe_id = json["recipeId"].as_str().unwrap().to_string();
+
+    // Verify it exists in original nested category
+    verify_recipe_file_exists(&temp_dir, "Chocolate Cake", "desserts/cakes/chocolate");
+
+    // Move to different nested category
+    let app2 = build_router();
+    let update_payload = serde_json::json!({
+        "path": "desserts/cakes/dark-chocolate"
+    });
+
+    let response = app2
+        .oneshot(make_request(
+            "PUT",
+            &format!("/api/v1/recipes/{}", recipe_id),
+            Some(update_payload),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
 
     let body = extract_response_body(response).await;
-    // Error message should mention title or front matter
-    assert!(
-        body.to_lowercase().contains("title") || body.to_lowercase().contains("front matter"),
-        "Error body: {}",
-        body
-    );
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["path"], "desserts/cakes/dark-chocolate");
+
+    // Verify file moved to new nested category
+    verify_recipe_file_exists(&temp_dir, "Chocolate Cake", "desserts/cakes/dark-chocolate");
+
+    // Verify file no longer exists in original category
+    verify_recipe_file_deleted(&temp_dir, "Chocolate Cake", "desserts/cakes/chocolate");
+
+    temp_dir
 }
 
 #[tokio::test]
-async fn test_create_recipe_missing_yaml_front_matter_git() {
-    test_create_recipe_missing_yaml_front_matter_impl("git").await;
+async fn test_move_recipe_between_nested_categories_git() {
+    let _temp_dir = test_move_recipe_between_nested_categories_impl("git").await;
 }
 
 #[tokio::test]
-async fn test_create_recipe_missing_yaml_front_matter_disk() {
-    test_create_recipe_missing_yaml_front_matter_impl("disk").await;
+async fn test_move_recipe_between_nested_categories_disk() {
+    let _temp_dir = test_move_recipe_between_nested_categories_impl("disk").await;
 }
 
-async fn test_create_recipe_with_valid_yaml_front_matter_impl(backend: &str) {
-    let (build_router, temp_dir) = setup_api_with_storage(backend).await;
-    let app = build_router();
-
-    // Content with valid YAML front matter including title
-    let content = "---\ntitle: Chocolate Cake\ndescription: Rich chocolate cake\n---\n\nMix flour with cocoa.";
-    let payload = serde_json::json!({
-        "content": content,
-        "path": "desserts"
-    });
+async fn test_get_recipes_from_nested_category_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_seeded_fixtures(
+        backend,
+        vec![
+            ("pad-thai", Some("meals/asian/thai"), "pad-thai.cook"),
+            ("green-curry", Some("meals/asian/thai"), "green-curry.cook"),
+            (
+                "spaghetti",
+                Some("meals/european/italian"),
+                "spaghetti.cook",
+            ),
+        ],
+    )
+    .await;
 
+    // Get recipes from nested Thai category (URL-encoded)
+    let app = build_router();
     let response = app
-        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/categories/meals%2Fasian%2Fthai",
+            None,
+        ))
         .await
         .unwrap();
 
-    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
 
     let body = extract_response_body(response).await;
     let json: Value = serde_json::from_str(&body).unwrap();
 
-    // The name in the response should be from YAML title, not the request name
-    assert_eq!(json["recipeName"], "Chocolate Cake");
-    assert_eq!(json["path"], "desserts");
-    assert!(json["recipeId"].is_string());
+    assert_eq!(json["path"], "meals/asian/thai");
+    assert_eq!(json["count"], 2);
 
-    // Verify file was created with name derived from title
-    let filename = verify_recipe_file_exists(&temp_dir, "Chocolate Cake", "desserts");
-    assert_eq!(filename, "chocolate-cake.cook");
+    let recipes = json["recipes"].as_array().unwrap();
+    assert_eq!(recipes.len(), 2);
 
-    let file_contents = read_recipe_file(&temp_dir, "Chocolate Cake", "desserts");
-    assert_eq!(file_contents, content);
+    let names: Vec<String> = recipes
+        .iter()
+        .map(|r| r["recipeName"].as_str().unwrap().to_string())
+        .collect();
+    assert!(names.contains(&"Pad Thai".to_string()));
+    assert!(names.contains(&"Green Curry".to_string()));
 }
 
 #[tokio::test]
-async fn test_create_recipe_with_valid_yaml_front_matter_git() {
-    test_create_recipe_with_valid_yaml_front_matter_impl("git").await;
+async fn test_get_recipes_from_nested_category_git() {
+    test_get_recipes_from_nested_category_impl("git").await;
 }
 
 #[tokio::test]
-async fn test_create_recipe_with_valid_yaml_front_matter_disk() {
-    test_create_recipe_with_valid_yaml_front_matter_impl("disk").await;
+async fn test_get_recipes_from_nested_category_disk() {
+    test_get_recipes_from_nested_category_impl("disk").await;
 }
 
-async fn test_update_recipe_title_causes_filename_change_impl(backend: &str) {
+async fn test_move_recipe_between_flat_and_nested_category_impl(backend: &str) -> TempDir {
     let (build_router, temp_dir) = setup_api_with_storage(backend).await;
-    let app = build_router();
+    let app1 = build_router();
 
-    // Step 1: Create initial recipe
-    let initial_content = "---\ntitle: Brownie\n---\n\nChocolate brownie recipe.";
-    let payload = serde_json::json!({
-        "content": initial_content,
+    // Create recipe in flat category
+    let content = load_recipe_fixture("vanilla-cake");
+    let create_payload = serde_json::json!({
+        "content": content,
         "path": "desserts"
     });
 
-    let response = app
-        .clone()
-        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+    let response = app1
+        .oneshot(make_request(
+            "POST",
+            "/api/v1/recipes",
+            Some(create_payload),
+        ))
         .await
         .unwrap();
 
-    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
     let body = extract_response_body(response).await;
     let json: Value = serde_json::from_str(&body).unwrap();
+    let recipe_id = json["recipeId"].as_str().unwrap().to_string();
 
-    let recipe_id = json["recipeId"].as_str().unwrap();
-    let initial_filename = "brownie.cook";
-
-    // Verify initial file exists
-    verify_recipe_file_exists(&temp_dir, "Brownie", "desserts");
+    // Verify in flat category
+    verify_recipe_file_exists(&temp_dir, "Vanilla Cake", "desserts");
 
-    // Step 2: Update recipe with new title
-    let updated_content = "---\ntitle: Fudgy Brownie\n---\n\nExtra fudgy chocolate brownie recipe.";
+    // Move to nested category
+    let app2 = build_router();
     let update_payload = serde_json::json!({
-        "content": updated_content
+        "path": "desserts/cakes/vanilla"
     });
 
-    let response = app
-        .clone()
+    let response = app2
         .oneshot(make_request(
             "PUT",
             &format!("/api/v1/recipes/{}", recipe_id),
@@ -1483,409 +2260,6032 @@ async fn test_update_recipe_title_causes_filename_change_impl(backend: &str) {
 
     let body = extract_response_body(response).await;
     let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["path"], "desserts/cakes/vanilla");
 
-    // Name should be updated to new title
-    assert_eq!(json["recipeName"], "Fudgy Brownie");
-    let new_recipe_id = json["recipeId"].as_str().unwrap();
+    // Verify moved to nested category
+    verify_recipe_file_exists(&temp_dir, "Vanilla Cake", "desserts/cakes/vanilla");
+    verify_recipe_file_deleted(&temp_dir, "Vanilla Cake", "desserts");
 
-    // recipe_id should change (because git_path changed)
+    temp_dir
+}
+
+#[tokio::test]
+async fn test_move_recipe_between_flat_and_nested_category_git() {
+    let _temp_dir = test_move_recipe_between_flat_and_nested_category_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_move_recipe_between_flat_and_nested_category_disk() {
+    let _temp_dir = test_move_recipe_between_flat_and_nested_category_impl("disk").await;
+}
+
+async fn test_list_categories_includes_nested_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_seeded_fixtures(
+        backend,
+        vec![
+            ("tiramisu", Some("desserts/cakes/italian"), "tiramisu.cook"),
+            (
+                "cheesecake",
+                Some("desserts/cakes/american"),
+                "cheesecake.cook",
+            ),
+            ("flan", Some("desserts/custards"), "flan.cook"),
+        ],
+    )
+    .await;
+
+    // List all categories
+    let app = build_router();
+    let response = app
+        .oneshot(make_request("GET", "/api/v1/categories", None))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+
+    let categories: Vec<String> = json["categories"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["path"].as_str().unwrap().to_string())
+        .collect();
+
+    // Should have nested categories, not flattened
+    assert!(categories.contains(&"desserts/cakes/italian".to_string()));
+    assert!(categories.contains(&"desserts/cakes/american".to_string()));
+    assert!(categories.contains(&"desserts/custards".to_string()));
+}
+
+#[tokio::test]
+async fn test_list_categories_counts_recipes() {
+    let recipe = |title: &str| format!("---\ntitle: {}\n---\n\nBake @flour{{200%g}}.\n", title);
+    let (brownies, sponge, lemon, scones) = (
+        recipe("Brownies"),
+        recipe("Sponge"),
+        recipe("Lemon Cake"),
+        recipe("Scones"),
+    );
+    let (build_router, _temp_dir) = setup_api_with_seeded_files(
+        "disk",
+        vec![],
+        vec![
+            ("recipes/desserts/brownies.cook", &brownies),
+            ("recipes/desserts/cakes/sponge.cook", &sponge),
+            ("recipes/desserts/cakes/lemon-cake.cook", &lemon),
+            ("recipes/desserts-old/scones.cook", &scones),
+        ],
+    )
+    .await;
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/categories", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        json["categories"],
+        serde_json::json!([
+            { "path": "desserts", "count": 3, "directCount": 1 },
+            { "path": "desserts-old", "count": 1, "directCount": 1 },
+            { "path": "desserts/cakes", "count": 2, "directCount": 2 },
+        ])
+    );
+}
+
+#[tokio::test]
+async fn test_list_categories_includes_nested_git() {
+    test_list_categories_includes_nested_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_list_categories_includes_nested_disk() {
+    test_list_categories_includes_nested_impl("disk").await;
+}
+
+async fn test_move_between_different_category_structures_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
+    let app1 = build_router();
+
+    // Create recipe in one category structure
+    let content = load_recipe_fixture("authors-dinner");
+    let create_payload = serde_json::json!({
+        "content": content,
+        "path": "author1/dinner/meat"
+    });
+
+    let response = app1
+        .oneshot(make_request(
+            "POST",
+            "/api/v1/recipes",
+            Some(create_payload),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let recipe_id = json["recipeId"].as_str().unwrap().to_string();
+
+    assert_eq!(json["path"], "author1/dinner/meat");
+
+    // Move to completely different category structure
+    let app2 = build_router();
+    let update_payload = serde_json::json!({
+        "path": "author2/meat/dinner"
+    });
+
+    let response = app2
+        .oneshot(make_request(
+            "PUT",
+            &format!("/api/v1/recipes/{}", recipe_id),
+            Some(update_payload),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+
+    // Verify category updated to new structure
+    assert_eq!(json["path"], "author2/meat/dinner");
+    assert_eq!(json["recipeName"], "Author's Dinner");
+}
+
+#[tokio::test]
+async fn test_move_between_different_category_structures_git() {
+    test_move_between_different_category_structures_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_move_between_different_category_structures_disk() {
+    test_move_between_different_category_structures_impl("disk").await;
+}
+
+// ============================================================================
+// YAML FRONT MATTER & FILE RENAMING TESTS (PHASE 2.4)
+// ============================================================================
+
+async fn test_create_recipe_missing_yaml_front_matter_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
+    let app = build_router();
+
+    // Content without YAML front matter (no title field)
+    let content = "---\ndescription: This is just a description\n---\n\nNo title provided.";
+    let payload = serde_json::json!({
+        "content": content,
+        "path": "desserts"
+    });
+
+    let response = app
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+
+    // Should return 400 Bad Request (invalid recipe content)
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+
+    let body = extract_response_body(response).await;
+    // Error message should mention title or front matter
+    assert!(
+        body.to_lowercase().contains("title") || body.to_lowercase().contains("front matter"),
+        "Error body: {}",
+        body
+    );
+}
+
+#[tokio::test]
+async fn test_create_recipe_missing_yaml_front_matter_git() {
+    test_create_recipe_missing_yaml_front_matter_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_create_recipe_missing_yaml_front_matter_disk() {
+    test_create_recipe_missing_yaml_front_matter_impl("disk").await;
+}
+
+async fn test_create_recipe_with_valid_yaml_front_matter_impl(backend: &str) {
+    let (build_router, temp_dir) = setup_api_with_storage(backend).await;
+    let app = build_router();
+
+    // Content with valid YAML front matter including title
+    let content = "---\ntitle: Chocolate Cake\ndescription: Rich chocolate cake\n---\n\nMix flour with cocoa.";
+    let payload = serde_json::json!({
+        "content": content,
+        "path": "desserts"
+    });
+
+    let response = app
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+
+    // The name in the response should be from YAML title, not the request name
+    assert_eq!(json["recipeName"], "Chocolate Cake");
+    assert_eq!(json["path"], "desserts");
+    assert!(json["recipeId"].is_string());
+
+    // Verify file was created with name derived from title
+    let filename = verify_recipe_file_exists(&temp_dir, "Chocolate Cake", "desserts");
+    assert_eq!(filename, "chocolate-cake.cook");
+
+    let file_contents = read_recipe_file(&temp_dir, "Chocolate Cake", "desserts");
+    assert_eq!(file_contents, content);
+}
+
+#[tokio::test]
+async fn test_create_recipe_with_valid_yaml_front_matter_git() {
+    test_create_recipe_with_valid_yaml_front_matter_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_create_recipe_with_valid_yaml_front_matter_disk() {
+    test_create_recipe_with_valid_yaml_front_matter_impl("disk").await;
+}
+
+async fn test_update_recipe_title_causes_filename_change_impl(backend: &str) {
+    let (build_router, temp_dir) = setup_api_with_storage(backend).await;
+    let app = build_router();
+
+    // Step 1: Create initial recipe
+    let initial_content = "---\ntitle: Brownie\n---\n\nChocolate brownie recipe.";
+    let payload = serde_json::json!({
+        "content": initial_content,
+        "path": "desserts"
+    });
+
+    let response = app
+        .clone()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+
+    let recipe_id = json["recipeId"].as_str().unwrap();
+    let initial_filename = "brownie.cook";
+
+    // Verify initial file exists
+    verify_recipe_file_exists(&temp_dir, "Brownie", "desserts");
+
+    // Step 2: Update recipe with new title
+    let updated_content = "---\ntitle: Fudgy Brownie\n---\n\nExtra fudgy chocolate brownie recipe.";
+    let update_payload = serde_json::json!({
+        "content": updated_content
+    });
+
+    let response = app
+        .clone()
+        .oneshot(make_request(
+            "PUT",
+            &format!("/api/v1/recipes/{}", recipe_id),
+            Some(update_payload),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+
+    // Name should be updated to new title
+    assert_eq!(json["recipeName"], "Fudgy Brownie");
+    let new_recipe_id = json["recipeId"].as_str().unwrap();
+
+    // recipe_id should change (because git_path changed)
     assert_ne!(recipe_id, new_recipe_id);
 
-    // Step 3: Verify file was renamed on disk
-    let new_filename = verify_recipe_file_exists(&temp_dir, "Fudgy Brownie", "desserts");
-    assert_eq!(new_filename, "fudgy-brownie.cook");
-    assert_ne!(initial_filename, new_filename);
+    // Step 3: Verify file was renamed on disk
+    let new_filename = verify_recipe_file_exists(&temp_dir, "Fudgy Brownie", "desserts");
+    assert_eq!(new_filename, "fudgy-brownie.cook");
+    assert_ne!(initial_filename, new_filename);
+
+    // Verify old file no longer exists
+    let files = std::fs::read_dir(temp_dir.path().join("recipes/desserts"))
+        .unwrap()
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let path = entry.path();
+            if path.is_file() {
+                path.file_name().map(|n| n.to_str().unwrap().to_string())
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    assert!(!files.contains(&initial_filename.to_string()));
+    assert!(files.contains(&new_filename.to_string()));
+}
+
+#[tokio::test]
+async fn test_update_recipe_title_causes_filename_change_git() {
+    test_update_recipe_title_causes_filename_change_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_update_recipe_title_causes_filename_change_disk() {
+    test_update_recipe_title_causes_filename_change_impl("disk").await;
+}
+
+async fn test_id_change_on_rename_scenario_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
+    let app = build_router();
+
+    // Step 1: Create recipe with initial title
+    let initial_content = "---\ntitle: Chocolate Cake\n---\n\nSimple chocolate cake.";
+    let payload = serde_json::json!({
+        "content": initial_content,
+        "path": "desserts"
+    });
+
+    let response = app
+        .clone()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+
+    let initial_recipe_id = json["recipeId"].as_str().unwrap().to_string();
+    println!("Initial recipe ID: {}", initial_recipe_id);
+
+    // Step 2: Update recipe content with new title
+    let new_content = "---\ntitle: Dark Chocolate Cake\n---\n\nRich dark chocolate cake.";
+    let update_payload = serde_json::json!({
+        "content": new_content
+    });
+
+    let response = app
+        .clone()
+        .oneshot(make_request(
+            "PUT",
+            &format!("/api/v1/recipes/{}", initial_recipe_id),
+            Some(update_payload),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+
+    let new_recipe_id = json["recipeId"].as_str().unwrap().to_string();
+    println!("New recipe ID: {}", new_recipe_id);
+
+    // Verify recipe_id changed
+    assert_ne!(initial_recipe_id, new_recipe_id);
+    assert_eq!(json["recipeName"], "Dark Chocolate Cake");
+
+    // Step 3: Try to access with old recipe_id - should return 404
+    let response = app
+        .clone()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}", initial_recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+
+    // Old ID should not be valid anymore
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+
+    // Step 4: Verify new recipe_id works
+    let response = app
+        .clone()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}", new_recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["recipeName"], "Dark Chocolate Cake");
+}
+
+#[tokio::test]
+async fn test_id_change_on_rename_scenario_git() {
+    test_id_change_on_rename_scenario_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_id_change_on_rename_scenario_disk() {
+    test_id_change_on_rename_scenario_impl("disk").await;
+}
+
+// ============================================================================
+// FIND BY NAME TESTS
+// ============================================================================
+
+async fn test_find_recipe_by_name_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_seeded_fixtures(
+        backend,
+        vec![
+            ("cake", Some("desserts"), "cake.cook"),
+            ("pasta", Some("main"), "pasta.cook"),
+            (
+                "chicken-biryani",
+                Some("main/indian"),
+                "chicken-biryani.cook",
+            ),
+        ],
+    )
+    .await;
+
+    let app = build_router();
+
+    // Test exact match - search for "Cake" which is in the fixtures
+    let response = app
+        .clone()
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/recipes/find-by-name?q=Cake",
+            None,
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+
+    assert!(json["recipes"].is_array());
+    assert!(json["recipes"].as_array().unwrap().iter().any(|recipe| {
+        recipe["recipeName"]
+            .as_str()
+            .map(|name| name.contains("Cake"))
+            .unwrap_or(false)
+    }));
+
+    // Test partial match (case-insensitive) - search for "pasta"
+    let response = app
+        .clone()
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/recipes/find-by-name?q=pasta",
+            None,
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+
+    assert!(json["recipes"].is_array());
+    assert!(!json["recipes"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_find_recipe_by_name_git() {
+    test_find_recipe_by_name_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_find_recipe_by_name_disk() {
+    test_find_recipe_by_name_impl("disk").await;
+}
+
+async fn test_find_recipe_by_name_pagination_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_seeded_fixtures(
+        backend,
+        vec![
+            ("cake", Some("desserts"), "cake.cook"),
+            ("pasta", Some("main"), "pasta.cook"),
+            (
+                "chicken-biryani",
+                Some("main/indian"),
+                "chicken-biryani.cook",
+            ),
+        ],
+    )
+    .await;
+
+    let app = build_router();
+
+    // Test with limit
+    let response = app
+        .clone()
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/recipes/find-by-name?q=test&limit=1",
+            None,
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+
+    assert!(json["recipes"].is_array());
+    assert!(json["recipes"].as_array().unwrap().len() <= 1);
+    assert_eq!(json["pagination"]["limit"], 1);
+
+    // Test with offset
+    let response = app
+        .clone()
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/recipes/find-by-name?q=test&offset=1",
+            None,
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_find_recipe_by_name_pagination_git() {
+    test_find_recipe_by_name_pagination_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_find_recipe_by_name_pagination_disk() {
+    test_find_recipe_by_name_pagination_impl("disk").await;
+}
+
+// ============================================================================
+// FIND BY PATH TESTS
+// ============================================================================
+
+async fn test_find_recipe_by_path_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_seeded_fixtures(
+        backend,
+        vec![
+            ("cake", Some("desserts"), "cake.cook"),
+            ("pasta", Some("main"), "pasta.cook"),
+            (
+                "chicken-biryani",
+                Some("main/indian"),
+                "chicken-biryani.cook",
+            ),
+        ],
+    )
+    .await;
+
+    let app = build_router();
+
+    // Test find recipe at specific path
+    let response = app
+        .clone()
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/recipes/find-by-path?path=main",
+            None,
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+
+    assert!(json.is_array());
+    assert!(json.as_array().unwrap().iter().all(|recipe| {
+        recipe["path"]
+            .as_str()
+            .map(|p| p == "main")
+            .unwrap_or(false)
+    }));
+}
+
+#[tokio::test]
+async fn test_find_recipe_by_path_git() {
+    test_find_recipe_by_path_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_find_recipe_by_path_disk() {
+    test_find_recipe_by_path_impl("disk").await;
+}
+
+async fn test_find_recipe_by_path_root_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_seeded_fixtures(
+        backend,
+        vec![
+            ("cake", None, "cake.cook"),
+            ("pasta", Some("main"), "pasta.cook"),
+        ],
+    )
+    .await;
+
+    let app = build_router();
+
+    // Test root path with empty string
+    let response = app
+        .clone()
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/recipes/find-by-path?path=",
+            None,
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+
+    assert!(json.is_array());
+    // Should find recipes in root (path field may be null or empty string for root)
+    assert!(json.as_array().unwrap().iter().any(|recipe| {
+        recipe["path"].is_null()
+            || recipe["path"]
+                .as_str()
+                .map(|p| p.is_empty())
+                .unwrap_or(false)
+    }));
+}
+
+#[tokio::test]
+async fn test_find_recipe_by_path_root_git() {
+    test_find_recipe_by_path_root_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_find_recipe_by_path_root_disk() {
+    test_find_recipe_by_path_root_impl("disk").await;
+}
+
+async fn test_find_recipe_by_path_not_found_impl(backend: &str) {
+    let (build_router, _temp_dir) =
+        setup_api_with_seeded_fixtures(backend, vec![("cake", Some("desserts"), "cake.cook")])
+            .await;
+
+    let app = build_router();
+
+    // Test non-existent path
+    let response = app
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/recipes/find-by-path?path=nonexistent",
+            None,
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+
+    // Should return empty array instead of 404
+    assert!(json.is_array());
+    assert_eq!(json.as_array().unwrap().len(), 0);
+}
+
+#[tokio::test]
+async fn test_find_recipe_by_path_not_found_git() {
+    test_find_recipe_by_path_not_found_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_find_recipe_by_path_not_found_disk() {
+    test_find_recipe_by_path_not_found_impl("disk").await;
+}
+
+// ============================================================================
+// MOVE & COPY TESTS
+// ============================================================================
+
+async fn test_move_recipe_endpoint_impl(backend: &str) -> TempDir {
+    let (build_router, temp_dir) =
+        setup_api_with_seeded_fixtures(backend, vec![("cake", Some("desserts"), "cake.cook")])
+            .await;
+
+    let recipe_id = cooklang_store::cache::generate_recipe_id("recipes/desserts/cake.cook");
+    let original = read_recipe_file(&temp_dir, "Cake", "desserts");
+
+    let payload = serde_json::json!({ "path": "baking/cakes" });
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            &format!("/api/v1/recipes/{}/move", recipe_id),
+            Some(payload),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["path"], "baking/cakes");
+    assert_eq!(json["fileName"], "cake.cook");
+    assert_ne!(json["recipeId"], recipe_id);
+
+    // Content travels with the file
+    assert_eq!(
+        read_recipe_file(&temp_dir, "Cake", "baking/cakes"),
+        original
+    );
+    verify_recipe_file_deleted(&temp_dir, "Cake", "desserts");
+
+    temp_dir
+}
+
+#[tokio::test]
+async fn test_move_recipe_endpoint_git() {
+    let _temp_dir = test_move_recipe_endpoint_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_move_recipe_endpoint_disk() {
+    let _temp_dir = test_move_recipe_endpoint_impl("disk").await;
+}
+
+async fn test_copy_recipe_endpoint_impl(backend: &str) {
+    let (build_router, temp_dir) =
+        setup_api_with_seeded_fixtures(backend, vec![("cake", Some("desserts"), "cake.cook")])
+            .await;
+
+    let recipe_id = cooklang_store::cache::generate_recipe_id("recipes/desserts/cake.cook");
+
+    // Copying into the same directory collides with the original file name
+    let payload = serde_json::json!({ "path": "desserts" });
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            &format!("/api/v1/recipes/{}/copy", recipe_id),
+            Some(payload),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["recipeName"], "Cake");
+    assert_eq!(json["path"], "desserts");
+    assert_eq!(json["fileName"], "cake-2.cook");
+
+    assert!(temp_dir.path().join("recipes/desserts/cake.cook").exists());
+    assert!(temp_dir
+        .path()
+        .join("recipes/desserts/cake-2.cook")
+        .exists());
+
+    // Copy to root
+    let payload = serde_json::json!({ "path": "" });
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            &format!("/api/v1/recipes/{}/copy", recipe_id),
+            Some(payload),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert!(json["path"].is_null());
+    verify_recipe_file_exists_at_root(&temp_dir, "Cake");
+}
+
+#[tokio::test]
+async fn test_copy_recipe_endpoint_git() {
+    test_copy_recipe_endpoint_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_copy_recipe_endpoint_disk() {
+    test_copy_recipe_endpoint_impl("disk").await;
+}
+
+async fn test_move_recipe_not_found_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
+
+    let payload = serde_json::json!({ "path": "mains" });
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            "/api/v1/recipes/nonexistent/move",
+            Some(payload),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_move_recipe_not_found_git() {
+    test_move_recipe_not_found_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_move_recipe_not_found_disk() {
+    test_move_recipe_not_found_impl("disk").await;
+}
+
+// ============================================================================
+// TRASH TESTS
+// ============================================================================
+
+async fn test_trash_and_restore_impl(backend: &str) {
+    let config = cooklang_store::config::StoreConfig {
+        trash_enabled: true,
+        ..Default::default()
+    };
+    let (build_router, temp_dir) = setup_api_with_config(backend, config).await;
+
+    let payload = serde_json::json!({
+        "content": load_recipe_fixture("test-recipe"),
+        "path": "desserts"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let recipe_id = json["recipeId"].as_str().unwrap().to_string();
+
+    let response = build_router()
+        .oneshot(make_request(
+            "DELETE",
+            &format!("/api/v1/recipes/{}", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NO_CONTENT);
+    verify_recipe_file_deleted(&temp_dir, "Test Recipe", "desserts");
+
+    // Deleted recipe shows up in the trash
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/trash", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["count"], 1);
+    assert_eq!(json["entries"][0]["recipeName"], "Test Recipe");
+    assert_eq!(json["entries"][0]["path"], "desserts");
+    assert!(json["entries"][0]["deletedAt"].is_string());
+    let trash_id = json["entries"][0]["trashId"].as_str().unwrap().to_string();
+
+    // Restore brings it back under the same recipe ID
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            &format!("/api/v1/trash/{}/restore", trash_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["recipeId"], recipe_id);
+    assert_eq!(json["path"], "desserts");
+    verify_recipe_file_exists(&temp_dir, "Test Recipe", "desserts");
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/trash", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["count"], 0);
+}
+
+#[tokio::test]
+async fn test_trash_and_restore_git() {
+    test_trash_and_restore_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_trash_and_restore_disk() {
+    test_trash_and_restore_impl("disk").await;
+}
+
+async fn test_restore_trash_not_found_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
+
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            "/api/v1/trash/nonexistent/restore",
+            None,
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_restore_trash_not_found_git() {
+    test_restore_trash_not_found_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_restore_trash_not_found_disk() {
+    test_restore_trash_not_found_impl("disk").await;
+}
+
+// ============================================================================
+// CUSTOM FRONT MATTER FIELD TESTS
+// ============================================================================
+
+async fn test_custom_fields_passthrough_and_filter_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
+
+    let recipes = [
+        "---\ntitle: Hot Cross Buns\nservings: 12\nholiday: easter\nfamily_rating: 5\n---\n\nMix @flour{500%g}.",
+        "---\ntitle: Mince Pies\nholiday: christmas\n---\n\nBake @pastry{1}.",
+        "---\ntitle: Plain Toast\n---\n\nToast @bread{2%slices}.",
+    ];
+
+    let mut buns_id = String::new();
+    for content in recipes {
+        let response = build_router()
+            .oneshot(make_request(
+                "POST",
+                "/api/v1/recipes",
+                Some(serde_json::json!({ "content": content })),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+        let body = extract_response_body(response).await;
+        let json: Value = serde_json::from_str(&body).unwrap();
+        if json["recipeName"] == "Hot Cross Buns" {
+            buns_id = json["recipeId"].as_str().unwrap().to_string();
+        }
+    }
+
+    // Unknown keys are exposed, standard metadata is not
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}", buns_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["customFields"]["holiday"], "easter");
+    assert_eq!(json["customFields"]["family_rating"], 5);
+    assert!(json["customFields"].get("servings").is_none());
+    assert!(json["customFields"].get("title").is_none());
+
+    // Filter the listing on a custom field
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/recipes?custom.holiday=easter",
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["pagination"]["total"], 1);
+    assert_eq!(json["recipes"][0]["recipeName"], "Hot Cross Buns");
+}
+
+#[tokio::test]
+async fn test_custom_fields_passthrough_and_filter_git() {
+    test_custom_fields_passthrough_and_filter_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_custom_fields_passthrough_and_filter_disk() {
+    test_custom_fields_passthrough_and_filter_impl("disk").await;
+}
+
+async fn test_filter_by_max_time_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
+
+    let recipes = [
+        "---\ntitle: Omelette\ntime: 10 minutes\n---\n\nWhisk @eggs{3}.\n",
+        "---\ntitle: Pasta\nprep time: 10 min\n---\n\nBoil for ~{12%minutes}.\n",
+        "---\ntitle: Roast\ntime: 1h 30m\nholiday: christmas\n---\n\nRoast @beef{1%kg}.\n",
+        "---\ntitle: Salad\n---\n\nToss @lettuce{1}.\n",
+    ];
+    for content in recipes {
+        let response = build_router()
+            .oneshot(make_request(
+                "POST",
+                "/api/v1/recipes",
+                Some(serde_json::json!({ "content": content })),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    }
+
+    let list = |query: &'static str| {
+        let app = build_router();
+        async move {
+            let response = app.oneshot(make_request("GET", query, None)).await.unwrap();
+            assert_eq!(response.status(), axum::http::StatusCode::OK);
+            let body = extract_response_body(response).await;
+            let json: Value = serde_json::from_str(&body).unwrap();
+            let mut names: Vec<String> = json["recipes"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|r| r["recipeName"].as_str().unwrap().to_string())
+                .collect();
+            names.sort();
+            names
+        }
+    };
+
+    // Recipes without a known time are left out
+    assert_eq!(
+        list("/api/v1/recipes?max_time=30").await,
+        vec!["Omelette", "Pasta"]
+    );
+    assert_eq!(list("/api/v1/recipes?max_time=10").await, vec!["Omelette"]);
+    assert_eq!(
+        list("/api/v1/recipes?max_time=120&custom.holiday=christmas").await,
+        vec!["Roast"]
+    );
+
+    // The pasta's timer is waiting time, so only its prep time is hands-on
+    assert_eq!(
+        list("/api/v1/recipes?max_active_time=10").await,
+        vec!["Omelette", "Pasta"]
+    );
+    assert_eq!(
+        list("/api/v1/recipes/search?q=a&max_time=60&max_active_time=10").await,
+        vec!["Pasta"]
+    );
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let recipes = json["recipes"].as_array().unwrap();
+    let pasta = recipes.iter().find(|r| r["recipeName"] == "Pasta").unwrap();
+    assert_eq!(pasta["totalTimeMinutes"], 22);
+    assert_eq!(pasta["activeTimeMinutes"], 10);
+    let salad = recipes.iter().find(|r| r["recipeName"] == "Salad").unwrap();
+    assert!(salad.get("totalTimeMinutes").is_none());
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}", pasta["recipeId"].as_str().unwrap()),
+            None,
+        ))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["totalTimeMinutes"], 22);
+    assert_eq!(json["activeTimeMinutes"], 10);
+}
+
+#[tokio::test]
+async fn test_filter_by_max_time_git() {
+    test_filter_by_max_time_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_filter_by_max_time_disk() {
+    test_filter_by_max_time_impl("disk").await;
+}
+
+async fn test_filter_by_metadata_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
+
+    let recipes = [
+        "---\ntitle: Green Curry\ncuisine: Thai\ntags: [spicy, dinner]\nservings: 4\n---\n\nSimmer @coconut milk{400%ml}.\n",
+        "---\ntitle: Pad Thai\ncuisine: thai\ntags: quick, dinner\nauthor: Jane\n---\n\nFry @noodles{200%g}.\n",
+        "---\ntitle: Carbonara\ncuisine: Italian\ntags: [quick]\nservings: 2\n---\n\nToss @spaghetti{200%g}.\n",
+    ];
+    for content in recipes {
+        let response = build_router()
+            .oneshot(make_request(
+                "POST",
+                "/api/v1/recipes",
+                Some(serde_json::json!({ "content": content })),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    }
+
+    let list = |query: &'static str| {
+        let app = build_router();
+        async move {
+            let response = app.oneshot(make_request("GET", query, None)).await.unwrap();
+            let body = extract_response_body(response).await;
+            let json: Value = serde_json::from_str(&body).unwrap();
+            let mut names: Vec<String> = json["recipes"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|r| r["recipeName"].as_str().unwrap().to_string())
+                .collect();
+            names.sort();
+            names
+        }
+    };
+
+    assert_eq!(
+        list("/api/v1/recipes?meta.cuisine=THAI").await,
+        vec!["Green Curry", "Pad Thai"]
+    );
+    // List and comma-separated tags both match any element; filters combine
+    assert_eq!(
+        list("/api/v1/recipes?meta.tags=quick&meta.cuisine=thai").await,
+        vec!["Pad Thai"]
+    );
+    assert_eq!(
+        list("/api/v1/recipes?meta.servings=2").await,
+        vec!["Carbonara"]
+    );
+    assert!(list("/api/v1/recipes?meta.calories=500").await.is_empty());
+}
+
+#[tokio::test]
+async fn test_filter_by_metadata_git() {
+    test_filter_by_metadata_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_filter_by_metadata_disk() {
+    test_filter_by_metadata_impl("disk").await;
+}
+
+// ============================================================================
+// PATH MATCHING TESTS
+// ============================================================================
+
+async fn test_category_lookup_ignores_case_and_trailing_slash_impl(backend: &str) {
+    let (build_router, _temp_dir) =
+        setup_api_with_seeded_fixtures(backend, vec![("cake", Some("desserts"), "cake.cook")])
+            .await;
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/categories/Desserts%2F", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["path"], "desserts");
+    assert_eq!(json["count"], 1);
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/recipes/find-by-path?path=DESSERTS/",
+            None,
+        ))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 1);
+
+    // New recipes written with a different case land in the existing directory
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Pie\n---\n\nBake @apples{3}.",
+        "path": "DESSERTS/"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["path"], "desserts");
+}
+
+#[tokio::test]
+async fn test_category_lookup_ignores_case_and_trailing_slash_git() {
+    test_category_lookup_ignores_case_and_trailing_slash_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_category_lookup_ignores_case_and_trailing_slash_disk() {
+    test_category_lookup_ignores_case_and_trailing_slash_impl("disk").await;
+}
+
+// ============================================================================
+// CATEGORY ALIAS TESTS
+// ============================================================================
+
+async fn test_renamed_category_redirects_impl(backend: &str) {
+    let aliases = serde_json::json!([{
+        "from": "sweets",
+        "to": "desserts",
+        "created_at": chrono::Utc::now().to_rfc3339()
+    }])
+    .to_string();
+    let (build_router, _temp_dir) = setup_api_with_seeded_files(
+        backend,
+        vec![("cake", Some("desserts/cakes"), "cake.cook")],
+        vec![(".cooklang-store/category-aliases.json", &aliases)],
+    )
+    .await;
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/categories/sweets%2Fcakes",
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        response.status(),
+        axum::http::StatusCode::TEMPORARY_REDIRECT
+    );
+    assert_eq!(
+        response.headers()["location"],
+        "/api/v1/categories/desserts%2Fcakes"
+    );
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/recipes/find-by-path?path=sweets/cakes",
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        response.status(),
+        axum::http::StatusCode::TEMPORARY_REDIRECT
+    );
+    assert_eq!(
+        response.headers()["location"],
+        "/api/v1/recipes/find-by-path?path=desserts%2Fcakes"
+    );
+
+    // The recipe ID from before the rename redirects to the current ID
+    let old_id = cooklang_store::cache::generate_recipe_id("recipes/sweets/cakes/cake.cook");
+    let new_id = cooklang_store::cache::generate_recipe_id("recipes/desserts/cakes/cake.cook");
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}", old_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        response.status(),
+        axum::http::StatusCode::TEMPORARY_REDIRECT
+    );
+    assert_eq!(
+        response.headers()["location"],
+        format!("/api/v1/recipes/{}", new_id).as_str()
+    );
+}
+
+#[tokio::test]
+async fn test_renamed_category_redirects_git() {
+    test_renamed_category_redirects_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_renamed_category_redirects_disk() {
+    test_renamed_category_redirects_impl("disk").await;
+}
+
+async fn test_rename_category_impl(backend: &str) {
+    let (build_router, temp_dir) = setup_api_with_seeded_files(
+        backend,
+        vec![],
+        vec![
+            (
+                "recipes/sweets/brownies.cook",
+                "---\ntitle: Brownies\n---\n\nBake @chocolate{200%g}.\n",
+            ),
+            (
+                "recipes/sweets/cakes/lemon-cake.cook",
+                "---\ntitle: Lemon Cake\n---\n\nBake @lemons{2}.\n",
+            ),
+            ("recipes/sweets/cakes/lemon-cake.notes.yaml", "rating: 5\n"),
+            (
+                "recipes/desserts/cakes/lemon-cake.cook",
+                "---\ntitle: Lemon Cake\n---\n\nBake @lemons{3}.\n",
+            ),
+        ],
+    )
+    .await;
+    let rename = |from: &str, to: &str| {
+        make_request(
+            "POST",
+            "/api/v1/categories/rename",
+            Some(serde_json::json!({ "from": from, "to": to })),
+        )
+    };
+
+    // A category can't move into itself, and an unknown one isn't found
+    let response = build_router()
+        .oneshot(rename("sweets", "sweets/old"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    let response = build_router()
+        .oneshot(rename("pies", "tarts"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+
+    // Nothing moves if any recipe would land on an existing one
+    let response = build_router()
+        .oneshot(rename("sweets", "desserts"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::CONFLICT);
+    assert!(temp_dir
+        .path()
+        .join("recipes/sweets/brownies.cook")
+        .exists());
+
+    let response = build_router()
+        .oneshot(rename("/sweets/", "baking"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["from"], "sweets");
+    assert_eq!(json["to"], "baking");
+    let old_id = cooklang_store::cache::generate_recipe_id("recipes/sweets/cakes/lemon-cake.cook");
+    let new_id = cooklang_store::cache::generate_recipe_id("recipes/baking/cakes/lemon-cake.cook");
+    assert_eq!(json["renamed"].as_object().unwrap().len(), 2);
+    assert_eq!(json["renamed"][&old_id], new_id.as_str());
+
+    // Notes move along and the emptied directories are gone
+    assert!(temp_dir
+        .path()
+        .join("recipes/baking/cakes/lemon-cake.notes.yaml")
+        .exists());
+    assert!(!temp_dir.path().join("recipes/sweets").exists());
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}", new_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/categories/sweets", None))
+        .await
+        .unwrap();
+    assert_eq!(
+        response.status(),
+        axum::http::StatusCode::TEMPORARY_REDIRECT
+    );
+    assert_eq!(response.headers()["location"], "/api/v1/categories/baking");
+}
+
+#[tokio::test]
+async fn test_rename_category_git() {
+    test_rename_category_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_rename_category_disk() {
+    test_rename_category_impl("disk").await;
+}
+
+// ============================================================================
+// RESUMABLE UPLOAD TESTS
+// ============================================================================
+
+fn make_chunk_request(
+    upload_id: &str,
+    offset: u64,
+    chunk: &[u8],
+) -> axum::http::Request<axum::body::Body> {
+    axum::http::Request::builder()
+        .method("PATCH")
+        .uri(format!("/api/v1/uploads/{}", upload_id))
+        .header("content-type", "application/offset+octet-stream")
+        .header("upload-offset", offset.to_string())
+        .body(axum::body::Body::from(chunk.to_vec()))
+        .unwrap()
+}
+
+async fn test_resumable_upload_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
+
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            "/api/v1/uploads",
+            Some(serde_json::json!({ "fileName": "export.paprikarecipes", "size": 10 })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let upload_id = json["uploadId"].as_str().unwrap().to_string();
+    assert_eq!(json["offset"], 0);
+    assert_eq!(json["complete"], false);
+
+    let response = build_router()
+        .oneshot(make_chunk_request(&upload_id, 0, b"hello"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+    // Resending the same chunk after a dropped response reports where to resume
+    let response = build_router()
+        .oneshot(make_chunk_request(&upload_id, 0, b"hello"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::CONFLICT);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["details"]["offset"], "5");
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/uploads/{}", upload_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["offset"], 5);
+
+    let response = build_router()
+        .oneshot(make_chunk_request(&upload_id, 5, b"world"))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["offset"], 10);
+    assert_eq!(json["complete"], true);
+
+    let response = build_router()
+        .oneshot(make_request(
+            "DELETE",
+            &format!("/api/v1/uploads/{}", upload_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NO_CONTENT);
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/uploads/{}", upload_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_resumable_upload_git() {
+    test_resumable_upload_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_resumable_upload_disk() {
+    test_resumable_upload_impl("disk").await;
+}
+
+async fn test_upload_validation_impl(backend: &str) {
+    let config = cooklang_store::config::StoreConfig {
+        upload_max_mb: 1,
+        ..Default::default()
+    };
+    let (build_router, _temp_dir) = setup_api_with_config(backend, config).await;
+
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            "/api/v1/uploads",
+            Some(serde_json::json!({ "fileName": "huge.zip", "size": 2 * 1024 * 1024 })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::PAYLOAD_TOO_LARGE);
+
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            "/api/v1/uploads",
+            Some(serde_json::json!({ "fileName": "", "size": 10 })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+
+    let response = build_router()
+        .oneshot(make_chunk_request("abc123", 0, b"data"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_upload_validation_git() {
+    test_upload_validation_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_upload_validation_disk() {
+    test_upload_validation_impl("disk").await;
+}
+
+// ============================================================================
+// DRAFT TESTS
+// ============================================================================
+
+#[tokio::test]
+async fn test_draft_create_and_publish_git() {
+    let (build_router, _temp_dir) = setup_api_with_storage("git").await;
+
+    let payload = serde_json::json!({
+        "content": load_recipe_fixture("test-recipe"),
+        "path": "desserts",
+        "draft": true
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["recipeName"], "Test Recipe");
+    assert_eq!(json["path"], "desserts");
+    let draft_id = json["draftId"].as_str().unwrap().to_string();
+
+    // Drafts stay out of the published recipes
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["recipes"].as_array().unwrap().len(), 0);
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/drafts", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["count"], 1);
+    assert_eq!(json["drafts"][0]["draftId"], draft_id);
+    assert!(json["drafts"][0]["updatedAt"].is_string());
+
+    // Publishing makes the recipe available under the draft's ID
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            &format!("/api/v1/drafts/{}/publish", draft_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["recipeId"], draft_id);
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}", draft_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/drafts", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["count"], 0);
+}
+
+#[tokio::test]
+async fn test_draft_update_keeps_published_content_git() {
+    let (build_router, _temp_dir) =
+        setup_api_with_seeded_fixtures("git", vec![("cake", Some("desserts"), "cake.cook")]).await;
+    let recipe_id = cooklang_store::cache::generate_recipe_id("recipes/desserts/cake.cook");
+
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Cake\n---\n\nMix @flour{200%g} and bake.",
+        "draft": true
+    });
+    let response = build_router()
+        .oneshot(make_request(
+            "PUT",
+            &format!("/api/v1/recipes/{}", recipe_id),
+            Some(payload),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["draftId"], recipe_id);
+
+    // The published recipe is untouched until the draft is published
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert!(!json["content"].as_str().unwrap().contains("@flour{200%g}"));
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/drafts/{}", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert!(json["content"].as_str().unwrap().contains("@flour{200%g}"));
+
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            &format!("/api/v1/drafts/{}/publish", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert!(json["content"].as_str().unwrap().contains("@flour{200%g}"));
+}
+
+#[tokio::test]
+async fn test_draft_discard_git() {
+    let (build_router, _temp_dir) = setup_api_with_storage("git").await;
+
+    let payload = serde_json::json!({
+        "content": load_recipe_fixture("test-recipe"),
+        "draft": true
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let draft_id = json["draftId"].as_str().unwrap().to_string();
+
+    let response = build_router()
+        .oneshot(make_request(
+            "DELETE",
+            &format!("/api/v1/drafts/{}", draft_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NO_CONTENT);
+
+    for (method, uri) in [
+        ("GET", format!("/api/v1/drafts/{}", draft_id)),
+        ("DELETE", format!("/api/v1/drafts/{}", draft_id)),
+        ("POST", format!("/api/v1/drafts/{}/publish", draft_id)),
+    ] {
+        let response = build_router()
+            .oneshot(make_request(method, &uri, None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+    }
+}
+
+#[tokio::test]
+async fn test_draft_requires_git_disk() {
+    let (build_router, _temp_dir) = setup_api_with_storage("disk").await;
+
+    let payload = serde_json::json!({
+        "content": load_recipe_fixture("test-recipe"),
+        "draft": true
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["error"], "drafts_unsupported");
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/drafts", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["count"], 0);
+}
+
+// ============================================================================
+// ADMIN TESTS
+// ============================================================================
+
+#[tokio::test]
+async fn test_git_gc_git() {
+    let (build_router, _temp_dir) = setup_api_with_storage("git").await;
+
+    for title in ["Pancakes", "Waffles"] {
+        let payload = serde_json::json!({
+            "content": format!("---\ntitle: {}\n---\n\nMix @flour{{200%g}}.", title)
+        });
+        let response = build_router()
+            .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    }
+
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/admin/git/gc", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert!(json["looseObjectsBefore"].as_u64().unwrap() > 0);
+    assert_eq!(json["looseObjectsAfter"], 0);
+    assert_eq!(json["packs"], 1);
+
+    // Recipes are still served from the packed repository
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["recipes"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_git_gc_requires_git_disk() {
+    let (build_router, _temp_dir) = setup_api_with_storage("disk").await;
+
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/admin/git/gc", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["error"], "maintenance_unsupported");
+}
+
+#[tokio::test]
+async fn test_mirror_check_and_repair() {
+    let mirror_dir = TempDir::new().unwrap();
+    let config = cooklang_store::config::StoreConfig {
+        mirror_path: Some(mirror_dir.path().to_string_lossy().into_owned()),
+        ..Default::default()
+    };
+    let (build_router, _temp_dir) = setup_api_with_config("disk", config).await;
+
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Pancakes\n---\n\nMix @flour{200%g}.",
+        "path": "breakfast"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+
+    // The check waits for the copy, so the mirror is already in line
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/admin/mirror", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["consistent"], true);
+    let mirrored = mirror_dir.path().join("recipes/breakfast/pancakes.cook");
+    assert!(mirrored.exists());
+
+    // Changes made behind the mirror's back are reported, then repaired
+    std::fs::write(&mirrored, "tampered").unwrap();
+    std::fs::write(mirror_dir.path().join("stray.cook"), "stray").unwrap();
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/admin/mirror", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["consistent"], false);
+    assert_eq!(
+        json["different"],
+        serde_json::json!(["recipes/breakfast/pancakes.cook"])
+    );
+    assert_eq!(json["extra"], serde_json::json!(["stray.cook"]));
+
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/admin/mirror/repair", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["consistent"], true);
+    assert!(std::fs::read_to_string(&mirrored)
+        .unwrap()
+        .contains("title: Pancakes"));
+    assert!(!mirror_dir.path().join("stray.cook").exists());
+}
+
+#[tokio::test]
+async fn test_mirror_requires_mirror_path() {
+    let (build_router, _temp_dir) = setup_api_with_storage("disk").await;
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/admin/mirror", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["error"], "validation_error");
+}
+
+#[tokio::test]
+async fn test_reindex_incremental_git() {
+    let (build_router, temp_dir) = setup_api_with_storage("git").await;
+
+    let mut file_names = Vec::new();
+    for title in ["Pancakes", "Waffles"] {
+        let payload = serde_json::json!({
+            "content": format!("---\ntitle: {}\n---\n\nMix @flour{{200%g}}.\n", title)
+        });
+        let response = build_router()
+            .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+            .await
+            .unwrap();
+        let body = extract_response_body(response).await;
+        let json: Value = serde_json::from_str(&body).unwrap();
+        file_names.push(json["fileName"].as_str().unwrap().to_string());
+    }
+
+    // The store started without commits, so the first reindex has nothing to diff against
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/admin/reindex", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["mode"], "full");
+
+    // Change files behind the API's back, as a pull would
+    std::fs::write(
+        temp_dir.path().join("recipes").join(&file_names[0]),
+        "---\ntitle: Fluffy Pancakes\n---\n\nMix @flour{250%g}.\n",
+    )
+    .unwrap();
+    std::fs::remove_file(temp_dir.path().join("recipes").join(&file_names[1])).unwrap();
+    std::fs::write(
+        temp_dir.path().join("recipes/crepes.cook"),
+        "---\ntitle: Crepes\n---\n\nWhisk @eggs{2}.\n",
+    )
+    .unwrap();
+
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/admin/reindex", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["mode"], "incremental");
+    assert_eq!(json["parsed"], 2);
+    assert_eq!(json["removed"], 1);
+    assert_eq!(json["revision"].as_str().unwrap().len(), 40);
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let mut names: Vec<&str> = json["recipes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|recipe| recipe["recipeName"].as_str().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["Crepes", "Fluffy Pancakes"]);
+
+    // A full rebuild can still be forced
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            "/api/v1/admin/reindex?full=true",
+            None,
+        ))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["mode"], "full");
+    assert_eq!(json["parsed"], 2);
+    assert_eq!(json["removed"], 0);
+}
+
+#[tokio::test]
+async fn test_reindex_incremental_disk() {
+    let (build_router, temp_dir) = setup_api_with_storage("disk").await;
+
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Pancakes\n---\n\nMix @flour{200%g}.\n"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    let json: Value = serde_json::from_str(&extract_response_body(response).await).unwrap();
+    let pancakes = temp_dir
+        .path()
+        .join("recipes")
+        .join(json["fileName"].as_str().unwrap());
+
+    std::fs::write(
+        temp_dir.path().join("crepes.cook"),
+        "---\ntitle: Crepes\n---\n\nWhisk @eggs{2}.\n",
+    )
+    .unwrap();
+
+    // Only the file added behind the API's back is parsed
+    let reindex = |full: bool| {
+        let uri = if full {
+            "/api/v1/admin/reindex?full=true"
+        } else {
+            "/api/v1/admin/reindex"
+        };
+        let response = build_router().oneshot(make_request("POST", uri, None));
+        async move {
+            let response = response.await.unwrap();
+            assert_eq!(response.status(), axum::http::StatusCode::OK);
+            serde_json::from_str::<Value>(&extract_response_body(response).await).unwrap()
+        }
+    };
+    let json = reindex(false).await;
+    assert_eq!(json["mode"], "incremental");
+    assert_eq!(json["parsed"], 1);
+    assert_eq!(json["removed"], 0);
+    assert!(json.get("revision").is_none());
+
+    // Changed and deleted files are found by size and modification time
+    std::fs::write(
+        temp_dir.path().join("crepes.cook"),
+        "---\ntitle: Thin Crepes\n---\n\nWhisk @eggs{3}.\n",
+    )
+    .unwrap();
+    std::fs::remove_file(&pancakes).unwrap();
+    let json = reindex(false).await;
+    assert_eq!(json["mode"], "incremental");
+    assert_eq!(json["parsed"], 1);
+    assert_eq!(json["removed"], 1);
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes", None))
+        .await
+        .unwrap();
+    let json: Value = serde_json::from_str(&extract_response_body(response).await).unwrap();
+    assert_eq!(json["recipes"][0]["recipeName"], "Thin Crepes");
+    assert_eq!(json["recipes"].as_array().unwrap().len(), 1);
+
+    let json = reindex(true).await;
+    assert_eq!(json["mode"], "full");
+    assert_eq!(json["parsed"], 1);
+}
+
+// ============================================================================
+// RECIPE VERSION TESTS
+// ============================================================================
+
+#[tokio::test]
+async fn test_recipe_versions_git() {
+    let (build_router, _temp_dir) = setup_api_with_storage("git").await;
+
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Stuffing\n---\n\nMix @bread{500%g} and @sage{1%tbsp}.",
+        "path": "sides"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let recipe_id = json["recipeId"].as_str().unwrap().to_string();
+
+    let payload = serde_json::json!({ "name": "thanksgiving-2024", "author": "Alice" });
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            &format!("/api/v1/recipes/{}/versions", recipe_id),
+            Some(payload.clone()),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["name"], "thanksgiving-2024");
+    assert_eq!(json["author"], "Alice");
+    assert!(json["commitId"].is_string());
+
+    // Names are unique per recipe
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            &format!("/api/v1/recipes/{}/versions", recipe_id),
+            Some(payload),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::CONFLICT);
+
+    // Edits after tagging don't change the tagged version
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Stuffing\n---\n\nMix @bread{500%g} and @sage{2%tbsp}."
+    });
+    let response = build_router()
+        .oneshot(make_request(
+            "PUT",
+            &format!("/api/v1/recipes/{}", recipe_id),
+            Some(payload),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}/versions/thanksgiving-2024", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert!(json["content"].as_str().unwrap().contains("@sage{1%tbsp}"));
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}/versions", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["count"], 1);
+    assert!(json["versions"][0].get("content").is_none());
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}/versions/easter-2025", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_recipe_version_invalid_name_git() {
+    let (build_router, _temp_dir) =
+        setup_api_with_seeded_fixtures("git", vec![("cake", Some("desserts"), "cake.cook")]).await;
+    let recipe_id = cooklang_store::cache::generate_recipe_id("recipes/desserts/cake.cook");
+
+    for name in ["", "../main", "has space", "-leading-dash"] {
+        let payload = serde_json::json!({ "name": name });
+        let response = build_router()
+            .oneshot(make_request(
+                "POST",
+                &format!("/api/v1/recipes/{}/versions", recipe_id),
+                Some(payload),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    }
+}
+
+#[tokio::test]
+async fn test_recipe_versions_require_git_disk() {
+    let (build_router, _temp_dir) =
+        setup_api_with_seeded_fixtures("disk", vec![("cake", Some("desserts"), "cake.cook")]).await;
+    let recipe_id = cooklang_store::cache::generate_recipe_id("recipes/desserts/cake.cook");
+
+    let payload = serde_json::json!({ "name": "v1" });
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            &format!("/api/v1/recipes/{}/versions", recipe_id),
+            Some(payload),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["error"], "versions_unsupported");
+}
+
+// ============================================================================
+// BLAME TESTS
+// ============================================================================
+
+#[tokio::test]
+async fn test_blame_recipe_git() {
+    let (build_router, _temp_dir) = setup_api_with_storage("git").await;
+
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Chili\n---\n\nAdd @cumin{1%tsp}.\nSimmer for ~{1%hour}.",
+        "author": "Alice"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let recipe_id = json["recipeId"].as_str().unwrap().to_string();
+
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Chili\n---\n\nAdd @cumin{2%tsp}.\nSimmer for ~{1%hour}.",
+        "author": "Bob",
+        "comment": "More cumin"
+    });
+    let response = build_router()
+        .oneshot(make_request(
+            "PUT",
+            &format!("/api/v1/recipes/{}", recipe_id),
+            Some(payload),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}/blame", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let lines = json["lines"].as_array().unwrap();
+    assert_eq!(lines.len(), 6);
+
+    let cumin = lines.iter().find(|l| l["line"] == 5).unwrap();
+    assert_eq!(cumin["content"], "Add @cumin{2%tsp}.");
+    assert_eq!(cumin["author"], "Bob");
+    assert!(cumin["commitId"].is_string());
+
+    let simmer = lines.iter().find(|l| l["line"] == 6).unwrap();
+    assert_eq!(simmer["author"], "Alice");
+}
+
+#[tokio::test]
+async fn test_blame_recipe_requires_git_disk() {
+    let (build_router, _temp_dir) =
+        setup_api_with_seeded_fixtures("disk", vec![("cake", Some("desserts"), "cake.cook")]).await;
+    let recipe_id = cooklang_store::cache::generate_recipe_id("recipes/desserts/cake.cook");
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}/blame", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["error"], "blame_unsupported");
+}
+
+// ============================================================================
+// INDEX SNAPSHOT TESTS
+// ============================================================================
+
+#[tokio::test]
+async fn test_index_snapshot_reused_after_restart() {
+    use cooklang_store::repository::RecipeRepository;
+    use std::sync::Arc;
+
+    let temp_dir = TempDir::new().unwrap();
+    let recipes = [("Pancakes", "pancakes.cook"), ("Waffles", "waffles.cook")];
+    for (title, file) in recipes {
+        std::fs::write(
+            temp_dir.path().join(file),
+            format!("---\ntitle: {}\n---\n\nMix @flour{{200%g}}.\n", title),
+        )
+        .unwrap();
+    }
+
+    let repo = RecipeRepository::with_storage(temp_dir.path(), "disk")
+        .await
+        .unwrap();
+    repo.save_index_snapshot().unwrap();
+    drop(repo);
+
+    let snapshot_path = temp_dir.path().join(".cooklang-store/cache/index.json");
+    assert!(snapshot_path.exists());
+    assert!(temp_dir
+        .path()
+        .join(".cooklang-store/cache/.gitignore")
+        .exists());
+
+    // Mark both snapshot entries so we can tell which ones were reused
+    let mut snapshot: Value =
+        serde_json::from_slice(&std::fs::read(&snapshot_path).unwrap()).unwrap();
+    for recipe in snapshot["recipes"].as_array_mut().unwrap() {
+        recipe["name"] = Value::from(format!("{} (snapshot)", recipe["name"].as_str().unwrap()));
+    }
+    std::fs::write(&snapshot_path, serde_json::to_vec(&snapshot).unwrap()).unwrap();
+
+    // Edit one recipe while the server is down
+    std::fs::write(
+        temp_dir.path().join("waffles.cook"),
+        "---\ntitle: Belgian Waffles\n---\n\nMix @flour{250%g}.\n",
+    )
+    .unwrap();
+
+    let repo = RecipeRepository::with_storage(temp_dir.path(), "disk")
+        .await
+        .unwrap();
+    let repo = Arc::new(repo);
+    let response = cooklang_store::api::build_router(repo)
+        .oneshot(make_request("GET", "/api/v1/recipes", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let mut names: Vec<&str> = json["recipes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|recipe| recipe["recipeName"].as_str().unwrap())
+        .collect();
+    names.sort();
+
+    // The unchanged recipe comes from the snapshot, the edited one is parsed again
+    assert_eq!(names, vec!["Belgian Waffles", "Pancakes (snapshot)"]);
+}
+
+// ============================================================================
+// FAVORITES TESTS
+// ============================================================================
+
+async fn test_favorites_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
+
+    let mut ids = Vec::new();
+    for title in ["Lemon Cake", "Lemon Soup"] {
+        let payload = serde_json::json!({
+            "content": format!("---\ntitle: {}\n---\n\nAdd @lemon{{1}}.\n", title)
+        });
+        let response = build_router()
+            .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+            .await
+            .unwrap();
+        let body = extract_response_body(response).await;
+        let json: Value = serde_json::from_str(&body).unwrap();
+        ids.push(json["recipeId"].as_str().unwrap().to_string());
+    }
+
+    let set_favorite = |recipe_id: &str, payload: Value| {
+        let app = build_router();
+        let uri = format!("/api/v1/recipes/{}/favorite", recipe_id);
+        async move {
+            app.oneshot(make_request("PUT", &uri, Some(payload)))
+                .await
+                .unwrap()
+        }
+    };
+
+    let response = set_favorite(&ids[0], serde_json::json!({})).await;
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["user"], "default");
+    assert_eq!(json["favorite"], true);
+
+    let response = set_favorite(&ids[1], serde_json::json!({ "user": "alice" })).await;
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+    let names = |uri: &'static str| {
+        let app = build_router();
+        async move {
+            let response = app.oneshot(make_request("GET", uri, None)).await.unwrap();
+            assert_eq!(response.status(), axum::http::StatusCode::OK);
+            let body = extract_response_body(response).await;
+            let json: Value = serde_json::from_str(&body).unwrap();
+            json["recipes"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|r| r["recipeName"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        }
+    };
+
+    assert_eq!(
+        names("/api/v1/recipes?favorite=true").await,
+        vec!["Lemon Cake"]
+    );
+    assert_eq!(
+        names("/api/v1/recipes?favorite=true&user=alice").await,
+        vec!["Lemon Soup"]
+    );
+    assert_eq!(
+        names("/api/v1/recipes?favorite=false&user=alice").await,
+        vec!["Lemon Cake"]
+    );
+    assert_eq!(
+        names("/api/v1/recipes/search?q=lemon&favorite=true&user=alice").await,
+        vec!["Lemon Soup"]
+    );
+
+    let response = set_favorite(&ids[0], serde_json::json!({ "favorite": false })).await;
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    assert!(names("/api/v1/recipes?favorite=true").await.is_empty());
+
+    let response = set_favorite("unknown", serde_json::json!({})).await;
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_favorites_git() {
+    test_favorites_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_favorites_disk() {
+    test_favorites_impl("disk").await;
+}
+
+// ============================================================================
+// RATINGS & NOTES TESTS
+// ============================================================================
+
+async fn test_recipe_notes_impl(backend: &str) {
+    let (build_router, temp_dir) = setup_api_with_storage(backend).await;
+
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Chocolate Cake\n---\n\nBake @flour{200%g}.\n",
+        "path": "desserts"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let recipe_id = json["recipeId"].as_str().unwrap().to_string();
+
+    let response = build_router()
+        .oneshot(make_request(
+            "PUT",
+            &format!("/api/v1/recipes/{}/rating", recipe_id),
+            Some(serde_json::json!({ "rating": 4 })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+    let response = build_router()
+        .oneshot(make_request(
+            "PUT",
+            &format!("/api/v1/recipes/{}/rating", recipe_id),
+            Some(serde_json::json!({ "rating": 6 })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+
+    for (date, text) in [
+        ("2024-05-03", "came out dry, add 10 min"),
+        ("2024-05-01", "first try"),
+    ] {
+        let response = build_router()
+            .oneshot(make_request(
+                "POST",
+                &format!("/api/v1/recipes/{}/notes", recipe_id),
+                Some(serde_json::json!({ "text": text, "date": date, "author": "Alice" })),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    }
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}/notes", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["rating"], 4);
+    let notes = json["notes"].as_array().unwrap();
+    assert_eq!(notes.len(), 2);
+    assert_eq!(notes[0]["date"], "2024-05-01");
+    assert_eq!(notes[1]["text"], "came out dry, add 10 min");
+    assert_eq!(notes[1]["author"], "Alice");
+
+    // Notes live in a sidecar file; the recipe content is untouched
+    let sidecar = temp_dir
+        .path()
+        .join("recipes/desserts/chocolate-cake.notes.yaml");
+    assert!(sidecar.exists());
+    let content =
+        std::fs::read_to_string(temp_dir.path().join("recipes/desserts/chocolate-cake.cook"))
+            .unwrap();
+    assert!(!content.contains("came out dry"));
+
+    let response = build_router()
+        .oneshot(make_request(
+            "DELETE",
+            &format!("/api/v1/recipes/{}/notes/0", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["notes"].as_array().unwrap().len(), 1);
+
+    let response = build_router()
+        .oneshot(make_request(
+            "DELETE",
+            &format!("/api/v1/recipes/{}/notes/5", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+
+    // The sidecar is not picked up as a recipe
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["pagination"]["total"], 1);
+}
+
+#[tokio::test]
+async fn test_recipe_notes_git() {
+    test_recipe_notes_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_recipe_notes_disk() {
+    test_recipe_notes_impl("disk").await;
+}
+
+#[tokio::test]
+async fn test_recipe_notes_unknown_recipe() {
+    let (build_router, _temp_dir) = setup_api_with_storage("disk").await;
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes/unknown/notes", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+}
+
+// ============================================================================
+// NUTRITION TESTS
+// ============================================================================
+
+async fn test_recipe_nutrition_impl(backend: &str) {
+    let config = cooklang_store::config::StoreConfig {
+        features: ["nutrition".to_string()].into_iter().collect(),
+        ..Default::default()
+    };
+    let (build_router, _temp_dir) = setup_api_with_config(backend, config).await;
+
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Omelette\nservings: 2\n---\n\nWhisk @eggs{4} with @milk{100%ml} and @chives{1%tbsp}.\nCook in @butter{20%g}.\n"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let recipe_id = json["recipeId"].as_str().unwrap().to_string();
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}/nutrition", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+
+    // 4 eggs (200 g) + 100 ml milk + 20 g butter; chives are not in the bundled table
+    assert_eq!(json["servings"], 2.0);
+    let total = json["total"]["calories"].as_f64().unwrap();
+    assert!((total - 492.2).abs() < 1.0, "total calories {}", total);
+    let per_serving = json["perServing"]["calories"].as_f64().unwrap();
+    assert!((per_serving - total / 2.0).abs() < 0.1);
+    assert!(json["perServing"]["protein"].as_f64().unwrap() > 10.0);
+    assert_eq!(json["unmatched"], serde_json::json!(["chives"]));
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/recipes/unknown/nutrition",
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_recipe_nutrition_git() {
+    test_recipe_nutrition_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_recipe_nutrition_disk() {
+    test_recipe_nutrition_impl("disk").await;
+}
+
+#[tokio::test]
+async fn test_recipe_nutrition_requires_feature() {
+    let (build_router, _temp_dir) =
+        setup_api_with_seeded_fixtures("disk", vec![("cake", Some("desserts"), "cake.cook")]).await;
+    let recipe_id = cooklang_store::cache::generate_recipe_id("recipes/desserts/cake.cook");
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}/nutrition", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+}
+
+// ============================================================================
+// MENUS TESTS
+// ============================================================================
+
+async fn test_menus_impl(backend: &str) {
+    let (build_router, temp_dir) = setup_api_with_storage(backend).await;
+
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Easy Pancakes\n---\n\nMix @flour{200%g}.\n",
+        "path": "breakfast"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let recipe_id = json["recipeId"].as_str().unwrap().to_string();
+
+    std::fs::create_dir_all(temp_dir.path().join("menus")).unwrap();
+    std::fs::write(
+        temp_dir.path().join("menus/week-1.menu"),
+        "---\ntitle: Week 1\nservings: 4\n---\n\n== Monday ==\n\nBreakfast:\n- @../recipes/breakfast/Easy Pancakes{2}\n\n== Tuesday ==\n\nBreakfast:\n- @../recipes/breakfast/easy-pancakes{}\n- @./unknown{1}\n",
+    )
+    .unwrap();
+
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            "/api/v1/admin/reindex?full=true",
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/menus", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let menus = json["menus"].as_array().unwrap();
+    assert_eq!(menus.len(), 1);
+    assert_eq!(menus[0]["menuName"], "Week 1");
+    assert_eq!(menus[0]["path"], "menus/week-1.menu");
+    let menu_id = menus[0]["menuId"].as_str().unwrap().to_string();
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/menus/{}", menu_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["servings"], "4");
+
+    let sections = json["sections"].as_array().unwrap();
+    assert_eq!(sections.len(), 2);
+    assert_eq!(sections[0]["name"], "Monday");
+    let monday = &sections[0]["items"][0];
+    assert_eq!(monday["meal"], "Breakfast");
+    assert_eq!(monday["quantity"], 2.0);
+    assert_eq!(monday["recipeId"], recipe_id.as_str());
+    assert_eq!(monday["recipeName"], "Easy Pancakes");
+
+    let tuesday = sections[1]["items"].as_array().unwrap();
+    assert_eq!(tuesday[0]["recipeId"], recipe_id.as_str());
+    assert!(tuesday[1]["recipeId"].is_null());
+    assert_eq!(json["recipeIds"], serde_json::json!([recipe_id]));
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/menus/unknown", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_menus_git() {
+    test_menus_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_menus_disk() {
+    test_menus_impl("disk").await;
+}
+
+// ============================================================================
+// INGREDIENT CATALOG TESTS
+// ============================================================================
+
+async fn test_ingredient_catalog_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
+
+    let mut recipe_ids = std::collections::HashMap::new();
+    for (title, body) in [
+        ("Pancakes", "Mix @flour{200%g} with @Milk{300%ml}."),
+        ("Bread", "Knead @flour{500%g} with @water{300%ml}."),
+    ] {
+        let payload = serde_json::json!({
+            "content": format!("---\ntitle: {}\n---\n\n{}\n", title, body)
+        });
+        let response = build_router()
+            .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+            .await
+            .unwrap();
+        let body = extract_response_body(response).await;
+        let json: Value = serde_json::from_str(&body).unwrap();
+        recipe_ids.insert(title, json["recipeId"].as_str().unwrap().to_string());
+    }
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/ingredients", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let ingredients = json["ingredients"].as_array().unwrap();
+    let names: Vec<&str> = ingredients
+        .iter()
+        .map(|i| i["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["flour", "milk", "water"]);
+
+    let flour = &ingredients[0];
+    assert_eq!(flour["recipeCount"], 2);
+    let flour_recipes: Vec<&str> = flour["recipes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["recipeName"].as_str().unwrap())
+        .collect();
+    assert_eq!(flour_recipes, vec!["Bread", "Pancakes"]);
+
+    // Deleting a recipe drops ingredients only it used
+    let response = build_router()
+        .oneshot(make_request(
+            "DELETE",
+            &format!("/api/v1/recipes/{}", recipe_ids["Bread"]),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/ingredients", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let ingredients = json["ingredients"].as_array().unwrap();
+    assert_eq!(ingredients.len(), 2);
+    assert_eq!(ingredients[0]["name"], "flour");
+    assert_eq!(ingredients[0]["recipeCount"], 1);
+    assert_eq!(
+        ingredients[0]["recipes"][0]["recipeId"],
+        recipe_ids["Pancakes"].as_str()
+    );
+}
+
+#[tokio::test]
+async fn test_ingredient_catalog_git() {
+    test_ingredient_catalog_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_ingredient_catalog_disk() {
+    test_ingredient_catalog_impl("disk").await;
+}
+
+// ============================================================================
+// PANTRY TESTS
+// ============================================================================
+
+async fn test_pantry_and_cookable_impl(backend: &str) {
+    let (build_router, temp_dir) = setup_api_with_storage(backend).await;
+
+    for (title, body) in [
+        ("Pancakes", "Mix @flour{200%g}, @eggs{2} and @milk{300%ml}."),
+        ("Omelette", "Whisk @eggs{3} with @salt{}."),
+        (
+            "Bread",
+            "Knead @flour{500%g} with @water{300%ml} and @yeast{7%g}.",
+        ),
+        ("Salad", "Toss @lettuce{1} with @olive oil{2%tbsp}."),
+    ] {
+        let payload = serde_json::json!({
+            "content": format!("---\ntitle: {}\n---\n\n{}\n", title, body)
+        });
+        let response = build_router()
+            .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    }
+
+    for (name, item) in [
+        ("Eggs", serde_json::json!({ "quantity": 6 })),
+        ("flour", serde_json::json!({ "quantity": 1, "unit": "kg" })),
+        ("salt", serde_json::json!({})),
+        ("milk", serde_json::json!({ "quantity": 0, "unit": "ml" })),
+    ] {
+        let response = build_router()
+            .oneshot(make_request(
+                "PUT",
+                &format!("/api/v1/pantry/{}", name),
+                Some(item),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+    }
+
+    let response = build_router()
+        .oneshot(make_request(
+            "PUT",
+            "/api/v1/pantry/water",
+            Some(serde_json::json!({ "quantity": -1 })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/pantry", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let names: Vec<&str> = json["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["eggs", "flour", "milk", "salt"]);
+    assert_eq!(json["items"][1]["unit"], "kg");
+    assert!(temp_dir.path().join(".cooklang-store/pantry.json").exists());
+
+    // Milk is out of stock, so pancakes miss one ingredient; the salad has nothing on hand
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes/cookable", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let ranked: Vec<&str> = json["recipes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|recipe| recipe["recipeName"].as_str().unwrap())
+        .collect();
+    assert_eq!(ranked, vec!["Omelette", "Pancakes", "Bread"]);
+    assert_eq!(json["recipes"][0]["coverage"], 1.0);
+    assert_eq!(json["recipes"][1]["missing"], serde_json::json!(["milk"]));
+    assert_eq!(json["pagination"]["total"], 3);
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/recipes/cookable?max_missing=0",
+            None,
+        ))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["recipes"].as_array().unwrap().len(), 1);
+    assert_eq!(json["recipes"][0]["recipeName"], "Omelette");
+
+    let response = build_router()
+        .oneshot(make_request("DELETE", "/api/v1/pantry/SALT", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NO_CONTENT);
+    let response = build_router()
+        .oneshot(make_request("DELETE", "/api/v1/pantry/salt", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_pantry_and_cookable_git() {
+    test_pantry_and_cookable_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_pantry_and_cookable_disk() {
+    test_pantry_and_cookable_impl("disk").await;
+}
+
+// ============================================================================
+// UNITS TESTS
+// ============================================================================
+
+async fn test_reload_units_impl(backend: &str) {
+    let (build_router, temp_dir) = setup_api_with_storage(backend).await;
+
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Bread\n---\n\nKnead @flour{500%g} with @water{300%ml}.\n"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+
+    let units_path = temp_dir.path().join(".cooklang-store/units.toml");
+    std::fs::create_dir_all(units_path.parent().unwrap()).unwrap();
+    std::fs::write(&units_path, "default_system = [").unwrap();
+
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/admin/units/reload", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["error"], "invalid_units");
+
+    std::fs::write(&units_path, "default_system = \"metric\"\n").unwrap();
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/admin/units/reload", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["customUnits"], true);
+    assert_eq!(json["reindex"]["mode"], "full");
+    assert_eq!(json["reindex"]["parsed"], 1);
+
+    std::fs::remove_file(&units_path).unwrap();
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/admin/units/reload", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["customUnits"], false);
+}
+
+#[tokio::test]
+async fn test_reload_units_git() {
+    test_reload_units_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_reload_units_disk() {
+    test_reload_units_impl("disk").await;
+}
+
+// ============================================================================
+// ERROR CODE TESTS
+// ============================================================================
+
+async fn test_error_codes_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
+
+    let error_code = |response: axum::response::Response| async move {
+        let body = extract_response_body(response).await;
+        let json: Value = serde_json::from_str(&body).unwrap();
+        json["error"].as_str().unwrap_or_default().to_string()
+    };
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes/missing", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+    assert_eq!(error_code(response).await, "recipe_not_found");
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/menus/missing", None))
+        .await
+        .unwrap();
+    assert_eq!(error_code(response).await, "menu_not_found");
+
+    let payload = serde_json::json!({ "content": "No front matter here." });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    assert_eq!(error_code(response).await, "invalid_front_matter");
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes/search?q=%20", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    assert_eq!(error_code(response).await, "validation_error");
+}
+
+#[tokio::test]
+async fn test_error_codes_git() {
+    test_error_codes_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_error_codes_disk() {
+    test_error_codes_impl("disk").await;
+}
+
+// ============================================================================
+// TEMPLATES TESTS
+// ============================================================================
+
+async fn test_recipe_templates_impl(backend: &str) {
+    let (build_router, temp_dir) = setup_api_with_storage(backend).await;
+
+    let templates_dir = temp_dir.path().join("templates");
+    std::fs::create_dir_all(&templates_dir).unwrap();
+    std::fs::write(
+        templates_dir.join("salad.cook"),
+        "---\ntitle: Salad\ntags: [salad]\n---\n\nToss @greens{200%g} with @dressing{}.\n",
+    )
+    .unwrap();
+    std::fs::write(
+        templates_dir.join("bread.cook"),
+        "---\ntitle: Flatbread\n---\n\nMix @flour{250%g} and @water{150%ml}.\n",
+    )
+    .unwrap();
+
+    // Templates are not recipes
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            "/api/v1/admin/reindex?full=true",
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["pagination"]["total"], 0);
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/templates", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let templates = json["templates"].as_array().unwrap();
+    let names: Vec<&str> = templates
+        .iter()
+        .map(|t| t["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["bread", "cocktail", "salad", "stew"]);
+    // A data dir template replaces the built-in one of the same name
+    assert_eq!(templates[0]["title"], "Flatbread");
+    assert_eq!(templates[0]["builtin"], false);
+    assert_eq!(templates[1]["builtin"], true);
+
+    let payload = serde_json::json!({ "title": "Summer Salad", "path": "sides" });
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            "/api/v1/recipes/from-template/salad",
+            Some(payload),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["recipeName"], "Summer Salad");
+    assert_eq!(json["path"], "sides");
+    assert_eq!(json["fileName"], "summer-salad.cook");
+    let content = json["content"].as_str().unwrap();
+    assert!(content.contains("title: Summer Salad"));
+    assert!(content.contains("tags: [salad]"));
+    assert!(content.contains("Toss @greens{200%g}"));
+
+    let payload = serde_json::json!({ "title": "Hot Toddy" });
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            "/api/v1/recipes/from-template/cocktail",
+            Some(payload),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+
+    let payload = serde_json::json!({ "title": "Nothing" });
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            "/api/v1/recipes/from-template/missing",
+            Some(payload),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["error"], "template_not_found");
+
+    let payload = serde_json::json!({ "title": "  " });
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            "/api/v1/recipes/from-template/stew",
+            Some(payload),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_recipe_templates_git() {
+    test_recipe_templates_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_recipe_templates_disk() {
+    test_recipe_templates_impl("disk").await;
+}
+
+// ============================================================================
+// FRONT MATTER SCHEMA TESTS
+// ============================================================================
+
+async fn test_front_matter_schema_impl(backend: &str) {
+    let config = cooklang_store::config::StoreConfig {
+        front_matter_schema: cooklang_store::schema::FrontMatterSchema::parse(
+            "servings=integer required,source=url",
+        ),
+        ..Default::default()
+    };
+    let (build_router, _temp_dir) = setup_api_with_config(backend, config).await;
+
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Soup\nsource: grandma\n---\n\nSimmer @stock{1%l}.\n"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["error"], "schema_violation");
+    assert_eq!(json["details"]["servings"], "is required");
+    assert_eq!(json["details"]["source"], "must be an http(s) URL");
+
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Soup\nservings: 4\nsource: https://example.com/soup\n---\n\nSimmer @stock{1%l}.\n"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let recipe_id = json["recipeId"].as_str().unwrap().to_string();
+
+    // Updates are checked too, including front matter patches
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Soup\nservings: four\n---\n\nSimmer @stock{1%l}.\n"
+    });
+    let response = build_router()
+        .oneshot(make_request(
+            "PUT",
+            &format!("/api/v1/recipes/{}", recipe_id),
+            Some(payload),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["details"]["servings"], "must be an integer");
+
+    let response = build_router()
+        .oneshot(make_request(
+            "PATCH",
+            &format!("/api/v1/recipes/{}", recipe_id),
+            Some(serde_json::json!({ "servings": "a crowd" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+
+    let response = build_router()
+        .oneshot(make_request(
+            "PATCH",
+            &format!("/api/v1/recipes/{}", recipe_id),
+            Some(serde_json::json!({ "servings": 6 })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_front_matter_schema_git() {
+    test_front_matter_schema_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_front_matter_schema_disk() {
+    test_front_matter_schema_impl("disk").await;
+}
+
+// ============================================================================
+// FRONT MATTER NORMALIZATION TESTS
+// ============================================================================
+
+async fn test_normalize_front_matter_impl(backend: &str) {
+    let (build_router, temp_dir) = setup_api_with_storage(backend).await;
+
+    for content in [
+        "---\nServings: 4\nTags: soup, quick\ntitle: Soup\n---\n\nSimmer @stock{1%l}.\n",
+        "---\ntitle: Toast\ntags:\n- breakfast\n---\n\nToast @bread{2%slices}.\n",
+    ] {
+        let response = build_router()
+            .oneshot(make_request(
+                "POST",
+                "/api/v1/recipes",
+                Some(serde_json::json!({ "content": content })),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    }
+    let soup_path = temp_dir.path().join("recipes/soup.cook");
+    let original = std::fs::read_to_string(&soup_path).unwrap();
+    let commits_before = (backend == "git").then(|| count_git_commits(&temp_dir));
+
+    // A dry run reports the change without writing it
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            "/api/v1/admin/normalize?dry_run=true",
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["dryRun"], true);
+    assert_eq!(json["updated"].as_array().unwrap().len(), 1);
+    assert_eq!(json["unchanged"], 1);
+    assert_eq!(std::fs::read_to_string(&soup_path).unwrap(), original);
+
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/admin/normalize", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let recipe_id = json["updated"][0].as_str().unwrap().to_string();
+
+    let content = std::fs::read_to_string(&soup_path).unwrap();
+    assert!(content.starts_with("---\ntitle: Soup\ntags:\n- soup\n- quick\nservings: 4\n---\n"));
+    assert!(content.ends_with("Simmer @stock{1%l}.\n"));
+    if let Some(commits_before) = commits_before {
+        assert_eq!(count_git_commits(&temp_dir), commits_before + 1);
+    }
+
+    // The index serves the rewritten content
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["content"], content);
+
+    // Normalizing again changes nothing
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/admin/normalize", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["updated"], serde_json::json!([]));
+    assert_eq!(json["unchanged"], 2);
+}
+
+#[tokio::test]
+async fn test_normalize_front_matter_git() {
+    test_normalize_front_matter_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_normalize_front_matter_disk() {
+    test_normalize_front_matter_impl("disk").await;
+}
+
+// ============================================================================
+// FILENAME MISALIGNMENT TESTS
+// ============================================================================
+
+async fn test_misaligned_files_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_seeded_files(
+        backend,
+        vec![],
+        vec![
+            (
+                "recipes/soup.cook",
+                "---\ntitle: Soup\n---\n\nSimmer @stock{1%l}.\n",
+            ),
+            (
+                "recipes/desserts/old-name.cook",
+                "---\ntitle: Lemon Tart\n---\n\nBake @lemons{3}.\n",
+            ),
+        ],
+    )
+    .await;
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/admin/misaligned-files", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["total"], 1);
+    let file = &json["files"][0];
+    assert_eq!(file["recipeName"], "Lemon Tart");
+    assert_eq!(file["path"], "desserts");
+    assert_eq!(file["fileName"], "old-name.cook");
+    assert_eq!(file["expectedFileName"], "lemon-tart.cook");
+
+    // Updating the recipe realigns its file
+    let response = build_router()
+        .oneshot(make_request(
+            "PUT",
+            &format!("/api/v1/recipes/{}", file["recipeId"].as_str().unwrap()),
+            Some(serde_json::json!({
+                "content": "---\ntitle: Lemon Tart\n---\n\nBake @lemons{4}.\n"
+            })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/admin/misaligned-files", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["total"], 0);
+}
+
+#[tokio::test]
+async fn test_misaligned_files_git() {
+    test_misaligned_files_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_misaligned_files_disk() {
+    test_misaligned_files_impl("disk").await;
+}
+
+async fn test_reconcile_filenames_impl(backend: &str) {
+    let (build_router, temp_dir) = setup_api_with_seeded_files(
+        backend,
+        vec![],
+        vec![
+            (
+                "recipes/soup.cook",
+                "---\ntitle: Soup\n---\n\nSimmer @stock{1%l}.\n",
+            ),
+            (
+                "recipes/draft.cook",
+                "---\ntitle: Soup\n---\n\nSimmer @water{1%l}.\n",
+            ),
+            (
+                "recipes/desserts/old-name.cook",
+                "---\ntitle: Lemon Tart\n---\n\nBake @lemons{3}.\n",
+            ),
+        ],
+    )
+    .await;
+
+    // A recipe created through the API already has a matching name
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            "/api/v1/recipes",
+            Some(serde_json::json!({
+                "content": "---\ntitle: Bread\n---\n\nBake @flour{500%g}.\n"
+            })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    let commits_before = (backend == "git").then(|| count_git_commits(&temp_dir));
+
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            "/api/v1/admin/reconcile?dry_run=true",
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["dryRun"], true);
+    assert_eq!(json["renamed"].as_object().unwrap().len(), 2);
+    assert!(temp_dir.path().join("recipes/draft.cook").exists());
+
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/admin/reconcile", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let renamed = json["renamed"].as_object().unwrap();
+    assert_eq!(renamed.len(), 2);
+
+    // A taken name gets a suffix, as on create
+    assert!(!temp_dir.path().join("recipes/draft.cook").exists());
+    assert!(temp_dir.path().join("recipes/soup-2.cook").exists());
+    assert!(temp_dir
+        .path()
+        .join("recipes/desserts/lemon-tart.cook")
+        .exists());
+    if let Some(commits_before) = commits_before {
+        assert_eq!(count_git_commits(&temp_dir), commits_before + 1);
+    }
+
+    // The new IDs resolve, the old ones are gone
+    for (old_id, new_id) in renamed {
+        let response = build_router()
+            .oneshot(make_request(
+                "GET",
+                &format!("/api/v1/recipes/{}", new_id.as_str().unwrap()),
+                None,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+        let response = build_router()
+            .oneshot(make_request(
+                "GET",
+                &format!("/api/v1/recipes/{}", old_id),
+                None,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+    }
+
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/admin/reconcile", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["renamed"], serde_json::json!({}));
+}
+
+#[tokio::test]
+async fn test_reconcile_filenames_git() {
+    test_reconcile_filenames_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_reconcile_filenames_disk() {
+    test_reconcile_filenames_impl("disk").await;
+}
+
+// ============================================================================
+// RECIPES DIRECTORY TESTS
+// ============================================================================
+
+async fn test_recipes_at_data_dir_root_impl(backend: &str) {
+    let config = cooklang_store::config::StoreConfig {
+        recipes_dir: String::new(),
+        ..Default::default()
+    };
+    let (build_router, temp_dir) = setup_api_with_config(backend, config).await;
+
+    // Files written outside the API are categorized from the root
+    std::fs::create_dir_all(temp_dir.path().join("mains")).unwrap();
+    std::fs::write(
+        temp_dir.path().join("mains/chili.cook"),
+        "---\ntitle: Chili\n---\n\nSimmer @beans{400%g}.\n",
+    )
+    .unwrap();
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            "/api/v1/admin/reindex?full=true",
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/recipes/find-by-path?path=mains",
+            None,
+        ))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json[0]["recipeName"], "Chili");
+    assert_eq!(json[0]["path"], "mains");
+
+    // New recipes are written there too
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Lemon Tart\n---\n\nBake @lemons{3}.\n",
+        "path": "desserts"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["path"], "desserts");
+    assert!(temp_dir.path().join("desserts/lemon-tart.cook").exists());
+    assert!(!temp_dir.path().join("recipes").exists());
+}
+
+#[tokio::test]
+async fn test_recipes_at_data_dir_root_git() {
+    test_recipes_at_data_dir_root_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_recipes_at_data_dir_root_disk() {
+    test_recipes_at_data_dir_root_impl("disk").await;
+}
+
+// ============================================================================
+// COLLECTION TESTS
+// ============================================================================
+
+async fn test_collections_impl(backend: &str) {
+    let (build_router, temp_dirs) = setup_api_with_collections(backend, &["family", "work"]).await;
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/collections", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["collections"], serde_json::json!(["family", "work"]));
+
+    // Selected by path
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Sunday Roast\n---\n\nRoast @beef{1%kg}.\n"
+    });
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            "/api/v1/collections/family/recipes",
+            Some(payload),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    assert!(temp_dirs[1]
+        .path()
+        .join("recipes/sunday-roast.cook")
+        .exists());
+
+    // Selected by header
+    let request = axum::http::Request::builder()
+        .method("GET")
+        .uri("/api/v1/recipes")
+        .header("x-collection", "family")
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let response = build_router().oneshot(request).await.unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["recipes"][0]["recipeName"], "Sunday Roast");
+
+    // Other collections and the default one are untouched
+    for uri in ["/api/v1/recipes", "/api/v1/collections/work/recipes"] {
+        let response = build_router()
+            .oneshot(make_request("GET", uri, None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+        let body = extract_response_body(response).await;
+        let json: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["recipes"], serde_json::json!([]));
+    }
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/collections/blog/recipes",
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["error"], "collection_not_found");
+}
+
+#[tokio::test]
+async fn test_collections_git() {
+    test_collections_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_collections_disk() {
+    test_collections_impl("disk").await;
+}
+
+// ============================================================================
+// REVISION TESTS
+// ============================================================================
+
+#[tokio::test]
+async fn test_recipe_revision() {
+    let (build_router, temp_dir) = setup_api_with_storage("git").await;
+
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Soup\n---\n\nSimmer @stock{1%l}.\n"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let recipe_id = json["recipeId"].as_str().unwrap().to_string();
+    let created = json["revision"].as_str().unwrap().to_string();
+
+    let head = |temp_dir: &tempfile::TempDir| {
+        let repo = git2::Repository::open(temp_dir.path()).unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap().id();
+        head.to_string()
+    };
+    assert_eq!(created, head(&temp_dir));
+
+    // Changes to other recipes leave the revision alone
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Bread\n---\n\nBake @flour{500%g}.\n"
+    });
+    build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["revision"], created.as_str());
+    assert_ne!(created, head(&temp_dir));
+
+    // Disk storage has no revisions
+    let (build_router, _temp_dir) = setup_api_with_storage("disk").await;
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Soup\n---\n\nSimmer @stock{1%l}.\n"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert!(json.get("revision").is_none());
+}
+
+#[tokio::test]
+async fn test_recipe_last_modified() {
+    let (build_router, _temp_dir) = setup_api_with_storage("git").await;
+
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Soup\n---\n\nSimmer @stock{1%l}.\n",
+        "author": "Alice",
+        "comment": "Grandma's recipe"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["lastModifiedBy"], "Alice");
+    assert!(json["lastCommitMessage"]
+        .as_str()
+        .unwrap()
+        .contains("Grandma's recipe"));
+    let modified_at = json["lastModifiedAt"].as_str().unwrap().to_string();
+    assert!(chrono::DateTime::parse_from_rfc3339(&modified_at).is_ok());
+
+    // Listings carry the same details from the index
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let row = &json["recipes"][0];
+    assert_eq!(row["lastModifiedBy"], "Alice");
+    assert_eq!(row["lastModifiedAt"], modified_at.as_str());
+    assert!(row["revision"].is_string());
+
+    // Disk storage only knows the modification time
+    let (build_router, _temp_dir) = setup_api_with_storage("disk").await;
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Soup\n---\n\nSimmer @stock{1%l}.\n",
+        "author": "Alice"
+    });
+    build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let row = &json["recipes"][0];
+    assert!(row["lastModifiedAt"].is_string());
+    assert!(row.get("lastModifiedBy").is_none());
+    assert!(row.get("lastCommitMessage").is_none());
+}
+
+#[tokio::test]
+async fn test_submodule_recipes_are_read_only() {
+    let (build_router, temp_dir) = setup_api_with_storage("git").await;
+    add_submodule(
+        &temp_dir,
+        "recipes/community",
+        &[("pasta.cook", "---\ntitle: Pasta\n---\n\nBoil @pasta{}.\n")],
+    );
+    build_router()
+        .oneshot(make_request("POST", "/api/v1/admin/reindex", None))
+        .await
+        .unwrap();
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let row = &json["recipes"][0];
+    assert_eq!(row["path"], "community");
+    assert_eq!(row["readOnly"], true);
+    assert_eq!(row["lastModifiedBy"], "Pack Maintainer");
+    let recipe_id = row["recipeId"].as_str().unwrap().to_string();
+
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Pasta\n---\n\nBurn @pasta{}.\n"
+    });
+    let response = build_router()
+        .oneshot(make_request(
+            "PUT",
+            &format!("/api/v1/recipes/{}", recipe_id),
+            Some(payload),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 403);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["error"], "read_only");
+
+    let response = build_router()
+        .oneshot(make_request(
+            "DELETE",
+            &format!("/api/v1/recipes/{}", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 403);
+
+    // The recipe is untouched and still readable
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["readOnly"], true);
+    assert!(json["content"].as_str().unwrap().contains("Boil"));
+
+    // Recipes outside the submodule stay writable
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Soup\n---\n\nSimmer @stock{1%l}.\n"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["readOnly"], false);
+}
+
+// ============================================================================
+// WRITE HOOK TESTS
+// ============================================================================
+
+#[tokio::test]
+async fn test_write_hooks_reject_changes() {
+    let config = cooklang_store::config::StoreConfig {
+        banned_words: vec!["lard".to_string()],
+        write_hook_command: Some(
+            "if [ \"$COOKLANG_ACTION\" = Copy ]; then echo 'copies need review'; exit 1; fi"
+                .to_string(),
+        ),
+        ..Default::default()
+    };
+    let (build_router, _temp_dir) = setup_api_with_config("git", config).await;
+
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Pie\n---\n\nRub in @Lard{100%g}.\n"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["error"], "write_rejected");
+    assert_eq!(json["details"]["hook"], "banned-words");
+    assert_eq!(json["details"]["reason"], "contains banned words: lard");
+
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Pie\n---\n\nRub in @butter{100%g}.\n"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let recipe_id = json["recipeId"].as_str().unwrap().to_string();
+
+    // The command hook sees the action and turns copies down
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            &format!("/api/v1/recipes/{}/copy", recipe_id),
+            Some(serde_json::json!({ "path": "baking" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["details"]["hook"], "command");
+    assert_eq!(json["details"]["reason"], "copies need review");
+
+    // Nothing rejected was written
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["recipes"].as_array().unwrap().len(), 1);
+}
+
+// ============================================================================
+// WEB UI TESTS
+// ============================================================================
+
+#[tokio::test]
+async fn test_web_ui() {
+    let (build_router, _temp_dir) = setup_api_with_storage("disk").await;
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    assert!(response.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .starts_with("text/html"));
+    let body = extract_response_body(response).await;
+    assert!(body.contains("/ui/app.js"));
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/ui/app.js", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    assert!(response.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .contains("javascript"));
+    let etag = response.headers()["etag"].clone();
+
+    // Unchanged assets are revalidated without a body
+    let response = build_router()
+        .oneshot(
+            axum::http::Request::builder()
+                .uri("/ui/app.js")
+                .header("if-none-match", etag)
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_MODIFIED);
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/ui/missing.js", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+
+    // WEB_UI=false leaves only the API
+    let config = cooklang_store::config::StoreConfig {
+        web_ui: false,
+        ..Default::default()
+    };
+    let (build_router, _temp_dir) = setup_api_with_config("disk", config).await;
+    let response = build_router()
+        .oneshot(make_request("GET", "/", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+}
+
+// ============================================================================
+// HTML RENDERING TESTS
+// ============================================================================
+
+async fn test_recipe_html_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
+
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Fish & Chips\nservings: 2\n---\n\nFry @cod{2%fillets} in @oil{1%l} using a #deep pan{}.\n"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let recipe_id = json["recipeId"].as_str().unwrap().to_string();
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}/html", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    assert_eq!(
+        response.headers()["content-type"],
+        "text/html; charset=utf-8"
+    );
+    let html = extract_response_body(response).await;
+    assert!(html.starts_with("<!doctype html>"));
+    assert!(html.contains("<h1>Fish &amp; Chips</h1>"));
+    assert!(html.contains("<li><span class=\"quantity\">2 fillets</span> cod</li>"));
+    assert!(html.contains("<span class=\"cookware\">deep pan</span>"));
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}/html?fragment=true", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    let html = extract_response_body(response).await;
+    assert!(html.starts_with("<article class=\"recipe\">"));
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}/html?print=true", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    let html = extract_response_body(response).await;
+    assert!(html.contains("<input type=\"checkbox\"> <span class=\"quantity\">1 l</span> oil"));
+
+    // Edits show up right away despite the render cache
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Fish & Chips\n---\n\nFry @haddock{2%fillets}.\n"
+    });
+    build_router()
+        .oneshot(make_request(
+            "PUT",
+            &format!("/api/v1/recipes/{}", recipe_id),
+            Some(payload),
+        ))
+        .await
+        .unwrap();
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}/html", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    let html = extract_response_body(response).await;
+    assert!(html.contains("haddock"));
+    assert!(!html.contains("cod"));
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/recipes/000000000000/html",
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_recipe_html_git() {
+    test_recipe_html_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_recipe_html_disk() {
+    test_recipe_html_impl("disk").await;
+}
+
+// ============================================================================
+// QR CODE TESTS
+// ============================================================================
+
+#[tokio::test]
+async fn test_recipe_qr_code() {
+    let (build_router, _temp_dir) = setup_api_with_storage("disk").await;
+
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Soup\n---\n\nSimmer @stock{1%l}.\n"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let recipe_id = json["recipeId"].as_str().unwrap().to_string();
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}/qr", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "image/svg+xml");
+    let svg = extract_response_body(response).await;
+    assert!(svg.contains("<svg"));
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}/qr?format=png", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "image/png");
+    let image = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert!(image.starts_with(b"\x89PNG"));
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}/qr?format=gif", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes/000000000000/qr", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+}
+
+// =============================================================================
+// SHARE LINK TESTS
+// =============================================================================
+
+#[tokio::test]
+async fn test_share_links() {
+    let config = cooklang_store::config::StoreConfig {
+        share_secret: Some("test-secret".to_string()),
+        public_url: Some("https://recipes.example.com".to_string()),
+        ..Default::default()
+    };
+    let (build_router, _temp_dir) = setup_api_with_config("git", config).await;
+
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Soup\n---\n\nSimmer @stock{1%l}.\n"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let recipe_id = json["recipeId"].as_str().unwrap().to_string();
+
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            &format!("/api/v1/recipes/{}/share", recipe_id),
+            Some(serde_json::json!({ "expiresInHours": 2, "author": "ana" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    let body = extract_response_body(response).await;
+    let share: Value = serde_json::from_str(&body).unwrap();
+    let token = share["token"].as_str().unwrap().to_string();
+    let share_id = share["shareId"].as_str().unwrap().to_string();
+    assert_eq!(share["recipeId"], recipe_id.as_str());
+    assert_eq!(share["createdBy"], "ana");
+    assert_eq!(
+        share["htmlUrl"],
+        format!("https://recipes.example.com/api/v1/shared/{}/html", token)
+    );
+
+    // Anyone with the token can read the recipe, and only that recipe
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/shared/{}", token),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    assert_eq!(response.headers()["cache-control"], "no-store");
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["recipeName"], "Soup");
+    assert_eq!(json["readOnly"], true);
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/shared/{}/html", token),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    assert!(extract_response_body(response)
+        .await
+        .contains("<h1>Soup</h1>"));
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}/shares", recipe_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["shares"].as_array().unwrap().len(), 1);
+    assert_eq!(json["shares"][0]["token"], token.as_str());
+
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            &format!("/api/v1/recipes/{}/share", recipe_id),
+            Some(serde_json::json!({ "expiresInHours": 0 })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/shared/{}x", token),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+
+    // Revoking stops the token from working
+    let response = build_router()
+        .oneshot(make_request(
+            "DELETE",
+            &format!("/api/v1/shares/{}", share_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NO_CONTENT);
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/shared/{}", token),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["error"], "share_not_found");
+
+    let response = build_router()
+        .oneshot(make_request(
+            "DELETE",
+            &format!("/api/v1/shares/{}", share_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_share_links_need_secret() {
+    let (build_router, _temp_dir) = setup_api_with_storage("disk").await;
+
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Soup\n---\n\nSimmer @stock{1%l}.\n"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let recipe_id = json["recipeId"].as_str().unwrap().to_string();
+
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            &format!("/api/v1/recipes/{}/share", recipe_id),
+            Some(serde_json::json!({})),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+}
+
+// =============================================================================
+// VISIBILITY TESTS
+// =============================================================================
 
-    // Verify old file no longer exists
-    let files = std::fs::read_dir(temp_dir.path().join("recipes/desserts"))
+#[tokio::test]
+async fn test_recipe_visibility() {
+    let config = cooklang_store::config::StoreConfig {
+        api_token: Some("kitchen-secret".to_string()),
+        ..Default::default()
+    };
+    let (build_router, _temp_dir) = setup_api_with_config("disk", config).await;
+    let authorized = |method: &str, uri: &str, body: Option<Value>| {
+        let mut request = make_request(method, uri, body);
+        request
+            .headers_mut()
+            .insert("authorization", "Bearer kitchen-secret".parse().unwrap());
+        request
+    };
+
+    let mut ids = Vec::new();
+    for (title, visibility) in [("Soup", "public"), ("Stew", "private")] {
+        let payload = serde_json::json!({
+            "content": format!("---\ntitle: {}\nvisibility: {}\n---\n\nSimmer @stock{{1%l}}.\n", title, visibility)
+        });
+        let response = build_router()
+            .oneshot(authorized("POST", "/api/v1/recipes", Some(payload)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+        let body = extract_response_body(response).await;
+        let json: Value = serde_json::from_str(&body).unwrap();
+        ids.push(json["recipeId"].as_str().unwrap().to_string());
+    }
+
+    // The API needs the token; the public routes don't
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::UNAUTHORIZED);
+    assert_eq!(response.headers()["www-authenticate"], "Bearer");
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["error"], "unauthorized");
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/recipes/{}", ids[1]),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::UNAUTHORIZED);
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/public/recipes", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["pagination"]["total"], 1);
+    assert_eq!(json["recipes"][0]["recipeName"], "Soup");
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/public/recipes/{}", ids[0]),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["readOnly"], true);
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/public/recipes/{}/html", ids[0]),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+    // Private recipes look missing on the public routes
+    for uri in [
+        format!("/api/v1/public/recipes/{}", ids[1]),
+        format!("/api/v1/public/recipes/{}/html", ids[1]),
+    ] {
+        let response = build_router()
+            .oneshot(make_request("GET", &uri, None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+    }
+
+    let response = build_router()
+        .oneshot(authorized(
+            "GET",
+            &format!("/api/v1/recipes/{}", ids[1]),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    // Shared caches must not hand a token holder's response to anyone else
+    assert_eq!(response.headers()["cache-control"], "private, no-cache");
+    assert_eq!(
+        response.headers()["vary"],
+        "accept, x-collection, authorization"
+    );
+
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Stew\nvisibility: friends\n---\n\nSimmer @stock{1%l}.\n"
+    });
+    let response = build_router()
+        .oneshot(authorized(
+            "PUT",
+            &format!("/api/v1/recipes/{}", ids[1]),
+            Some(payload),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["error"], "invalid_front_matter");
+}
+
+#[tokio::test]
+async fn test_admin_token() {
+    let config = cooklang_store::config::StoreConfig {
+        api_token: Some("kitchen-secret".to_string()),
+        admin_token: Some("admin-secret".to_string()),
+        ..Default::default()
+    };
+    let (build_router, _temp_dir) = setup_api_with_config("disk", config).await;
+    let with_token = |uri: &str, token: &str| {
+        let mut request = make_request("GET", uri, None);
+        request.headers_mut().insert(
+            "authorization",
+            format!("Bearer {}", token).parse().unwrap(),
+        );
+        request
+    };
+
+    // Each token opens its own routes only
+    for (uri, token, status) in [
+        ("/api/v1/admin/webhooks", "admin-secret", 200),
+        ("/api/v1/admin/webhooks", "kitchen-secret", 401),
+        ("/api/v1/recipes", "kitchen-secret", 200),
+        ("/api/v1/recipes", "admin-secret", 401),
+    ] {
+        let response = build_router()
+            .oneshot(with_token(uri, token))
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), status, "{} with {}", uri, token);
+    }
+
+    // The client token alone never opens the admin routes: without `ADMIN_TOKEN` they're off
+    let config = cooklang_store::config::StoreConfig {
+        api_token: Some("kitchen-secret".to_string()),
+        ..Default::default()
+    };
+    let (build_router, _temp_dir) = setup_api_with_config("disk", config).await;
+    for token in ["kitchen-secret", "anything"] {
+        let response = build_router()
+            .oneshot(with_token("/api/v1/admin/webhooks", token))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+    }
+
+    // Without `API_TOKEN` only the admin routes are locked
+    let config = cooklang_store::config::StoreConfig {
+        admin_token: Some("admin-secret".to_string()),
+        ..Default::default()
+    };
+    let (build_router, _temp_dir) = setup_api_with_config("disk", config).await;
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/admin/reindex", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::UNAUTHORIZED);
+}
+
+// ============================================================================
+// SYNONYMS TESTS
+// ============================================================================
+
+#[tokio::test]
+async fn test_ingredient_synonyms() {
+    let (build_router, temp_dir) = setup_api_with_storage("disk").await;
+
+    for (title, body) in [
+        ("Noodles", "Top with @scallions{2}."),
+        ("Omelette", "Whisk @eggs{3} with @green onion{1}."),
+        ("Pancakes", "Mix @eggs{2} with @spring onion{1}."),
+    ] {
+        let payload = serde_json::json!({
+            "content": format!("---\ntitle: {}\n---\n\n{}\n", title, body)
+        });
+        let response = build_router()
+            .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    }
+
+    let synonyms_path = temp_dir.path().join(".cooklang-store/synonyms.txt");
+    std::fs::create_dir_all(synonyms_path.parent().unwrap()).unwrap();
+    std::fs::write(&synonyms_path, "scallion\n").unwrap();
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/admin/synonyms/reload", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["error"], "invalid_synonyms");
+
+    std::fs::write(
+        &synonyms_path,
+        "# onions\nscallions = green onion = spring onion\n",
+    )
+    .unwrap();
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/admin/synonyms/reload", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["groups"], 1);
+
+    // The catalog merges every name under the canonical one
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/ingredients", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let ingredients = json["ingredients"].as_array().unwrap();
+    assert_eq!(ingredients.len(), 2);
+    assert_eq!(ingredients[1]["name"], "scallions");
+    assert_eq!(
+        ingredients[1]["synonyms"],
+        serde_json::json!(["green onion", "spring onion"])
+    );
+    assert_eq!(ingredients[1]["recipeCount"], 3);
+
+    // Searching by any name finds recipes using the others
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/recipes/search?ingredient=Green%20Onion,eggs",
+            None,
+        ))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let names: Vec<&str> = json["recipes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|recipe| recipe["recipeName"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["Omelette", "Pancakes"]);
+
+    // A synonym in the pantry covers the recipe's name for it
+    let response = build_router()
+        .oneshot(make_request(
+            "PUT",
+            "/api/v1/pantry/spring%20onion",
+            Some(serde_json::json!({})),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/recipes/cookable?max_missing=0",
+            None,
+        ))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["recipes"].as_array().unwrap().len(), 1);
+    assert_eq!(json["recipes"][0]["recipeName"], "Noodles");
+    assert_eq!(
+        json["recipes"][0]["onHand"],
+        serde_json::json!(["scallions"])
+    );
+}
+
+// ============================================================================
+// SHOPPING LIST TESTS
+// ============================================================================
+
+async fn test_shopping_lists_impl(backend: &str) {
+    let (build_router, temp_dir) = setup_api_with_storage(backend).await;
+
+    let mut recipe_ids = Vec::new();
+    for (title, body) in [
+        ("Pancakes", "Mix @flour{200%g}, @eggs{2} and @milk{300%ml}."),
+        (
+            "Bread",
+            "Knead @flour{300%g} with @water{} and @salt{1%tsp}.",
+        ),
+    ] {
+        let payload = serde_json::json!({
+            "content": format!("---\ntitle: {}\n---\n\n{}\n", title, body)
+        });
+        let response = build_router()
+            .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+        let body = extract_response_body(response).await;
+        let json: Value = serde_json::from_str(&body).unwrap();
+        recipe_ids.push(json["recipeId"].as_str().unwrap().to_string());
+    }
+
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            "/api/v1/shopping-lists",
+            Some(serde_json::json!({ "recipeIds": recipe_ids })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let list_id = json["id"].as_str().unwrap().to_string();
+    assert_eq!(json["name"], "Pancakes, Bread");
+    assert_eq!(json["itemCount"], 5);
+    assert_eq!(json["items"][1]["name"], "flour");
+    assert_eq!(json["items"][1]["quantity"], 500.0);
+    assert_eq!(json["items"][1]["unit"], "g");
+    assert!(temp_dir
+        .path()
+        .join(".cooklang-store/shopping-lists.json")
+        .exists());
+
+    // Check off the flour and buy a bit more milk than needed
+    let response = build_router()
+        .oneshot(make_request(
+            "PATCH",
+            &format!("/api/v1/shopping-lists/{}/items/2", list_id),
+            Some(serde_json::json!({ "purchased": true })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let response = build_router()
+        .oneshot(make_request(
+            "PATCH",
+            &format!("/api/v1/shopping-lists/{}/items/3", list_id),
+            Some(serde_json::json!({ "quantity": 500, "unit": "ml" })),
+        ))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["name"], "milk");
+    assert_eq!(json["quantity"], 500.0);
+    assert_eq!(json["purchased"], false);
+
+    let response = build_router()
+        .oneshot(make_request(
+            "PATCH",
+            &format!("/api/v1/shopping-lists/{}/items/3", list_id),
+            Some(serde_json::json!({ "quantity": -1 })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    let response = build_router()
+        .oneshot(make_request(
+            "PATCH",
+            &format!("/api/v1/shopping-lists/{}/items/99", list_id),
+            Some(serde_json::json!({ "purchased": true })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+    let body = extract_response_body(response).await;
+    assert!(body.contains("shopping_item_not_found"));
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/shopping-lists/{}?purchased=false", list_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    let names: Vec<&str> = json["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["eggs", "milk", "salt", "water"]);
+    assert_eq!(json["itemCount"], 5);
+    assert_eq!(json["purchasedCount"], 1);
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/shopping-lists", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["shoppingLists"].as_array().unwrap().len(), 1);
+    assert_eq!(json["shoppingLists"][0]["purchasedCount"], 1);
+
+    // Exports follow the aisles file, with items in no aisle last
+    let aisles_path = temp_dir.path().join(".cooklang-store/aisle.conf");
+    std::fs::write(&aisles_path, "[dairy]\nmilk | eggs\n\n[baking]\nflour\n").unwrap();
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/shopping-lists/{}/export?format=markdown", list_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "text/markdown; charset=utf-8"
+    );
+    let body = extract_response_body(response).await;
+    assert_eq!(
+        body,
+        "# Pancakes, Bread\n\n## dairy\n\n- [ ] 2 eggs\n- [ ] 500 ml milk\n\n\
+         ## baking\n\n- [x] 500 g flour\n\n## Other\n\n- [ ] 1 tsp salt\n- [ ] water\n"
+    );
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!(
+                "/api/v1/shopping-lists/{}/export?format=csv&purchased=false",
+                list_id
+            ),
+            None,
+        ))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    assert_eq!(
+        body.lines().next(),
+        Some("aisle,name,quantity,unit,purchased")
+    );
+    assert_eq!(body.lines().count(), 5);
+    assert!(body.contains("\nOther,water,,,false\n"));
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/shopping-lists/{}/export?format=pdf", list_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    std::fs::write(&aisles_path, "milk\n").unwrap();
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/shopping-lists/{}/export", list_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    let body = extract_response_body(response).await;
+    assert!(body.contains("invalid_aisles"));
+
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            "/api/v1/shopping-lists",
+            Some(serde_json::json!({ "recipeIds": [] })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+
+    let response = build_router()
+        .oneshot(make_request(
+            "DELETE",
+            &format!("/api/v1/shopping-lists/{}", list_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NO_CONTENT);
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!("/api/v1/shopping-lists/{}", list_id),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_shopping_lists_git() {
+    test_shopping_lists_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_shopping_lists_disk() {
+    test_shopping_lists_impl("disk").await;
+}
+
+// ============================================================================
+// EXPORT TESTS
+// ============================================================================
+
+async fn test_export_paprika_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
+
+    for content in [
+        "---\ntitle: Pancakes\nservings: 4\n---\n\nMix @flour{0.5%cup} and @eggs{2}.\n",
+        "---\ntitle: Bread\ntags: [baking]\n---\n\nKnead @flour{500%g}.\n\nBake.\n",
+        "---\ntitle: Secret Stew\ndraft: true\n---\n\nStew @beef{1%kg}.\n",
+    ] {
+        let payload = serde_json::json!({ "content": content });
+        let response = build_router()
+            .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    }
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            "/api/v1/recipes/export?format=paprika",
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "application/zip"
+    );
+    assert!(response
+        .headers()
+        .get("content-disposition")
         .unwrap()
-        .filter_map(|entry| {
-            let entry = entry.ok()?;
-            let path = entry.path();
-            if path.is_file() {
-                path.file_name().map(|n| n.to_str().unwrap().to_string())
-            } else {
-                None
-            }
-        })
-        .collect::<Vec<_>>();
+        .to_str()
+        .unwrap()
+        .contains("recipes.paprikarecipes"));
+    let archive = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
 
-    assert!(!files.contains(&initial_filename.to_string()));
-    assert!(files.contains(&new_filename.to_string()));
+    // Walk the zip's local headers: stored entries holding gzipped JSON, drafts left out
+    let mut recipes = Vec::new();
+    let mut pos = 0;
+    while archive[pos..].starts_with(b"PK\x03\x04") {
+        let size = u32::from_le_bytes(archive[pos + 18..pos + 22].try_into().unwrap()) as usize;
+        let name_len = u16::from_le_bytes([archive[pos + 26], archive[pos + 27]]) as usize;
+        let name = std::str::from_utf8(&archive[pos + 30..pos + 30 + name_len]).unwrap();
+        let data = &archive[pos + 30 + name_len..pos + 30 + name_len + size];
+        let mut json = String::new();
+        std::io::Read::read_to_string(&mut flate2::read::GzDecoder::new(data), &mut json).unwrap();
+        let recipe: Value = serde_json::from_str(&json).unwrap();
+        recipes.push((name.to_string(), recipe));
+        pos += 30 + name_len + size;
+    }
+    let names: Vec<&str> = recipes.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, vec!["Bread.paprikarecipe", "Pancakes.paprikarecipe"]);
+    assert_eq!(recipes[0].1["directions"], "Knead flour.\n\nBake.");
+    assert_eq!(recipes[0].1["categories"], serde_json::json!(["baking"]));
+    assert_eq!(recipes[1].1["ingredients"], "½ cup flour\n2 eggs");
+    assert_eq!(recipes[1].1["servings"], "4");
+
+    for query in ["", "?format=mealie"] {
+        let response = build_router()
+            .oneshot(make_request(
+                "GET",
+                &format!("/api/v1/recipes/export{}", query),
+                None,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    }
 }
 
 #[tokio::test]
-async fn test_update_recipe_title_causes_filename_change_git() {
-    test_update_recipe_title_causes_filename_change_impl("git").await;
+async fn test_export_paprika_git() {
+    test_export_paprika_impl("git").await;
 }
 
 #[tokio::test]
-async fn test_update_recipe_title_causes_filename_change_disk() {
-    test_update_recipe_title_causes_filename_change_impl("disk").await;
+async fn test_export_paprika_disk() {
+    test_export_paprika_impl("disk").await;
 }
 
-async fn test_id_change_on_rename_scenario_impl(backend: &str) {
+// ============================================================================
+// PAPRIKA IMPORT TESTS
+// ============================================================================
+
+fn make_import_request(uri: &str, body: Vec<u8>) -> axum::http::Request<axum::body::Body> {
+    axum::http::Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-type", "application/octet-stream")
+        .body(axum::body::Body::from(body))
+        .unwrap()
+}
+
+async fn test_import_paprika_impl(backend: &str) {
+    use cooklang_store::paprika::{self, PaprikaRecipe};
+
     let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
-    let app = build_router();
 
-    // Step 1: Create recipe with initial title
-    let initial_content = "---\ntitle: Chocolate Cake\n---\n\nSimple chocolate cake.";
-    let payload = serde_json::json!({
-        "content": initial_content,
-        "path": "desserts"
-    });
+    let archive = paprika::archive(
+        &[
+            PaprikaRecipe {
+                name: "Pancakes".to_string(),
+                ingredients: "1 1/2 cups flour\n2 eggs\na pinch of salt".to_string(),
+                directions: "Whisk the flour and eggs.\nFry.".to_string(),
+                servings: "4".to_string(),
+                source_url: "https://example.com/pancakes".to_string(),
+                categories: vec!["Breakfast".to_string(), "Quick".to_string()],
+                rating: 4,
+                photo_data: Some("aGVsbG8=".to_string()),
+                ..Default::default()
+            },
+            PaprikaRecipe::default(),
+        ],
+        chrono::Utc::now(),
+    )
+    .unwrap();
 
-    let response = app
-        .clone()
-        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+    let response = build_router()
+        .oneshot(make_import_request(
+            "/api/v1/import/paprika",
+            archive.clone(),
+        ))
         .await
         .unwrap();
-
-    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
     let body = extract_response_body(response).await;
     let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["imported"], 1);
+    assert_eq!(json["failed"], 1);
+    let pancakes = &json["recipes"][0];
+    assert_eq!(pancakes["name"], "Pancakes");
+    assert_eq!(pancakes["status"], "imported");
+    assert_eq!(pancakes["path"], "breakfast");
+    assert_eq!(pancakes["fidelity"]["ingredients"], 3);
+    assert_eq!(pancakes["fidelity"]["inline"], 2);
+    assert_eq!(pancakes["fidelity"]["listed"], 1);
+    assert_eq!(
+        pancakes["fidelity"]["dropped"],
+        serde_json::json!(["photo"])
+    );
+    assert_eq!(json["recipes"][1]["status"], "failed");
+    assert!(json["recipes"][1]["error"].is_string());
 
-    let initial_recipe_id = json["recipeId"].as_str().unwrap().to_string();
-    println!("Initial recipe ID: {}", initial_recipe_id);
-
-    // Step 2: Update recipe content with new title
-    let new_content = "---\ntitle: Dark Chocolate Cake\n---\n\nRich dark chocolate cake.";
-    let update_payload = serde_json::json!({
-        "content": new_content
-    });
-
-    let response = app
-        .clone()
+    let recipe_id = pancakes["recipeId"].as_str().unwrap();
+    let response = build_router()
         .oneshot(make_request(
-            "PUT",
-            &format!("/api/v1/recipes/{}", initial_recipe_id),
-            Some(update_payload),
+            "GET",
+            &format!("/api/v1/recipes/{}", recipe_id),
+            None,
         ))
         .await
         .unwrap();
-
     assert_eq!(response.status(), axum::http::StatusCode::OK);
-
+    let body = extract_response_body(response).await;
+    let recipe: Value = serde_json::from_str(&body).unwrap();
+    let content = recipe["content"].as_str().unwrap();
+    assert!(content.contains("source: https://example.com/pancakes"));
+    assert!(content.contains("Whisk the @flour{1.5%cups} and @eggs{2}."));
+    assert!(content.contains("@salt{1%pinch}"));
+    assert!(content.contains("tags:\n- Quick"));
+
+    // The same archive, uploaded first
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            "/api/v1/uploads",
+            Some(serde_json::json!({ "fileName": "export.paprikarecipes", "size": archive.len() })),
+        ))
+        .await
+        .unwrap();
     let body = extract_response_body(response).await;
     let json: Value = serde_json::from_str(&body).unwrap();
+    let upload_id = json["uploadId"].as_str().unwrap().to_string();
 
-    let new_recipe_id = json["recipeId"].as_str().unwrap().to_string();
-    println!("New recipe ID: {}", new_recipe_id);
+    let response = build_router()
+        .oneshot(make_import_request(
+            &format!("/api/v1/import/paprika?upload_id={}", upload_id),
+            Vec::new(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
 
-    // Verify recipe_id changed
-    assert_ne!(initial_recipe_id, new_recipe_id);
-    assert_eq!(json["recipeName"], "Dark Chocolate Cake");
+    let response = build_router()
+        .oneshot(make_chunk_request(&upload_id, 0, &archive))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let response = build_router()
+        .oneshot(make_import_request(
+            &format!("/api/v1/import/paprika?upload_id={}", upload_id),
+            Vec::new(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["imported"], 1);
 
-    // Step 3: Try to access with old recipe_id - should return 404
-    let response = app
-        .clone()
+    // The upload is discarded once imported
+    let response = build_router()
         .oneshot(make_request(
             "GET",
-            &format!("/api/v1/recipes/{}", initial_recipe_id),
+            &format!("/api/v1/uploads/{}", upload_id),
             None,
         ))
         .await
         .unwrap();
-
-    // Old ID should not be valid anymore
     assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
 
-    // Step 4: Verify new recipe_id works
-    let response = app
-        .clone()
+    for body in [Vec::new(), b"not a zip".to_vec()] {
+        let response = build_router()
+            .oneshot(make_import_request("/api/v1/import/paprika", body))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    }
+}
+
+#[tokio::test]
+async fn test_import_paprika_git() {
+    test_import_paprika_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_import_paprika_disk() {
+    test_import_paprika_impl("disk").await;
+}
+
+// ============================================================================
+// MEALIE IMPORT TESTS
+// ============================================================================
+
+async fn test_import_mealie_impl(backend: &str) {
+    use cooklang_store::archive::{write_zip, ZipEntry};
+
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
+
+    let recipe = serde_json::json!({
+        "name": "Tomato Soup",
+        "recipeYield": "2 servings",
+        "orgURL": "https://example.com/soup",
+        "recipeCategory": [{ "name": "Soups" }],
+        "tags": [{ "name": "Vegan" }],
+        "recipeIngredient": [
+            {
+                "quantity": 800,
+                "unit": { "name": "gram", "abbreviation": "g" },
+                "food": { "name": "tomato" },
+                "note": "chopped",
+                "referenceId": "t"
+            },
+            { "quantity": 0, "food": null, "note": "1 onion" }
+        ],
+        "recipeInstructions": [
+            { "text": "Fry the onion.", "ingredientReferences": [] },
+            { "text": "Add the tomatoes and simmer.", "ingredientReferences": [{ "referenceId": "t" }] }
+        ],
+        "image": null,
+        "nutrition": null
+    });
+    let archive = write_zip(
+        &[ZipEntry {
+            name: "tomato-soup/tomato-soup.json".to_string(),
+            data: serde_json::to_vec(&recipe).unwrap(),
+        }],
+        chrono::Utc::now(),
+    )
+    .unwrap();
+
+    let response = build_router()
+        .oneshot(make_import_request("/api/v1/import/mealie", archive))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["imported"], 1);
+    let soup = &json["recipes"][0];
+    assert_eq!(soup["name"], "Tomato Soup");
+    assert_eq!(soup["path"], "soups");
+    assert_eq!(soup["fidelity"]["score"], 1.0);
+
+    let response = build_router()
         .oneshot(make_request(
             "GET",
-            &format!("/api/v1/recipes/{}", new_recipe_id),
+            &format!("/api/v1/recipes/{}", soup["recipeId"].as_str().unwrap()),
             None,
         ))
         .await
         .unwrap();
+    let body = extract_response_body(response).await;
+    let recipe: Value = serde_json::from_str(&body).unwrap();
+    let content = recipe["content"].as_str().unwrap();
+    assert!(content.contains("servings: 2 servings"));
+    assert!(content.contains("Fry the @onion{1}."));
+    assert!(content.contains("Add the @tomatoes{800%g}(chopped) and simmer."));
+
+    let other = write_zip(
+        &[ZipEntry {
+            name: "readme.txt".to_string(),
+            data: b"hello".to_vec(),
+        }],
+        chrono::Utc::now(),
+    )
+    .unwrap();
+    let response = build_router()
+        .oneshot(make_import_request("/api/v1/import/mealie", other))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+}
 
-    assert_eq!(response.status(), axum::http::StatusCode::OK);
+#[tokio::test]
+async fn test_import_mealie_git() {
+    test_import_mealie_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_import_mealie_disk() {
+    test_import_mealie_impl("disk").await;
+}
+
+// ============================================================================
+// TANDOOR IMPORT TESTS
+// ============================================================================
+
+async fn test_import_tandoor_impl(backend: &str) {
+    use cooklang_store::archive::{write_zip, ZipEntry};
+
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
+
+    let recipe = serde_json::json!({
+        "name": "Flatbread",
+        "keywords": [{ "name": "Bread" }, { "name": "Quick" }],
+        "working_time": 15,
+        "waiting_time": 30,
+        "servings": 4,
+        "steps": [
+            {
+                "name": "",
+                "instruction": "Mix flour, yogurt and salt into a dough.",
+                "ingredients": [
+                    { "food": { "name": "flour" }, "unit": { "name": "g" }, "amount": 250 },
+                    { "food": { "name": "yogurt" }, "unit": { "name": "g" }, "amount": 200 },
+                    { "food": { "name": "salt" }, "unit": null, "amount": 0, "no_amount": true }
+                ]
+            },
+            {
+                "name": "",
+                "instruction": "Fry in a dry pan.",
+                "ingredients": []
+            }
+        ]
+    });
+    let inner = write_zip(
+        &[ZipEntry {
+            name: "recipe.json".to_string(),
+            data: serde_json::to_vec(&recipe).unwrap(),
+        }],
+        chrono::Utc::now(),
+    )
+    .unwrap();
+    let archive = write_zip(
+        &[ZipEntry {
+            name: "7.zip".to_string(),
+            data: inner,
+        }],
+        chrono::Utc::now(),
+    )
+    .unwrap();
 
+    let response = build_router()
+        .oneshot(make_import_request("/api/v1/import/tandoor", archive))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
     let body = extract_response_body(response).await;
     let json: Value = serde_json::from_str(&body).unwrap();
-    assert_eq!(json["recipeName"], "Dark Chocolate Cake");
+    assert_eq!(json["imported"], 1);
+    let flatbread = &json["recipes"][0];
+    assert_eq!(flatbread["name"], "Flatbread");
+    assert!(flatbread.get("path").is_none());
+    assert_eq!(flatbread["fidelity"]["inline"], 3);
+
+    let response = build_router()
+        .oneshot(make_request(
+            "GET",
+            &format!(
+                "/api/v1/recipes/{}",
+                flatbread["recipeId"].as_str().unwrap()
+            ),
+            None,
+        ))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let recipe: Value = serde_json::from_str(&body).unwrap();
+    let content = recipe["content"].as_str().unwrap();
+    assert!(content.contains("prep time: 15 minutes\ncook time: 30 minutes"));
+    assert!(content.contains("tags:\n- Bread\n- Quick"));
+    assert!(content.contains("Mix @flour{250%g}, @yogurt{200%g} and @salt{} into a dough."));
+
+    let response = build_router()
+        .oneshot(make_import_request(
+            "/api/v1/import/tandoor",
+            b"not a zip".to_vec(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
 }
 
 #[tokio::test]
-async fn test_id_change_on_rename_scenario_git() {
-    test_id_change_on_rename_scenario_impl("git").await;
+async fn test_import_tandoor_git() {
+    test_import_tandoor_impl("git").await;
 }
 
 #[tokio::test]
-async fn test_id_change_on_rename_scenario_disk() {
-    test_id_change_on_rename_scenario_impl("disk").await;
+async fn test_import_tandoor_disk() {
+    test_import_tandoor_impl("disk").await;
 }
 
 // ============================================================================
-// FIND BY NAME TESTS
+// NEXTCLOUD COOKBOOK IMPORT TESTS
 // ============================================================================
 
-async fn test_find_recipe_by_name_impl(backend: &str) {
-    let (build_router, _temp_dir) = setup_api_with_seeded_fixtures(
-        backend,
-        vec![
-            ("cake", Some("desserts"), "cake.cook"),
-            ("pasta", Some("main"), "pasta.cook"),
-            (
-                "chicken-biryani",
-                Some("main/indian"),
-                "chicken-biryani.cook",
-            ),
+async fn test_import_nextcloud_impl(backend: &str) {
+    use cooklang_store::archive::{write_zip, ZipEntry};
+
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
+
+    let recipe = serde_json::json!({
+        "@type": "Recipe",
+        "name": "Lemon Tart",
+        "recipeCategory": "Baking",
+        "keywords": "lemon,dessert",
+        "recipeYield": 8,
+        "prepTime": "PT0H30M0S",
+        "cookTime": "PT0H25M0S",
+        "url": "https://example.com/tart",
+        "recipeIngredient": ["3 lemons", "100 g sugar"],
+        "recipeInstructions": ["Zest the lemons.", "Stir in the sugar."]
+    });
+    let archive = write_zip(
+        &[
+            ZipEntry {
+                name: "Recipes/Desserts/Lemon Tart/recipe.json".to_string(),
+                data: serde_json::to_vec(&recipe).unwrap(),
+            },
+            ZipEntry {
+                name: "Recipes/Desserts/Lemon Tart/full.jpg".to_string(),
+                data: vec![0xff, 0xd8, 0xff],
+            },
         ],
+        chrono::Utc::now(),
     )
-    .await;
-
-    let app = build_router();
+    .unwrap();
 
-    // Test exact match - search for "Cake" which is in the fixtures
-    let response = app
-        .clone()
+    let response = build_router()
+        .oneshot(make_import_request("/api/v1/import/nextcloud", archive))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["imported"], 1);
+    let tart = &json["recipes"][0];
+    assert_eq!(tart["name"], "Lemon Tart");
+    assert_eq!(tart["path"], "desserts");
+    assert_eq!(tart["fidelity"]["score"], 1.0);
+    assert_eq!(tart["fidelity"]["dropped"], serde_json::json!(["image"]));
+
+    let response = build_router()
         .oneshot(make_request(
             "GET",
-            "/api/v1/recipes/find-by-name?q=Cake",
+            &format!("/api/v1/recipes/{}", tart["recipeId"].as_str().unwrap()),
             None,
         ))
         .await
         .unwrap();
+    let body = extract_response_body(response).await;
+    let recipe: Value = serde_json::from_str(&body).unwrap();
+    let content = recipe["content"].as_str().unwrap();
+    assert!(content.contains("prep time: 30 minutes\ncook time: 25 minutes"));
+    assert!(content.contains("tags:\n- Baking\n- lemon\n- dessert"));
+    assert!(content.contains("Zest the @lemons{3}."));
+    assert!(content.contains("Stir in the @sugar{100%g}."));
+
+    let response = build_router()
+        .oneshot(make_import_request("/api/v1/import/nextcloud", Vec::new()))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_import_nextcloud_git() {
+    test_import_nextcloud_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_import_nextcloud_disk() {
+    test_import_nextcloud_impl("disk").await;
+}
+
+// ============================================================================
+// MARKDOWN IMPORT TESTS
+// ============================================================================
 
+async fn test_import_markdown_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
+
+    let markdown = "# Garlic Bread\n\nServings: 4\n\n## Ingredients\n\n- 1 baguette\n- 3 cloves garlic, minced\n- 50 g butter\n- parsley\n\n## Instructions\n\n1. Mix the butter with the garlic.\n2. Spread on the baguette and bake.\n";
+    let response = build_router()
+        .oneshot(
+            axum::http::Request::builder()
+                .method("POST")
+                .uri("/api/v1/import/markdown")
+                .header("content-type", "text/markdown")
+                .body(axum::body::Body::from(markdown))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
     assert_eq!(response.status(), axum::http::StatusCode::OK);
     let body = extract_response_body(response).await;
     let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["fileName"], "garlic-bread.cook");
+    assert_eq!(
+        json["content"],
+        "---\ntitle: Garlic Bread\nservings: '4'\n---\n\n\
+         Ingredients: @parsley{}.\n\n\
+         Mix the @butter{50%g} with the @garlic{3%cloves}(minced).\n\n\
+         Spread on the @baguette{1} and bake.\n"
+    );
+    assert_eq!(json["fidelity"]["inline"], 3);
+    assert_eq!(json["fidelity"]["listed"], 1);
 
-    assert!(json["recipes"].is_array());
-    assert!(json["recipes"].as_array().unwrap().iter().any(|recipe| {
-        recipe["recipeName"]
-            .as_str()
-            .map(|name| name.contains("Cake"))
-            .unwrap_or(false)
-    }));
+    // Nothing is saved
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["pagination"]["total"], 0);
 
-    // Test partial match (case-insensitive) - search for "pasta"
-    let response = app
-        .clone()
-        .oneshot(make_request(
-            "GET",
-            "/api/v1/recipes/find-by-name?q=pasta",
-            None,
-        ))
+    for markdown in ["", "Just some words."] {
+        let response = build_router()
+            .oneshot(
+                axum::http::Request::builder()
+                    .method("POST")
+                    .uri("/api/v1/import/markdown")
+                    .body(axum::body::Body::from(markdown))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    }
+}
+
+#[tokio::test]
+async fn test_import_markdown_git() {
+    test_import_markdown_impl("git").await;
+}
+
+#[tokio::test]
+async fn test_import_markdown_disk() {
+    test_import_markdown_impl("disk").await;
+}
+
+// ============================================================================
+// CONVERT TESTS
+// ============================================================================
+
+async fn test_convert_recipe_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
+
+    let text = "Quick Flatbread\n\n200 g flour\n120 ml water\nsalt\n\n\
+                Mix the flour, water and salt.\n\
+                Rest the dough for 15 minutes.\n\
+                Fry each side for 2 min.\n";
+    let response = build_router()
+        .oneshot(
+            axum::http::Request::builder()
+                .method("POST")
+                .uri("/api/v1/convert")
+                .header("content-type", "text/plain")
+                .body(axum::body::Body::from(text))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["fileName"], "quick-flatbread.cook");
+    let content = json["content"].as_str().unwrap();
+    assert!(content.starts_with("---\ntitle: Quick Flatbread\n---\n"));
+    assert!(content.contains("@flour{200%g}"));
+    assert!(content.contains("@water{120%ml}"));
+    assert!(content.contains("~{15%minutes}"));
+    assert!(content.contains("~{2%min}"));
+    assert_eq!(json["timers"], 2);
+
+    // A title for text without one
+    let response = build_router()
+        .oneshot(
+            axum::http::Request::builder()
+                .method("POST")
+                .uri("/api/v1/convert?title=Fried%20Eggs")
+                .body(axum::body::Body::from("2 eggs\nFry the eggs in butter.\n"))
+                .unwrap(),
+        )
         .await
         .unwrap();
-
     assert_eq!(response.status(), axum::http::StatusCode::OK);
     let body = extract_response_body(response).await;
     let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["fileName"], "fried-eggs.cook");
+    assert_eq!(json["timers"], 0);
 
-    assert!(json["recipes"].is_array());
-    assert!(!json["recipes"].as_array().unwrap().is_empty());
+    // Nothing is saved
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["pagination"]["total"], 0);
+
+    for text in ["", "   \n", "Just a title"] {
+        let response = build_router()
+            .oneshot(
+                axum::http::Request::builder()
+                    .method("POST")
+                    .uri("/api/v1/convert")
+                    .body(axum::body::Body::from(text))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    }
 }
 
 #[tokio::test]
-async fn test_find_recipe_by_name_git() {
-    test_find_recipe_by_name_impl("git").await;
+async fn test_convert_recipe_git() {
+    test_convert_recipe_impl("git").await;
 }
 
 #[tokio::test]
-async fn test_find_recipe_by_name_disk() {
-    test_find_recipe_by_name_impl("disk").await;
+async fn test_convert_recipe_disk() {
+    test_convert_recipe_impl("disk").await;
 }
 
-async fn test_find_recipe_by_name_pagination_impl(backend: &str) {
-    let (build_router, _temp_dir) = setup_api_with_seeded_fixtures(
-        backend,
-        vec![
-            ("cake", Some("desserts"), "cake.cook"),
-            ("pasta", Some("main"), "pasta.cook"),
-            (
-                "chicken-biryani",
-                Some("main/indian"),
-                "chicken-biryani.cook",
-            ),
-        ],
-    )
-    .await;
+// ============================================================================
+// WEBHOOK TESTS
+// ============================================================================
 
-    let app = build_router();
+/// Accept one request and answer 200, returning its headers and body
+fn webhook_receiver() -> (String, std::thread::JoinHandle<String>) {
+    use std::io::{BufRead, BufReader, Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let server = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let (mut request, mut length) = (String::new(), 0);
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                length = value.trim().parse().unwrap();
+            }
+            request.push_str(&line);
+            if line.trim().is_empty() {
+                break;
+            }
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+        request.push_str(&String::from_utf8(body).unwrap());
+        write!(
+            &stream,
+            "HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+        )
+        .unwrap();
+        request
+    });
+    (url, server)
+}
 
-    // Test with limit
-    let response = app
-        .clone()
+async fn test_webhooks_impl(backend: &str) {
+    let config = cooklang_store::config::StoreConfig {
+        webhook_max_attempts: 1,
+        ..Default::default()
+    };
+    let (build_router, _temp_dir) = setup_api_with_config(backend, config).await;
+    let (url, receiver) = webhook_receiver();
+
+    let response = build_router()
         .oneshot(make_request(
-            "GET",
-            "/api/v1/recipes/find-by-name?q=test&limit=1",
-            None,
+            "POST",
+            "/api/v1/admin/webhooks",
+            Some(serde_json::json!({
+                "url": url,
+                "events": ["recipe.created"],
+                "secret": "s3cret",
+                "author": "Alice"
+            })),
         ))
         .await
         .unwrap();
-
-    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
     let body = extract_response_body(response).await;
     let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["secret"], "s3cret");
+    assert_eq!(json["createdBy"], "Alice");
+    let webhook_id = json["webhookId"].as_str().unwrap().to_string();
 
-    assert!(json["recipes"].is_array());
-    assert!(json["recipes"].as_array().unwrap().len() <= 1);
-    assert_eq!(json["pagination"]["limit"], 1);
+    // The secret is not shown again
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/admin/webhooks", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["webhooks"].as_array().unwrap().len(), 1);
+    assert!(json["webhooks"][0].get("secret").is_none());
+    assert_eq!(json["webhooks"][0]["events"][0], "recipe.created");
 
-    // Test with offset
-    let response = app
-        .clone()
+    // A new recipe is POSTed to the webhook, signed with its secret
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Pea Soup\n---\n\nSimmer @peas{500%g}.\n",
+        "author": "Bob"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    let request = tokio::task::spawn_blocking(move || receiver.join().unwrap())
+        .await
+        .unwrap();
+    let (headers, body) = request.split_once("\r\n\r\n").unwrap();
+    let sent: Value = serde_json::from_str(body).unwrap();
+    assert_eq!(sent["event"], "recipe.created");
+    assert_eq!(sent["name"], "Pea Soup");
+    assert_eq!(sent["author"], "Bob");
+    let signature = cooklang_store::webhooks::sign("s3cret", body.as_bytes());
+    assert!(headers
+        .to_lowercase()
+        .contains(&format!("x-signature: {}", signature)));
+
+    // The delivery is logged once the response is in
+    let uri = format!("/api/v1/admin/webhooks/{}/deliveries", webhook_id);
+    let mut json = Value::Null;
+    for _ in 0..100 {
+        let response = build_router()
+            .oneshot(make_request("GET", &uri, None))
+            .await
+            .unwrap();
+        let body = extract_response_body(response).await;
+        json = serde_json::from_str(&body).unwrap();
+        if json["deliveries"][0]["status"] == "delivered" {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert_eq!(json["deliveries"][0]["status"], "delivered");
+    assert_eq!(json["deliveries"][0]["attempts"][0]["statusCode"], 200);
+    assert_eq!(json["deliveries"][0]["payload"]["name"], "Pea Soup");
+
+    // An unreachable webhook ends up in the dead letters
+    let response = build_router()
         .oneshot(make_request(
-            "GET",
-            "/api/v1/recipes/find-by-name?q=test&offset=1",
+            "POST",
+            "/api/v1/admin/webhooks",
+            Some(serde_json::json!({ "url": "http://127.0.0.1:9/down" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["secret"].as_str().unwrap().len(), 64);
+    let recipe_id = cooklang_store::cache::generate_recipe_id("recipes/pea-soup.cook");
+    let response = build_router()
+        .oneshot(make_request(
+            "DELETE",
+            &format!("/api/v1/recipes/{}", recipe_id),
             None,
         ))
         .await
         .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NO_CONTENT);
+    let mut json = Value::Null;
+    for _ in 0..100 {
+        let response = build_router()
+            .oneshot(make_request(
+                "GET",
+                "/api/v1/admin/webhooks/dead-letters",
+                None,
+            ))
+            .await
+            .unwrap();
+        let body = extract_response_body(response).await;
+        json = serde_json::from_str(&body).unwrap();
+        if !json["deliveries"].as_array().unwrap().is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert_eq!(json["deliveries"][0]["event"], "recipe.deleted");
+    assert_eq!(json["deliveries"][0]["status"], "failed");
+    assert!(json["deliveries"][0]["attempts"][0]["error"].is_string());
+
+    // Bad requests and removal
+    for payload in [
+        serde_json::json!({ "url": "not a url" }),
+        serde_json::json!({ "url": "http://example.com", "events": ["recipe.cooked"] }),
+    ] {
+        let response = build_router()
+            .oneshot(make_request(
+                "POST",
+                "/api/v1/admin/webhooks",
+                Some(payload),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    }
+    let response = build_router()
+        .oneshot(make_request("GET", &format!("{}?status=lost", uri), None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
 
-    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let webhook_uri = format!("/api/v1/admin/webhooks/{}", webhook_id);
+    let response = build_router()
+        .oneshot(make_request("DELETE", &webhook_uri, None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NO_CONTENT);
+    let response = build_router()
+        .oneshot(make_request("GET", &webhook_uri, None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+    let body = extract_response_body(response).await;
+    assert!(body.contains("webhook_not_found"));
 }
 
 #[tokio::test]
-async fn test_find_recipe_by_name_pagination_git() {
-    test_find_recipe_by_name_pagination_impl("git").await;
+async fn test_webhooks_git() {
+    test_webhooks_impl("git").await;
 }
 
 #[tokio::test]
-async fn test_find_recipe_by_name_pagination_disk() {
-    test_find_recipe_by_name_pagination_impl("disk").await;
+async fn test_webhooks_disk() {
+    test_webhooks_impl("disk").await;
 }
 
 // ============================================================================
-// FIND BY PATH TESTS
+// EVENT STREAM TESTS
 // ============================================================================
 
-async fn test_find_recipe_by_path_impl(backend: &str) {
-    let (build_router, _temp_dir) = setup_api_with_seeded_fixtures(
-        backend,
-        vec![
-            ("cake", Some("desserts"), "cake.cook"),
-            ("pasta", Some("main"), "pasta.cook"),
-            (
-                "chicken-biryani",
-                Some("main/indian"),
-                "chicken-biryani.cook",
-            ),
-        ],
-    )
-    .await;
-
-    let app = build_router();
+/// Wait for the next server-sent event on a streaming body, as `(event, data)`
+async fn next_event(body: &mut axum::body::Body) -> (String, Value) {
+    use http_body_util::BodyExt;
 
-    // Test find recipe at specific path
-    let response = app
-        .clone()
-        .oneshot(make_request(
-            "GET",
-            "/api/v1/recipes/find-by-path?path=main",
-            None,
-        ))
+    let frame = tokio::time::timeout(std::time::Duration::from_secs(5), body.frame())
         .await
+        .expect("No event within 5s")
+        .unwrap()
         .unwrap();
-
-    assert_eq!(response.status(), axum::http::StatusCode::OK);
-    let body = extract_response_body(response).await;
-    let json: Value = serde_json::from_str(&body).unwrap();
-
-    assert!(json.is_array());
-    assert!(json.as_array().unwrap().iter().all(|recipe| {
-        recipe["path"]
-            .as_str()
-            .map(|p| p == "main")
-            .unwrap_or(false)
-    }));
-}
-
-#[tokio::test]
-async fn test_find_recipe_by_path_git() {
-    test_find_recipe_by_path_impl("git").await;
+    let text = String::from_utf8(frame.into_data().unwrap().to_vec()).unwrap();
+    let field = |name: &str| {
+        text.lines()
+            .find_map(|line| line.strip_prefix(name))
+            .unwrap_or_default()
+            .to_string()
+    };
+    let data = serde_json::from_str(&field("data: ")).unwrap();
+    (field("event: "), data)
 }
 
-#[tokio::test]
-async fn test_find_recipe_by_path_disk() {
-    test_find_recipe_by_path_impl("disk").await;
-}
+async fn test_event_stream_impl(backend: &str) {
+    let (build_router, _temp_dir) = setup_api_with_storage(backend).await;
 
-async fn test_find_recipe_by_path_root_impl(backend: &str) {
-    let (build_router, _temp_dir) = setup_api_with_seeded_fixtures(
-        backend,
-        vec![
-            ("cake", None, "cake.cook"),
-            ("pasta", Some("main"), "pasta.cook"),
-        ],
-    )
-    .await;
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/events", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "text/event-stream");
+    let mut events = response.into_body();
 
-    let app = build_router();
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Pea Soup\n---\n\nSimmer @peas{500%g}.\n",
+        "author": "Bob"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
+    let (event, data) = next_event(&mut events).await;
+    assert_eq!(event, "recipe.created");
+    assert_eq!(data["name"], "Pea Soup");
+    assert_eq!(data["path"], "recipes/pea-soup.cook");
+    assert_eq!(data["author"], "Bob");
+    let recipe_id = data["recipeId"].as_str().unwrap().to_string();
+
+    // A move carries the old path
+    let response = build_router()
+        .oneshot(make_request(
+            "POST",
+            &format!("/api/v1/recipes/{}/move", recipe_id),
+            Some(serde_json::json!({ "path": "soups" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let (event, data) = next_event(&mut events).await;
+    assert_eq!(event, "recipe.updated");
+    assert_eq!(data["path"], "recipes/soups/pea-soup.cook");
+    assert_eq!(data["previousPath"], "recipes/pea-soup.cook");
+    assert_eq!(data["category"], "soups");
 
-    // Test root path with empty string
-    let response = app
-        .clone()
+    let response = build_router()
         .oneshot(make_request(
-            "GET",
-            "/api/v1/recipes/find-by-path?path=",
+            "DELETE",
+            &format!("/api/v1/recipes/{}", data["recipeId"].as_str().unwrap()),
             None,
         ))
         .await
         .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NO_CONTENT);
+    let (event, data) = next_event(&mut events).await;
+    assert_eq!(event, "recipe.deleted");
+    assert_eq!(data["name"], "Pea Soup");
 
-    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    // The index already reflects the change the event reports
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes/search?q=pea", None))
+        .await
+        .unwrap();
     let body = extract_response_body(response).await;
     let json: Value = serde_json::from_str(&body).unwrap();
-
-    assert!(json.is_array());
-    // Should find recipes in root (path field may be null or empty string for root)
-    assert!(json.as_array().unwrap().iter().any(|recipe| {
-        recipe["path"].is_null()
-            || recipe["path"]
-                .as_str()
-                .map(|p| p.is_empty())
-                .unwrap_or(false)
-    }));
+    assert_eq!(json["recipes"].as_array().unwrap().len(), 0);
 }
 
 #[tokio::test]
-async fn test_find_recipe_by_path_root_git() {
-    test_find_recipe_by_path_root_impl("git").await;
+async fn test_event_stream_git() {
+    test_event_stream_impl("git").await;
 }
 
 #[tokio::test]
-async fn test_find_recipe_by_path_root_disk() {
-    test_find_recipe_by_path_root_impl("disk").await;
+async fn test_event_stream_disk() {
+    test_event_stream_impl("disk").await;
 }
 
-async fn test_find_recipe_by_path_not_found_impl(backend: &str) {
-    let (build_router, _temp_dir) =
-        setup_api_with_seeded_fixtures(backend, vec![("cake", Some("desserts"), "cake.cook")])
-            .await;
+// ============================================================================
+// STORAGE METRICS TESTS
+// ============================================================================
 
-    let app = build_router();
+#[tokio::test]
+async fn test_storage_metrics() {
+    let (build_router, _temp_dir) = setup_api_with_storage("git").await;
 
-    // Test non-existent path
-    let response = app
-        .oneshot(make_request(
-            "GET",
-            "/api/v1/recipes/find-by-path?path=nonexistent",
-            None,
-        ))
+    let payload = serde_json::json!({
+        "content": "---\ntitle: Pea Soup\n---\n\nSimmer @peas{500%g}.\n"
+    });
+    let response = build_router()
+        .oneshot(make_request("POST", "/api/v1/recipes", Some(payload)))
         .await
         .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::CREATED);
 
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/admin/metrics", None))
+        .await
+        .unwrap();
     assert_eq!(response.status(), axum::http::StatusCode::OK);
+    assert!(response.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .starts_with("text/plain; version=0.0.4"));
     let body = extract_response_body(response).await;
-    let json: Value = serde_json::from_str(&body).unwrap();
-
-    // Should return empty array instead of 404
-    assert!(json.is_array());
-    assert_eq!(json.as_array().unwrap().len(), 0);
+    assert!(body.contains("# TYPE cooklang_storage_operation_duration_seconds histogram"));
+    assert!(body.contains(
+        "cooklang_storage_operation_duration_seconds_count{backend=\"git\",operation=\"write_file_with_commit\"} 1"
+    ));
+    assert!(body.contains(
+        "cooklang_storage_operation_errors_total{backend=\"git\",operation=\"write_file_with_commit\"} 0"
+    ));
 }
 
 #[tokio::test]
-async fn test_find_recipe_by_path_not_found_git() {
-    test_find_recipe_by_path_not_found_impl("git").await;
+async fn test_cache_stats() {
+    let (build_router, temp_dir) = setup_api_with_seeded_files(
+        "disk",
+        vec![],
+        vec![
+            (
+                "recipes/soups/pea-soup.cook",
+                "---\ntitle: Pea Soup\n---\n\nSimmer @peas{500%g}.\n",
+            ),
+            (
+                "bread.cook",
+                "---\ntitle: Bread\n---\n\nKnead @flour{1%kg}.\n",
+            ),
+        ],
+    )
+    .await;
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/admin/cache", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let json: Value = serde_json::from_str(&extract_response_body(response).await).unwrap();
+    assert_eq!(json["entries"], 2);
+    assert!(json["approximateBytes"].as_u64().unwrap() > 0);
+    assert_eq!(json["lastRebuild"]["mode"], "full");
+    assert_eq!(json["lastRebuild"]["parsed"], 2);
+    assert_eq!(json["fullRebuilds"], 1);
+    assert_eq!(json["parseFailures"], 0);
+    assert_eq!(json["failingFiles"], serde_json::json!([]));
+    assert_eq!(json["categories"][0]["path"], "soups");
+    assert_eq!(json["categories"][0]["count"], 1);
+    assert_eq!(json["uncategorized"], 1);
+
+    std::fs::write(
+        temp_dir.path().join("recipes/soups/leek-soup.cook"),
+        "---\ntitle: Leek Soup\n---\n\nSweat @leeks{2}.\n",
+    )
+    .unwrap();
+    build_router()
+        .oneshot(make_request("POST", "/api/v1/admin/reindex", None))
+        .await
+        .unwrap();
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/admin/cache", None))
+        .await
+        .unwrap();
+    let json: Value = serde_json::from_str(&extract_response_body(response).await).unwrap();
+    assert_eq!(json["entries"], 3);
+    assert_eq!(json["lastRebuild"]["mode"], "incremental");
+    assert_eq!(json["lastRebuild"]["parsed"], 1);
+    assert_eq!(json["incrementalRebuilds"], 1);
+    assert_eq!(json["categories"][0]["count"], 2);
+
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/admin/metrics", None))
+        .await
+        .unwrap();
+    let body = extract_response_body(response).await;
+    for line in [
+        "cooklang_index_entries 3",
+        "cooklang_index_rebuilds_total{mode=\"full\"} 1",
+        "cooklang_index_rebuilds_total{mode=\"incremental\"} 1",
+        "cooklang_index_parse_failures_total 0",
+        "cooklang_index_failing_files 0",
+    ] {
+        assert!(body.contains(line), "missing {}", line);
+    }
 }
 
+// ============================================================================
+// REQUEST ID TESTS
+// ============================================================================
+
 #[tokio::test]
-async fn test_find_recipe_by_path_not_found_disk() {
-    test_find_recipe_by_path_not_found_impl("disk").await;
+async fn test_request_id() {
+    let (build_router, _temp_dir) = setup_api_with_storage("disk").await;
+
+    // A client-sent ID is echoed, in the header and in error bodies
+    let mut request = make_request("GET", "/api/v1/recipes/missing", None);
+    request
+        .headers_mut()
+        .insert("x-request-id", "support-1234".parse().unwrap());
+    let response = build_router().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+    assert_eq!(response.headers()["x-request-id"], "support-1234");
+    let body = extract_response_body(response).await;
+    let json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["requestId"], "support-1234");
+
+    // Otherwise one is generated, different per request
+    let mut ids = Vec::new();
+    for _ in 0..2 {
+        let mut request = make_request("GET", "/api/v1/recipes/missing", None);
+        request
+            .headers_mut()
+            .insert("x-request-id", "bad id".parse().unwrap());
+        let response = build_router().oneshot(request).await.unwrap();
+        let id = response.headers()["x-request-id"]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let body = extract_response_body(response).await;
+        let json: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["requestId"], id);
+        ids.push(id);
+    }
+    assert_eq!(ids[0].len(), 16);
+    assert_ne!(ids[0], ids[1]);
+
+    // Successful responses carry it too
+    let response = build_router()
+        .oneshot(make_request("GET", "/api/v1/recipes", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    assert!(response.headers().contains_key("x-request-id"));
 }
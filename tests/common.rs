@@ -1,4 +1,5 @@
-use cooklang_store::{api, repository::RecipeRepository};
+use cooklang_store::{api, config::StoreConfig, repository::RecipeRepository};
+use std::collections::BTreeMap;
 use std::fs;
 use std::sync::Arc;
 use tempfile::TempDir;
@@ -75,6 +76,54 @@ pub async fn setup_api_with_storage(storage_type: &str) -> (impl Fn() -> axum::R
     (build_router, temp_dir)
 }
 
+/// Setup API with a custom store configuration (e.g. trash enabled).
+pub async fn setup_api_with_config(
+    storage_type: &str,
+    config: StoreConfig,
+) -> (impl Fn() -> axum::Router, TempDir) {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = RecipeRepository::with_config(temp_dir.path(), storage_type, "memory", config)
+        .await
+        .expect("Failed to create repo");
+
+    let repo_arc = Arc::new(repo);
+
+    let build_router = move || api::build_router(repo_arc.clone());
+
+    (build_router, temp_dir)
+}
+
+/// Setup API with a default repository plus one named collection per entry of `names`.
+///
+/// Returns the default data directory followed by one per collection, in order.
+#[allow(dead_code)]
+pub async fn setup_api_with_collections(
+    storage_type: &str,
+    names: &[&str],
+) -> (impl Fn() -> axum::Router, Vec<TempDir>) {
+    let mut temp_dirs = vec![TempDir::new().unwrap()];
+    let repo = RecipeRepository::with_storage(temp_dirs[0].path(), storage_type)
+        .await
+        .expect("Failed to create repo");
+
+    let mut collections = BTreeMap::new();
+    for name in names {
+        let temp_dir = TempDir::new().unwrap();
+        let collection = RecipeRepository::with_storage(temp_dir.path(), storage_type)
+            .await
+            .expect("Failed to create collection");
+        collections.insert(name.to_string(), Arc::new(collection));
+        temp_dirs.push(temp_dir);
+    }
+
+    let repo_arc = Arc::new(repo);
+
+    let build_router =
+        move || api::build_router_with_collections(repo_arc.clone(), collections.clone());
+
+    (build_router, temp_dirs)
+}
+
 /// Setup API with pre-seeded fixture files.
 ///
 /// Use this when tests need pre-existing recipe files on disk.
@@ -110,6 +159,74 @@ pub async fn setup_api_with_seeded_fixtures(
     (build_router, temp_dir)
 }
 
+/// Setup API with pre-seeded fixture files plus arbitrary files (paths relative to the data dir).
+///
+/// Use this for state files such as `.cooklang-store/category-aliases.json` that must
+/// exist before the repository loads.
+pub async fn setup_api_with_seeded_files(
+    storage_type: &str,
+    fixtures: Vec<(&str, Option<&str>, &str)>,
+    files: Vec<(&str, &str)>,
+) -> (impl Fn() -> axum::Router, TempDir) {
+    let temp_dir = TempDir::new().unwrap();
+
+    for (fixture_name, category, filename) in fixtures {
+        copy_fixture_to_recipes_dir(&temp_dir, fixture_name, category, filename);
+    }
+
+    for (rel_path, content) in files {
+        let path = temp_dir.path().join(rel_path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("Failed to create seeded file directory");
+        }
+        fs::write(&path, content).expect("Failed to write seeded file");
+    }
+
+    let repo = RecipeRepository::with_storage(temp_dir.path(), storage_type)
+        .await
+        .expect("Failed to create repo");
+
+    let repo_arc = Arc::new(repo);
+
+    let build_router = move || api::build_router(repo_arc.clone());
+
+    (build_router, temp_dir)
+}
+
+/// Add a git repository holding `files` (path, content) as submodule `path` of the data dir.
+///
+/// The data dir must already be a git repository (git storage).
+#[allow(dead_code)]
+pub fn add_submodule(temp_dir: &TempDir, path: &str, files: &[(&str, &str)]) {
+    // The source lives in a hidden directory of the data dir, which is never indexed
+    let source = temp_dir.path().join(".submodule-sources").join(path);
+    let pack = git2::Repository::init(&source).expect("Failed to create submodule repository");
+    let mut index = pack.index().unwrap();
+    for (rel_path, content) in files {
+        fs::write(source.join(rel_path), content).expect("Failed to write submodule file");
+        index.add_path(std::path::Path::new(rel_path)).unwrap();
+    }
+    index.write().unwrap();
+    let tree = pack.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = git2::Signature::now("Pack Maintainer", "pack@example.com").unwrap();
+    pack.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "Add recipes",
+        &tree,
+        &[],
+    )
+    .unwrap();
+
+    let repo = git2::Repository::open(temp_dir.path()).expect("Data dir is not a git repository");
+    let mut submodule = repo
+        .submodule(&source.to_string_lossy(), std::path::Path::new(path), true)
+        .expect("Failed to add submodule");
+    submodule.clone(None).expect("Failed to clone submodule");
+    submodule.add_finalize().unwrap();
+}
+
 pub fn make_request(
     method: &str,
     uri: &str,
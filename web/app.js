@@ -0,0 +1,275 @@
+// Minimal browser UI for the recipe API: list, search, view, create, edit, delete.
+// Routes live in the URL hash (#/, #/search?q=, #/recipes/{id}, #/recipes/{id}/edit, #/new).
+"use strict";
+
+const PAGE_SIZE = 50;
+const view = document.getElementById("view");
+const errorBox = document.getElementById("error");
+const collectionSelect = document.getElementById("collection");
+
+// Element helper: el("a", { href: "#/" }, "text", child, ...)
+function el(tag, attrs = {}, ...children) {
+  const node = document.createElement(tag);
+  for (const [key, value] of Object.entries(attrs)) {
+    if (key.startsWith("on")) {
+      node.addEventListener(key.slice(2), value);
+    } else if (value !== false && value != null) {
+      node.setAttribute(key, value === true ? "" : value);
+    }
+  }
+  node.append(...children.filter((child) => child != null));
+  return node;
+}
+
+function showError(message) {
+  errorBox.textContent = message || "";
+  errorBox.hidden = !message;
+}
+
+// Call the API, sending the selected collection and API token; resolves to JSON (or
+// text for other responses) and rejects with the API's message
+async function api(method, path, body) {
+  const headers = {};
+  const collection = localStorage.getItem("collection");
+  if (collection) headers["X-Collection"] = collection;
+  const token = localStorage.getItem("token");
+  if (token) headers["Authorization"] = `Bearer ${token}`;
+  if (body !== undefined) headers["Content-Type"] = "application/json";
+
+  const response = await fetch("/api/v1" + path, {
+    method,
+    headers,
+    body: body === undefined ? undefined : JSON.stringify(body),
+  });
+  // The server has API_TOKEN set: ask for it once and retry
+  if (response.status === 401) {
+    const entered = prompt("API token");
+    if (entered && entered.trim() !== token) {
+      localStorage.setItem("token", entered.trim());
+      return api(method, path, body);
+    }
+  }
+  if (response.status === 204) return null;
+  const isJson = (response.headers.get("content-type") || "").includes("json");
+  const data = isJson ? await response.json().catch(() => null) : await response.text();
+  if (!response.ok) {
+    throw new Error((isJson && data && data.message) || `Request failed (${response.status})`);
+  }
+  return data;
+}
+
+function recipeLink(recipe) {
+  return el("a", { href: `#/recipes/${recipe.recipeId}` }, recipe.recipeName);
+}
+
+// `snippet` with its [start, end) matches wrapped in <mark>
+function highlighted(snippet, matches) {
+  const chars = Array.from(snippet);
+  const parts = [];
+  let last = 0;
+  for (const [start, end] of matches) {
+    parts.push(chars.slice(last, start).join(""), el("mark", {}, chars.slice(start, end).join("")));
+    last = end;
+  }
+  parts.push(chars.slice(last).join(""));
+  return parts;
+}
+
+function recipeList(recipes, pagination, pageHref) {
+  const list = el("ul", { class: "recipes" });
+  for (const recipe of recipes) {
+    const match = (recipe.highlights || []).find((h) => h.field !== "name");
+    list.append(
+      el(
+        "li",
+        {},
+        recipeLink(recipe),
+        recipe.path ? el("span", { class: "path" }, " · " + recipe.path) : null,
+        match ? el("div", { class: "muted" }, ...highlighted(match.snippet, match.matches)) : null
+      )
+    );
+  }
+
+  const { offset, limit, total } = pagination;
+  const pager = el(
+    "div",
+    { class: "actions" },
+    el("span", { class: "muted" }, total ? `${offset + 1}–${offset + recipes.length} of ${total}` : "No recipes")
+  );
+  if (offset > 0) pager.append(el("a", { class: "button", href: pageHref(Math.max(0, offset - limit)) }, "Previous"));
+  if (offset + limit < total) pager.append(el("a", { class: "button", href: pageHref(offset + limit) }, "Next"));
+  return [list, pager];
+}
+
+async function listPage(params) {
+  const offset = Number(params.get("offset")) || 0;
+  const data = await api("GET", `/recipes?limit=${PAGE_SIZE}&offset=${offset}`);
+  view.replaceChildren(
+    el("h1", {}, "Recipes"),
+    ...recipeList(data.recipes, data.pagination, (o) => `#/?offset=${o}`)
+  );
+}
+
+async function searchPage(params) {
+  const q = params.get("q") || "";
+  const offset = Number(params.get("offset")) || 0;
+  document.querySelector("#search input").value = q;
+  const query = new URLSearchParams({ q, limit: PAGE_SIZE, offset });
+  const data = await api("GET", `/recipes/search?${query}`);
+  view.replaceChildren(
+    el("h1", {}, `Results for “${q}”`),
+    ...recipeList(data.recipes, data.pagination, (o) => `#/search?${new URLSearchParams({ q, offset: o })}`)
+  );
+}
+
+async function recipePage(id) {
+  const [recipe, html] = await Promise.all([
+    api("GET", `/recipes/${id}`),
+    api("GET", `/recipes/${id}/html?fragment=true`),
+  ]);
+  const remove = async () => {
+    if (!confirm(`Delete “${recipe.recipeName}”?`)) return;
+    await api("DELETE", `/recipes/${id}`);
+    location.hash = "#/";
+  };
+  // Pages opened in a new tab can't send the API token, so public recipes link to the
+  // routes that need none
+  const page = (recipe.customFields.visibility || "").toLowerCase() === "public" ? "/public/recipes" : "/recipes";
+  // The server escapes everything in the rendered recipe
+  const rendered = el("div");
+  rendered.innerHTML = html;
+  rendered.querySelector("h1").after(
+    el(
+      "p",
+      { class: "muted" },
+      [recipe.path, recipe.lastModifiedBy, recipe.lastModifiedAt && new Date(recipe.lastModifiedAt).toLocaleString()]
+        .filter(Boolean)
+        .join(" · ")
+    ),
+    el(
+      "div",
+      { class: "actions" },
+      recipe.readOnly
+        ? el("span", { class: "muted" }, "Read-only")
+        : el("a", { class: "button", href: `#/recipes/${id}/edit` }, "Edit"),
+      el("button", { class: "danger", disabled: recipe.readOnly, onclick: () => remove().catch((e) => showError(e.message)) }, "Delete"),
+      el("a", { href: linkUrl(`${page}/${id}/html`), target: "_blank" }, "Share"),
+      el("a", { href: linkUrl(`${page}/${id}/html?print=true`), target: "_blank" }, "Print"),
+      el("a", { href: linkUrl(`/recipes/${id}/qr`), target: "_blank" }, "QR code")
+    )
+  );
+  view.replaceChildren(rendered, el("details", {}, el("summary", {}, "Cooklang source"), el("pre", {}, recipe.content)));
+}
+
+// API URL for links, which carry the collection in the path since they can't send headers
+function linkUrl(path) {
+  const collection = localStorage.getItem("collection");
+  const base = collection ? `/api/v1/collections/${encodeURIComponent(collection)}` : "/api/v1";
+  return base + path;
+}
+
+function author() {
+  const name = localStorage.getItem("author");
+  return name ? { author: name } : {};
+}
+
+function editorPage(recipe) {
+  const isNew = !recipe;
+  const content = el("textarea", { id: "content", name: "content", required: true, spellcheck: "false" });
+  content.value = isNew ? "---\ntitle: \n---\n\n" : recipe.content;
+  const path = el("input", { name: "path", value: isNew ? "" : recipe.path || "", placeholder: "e.g. desserts/cakes" });
+  const authorInput = el("input", { name: "author", value: localStorage.getItem("author") || "" });
+  const comment = el("input", { name: "comment", placeholder: "What changed?" });
+
+  const save = async (event) => {
+    event.preventDefault();
+    showError();
+    localStorage.setItem("author", authorInput.value.trim());
+    const body = { content: content.value, ...author() };
+    if (comment.value.trim()) body.comment = comment.value.trim();
+    if (isNew || path.value.trim() !== (recipe.path || "")) body.path = path.value.trim();
+
+    const saved = isNew
+      ? await api("POST", "/recipes", body)
+      : await api("PUT", `/recipes/${recipe.recipeId}`, body);
+    location.hash = `#/recipes/${saved.recipeId}`;
+  };
+
+  view.replaceChildren(
+    el("h1", {}, isNew ? "New recipe" : `Edit ${recipe.recipeName}`),
+    el(
+      "form",
+      { onsubmit: (event) => save(event).catch((e) => showError(e.message)) },
+      el("label", { for: "content" }, "Recipe (Cooklang with a title in the front matter)"),
+      content,
+      el("label", {}, "Category path"),
+      path,
+      el("label", {}, "Your name"),
+      authorInput,
+      el("label", {}, "Comment"),
+      comment,
+      el(
+        "div",
+        { class: "actions" },
+        el("button", { type: "submit" }, "Save"),
+        el("a", { href: isNew ? "#/" : `#/recipes/${recipe.recipeId}` }, "Cancel")
+      )
+    )
+  );
+  content.focus();
+}
+
+async function route() {
+  showError();
+  const [path, query] = location.hash.replace(/^#/, "").split("?");
+  const params = new URLSearchParams(query);
+  const parts = (path || "/").split("/").filter(Boolean);
+  try {
+    if (parts[0] === "search") {
+      await searchPage(params);
+    } else if (parts[0] === "new") {
+      editorPage(null);
+    } else if (parts[0] === "recipes" && parts[2] === "edit") {
+      editorPage(await api("GET", `/recipes/${parts[1]}`));
+    } else if (parts[0] === "recipes" && parts[1]) {
+      await recipePage(parts[1]);
+    } else {
+      await listPage(params);
+    }
+  } catch (e) {
+    view.replaceChildren();
+    showError(e.message);
+  }
+}
+
+async function loadCollections() {
+  let collections;
+  try {
+    ({ collections } = await api("GET", "/collections"));
+  } catch {
+    // The saved collection may have been removed from COLLECTIONS
+    localStorage.removeItem("collection");
+    ({ collections } = await api("GET", "/collections").catch(() => ({ collections: [] })));
+  }
+  if (!collections.length) return;
+
+  const selected = localStorage.getItem("collection") || "";
+  collectionSelect.append(el("option", { value: "" }, "Default collection"));
+  for (const name of collections) {
+    collectionSelect.append(el("option", { value: name, selected: name === selected }, name));
+  }
+  collectionSelect.hidden = false;
+  collectionSelect.addEventListener("change", () => {
+    localStorage.setItem("collection", collectionSelect.value);
+    if (location.hash === "#/") route();
+    else location.hash = "#/";
+  });
+}
+
+document.getElementById("search").addEventListener("submit", (event) => {
+  event.preventDefault();
+  const q = new FormData(event.target).get("q").trim();
+  location.hash = q ? `#/search?${new URLSearchParams({ q })}` : "#/";
+});
+window.addEventListener("hashchange", route);
+loadCollections().then(route);